@@ -1,6 +1,10 @@
 //! Analytics functions for task analysis.
 
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::fmt;
+
+use chrono::{Datelike, NaiveDate, NaiveTime, Weekday};
+use crate::project::Project;
 use crate::task::{Priority, Task, TaskStatus};
 
 /// Group tasks by priority.
@@ -38,3 +42,275 @@ pub fn workload_by_assignee(tasks: &[Task]) -> HashMap<String, f32> {
     }
     workload
 }
+
+/// Hours logged per developer per day, from timer sessions.
+///
+/// Sessions that run past midnight are split across the days they cover.
+pub fn daily_timesheet(tasks: &[Task]) -> BTreeMap<String, BTreeMap<NaiveDate, f32>> {
+    let mut sheet: BTreeMap<String, BTreeMap<NaiveDate, f32>> = BTreeMap::new();
+    for entry in tasks.iter().flat_map(|t| &t.work_log) {
+        let days = sheet.entry(entry.developer.clone()).or_default();
+        let mut start = entry.started;
+        while start < entry.stopped {
+            let next_midnight = start
+                .date()
+                .succ_opt()
+                .expect("date in range")
+                .and_time(NaiveTime::MIN);
+            let end = next_midnight.min(entry.stopped);
+            *days.entry(start.date()).or_default() += (end - start).num_seconds() as f32 / 3600.0;
+            start = end;
+        }
+    }
+    sheet
+}
+
+/// Working hours one person puts in per day, used for schedule projections.
+pub const HOURS_PER_DAY: f32 = 8.0;
+
+/// A hypothetical change to a project's plan.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ScenarioChange {
+    /// Add this many people on top of the current assignees.
+    AddHeadcount(u32),
+    /// Drop a task (and any dependencies on it) from the plan.
+    RemoveTask(u32),
+    /// Replace a task's estimate.
+    ChangeEstimate { task_id: u32, hours: f32 },
+}
+
+impl fmt::Display for ScenarioChange {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ScenarioChange::AddHeadcount(n) => write!(f, "+{} headcount", n),
+            ScenarioChange::RemoveTask(id) => write!(f, "drop task {}", id),
+            ScenarioChange::ChangeEstimate { task_id, hours } => {
+                write!(f, "task {} = {}h", task_id, hours)
+            }
+        }
+    }
+}
+
+/// The projected schedule for one scenario.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ScenarioOutcome {
+    pub label: String,
+    pub headcount: u32,
+    /// Estimated hours of all unfinished tasks.
+    pub remaining_hours: f32,
+    /// Unfinished task ids on the longest dependency chain, in order.
+    pub critical_path: Vec<u32>,
+    pub critical_path_hours: f32,
+    /// Working days (weekdays) until the last task is done, counted from
+    /// the start date given to `what_if`.
+    pub projected_days: u32,
+    /// The working day the last task is done on; the start date itself if
+    /// nothing is left to do.
+    pub projected_end: NaiveDate,
+}
+
+/// Scenario outcomes side by side; the first row is the unchanged plan.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ScenarioComparison {
+    pub rows: Vec<ScenarioOutcome>,
+}
+
+impl fmt::Display for ScenarioComparison {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(
+            f,
+            "{:<20} {:>5} {:>10} {:>14} {:>6}  {:<10}  Critical path",
+            "Scenario", "Team", "Remaining", "Critical hours", "Days", "End"
+        )?;
+        for row in &self.rows {
+            let path: Vec<String> = row.critical_path.iter().map(|id| id.to_string()).collect();
+            writeln!(
+                f,
+                "{:<20} {:>5} {:>9.1}h {:>13.1}h {:>6}  {}  {}",
+                row.label,
+                row.headcount,
+                row.remaining_hours,
+                row.critical_path_hours,
+                row.projected_days,
+                row.projected_end,
+                path.join(" -> ")
+            )?;
+        }
+        Ok(())
+    }
+}
+
+/// Projects the schedule for the current plan and for each change applied
+/// on its own, with work starting on `start`.
+///
+/// Only unfinished tasks count; a task without an estimate counts as 0h.
+/// The project can't finish sooner than its critical path, nor sooner than
+/// the remaining hours split evenly across the team. Headcount is the
+/// number of distinct assignees (at least 1).
+pub fn what_if(project: &Project, start: NaiveDate, changes: &[ScenarioChange]) -> ScenarioComparison {
+    let tasks: Vec<Task> = project
+        .tasks()
+        .iter()
+        .filter(|t| !t.status.is_done())
+        .cloned()
+        .collect();
+    let assignees: HashSet<&str> = tasks.iter().filter_map(|t| t.assignee.as_deref()).collect();
+    let headcount = (assignees.len() as u32).max(1);
+
+    let mut rows = vec![project_schedule("Current plan", &tasks, headcount, start)];
+    for change in changes {
+        let mut tasks = tasks.clone();
+        let mut headcount = headcount;
+        match change {
+            ScenarioChange::AddHeadcount(n) => headcount = headcount.saturating_add(*n),
+            ScenarioChange::RemoveTask(id) => tasks.retain(|t| t.id != *id),
+            ScenarioChange::ChangeEstimate { task_id, hours } => {
+                if let Some(task) = tasks.iter_mut().find(|t| t.id == *task_id) {
+                    task.estimated_hours = Some(*hours);
+                }
+            }
+        }
+        rows.push(project_schedule(&change.to_string(), &tasks, headcount, start));
+    }
+    ScenarioComparison { rows }
+}
+
+fn project_schedule(label: &str, tasks: &[Task], headcount: u32, start: NaiveDate) -> ScenarioOutcome {
+    let remaining_hours: f32 = tasks.iter().map(|t| t.estimated_hours.unwrap_or(0.0)).sum();
+    let critical_path = critical_path(tasks);
+    let critical_path_hours: f32 = critical_path
+        .iter()
+        .filter_map(|id| tasks.iter().find(|t| t.id == *id))
+        .map(|t| t.estimated_hours.unwrap_or(0.0))
+        .sum();
+    let hours = critical_path_hours.max(remaining_hours / headcount as f32);
+    let projected_days = (hours / HOURS_PER_DAY).ceil() as u32;
+    ScenarioOutcome {
+        label: String::from(label),
+        headcount,
+        remaining_hours,
+        critical_path,
+        critical_path_hours,
+        projected_days,
+        projected_end: nth_working_day(start, projected_days),
+    }
+}
+
+/// The `n`th weekday counting from `start` (which counts if it is one
+/// itself), or `start` for `n == 0`.
+fn nth_working_day(start: NaiveDate, n: u32) -> NaiveDate {
+    let mut day = start;
+    let mut counted = 0;
+    while counted < n {
+        if !matches!(day.weekday(), Weekday::Sat | Weekday::Sun) {
+            counted += 1;
+            if counted == n {
+                break;
+            }
+        }
+        day = day.succ_opt().expect("date in range");
+    }
+    day
+}
+
+/// The chain of dependent tasks with the most estimated hours.
+///
+/// Dependencies on tasks not in `tasks` are ignored, as are edges that
+/// would close a cycle.
+fn critical_path(tasks: &[Task]) -> Vec<u32> {
+    fn longest(
+        id: u32,
+        by_id: &HashMap<u32, &Task>,
+        memo: &mut HashMap<u32, (f32, Vec<u32>)>,
+        visiting: &mut HashSet<u32>,
+    ) -> (f32, Vec<u32>) {
+        if let Some(done) = memo.get(&id) {
+            return done.clone();
+        }
+        visiting.insert(id);
+        let task = by_id[&id];
+        let mut best: (f32, Vec<u32>) = (0.0, Vec::new());
+        for dep in &task.depends_on {
+            if by_id.contains_key(dep) && !visiting.contains(dep) {
+                let chain = longest(*dep, by_id, memo, visiting);
+                if chain.0 > best.0 || best.1.is_empty() {
+                    best = chain;
+                }
+            }
+        }
+        visiting.remove(&id);
+        best.0 += task.estimated_hours.unwrap_or(0.0);
+        best.1.push(id);
+        memo.insert(id, best.clone());
+        best
+    }
+
+    let by_id: HashMap<u32, &Task> = tasks.iter().map(|t| (t.id, t)).collect();
+    let mut memo = HashMap::new();
+    let mut best: (f32, Vec<u32>) = (0.0, Vec::new());
+    for task in tasks {
+        let chain = longest(task.id, &by_id, &mut memo, &mut HashSet::new());
+        if chain.0 > best.0 || best.1.is_empty() {
+            best = chain;
+        }
+    }
+    best.1
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::task::TaskType;
+
+    fn task(id: u32, hours: f32, depends_on: &[u32]) -> Task {
+        let mut task = Task::new(id, "Task", TaskType::Feature).with_estimate(hours);
+        task.depends_on = depends_on.to_vec();
+        task
+    }
+
+    fn date(y: i32, m: u32, d: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(y, m, d).unwrap()
+    }
+
+    #[test]
+    fn test_critical_path_takes_the_most_hours() {
+        // 1 -> 2 -> 3 is the longer chain by count, 4 -> 5 by hours
+        let tasks = [task(1, 2.0, &[]), task(2, 2.0, &[1]), task(3, 2.0, &[2]), task(4, 5.0, &[]), task(5, 4.0, &[4])];
+        assert_eq!(critical_path(&tasks), [4, 5]);
+        // Dependencies outside the task list are ignored
+        assert_eq!(critical_path(&[task(7, 1.0, &[99])]), [7]);
+        assert!(critical_path(&[]).is_empty());
+    }
+
+    #[test]
+    fn test_critical_path_ignores_edges_that_close_a_cycle() {
+        let tasks = [task(1, 3.0, &[3]), task(2, 3.0, &[1]), task(3, 3.0, &[2])];
+        let path = critical_path(&tasks);
+        assert_eq!(path.len(), 3);
+        let unique: HashSet<u32> = path.iter().copied().collect();
+        assert_eq!(unique.len(), 3);
+    }
+
+    #[test]
+    fn test_what_if_projects_an_end_date() {
+        let mut project = Project::new("Demo");
+        project.add_task(task(1, 16.0, &[]).assigned_to("Alice"));
+        project.add_task(task(2, 8.0, &[1]).assigned_to("Bob"));
+        // Friday: three working days end on Tuesday
+        let comparison = what_if(&project, date(2024, 3, 8), &[ScenarioChange::AddHeadcount(u32::MAX)]);
+
+        let current = &comparison.rows[0];
+        assert_eq!(current.critical_path, [1, 2]);
+        assert_eq!(current.projected_days, 3);
+        assert_eq!(current.projected_end, date(2024, 3, 12));
+        assert_eq!(comparison.rows[1].headcount, u32::MAX);
+        assert_eq!(comparison.rows[1].projected_days, 3);
+    }
+
+    #[test]
+    fn test_nothing_left_ends_on_the_start_date() {
+        let comparison = what_if(&Project::new("Empty"), date(2024, 3, 9), &[]);
+        assert_eq!(comparison.rows[0].projected_days, 0);
+        assert_eq!(comparison.rows[0].projected_end, date(2024, 3, 9));
+    }
+}
@@ -1,34 +1,56 @@
 //! Task Management System
+//!
+//! Run without arguments for the demo, or `cargo run -- add "<line>" ...`
+//! to quick-add tasks (see `Task::parse_quick_add` for the line format).
 
 mod task;
 mod project;
+mod store;
 mod traits;
 mod analytics;
+mod portfolio;
 
-use task::{Priority, Task, TaskType};
+use task::{Priority, StatusKind, Task, TaskStatus, TaskType};
 use project::Project;
+use analytics::ScenarioChange;
+use portfolio::{Calendar, Portfolio};
+use chrono::NaiveDate;
 use traits::{Summarizable, Statistics};
 
 fn main() {
+    // `cargo run -- add "<line>" ...` quick-adds tasks instead of running the demo
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    if args.first().is_some_and(|command| command == "add") {
+        run_add(&args[1..]);
+        return;
+    }
+
     // Create tasks
     let task1 = Task::new(1, "Fix login authentication bug", TaskType::Bug)
         .with_priority(Priority::Critical)
         .assigned_to("Alice")
-        .with_estimate(4.0);
+        .with_estimate(4.0)
+        .with_label("backend")
+        .with_label("security");
 
     let task2 = Task::new(2, "Implement dark mode", TaskType::Feature)
         .with_priority(Priority::Medium)
         .assigned_to("Bob")
-        .with_estimate(16.0);
+        .with_estimate(16.0)
+        .due_on(NaiveDate::from_ymd_opt(2024, 3, 15).unwrap())
+        .with_label("frontend");
 
     let task3 = Task::new(3, "Optimize database queries", TaskType::Improvement)
         .with_priority(Priority::High)
-        .with_estimate(8.0);
+        .with_estimate(8.0)
+        .with_label("backend")
+        .with_dependency(1);
 
     let task4 = Task::new(4, "Update API documentation", TaskType::Documentation)
         .with_priority(Priority::Low)
         .assigned_to("Charlie")
-        .with_estimate(3.0);
+        .with_estimate(3.0)
+        .with_dependency(3);
 
     // Create project and add tasks
     let mut project = Project::new("Website Redesign");
@@ -37,9 +59,24 @@ fn main() {
     project.add_task(task3);
     project.add_task(task4);
 
+    // Quick-add: one line sets the title and the fields marked with ! @ # due: est:
+    match project.quick_add("Fix login redirect !high @Alice #auth #backend due:2024-03-08 est:90m type:bug") {
+        Ok(id) => {
+            if let Some(task) = project.tasks().iter().find(|t| t.id == id) {
+                let due = task.due.map_or(String::from("-"), |d| d.to_string());
+                println!("Quick-added task-{}: {} (due {})", id, task.title, due);
+            }
+        }
+        Err(error_message) => println!("Error Message: {error_message}"),
+    }
+    if let Err(error_message) = project.quick_add("Write release notes !urgent") {
+        println!("Quick-add rejected: {error_message}");
+    }
+    println!();
+
     // Display project overview
     println!("{}\n---", project.summary());
-    for task in &project.tasks {
+    for task in project.tasks() {
         println!("  {}", task.one_line_summary());
     }
 
@@ -53,7 +90,7 @@ fn main() {
     println!();
 
     // Complete a task
-    if let Some(task) = project.find_task_mut(1) {
+    if let Some(mut task) = project.find_task_mut(1) {
         match task.start("Alice") {
             Ok(_) => println!("Alice started task-{}: {} ({:?},{:?})", task.id, task.title, task.task_type, task.priority),
             Err(error_message) => println!("Error Message: {error_message}"),
@@ -63,28 +100,174 @@ fn main() {
             Err(error_message) => println!("Error Message: {error_message}"),
         }
     }
+    // Block a task - the status index is updated when the borrow ends
+    if let Some(mut task) = project.find_task_mut(3) {
+        task.status = TaskStatus::Blocked {
+            reason: String::from("Waiting for DBA access"),
+        };
+    }
+
     println!("\nAfter completing task 1:");
     println!("{}", project.summary());
 
+    // Board view: one column per status, served from the status index
+    println!("\nBoard:");
+    for kind in [StatusKind::Todo, StatusKind::InProgress, StatusKind::Blocked, StatusKind::Completed] {
+        let titles: Vec<&str> = project.tasks_with_status(kind).map(|t| t.title.as_str()).collect();
+        println!("  {:?}: {:?}", kind, titles);
+    }
+
+    println!("\nBackend tasks:");
+    for task in project.tasks_with_label("backend") {
+        let mark = if task.status.is_done() { "✓" } else { " " };
+        println!("  {} {}", mark, task.one_line_summary());
+    }
+
+    let alice = project.tasks_assigned_to(Some("Alice")).count();
+    println!("\nTasks assigned to Alice: {}", alice);
+
     // Analytics
     println!("\nWorkload by developer:");
-    for (dev, hours) in &analytics::workload_by_assignee(&project.tasks) {
+    for (dev, hours) in &analytics::workload_by_assignee(project.tasks()) {
         println!("  {}: {:.1}h", dev, hours);
     }
 
     println!("\nBy priority:");
-    for (priority, tasks) in &analytics::tasks_by_priority(&project.tasks) {
+    for (priority, tasks) in &analytics::tasks_by_priority(project.tasks()) {
         println!("  {:?}: {}", priority, tasks.len());
     }
 
     println!("\nBy status:");
-    for (status, count) in &analytics::tasks_by_status(&project.tasks) {
+    for (status, count) in &analytics::tasks_by_status(project.tasks()) {
         println!("  {}: {}", status, count);
     }
 
     // Find unassigned tasks
-    let unassigned: Vec<_> = project.tasks.iter()
-        .filter(|t| t.assignee.is_none())
-        .collect();
+    let unassigned: Vec<_> = project.tasks_assigned_to(None).collect();
     println!("\nUnassigned tasks: {}", unassigned.len());
+
+    // Scenario planning
+    let scenarios = [
+        ScenarioChange::AddHeadcount(2),
+        ScenarioChange::RemoveTask(2),
+        ScenarioChange::ChangeEstimate { task_id: 3, hours: 20.0 },
+    ];
+    let monday = NaiveDate::from_ymd_opt(2024, 3, 4).unwrap();
+    println!("\nWhat if ({}h working days, starting {}):", analytics::HOURS_PER_DAY, monday);
+    print!("{}", analytics::what_if(&project, monday, &scenarios));
+
+    // Time tracking: timers produce work-log entries
+    let at = |day: NaiveDate, hour: u32, minute: u32| day.and_hms_opt(hour, minute, 0).unwrap();
+    let tuesday = monday.succ_opt().unwrap();
+
+    println!("\nTimers:");
+    project.start_timer(2, "Bob", at(monday, 9, 0)).unwrap();
+    if let Err(error_message) = project.start_timer(4, "Bob", at(monday, 10, 0)) {
+        println!("  Error Message: {error_message}");
+    }
+    if let Some(mut task) = project.find_task_mut(2)
+        && let Ok(entry) = task.stop_timer(at(monday, 12, 30))
+    {
+        println!("  Bob logged {:.1}h on task-2", entry.hours());
+    }
+    project.start_timer(4, "Charlie", at(monday, 23, 0)).unwrap();
+    if let Some(mut task) = project.find_task_mut(4) {
+        task.stop_timer(at(tuesday, 1, 30)).unwrap();
+    }
+
+    println!("\nTimesheet:");
+    for (developer, days) in &analytics::daily_timesheet(project.tasks()) {
+        for (day, hours) in days {
+            println!("  {} {}: {:.1}h", developer, day, hours);
+        }
+    }
+
+    // Resource leveling across projects that share developers
+    let mut mobile = Project::new("Mobile App");
+    mobile.add_task(
+        Task::new(1, "Push notifications", TaskType::Feature)
+            .with_priority(Priority::High)
+            .assigned_to("Bob")
+            .with_estimate(12.0)
+            .due_on(NaiveDate::from_ymd_opt(2024, 3, 7).unwrap()),
+    );
+    mobile.add_task(
+        Task::new(2, "Offline mode", TaskType::Feature)
+            .assigned_to("Alice")
+            .with_estimate(20.0)
+            .due_on(NaiveDate::from_ymd_opt(2024, 3, 8).unwrap())
+            .starts_on(tuesday)
+            .with_dependency(1),
+    );
+
+    let mut portfolio = Portfolio::new("Q1");
+    portfolio.add_project(project);
+    portfolio.add_project(mobile);
+    let calendar = Calendar::starting(monday)
+        .with_capacity("Alice", 6.0)
+        .with_day_off("Bob", tuesday);
+    let leveled = portfolio.level_resources(&calendar);
+    println!("\nLeveled schedule for {}:", portfolio.name);
+    print!("{}", leveled);
+    for (project, task_id) in &leveled.unleveled {
+        println!("  Can't meet deadline: {} task-{}", project, task_id);
+    }
+    for project in portfolio.projects() {
+        let scheduled = project.tasks().iter().filter(|t| t.scheduled_start.is_some()).count();
+        println!("  {}: {} tasks scheduled", project.name, scheduled);
+    }
+
+    // Portfolio timers stop a developer timing tasks in two projects at once
+    let wednesday = tuesday.succ_opt().unwrap();
+    portfolio.start_timer("Mobile App", 1, "Bob", at(wednesday, 9, 0)).unwrap();
+    if let Err(error_message) = portfolio.start_timer("Website Redesign", 4, "Bob", at(wednesday, 9, 30)) {
+        println!("\n  Error Message: {error_message}");
+    }
+    if let Ok(entry) = portfolio.stop_timer("Mobile App", 1, at(wednesday, 13, 0)) {
+        println!("  Bob logged {:.1}h on Mobile App task-1", entry.hours());
+    }
+    println!("\nPortfolio timesheet:");
+    for (developer, days) in &portfolio.timesheet() {
+        for (day, hours) in days {
+            println!("  {} {}: {:.1}h", developer, day, hours);
+        }
+    }
+}
+
+/// The `add` command: quick-adds each line to a new project and prints the
+/// resulting tasks. Exits with status 1 if there are no lines or any line
+/// is rejected.
+fn run_add(lines: &[String]) {
+    if lines.is_empty() {
+        eprintln!("usage: module-6 add \"<title> [!priority] [@assignee] [#label] [due:YYYY-MM-DD] [est:4h] [type:bug]\" ...");
+        std::process::exit(1);
+    }
+
+    let mut project = Project::new("Quick Add");
+    let mut rejected = false;
+    for line in lines {
+        if let Err(error_message) = project.quick_add(line) {
+            eprintln!("Quick-add rejected {:?}: {}", line, error_message);
+            rejected = true;
+        }
+    }
+    for task in project.tasks() {
+        let assignee = task.assignee.as_deref().unwrap_or("unassigned");
+        let due = task.due.map_or(String::from("-"), |d| d.to_string());
+        let estimate = task.estimated_hours.map_or(String::from("-"), |h| format!("{:.1}h", h));
+        println!(
+            "task-{}: {} [{:?} {:?}] {} due {} est {} labels [{}]",
+            task.id,
+            task.title,
+            task.priority,
+            task.task_type,
+            assignee,
+            due,
+            estimate,
+            task.labels.join(", ")
+        );
+    }
+    if rejected {
+        std::process::exit(1);
+    }
 }
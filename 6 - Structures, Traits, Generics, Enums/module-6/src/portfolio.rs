@@ -0,0 +1,421 @@
+//! Portfolios of projects sharing one team, and resource leveling.
+
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::fmt;
+
+use chrono::{Datelike, NaiveDate, NaiveDateTime, Weekday};
+
+use crate::analytics::{self, HOURS_PER_DAY};
+use crate::project::Project;
+use crate::task::{Task, WorkLogEntry};
+
+/// How far past its earliest start a task may be pushed before leveling
+/// gives up on it.
+const MAX_SCHEDULE_DAYS: u32 = 366;
+
+/// Who can work how many hours on which days, from a start date onwards.
+///
+/// Everyone works `HOURS_PER_DAY` on weekdays unless given a different
+/// capacity; weekends and days off have no capacity.
+#[derive(Debug, Clone)]
+pub struct Calendar {
+    pub start: NaiveDate,
+    capacities: HashMap<String, f32>,
+    days_off: HashSet<(String, NaiveDate)>,
+}
+
+impl Calendar {
+    pub fn starting(start: NaiveDate) -> Self {
+        Calendar {
+            start,
+            capacities: HashMap::new(),
+            days_off: HashSet::new(),
+        }
+    }
+
+    /// Sets the hours `developer` can work per weekday.
+    pub fn with_capacity(mut self, developer: &str, hours: f32) -> Self {
+        self.capacities.insert(String::from(developer), hours);
+        self
+    }
+
+    pub fn with_day_off(mut self, developer: &str, date: NaiveDate) -> Self {
+        self.days_off.insert((String::from(developer), date));
+        self
+    }
+
+    /// Hours `developer` can work on `date`.
+    pub fn capacity(&self, developer: &str, date: NaiveDate) -> f32 {
+        let weekend = matches!(date.weekday(), Weekday::Sat | Weekday::Sun);
+        if weekend || self.days_off.contains(&(String::from(developer), date)) {
+            return 0.0;
+        }
+        self.capacities.get(developer).copied().unwrap_or(HOURS_PER_DAY)
+    }
+}
+
+/// Several projects worked on by the same people.
+#[derive(Debug)]
+pub struct Portfolio {
+    pub name: String,
+    projects: Vec<Project>,
+}
+
+/// Where leveling placed one task.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ScheduledTask {
+    pub project: String,
+    pub task_id: u32,
+    pub assignee: Option<String>,
+    pub start: NaiveDate,
+    /// The last day work is planned on the task.
+    pub finish: NaiveDate,
+    pub due: Option<NaiveDate>,
+}
+
+impl ScheduledTask {
+    pub fn is_late(&self) -> bool {
+        self.due.is_some_and(|due| self.finish > due)
+    }
+}
+
+/// The result of `Portfolio::level_resources`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LevelingReport {
+    /// Every scheduled task, in the order it was placed.
+    pub schedule: Vec<ScheduledTask>,
+    /// Tasks that finish after their due date once leveled, or that
+    /// couldn't be placed at all, as (project, task id).
+    pub unleveled: Vec<(String, u32)>,
+}
+
+impl fmt::Display for LevelingReport {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(f, "{:<20} {:>5} {:<10} {:<10}  {:<10}  Due", "Project", "Task", "Assignee", "Start", "Finish")?;
+        for task in &self.schedule {
+            let due = task.due.map_or(String::from("-"), |d| d.to_string());
+            writeln!(
+                f,
+                "{:<20} {:>5} {:<10} {}  {}  {}{}",
+                task.project,
+                task.task_id,
+                task.assignee.as_deref().unwrap_or("-"),
+                task.start,
+                task.finish,
+                due,
+                if task.is_late() { "  LATE" } else { "" }
+            )?;
+        }
+        Ok(())
+    }
+}
+
+impl Portfolio {
+    pub fn new(name: &str) -> Self {
+        Portfolio {
+            name: String::from(name),
+            projects: Vec::new(),
+        }
+    }
+
+    pub fn add_project(&mut self, project: Project) {
+        self.projects.push(project);
+    }
+
+    pub fn projects(&self) -> &[Project] {
+        &self.projects
+    }
+
+    /// Starts `developer`'s timer on a task in the named project, refusing
+    /// if they already have a timer running on any task in the portfolio.
+    pub fn start_timer(&mut self, project: &str, task_id: u32, developer: &str, now: NaiveDateTime) -> Result<(), String> {
+        for other in &self.projects {
+            if let Some(busy) = other.tasks().iter().find(|t| t.timer_owner() == Some(developer)) {
+                return Err(format!("{} is already timing task {} in {}", developer, busy.id, other.name));
+            }
+        }
+        self.project_mut(project)?.start_timer(task_id, developer, now)
+    }
+
+    /// Stops the timer on a task in the named project, returning the
+    /// logged session.
+    pub fn stop_timer(&mut self, project: &str, task_id: u32, now: NaiveDateTime) -> Result<WorkLogEntry, String> {
+        let mut task = self
+            .project_mut(project)?
+            .find_task_mut(task_id)
+            .ok_or_else(|| format!("No task {} in {}", task_id, project))?;
+        task.stop_timer(now).cloned()
+    }
+
+    /// Hours logged per developer per day across all projects; see
+    /// `analytics::daily_timesheet`.
+    pub fn timesheet(&self) -> BTreeMap<String, BTreeMap<NaiveDate, f32>> {
+        let mut sheet: BTreeMap<String, BTreeMap<NaiveDate, f32>> = BTreeMap::new();
+        for project in &self.projects {
+            for (developer, days) in analytics::daily_timesheet(project.tasks()) {
+                let total = sheet.entry(developer).or_default();
+                for (day, hours) in days {
+                    *total.entry(day).or_default() += hours;
+                }
+            }
+        }
+        sheet
+    }
+
+    fn project_mut(&mut self, name: &str) -> Result<&mut Project, String> {
+        self.projects
+            .iter_mut()
+            .find(|p| p.name == name)
+            .ok_or_else(|| format!("No project {}", name))
+    }
+
+    /// Reschedules every unfinished task so no developer is booked past
+    /// their capacity on any day, and sets each task's `scheduled_start`.
+    ///
+    /// Tasks are placed one at a time once their dependencies (within the
+    /// same project) are placed: earliest due date first, then highest
+    /// priority. A task starts no earlier than the calendar start, its
+    /// `pinned_start` and the day after its dependencies finish, and takes
+    /// the free hours of its assignee from then on. The previous
+    /// `scheduled_start` is ignored, so running it again with the same
+    /// calendar gives the same schedule. Unassigned
+    /// tasks aren't limited by capacity. A task without an estimate takes
+    /// one day.
+    ///
+    /// Tasks that end up finishing after their due date are still
+    /// scheduled, and are listed in `unleveled`, as are tasks that can't be
+    /// placed because of a dependency cycle or a lack of capacity.
+    pub fn level_resources(&mut self, calendar: &Calendar) -> LevelingReport {
+        let mut pending: Vec<(usize, &Task)> = Vec::new();
+        for (p, project) in self.projects.iter().enumerate() {
+            pending.extend(project.tasks().iter().filter(|t| !t.status.is_done()).map(|t| (p, t)));
+        }
+        // (project, task id) of everything still waiting to be placed
+        let mut pending_ids: HashSet<(usize, u32)> = pending.iter().map(|(p, t)| (*p, t.id)).collect();
+
+        let mut booked: HashMap<(String, NaiveDate), f32> = HashMap::new();
+        let mut finished: HashMap<(usize, u32), NaiveDate> = HashMap::new();
+        let mut unplaced: HashSet<(usize, u32)> = HashSet::new();
+        let mut placed: Vec<(usize, ScheduledTask)> = Vec::new();
+        let mut unleveled = Vec::new();
+
+        loop {
+            let is_ready = |(p, task): &(usize, &Task)| task.depends_on.iter().all(|dep| !pending_ids.contains(&(*p, *dep)));
+            let next = pending
+                .iter()
+                .enumerate()
+                .filter(|(_, entry)| is_ready(entry))
+                .min_by_key(|(_, (p, task))| (task.due.is_none(), task.due, std::cmp::Reverse(task.priority), *p, task.id))
+                .map(|(i, _)| i);
+            let Some(next) = next else { break };
+            let (p, task) = pending.remove(next);
+            pending_ids.remove(&(p, task.id));
+            if task.depends_on.iter().any(|dep| unplaced.contains(&(p, *dep))) {
+                unplaced.insert((p, task.id));
+                unleveled.push((self.projects[p].name.clone(), task.id));
+                continue;
+            }
+
+            let mut earliest = calendar.start.max(task.pinned_start.unwrap_or(calendar.start));
+            for dep in &task.depends_on {
+                if let Some(&done) = finished.get(&(p, *dep)) {
+                    earliest = earliest.max(done.succ_opt().expect("date in range"));
+                }
+            }
+
+            let Some((start, finish)) = book(calendar, &mut booked, task, earliest) else {
+                unplaced.insert((p, task.id));
+                unleveled.push((self.projects[p].name.clone(), task.id));
+                continue;
+            };
+            finished.insert((p, task.id), finish);
+            let scheduled = ScheduledTask {
+                project: self.projects[p].name.clone(),
+                task_id: task.id,
+                assignee: task.assignee.clone(),
+                start,
+                finish,
+                due: task.due,
+            };
+            if scheduled.is_late() {
+                unleveled.push((scheduled.project.clone(), task.id));
+            }
+            placed.push((p, scheduled));
+        }
+        // Whatever is left waits on a dependency cycle
+        unleveled.extend(pending.iter().map(|(p, t)| (self.projects[*p].name.clone(), t.id)));
+
+        // Tasks that couldn't be placed lose any start an earlier run gave them
+        let starts: HashMap<(usize, u32), NaiveDate> = placed.iter().map(|(p, s)| ((*p, s.task_id), s.start)).collect();
+        for (p, project) in self.projects.iter_mut().enumerate() {
+            let unfinished: Vec<u32> = project.tasks().iter().filter(|t| !t.status.is_done()).map(|t| t.id).collect();
+            for id in unfinished {
+                if let Some(mut task) = project.find_task_mut(id) {
+                    task.scheduled_start = starts.get(&(p, id)).copied();
+                }
+            }
+        }
+        LevelingReport {
+            schedule: placed.into_iter().map(|(_, scheduled)| scheduled).collect(),
+            unleveled,
+        }
+    }
+}
+
+/// Books `task`'s hours into its assignee's free capacity from `earliest`
+/// on, returning the first and last day worked.
+fn book(
+    calendar: &Calendar,
+    booked: &mut HashMap<(String, NaiveDate), f32>,
+    task: &Task,
+    earliest: NaiveDate,
+) -> Option<(NaiveDate, NaiveDate)> {
+    let Some(developer) = task.assignee.as_deref() else {
+        let days = (task.estimated_hours.unwrap_or(0.0) / HOURS_PER_DAY).ceil().max(1.0) as u64;
+        return Some((earliest, earliest + chrono::Days::new(days - 1)));
+    };
+
+    let mut remaining = task.estimated_hours.unwrap_or(0.0);
+    let mut day = earliest;
+    let mut plan: Vec<(NaiveDate, f32)> = Vec::new();
+    for _ in 0..MAX_SCHEDULE_DAYS {
+        let used = booked.get(&(String::from(developer), day)).copied().unwrap_or(0.0);
+        let free = calendar.capacity(developer, day) - used;
+        if free > 0.0 {
+            let hours = remaining.min(free);
+            plan.push((day, hours));
+            remaining -= hours;
+            if remaining <= 0.0 {
+                // Only book the hours once the whole task fits
+                for &(day, hours) in &plan {
+                    *booked.entry((String::from(developer), day)).or_default() += hours;
+                }
+                return Some((plan[0].0, day));
+            }
+        }
+        day = day.succ_opt()?;
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::task::TaskType;
+
+    fn date(y: i32, m: u32, d: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(y, m, d).unwrap()
+    }
+
+    fn portfolio(tasks: Vec<Task>) -> Portfolio {
+        let mut project = Project::new("Web");
+        for task in tasks {
+            project.add_task(task);
+        }
+        let mut portfolio = Portfolio::new("Q1");
+        portfolio.add_project(project);
+        portfolio
+    }
+
+    fn starts(report: &LevelingReport) -> Vec<(u32, NaiveDate, NaiveDate)> {
+        report.schedule.iter().map(|s| (s.task_id, s.start, s.finish)).collect()
+    }
+
+    #[test]
+    fn test_leveling_shifts_work_past_capacity() {
+        // Thursday 2024-03-07; Bob works 8h a day, not at weekends
+        let mut portfolio = portfolio(vec![
+            Task::new(1, "API", TaskType::Feature).assigned_to("Bob").with_estimate(12.0),
+            Task::new(2, "UI", TaskType::Feature).assigned_to("Bob").with_estimate(8.0),
+        ]);
+        let report = portfolio.level_resources(&Calendar::starting(date(2024, 3, 7)));
+
+        assert_eq!(
+            starts(&report),
+            [(1, date(2024, 3, 7), date(2024, 3, 8)), (2, date(2024, 3, 8), date(2024, 3, 11))]
+        );
+        assert!(report.unleveled.is_empty());
+    }
+
+    #[test]
+    fn test_leveling_places_dependencies_first() {
+        let mut portfolio = portfolio(vec![
+            Task::new(1, "Schema", TaskType::Feature).assigned_to("Alice").with_estimate(8.0),
+            Task::new(2, "Report", TaskType::Feature)
+                .assigned_to("Bob")
+                .with_estimate(4.0)
+                .due_on(date(2024, 3, 29))
+                .with_dependency(1),
+        ]);
+        let report = portfolio.level_resources(&Calendar::starting(date(2024, 3, 4)));
+
+        // Task 2 sorts first by due date, but waits for task 1
+        assert_eq!(
+            starts(&report),
+            [(1, date(2024, 3, 4), date(2024, 3, 4)), (2, date(2024, 3, 5), date(2024, 3, 5))]
+        );
+    }
+
+    #[test]
+    fn test_leveling_lists_missed_due_dates_and_cycles() {
+        let mut portfolio = portfolio(vec![
+            Task::new(1, "Migration", TaskType::Feature)
+                .assigned_to("Bob")
+                .with_estimate(24.0)
+                .due_on(date(2024, 3, 5)),
+            Task::new(2, "Chicken", TaskType::Feature).with_dependency(3),
+            Task::new(3, "Egg", TaskType::Feature).with_dependency(2),
+        ]);
+        let report = portfolio.level_resources(&Calendar::starting(date(2024, 3, 4)));
+
+        assert_eq!(starts(&report), [(1, date(2024, 3, 4), date(2024, 3, 6))]);
+        assert!(report.schedule[0].is_late());
+        let unleveled: Vec<u32> = report.unleveled.iter().map(|(_, id)| *id).collect();
+        assert_eq!(unleveled, [1, 2, 3]);
+    }
+
+    #[test]
+    fn test_leveling_again_gives_the_same_schedule() {
+        let mut portfolio = portfolio(vec![
+            Task::new(1, "API", TaskType::Feature).assigned_to("Bob").with_estimate(8.0),
+            Task::new(2, "Docs", TaskType::Documentation)
+                .assigned_to("Bob")
+                .with_estimate(8.0)
+                .starts_on(date(2024, 3, 6)),
+        ]);
+        let calendar = Calendar::starting(date(2024, 3, 4));
+        let first = portfolio.level_resources(&calendar);
+        let second = portfolio.level_resources(&calendar);
+
+        assert_eq!(first, second);
+        assert_eq!(starts(&first)[1], (2, date(2024, 3, 6), date(2024, 3, 6)));
+        let task = &portfolio.projects()[0].tasks()[1];
+        assert_eq!((task.pinned_start, task.scheduled_start), (Some(date(2024, 3, 6)), Some(date(2024, 3, 6))));
+
+        // An earlier calendar start can move tasks earlier again
+        let report = portfolio.level_resources(&Calendar::starting(date(2024, 3, 1)));
+        assert_eq!(starts(&report)[0], (1, date(2024, 3, 1), date(2024, 3, 1)));
+    }
+
+    #[test]
+    fn test_timers_span_the_portfolio() {
+        let mut web = Project::new("Web");
+        web.add_task(Task::new(1, "Login", TaskType::Feature));
+        let mut mobile = Project::new("Mobile");
+        mobile.add_task(Task::new(1, "Sync", TaskType::Feature));
+        let mut portfolio = Portfolio::new("Q1");
+        portfolio.add_project(web);
+        portfolio.add_project(mobile);
+        let at = |hour| date(2024, 3, 4).and_hms_opt(hour, 0, 0).unwrap();
+
+        portfolio.start_timer("Web", 1, "Bob", at(9)).unwrap();
+        let err = portfolio.start_timer("Mobile", 1, "Bob", at(10)).unwrap_err();
+        assert_eq!(err, "Bob is already timing task 1 in Web");
+        assert!(portfolio.start_timer("Desktop", 1, "Alice", at(10)).is_err());
+
+        assert_eq!(portfolio.stop_timer("Web", 1, at(11)).unwrap().hours(), 2.0);
+        portfolio.start_timer("Mobile", 1, "Bob", at(12)).unwrap();
+        portfolio.stop_timer("Mobile", 1, at(13)).unwrap();
+        assert_eq!(portfolio.timesheet()["Bob"][&date(2024, 3, 4)], 3.0);
+    }
+}
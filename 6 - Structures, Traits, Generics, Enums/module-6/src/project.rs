@@ -1,35 +1,81 @@
 //! Project containing multiple tasks.
 
-use crate::task::Task;
+use chrono::NaiveDateTime;
+
+use crate::store::{TaskMut, TaskStore};
+use crate::task::{StatusKind, Task};
 
 /// A project with a collection of tasks.
+///
+/// Tasks live in an indexed `TaskStore`, so lookups and filters stay fast
+/// on large backlogs.
 #[derive(Debug)]
 pub struct Project {
     pub name: String,
-    pub tasks: Vec<Task>,
+    tasks: TaskStore,
 }
 
 impl Project {
     pub fn new(name: &str) -> Self {
         Project {
             name: String::from(name),
-            tasks: Vec::new(),
+            tasks: TaskStore::default(),
         }
     }
 
+    /// Adds a task, replacing any existing task with the same id.
     pub fn add_task(&mut self, task: Task) {
-        self.tasks.push(task);
+        self.tasks.insert(task);
+    }
+
+    /// Adds a task described by a quick-add line (see
+    /// `Task::parse_quick_add`) under the next free id, returning the id.
+    pub fn quick_add(&mut self, line: &str) -> Result<u32, String> {
+        let id = self.tasks().iter().map(|t| t.id).max().map_or(1, |max| max + 1);
+        self.add_task(Task::parse_quick_add(id, line)?);
+        Ok(id)
+    }
+
+    /// All tasks in the order they were added.
+    pub fn tasks(&self) -> &[Task] {
+        self.tasks.as_slice()
     }
 
     pub fn completion_percentage(&self) -> f32 {
-        if self.tasks.is_empty() {
+        let total = self.tasks.as_slice().len();
+        if total == 0 {
             return 0.0;
         }
-        let completed = self.tasks.iter().filter(|t| t.status.is_done()).count();
-        (completed as f32 / self.tasks.len() as f32) * 100.0
+        let completed = self.tasks.count_with_status(StatusKind::Completed);
+        (completed as f32 / total as f32) * 100.0
+    }
+
+    pub fn find_task_mut(&mut self, id: u32) -> Option<TaskMut<'_>> {
+        self.tasks.get_mut(id)
+    }
+
+    /// Starts `developer`'s timer on a task, refusing if they already have
+    /// a timer running on any task in the project.
+    pub fn start_timer(&mut self, task_id: u32, developer: &str, now: NaiveDateTime) -> Result<(), String> {
+        if let Some(busy) = self.tasks().iter().find(|t| t.timer_owner() == Some(developer)) {
+            return Err(format!("{} is already timing task {}", developer, busy.id));
+        }
+        let mut task = self
+            .find_task_mut(task_id)
+            .ok_or_else(|| format!("No task {}", task_id))?;
+        task.start_timer(developer, now)
+    }
+
+    pub fn tasks_with_status(&self, kind: StatusKind) -> impl Iterator<Item = &Task> {
+        self.tasks.with_status(kind)
+    }
+
+    /// Tasks assigned to `person`, or unassigned tasks for `None`.
+    pub fn tasks_assigned_to(&self, person: Option<&str>) -> impl Iterator<Item = &Task> {
+        self.tasks.assigned_to(person)
     }
 
-    pub fn find_task_mut(&mut self, id: u32) -> Option<&mut Task> {
-        self.tasks.iter_mut().find(|t| t.id == id)
+    pub fn tasks_with_label(&self, label: &str) -> impl Iterator<Item = &Task> {
+        self.tasks.with_label(label)
     }
 }
@@ -0,0 +1,188 @@
+//! Indexed task storage backing `Project`.
+
+use std::collections::{BTreeSet, HashMap};
+use std::ops::{Deref, DerefMut};
+
+use crate::task::{StatusKind, Task};
+
+/// Tasks in insertion order, plus hash indexes for id, status, assignee and label.
+///
+/// Secondary indexes hold slots in `BTreeSet`s so filtered results keep
+/// insertion order.
+#[derive(Debug, Default)]
+pub struct TaskStore {
+    tasks: Vec<Task>,
+    by_id: HashMap<u32, usize>,
+    by_status: HashMap<StatusKind, BTreeSet<usize>>,
+    by_assignee: HashMap<Option<String>, BTreeSet<usize>>,
+    by_label: HashMap<String, BTreeSet<usize>>,
+}
+
+impl TaskStore {
+    /// Adds a task, replacing any existing task with the same id.
+    pub fn insert(&mut self, task: Task) {
+        let slot = match self.by_id.get(&task.id) {
+            Some(&slot) => {
+                self.unindex(slot);
+                self.tasks[slot] = task;
+                slot
+            }
+            None => {
+                self.tasks.push(task);
+                self.tasks.len() - 1
+            }
+        };
+        self.index(slot);
+    }
+
+    /// Mutable access that re-indexes the task when the guard is dropped.
+    pub fn get_mut(&mut self, id: u32) -> Option<TaskMut<'_>> {
+        let slot = *self.by_id.get(&id)?;
+        self.unindex(slot);
+        Some(TaskMut { store: self, slot, original_id: id })
+    }
+
+    pub fn as_slice(&self) -> &[Task] {
+        &self.tasks
+    }
+
+    pub fn count_with_status(&self, kind: StatusKind) -> usize {
+        self.by_status.get(&kind).map_or(0, BTreeSet::len)
+    }
+
+    pub fn with_status(&self, kind: StatusKind) -> impl Iterator<Item = &Task> {
+        self.lookup(self.by_status.get(&kind))
+    }
+
+    /// Tasks assigned to `person`, or unassigned tasks for `None`.
+    pub fn assigned_to(&self, person: Option<&str>) -> impl Iterator<Item = &Task> {
+        self.lookup(self.by_assignee.get(&person.map(String::from)))
+    }
+
+    pub fn with_label(&self, label: &str) -> impl Iterator<Item = &Task> {
+        self.lookup(self.by_label.get(label))
+    }
+
+    fn lookup<'a>(&'a self, slots: Option<&'a BTreeSet<usize>>) -> impl Iterator<Item = &'a Task> {
+        slots.into_iter().flatten().map(|&slot| &self.tasks[slot])
+    }
+
+    fn index(&mut self, slot: usize) {
+        let task = &self.tasks[slot];
+        self.by_id.insert(task.id, slot);
+        self.by_status.entry(task.status.kind()).or_default().insert(slot);
+        self.by_assignee.entry(task.assignee.clone()).or_default().insert(slot);
+        for label in &task.labels {
+            self.by_label.entry(label.clone()).or_default().insert(slot);
+        }
+    }
+
+    fn unindex(&mut self, slot: usize) {
+        let task = &self.tasks[slot];
+        self.by_id.remove(&task.id);
+        if let Some(slots) = self.by_status.get_mut(&task.status.kind()) {
+            slots.remove(&slot);
+        }
+        if let Some(slots) = self.by_assignee.get_mut(&task.assignee) {
+            slots.remove(&slot);
+        }
+        for label in &task.labels {
+            if let Some(slots) = self.by_label.get_mut(label) {
+                slots.remove(&slot);
+            }
+        }
+    }
+}
+
+/// A mutable borrow of one stored task.
+///
+/// Derefs to `Task`; on drop the task is indexed again under its new
+/// status, assignee and labels.
+///
+/// The id can't be changed this way: it is the key of the task's slot, and
+/// another task may already have the new one. Drop puts the old id back.
+pub struct TaskMut<'a> {
+    store: &'a mut TaskStore,
+    slot: usize,
+    original_id: u32,
+}
+
+impl Deref for TaskMut<'_> {
+    type Target = Task;
+
+    fn deref(&self) -> &Task {
+        &self.store.tasks[self.slot]
+    }
+}
+
+impl DerefMut for TaskMut<'_> {
+    fn deref_mut(&mut self) -> &mut Task {
+        &mut self.store.tasks[self.slot]
+    }
+}
+
+impl Drop for TaskMut<'_> {
+    fn drop(&mut self) {
+        self.store.tasks[self.slot].id = self.original_id;
+        self.store.index(self.slot);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::task::{TaskStatus, TaskType};
+
+    fn store() -> TaskStore {
+        let mut store = TaskStore::default();
+        store.insert(Task::new(1, "Parser", TaskType::Feature).assigned_to("Alice").with_label("core"));
+        store.insert(Task::new(2, "Docs", TaskType::Documentation).with_label("docs"));
+        store
+    }
+
+    fn ids<'a>(tasks: impl Iterator<Item = &'a Task>) -> Vec<u32> {
+        tasks.map(|t| t.id).collect()
+    }
+
+    #[test]
+    fn test_guard_reindexes_status_assignee_and_labels() {
+        let mut store = store();
+        {
+            let mut task = store.get_mut(1).unwrap();
+            task.status = TaskStatus::InProgress { started_by: String::from("Bob") };
+            task.assignee = Some(String::from("Bob"));
+            task.labels = vec![String::from("docs")];
+        }
+
+        assert_eq!(ids(store.with_status(StatusKind::InProgress)), [1]);
+        assert_eq!(ids(store.with_status(StatusKind::Todo)), [2]);
+        assert_eq!(store.count_with_status(StatusKind::Todo), 1);
+        assert_eq!(ids(store.assigned_to(Some("Bob"))), [1]);
+        assert_eq!(store.assigned_to(Some("Alice")).count(), 0);
+        assert_eq!(ids(store.with_label("docs")), [1, 2]);
+        assert_eq!(store.with_label("core").count(), 0);
+    }
+
+    #[test]
+    fn test_guard_keeps_the_id() {
+        let mut store = store();
+        store.get_mut(1).unwrap().id = 2;
+
+        assert_eq!(ids(store.as_slice().iter()), [1, 2]);
+        assert_eq!(store.get_mut(2).unwrap().title, "Docs");
+        assert_eq!(store.get_mut(1).unwrap().title, "Parser");
+    }
+
+    #[test]
+    fn test_insert_replaces_a_task_with_the_same_id() {
+        let mut store = store();
+        store.insert(Task::new(1, "Lexer", TaskType::Bug).with_label("bugs"));
+
+        assert_eq!(store.as_slice().len(), 2);
+        assert_eq!(store.as_slice()[0].title, "Lexer");
+        assert_eq!(store.assigned_to(Some("Alice")).count(), 0);
+        assert_eq!(ids(store.assigned_to(None)), [1, 2]);
+        assert_eq!(store.with_label("core").count(), 0);
+        assert_eq!(ids(store.with_label("bugs")), [1]);
+    }
+}
@@ -1,7 +1,9 @@
 //! Task and related types.
 
-/// Priority levels for tasks.
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+use chrono::{NaiveDate, NaiveDateTime};
+
+/// Priority levels for tasks, from lowest to highest.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub enum Priority {
     Low,
     Medium,
@@ -27,10 +29,42 @@ pub enum TaskStatus {
     Completed { completed_by: String, hours_spent: f32 },
 }
 
+/// The kind of a `TaskStatus`, without its data. Used as an index key.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum StatusKind {
+    Todo,
+    InProgress,
+    Blocked,
+    Completed,
+}
+
 impl TaskStatus {
     pub fn is_done(&self) -> bool {
         matches!(self, TaskStatus::Completed { .. })
     }
+
+    pub fn kind(&self) -> StatusKind {
+        match self {
+            TaskStatus::Todo => StatusKind::Todo,
+            TaskStatus::InProgress { .. } => StatusKind::InProgress,
+            TaskStatus::Blocked { .. } => StatusKind::Blocked,
+            TaskStatus::Completed { .. } => StatusKind::Completed,
+        }
+    }
+}
+
+/// One timed work session on a task.
+#[derive(Debug, Clone, PartialEq)]
+pub struct WorkLogEntry {
+    pub developer: String,
+    pub started: NaiveDateTime,
+    pub stopped: NaiveDateTime,
+}
+
+impl WorkLogEntry {
+    pub fn hours(&self) -> f32 {
+        (self.stopped - self.started).num_seconds() as f32 / 3600.0
+    }
 }
 
 /// A task in the system.
@@ -43,6 +77,19 @@ pub struct Task {
     pub task_type: TaskType,
     pub assignee: Option<String>,
     pub estimated_hours: Option<f32>,
+    pub due: Option<NaiveDate>,
+    /// Earliest day work may start, pinned by whoever plans the task.
+    pub pinned_start: Option<NaiveDate>,
+    /// Planned first day of work; set by `Portfolio::level_resources`,
+    /// which never starts a task before its `pinned_start`.
+    pub scheduled_start: Option<NaiveDate>,
+    pub labels: Vec<String>,
+    /// Ids of tasks that must finish before this one can start.
+    pub depends_on: Vec<u32>,
+    /// Finished timer sessions, oldest first.
+    pub work_log: Vec<WorkLogEntry>,
+    /// The running timer's developer and start time, if any.
+    timer: Option<(String, NaiveDateTime)>,
 }
 
 impl Task {
@@ -55,6 +102,13 @@ impl Task {
             task_type,
             assignee: None,
             estimated_hours: None,
+            due: None,
+            pinned_start: None,
+            scheduled_start: None,
+            labels: Vec::new(),
+            depends_on: Vec::new(),
+            work_log: Vec::new(),
+            timer: None,
         }
     }
 
@@ -73,6 +127,137 @@ impl Task {
         self
     }
 
+    pub fn due_on(mut self, date: NaiveDate) -> Self {
+        self.due = Some(date);
+        self
+    }
+
+    /// Pins the earliest start; see `pinned_start`.
+    pub fn starts_on(mut self, date: NaiveDate) -> Self {
+        self.pinned_start = Some(date);
+        self
+    }
+
+    pub fn with_label(mut self, label: &str) -> Self {
+        self.labels.push(String::from(label));
+        self
+    }
+
+    pub fn with_dependency(mut self, task_id: u32) -> Self {
+        self.depends_on.push(task_id);
+        self
+    }
+
+    /// Builds a task from a one-line description, for fast entry:
+    ///
+    /// ```text
+    /// Fix login bug !critical @Alice #auth due:2025-07-01 est:4h
+    /// ```
+    ///
+    /// Words are read left to right. Marked words set a field; every other
+    /// word is part of the title.
+    ///
+    /// - `!low`, `!medium`, `!high`, `!critical` - priority (default medium)
+    /// - `@name` - assignee
+    /// - `#label` - a label; may be repeated
+    /// - `due:YYYY-MM-DD` - due date
+    /// - `est:4h`, `est:1.5h`, `est:90m` - estimate
+    /// - `type:bug`, `type:feature`, `type:improvement`, `type:docs` -
+    ///   task type (default feature)
+    ///
+    /// Errors name the offending word, e.g. `word 3 "!urgent": unknown priority`.
+    pub fn parse_quick_add(id: u32, line: &str) -> Result<Task, String> {
+        let mut task = Task::new(id, "", TaskType::Feature);
+        let mut title = Vec::new();
+        let mut seen = Vec::new();
+
+        for (position, word) in line.split_whitespace().enumerate() {
+            let fail = |message: &str| format!("word {} {:?}: {}", position + 1, word, message);
+            let (field, value) = if let Some(value) = word.strip_prefix('!') {
+                ("priority", value)
+            } else if let Some(value) = word.strip_prefix('@') {
+                ("assignee", value)
+            } else if let Some(value) = word.strip_prefix('#') {
+                ("label", value)
+            } else if let Some((key @ ("due" | "est" | "type"), value)) = word.split_once(':') {
+                (key, value)
+            } else {
+                title.push(word);
+                continue;
+            };
+
+            if value.is_empty() {
+                return Err(fail("missing value"));
+            }
+            if field != "label" {
+                if seen.contains(&field) {
+                    return Err(fail(&format!("{} given twice", field)));
+                }
+                seen.push(field);
+            }
+            match field {
+                "priority" => task.priority = parse_priority(value).ok_or_else(|| fail("unknown priority"))?,
+                "assignee" => task.assignee = Some(String::from(value)),
+                "label" => {
+                    if !task.labels.iter().any(|l| l == value) {
+                        task.labels.push(String::from(value));
+                    }
+                }
+                "due" => {
+                    let date = NaiveDate::parse_from_str(value, "%Y-%m-%d")
+                        .map_err(|_| fail("due date must be YYYY-MM-DD"))?;
+                    task.due = Some(date);
+                }
+                "est" => {
+                    let hours = parse_estimate(value).ok_or_else(|| fail("estimate must look like 4h or 30m"))?;
+                    task.estimated_hours = Some(hours);
+                }
+                _ => task.task_type = parse_task_type(value).ok_or_else(|| fail("unknown task type"))?,
+            }
+        }
+
+        if title.is_empty() {
+            return Err(String::from("task has no title"));
+        }
+        task.title = title.join(" ");
+        Ok(task)
+    }
+
+    /// Developer whose timer is running on this task, if any.
+    pub fn timer_owner(&self) -> Option<&str> {
+        self.timer.as_ref().map(|(developer, _)| developer.as_str())
+    }
+
+    /// Starts timing work on this task. Only one timer runs per task.
+    ///
+    /// Crate-private: `Project::start_timer` and `Portfolio::start_timer`
+    /// also stop a developer from timing two tasks at once.
+    pub(crate) fn start_timer(&mut self, developer: &str, now: NaiveDateTime) -> Result<(), String> {
+        if let Some((running, _)) = &self.timer {
+            return Err(format!("Timer already running for {}", running));
+        }
+        if self.status.is_done() {
+            return Err(String::from("Task already completed"));
+        }
+        self.timer = Some((String::from(developer), now));
+        Ok(())
+    }
+
+    /// Stops the running timer and appends the session to the work log.
+    pub fn stop_timer(&mut self, now: NaiveDateTime) -> Result<&WorkLogEntry, String> {
+        let (developer, started) = self.timer.take().ok_or("No timer running")?;
+        if now < started {
+            self.timer = Some((developer, started));
+            return Err(String::from("Timer cannot stop before it started"));
+        }
+        self.work_log.push(WorkLogEntry {
+            developer,
+            started,
+            stopped: now,
+        });
+        Ok(self.work_log.last().expect("just pushed"))
+    }
+
     pub fn start(&mut self, developer: &str) -> Result<(), String> {
         match &self.status {
             TaskStatus::Todo => {
@@ -106,3 +291,33 @@ impl Task {
         }
     }
 }
+
+fn parse_priority(name: &str) -> Option<Priority> {
+    match name.to_lowercase().as_str() {
+        "low" => Some(Priority::Low),
+        "medium" => Some(Priority::Medium),
+        "high" => Some(Priority::High),
+        "critical" => Some(Priority::Critical),
+        _ => None,
+    }
+}
+
+fn parse_task_type(name: &str) -> Option<TaskType> {
+    match name.to_lowercase().as_str() {
+        "bug" => Some(TaskType::Bug),
+        "feature" => Some(TaskType::Feature),
+        "improvement" => Some(TaskType::Improvement),
+        "docs" | "documentation" => Some(TaskType::Documentation),
+        _ => None,
+    }
+}
+
+/// Hours from `4h`, `1.5h` or `90m`; `None` if malformed or not positive.
+fn parse_estimate(text: &str) -> Option<f32> {
+    let hours = if let Some(hours) = text.strip_suffix('h') {
+        hours.parse::<f32>().ok()?
+    } else {
+        text.strip_suffix('m')?.parse::<f32>().ok()? / 60.0
+    };
+    (hours.is_finite() && hours > 0.0).then_some(hours)
+}
@@ -36,7 +36,7 @@ impl Summarizable for Project {
         format!(
             "Project: {} ({} tasks, {:.1}% complete)",
             self.name,
-            self.tasks.len(),
+            self.tasks().len(),
             self.completion_percentage()
         )
     }
@@ -50,7 +50,7 @@ pub trait Statistics {
 
 impl Statistics for Project {
     fn total_estimate(&self) -> Option<f32> {
-        let estimates: Vec<f32> = self.tasks
+        let estimates: Vec<f32> = self.tasks()
             .iter()
             .filter_map(|t| t.estimated_hours)
             .collect();
@@ -64,10 +64,10 @@ impl Statistics for Project {
 
     fn average_estimate(&self) -> Option<f32> {
         let total = self.total_estimate()?;
-        if self.tasks.is_empty() {
+        if self.tasks().is_empty() {
             None
         } else {
-            Some(total / self.tasks.len() as f32)
+            Some(total / self.tasks().len() as f32)
         }
     }
 }
@@ -0,0 +1,102 @@
+// =============================================================================
+// BENCHES/ARENA.RS - Vec/String extraction vs arena + interning
+// =============================================================================
+//
+// Run with:
+//   cargo bench --features arena
+//
+// A counting global allocator records how many heap allocations each
+// approach makes, alongside wall-clock time, on a multi-megabyte input.
+// =============================================================================
+
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::Instant;
+
+use module_7::arena::{extract_words_in, Arena};
+use module_7::frequency::WordFrequency;
+use module_7::word::extract_words;
+
+// -----------------------------------------------------------------------------
+// COUNTING ALLOCATOR
+// -----------------------------------------------------------------------------
+
+struct CountingAlloc;
+
+static ALLOCATIONS: AtomicUsize = AtomicUsize::new(0);
+
+unsafe impl GlobalAlloc for CountingAlloc {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        unsafe { System.alloc(layout) }
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        unsafe { System.dealloc(ptr, layout) }
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        unsafe { System.realloc(ptr, layout, new_size) }
+    }
+}
+
+#[global_allocator]
+static GLOBAL: CountingAlloc = CountingAlloc;
+
+/// Runs `f`, returning its result plus (allocations, milliseconds).
+fn measure<T>(f: impl FnOnce() -> T) -> (T, usize, u128) {
+    let before = ALLOCATIONS.load(Ordering::Relaxed);
+    let start = Instant::now();
+    let result = f();
+    let elapsed = start.elapsed().as_millis();
+    (result, ALLOCATIONS.load(Ordering::Relaxed) - before, elapsed)
+}
+
+/// Builds roughly `target_bytes` of prose: a small set of common words mixed
+/// with a long tail of rarer ones (about 1,300 distinct words in total).
+fn sample_text(target_bytes: usize) -> String {
+    let vocabulary = [
+        "Rust", "is", "a", "systems", "programming", "language", "that", "provides",
+        "memory", "safety", "without", "garbage", "collection", "The", "ownership",
+        "model", "ensures", "safe", "concurrency", "and", "zero-cost", "abstractions.",
+    ];
+    let mut text = String::with_capacity(target_bytes + 64);
+    let mut i = 0usize;
+    while text.len() < target_bytes {
+        if i % 4 == 3 {
+            text.push_str(&format!("Term{}", (i * 31) % 5_000));
+        } else {
+            text.push_str(vocabulary[i % vocabulary.len()]);
+        }
+        text.push(if i % 12 == 11 { '\n' } else { ' ' });
+        i += 1;
+    }
+    text
+}
+
+fn main() {
+    for megabytes in [1, 4, 16] {
+        let text = sample_text(megabytes * 1024 * 1024);
+        // (sample_text's own allocations happen before measuring starts)
+        println!("=== {} MB input ===", megabytes);
+
+        let (baseline, allocs, ms) = measure(|| {
+            let words = extract_words(&text);
+            let freq = WordFrequency::from_words(&words);
+            (words.len(), freq.unique_count())
+        });
+        println!("  Vec + String per token : {:>9} allocations, {:>5} ms", allocs, ms);
+
+        let (arena_result, allocs, ms) = measure(|| {
+            let arena = Arena::with_capacity(text.len() * 4);
+            let words = extract_words_in(&arena, &text);
+            let freq = words.frequency(&arena);
+            (words.len(), freq.unique_count())
+        });
+        println!("  Arena + interning      : {:>9} allocations, {:>5} ms", allocs, ms);
+
+        assert_eq!(baseline, arena_result, "both paths must agree");
+        println!("  words: {}, unique: {}\n", baseline.0, baseline.1);
+    }
+}
@@ -22,11 +22,21 @@
 //
 // =============================================================================
 
-use std::fmt;
-
-use crate::error::{AnalysisError, AnalysisResult};
+use core::fmt;
+#[cfg(feature = "std")]
+use std::io::{self, BufRead};
+
+#[cfg(feature = "std")]
+use crate::error::AnalysisError;
+use crate::error::AnalysisResult;
+use crate::frequency::WordFrequency;
+use crate::prelude::*;
+use crate::readability::Readability;
+#[cfg(feature = "std")]
+use crate::stats::TextStatsBuilder;
 use crate::stats::TextStats;
-use crate::word::{extract_words, try_extract_words};
+use crate::stopwords::Stopwords;
+use crate::word::{extract_words, try_extract_words, Word};
 
 // =============================================================================
 // FUNCTION TYPE ALIAS
@@ -108,6 +118,9 @@ pub struct TextAnalyzer {
     // The formatter field holds a function pointer.
     // Different TextAnalyzer instances can have different formatters.
     formatter: Formatter,
+    // OPTION AS A TOGGLE:
+    // Some(list) filters the word counts, None counts every word.
+    stopwords: Option<Stopwords>,
 }
 
 impl TextAnalyzer {
@@ -121,7 +134,10 @@ impl TextAnalyzer {
     // -------------------------------------------------------------------------
 
     pub fn new(formatter: Formatter) -> TextAnalyzer {
-        TextAnalyzer { formatter }
+        TextAnalyzer {
+            formatter,
+            stopwords: None,
+        }
     }
 
     /// Convenience constructor with simple formatting.
@@ -133,6 +149,32 @@ impl TextAnalyzer {
         TextAnalyzer::new(simple_format)
     }
 
+    /// Leaves `stopwords` out of the report's top words.
+    pub fn with_stopwords(mut self, stopwords: Stopwords) -> TextAnalyzer {
+        self.stopwords = Some(stopwords);
+        self
+    }
+
+    /// Counts every word again.
+    pub fn without_stopwords(mut self) -> TextAnalyzer {
+        self.stopwords = None;
+        self
+    }
+
+    /// The stopwords in use, if filtering is on.
+    pub fn stopwords(&self) -> Option<&Stopwords> {
+        self.stopwords.as_ref()
+    }
+
+    /// Word counts for `words`, without stopwords if filtering is on.
+    pub fn frequency(&self, words: &[Word]) -> WordFrequency {
+        // MATCH ON Option<&T>: borrow the list rather than move it out of self
+        match &self.stopwords {
+            Some(stopwords) => WordFrequency::from_words_excluding(words, stopwords),
+            None => WordFrequency::from_words(words),
+        }
+    }
+
     // -------------------------------------------------------------------------
     // CALLING STORED FUNCTION
     // -------------------------------------------------------------------------
@@ -161,8 +203,8 @@ impl TextAnalyzer {
     // Don't Repeat Yourself (DRY)
     // -------------------------------------------------------------------------
 
-    fn build_report(&self, stats: &TextStats) -> AnalysisReport {
-        let lines = vec![
+    fn build_report(&self, stats: &TextStats, frequency: &WordFrequency) -> AnalysisReport {
+        let mut lines = vec![
             self.format_line("Total words", &stats.total_words.to_string()),
             self.format_line("Total characters", &stats.total_chars.to_string()),
             self.format_line(
@@ -174,7 +216,30 @@ impl TextAnalyzer {
             self.format_line("Capitalized words", &stats.capitalized_count.to_string()),
             // {:?} uses Debug formatting for the enum
             self.format_line("Reading level", &format!("{:?}", stats.reading_level)),
+            self.format_line("Sentences", &stats.sentence_count.to_string()),
+            self.format_line(
+                "Average sentence length",
+                &format!("{:.2}", stats.avg_sentence_length),
+            ),
+            self.format_line("Longest sentence", &stats.longest_sentence_len.to_string()),
         ];
+        let top_words: Vec<String> = frequency
+            .top_n(5)
+            .into_iter()
+            .map(|(word, count)| format!("{} ({})", word, count))
+            .collect();
+        lines.push(self.format_line("Top words", &top_words.join(", ")));
+        // if let: the scores only exist for text with words and sentences
+        if let Some(scores) = Readability::from_stats(stats) {
+            lines.push(self.format_line(
+                "Flesch reading ease",
+                &format!("{:.1} ({})", scores.reading_ease, scores.difficulty()),
+            ));
+            lines.push(self.format_line(
+                "Flesch-Kincaid grade",
+                &format!("{:.1}", scores.grade_level),
+            ));
+        }
         AnalysisReport { lines }
     }
 
@@ -182,8 +247,8 @@ impl TextAnalyzer {
     /// This version never fails (returns AnalysisReport directly).
     pub fn analyze(&self, text: &str) -> AnalysisReport {
         let words = extract_words(text);
-        let stats = TextStats::from_words(&words);
-        self.build_report(&stats)
+        let stats = TextStats::from_words(&words).with_sentences(text);
+        self.build_report(&stats, &self.frequency(&words))
     }
 
     // -------------------------------------------------------------------------
@@ -218,10 +283,58 @@ impl TextAnalyzer {
         // If it returns Ok(words), we get the words and continue.
         let words = try_extract_words(text)?;
 
-        let stats = TextStats::from_words(&words);
+        let stats = TextStats::from_words(&words).with_sentences(text);
 
         // Wrap successful result in Ok
-        Ok(self.build_report(&stats))
+        Ok(self.build_report(&stats, &self.frequency(&words)))
+    }
+
+    // -------------------------------------------------------------------------
+    // STREAMING: OWNED STATE, ONE LINE AT A TIME
+    // -------------------------------------------------------------------------
+    //
+    // analyze() needs the whole text: every Word borrows from it. Here each
+    // line is read into the same String buffer, its Words live only until
+    // the line is counted, and everything kept between lines is owned
+    // (totals in TextStatsBuilder, String keys in the word counts). Memory
+    // grows with the vocabulary, not with the input.
+    //
+    // `impl BufRead` accepts a BufReader<File>, stdin().lock(), or a byte
+    // slice (&[u8]) in tests.
+    // -------------------------------------------------------------------------
+
+    /// Analyzes text from a reader a line at a time, without loading it all;
+    /// the report is the same as `analyze()` on the full text.
+    ///
+    /// Only available with the `std` feature.
+    ///
+    /// # Errors
+    ///
+    /// Any error from the reader, including `InvalidData` for input that
+    /// isn't UTF-8.
+    #[cfg(feature = "std")]
+    pub fn analyze_reader(&self, mut reader: impl BufRead) -> io::Result<AnalysisReport> {
+        let mut stats = TextStatsBuilder::new();
+        let mut counts: HashMap<String, usize> = HashMap::new();
+        let mut line = String::new();
+
+        // read_line() appends to the buffer and returns 0 at end of input
+        while reader.read_line(&mut line)? > 0 {
+            let words = extract_words(&line);
+            stats.add_words(&words);
+            stats.add_sentences(&line);
+
+            let counted = words
+                .iter()
+                .filter(|w| self.stopwords.as_ref().is_none_or(|s| !s.contains(w.text)));
+            for word in counted {
+                *counts.entry(word.text.to_lowercase()).or_insert(0) += 1;
+            }
+            // Reuse the buffer's allocation for the next line
+            line.clear();
+        }
+
+        Ok(self.build_report(&stats.finish(), &WordFrequency::from_counts(counts)))
     }
 }
 
@@ -311,6 +424,9 @@ pub fn format_with_all(label: &str, value: &str, formatters: &[Formatter]) -> Ve
 // =============================================================================
 
 /// Handle analysis result with match expression.
+///
+/// Only available with the `std` feature because it prints to stdout.
+#[cfg(feature = "std")]
 pub fn handle_analysis_result(result: AnalysisResult<AnalysisReport>) {
     match result {
         // SUCCESS CASE:
@@ -340,3 +456,51 @@ pub fn handle_analysis_result(result: AnalysisResult<AnalysisReport>) {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_report_lines() {
+        let text = "The cat sat. The cat ran away!";
+        let report = TextAnalyzer::with_simple_format().analyze(text);
+        assert_eq!(report.lines[0], "Total words: 7");
+        assert!(report.lines.contains(&String::from("Sentences: 2")));
+        assert!(report.lines.contains(&String::from("Top words: cat (2), the (2), away (1), ran (1), sat (1)")));
+
+        let filtered = TextAnalyzer::with_simple_format().with_stopwords(Stopwords::english()).analyze(text);
+        assert!(filtered.lines.contains(&String::from("Top words: cat (2), away (1), ran (1), sat (1)")));
+        assert!(matches!(
+            TextAnalyzer::with_simple_format().try_analyze("?!"),
+            Err(crate::error::AnalysisError::NoWordsFound)
+        ));
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_analyze_reader_matches_analyze() {
+        let texts = [
+            "",
+            "One line, no ending",
+            "The cat sat. The dog ran!\nThe cat sat again?\n",
+            // Sentences running across lines, around abbreviations and numbers
+            "Dr.\nSmith came in\nlate. I said no.\nShe left. See No. 5\nor No.\n6 today...\n\n",
+            "Windows\r\nline endings. Here\r\n",
+        ];
+        let english = TextAnalyzer::with_simple_format().with_stopwords(Stopwords::english());
+        for analyzer in [TextAnalyzer::with_simple_format(), english] {
+            for text in texts {
+                let streamed = analyzer.analyze_reader(text.as_bytes()).unwrap();
+                assert_eq!(streamed.lines, analyzer.analyze(text).lines, "{:?}", text);
+            }
+        }
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_analyze_reader_rejects_invalid_utf8() {
+        let result = TextAnalyzer::with_simple_format().analyze_reader(&b"ok\n\xff\n"[..]);
+        assert!(result.is_err_and(|e| e.kind() == io::ErrorKind::InvalidData));
+    }
+}
@@ -0,0 +1,281 @@
+// =============================================================================
+// ARENA.RS - Bump Allocation and String Interning for Large Texts
+// =============================================================================
+//
+// CONCEPTS DEMONSTRATED:
+// ----------------------
+// 1. ARENA (BUMP) ALLOCATION
+//    - One big block of memory handed out front-to-back
+//    - Allocating is a pointer bump; everything is freed at once on drop
+//    - Ideal for "build many small values, use them, throw them all away"
+//
+// 2. STRING INTERNING
+//    - Each distinct word is stored ONCE and given a small integer Symbol
+//    - Counting by Symbol needs no String per token and no string hashing
+//
+// 3. TWO LIFETIMES ON ONE VALUE
+//    - ArenaWords<'b, 'a>: the slices live in the arena ('b),
+//      the Word text still borrows from the source text ('a)
+//
+// 4. INTERIOR MUTABILITY (RefCell)
+//    - extract_words_in takes &Arena (shared), yet the interner must grow
+//
+// WHY?
+// ----
+// extract_words() grows a Vec<Word> by repeated reallocation, and
+// WordFrequency::from_words() allocates a lowercase String for EVERY token.
+// On multi-megabyte inputs that is millions of allocations. The arena path
+// allocates per DISTINCT word instead. See benches/arena.rs.
+//
+// Enabled with the `arena` feature (pulls in the `bumpalo` crate).
+// =============================================================================
+
+use core::cell::RefCell;
+
+use bumpalo::Bump;
+
+use crate::frequency::WordFrequency;
+use crate::prelude::*;
+use crate::word::Word;
+
+// =============================================================================
+// SYMBOL + INTERNER
+// =============================================================================
+
+/// Handle to an interned (lowercased) word.
+///
+/// Symbols are dense indices (0, 1, 2, ...) in interning order, so they can
+/// index straight into a Vec - no hashing needed once a word is interned.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Symbol(u32);
+
+impl Symbol {
+    /// The symbol's position in interning order.
+    pub fn index(self) -> usize {
+        self.0 as usize
+    }
+}
+
+/// Maps lowercased words to symbols and back.
+#[derive(Debug, Default)]
+pub struct Interner {
+    lookup: HashMap<String, Symbol>,
+    names: Vec<String>,
+    // Reused buffer for lowercasing - avoids one String per lookup.
+    scratch: String,
+}
+
+impl Interner {
+    pub fn new() -> Interner {
+        Interner::default()
+    }
+
+    /// Interns `text` case-insensitively, allocating only for new words.
+    pub fn intern(&mut self, text: &str) -> Symbol {
+        self.scratch.clear();
+        if text.is_ascii() {
+            // Fast path: in-place ASCII lowercasing, no per-char iteration
+            self.scratch.push_str(text);
+            self.scratch.make_ascii_lowercase();
+        } else {
+            self.scratch.extend(text.chars().flat_map(char::to_lowercase));
+        }
+
+        // Lookup with &str: no allocation when the word is already known
+        if let Some(&symbol) = self.lookup.get(self.scratch.as_str()) {
+            return symbol;
+        }
+        let symbol = Symbol(self.names.len() as u32);
+        self.names.push(self.scratch.clone());
+        self.lookup.insert(self.scratch.clone(), symbol);
+        symbol
+    }
+
+    /// Returns the lowercased text for a symbol.
+    pub fn resolve(&self, symbol: Symbol) -> Option<&str> {
+        self.names.get(symbol.index()).map(String::as_str)
+    }
+
+    /// Number of distinct words interned so far.
+    pub fn len(&self) -> usize {
+        self.names.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.names.is_empty()
+    }
+}
+
+// =============================================================================
+// ARENA
+// =============================================================================
+
+/// Bump allocator plus interner shared by every extraction into it.
+///
+/// Reusing one arena across several texts keeps a single symbol table, so
+/// frequencies from different documents are directly comparable.
+#[derive(Debug, Default)]
+pub struct Arena {
+    bump: Bump,
+    interner: RefCell<Interner>,
+}
+
+impl Arena {
+    pub fn new() -> Arena {
+        Arena::default()
+    }
+
+    /// Pre-allocates roughly `bytes` of arena space.
+    ///
+    /// A `Word` is a few machine words, so `text.len() * 4` is a generous
+    /// estimate for extracting all words of `text`.
+    pub fn with_capacity(bytes: usize) -> Arena {
+        Arena {
+            bump: Bump::with_capacity(bytes),
+            interner: RefCell::new(Interner::new()),
+        }
+    }
+
+    /// Interns a word into this arena's symbol table.
+    pub fn intern(&self, text: &str) -> Symbol {
+        self.interner.borrow_mut().intern(text)
+    }
+
+    /// Returns the lowercased word for a symbol.
+    pub fn resolve(&self, symbol: Symbol) -> Option<String> {
+        self.interner.borrow().resolve(symbol).map(String::from)
+    }
+
+    /// Number of distinct words seen by this arena.
+    pub fn symbol_count(&self) -> usize {
+        self.interner.borrow().len()
+    }
+
+    /// Bytes currently allocated by the bump allocator.
+    pub fn allocated_bytes(&self) -> usize {
+        self.bump.allocated_bytes()
+    }
+}
+
+// =============================================================================
+// EXTRACTION INTO THE ARENA
+// =============================================================================
+
+/// Words extracted into an [`Arena`], with a parallel slice of symbols.
+///
+/// `words[i]` and `symbols[i]` describe the same token.
+#[derive(Debug, Clone, Copy)]
+pub struct ArenaWords<'b, 'a> {
+    pub words: &'b [Word<'a>],
+    pub symbols: &'b [Symbol],
+}
+
+impl<'b, 'a> ArenaWords<'b, 'a> {
+    pub fn len(&self) -> usize {
+        self.words.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.words.is_empty()
+    }
+
+    /// Counts occurrences per symbol: a Vec indexed by `Symbol::index()`.
+    ///
+    /// Symbols interned by other texts in the same arena count as 0.
+    pub fn symbol_counts(&self, arena: &Arena) -> Vec<usize> {
+        let mut counts = vec![0; arena.symbol_count()];
+        for symbol in self.symbols {
+            counts[symbol.index()] += 1;
+        }
+        counts
+    }
+
+    /// Builds a [`WordFrequency`] from the interned symbols.
+    ///
+    /// Produces the same result as `WordFrequency::from_words(words)` but
+    /// allocates one String per DISTINCT word rather than per token.
+    pub fn frequency(&self, arena: &Arena) -> WordFrequency {
+        let interner = arena.interner.borrow();
+        let counts = self
+            .symbol_counts(arena)
+            .into_iter()
+            .enumerate()
+            .filter(|&(_, count)| count > 0)
+            .filter_map(|(index, count)| {
+                interner
+                    .resolve(Symbol(index as u32))
+                    .map(|word| (String::from(word), count))
+            });
+        WordFrequency::from_counts(counts)
+    }
+}
+
+/// Extracts words like [`extract_words`](crate::word::extract_words), but
+/// stores them (and their symbols) in `arena` instead of a growing Vec.
+///
+/// LIFETIMES:
+/// - 'b: the arena borrow - the returned slices live inside the arena
+/// - 'a: the source text - each Word still points into `text` (zero-copy)
+pub fn extract_words_in<'b, 'a>(arena: &'b Arena, text: &'a str) -> ArenaWords<'b, 'a> {
+    let mut words = bumpalo::collections::Vec::new_in(&arena.bump);
+    let mut symbols = bumpalo::collections::Vec::new_in(&arena.bump);
+    let mut interner = arena.interner.borrow_mut();
+
+    for (line_num, line) in text.lines().enumerate() {
+        for (position, word_text) in line.split_whitespace().enumerate() {
+            let cleaned = word_text.trim_matches(|c: char| !c.is_alphanumeric());
+            if !cleaned.is_empty() {
+                words.push(Word::new(cleaned, position, line_num + 1));
+                symbols.push(interner.intern(cleaned));
+            }
+        }
+    }
+
+    // into_bump_slice() gives up the ability to grow, in exchange for a
+    // plain slice that lives as long as the arena borrow.
+    ArenaWords {
+        words: words.into_bump_slice(),
+        symbols: symbols.into_bump_slice(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::word::extract_words;
+
+    /// Every (word, count) pair, sorted so two frequencies compare equal.
+    fn sorted(frequency: &WordFrequency) -> Vec<(String, usize)> {
+        let mut pairs: Vec<_> = frequency.iter().map(|(w, c)| (String::from(w), c)).collect();
+        pairs.sort();
+        pairs
+    }
+
+    #[test]
+    fn test_arena_frequency_matches_heap_frequency() {
+        let arena = Arena::new();
+        let texts = ["The cat and THE hat.\n  Cat -- café, Café!", "", "Only the cat\nagain"];
+        for text in texts {
+            let words = extract_words_in(&arena, text);
+            let heap = extract_words(text);
+            assert_eq!(words.len(), heap.len());
+            for (a, h) in words.words.iter().zip(&heap) {
+                assert_eq!((a.text, a.position, a.line), (h.text, h.position, h.line));
+            }
+            assert_eq!(sorted(&words.frequency(&arena)), sorted(&WordFrequency::from_words(&heap)));
+        }
+    }
+
+    #[test]
+    fn test_interning_is_case_insensitive_and_shared() {
+        let arena = Arena::new();
+        let first = extract_words_in(&arena, "Cat cat CAT dog");
+        let second = extract_words_in(&arena, "dog Bird");
+        assert_eq!(first.symbols[0], first.symbols[2]);
+        assert_eq!(first.symbols[3], second.symbols[0]);
+        assert_eq!(arena.symbol_count(), 3);
+        assert_eq!(arena.resolve(first.symbols[2]).as_deref(), Some("cat"));
+        // Symbols from the other text count as 0
+        assert_eq!(second.symbol_counts(&arena), vec![0, 1, 1]);
+    }
+}
@@ -0,0 +1,407 @@
+// =============================================================================
+// BATCH.RS - Analyzing Many Files into One Comparison Report
+// =============================================================================
+//
+// CONCEPTS DEMONSTRATED:
+// ----------------------
+// 1. SCOPED THREADS
+//    - std::thread::scope lets worker threads borrow `paths` and `options`
+//      directly; the scope joins every thread before it returns, so the
+//      borrows can't outlive the data
+//    - chunks() splits the work, and joining in spawn order keeps the
+//      results in input order
+//
+// 2. GENERIC PATH ARGUMENTS
+//    - `P: AsRef<Path> + Sync` accepts &str, String, PathBuf, ... and is
+//      safe to share between the worker threads
+//
+// 3. ITERATOR AGGREGATION
+//    - Corpus totals are sums over the per-file rows, plus per-file word
+//      counts merged with the Entry API
+//
+// 4. WRITING TEXT FORMATS BY HAND
+//    - fmt::Write lets write!() append to a String
+//    - CSV quoting and JSON escaping are small match expressions, so the
+//      crate needs no serialization dependency
+//
+// WHY?
+// ----
+// A documentation audit compares many files at once: which are long, which
+// are hard to read, what each one is mostly about. analyze_many() produces
+// one row per file plus corpus totals, as a table for spreadsheets (CSV) or
+// for other tools (JSON).
+//
+// Needs `std` for the filesystem and threads.
+// =============================================================================
+
+use std::fmt::Write;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::thread;
+
+use crate::frequency::WordFrequency;
+use crate::prelude::*;
+use crate::stats::{ReadingLevel, TextStats};
+use crate::word::extract_words;
+
+// =============================================================================
+// OPTIONS
+// =============================================================================
+
+/// How [`analyze_many`] runs and what it counts as a keyword.
+#[derive(Debug, Clone)]
+pub struct BatchOptions {
+    /// Worker threads; 1 analyzes the files one after another.
+    pub threads: usize,
+    /// Shorter words ("the", "and", ...) are never keywords.
+    pub min_keyword_len: usize,
+    /// How many corpus-wide keywords to report.
+    pub top_keywords: usize,
+}
+
+impl Default for BatchOptions {
+    /// One thread per CPU, keywords of 4+ characters, top 10 for the corpus.
+    fn default() -> BatchOptions {
+        BatchOptions {
+            threads: thread::available_parallelism().map_or(1, |n| n.get()),
+            min_keyword_len: 4,
+            top_keywords: 10,
+        }
+    }
+}
+
+// =============================================================================
+// REPORT TYPES
+// =============================================================================
+
+/// Key metrics for one file.
+#[derive(Debug, Clone)]
+pub struct FileMetrics {
+    pub path: PathBuf,
+    pub words: usize,
+    /// Characters in words (punctuation and whitespace not counted).
+    pub chars: usize,
+    pub unique_words: usize,
+    pub avg_word_length: f64,
+    pub reading_level: ReadingLevel,
+    pub sentences: usize,
+    /// Words per sentence.
+    pub avg_sentence_length: f64,
+    /// Most frequent word of at least `min_keyword_len` characters
+    /// (alphabetically first on ties), lowercased.
+    pub top_keyword: Option<String>,
+}
+
+/// Totals across every file that was analyzed.
+#[derive(Debug, Clone)]
+pub struct CorpusTotals {
+    pub files: usize,
+    pub words: usize,
+    /// Distinct words across all files (not the sum of per-file counts).
+    pub unique_words: usize,
+    /// Weighted by word count, so long files count for more.
+    pub avg_word_length: f64,
+    pub reading_level: ReadingLevel,
+    pub sentences: usize,
+    /// Weighted by sentence count, like `avg_word_length`.
+    pub avg_sentence_length: f64,
+    /// The most frequent keywords, highest count first.
+    pub top_keywords: Vec<(String, usize)>,
+}
+
+/// Result of [`analyze_many`]: one row per file plus corpus totals.
+#[derive(Debug)]
+pub struct BatchReport {
+    /// Analyzed files, in the order they were given.
+    pub files: Vec<FileMetrics>,
+    /// Files that couldn't be read (missing, not UTF-8, ...).
+    /// They are left out of `files` and `totals`.
+    pub failures: Vec<(PathBuf, io::Error)>,
+    pub totals: CorpusTotals,
+}
+
+// =============================================================================
+// ANALYSIS
+// =============================================================================
+
+/// Analyzes every file in `paths`, spreading the work over
+/// `options.threads` threads.
+///
+/// Unreadable files are reported in `failures` rather than stopping the
+/// batch; the result is the same whatever the thread count.
+pub fn analyze_many<P>(paths: &[P], options: &BatchOptions) -> BatchReport
+where
+    P: AsRef<Path> + Sync,
+{
+    let threads = options.threads.clamp(1, paths.len().max(1));
+    // Ceiling division, so at most `threads` chunks
+    let chunk_size = paths.len().div_ceil(threads).max(1);
+
+    let results: Vec<io::Result<(FileMetrics, WordFrequency)>> = if threads == 1 {
+        paths.iter().map(|p| analyze_file(p.as_ref(), options)).collect()
+    } else {
+        thread::scope(|scope| {
+            // Spawn every worker before joining any, or they'd run one by one
+            let handles: Vec<_> = paths
+                .chunks(chunk_size)
+                .map(|chunk| {
+                    scope.spawn(move || {
+                        chunk
+                            .iter()
+                            .map(|p| analyze_file(p.as_ref(), options))
+                            .collect::<Vec<_>>()
+                    })
+                })
+                .collect();
+            handles
+                .into_iter()
+                .flat_map(|h| h.join().expect("analysis thread panicked"))
+                .collect()
+        })
+    };
+
+    let mut files = Vec::new();
+    let mut failures = Vec::new();
+    let mut counts: HashMap<String, usize> = HashMap::new();
+    for (path, result) in paths.iter().zip(results) {
+        match result {
+            Ok((metrics, frequency)) => {
+                for (word, count) in frequency.iter() {
+                    *counts.entry(word.to_string()).or_insert(0) += count;
+                }
+                files.push(metrics);
+            }
+            Err(e) => failures.push((path.as_ref().to_path_buf(), e)),
+        }
+    }
+
+    let words: usize = files.iter().map(|f| f.words).sum();
+    let total_chars: usize = files.iter().map(|f| f.chars).sum();
+    let avg_word_length = if words == 0 { 0.0 } else { total_chars as f64 / words as f64 };
+    let sentences: usize = files.iter().map(|f| f.sentences).sum();
+    let sentence_words: f64 = files.iter().map(|f| f.avg_sentence_length * f.sentences as f64).sum();
+    let avg_sentence_length = if sentences == 0 { 0.0 } else { sentence_words / sentences as f64 };
+    let totals = CorpusTotals {
+        files: files.len(),
+        words,
+        unique_words: counts.len(),
+        avg_word_length,
+        reading_level: ReadingLevel::from_avg_length(avg_word_length),
+        sentences,
+        avg_sentence_length,
+        top_keywords: keywords(counts.iter().map(|(w, &c)| (w.as_str(), c)), options.min_keyword_len)
+            .into_iter()
+            .take(options.top_keywords)
+            .map(|(w, c)| (w.to_string(), c))
+            .collect(),
+    };
+
+    BatchReport { files, failures, totals }
+}
+
+/// Reads and analyzes one file, keeping its word counts for the totals.
+fn analyze_file(path: &Path, options: &BatchOptions) -> io::Result<(FileMetrics, WordFrequency)> {
+    let text = fs::read_to_string(path)?;
+    let words = extract_words(&text);
+    let stats = TextStats::from_words(&words).with_sentences(&text);
+    let frequency = WordFrequency::from_words(&words);
+    let top_keyword = keywords(frequency.iter(), options.min_keyword_len)
+        .first()
+        .map(|(w, _)| w.to_string());
+    let metrics = FileMetrics {
+        path: path.to_path_buf(),
+        words: stats.total_words,
+        chars: stats.total_chars,
+        unique_words: frequency.unique_count(),
+        avg_word_length: stats.avg_word_length,
+        reading_level: stats.reading_level,
+        sentences: stats.sentence_count,
+        avg_sentence_length: stats.avg_sentence_length,
+        top_keyword,
+    };
+    Ok((metrics, frequency))
+}
+
+/// Words of at least `min_len` characters, highest count first, then
+/// alphabetically.
+fn keywords<'a>(counts: impl Iterator<Item = (&'a str, usize)>, min_len: usize) -> Vec<(&'a str, usize)> {
+    let mut found: Vec<_> = counts.filter(|(w, _)| w.chars().count() >= min_len).collect();
+    found.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(b.0)));
+    found
+}
+
+// =============================================================================
+// OUTPUT FORMATS
+// =============================================================================
+
+impl BatchReport {
+    /// One header row, then one row per analyzed file. Totals and failures
+    /// are not included; a CSV table holds one kind of row.
+    pub fn to_csv(&self) -> String {
+        let mut out =
+            String::from("path,words,unique_words,avg_word_length,reading_level,sentences,avg_sentence_length,top_keyword\n");
+        for file in &self.files {
+            // write! to a String can't fail, so the Result is safe to ignore
+            let _ = writeln!(
+                out,
+                "{},{},{},{:.2},{:?},{},{:.2},{}",
+                csv_field(&file.path.display().to_string()),
+                file.words,
+                file.unique_words,
+                file.avg_word_length,
+                file.reading_level,
+                file.sentences,
+                file.avg_sentence_length,
+                csv_field(file.top_keyword.as_deref().unwrap_or("")),
+            );
+        }
+        out
+    }
+
+    /// The whole report (files, failures and totals) as a JSON object.
+    pub fn to_json(&self) -> String {
+        let mut out = String::from("{\n  \"files\": [");
+        for (i, file) in self.files.iter().enumerate() {
+            let keyword = file.top_keyword.as_deref().map_or(String::from("null"), json_string);
+            let _ = write!(
+                out,
+                "{}\n    {{\"path\": {}, \"words\": {}, \"unique_words\": {}, \"avg_word_length\": {:.2}, \"reading_level\": \"{:?}\", \"sentences\": {}, \"avg_sentence_length\": {:.2}, \"top_keyword\": {}}}",
+                if i == 0 { "" } else { "," },
+                json_string(&file.path.display().to_string()),
+                file.words,
+                file.unique_words,
+                file.avg_word_length,
+                file.reading_level,
+                file.sentences,
+                file.avg_sentence_length,
+                keyword,
+            );
+        }
+        out.push_str("\n  ],\n  \"failures\": [");
+        for (i, (path, error)) in self.failures.iter().enumerate() {
+            let _ = write!(
+                out,
+                "{}\n    {{\"path\": {}, \"error\": {}}}",
+                if i == 0 { "" } else { "," },
+                json_string(&path.display().to_string()),
+                json_string(&error.to_string()),
+            );
+        }
+        let totals = &self.totals;
+        let keywords: Vec<String> = totals
+            .top_keywords
+            .iter()
+            .map(|(w, c)| format!("[{}, {}]", json_string(w), c))
+            .collect();
+        let _ = write!(
+            out,
+            "\n  ],\n  \"totals\": {{\"files\": {}, \"words\": {}, \"unique_words\": {}, \"avg_word_length\": {:.2}, \"reading_level\": \"{:?}\", \"sentences\": {}, \"avg_sentence_length\": {:.2}, \"top_keywords\": [{}]}}\n}}\n",
+            totals.files,
+            totals.words,
+            totals.unique_words,
+            totals.avg_word_length,
+            totals.reading_level,
+            totals.sentences,
+            totals.avg_sentence_length,
+            keywords.join(", "),
+        );
+        out
+    }
+}
+
+/// Quotes a CSV field if it contains a comma, quote or line break,
+/// doubling any quotes inside.
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+/// A JSON string literal, with quotes, backslashes and control characters
+/// escaped.
+fn json_string(value: &str) -> String {
+    let mut out = String::with_capacity(value.len() + 2);
+    out.push('"');
+    for c in value.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            c if c.is_control() => {
+                let _ = write!(out, "\\u{:04x}", c as u32);
+            }
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A fresh directory under the system temp dir, unique to this test.
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("module-7-{}-{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    #[test]
+    fn test_report_formats() {
+        let dir = temp_dir("batch");
+        let a = dir.join("a.txt");
+        let b = dir.join("b, \"c\".txt");
+        let missing = dir.join("missing.txt");
+        fs::write(&a, "The cat sat. The cat ran away!").unwrap();
+        fs::write(&b, "Rust rust RUST.").unwrap();
+
+        let options = BatchOptions { threads: 2, ..BatchOptions::default() };
+        let report = analyze_many(&[&a, &missing, &b], &options);
+        assert_eq!(report.failures.len(), 1);
+        assert_eq!(report.failures[0].0, missing);
+
+        let quoted_b = format!("\"{}\"", b.display().to_string().replace('"', "\"\""));
+        let csv = format!(
+            "path,words,unique_words,avg_word_length,reading_level,sentences,avg_sentence_length,top_keyword\n\
+             {},7,5,3.14,Elementary,2,3.50,away\n\
+             {},3,1,4.00,Intermediate,1,3.00,rust\n",
+            a.display(),
+            quoted_b,
+        );
+        assert_eq!(report.to_csv(), csv);
+
+        let json = format!(
+            "{{\n  \"files\": [\n    \
+             {{\"path\": {}, \"words\": 7, \"unique_words\": 5, \"avg_word_length\": 3.14, \"reading_level\": \"Elementary\", \"sentences\": 2, \"avg_sentence_length\": 3.50, \"top_keyword\": \"away\"}},\n    \
+             {{\"path\": {}, \"words\": 3, \"unique_words\": 1, \"avg_word_length\": 4.00, \"reading_level\": \"Intermediate\", \"sentences\": 1, \"avg_sentence_length\": 3.00, \"top_keyword\": \"rust\"}}\n  ],\n  \
+             \"failures\": [\n    {{\"path\": {}, \"error\": {}}}\n  ],\n  \
+             \"totals\": {{\"files\": 2, \"words\": 10, \"unique_words\": 6, \"avg_word_length\": 3.40, \"reading_level\": \"Elementary\", \"sentences\": 3, \"avg_sentence_length\": 3.33, \"top_keywords\": [[\"rust\", 3], [\"away\", 1]]}}\n}}\n",
+            json_string(&a.display().to_string()),
+            json_string(&b.display().to_string()),
+            json_string(&missing.display().to_string()),
+            json_string(&report.failures[0].1.to_string()),
+        );
+        assert_eq!(report.to_json(), json);
+
+        // The thread count doesn't change the result
+        let serial = analyze_many(&[&a, &missing, &b], &BatchOptions { threads: 1, ..options });
+        assert_eq!(serial.to_json(), report.to_json());
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_escaping() {
+        assert_eq!(csv_field("plain"), "plain");
+        assert_eq!(csv_field("a,b"), "\"a,b\"");
+        assert_eq!(csv_field("say \"hi\""), "\"say \"\"hi\"\"\"");
+        assert_eq!(json_string("a\"b\\c\nd\u{1}"), "\"a\\\"b\\\\c\\nd\\u0001\"");
+    }
+}
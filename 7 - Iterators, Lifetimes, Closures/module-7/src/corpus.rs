@@ -0,0 +1,356 @@
+// =============================================================================
+// CORPUS.RS - Incremental Analysis of a Directory of Text Files
+// =============================================================================
+//
+// CONCEPTS DEMONSTRATED:
+// ----------------------
+// 1. CLOSURES STORED IN STRUCTS (FnMut)
+//    - watch() takes the callback by value and keeps it in the watcher
+//    - FnMut because callers usually count or log changes (mutable capture)
+//
+// 2. LIFETIMES IN ENUMS
+//    - CorpusEvent<'a> borrows the path and stats from the corpus, so
+//      reporting a change copies nothing
+//
+// 3. INCREMENTAL AGGREGATION
+//    - Each document keeps its own word counts
+//    - On change: subtract the old counts, add the new ones
+//    - Unchanged files are never re-read
+//
+// 4. std::fs + std::io
+//    - read_dir(), metadata(), read_to_string() and io::Result with ?
+//
+// WHY?
+// ----
+// Monitoring a documentation folder by re-running the analyzer over every
+// file on each check scales with the size of the folder. Here the cost of
+// poll() is one metadata() call per file plus the analysis of whatever
+// actually changed.
+//
+// Enabled with the `watch` feature (needs `std` for the filesystem).
+// =============================================================================
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+use crate::frequency::WordFrequency;
+use crate::prelude::*;
+use crate::stats::TextStats;
+use crate::word::extract_words;
+
+// =============================================================================
+// OPTIONS
+// =============================================================================
+
+/// Which files in the directory belong to the corpus.
+#[derive(Debug, Clone)]
+pub struct WatchOptions {
+    /// File extensions to analyze, without the dot; matched ignoring case.
+    pub extensions: Vec<String>,
+    /// Also scan subdirectories.
+    pub recursive: bool,
+}
+
+impl Default for WatchOptions {
+    /// Plain text and Markdown files in the top-level directory only.
+    fn default() -> WatchOptions {
+        WatchOptions {
+            extensions: vec![String::from("txt"), String::from("md")],
+            recursive: false,
+        }
+    }
+}
+
+impl WatchOptions {
+    fn matches(&self, path: &Path) -> bool {
+        // and_then() chains the two Option-returning steps:
+        // no extension, or one that isn't UTF-8, both give None
+        path.extension()
+            .and_then(|ext| ext.to_str())
+            .is_some_and(|ext| self.extensions.iter().any(|e| e.eq_ignore_ascii_case(ext)))
+    }
+}
+
+// =============================================================================
+// CORPUS
+// =============================================================================
+
+/// One analyzed file, plus what we need to tell whether it changed.
+struct Document {
+    modified: SystemTime,
+    len: u64,
+    stats: TextStats,
+    frequency: WordFrequency,
+}
+
+/// Per-file analysis plus running totals across all files.
+#[derive(Default)]
+pub struct Corpus {
+    // BTreeMap so documents() lists paths in a stable, sorted order
+    documents: BTreeMap<PathBuf, Document>,
+    counts: HashMap<String, usize>,
+    total_words: usize,
+}
+
+impl Corpus {
+    /// Number of files currently in the corpus.
+    pub fn document_count(&self) -> usize {
+        self.documents.len()
+    }
+
+    /// Words across all files.
+    pub fn total_words(&self) -> usize {
+        self.total_words
+    }
+
+    /// Combined word frequency across all files.
+    pub fn frequency(&self) -> WordFrequency {
+        WordFrequency::from_counts(self.counts.iter().map(|(w, &c)| (w.clone(), c)))
+    }
+
+    /// Stats for one file, if it's part of the corpus.
+    pub fn document_stats(&self, path: &Path) -> Option<&TextStats> {
+        self.documents.get(path).map(|doc| &doc.stats)
+    }
+
+    /// Paths of all files in the corpus, sorted.
+    pub fn documents(&self) -> impl Iterator<Item = &Path> {
+        self.documents.keys().map(PathBuf::as_path)
+    }
+
+    fn insert(&mut self, path: PathBuf, doc: Document) {
+        self.total_words += doc.stats.total_words;
+        for (word, count) in doc.frequency.iter() {
+            *self.counts.entry(word.to_string()).or_insert(0) += count;
+        }
+        self.documents.insert(path, doc);
+    }
+
+    fn remove(&mut self, path: &Path) -> Option<Document> {
+        let doc = self.documents.remove(path)?;
+        self.total_words -= doc.stats.total_words;
+        for (word, count) in doc.frequency.iter() {
+            // Drop words that no longer appear anywhere, so unique counts
+            // stay correct after edits and deletions
+            if let Some(total) = self.counts.get_mut(word) {
+                *total -= count;
+                if *total == 0 {
+                    self.counts.remove(word);
+                }
+            }
+        }
+        Some(doc)
+    }
+}
+
+// =============================================================================
+// CHANGE EVENTS
+// =============================================================================
+
+/// A change found by [`CorpusWatcher::poll`], passed to the callback.
+///
+/// Borrows from the corpus; the stats are those of the newly analyzed text.
+#[derive(Debug)]
+pub enum CorpusEvent<'a> {
+    Added { path: &'a Path, stats: &'a TextStats },
+    Modified { path: &'a Path, stats: &'a TextStats },
+    Removed { path: &'a Path },
+}
+
+// =============================================================================
+// WATCHER
+// =============================================================================
+
+/// Keeps a [`Corpus`] in sync with a directory; see [`watch`].
+pub struct CorpusWatcher<F> {
+    dir: PathBuf,
+    options: WatchOptions,
+    // The callback closure lives as long as the watcher
+    callback: F,
+    corpus: Corpus,
+}
+
+/// Starts watching `dir`. Nothing is read until the first [`poll`].
+///
+/// Fails if `dir` is not a readable directory.
+///
+/// [`poll`]: CorpusWatcher::poll
+pub fn watch<F>(dir: impl Into<PathBuf>, options: WatchOptions, callback: F) -> io::Result<CorpusWatcher<F>>
+where
+    F: FnMut(&CorpusEvent),
+{
+    let dir = dir.into();
+    if !fs::metadata(&dir)?.is_dir() {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!("{} is not a directory", dir.display()),
+        ));
+    }
+    Ok(CorpusWatcher {
+        dir,
+        options,
+        callback,
+        corpus: Corpus::default(),
+    })
+}
+
+impl<F> CorpusWatcher<F>
+where
+    F: FnMut(&CorpusEvent),
+{
+    /// The corpus as of the last poll.
+    pub fn corpus(&self) -> &Corpus {
+        &self.corpus
+    }
+
+    /// Rescans the directory, analyzing new and modified files and dropping
+    /// deleted ones. Calls the callback once per change and returns how many
+    /// changes there were.
+    ///
+    /// A file counts as modified when its size or modification time differs
+    /// from the last poll. Files that aren't valid UTF-8 are skipped.
+    pub fn poll(&mut self) -> io::Result<usize> {
+        let mut found = Vec::new();
+        scan(&self.dir, &self.options, &mut found)?;
+        found.sort();
+
+        let mut changes = 0;
+
+        // Deletions first: anything known that the scan didn't see
+        let gone: Vec<PathBuf> = self
+            .corpus
+            .documents()
+            .filter(|path| found.binary_search_by(|p| p.as_path().cmp(*path)).is_err())
+            .map(Path::to_path_buf)
+            .collect();
+        for path in gone {
+            self.corpus.remove(&path);
+            (self.callback)(&CorpusEvent::Removed { path: &path });
+            changes += 1;
+        }
+
+        for path in found {
+            let meta = fs::metadata(&path)?;
+            let modified = meta.modified()?;
+            let unchanged = self
+                .corpus
+                .documents
+                .get(&path)
+                .is_some_and(|doc| doc.modified == modified && doc.len == meta.len());
+            if unchanged {
+                continue;
+            }
+
+            let text = match fs::read_to_string(&path) {
+                Ok(text) => text,
+                // Not UTF-8: not a text file we can analyze (any more)
+                Err(e) if e.kind() == io::ErrorKind::InvalidData => {
+                    if self.corpus.remove(&path).is_some() {
+                        (self.callback)(&CorpusEvent::Removed { path: &path });
+                        changes += 1;
+                    }
+                    continue;
+                }
+                Err(e) => return Err(e),
+            };
+            let words = extract_words(&text);
+            let doc = Document {
+                modified,
+                len: meta.len(),
+                stats: TextStats::from_words(&words).with_sentences(&text),
+                frequency: WordFrequency::from_words(&words),
+            };
+
+            let existed = self.corpus.remove(&path).is_some();
+            self.corpus.insert(path.clone(), doc);
+            let stats = &self.corpus.documents[&path].stats;
+            let event = if existed {
+                CorpusEvent::Modified { path: &path, stats }
+            } else {
+                CorpusEvent::Added { path: &path, stats }
+            };
+            (self.callback)(&event);
+            changes += 1;
+        }
+
+        Ok(changes)
+    }
+}
+
+/// Collects matching files under `dir` into `found`.
+fn scan(dir: &Path, options: &WatchOptions, found: &mut Vec<PathBuf>) -> io::Result<()> {
+    for entry in fs::read_dir(dir)? {
+        let entry = entry?;
+        let path = entry.path();
+        let file_type = entry.file_type()?;
+        if file_type.is_dir() {
+            if options.recursive {
+                scan(&path, options, found)?;
+            }
+        } else if file_type.is_file() && options.matches(&path) {
+            found.push(path);
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cell::RefCell;
+
+    use super::*;
+
+    #[test]
+    fn test_poll_tracks_changes() {
+        let dir = std::env::temp_dir().join(format!("module-7-corpus-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(dir.join("sub")).unwrap();
+        let a = dir.join("a.txt");
+        fs::write(&a, "The cat sat.").unwrap();
+        fs::write(dir.join("b.MD"), "The dog ran").unwrap();
+        fs::write(dir.join("skip.rs"), "fn main() {}").unwrap();
+        fs::write(dir.join("sub").join("c.txt"), "Not scanned").unwrap();
+
+        let events = RefCell::new(Vec::new());
+        let mut watcher = watch(&dir, WatchOptions::default(), |event: &CorpusEvent| {
+            let (kind, path) = match event {
+                CorpusEvent::Added { path, .. } => ("added", path),
+                CorpusEvent::Modified { path, .. } => ("modified", path),
+                CorpusEvent::Removed { path } => ("removed", path),
+            };
+            let name = path.file_name().unwrap().to_string_lossy().into_owned();
+            events.borrow_mut().push(format!("{} {}", kind, name));
+        })
+        .unwrap();
+
+        assert_eq!(watcher.poll().unwrap(), 2);
+        assert_eq!(watcher.corpus().total_words(), 6);
+        assert_eq!(watcher.corpus().frequency().get("the"), Some(2));
+        // Nothing changed since
+        assert_eq!(watcher.poll().unwrap(), 0);
+
+        fs::write(&a, "A bird sang. It left.").unwrap();
+        fs::remove_file(dir.join("b.MD")).unwrap();
+        assert_eq!(watcher.poll().unwrap(), 2);
+        let corpus = watcher.corpus();
+        assert_eq!(corpus.document_count(), 1);
+        assert_eq!(corpus.total_words(), 5);
+        assert_eq!(corpus.frequency().get("the"), None);
+        assert_eq!(corpus.document_stats(&a).map(|s| s.sentence_count), Some(2));
+
+        assert_eq!(*events.borrow(), vec!["added a.txt", "added b.MD", "removed b.MD", "modified a.txt"]);
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_watch_needs_a_directory() {
+        let file = std::env::temp_dir().join(format!("module-7-not-a-dir-{}.txt", std::process::id()));
+        fs::write(&file, "text").unwrap();
+        let result = watch(&file, WatchOptions::default(), |_: &CorpusEvent| {});
+        assert!(result.is_err_and(|e| e.kind() == io::ErrorKind::InvalidInput));
+        fs::remove_file(&file).unwrap();
+    }
+}
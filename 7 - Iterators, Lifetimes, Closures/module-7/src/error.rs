@@ -23,7 +23,9 @@
 //
 // =============================================================================
 
-use std::fmt;
+use core::fmt;
+
+use crate::prelude::*;
 
 // -----------------------------------------------------------------------------
 // CUSTOM ERROR ENUM
@@ -126,12 +128,15 @@ impl fmt::Display for AnalysisError {
 // - Works with Box<dyn Error> for heterogeneous error handling
 // - Compatibility with error handling crates (anyhow, thiserror)
 //
+// The trait lives in `core`, so this works in no_std builds too
+// (std::error::Error is a re-export of core::error::Error).
+//
 // Empty implementation {} uses default trait methods.
 // The Error trait has optional methods like source() for error chains,
 // but we don't need them for this simple example.
 // -----------------------------------------------------------------------------
 
-impl std::error::Error for AnalysisError {}
+impl core::error::Error for AnalysisError {}
 
 // -----------------------------------------------------------------------------
 // TYPE ALIAS
@@ -23,11 +23,16 @@
 // 5. CLOSURES
 //    - Comparison closures for sorting
 //
+// 6. SLICE WINDOWS (Module 7 - Iterators)
+//    - windows(n) yields every run of n neighbouring words, for n-grams
+//
 // =============================================================================
 
-use std::collections::HashMap;
-
+// HashMap comes from the crate prelude: std's HashMap in the default build,
+// hashbrown's in no_std builds (see lib.rs).
+use crate::prelude::*;
 use crate::stats::Summarizable;
+use crate::stopwords::Stopwords;
 use crate::word::Word;
 
 // =============================================================================
@@ -110,6 +115,39 @@ impl WordFrequency {
         WordFrequency { counts }
     }
 
+    // -------------------------------------------------------------------------
+    // FILTERING BEFORE COUNTING
+    // -------------------------------------------------------------------------
+    //
+    // filter() drops stopwords before they reach the HashMap, so "the" and
+    // "is" never take a slot in top_n(). The counting is the same Entry API
+    // pattern as from_words().
+    // -------------------------------------------------------------------------
+
+    /// Like `from_words()`, but leaves out every word in `stopwords`.
+    pub fn from_words_excluding(words: &[Word], stopwords: &Stopwords) -> WordFrequency {
+        let mut counts = HashMap::new();
+        for word in words.iter().filter(|w| !stopwords.contains(w.text)) {
+            *counts.entry(word.text.to_lowercase()).or_insert(0) += 1;
+        }
+        WordFrequency { counts }
+    }
+
+    // -------------------------------------------------------------------------
+    // BUILDING FROM PRECOMPUTED COUNTS
+    // -------------------------------------------------------------------------
+    //
+    // collect() can build a HashMap directly from an iterator of (key, value)
+    // tuples. Used by the arena module, which counts by interned symbol first
+    // and only creates one String per distinct word.
+    // -------------------------------------------------------------------------
+
+    pub fn from_counts(counts: impl IntoIterator<Item = (String, usize)>) -> WordFrequency {
+        WordFrequency {
+            counts: counts.into_iter().collect(),
+        }
+    }
+
     // -------------------------------------------------------------------------
     // HASHMAP LOOKUP
     // -------------------------------------------------------------------------
@@ -172,7 +210,7 @@ impl WordFrequency {
         // If counts are equal, use alphabetical order.
         // Otherwise, use the count comparison result.
         entries.sort_by(|a, b| match b.1.cmp(a.1) {
-            std::cmp::Ordering::Equal => a.0.cmp(b.0),
+            core::cmp::Ordering::Equal => a.0.cmp(b.0),
             other => other,
         });
 
@@ -279,12 +317,18 @@ impl Summarizable for WordFrequency {
 // =============================================================================
 
 pub fn frequency_distribution(freq: &WordFrequency) -> HashMap<usize, usize> {
+    distribution_of(freq.iter())
+}
+
+/// Builds a distribution from (item, count) pairs; shared by both frequency
+/// types so they count the same way.
+fn distribution_of<'a>(counts: impl Iterator<Item = (&'a str, usize)>) -> HashMap<usize, usize> {
     let mut distribution = HashMap::new();
 
     // DESTRUCTURING IN FOR LOOP:
     // for (_, count) iterates over (word, count) pairs
     // _ discards the word since we don't need it
-    for (_, count) in freq.iter() {
+    for (_, count) in counts {
         // Same entry() pattern:
         // Get entry for this count, insert 0 if new, then increment
         *distribution.entry(count).or_insert(0) += 1;
@@ -292,3 +336,136 @@ pub fn frequency_distribution(freq: &WordFrequency) -> HashMap<usize, usize> {
 
     distribution
 }
+
+// =============================================================================
+// N-GRAM FREQUENCY
+// =============================================================================
+//
+// Single words miss phrases: "memory" and "safety" may each be common, but
+// how often do they appear together? An n-gram is a run of n neighbouring
+// words ("memory safety" is a bigram, "without garbage collection" a
+// trigram), counted with the same HashMap + Entry API as single words.
+//
+// SLICE WINDOWS:
+//   [a, b, c, d].windows(2)  yields  [a, b], [b, c], [c, d]
+//
+// Each window is a &[Word], borrowed from `words`; only the joined,
+// lowercased key is a new String.
+// =============================================================================
+
+/// Phrase frequency: how often each run of `n` words occurs.
+pub struct NgramFrequency {
+    n: usize,
+    // Keys are the lowercased words joined by single spaces: "memory safety"
+    counts: HashMap<String, usize>,
+}
+
+impl NgramFrequency {
+    /// Counts every run of `n` neighbouring words, across line breaks.
+    /// With `n` of 0, or fewer than `n` words, nothing is counted.
+    pub fn from_words(words: &[Word], n: usize) -> NgramFrequency {
+        let mut counts = HashMap::new();
+
+        // windows() panics on 0, so only ask for windows of at least one word
+        if n > 0 {
+            for window in words.windows(n) {
+                let phrase: Vec<String> = window.iter().map(|w| w.text.to_lowercase()).collect();
+                *counts.entry(phrase.join(" ")).or_insert(0) += 1;
+            }
+        }
+
+        NgramFrequency { n, counts }
+    }
+
+    /// Words per phrase.
+    pub fn n(&self) -> usize {
+        self.n
+    }
+
+    /// Occurrences of a phrase, ignoring case and how the words are spaced.
+    pub fn get(&self, phrase: &str) -> Option<usize> {
+        let key: Vec<String> = phrase.split_whitespace().map(|w| w.to_lowercase()).collect();
+        self.counts.get(&key.join(" ")).copied()
+    }
+
+    /// Number of distinct phrases.
+    pub fn unique_count(&self) -> usize {
+        self.counts.len()
+    }
+
+    /// The `n` most frequent phrases, highest count first, then
+    /// alphabetically (the same order as `WordFrequency::top_n()`).
+    pub fn top_n(&self, n: usize) -> Vec<(&str, usize)> {
+        let mut entries: Vec<_> = self.counts.iter().collect();
+        // then(): compare by count, and alphabetically only on a tie
+        entries.sort_by(|a, b| b.1.cmp(a.1).then(a.0.cmp(b.0)));
+        entries
+            .into_iter()
+            .take(n)
+            .map(|(phrase, &count)| (phrase.as_str(), count))
+            .collect()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (&str, usize)> {
+        self.counts.iter().map(|(p, &c)| (p.as_str(), c))
+    }
+
+    pub fn total_occurrences(&self) -> usize {
+        self.counts.values().sum()
+    }
+
+    /// How many phrases occur once, twice, ... (see `frequency_distribution()`).
+    pub fn distribution(&self) -> HashMap<usize, usize> {
+        distribution_of(self.iter())
+    }
+}
+
+impl Summarizable for NgramFrequency {
+    fn summarize(&self) -> String {
+        format!(
+            "{}-grams: {} unique phrases, {} total occurrences",
+            self.n,
+            self.unique_count(),
+            self.total_occurrences()
+        )
+    }
+
+    fn item_count(&self) -> usize {
+        self.unique_count()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::word::extract_words;
+
+    #[test]
+    fn test_word_frequency_and_stopwords() {
+        let words = extract_words("The cat and the hat. THE END");
+        let all = WordFrequency::from_words(&words);
+        assert_eq!(all.get("the"), Some(3));
+        assert_eq!(all.get("The"), Some(3));
+        assert_eq!(all.get("dog"), None);
+        assert_eq!(all.top_n(2), vec![("the", 3), ("and", 1)]);
+        assert_eq!(all.total_occurrences(), 7);
+
+        let filtered = WordFrequency::from_words_excluding(&words, &Stopwords::english().with_words(&["Hat"]));
+        assert_eq!(filtered.top_n(10), vec![("cat", 1), ("end", 1)]);
+        assert_eq!(frequency_distribution(&all), HashMap::from([(1, 4), (3, 1)]));
+    }
+
+    #[test]
+    fn test_ngrams() {
+        let words = extract_words("Memory safety matters.\nMemory SAFETY wins");
+        let bigrams = NgramFrequency::from_words(&words, 2);
+        assert_eq!(bigrams.get("memory   safety"), Some(2));
+        // Runs across the line break
+        assert_eq!(bigrams.get("matters memory"), Some(1));
+        assert_eq!(bigrams.top_n(1), vec![("memory safety", 2)]);
+        assert_eq!(bigrams.total_occurrences(), 5);
+
+        assert_eq!(NgramFrequency::from_words(&words, 0).unique_count(), 0);
+        assert_eq!(NgramFrequency::from_words(&words, 7).unique_count(), 0);
+    }
+}
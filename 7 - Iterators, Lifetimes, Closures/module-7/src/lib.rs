@@ -0,0 +1,75 @@
+// =============================================================================
+// LIB.RS - Text Analytics Core (std or no_std + alloc)
+// =============================================================================
+//
+// CONCEPTS DEMONSTRATED:
+// ----------------------
+// 1. LIBRARY + BINARY CRATES
+//    - lib.rs holds the reusable analysis types
+//    - main.rs is a demo that uses them like any other consumer would
+//
+// 2. no_std + alloc
+//    - `#![no_std]` drops the standard library (no OS, no files, no println!)
+//    - `extern crate alloc` keeps heap types: String, Vec, format!
+//    - Lets tokenization, frequency counting and stats run on embedded/WASM
+//
+// 3. CARGO FEATURES
+//    - `std` (default): normal build, std::collections::HashMap
+//    - `alloc`: no_std build, HashMap comes from the `hashbrown` crate
+//    - `arena`: bump-allocated extraction + string interning (bumpalo)
+//    - `watch`: incremental analysis of a directory of files (needs std)
+//
+//    cargo build                                         # std
+//    cargo build --lib --no-default-features --features alloc   # no_std
+//
+// =============================================================================
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+#[cfg(not(any(feature = "std", feature = "alloc")))]
+compile_error!("module-7 needs either the `std` (default) or the `alloc` feature");
+
+// The alloc crate is always linked; with std it is the same allocator that
+// std re-exports, so the types below are identical in both builds.
+extern crate alloc;
+
+pub mod analyzer;
+#[cfg(feature = "arena")]
+pub mod arena;
+#[cfg(feature = "std")]
+pub mod batch;
+#[cfg(feature = "watch")]
+pub mod corpus;
+pub mod error;
+pub mod frequency;
+pub mod readability;
+pub mod screening;
+pub mod sentence;
+pub mod stats;
+pub mod stopwords;
+pub mod word;
+
+// =============================================================================
+// CRATE PRELUDE
+// =============================================================================
+//
+// The std prelude imports String, Vec, format! etc. automatically, but a
+// no_std crate only gets the much smaller core prelude. Every module does
+// `use crate::prelude::*;` so the same source compiles both ways.
+//
+// HashMap and HashSet are the types that differ: std's versions need OS
+// randomness for their hasher, so the no_std build swaps in hashbrown (which
+// is what std's collections are built on anyway).
+// =============================================================================
+
+mod prelude {
+    pub use alloc::format;
+    pub use alloc::string::{String, ToString};
+    pub use alloc::vec;
+    pub use alloc::vec::Vec;
+
+    #[cfg(not(feature = "std"))]
+    pub use hashbrown::{HashMap, HashSet};
+    #[cfg(feature = "std")]
+    pub use std::collections::{HashMap, HashSet};
+}
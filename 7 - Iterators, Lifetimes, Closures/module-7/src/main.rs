@@ -1,19 +1,23 @@
-mod analyzer;
-mod error;
-mod frequency;
-mod stats;
-mod word;
+use std::fs::File;
+use std::io::BufReader;
 
-use analyzer::{
+// The analysis types live in the library crate (lib.rs) so they can also be
+// built without std; this binary is just one consumer of that library.
+use module_7::analyzer::{
     bracketed_format, format_with_all, handle_analysis_result, simple_format, verbose_format,
     TextAnalyzer,
 };
-use frequency::{frequency_distribution, WordFrequency};
-use stats::{
+use module_7::batch::{analyze_many, BatchOptions};
+use module_7::frequency::{frequency_distribution, NgramFrequency, WordFrequency};
+use module_7::readability::{count_syllables, Readability};
+use module_7::screening::{DenyList, FlagKind, Screener, Severity};
+use module_7::sentence::split_sentences;
+use module_7::stopwords::Stopwords;
+use module_7::stats::{
     any_matches, count_where, filter_words, find_max, fold_words, partition_words, Summarizable,
     TextStats,
 };
-use word::{extract_words, find_longest, find_word_by_text, try_extract_words, try_find_word};
+use module_7::word::{self, extract_words, find_longest, find_word_by_text, try_extract_words, try_find_word};
 
 fn main() {
     let sample_text = "Rust is a systems programming language.
@@ -65,6 +69,37 @@ Many developers find Rust both challenging and rewarding.";
     print_summary(&freq);
     println!();
 
+    // =========================================================================
+    // SENTENCES: slices of the text, split on . ! ? but not after "Dr."
+    // =========================================================================
+    println!("--- Sentences ---");
+    let stats = stats.with_sentences(sample_text);
+    println!(
+        "{} sentences, {:.1} words on average, longest {} words",
+        stats.sentence_count, stats.avg_sentence_length, stats.longest_sentence_len
+    );
+    for sentence in split_sentences("Dr. Hoare called it a mistake. Was it? Yes... e.g. null.") {
+        println!("  [{}]", sentence);
+    }
+    println!();
+
+    // =========================================================================
+    // READABILITY: Flesch scores from words, sentences and syllables
+    // =========================================================================
+    println!("--- Readability ---");
+    for w in ["make", "table", "programming", "rewarding"] {
+        println!("  '{}': {} syllable(s)", w, count_syllables(w));
+    }
+    if let Some(scores) = Readability::from_stats(&stats) {
+        println!(
+            "Reading ease {:.1} ({}), grade level {:.1}",
+            scores.reading_ease,
+            scores.difficulty(),
+            scores.grade_level
+        );
+    }
+    println!();
+
     // =========================================================================
     // GENERICS: Type-agnostic functions with trait bounds (Module 6)
     // =========================================================================
@@ -134,6 +169,23 @@ Many developers find Rust both challenging and rewarding.";
     // Frequency distribution using Entry API
     let dist = frequency_distribution(&freq);
     println!("Distribution: {:?}", dist);
+
+    // Stopwords: the built-in English list plus a project-specific word
+    let stopwords = Stopwords::english().with_words(&["rust"]);
+    let content = WordFrequency::from_words_excluding(&words, &stopwords);
+    println!("Top 3 without {} stopwords: {:?}", stopwords.len(), content.top_n(3));
+
+    // N-grams: the same counting over runs of neighbouring words
+    let bigrams = NgramFrequency::from_words(&words, 2);
+    println!("{}", bigrams.summarize());
+    println!("'memory safety': {:?}", bigrams.get("Memory  safety"));
+    println!("Bigram distribution: {:?}", bigrams.distribution());
+
+    // Toggled per analyzer: this one's report lists content words only
+    let filtered = TextAnalyzer::with_simple_format().with_stopwords(Stopwords::english());
+    if let Some(line) = filtered.analyze(sample_text).lines.iter().find(|l| l.starts_with("Top words")) {
+        println!("{}", line);
+    }
     println!();
 
     // =========================================================================
@@ -179,6 +231,8 @@ Many developers find Rust both challenging and rewarding.";
     println!("FnMut (mutable borrow): first 5 word lengths sum = {}", running_total);
 
     // FnOnce (move): takes ownership of captured variable
+    // A Vec (not a Copy array) so that `move` really transfers ownership
+    #[allow(clippy::useless_vec)]
     let keywords = vec!["rust", "memory", "safe", "ownership"];
     let is_keyword = move |word: &word::Word| -> bool {
         keywords.iter().any(|k| word.text.eq_ignore_ascii_case(k))
@@ -249,4 +303,60 @@ Many developers find Rust both challenging and rewarding.";
         }
     }
     println!("After cap bonus:     {:?}", &scores[..5.min(scores.len())]);
+    println!();
+
+    // =========================================================================
+    // SCREENING: flags borrow from the draft AND the screener
+    // =========================================================================
+    println!("--- Screening ---");
+    let draft = "Ping jane.doe@example.com or call +1 (555) 010-4477 about Project Bluebird.";
+    let screener = Screener::new()
+        .with_list(DenyList::new("codenames", Severity::High, &["bluebird"]));
+
+    for flag in screener.screen(draft) {
+        let kind = match flag.kind {
+            FlagKind::Denied(list) => list,
+            FlagKind::Email => "email",
+            FlagKind::Phone => "phone",
+        };
+        println!("{} [{}..{}] {} ({})", kind, flag.start, flag.end, flag.text, flag.severity);
+    }
+    println!("Worst finding: {:?}", screener.max_severity(draft));
+    println!("Cleaned: {}", screener.clean(draft));
+    println!();
+
+    // =========================================================================
+    // BATCH ANALYSIS: many files, analyzed on scoped threads
+    // =========================================================================
+    println!("--- Batch Analysis ---");
+    let dir = env!("CARGO_MANIFEST_DIR");
+    let paths = [format!("{dir}/README.md"), format!("{dir}/src/lib.rs"), format!("{dir}/missing.txt")];
+    let report = analyze_many(&paths, &BatchOptions::default());
+    print!("{}", report.to_csv());
+    for (path, error) in &report.failures {
+        println!("Skipped {}: {}", path.display(), error);
+    }
+    println!(
+        "Corpus: {} files, {} words, {:?} reading level",
+        report.totals.files, report.totals.words, report.totals.reading_level
+    );
+    println!();
+
+    // =========================================================================
+    // STREAMING: any BufRead source, one line at a time
+    // =========================================================================
+    println!("--- Streaming Analysis ---");
+    // &[u8] implements BufRead, so the sample text can stand in for a file
+    match analyzer.analyze_reader(sample_text.as_bytes()) {
+        Ok(streamed) => println!(
+            "Streamed report matches analyze(): {}",
+            streamed.lines == analyzer.analyze(sample_text).lines
+        ),
+        Err(e) => println!("Error: {}", e),
+    }
+    let readme = File::open(format!("{dir}/README.md")).map(BufReader::new);
+    match readme.and_then(|reader| analyzer.analyze_reader(reader)) {
+        Ok(streamed) => println!("README.md, read line by line:\n{}", streamed),
+        Err(e) => println!("Error: {}", e),
+    }
 }
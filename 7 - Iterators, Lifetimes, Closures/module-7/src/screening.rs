@@ -0,0 +1,414 @@
+// =============================================================================
+// SCREENING.RS - Deny-List and PII Screening Before Publication
+// =============================================================================
+//
+// CONCEPTS DEMONSTRATED:
+// ----------------------
+// 1. MULTIPLE LIFETIME PARAMETERS IN A STRUCT
+//    - Flag<'t, 's> borrows the matched text from the input ('t) and the
+//      deny-list name from the screener ('s)
+//    - The two borrows are independent: a screener outlives many texts
+//
+// 2. BYTE SPANS INTO A &str
+//    - Each flag records start..end byte offsets, so callers can highlight
+//      the original text without copying it
+//    - char_indices() gives offsets that always fall on char boundaries
+//
+// 3. CLOSURES AS PREDICATES
+//    - trim_start_matches / trim_end_matches take a closure deciding which
+//      edge characters to strip
+//
+// 4. ORDERED ENUMS
+//    - Severity derives PartialOrd/Ord, so variants compare in declaration
+//      order (Low < Medium < High) and max() finds the worst flag
+//
+// WHY?
+// ----
+// Running the analyzer as a pre-publication check means catching words that
+// shouldn't go out (profanity, internal code names) and personal data
+// (emails, phone numbers). screen() reports what was found and where;
+// clean() returns a copy with each finding masked.
+//
+// Patterns are matched by hand rather than with a regex crate, so this
+// module also builds without std.
+// =============================================================================
+
+use core::fmt;
+
+use crate::prelude::*;
+
+// =============================================================================
+// SEVERITY AND FLAGS
+// =============================================================================
+
+/// How serious a finding is. Variants are ordered, Low < Medium < High.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Severity {
+    Low,
+    Medium,
+    High,
+}
+
+impl fmt::Display for Severity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let label = match self {
+            Severity::Low => "low",
+            Severity::Medium => "medium",
+            Severity::High => "high",
+        };
+        write!(f, "{}", label)
+    }
+}
+
+/// What kind of match a flag is.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FlagKind<'s> {
+    /// A word on the named deny-list.
+    Denied(&'s str),
+    /// Something shaped like an email address.
+    Email,
+    /// A run of at least MIN_PHONE_DIGITS digits, with the usual separators.
+    Phone,
+}
+
+/// One finding: the matched text, where it is, and why it was flagged.
+///
+/// `start..end` is a byte range into the screened text, so
+/// `&text[flag.start..flag.end] == flag.text`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Flag<'t, 's> {
+    pub text: &'t str,
+    pub start: usize,
+    pub end: usize,
+    pub kind: FlagKind<'s>,
+    pub severity: Severity,
+}
+
+// =============================================================================
+// DENY-LISTS
+// =============================================================================
+
+/// A named list of words to flag, matched as whole words ignoring case.
+#[derive(Debug, Clone)]
+pub struct DenyList {
+    pub name: String,
+    pub severity: Severity,
+    // Stored lowercase, so lookups only lowercase the candidate
+    words: Vec<String>,
+}
+
+impl DenyList {
+    pub fn new(name: &str, severity: Severity, words: &[&str]) -> DenyList {
+        DenyList {
+            name: name.to_string(),
+            severity,
+            words: words.iter().map(|w| w.to_lowercase()).collect(),
+        }
+    }
+
+    pub fn contains(&self, word: &str) -> bool {
+        let word = word.to_lowercase();
+        self.words.contains(&word)
+    }
+}
+
+// =============================================================================
+// SCREENER
+// =============================================================================
+
+/// Phone numbers need at least this many digits, which skips years, short
+/// counts and most dates (but not long IDs or account numbers).
+pub const MIN_PHONE_DIGITS: usize = 10;
+/// E.164 allows at most 15 digits including the country code.
+pub const MAX_PHONE_DIGITS: usize = 15;
+
+/// Configurable checker; see the module header.
+///
+/// The default checks emails and phone numbers (Medium) with no deny-lists.
+/// Set `emails` or `phones` to None to turn a check off.
+#[derive(Debug, Clone)]
+pub struct Screener {
+    lists: Vec<DenyList>,
+    pub emails: Option<Severity>,
+    pub phones: Option<Severity>,
+}
+
+impl Default for Screener {
+    fn default() -> Screener {
+        Screener {
+            lists: Vec::new(),
+            emails: Some(Severity::Medium),
+            phones: Some(Severity::Medium),
+        }
+    }
+}
+
+impl Screener {
+    pub fn new() -> Screener {
+        Screener::default()
+    }
+
+    /// Adds a deny-list; takes and returns self so calls can be chained.
+    pub fn with_list(mut self, list: DenyList) -> Screener {
+        self.lists.push(list);
+        self
+    }
+
+    // -------------------------------------------------------------------------
+    // SCREEN: the returned flags borrow from BOTH self and text
+    // -------------------------------------------------------------------------
+    //
+    // Lifetime elision would tie the output to &self only, which would be
+    // wrong: Flag::text points into `text`. Naming both lifetimes says
+    // exactly which borrow each field comes from.
+    // -------------------------------------------------------------------------
+
+    /// Finds every deny-listed word, email and phone number in `text`,
+    /// in order of position. Flags never overlap.
+    pub fn screen<'t, 's>(&'s self, text: &'t str) -> Vec<Flag<'t, 's>> {
+        let mut flags = Vec::new();
+
+        for (offset, token) in tokens(text) {
+            // Strip punctuation from both ends, keeping track of the offset
+            let edge = |c: char| !c.is_alphanumeric();
+            let trimmed = token.trim_start_matches(edge);
+            let start = offset + (token.len() - trimmed.len());
+            let trimmed = trimmed.trim_end_matches(edge);
+            if trimmed.is_empty() {
+                continue;
+            }
+            let end = start + trimmed.len();
+
+            let found = if is_email(trimmed) {
+                self.emails.map(|severity| (FlagKind::Email, severity))
+            } else {
+                // First matching list wins
+                self.lists
+                    .iter()
+                    .find(|list| list.contains(trimmed))
+                    .map(|list| (FlagKind::Denied(list.name.as_str()), list.severity))
+            };
+            if let Some((kind, severity)) = found {
+                flags.push(Flag { text: trimmed, start, end, kind, severity });
+            }
+        }
+
+        if let Some(severity) = self.phones {
+            for (start, end) in phone_spans(text) {
+                // Digits inside an email were already reported with it
+                let overlaps = flags.iter().any(|f| start < f.end && f.start < end);
+                if !overlaps {
+                    flags.push(Flag { text: &text[start..end], start, end, kind: FlagKind::Phone, severity });
+                }
+            }
+        }
+
+        flags.sort_by_key(|f| f.start);
+        flags
+    }
+
+    /// The worst severity found in `text`, or None if nothing was flagged.
+    pub fn max_severity(&self, text: &str) -> Option<Severity> {
+        self.screen(text).iter().map(|f| f.severity).max()
+    }
+
+    /// A copy of `text` with every flagged span replaced by one `*` per
+    /// character; everything else is unchanged.
+    pub fn clean(&self, text: &str) -> String {
+        let mut cleaned = String::with_capacity(text.len());
+        let mut last = 0;
+        for flag in self.screen(text) {
+            cleaned.push_str(&text[last..flag.start]);
+            cleaned.extend(flag.text.chars().map(|_| '*'));
+            last = flag.end;
+        }
+        cleaned.push_str(&text[last..]);
+        cleaned
+    }
+}
+
+// =============================================================================
+// MATCHING HELPERS
+// =============================================================================
+
+/// Whitespace-separated tokens with their byte offsets.
+fn tokens(text: &str) -> Vec<(usize, &str)> {
+    let mut found = Vec::new();
+    let mut start = None;
+    for (i, c) in text.char_indices() {
+        if c.is_whitespace() {
+            // take() reads the Option and resets it to None in one step
+            if let Some(s) = start.take() {
+                found.push((s, &text[s..i]));
+            }
+        } else if start.is_none() {
+            start = Some(i);
+        }
+    }
+    if let Some(s) = start {
+        found.push((s, &text[s..]));
+    }
+    found
+}
+
+/// `local@domain.tld`: one `@`, a dotted domain and an alphabetic TLD.
+fn is_email(candidate: &str) -> bool {
+    let Some((local, domain)) = candidate.split_once('@') else {
+        return false;
+    };
+    let local_ok = !local.is_empty()
+        && local.chars().all(|c| c.is_ascii_alphanumeric() || "._%+-".contains(c));
+    let labels: Vec<&str> = domain.split('.').collect();
+    let domain_ok = labels.len() >= 2
+        && labels
+            .iter()
+            .all(|l| !l.is_empty() && l.chars().all(|c| c.is_ascii_alphanumeric() || c == '-'));
+    let tld_ok = labels
+        .last()
+        .is_some_and(|tld| tld.len() >= 2 && tld.chars().all(|c| c.is_ascii_alphabetic()));
+    local_ok && domain_ok && tld_ok
+}
+
+/// Byte ranges of phone-number-like runs: digits with spaces, dashes, dots
+/// or parentheses between them, optionally starting with `+` or `(`.
+///
+/// A run with more than MAX_PHONE_DIGITS digits ("555-123-4567 555-987-6543")
+/// is split at its separators into numbers of the allowed length.
+fn phone_spans(text: &str) -> Vec<(usize, usize)> {
+    let bytes = text.as_bytes();
+    // Non-ASCII bytes are part of some letter, so count them as word bytes
+    let is_word = |b: u8| b.is_ascii_alphanumeric() || !b.is_ascii();
+    let is_phone = |b: u8| b.is_ascii_digit() || b" -.()+".contains(&b);
+
+    let mut spans = Vec::new();
+    let mut i = 0;
+    while i < bytes.len() {
+        let b = bytes[i];
+        let starts = (b.is_ascii_digit() || b == b'+' || b == b'(') && (i == 0 || !is_word(bytes[i - 1]));
+        if !starts {
+            i += 1;
+            continue;
+        }
+
+        // Extend the run, noting the groups of digits between separators
+        let mut j = i;
+        let mut groups = Vec::new();
+        while j < bytes.len() && is_phone(bytes[j]) {
+            if bytes[j].is_ascii_digit() {
+                let start = j;
+                while j < bytes.len() && bytes[j].is_ascii_digit() {
+                    j += 1;
+                }
+                groups.push((start, j));
+            } else {
+                j += 1;
+            }
+        }
+        // Digits running into a word ("4567abc") belong to that word
+        if groups.last().is_some_and(|&(_, end)| end < bytes.len() && is_word(bytes[end])) {
+            groups.pop();
+        }
+        spans.extend(split_run(bytes, i, &groups));
+        i = j.max(i + 1);
+    }
+    spans
+}
+
+/// Splits a run of digit groups into numbers of MIN_PHONE_DIGITS to
+/// MAX_PHONE_DIGITS digits, covering as many digits as possible. A run
+/// within the limits stays whole; each span ends after a digit, not a
+/// separator.
+fn split_run(bytes: &[u8], run_start: usize, groups: &[(usize, usize)]) -> Vec<(usize, usize)> {
+    // best[k]: the most digits the first k groups can cover, and the group
+    // starting the last span of that cover (None if group k-1 is left out)
+    let mut best: Vec<(usize, Option<usize>)> = vec![(0, None); groups.len() + 1];
+    for k in 1..=groups.len() {
+        best[k] = (best[k - 1].0, None);
+        let mut digits = 0;
+        for m in (0..k).rev() {
+            digits += groups[m].1 - groups[m].0;
+            if digits > MAX_PHONE_DIGITS {
+                break;
+            }
+            if digits >= MIN_PHONE_DIGITS && best[m].0 + digits > best[k].0 {
+                best[k] = (best[m].0 + digits, Some(m));
+            }
+        }
+    }
+
+    // Walk back from the end to read the spans off
+    let mut spans = Vec::new();
+    let mut k = groups.len();
+    while k > 0 {
+        let Some(m) = best[k].1 else {
+            k -= 1;
+            continue;
+        };
+        let start = match groups[m].0 {
+            _ if m == 0 => run_start,
+            // Keep an opening "(" or "+" with the number it starts
+            s if matches!(bytes[s - 1], b'(' | b'+') => s - 1,
+            s => s,
+        };
+        spans.push((start, groups[k - 1].1));
+        k = m;
+    }
+    spans.reverse();
+    spans
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn phones(text: &str) -> Vec<&str> {
+        phone_spans(text).into_iter().map(|(start, end)| &text[start..end]).collect()
+    }
+
+    #[test]
+    fn test_screen_spans() {
+        let screener = Screener::new()
+            .with_list(DenyList::new("profanity", Severity::High, &["darn"]))
+            .with_list(DenyList::new("codenames", Severity::Low, &["Darn", "bluebird"]));
+        let text = "Darn! Mail (jo.ann@mail.example.com) or call +1 555-123-4567 about BlueBird.";
+        let flags = screener.screen(text);
+
+        let found: Vec<_> = flags.iter().map(|f| (f.text, f.kind, f.severity)).collect();
+        // The first list a word is on decides its kind
+        let expected = vec![
+            ("Darn", FlagKind::Denied("profanity"), Severity::High),
+            ("jo.ann@mail.example.com", FlagKind::Email, Severity::Medium),
+            ("+1 555-123-4567", FlagKind::Phone, Severity::Medium),
+            ("BlueBird", FlagKind::Denied("codenames"), Severity::Low),
+        ];
+        assert_eq!(found, expected);
+        for flag in &flags {
+            assert_eq!(&text[flag.start..flag.end], flag.text);
+        }
+        assert_eq!(screener.max_severity(text), Some(Severity::High));
+        assert_eq!(screener.max_severity("All clear in 2024."), None);
+    }
+
+    #[test]
+    fn test_clean_masks_each_character() {
+        let screener = Screener::new().with_list(DenyList::new("words", Severity::Low, &["café"]));
+        assert_eq!(screener.clean("Café at me@ex.io, 5551234567."), "**** at ********, **********.");
+
+        // Digits in an email are reported with it, not as a phone number
+        let text = "5551234567@example.com";
+        assert_eq!(Screener::new().screen(text).len(), 1);
+        let quiet = Screener { emails: None, phones: None, ..Screener::new() };
+        assert_eq!(quiet.clean("me@ex.io 5551234567"), "me@ex.io 5551234567");
+    }
+
+    #[test]
+    fn test_phone_runs_split_at_separators() {
+        assert_eq!(phones("Call 555-123-4567 555-987-6543 today"), vec!["555-123-4567", "555-987-6543"]);
+        assert_eq!(phones("+1 (555) 123 4567 (555) 987 6543."), vec!["+1 (555) 123 4567", "(555) 987 6543"]);
+        // A run within the limits stays whole
+        assert_eq!(phones("+44 20 7946 0958"), vec!["+44 20 7946 0958"]);
+        // Too long to be a phone number on its own, or part of a word
+        assert_eq!(phones("ID 12345678901234567 5551234567"), vec!["5551234567"]);
+        assert!(phones("in 2024, 555-1234 or 5551234567abc").is_empty());
+    }
+}
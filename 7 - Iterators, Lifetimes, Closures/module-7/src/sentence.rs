@@ -0,0 +1,215 @@
+// =============================================================================
+// SENTENCE.RS - Splitting Text into Sentences
+// =============================================================================
+//
+// CONCEPTS DEMONSTRATED:
+// ----------------------
+// 1. LIFETIME ELISION (Module 7 - Eliding Lifetimes)
+//    - split_sentences(&str) -> Vec<&str> needs no annotations: with one
+//      input reference, the output borrows from it
+//
+// 2. PEEKABLE ITERATORS (Module 7 - Iterators Part 2)
+//    - char_indices().peekable() looks at the next character without
+//      consuming it, to see how a sentence ends
+//
+// 3. CLOSURES AS PATTERNS
+//    - trim_start_matches() takes a closure over chars; map() and any()
+//      take function paths like str::trim and char::is_alphanumeric
+//
+// WHY?
+// ----
+// Words alone don't say where one thought ends and the next begins. Average
+// and longest sentence length are the first thing a style check looks at,
+// and they need the punctuation that extract_words() trims away.
+//
+// =============================================================================
+
+use crate::prelude::*;
+
+// =============================================================================
+// ABBREVIATIONS
+// =============================================================================
+//
+// A period after one of these (in any case) doesn't end a sentence:
+// "Dr. Smith arrived." is one sentence, not two.
+//
+// Single letters ("J. R. R. Tolkien") and words with a period inside
+// ("e.g.", "U.S.") are treated the same way without being listed. The price
+// is that a sentence really ending in one of them runs on into the next.
+//
+// Ordinary words stay off the list: "I said no. She left." is two
+// sentences. "No." only counts as an abbreviation when a number follows it
+// on the same line ("No. 5").
+// =============================================================================
+
+const ABBREVIATIONS: &[&str] = &[
+    "mr", "mrs", "ms", "dr", "prof", "sr", "jr", "vs", "etc", "inc", "ltd", "corp", "vol", "fig",
+    "approx", "dept",
+];
+
+/// Abbreviations that are also ordinary words, so only count before a number.
+const NUMBER_ABBREVIATIONS: &[&str] = &["no"];
+
+/// Characters that can end a sentence.
+fn is_terminator(c: char) -> bool {
+    matches!(c, '.' | '!' | '?')
+}
+
+/// Closing quotes and brackets that belong to the sentence before them.
+fn is_closing(c: char) -> bool {
+    matches!(c, '"' | '\'' | ')' | ']' | '\u{201D}' | '\u{2019}')
+}
+
+/// Whether the period between `before` and `after` belongs to an
+/// abbreviation.
+fn ends_with_abbreviation(before: &str, after: &str) -> bool {
+    // The last whitespace-separated token, without an opening quote or bracket
+    let token = before
+        .rsplit(char::is_whitespace)
+        .next()
+        .unwrap_or("")
+        .trim_start_matches(|c: char| !c.is_alphanumeric());
+
+    let mut chars = token.chars();
+    match (chars.next(), chars.next()) {
+        // A lone letter is an initial
+        (Some(c), None) => c.is_alphabetic(),
+        _ if token.contains('.') || ABBREVIATIONS.iter().any(|a| a.eq_ignore_ascii_case(token)) => true,
+        _ => NUMBER_ABBREVIATIONS.iter().any(|a| a.eq_ignore_ascii_case(token)) && number_follows(after),
+    }
+}
+
+/// Whether `after` goes on to a digit on the same line. Stopping at the
+/// line break keeps line-by-line analysis in step with the whole text.
+fn number_follows(after: &str) -> bool {
+    after
+        .trim_start_matches(|c: char| c.is_whitespace() && c != '\n' && c != '\r')
+        .starts_with(|c: char| c.is_ascii_digit())
+}
+
+// =============================================================================
+// SEGMENTATION
+// =============================================================================
+
+/// Splits text into sentences, each a trimmed slice of `text`.
+///
+/// A sentence ends at a run of `.`, `!` or `?` (with any closing quotes or
+/// brackets) followed by whitespace or the end of the text, so "3.14" and
+/// "example.com" don't split. A lone period after an abbreviation or an
+/// initial doesn't end one either. Text after the last terminator is a
+/// sentence of its own, and pieces without any letters or digits ("...")
+/// are dropped.
+pub fn split_sentences(text: &str) -> Vec<&str> {
+    segments(text)
+        .into_iter()
+        .map(str::trim)
+        .filter(|piece| piece.chars().any(char::is_alphanumeric))
+        .collect()
+}
+
+/// The untrimmed pieces between sentence boundaries, including ones without
+/// words and the (possibly empty) rest after the last boundary.
+pub(crate) fn segments(text: &str) -> Vec<&str> {
+    let mut pieces = Vec::new();
+    let mut start = 0;
+
+    let mut chars = text.char_indices().peekable();
+    while let Some((i, c)) = chars.next() {
+        if !is_terminator(c) {
+            continue;
+        }
+
+        // PEEK: take the rest of the run ("?!", "...") and closing quotes
+        let mut end = i + c.len_utf8();
+        while let Some(&(j, next)) = chars.peek() {
+            if !is_terminator(next) && !is_closing(next) {
+                break;
+            }
+            end = j + next.len_utf8();
+            chars.next();
+        }
+
+        // A boundary needs whitespace (or the end of the text) after it
+        if chars.peek().is_some_and(|&(_, next)| !next.is_whitespace()) {
+            continue;
+        }
+        if c == '.' && end == i + 1 && ends_with_abbreviation(&text[start..i], &text[end..]) {
+            continue;
+        }
+
+        pieces.push(&text[start..end]);
+        start = end;
+    }
+    pieces.push(&text[start..]);
+
+    pieces
+}
+
+/// Whether a piece from `split_sentences()` closes its sentence, rather
+/// than being cut off by the end of the text. Used when text arrives a line
+/// at a time and a sentence may continue on the next line.
+pub fn ends_sentence(piece: &str) -> bool {
+    let body = piece.trim_end().trim_end_matches(is_closing);
+    let Some(last) = body.chars().next_back() else {
+        return false;
+    };
+    if !is_terminator(last) {
+        return false;
+    }
+    // A lone period after an abbreviation doesn't count ("... with Dr."),
+    // and nothing follows it here for "No." to number
+    let before = &body[..body.len() - 1];
+    !(last == '.' && !before.ends_with(is_terminator) && ends_with_abbreviation(before, ""))
+}
+
+/// Counts the words in a sentence the way `extract_words()` does: every
+/// whitespace-separated token with at least one letter or digit.
+pub fn count_words(sentence: &str) -> usize {
+    sentence
+        .split_whitespace()
+        .filter(|token| token.chars().any(char::is_alphanumeric))
+        .count()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_split_sentences_edge_cases() {
+        assert_eq!(split_sentences("Dr. Smith left. Why?"), vec!["Dr. Smith left.", "Why?"]);
+        // Initials, inner periods and decimals don't end a sentence
+        let text = "J. R. R. Tolkien wrote it, e.g. in 1937.";
+        assert_eq!(split_sentences(text), vec![text]);
+        assert_eq!(split_sentences("See example.com for 3.14 pies. Yes."), vec!["See example.com for 3.14 pies.", "Yes."]);
+        // Runs of terminators and closing quotes stay with their sentence
+        assert_eq!(split_sentences("Really?! \"Yes.\" (Fine.) Then"), vec!["Really?!", "\"Yes.\"", "(Fine.)", "Then"]);
+        // Pieces without words are dropped
+        assert_eq!(split_sentences("... Wait. !!"), vec!["Wait."]);
+        assert!(split_sentences("").is_empty());
+        assert!(split_sentences("  \n ").is_empty());
+    }
+
+    #[test]
+    fn test_ends_sentence_and_count_words() {
+        assert!(ends_sentence("It rained.\n"));
+        assert!(ends_sentence("\"Did it?\""));
+        assert!(!ends_sentence("It rained and"));
+        assert!(!ends_sentence("He met Dr."));
+        assert!(ends_sentence("The end..."));
+        assert!(!ends_sentence(""));
+
+        assert_eq!(count_words("Well -- it's 5 o'clock!"), 4);
+        assert_eq!(count_words("..."), 0);
+    }
+
+    #[test]
+    fn test_ordinary_words_end_sentences() {
+        assert_eq!(split_sentences("I said no. She left."), vec!["I said no.", "She left."]);
+        assert_eq!(split_sentences("Ask the co. Then the st. Done."), vec!["Ask the co.", "Then the st.", "Done."]);
+        assert_eq!(split_sentences("See No. 5 and fig. 3 today."), vec!["See No. 5 and fig. 3 today."]);
+        // The number has to be on the same line
+        assert_eq!(split_sentences("Say no.\n5 said yes."), vec!["Say no.", "5 said yes."]);
+        assert!(ends_sentence("I said no."));
+    }
+}
@@ -33,7 +33,10 @@
 //
 // =============================================================================
 
-use crate::word::Word;
+use crate::prelude::*;
+use crate::readability::count_syllables;
+use crate::sentence::{count_words, ends_sentence, segments, split_sentences};
+use crate::word::{extract_words, Word};
 
 // =============================================================================
 // TRAIT DEFINITION
@@ -222,6 +225,15 @@ pub struct TextStats {
     pub shortest_word_len: usize,
     pub capitalized_count: usize,
     pub reading_level: ReadingLevel,
+    /// Approximate, see `readability::count_syllables()`.
+    pub syllable_count: usize,
+    /// The sentence fields need the punctuation words don't keep, so they
+    /// stay zero until `with_sentences()` is given the source text.
+    pub sentence_count: usize,
+    /// Words per sentence.
+    pub avg_sentence_length: f64,
+    /// Words in the longest sentence.
+    pub longest_sentence_len: usize,
 }
 
 impl TextStats {
@@ -249,6 +261,10 @@ impl TextStats {
                 shortest_word_len: 0,
                 capitalized_count: 0,
                 reading_level: ReadingLevel::Elementary,
+                syllable_count: 0,
+                sentence_count: 0,
+                avg_sentence_length: 0.0,
+                longest_sentence_len: 0,
             };
         }
 
@@ -289,6 +305,9 @@ impl TextStats {
 
         let reading_level = ReadingLevel::from_avg_length(avg_word_length);
 
+        // ITERATOR: map() with a function path instead of a closure
+        let syllable_count = words.iter().map(|w| w.text).map(count_syllables).sum();
+
         TextStats {
             total_words,
             total_chars,
@@ -297,6 +316,148 @@ impl TextStats {
             shortest_word_len,
             capitalized_count,
             reading_level,
+            syllable_count,
+            sentence_count: 0,
+            avg_sentence_length: 0.0,
+            longest_sentence_len: 0,
+        }
+    }
+
+    // -------------------------------------------------------------------------
+    // CONSUMING BUILDER METHOD
+    // -------------------------------------------------------------------------
+    //
+    // `mut self` takes the stats by value, fills in more fields and hands
+    // them back, so calls chain:
+    //   TextStats::from_words(&words).with_sentences(text)
+    // -------------------------------------------------------------------------
+
+    /// Adds sentence statistics, from the text the words were extracted from.
+    pub fn with_sentences(mut self, text: &str) -> TextStats {
+        // ITERATOR: map() + collect() - one word count per sentence
+        let lengths: Vec<usize> = split_sentences(text).into_iter().map(count_words).collect();
+
+        self.sentence_count = lengths.len();
+        self.longest_sentence_len = lengths.iter().copied().max().unwrap_or(0);
+        self.avg_sentence_length = if lengths.is_empty() {
+            0.0
+        } else {
+            lengths.iter().sum::<usize>() as f64 / lengths.len() as f64
+        };
+        self
+    }
+}
+
+// =============================================================================
+// BUILDING STATS INCREMENTALLY
+// =============================================================================
+//
+// from_words() needs every Word at once, and every Word borrows the text, so
+// the whole text has to be in memory. The builder instead keeps only running
+// totals (all owned usizes): feed it one line, drop the line, feed the next.
+//
+// Sentences can run across lines, so the builder also remembers how many
+// words the unfinished sentence has so far.
+// =============================================================================
+
+/// Builds `TextStats` a line at a time, for text too large to hold in memory.
+///
+/// Gives the same stats as `TextStats::from_words(..).with_sentences(..)`
+/// on the whole text.
+#[derive(Debug, Default)]
+pub struct TextStatsBuilder {
+    total_words: usize,
+    total_chars: usize,
+    longest_word_len: usize,
+    // None until the first word: 0 would always be the minimum
+    shortest_word_len: Option<usize>,
+    capitalized_count: usize,
+    syllable_count: usize,
+    sentence_count: usize,
+    // Words in finished sentences, for the average
+    sentence_words: usize,
+    longest_sentence_len: usize,
+    open_sentence_len: usize,
+}
+
+impl TextStatsBuilder {
+    pub fn new() -> TextStatsBuilder {
+        TextStatsBuilder::default()
+    }
+
+    /// Adds one line of text (with or without its line break).
+    pub fn add_line(&mut self, line: &str) {
+        self.add_words(&extract_words(line));
+        self.add_sentences(line);
+    }
+
+    /// Adds words, without looking for sentence boundaries. Use with
+    /// `add_sentences()` on the same line when the words are needed for
+    /// something else too.
+    pub fn add_words(&mut self, words: &[Word]) {
+        for word in words {
+            self.total_words += 1;
+            self.total_chars += word.char_count();
+            self.longest_word_len = self.longest_word_len.max(word.len());
+            // map_or: the first word is the shortest so far
+            self.shortest_word_len = Some(self.shortest_word_len.map_or(word.len(), |s| s.min(word.len())));
+            if word.is_capitalized() {
+                self.capitalized_count += 1;
+            }
+            self.syllable_count += count_syllables(word.text);
+        }
+    }
+
+    /// Counts the sentences ending in `line`, carrying an unfinished one
+    /// over to the next line.
+    pub fn add_sentences(&mut self, line: &str) {
+        // Raw segments, so a line of just "..." still ends the open sentence
+        let pieces = segments(line);
+        let last = pieces.len() - 1;
+        for (i, piece) in pieces.into_iter().enumerate() {
+            self.open_sentence_len += count_words(piece);
+            // Only the last piece can be cut off by the end of the line
+            if i < last || ends_sentence(piece) {
+                self.close_sentence();
+            }
+        }
+    }
+
+    fn close_sentence(&mut self) {
+        if self.open_sentence_len == 0 {
+            return;
+        }
+        self.sentence_count += 1;
+        self.sentence_words += self.open_sentence_len;
+        self.longest_sentence_len = self.longest_sentence_len.max(self.open_sentence_len);
+        self.open_sentence_len = 0;
+    }
+
+    /// The stats so far; text after the last sentence end counts as a sentence.
+    pub fn finish(mut self) -> TextStats {
+        self.close_sentence();
+        let avg_word_length = if self.total_words == 0 {
+            0.0
+        } else {
+            self.total_chars as f64 / self.total_words as f64
+        };
+        let avg_sentence_length = if self.sentence_count == 0 {
+            0.0
+        } else {
+            self.sentence_words as f64 / self.sentence_count as f64
+        };
+        TextStats {
+            total_words: self.total_words,
+            total_chars: self.total_chars,
+            avg_word_length,
+            longest_word_len: self.longest_word_len,
+            shortest_word_len: self.shortest_word_len.unwrap_or(0),
+            capitalized_count: self.capitalized_count,
+            reading_level: ReadingLevel::from_avg_length(avg_word_length),
+            syllable_count: self.syllable_count,
+            sentence_count: self.sentence_count,
+            avg_sentence_length,
+            longest_sentence_len: self.longest_sentence_len,
         }
     }
 }
@@ -361,7 +522,7 @@ pub fn any_matches<F>(words: &[Word], predicate: F) -> bool
 where
     F: Fn(&Word) -> bool,
 {
-    words.iter().any(|w| predicate(w))
+    words.iter().any(predicate)
 }
 
 /// Collect words matching a predicate into a new Vec.
@@ -431,5 +592,5 @@ where
 {
     // iter() creates an iterator over references
     // fold() accumulates a result
-    words.iter().fold(init, |acc, w| folder(acc, w))
+    words.iter().fold(init, folder)
 }
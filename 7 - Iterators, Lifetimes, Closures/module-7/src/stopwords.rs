@@ -0,0 +1,90 @@
+// =============================================================================
+// STOPWORDS.RS - Words Too Common to Count
+// =============================================================================
+//
+// CONCEPTS DEMONSTRATED:
+// ----------------------
+// 1. HASHSET (Ch 8 - Storing Keys with Associated Values)
+//    - A HashMap without values: fast "is this word in the set?" checks
+//    - extend() to add many items from an iterator
+//
+// 2. CONSUMING BUILDER METHODS
+//    - with_words() takes and returns self, like Screener::with_list()
+//
+// 3. STATIC SLICES
+//    - &'static [&'static str] for a word list compiled into the binary
+//
+// WHY?
+// ----
+// In any English text "the", "is" and "a" are the most frequent words, so
+// WordFrequency::top_n() says little about what the text is about until
+// they are filtered out. A project can add its own list on top ("rust" in
+// a Rust book, the company name in its reports).
+//
+// =============================================================================
+
+use crate::prelude::*;
+
+// =============================================================================
+// BUILT-IN LIST
+// =============================================================================
+
+/// Common English function words: articles, pronouns, auxiliaries,
+/// prepositions and conjunctions.
+pub const ENGLISH: &[&str] = &[
+    "a", "about", "above", "after", "again", "against", "all", "am", "an", "and", "any", "are",
+    "as", "at", "be", "because", "been", "before", "being", "below", "between", "both", "but",
+    "by", "can", "could", "did", "do", "does", "doing", "down", "during", "each", "few", "for",
+    "from", "further", "had", "has", "have", "having", "he", "her", "here", "hers", "herself",
+    "him", "himself", "his", "how", "i", "if", "in", "into", "is", "it", "its", "itself", "just",
+    "me", "more", "most", "my", "myself", "no", "nor", "not", "now", "of", "off", "on", "once",
+    "only", "or", "other", "our", "ours", "ourselves", "out", "over", "own", "same", "she",
+    "should", "so", "some", "such", "than", "that", "the", "their", "theirs", "them",
+    "themselves", "then", "there", "these", "they", "this", "those", "through", "to", "too",
+    "under", "until", "up", "very", "was", "we", "were", "what", "when", "where", "which",
+    "while", "who", "whom", "why", "will", "with", "would", "you", "your", "yours", "yourself",
+    "yourselves",
+];
+
+// =============================================================================
+// STOPWORD SET
+// =============================================================================
+
+/// A set of words to leave out of frequency counts, matched ignoring case.
+#[derive(Debug, Clone, Default)]
+pub struct Stopwords {
+    // Stored lowercase, so lookups only lowercase the candidate
+    words: HashSet<String>,
+}
+
+impl Stopwords {
+    /// An empty set; add words with `with_words()`.
+    pub fn new() -> Stopwords {
+        Stopwords::default()
+    }
+
+    /// The built-in [`ENGLISH`] list.
+    pub fn english() -> Stopwords {
+        Stopwords::new().with_words(ENGLISH)
+    }
+
+    /// Adds words to the set; takes and returns self so calls can be chained:
+    ///   Stopwords::english().with_words(&["rust", "cargo"])
+    pub fn with_words(mut self, words: &[&str]) -> Stopwords {
+        // extend() inserts every item of the iterator; duplicates are ignored
+        self.words.extend(words.iter().map(|w| w.to_lowercase()));
+        self
+    }
+
+    pub fn contains(&self, word: &str) -> bool {
+        self.words.contains(&word.to_lowercase())
+    }
+
+    pub fn len(&self) -> usize {
+        self.words.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.words.is_empty()
+    }
+}
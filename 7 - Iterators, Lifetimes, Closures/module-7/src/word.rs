@@ -35,6 +35,7 @@
 // =============================================================================
 
 use crate::error::{AnalysisError, AnalysisResult};
+use crate::prelude::*;
 
 // =============================================================================
 // STRUCT WITH LIFETIME PARAMETER
@@ -169,8 +170,12 @@ impl<'a> Word<'a> {
     // self.text.chars() - creates iterator over characters
     // .next() - gets first element as Option<char>
     // .map_or(false, |c| ...) - handles the Option
+    //
+    // Newer Rust also offers `is_some_and(|c| ...)` for exactly this shape;
+    // we keep map_or here because it generalizes to any default value.
     // -------------------------------------------------------------------------
 
+    #[allow(clippy::unnecessary_map_or)]
     pub fn is_capitalized(&self) -> bool {
         // CLOSURE: |c| c.is_uppercase()
         // This is an inline function that takes c and returns whether it's uppercase.
@@ -266,10 +271,9 @@ pub fn extract_words<'a>(text: &'a str) -> Vec<Word<'a>> {
     //
     // From Module 7 (Iterators Part 1): for (line_num, line) in text.lines().enumerate()
     for (line_num, line) in text.lines().enumerate() {
-        let mut position = 0;
-
         // split_whitespace() splits on any whitespace and skips empty strings
-        for word_text in line.split_whitespace() {
+        // enumerate() again supplies the word's position within the line
+        for (position, word_text) in line.split_whitespace().enumerate() {
             // CLOSURE FOR TRIMMING
             // trim_matches takes a closure that returns true for chars to remove
             // |c: char| !c.is_alphanumeric() removes non-alphanumeric chars from edges
@@ -281,7 +285,6 @@ pub fn extract_words<'a>(text: &'a str) -> Vec<Word<'a>> {
                 // This is why Word can borrow with lifetime 'a
                 words.push(Word::new(cleaned, position, line_num + 1));
             }
-            position += 1;
         }
     }
 
@@ -0,0 +1,65 @@
+// =============================================================================
+// BENCHES/TITLE_SEARCH.RS - inverted word index vs scanning every title
+// =============================================================================
+//
+// Run with:
+//   cargo bench --bench title_search
+//
+// Builds a 50,000-book catalog from the fixtures and times the same
+// multi-word queries answered two ways: `Library::search_titles`, which
+// intersects posting lists from the word index, and a linear scan that
+// normalizes and checks every title.
+// =============================================================================
+
+use std::time::Instant;
+
+use module_8::fixtures::Fixtures;
+use module_8::{Book, Library};
+
+const BOOKS: usize = 50_000;
+const ROUNDS: u32 = 20;
+
+/// Lowercase alphanumeric words, like the library's own normalization.
+fn words(text: &str) -> Vec<String> {
+    text.split(|c: char| !c.is_alphanumeric())
+        .filter(|w| !w.is_empty())
+        .map(str::to_lowercase)
+        .collect()
+}
+
+/// The same query answered by looking at every title.
+fn scan<'a>(library: &'a Library, query: &str) -> Vec<&'a Book> {
+    let query = words(query);
+    library
+        .books()
+        .filter(|book| {
+            let title = words(&book.title);
+            query.iter().all(|q| title.contains(q))
+        })
+        .collect()
+}
+
+/// Runs `f` `ROUNDS` times, returning its last result and the average
+/// time per call in microseconds.
+fn measure<T>(mut f: impl FnMut() -> T) -> (T, u128) {
+    let start = Instant::now();
+    let mut result = f();
+    for _ in 1..ROUNDS {
+        result = f();
+    }
+    (result, start.elapsed().as_micros() / u128::from(ROUNDS))
+}
+
+fn main() {
+    let start = Instant::now();
+    let library = Fixtures { seed: 42, books: BOOKS, members: 0 }.library();
+    println!("Catalog of {} books built in {} ms\n", BOOKS, start.elapsed().as_millis());
+
+    println!("{:<24} {:>7} {:>12} {:>12}", "query", "hits", "index (µs)", "scan (µs)");
+    for query in ["rust", "quiet rust", "the golden orbit", "the", "missing words"] {
+        let (indexed, index_us) = measure(|| library.search_titles(query));
+        let (scanned, scan_us) = measure(|| scan(&library, query));
+        assert_eq!(indexed.len(), scanned.len(), "both approaches agree on {:?}", query);
+        println!("{:<24} {:>7} {:>12} {:>12}", format!("{:?}", query), indexed.len(), index_us, scan_us);
+    }
+}
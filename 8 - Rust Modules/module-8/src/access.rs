@@ -0,0 +1,424 @@
+//! Access module - who may do what to a `Library`.
+//!
+//! The plain `Library` methods trust their caller: they're what the rest of
+//! this crate and the demo use. Applications that serve patrons and staff go
+//! through a [`Session`] instead, opened with [`Library::session`] for one
+//! [`Actor`]. Every session method checks the permission matrix (see
+//! [`Role::allows`]), records the decision in the library's audit log, and
+//! returns [`LibraryError::NotAuthorized`] when the actor isn't allowed.
+//!
+//! ```
+//! use chrono::NaiveDate;
+//! use module_8::access::{Actor, Operation};
+//! use module_8::{Book, Genre, Library, LibraryError, Member, MembershipTier, Money};
+//!
+//! let mut lib = Library::new();
+//! lib.add_book(Book::new(1, "Dune", Genre::SciFi)).unwrap();
+//! lib.register_member(Member::new(10, "Alice", MembershipTier::Basic)).unwrap();
+//! let today = NaiveDate::from_ymd_opt(2024, 3, 1).unwrap();
+//!
+//! let alice = Actor::patron(10);
+//! lib.session(alice, today).checkout(10, 1).unwrap();
+//! assert_eq!(
+//!     lib.session(alice, today).waive_fines(10, Money::from_cents(100)),
+//!     Err(LibraryError::NotAuthorized { actor: alice, operation: Operation::WaiveFines })
+//! );
+//! assert_eq!(lib.audit_log().len(), 2);
+//! ```
+
+use std::fmt;
+
+use chrono::NaiveDate;
+
+use crate::book::Book;
+use crate::error::LibraryError;
+use crate::member::Member;
+use crate::merge::{MergePolicy, MergeReport};
+use crate::money::Money;
+use crate::Library;
+
+// =============================================================================
+// ROLES AND ACTORS
+// =============================================================================
+
+/// What kind of user is acting.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Role {
+    /// A library member acting on their own account.
+    Patron,
+    /// Front-desk staff.
+    Librarian,
+    /// Staff who administer the catalog as a whole.
+    Admin,
+    /// The library's own scheduled jobs, such as hold expiry.
+    System,
+}
+
+/// An operation that changes the library.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Operation {
+    Checkout,
+    Return,
+    Renew,
+    PlaceHold,
+    PayFines,
+    WaiveFines,
+    AddBook,
+    RemoveBook,
+    RegisterMember,
+    DeactivateMember,
+    MergeCatalogs,
+    ExpireHold,
+}
+
+impl Operation {
+    /// Operations a patron may perform, but only on their own account.
+    fn is_self_service(self) -> bool {
+        matches!(
+            self,
+            Operation::Checkout
+                | Operation::Return
+                | Operation::Renew
+                | Operation::PlaceHold
+                | Operation::PayFines
+        )
+    }
+}
+
+impl fmt::Display for Operation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Operation::Checkout => "check out books",
+            Operation::Return => "return books",
+            Operation::Renew => "renew loans",
+            Operation::PlaceHold => "place holds",
+            Operation::PayFines => "pay fines",
+            Operation::WaiveFines => "waive fines",
+            Operation::AddBook => "add books",
+            Operation::RemoveBook => "remove books",
+            Operation::RegisterMember => "register members",
+            Operation::DeactivateMember => "deactivate members",
+            Operation::MergeCatalogs => "merge catalogs",
+            Operation::ExpireHold => "expire holds",
+        };
+        f.write_str(name)
+    }
+}
+
+impl Role {
+    /// The permission matrix.
+    ///
+    /// | Operation | Patron | Librarian | Admin | System |
+    /// |-----------|:------:|:---------:|:-----:|:------:|
+    /// | checkout, return, renew, place hold, pay fines | own account | ✓ | ✓ | |
+    /// | waive fines, add/remove books, register/deactivate members | | ✓ | ✓ | |
+    /// | merge catalogs | | | ✓ | |
+    /// | expire holds | | ✓ | ✓ | ✓ |
+    ///
+    /// "Own account" is enforced by [`Actor::may`]; this only says whether
+    /// the role can perform the operation at all.
+    pub fn allows(self, operation: Operation) -> bool {
+        match self {
+            Role::Patron => operation.is_self_service(),
+            Role::Librarian => operation != Operation::MergeCatalogs,
+            Role::Admin => true,
+            Role::System => operation == Operation::ExpireHold,
+        }
+    }
+}
+
+impl fmt::Display for Role {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(self, f)
+    }
+}
+
+/// A user acting on the library: a member ID for patrons, a staff ID otherwise.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Actor {
+    pub id: u64,
+    pub role: Role,
+}
+
+impl Actor {
+    pub fn patron(member_id: u64) -> Self {
+        Actor { id: member_id, role: Role::Patron }
+    }
+
+    pub fn librarian(staff_id: u64) -> Self {
+        Actor { id: staff_id, role: Role::Librarian }
+    }
+
+    pub fn admin(staff_id: u64) -> Self {
+        Actor { id: staff_id, role: Role::Admin }
+    }
+
+    /// The library itself, recorded in the audit log for scheduled jobs.
+    pub fn system() -> Self {
+        Actor { id: 0, role: Role::System }
+    }
+
+    /// Whether this actor may perform `operation`, on behalf of `member_id`
+    /// if the operation concerns one member's account.
+    pub fn may(&self, operation: Operation, member_id: Option<u64>) -> bool {
+        match self.role {
+            Role::Patron => self.role.allows(operation) && member_id == Some(self.id),
+            role => role.allows(operation),
+        }
+    }
+}
+
+// =============================================================================
+// AUDIT LOG
+// =============================================================================
+
+/// One authorization decision, as recorded in [`Library::audit_log`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct AuditEntry {
+    pub on: NaiveDate,
+    pub actor: Actor,
+    pub operation: Operation,
+    /// The member whose account was concerned, if any.
+    pub member_id: Option<u64>,
+    /// The book concerned, if any.
+    pub book_id: Option<u64>,
+    /// `false` if the request was refused as not authorized. An allowed
+    /// request may still fail for other reasons (e.g. the book is on loan).
+    pub allowed: bool,
+}
+
+// =============================================================================
+// SESSIONS
+// =============================================================================
+
+/// A [`Library`] borrowed on behalf of one actor; see the module docs.
+pub struct Session<'a> {
+    library: &'a mut Library,
+    actor: Actor,
+    today: NaiveDate,
+}
+
+impl Library {
+    /// Opens a session in which every change is checked against `actor`'s
+    /// permissions and audited under `today`'s date.
+    pub fn session(&mut self, actor: Actor, today: NaiveDate) -> Session<'_> {
+        Session { library: self, actor, today }
+    }
+
+    /// Every authorization decision made by sessions, and every hold
+    /// [expired](Library::process_expired_holds) by the library, oldest
+    /// first.
+    pub fn audit_log(&self) -> &[AuditEntry] {
+        &self.audit_log
+    }
+
+    /// Checks `actor`'s permission and records the decision.
+    pub(crate) fn authorize(
+        &mut self,
+        actor: Actor,
+        operation: Operation,
+        member_id: Option<u64>,
+        book_id: Option<u64>,
+        today: NaiveDate,
+    ) -> Result<(), LibraryError> {
+        let allowed = actor.may(operation, member_id);
+        self.audit_log.push(AuditEntry {
+            on: today,
+            actor,
+            operation,
+            member_id,
+            book_id,
+            allowed,
+        });
+        if allowed {
+            Ok(())
+        } else {
+            Err(LibraryError::NotAuthorized { actor, operation })
+        }
+    }
+}
+
+impl Session<'_> {
+    pub fn actor(&self) -> Actor {
+        self.actor
+    }
+
+    /// Read-only access to the library; reads need no permission.
+    pub fn library(&self) -> &Library {
+        self.library
+    }
+
+    fn authorize(&mut self, operation: Operation, member_id: Option<u64>, book_id: Option<u64>) -> Result<(), LibraryError> {
+        self.library
+            .authorize(self.actor, operation, member_id, book_id, self.today)
+    }
+
+    /// [`Library::checkout`], for the member's own account if a patron.
+    pub fn checkout(&mut self, member_id: u64, book_id: u64) -> Result<NaiveDate, LibraryError> {
+        self.authorize(Operation::Checkout, Some(member_id), Some(book_id))?;
+        self.library.checkout(member_id, book_id, self.today)
+    }
+
+    /// [`Library::return_book`], for the member's own account if a patron.
+    pub fn return_book(&mut self, member_id: u64, book_id: u64) -> Result<Money, LibraryError> {
+        self.authorize(Operation::Return, Some(member_id), Some(book_id))?;
+        self.library.return_book(member_id, book_id, self.today)
+    }
+
+    /// [`Library::renew`], for the member's own account if a patron.
+    pub fn renew(&mut self, member_id: u64, book_id: u64) -> Result<NaiveDate, LibraryError> {
+        self.authorize(Operation::Renew, Some(member_id), Some(book_id))?;
+        self.library.renew(member_id, book_id, self.today)
+    }
+
+    /// [`Library::place_hold`], for the member's own account if a patron.
+    pub fn place_hold(&mut self, member_id: u64, book_id: u64) -> Result<(), LibraryError> {
+        self.authorize(Operation::PlaceHold, Some(member_id), Some(book_id))?;
+        self.library.place_hold(member_id, book_id, self.today)
+    }
+
+    /// [`Library::pay_fines`], for the member's own account if a patron.
+    pub fn pay_fines(&mut self, member_id: u64, amount: Money) -> Result<Money, LibraryError> {
+        self.authorize(Operation::PayFines, Some(member_id), None)?;
+        self.library.pay_fines(member_id, amount, self.today)
+    }
+
+    /// [`Library::waive_fines`]; staff only.
+    pub fn waive_fines(&mut self, member_id: u64, amount: Money) -> Result<Money, LibraryError> {
+        self.authorize(Operation::WaiveFines, Some(member_id), None)?;
+        self.library.waive_fines(member_id, amount, self.today)
+    }
+
+    /// [`Library::add_book`]; staff only.
+    pub fn add_book(&mut self, book: Book) -> Result<(), LibraryError> {
+        self.authorize(Operation::AddBook, None, Some(book.id()))?;
+        self.library.add_book(book)
+    }
+
+    /// [`Library::remove_book`]; staff only.
+    pub fn remove_book(&mut self, book_id: u64) -> Result<Book, LibraryError> {
+        self.authorize(Operation::RemoveBook, None, Some(book_id))?;
+        self.library.remove_book(book_id)
+    }
+
+    /// [`Library::register_member`]; staff only.
+    pub fn register_member(&mut self, member: Member) -> Result<(), LibraryError> {
+        self.authorize(Operation::RegisterMember, Some(member.id()), None)?;
+        self.library.register_member(member)
+    }
+
+    /// [`Library::deactivate_member`]; staff only.
+    pub fn deactivate_member(&mut self, member_id: u64) -> Result<(), LibraryError> {
+        self.authorize(Operation::DeactivateMember, Some(member_id), None)?;
+        self.library.deactivate_member(member_id)
+    }
+
+    /// [`Library::merge`]; admins only.
+    pub fn merge(&mut self, other: Library, policy: MergePolicy) -> Result<MergeReport, LibraryError> {
+        self.authorize(Operation::MergeCatalogs, None, None)?;
+        self.library.merge(other, policy)
+    }
+}
+
+// =============================================================================
+// TESTS
+// =============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Genre, MembershipTier};
+
+    fn setup() -> (Library, NaiveDate) {
+        let mut lib = Library::new();
+        lib.add_book(Book::new(1, "Dune", Genre::SciFi)).unwrap();
+        lib.register_member(Member::new(10, "Alice", MembershipTier::Basic)).unwrap();
+        lib.register_member(Member::new(11, "Bob", MembershipTier::Basic)).unwrap();
+        (lib, NaiveDate::from_ymd_opt(2024, 3, 1).unwrap())
+    }
+
+    #[test]
+    fn test_permission_matrix() {
+        use Operation::*;
+        let all = [
+            Checkout, Return, Renew, PlaceHold, PayFines, WaiveFines, AddBook, RemoveBook,
+            RegisterMember, DeactivateMember, MergeCatalogs, ExpireHold,
+        ];
+        let patron: Vec<Operation> = all.into_iter().filter(|&op| Role::Patron.allows(op)).collect();
+        assert_eq!(patron, [Checkout, Return, Renew, PlaceHold, PayFines]);
+        assert!(all.iter().all(|&op| Role::Librarian.allows(op) == (op != MergeCatalogs)));
+        assert!(all.iter().all(|&op| Role::Admin.allows(op)));
+        assert!(all.iter().all(|&op| Role::System.allows(op) == (op == ExpireHold)));
+
+        assert!(Actor::patron(10).may(Renew, Some(10)));
+        assert!(!Actor::patron(10).may(Renew, Some(11)));
+        assert!(Actor::librarian(1).may(Renew, Some(11)));
+    }
+
+    #[test]
+    fn test_patron_acts_only_on_own_account() {
+        let (mut lib, today) = setup();
+        let bob = Actor::patron(11);
+
+        let denied = lib.session(bob, today).checkout(10, 1);
+        assert_eq!(denied, Err(LibraryError::NotAuthorized { actor: bob, operation: Operation::Checkout }));
+        assert!(lib.loans().is_empty());
+
+        lib.session(bob, today).checkout(11, 1).unwrap();
+        assert_eq!(lib.session(bob, today).renew(11, 1), Ok(NaiveDate::from_ymd_opt(2024, 3, 15).unwrap()));
+    }
+
+    #[test]
+    fn test_only_staff_waive_fines() {
+        let (mut lib, today) = setup();
+        lib.checkout(10, 1, today).unwrap();
+        lib.return_book(10, 1, NaiveDate::from_ymd_opt(2024, 3, 25).unwrap()).unwrap();
+        let owed = lib.find_member(10).unwrap().outstanding_fines();
+        assert!(!owed.is_zero());
+
+        assert!(lib.session(Actor::patron(10), today).waive_fines(10, owed).is_err());
+        assert_eq!(lib.session(Actor::librarian(1), today).waive_fines(10, owed), Ok(Money::ZERO));
+    }
+
+    #[test]
+    fn test_only_admins_merge() {
+        let (mut lib, today) = setup();
+        let branch = || {
+            let mut branch = Library::new();
+            branch.add_book(Book::new(2, "Emma", Genre::Fiction)).unwrap();
+            branch
+        };
+        let denied = lib.session(Actor::librarian(1), today).merge(branch(), MergePolicy::default());
+        assert!(matches!(denied, Err(LibraryError::NotAuthorized { .. })));
+        assert_eq!(lib.book_count(), 1);
+
+        let report = lib.session(Actor::admin(1), today).merge(branch(), MergePolicy::default()).unwrap();
+        assert_eq!(report.books_added, 1);
+    }
+
+    #[test]
+    fn test_audit_log_records_actor_and_outcome() {
+        let (mut lib, today) = setup();
+        let admin = Actor::admin(1);
+        let _ = lib.session(Actor::patron(10), today).remove_book(1);
+        lib.session(admin, today).remove_book(1).unwrap();
+
+        let log = lib.audit_log();
+        assert_eq!(log.len(), 2);
+        assert_eq!((log[0].actor, log[0].allowed), (Actor::patron(10), false));
+        assert_eq!(
+            log[1],
+            AuditEntry {
+                on: today,
+                actor: admin,
+                operation: Operation::RemoveBook,
+                member_id: None,
+                book_id: Some(1),
+                allowed: true,
+            }
+        );
+    }
+}
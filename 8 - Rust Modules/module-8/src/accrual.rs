@@ -0,0 +1,187 @@
+//! Accrual module - late fees charged day by day instead of at return time.
+//!
+//! Without accrual, a member's balance only grows when an overdue book comes
+//! back. [`Library::tick`] is the daily job that brings every overdue loan's
+//! charges up to date, so fines show up (and count against the member) while
+//! the book is still out. Each run charges only the difference between what
+//! the loan owes as of that day and what was already charged for it, which
+//! makes re-running a day, or running an earlier day late, harmless.
+//!
+//! ```
+//! use chrono::NaiveDate;
+//! use module_8::{Book, Genre, Library, Member, MembershipTier, Money};
+//!
+//! let mut lib = Library::new();
+//! lib.add_book(Book::new(1, "Dune", Genre::SciFi)).unwrap();
+//! lib.register_member(Member::new(10, "Alice", MembershipTier::Basic)).unwrap();
+//! let day = |d| NaiveDate::from_ymd_opt(2024, 3, d).unwrap();
+//!
+//! lib.checkout(10, 1, day(1)).unwrap(); // due on the 15th
+//! assert_eq!(lib.tick(day(17)).fees_charged, Money::from_cents(50));
+//! assert_eq!(lib.tick(day(17)).fees_charged, Money::ZERO); // same day again
+//! assert_eq!(lib.return_book(10, 1, day(18)), Ok(Money::from_cents(75)));
+//! assert_eq!(lib.find_member(10).unwrap().outstanding_fines().to_string(), "$0.75");
+//! ```
+
+use chrono::NaiveDate;
+
+use crate::circulation::Loan;
+use crate::ledger::FineKind;
+use crate::money::Money;
+use crate::Library;
+
+/// One day's late-fee charge against one loan.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct FeeAccrual {
+    pub member_id: u64,
+    pub book_id: u64,
+    /// Identifies the loan, together with the member and book.
+    pub borrowed_on: NaiveDate,
+    /// The day the charge was made.
+    pub on: NaiveDate,
+    /// Amount charged, after the member's tier discount.
+    pub amount: Money,
+}
+
+impl FeeAccrual {
+    fn is_for(&self, loan: &Loan) -> bool {
+        self.member_id == loan.member_id
+            && self.book_id == loan.book_id
+            && self.borrowed_on == loan.borrowed_on
+    }
+}
+
+/// What one run of [`Library::tick`] did.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct Tick {
+    /// Charged across all loans by this run.
+    pub fees_charged: Money,
+    /// Loans past due, each of which fired a `LibraryEvent::Overdue`.
+    pub overdue_loans: usize,
+    /// Expired memberships, each of which fired a `LibraryEvent::MembershipExpired`.
+    pub expired_members: usize,
+    /// Holds that lapsed on the hold shelf; see
+    /// [`process_expired_holds`](Library::process_expired_holds).
+    pub expired_holds: usize,
+}
+
+impl Library {
+    /// The daily job: accrues late fees, then runs
+    /// [`check_overdue`](Library::check_overdue),
+    /// [`check_memberships`](Library::check_memberships) and
+    /// [`process_expired_holds`](Library::process_expired_holds).
+    ///
+    /// Charging is idempotent: running `tick` twice for the same day charges
+    /// nothing the second time. The overdue and expiry notifications fire
+    /// on every run, as they do when those checks are called directly.
+    pub fn tick(&mut self, today: NaiveDate) -> Tick {
+        let fees_charged = self.accrue_fees(today);
+        Tick {
+            fees_charged,
+            overdue_loans: self.check_overdue(today),
+            expired_members: self.check_memberships(today),
+            expired_holds: self.process_expired_holds(today).len(),
+        }
+    }
+
+    /// Charges every open loan the late fee it owes as of `today`, less what
+    /// earlier runs already charged it. Returns the total charged.
+    pub fn accrue_fees(&mut self, today: NaiveDate) -> Money {
+        let mut charges = Vec::new();
+        for loan in &self.loans {
+            let Some(member) = self.members.get(&loan.member_id) else { continue };
+            let owed = self.member_late_fee(member, loan, today);
+            let due = owed.saturating_sub(self.accrued_for(loan));
+            if !due.is_zero() {
+                charges.push(FeeAccrual {
+                    member_id: loan.member_id,
+                    book_id: loan.book_id,
+                    borrowed_on: loan.borrowed_on,
+                    on: today,
+                    amount: due,
+                });
+            }
+        }
+
+        let mut total = Money::ZERO;
+        for accrual in charges {
+            if let Some(member) = self.member_mut(accrual.member_id) {
+                member.charge(accrual.amount);
+            }
+            self.record_fine(today, accrual.member_id, FineKind::Assessed, accrual.amount);
+            self.review_status(accrual.member_id);
+            total = total.saturating_add(accrual.amount);
+            self.fee_accruals.push(accrual);
+        }
+        total
+    }
+
+    /// A member's accrued charges, oldest first, for open and returned loans.
+    pub fn fee_accruals(&self, member_id: u64) -> Vec<&FeeAccrual> {
+        self.fee_accruals
+            .iter()
+            .filter(|a| a.member_id == member_id)
+            .collect()
+    }
+
+    /// Amount already charged to `loan` by [`accrue_fees`](Library::accrue_fees).
+    pub(crate) fn accrued_for(&self, loan: &Loan) -> Money {
+        Money::saturating_sum(self.fee_accruals.iter().filter(|a| a.is_for(loan)).map(|a| a.amount))
+    }
+}
+
+// =============================================================================
+// TESTS
+// =============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Book, Genre, Member, MembershipTier};
+
+    fn date(day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(2024, 3, day).unwrap()
+    }
+
+    fn setup() -> Library {
+        let mut lib = Library::new();
+        lib.add_book(Book::new(1, "Dune", Genre::SciFi)).unwrap();
+        lib.register_member(Member::new(10, "Alice", MembershipTier::Basic)).unwrap();
+        lib.checkout(10, 1, date(1)).unwrap();
+        lib
+    }
+
+    #[test]
+    fn test_fees_accrue_daily_and_idempotently() {
+        let mut lib = setup();
+        assert_eq!(lib.tick(date(15)), Tick::default());
+
+        assert_eq!(lib.accrue_fees(date(16)), Money::from_cents(25));
+        assert_eq!(lib.accrue_fees(date(16)), Money::ZERO);
+        assert_eq!(lib.accrue_fees(date(19)), Money::from_cents(75));
+        // A late run for an earlier day charges nothing
+        assert_eq!(lib.accrue_fees(date(17)), Money::ZERO);
+
+        let history: Vec<(NaiveDate, u64)> = lib.fee_accruals(10).iter().map(|a| (a.on, a.amount.cents())).collect();
+        assert_eq!(history, [(date(16), 25), (date(19), 75)]);
+        assert_eq!(lib.find_member(10).unwrap().outstanding_fines(), Money::from_cents(100));
+    }
+
+    #[test]
+    fn test_return_charges_only_the_remainder() {
+        let mut lib = setup();
+        lib.accrue_fees(date(17));
+
+        // Four days late in total; two were already accrued
+        assert_eq!(lib.return_book(10, 1, date(19)), Ok(Money::from_cents(100)));
+        let alice = lib.find_member(10).unwrap();
+        assert_eq!(alice.outstanding_fines(), Money::from_cents(100));
+        assert_eq!(alice.history()[0].fee_charged, Money::from_cents(100));
+
+        // Accruals for a returned loan don't carry over to the next one
+        lib.checkout(10, 1, date(19)).unwrap();
+        assert_eq!(lib.return_book(10, 1, date(20)), Ok(Money::ZERO));
+        assert_eq!(lib.find_member(10).unwrap().outstanding_fines(), Money::from_cents(100));
+    }
+}
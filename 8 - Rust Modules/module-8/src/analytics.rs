@@ -0,0 +1,149 @@
+//! Analytics module - read-only statistics and staff reports over a
+//! `Library`.
+//!
+//! Everything here borrows the library immutably and returns references
+//! into it, so computing a report never copies members or books.
+
+use std::cmp::Reverse;
+
+use chrono::NaiveDate;
+
+use crate::member::Member;
+use crate::Library;
+
+/// How many loans a member has completed.
+#[derive(Debug, Clone, Copy)]
+pub struct ReaderActivity<'a> {
+    pub member: &'a Member,
+    pub loans: usize,
+}
+
+impl Library {
+    /// Members without an email address or a phone number on file, in
+    /// registration order, so staff can complete their records.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use module_8::{Library, Member, MembershipTier};
+    ///
+    /// let mut lib = Library::new();
+    /// let mut alice = Member::new(1, "Alice", MembershipTier::Basic);
+    /// alice.set_email(Some("alice@example.com")).unwrap();
+    /// alice.set_phone(Some("555 0100")).unwrap();
+    /// lib.register_member(alice).unwrap();
+    /// lib.register_member(Member::new(2, "Bob", MembershipTier::Basic)).unwrap();
+    ///
+    /// let missing: Vec<&str> = lib.members_missing_contact().iter().map(|m| m.name.as_str()).collect();
+    /// assert_eq!(missing, ["Bob"]);
+    /// ```
+    pub fn members_missing_contact(&self) -> Vec<&Member> {
+        self.members()
+            .filter(|member| member.email().is_none() || member.phone().is_none())
+            .collect()
+    }
+
+    /// Ranks members by completed loans in their history, busiest first.
+    ///
+    /// Only loans returned on or after `since` count when it is given.
+    /// Members with no qualifying loans are left out; ties keep registration
+    /// order. At most `limit` readers are returned.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use chrono::NaiveDate;
+    /// use module_8::{Book, Genre, Library, Member, MembershipTier};
+    ///
+    /// let mut lib = Library::new();
+    /// lib.add_book(Book::new(1, "Dune", Genre::SciFi)).unwrap();
+    /// lib.register_member(Member::new(7, "Alice", MembershipTier::Basic)).unwrap();
+    ///
+    /// let day = NaiveDate::from_ymd_opt(2024, 3, 1).unwrap();
+    /// lib.checkout(7, 1, day).unwrap();
+    /// lib.return_book(7, 1, day).unwrap();
+    ///
+    /// let top = lib.most_active_readers(3, None);
+    /// assert_eq!(top[0].member.name, "Alice");
+    /// assert_eq!(top[0].loans, 1);
+    /// ```
+    pub fn most_active_readers(&self, limit: usize, since: Option<NaiveDate>) -> Vec<ReaderActivity<'_>> {
+        let mut readers: Vec<ReaderActivity> = self
+            .members()
+            .map(|member| ReaderActivity {
+                member,
+                loans: member
+                    .history()
+                    .iter()
+                    .filter(|record| since.is_none_or(|date| record.returned_on >= date))
+                    .count(),
+            })
+            .filter(|activity| activity.loans > 0)
+            .collect();
+
+        // Stable sort, so equal counts stay in registration order
+        readers.sort_by_key(|activity| Reverse(activity.loans));
+        readers.truncate(limit);
+        readers
+    }
+}
+
+// =============================================================================
+// TESTS
+// =============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Book, Genre, MembershipTier};
+
+    fn date(day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(2024, 3, day).unwrap()
+    }
+
+    #[test]
+    fn test_most_active_readers() {
+        let mut lib = Library::new();
+        lib.add_book(Book::new(1, "Dune", Genre::SciFi)).unwrap();
+        lib.add_book(Book::new(2, "Emma", Genre::Fiction)).unwrap();
+        for (id, name) in [(10, "Alice"), (11, "Bob"), (12, "Carol")] {
+            lib.register_member(Member::new(id, name, MembershipTier::Basic)).unwrap();
+        }
+
+        // Bob: three loans, Alice: one, Carol: none
+        for (member, book, day) in [(11, 1, 1), (10, 2, 2), (11, 1, 5), (11, 2, 9)] {
+            lib.checkout(member, book, date(day)).unwrap();
+            lib.return_book(member, book, date(day + 1)).unwrap();
+        }
+
+        let top = lib.most_active_readers(5, None);
+        let ranking: Vec<(&str, usize)> = top.iter().map(|r| (r.member.name.as_str(), r.loans)).collect();
+        assert_eq!(ranking, [("Bob", 3), ("Alice", 1)]);
+
+        assert_eq!(lib.most_active_readers(1, None).len(), 1);
+        let recent = lib.most_active_readers(5, Some(date(6)));
+        assert_eq!(recent.len(), 1);
+        assert_eq!(recent[0].loans, 2);
+    }
+
+    #[test]
+    fn test_members_missing_contact() {
+        let mut lib = Library::new();
+        for (id, name, email, phone) in [
+            (10, "Alice", Some("alice@example.com"), Some("555 0100")),
+            (11, "Bob", Some("bob@example.com"), None),
+            (12, "Carol", None, Some("+1 555 0102")),
+        ] {
+            let mut member = Member::new(id, name, MembershipTier::Basic);
+            member.set_email(email).unwrap();
+            member.set_phone(phone).unwrap();
+            lib.register_member(member).unwrap();
+        }
+        let ids = |lib: &Library| lib.members_missing_contact().iter().map(|m| m.id()).collect::<Vec<_>>();
+        assert_eq!(ids(&lib), [11, 12]);
+
+        lib.member_mut(11).unwrap().set_phone(Some("555 0101")).unwrap();
+        lib.member_mut(10).unwrap().set_email(None).unwrap();
+        assert_eq!(ids(&lib), [10, 12]);
+    }
+}
@@ -3,6 +3,13 @@
 //! This file is loaded because `lib.rs` contains `mod book;`.
 //! Rust automatically looks for `src/book.rs` or `src/book/mod.rs`.
 
+use std::fmt;
+use std::str::FromStr;
+
+use crate::item::{Format, ItemKind};
+use crate::location::Location;
+use crate::validation::{validate_id, validate_title, ValidationError};
+
 // =============================================================================
 // ENUM WITH PUBLIC VARIANTS
 // =============================================================================
@@ -12,13 +19,110 @@
 /// When an enum is marked `pub`, ALL its variants are automatically public.
 /// This is different from structs, where each field's visibility must be
 /// specified individually.
-#[derive(Debug, Clone, PartialEq)]
+///
+/// `Custom` covers genres the built-in list doesn't know about, such as
+/// "Poetry" or "Children". Parsing any unrecognized name produces one:
+///
+/// ```
+/// use module_8::Genre;
+///
+/// assert_eq!("sci-fi".parse::<Genre>(), Ok(Genre::SciFi));
+/// assert_eq!("Poetry".parse::<Genre>(), Ok(Genre::Custom(String::from("Poetry"))));
+/// assert_eq!(Genre::NonFiction.to_string(), "Non-Fiction");
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Genre {
     Fiction,
     NonFiction,
     Technical,
     Mystery,
     SciFi,
+    Custom(String),
+}
+
+/// Physical state of one copy, updated when it comes back from a loan.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Condition {
+    New,
+    Good,
+    Worn,
+    /// Needs repair before it should be lent again.
+    Damaged,
+    /// Not returned; the borrower is billed for a replacement.
+    Lost,
+}
+
+/// Error returned when parsing a [`Genre`] from an empty string.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ParseGenreError;
+
+impl fmt::Display for ParseGenreError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "genre name cannot be empty")
+    }
+}
+
+impl std::error::Error for ParseGenreError {}
+
+impl Genre {
+    /// The built-in genres, e.g. for building a pick list. `Custom` genres
+    /// aren't included.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use module_8::Genre;
+    ///
+    /// let names: Vec<String> = Genre::all().map(|g| g.to_string()).collect();
+    /// assert_eq!(names, ["Fiction", "Non-Fiction", "Technical", "Mystery", "Science Fiction"]);
+    /// ```
+    pub fn all() -> impl Iterator<Item = Genre> {
+        [
+            Genre::Fiction,
+            Genre::NonFiction,
+            Genre::Technical,
+            Genre::Mystery,
+            Genre::SciFi,
+        ]
+        .into_iter()
+    }
+}
+
+impl FromStr for Genre {
+    type Err = ParseGenreError;
+
+    /// Parses a genre name case-insensitively, accepting common spellings
+    /// ("sci-fi", "Science Fiction", "nonfiction"). Anything else becomes
+    /// `Genre::Custom` with the trimmed input.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let name = s.trim();
+        if name.is_empty() {
+            return Err(ParseGenreError);
+        }
+        let key: String = name
+            .chars()
+            .filter(|c| c.is_alphanumeric())
+            .flat_map(char::to_lowercase)
+            .collect();
+        Ok(match key.as_str() {
+            "fiction" => Genre::Fiction,
+            "nonfiction" => Genre::NonFiction,
+            "technical" => Genre::Technical,
+            "mystery" => Genre::Mystery,
+            "scifi" | "sciencefiction" => Genre::SciFi,
+            _ => Genre::Custom(String::from(name)),
+        })
+    }
+}
+
+impl fmt::Display for Genre {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        // Reuse the display names from the utils module so there's one
+        // source of truth for how genres are spelled.
+        f.write_str(crate::utils::format_genre(self))
+    }
 }
 
 // =============================================================================
@@ -32,10 +136,18 @@ pub enum Genre {
 /// - `id`: private - can only be set via `new()`, prevents external modification
 /// - `title`: public - can be read and modified externally
 /// - `genre`: public - can be read and modified externally
+/// - `author` / `isbn` / `year` / `publisher` / `pages` / `language` /
+///   `summary`: public, optional catalog metadata (see [`BookBuilder`])
+/// - `condition`: public, the copy's physical state (starts as `New`)
+/// - `kind`: public, a book unless it's a periodical or media (see `item`)
+/// - `format`: public, physical unless it's a digital copy (see `item`)
+/// - `location`: public, the shelf the copy is kept on (see `location`)
+/// - `tags`: private - normalized through `add_tag()`
 /// - `is_available`: private - controlled via methods to maintain invariants
 ///
 /// This demonstrates how Rust lets you control access at the field level.
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Book {
     // Private field: only accessible within this module
     id: u64,
@@ -43,12 +155,39 @@ pub struct Book {
     // Public fields: accessible from anywhere the struct is visible
     pub title: String,
     pub genre: Genre,
+    pub author: Option<String>,
+    pub isbn: Option<String>,
+    /// Year of publication.
+    pub year: Option<u16>,
+    pub publisher: Option<String>,
+    /// Number of pages.
+    pub pages: Option<u32>,
+    /// Language of the text, e.g. "English".
+    pub language: Option<String>,
+    /// A short description for catalog listings.
+    pub summary: Option<String>,
+    pub condition: Condition,
+    pub kind: ItemKind,
+    pub format: Format,
+    /// Where the copy is kept; `None` until it's given a place.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub location: Option<Location>,
+
+    // Private field: lowercase, trimmed and de-duplicated by `add_tag()`
+    tags: Vec<String>,
 
     // Private field: we control availability through methods
     is_available: bool,
 
     // Private field: internal tracking
     times_borrowed: u32,
+
+    // Private fields: sum and number of member ratings, kept in step with
+    // the library's reviews (see `reviews`)
+    #[cfg_attr(feature = "serde", serde(default))]
+    rating_total: u32,
+    #[cfg_attr(feature = "serde", serde(default))]
+    rating_count: u32,
 }
 
 impl Book {
@@ -65,13 +204,78 @@ impl Book {
     /// let book = Book::new(1, "Rust Programming", Genre::Technical);
     /// assert!(book.is_available());
     /// ```
+    ///
+    /// The ID and title aren't checked; use [`try_new`](Book::try_new) for
+    /// input that hasn't been validated yet.
     pub fn new(id: u64, title: &str, genre: Genre) -> Self {
         Book {
             id,
             title: String::from(title),
             genre,
+            author: None,
+            isbn: None,
+            year: None,
+            publisher: None,
+            pages: None,
+            language: None,
+            summary: None,
+            condition: Condition::New,
+            kind: ItemKind::Book,
+            format: Format::Physical,
+            location: None,
+            tags: Vec::new(),
             is_available: true,
             times_borrowed: 0,
+            rating_total: 0,
+            rating_count: 0,
+        }
+    }
+
+    /// Creates a new book, or says why `id` or `title` can't be used (see
+    /// [`validation`](crate::validation)).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use module_8::{Book, Genre};
+    /// let err = Book::try_new(1, "", Genre::Fiction).unwrap_err();
+    /// assert_eq!(err.to_string(), "title must not be empty");
+    /// ```
+    pub fn try_new(id: u64, title: &str, genre: Genre) -> Result<Self, ValidationError> {
+        validate_id(id)?;
+        validate_title(title)?;
+        Ok(Book::new(id, title, genre))
+    }
+
+    /// Starts a [`BookBuilder`] for a book with more catalog metadata than
+    /// [`new`](Book::new) takes.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use module_8::{Book, Genre};
+    ///
+    /// let book = Book::builder(1, "Programming Rust")
+    ///     .genre(Genre::Technical)
+    ///     .year(2021)
+    ///     .publisher("O'Reilly")
+    ///     .pages(735)
+    ///     .build();
+    /// assert_eq!(book.publisher.as_deref(), Some("O'Reilly"));
+    /// assert_eq!((book.year, book.language), (Some(2021), None));
+    /// ```
+    pub fn builder(id: u64, title: &str) -> BookBuilder {
+        BookBuilder {
+            id,
+            title: String::from(title),
+            genre: Genre::Fiction,
+            author: None,
+            isbn: None,
+            year: None,
+            publisher: None,
+            pages: None,
+            language: None,
+            summary: None,
         }
     }
 
@@ -80,6 +284,62 @@ impl Book {
         self.id
     }
 
+    /// Adds a free-form tag such as "beginner" or "award-winner".
+    ///
+    /// Tags are case-insensitive: they are stored trimmed and lowercased.
+    /// Returns `false` if the tag is blank or the book already has it.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use module_8::{Book, Genre};
+    /// let mut book = Book::new(1, "The Rust Book", Genre::Technical);
+    /// assert!(book.add_tag("Beginner"));
+    /// assert!(!book.add_tag(" beginner "));
+    /// assert!(book.has_tag("BEGINNER"));
+    /// assert_eq!(book.tags(), ["beginner"]);
+    /// ```
+    pub fn add_tag(&mut self, tag: &str) -> bool {
+        let tag = normalize_tag(tag);
+        if tag.is_empty() || self.tags.contains(&tag) {
+            return false;
+        }
+        self.tags.push(tag);
+        true
+    }
+
+    /// The book's tags, in the order they were added.
+    pub fn tags(&self) -> &[String] {
+        &self.tags
+    }
+
+    /// Checks for a tag, ignoring case.
+    pub fn has_tag(&self, tag: &str) -> bool {
+        let tag = normalize_tag(tag);
+        self.tags.contains(&tag)
+    }
+
+    /// Gives the book a new ID, e.g. when merging libraries.
+    pub(crate) fn set_id(&mut self, id: u64) {
+        self.id = id;
+    }
+
+    /// Counts a loan of a digital copy, which stays available throughout.
+    pub(crate) fn lend_digital_copy(&mut self) {
+        self.times_borrowed += 1;
+    }
+
+    /// Marks the book as out on loan without counting another borrow, for
+    /// loans that already happened (restored, merged or undone).
+    pub(crate) fn mark_on_loan(&mut self) {
+        self.is_available = false;
+    }
+
+    /// Restores the borrow count of a book loaded from a saved library.
+    pub(crate) fn set_times_borrowed(&mut self, count: u32) {
+        self.times_borrowed = count;
+    }
+
     /// Checks if the book is available for borrowing.
     pub fn is_available(&self) -> bool {
         self.is_available
@@ -90,6 +350,26 @@ impl Book {
         self.times_borrowed
     }
 
+    /// The mean of the 1-5 ratings members have given the book, or `None`
+    /// if nobody has rated it (see [`Library::rate`](crate::Library::rate)).
+    pub fn average_rating(&self) -> Option<f64> {
+        (self.rating_count > 0).then(|| f64::from(self.rating_total) / f64::from(self.rating_count))
+    }
+
+    /// How many members have rated the book.
+    pub fn rating_count(&self) -> u32 {
+        self.rating_count
+    }
+
+    /// Counts a member's rating, replacing their `previous` one if any.
+    pub(crate) fn add_rating(&mut self, rating: u8, previous: Option<u8>) {
+        match previous {
+            Some(previous) => self.rating_total -= u32::from(previous),
+            None => self.rating_count += 1,
+        }
+        self.rating_total += u32::from(rating);
+    }
+
     /// Marks the book as borrowed.
     ///
     /// Returns `true` if successful, `false` if already borrowed.
@@ -109,10 +389,101 @@ impl Book {
     }
 }
 
+// =============================================================================
+// BUILDER
+// =============================================================================
+
+/// Builds a [`Book`] one piece of metadata at a time; start one with
+/// [`Book::builder`]. The genre defaults to `Fiction`, everything else to
+/// unknown.
+#[derive(Debug, Clone)]
+pub struct BookBuilder {
+    id: u64,
+    title: String,
+    genre: Genre,
+    author: Option<String>,
+    isbn: Option<String>,
+    year: Option<u16>,
+    publisher: Option<String>,
+    pages: Option<u32>,
+    language: Option<String>,
+    summary: Option<String>,
+}
+
+impl BookBuilder {
+    pub fn genre(mut self, genre: Genre) -> Self {
+        self.genre = genre;
+        self
+    }
+
+    pub fn author(mut self, author: &str) -> Self {
+        self.author = Some(String::from(author));
+        self
+    }
+
+    pub fn isbn(mut self, isbn: &str) -> Self {
+        self.isbn = Some(String::from(isbn));
+        self
+    }
+
+    /// Sets the year of publication.
+    pub fn year(mut self, year: u16) -> Self {
+        self.year = Some(year);
+        self
+    }
+
+    pub fn publisher(mut self, publisher: &str) -> Self {
+        self.publisher = Some(String::from(publisher));
+        self
+    }
+
+    pub fn pages(mut self, pages: u32) -> Self {
+        self.pages = Some(pages);
+        self
+    }
+
+    pub fn language(mut self, language: &str) -> Self {
+        self.language = Some(String::from(language));
+        self
+    }
+
+    pub fn summary(mut self, summary: &str) -> Self {
+        self.summary = Some(String::from(summary));
+        self
+    }
+
+    /// Builds the book. Like [`Book::new`], the ID and title aren't
+    /// checked; use [`try_build`](BookBuilder::try_build) for input that
+    /// hasn't been validated yet.
+    pub fn build(self) -> Book {
+        let mut book = Book::new(self.id, &self.title, self.genre);
+        book.author = self.author;
+        book.isbn = self.isbn;
+        book.year = self.year;
+        book.publisher = self.publisher;
+        book.pages = self.pages;
+        book.language = self.language;
+        book.summary = self.summary;
+        book
+    }
+
+    /// Builds the book, or says why its ID or title can't be used.
+    pub fn try_build(self) -> Result<Book, ValidationError> {
+        validate_id(self.id)?;
+        validate_title(&self.title)?;
+        Ok(self.build())
+    }
+}
+
 // =============================================================================
 // MODULE-PRIVATE HELPER (not visible outside this module)
 // =============================================================================
 
+/// Canonical form of a tag, so "Beginner" and " beginner" match.
+pub(crate) fn normalize_tag(tag: &str) -> String {
+    tag.trim().to_lowercase()
+}
+
 /// Internal helper function - not marked `pub`, so it's private to this module.
 /// Even though `book.rs` is a module file, items without `pub` are still private.
 #[allow(dead_code)]
@@ -152,10 +523,69 @@ mod tests {
         assert_eq!(book.times_borrowed(), 1);
     }
 
+    #[test]
+    fn test_builder_sets_metadata() {
+        let book = Book::builder(7, "Zero to Production")
+            .genre(Genre::Technical)
+            .author("Luca Palmieri")
+            .year(2022)
+            .publisher("Self-published")
+            .pages(450)
+            .language("English")
+            .summary("Backend development in Rust")
+            .build();
+        assert_eq!((book.id(), book.genre.clone()), (7, Genre::Technical));
+        assert_eq!(book.author.as_deref(), Some("Luca Palmieri"));
+        assert_eq!((book.year, book.pages), (Some(2022), Some(450)));
+        assert_eq!(book.language.as_deref(), Some("English"));
+        assert!(book.is_available());
+
+        let plain = Book::builder(8, "Emma").build();
+        assert_eq!(plain.genre, Genre::Fiction);
+        assert_eq!((plain.publisher, plain.summary), (None, None));
+        assert!(Book::builder(0, "Emma").try_build().is_err());
+    }
+
+    #[test]
+    fn test_tags_are_case_insensitive() {
+        let mut book = Book::new(1, "Test Book", Genre::Fiction);
+        assert!(book.add_tag("Classic"));
+        assert!(book.add_tag("short"));
+        assert!(!book.add_tag("CLASSIC"));
+        assert!(!book.add_tag("   "));
+        assert_eq!(book.tags(), ["classic", "short"]);
+        assert!(book.has_tag(" Short"));
+        assert!(!book.has_tag("long"));
+    }
+
+    #[test]
+    fn test_genre_parse_and_display_round_trip() {
+        for genre in Genre::all() {
+            assert_eq!(genre.to_string().parse::<Genre>(), Ok(genre));
+        }
+        let poetry = Genre::Custom(String::from("Poetry"));
+        assert_eq!(poetry.to_string(), "Poetry");
+        assert_eq!("  Poetry ".parse::<Genre>(), Ok(poetry));
+    }
+
+    #[test]
+    fn test_genre_parse_rejects_empty() {
+        assert_eq!("   ".parse::<Genre>(), Err(ParseGenreError));
+        assert_eq!("MYSTERY".parse::<Genre>(), Ok(Genre::Mystery));
+    }
+
     #[test]
     fn test_private_function_accessible_in_tests() {
         // We can test private functions from within the same module
         let isbn = generate_isbn(42);
         assert_eq!(isbn, "ISBN-0000000042");
     }
+
+    #[test]
+    fn test_new_book_condition() {
+        let mut book = Book::new(1, "Test", Genre::Fiction);
+        assert_eq!(book.condition, Condition::New);
+        book.condition = Condition::Worn;
+        assert!(book.condition < Condition::Damaged);
+    }
 }
@@ -0,0 +1,233 @@
+//! Branches module - several libraries run as one system.
+//!
+//! Each branch of a [`LibrarySystem`] is an ordinary [`Library`] with its
+//! own shelves and members, identified by its configured
+//! [`name`](crate::LibraryConfig::name). Day-to-day work goes through
+//! [`branch_mut`](LibrarySystem::branch_mut); the system adds what spans
+//! branches: moving copies between them, comparing their figures and
+//! searching them all at once.
+//!
+//! A library with a single branch doesn't need any of this and can keep
+//! using `Library` on its own.
+
+use chrono::NaiveDate;
+
+use crate::book::Book;
+use crate::error::LibraryError;
+use crate::index::normalize_title;
+use crate::money::Money;
+use crate::Library;
+
+/// One branch's figures, as returned by [`LibrarySystem::stats`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct BranchStats<'a> {
+    pub branch: &'a str,
+    /// Books on the shelf.
+    pub on_shelf: usize,
+    /// Physical copies out on loan.
+    pub on_loan: usize,
+    /// Loans of digital copies, which stay on the shelf.
+    pub digital_loans: usize,
+    /// Loans past their due date.
+    pub overdue: usize,
+    pub members: usize,
+    /// Unpaid fines across the branch's members.
+    pub fines_owed: Money,
+}
+
+/// Branches sharing one catalog search and moving copies between them.
+///
+/// # Examples
+///
+/// ```
+/// use module_8::{Book, Genre, Library, LibraryConfig, LibrarySystem};
+///
+/// let branch = |name: &str| Library::with_config(LibraryConfig {
+///     name: name.to_string(),
+///     ..LibraryConfig::default()
+/// });
+/// let mut system = LibrarySystem::new();
+/// system.add_branch(branch("North")).unwrap();
+/// system.add_branch(branch("South")).unwrap();
+/// system.branch_mut("North").unwrap().add_book(Book::new(1, "Dune", Genre::SciFi)).unwrap();
+///
+/// assert_eq!(system.transfer_book(1, "North", "South"), Ok(1));
+/// let hits = system.search("dune");
+/// assert_eq!((hits[0].0, hits[0].1.id()), ("South", 1));
+/// ```
+#[derive(Default)]
+pub struct LibrarySystem {
+    branches: Vec<Library>,
+}
+
+impl LibrarySystem {
+    pub fn new() -> Self {
+        LibrarySystem::default()
+    }
+
+    /// Adds a branch under its configured name, which must be unused.
+    pub fn add_branch(&mut self, library: Library) -> Result<(), LibraryError> {
+        if self.branch(library.name()).is_some() {
+            return Err(LibraryError::DuplicateBranch(library.name().to_string()));
+        }
+        self.branches.push(library);
+        Ok(())
+    }
+
+    pub fn branch(&self, name: &str) -> Option<&Library> {
+        self.branches.iter().find(|b| b.name() == name)
+    }
+
+    pub fn branch_mut(&mut self, name: &str) -> Result<&mut Library, LibraryError> {
+        self.branches
+            .iter_mut()
+            .find(|b| b.name() == name)
+            .ok_or_else(|| LibraryError::BranchNotFound(name.to_string()))
+    }
+
+    /// Iterates over the branches, in the order they were added.
+    pub fn branches(&self) -> impl Iterator<Item = &Library> {
+        self.branches.iter()
+    }
+
+    /// Moves a copy from the shelf of branch `from` to branch `to`,
+    /// returning its ID there.
+    ///
+    /// The copy keeps its ID unless `to` already uses it, in which case it
+    /// gets `to`'s next free ID. Holds on it at `from` are dropped, and it
+    /// arrives without a [location](crate::location), ready to be given a
+    /// shelf at `to`. Fails
+    /// with `BookUnavailable` while the copy is on loan, and with
+    /// `CapacityReached` if `to` is full.
+    pub fn transfer_book(&mut self, book_id: u64, from: &str, to: &str) -> Result<u64, LibraryError> {
+        let source = self.position(from)?;
+        let target = self.position(to)?;
+        if source == target {
+            return match self.branches[source].find_book(book_id) {
+                Some(_) => Ok(book_id),
+                None => Err(LibraryError::BookNotFound(book_id)),
+            };
+        }
+
+        self.branches[target].check_capacity(1, 0)?;
+        let mut book = self.branches[source].remove_book(book_id)?;
+        book.location = None;
+        let target = &mut self.branches[target];
+        if target.get_book(book.id()).is_some() {
+            book.set_id(target.generate_id());
+        }
+        let new_id = book.id();
+        target.insert_book(book);
+        Ok(new_id)
+    }
+
+    /// Each branch's figures as of `today`, in the order branches were added.
+    pub fn stats(&self, today: NaiveDate) -> Vec<BranchStats<'_>> {
+        self.branches
+            .iter()
+            .map(|b| {
+                let digital_loans = b.loans().iter().filter(|l| b.is_digital_loan(l)).count();
+                BranchStats {
+                    branch: b.name(),
+                    on_shelf: b.book_count(),
+                    on_loan: b.loans().len() - digital_loans,
+                    digital_loans,
+                    overdue: b.loans().iter().filter(|l| l.days_overdue(today) > 0).count(),
+                    members: b.member_count(),
+                    fines_owed: Money::saturating_sum(b.members().map(|m| m.outstanding_fines())),
+                }
+            })
+            .collect()
+    }
+
+    /// Every copy, in any branch, whose title contains `query` (ignoring
+    /// case and punctuation), with the branch holding it. Copies on loan
+    /// are included; hits are grouped by branch.
+    pub fn search(&self, query: &str) -> Vec<(&str, &Book)> {
+        let query = normalize_title(query);
+        self.branches
+            .iter()
+            .flat_map(|b| b.catalog().map(move |book| (b.name(), book)))
+            .filter(|(_, book)| normalize_title(&book.title).contains(&query))
+            .collect()
+    }
+
+    fn position(&self, name: &str) -> Result<usize, LibraryError> {
+        self.branches
+            .iter()
+            .position(|b| b.name() == name)
+            .ok_or_else(|| LibraryError::BranchNotFound(name.to_string()))
+    }
+}
+
+// =============================================================================
+// TESTS
+// =============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Genre, LibraryConfig, Member, MembershipTier};
+
+    fn date(day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(2024, 3, day).unwrap()
+    }
+
+    fn branch(name: &str) -> Library {
+        Library::with_config(LibraryConfig { name: name.to_string(), ..LibraryConfig::default() })
+    }
+
+    fn system() -> LibrarySystem {
+        let mut north = branch("North");
+        north.add_book(Book::new(1, "The Rust Book", Genre::Technical)).unwrap();
+        north.add_book(Book::new(2, "Dune", Genre::SciFi)).unwrap();
+        north.register_member(Member::new(10, "Alice", MembershipTier::Basic)).unwrap();
+        let mut south = branch("South");
+        south.add_book(Book::new(1, "Rust in Action", Genre::Technical)).unwrap();
+
+        let mut system = LibrarySystem::new();
+        system.add_branch(north).unwrap();
+        system.add_branch(south).unwrap();
+        system
+    }
+
+    #[test]
+    fn test_transfer_renumbers_on_collision() {
+        let mut system = system();
+        assert_eq!(system.transfer_book(1, "North", "South"), Ok(2));
+        assert!(system.branch("North").unwrap().get_book(1).is_none());
+        assert_eq!(system.branch("South").unwrap().find_book(2).unwrap().title, "The Rust Book");
+
+        assert_eq!(system.transfer_book(1, "North", "South"), Err(LibraryError::BookNotFound(1)));
+        assert_eq!(
+            system.transfer_book(2, "North", "East"),
+            Err(LibraryError::BranchNotFound(String::from("East")))
+        );
+        assert_eq!(
+            system.add_branch(branch("North")),
+            Err(LibraryError::DuplicateBranch(String::from("North")))
+        );
+    }
+
+    #[test]
+    fn test_books_on_loan_stay_put() {
+        let mut system = system();
+        system.branch_mut("North").unwrap().checkout(10, 2, date(1)).unwrap();
+        assert_eq!(system.transfer_book(2, "North", "South"), Err(LibraryError::BookUnavailable(2)));
+
+        let stats = system.stats(date(20));
+        assert_eq!(
+            stats[0],
+            BranchStats { branch: "North", on_shelf: 1, on_loan: 1, digital_loans: 0, overdue: 1, members: 1, fines_owed: Money::ZERO }
+        );
+        assert_eq!((stats[1].branch, stats[1].on_shelf, stats[1].on_loan), ("South", 1, 0));
+    }
+
+    #[test]
+    fn test_search_across_branches() {
+        let mut system = system();
+        system.branch_mut("North").unwrap().checkout(10, 1, date(1)).unwrap();
+        let hits: Vec<(&str, u64)> = system.search("rust").iter().map(|(b, book)| (*b, book.id())).collect();
+        assert_eq!(hits, [("North", 1), ("South", 1)]);
+    }
+}
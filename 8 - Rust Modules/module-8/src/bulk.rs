@@ -0,0 +1,258 @@
+//! Bulk module - adding many books or members in one call.
+//!
+//! Importing a large catalog with one `add_book` per row grows the
+//! library's maps a little at a time, rehashing them again and again.
+//! [`Library::add_books`] and [`Library::register_members`] check the whole
+//! batch first (IDs already in use or repeated within the batch, likely
+//! duplicates of a catalogued book or an earlier one in the batch, and room
+//! under the capacity limits), reserve space for everything that passed and
+//! then insert it. Items that fail are reported by their position in the
+//! batch; the rest are still added.
+//!
+//! ```
+//! use module_8::{Book, Genre, Library, LibraryError};
+//!
+//! let mut lib = Library::new();
+//! lib.add_book(Book::new(1, "Dune", Genre::SciFi)).unwrap();
+//!
+//! let report = lib.add_books(vec![
+//!     Book::new(2, "Emma", Genre::Fiction),
+//!     Book::new(1, "Dune Messiah", Genre::SciFi),
+//!     Book::new(3, "Ulysses", Genre::Fiction),
+//!     Book::new(3, "Beloved", Genre::Fiction),
+//! ]);
+//! assert_eq!(report.added, [2, 3]);
+//! assert_eq!(report.failed[0].position, 1);
+//! assert_eq!(report.failed[1].error, LibraryError::DuplicateBook(3));
+//! assert_eq!(lib.book_count(), 3);
+//! ```
+
+use std::collections::{HashMap, HashSet};
+
+use crate::book::Book;
+use crate::config::Capacity;
+use crate::dedup::duplicate_key;
+use crate::error::LibraryError;
+use crate::member::Member;
+use crate::Library;
+
+// =============================================================================
+// REPORTS
+// =============================================================================
+
+/// An item of a batch that wasn't added.
+#[derive(Debug, Clone, PartialEq)]
+pub struct BatchError {
+    /// Where the item was in the batch, counting from 0.
+    pub position: usize,
+    pub error: LibraryError,
+}
+
+/// What a bulk insert added and what it refused.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct BatchReport {
+    /// IDs of the items added, in batch order.
+    pub added: Vec<u64>,
+    /// Refused items, in batch order.
+    pub failed: Vec<BatchError>,
+}
+
+impl BatchReport {
+    /// Returns `true` if every item in the batch was added.
+    pub fn is_complete(&self) -> bool {
+        self.failed.is_empty()
+    }
+}
+
+/// Room left under a capacity limit while a batch is checked.
+struct Room {
+    capacity: Capacity,
+    limit: Option<usize>,
+    left: Option<usize>,
+}
+
+impl Room {
+    fn new(library: &Library, capacity: Capacity) -> Room {
+        Room {
+            capacity,
+            limit: library.config().limit(capacity),
+            left: library.remaining_capacity(capacity),
+        }
+    }
+
+    fn check(&self, needed: usize) -> Result<(), LibraryError> {
+        match (self.left, self.limit) {
+            (Some(left), Some(limit)) if needed > left => Err(LibraryError::CapacityReached {
+                capacity: self.capacity,
+                limit,
+            }),
+            _ => Ok(()),
+        }
+    }
+
+    fn take(&mut self, used: usize) {
+        if let Some(left) = &mut self.left {
+            *left -= used;
+        }
+    }
+}
+
+// =============================================================================
+// LIBRARY INTEGRATION
+// =============================================================================
+
+impl Library {
+    /// Adds every book that [`add_book`](Library::add_book) would
+    /// accept, checking the whole batch before inserting any of it. Once the
+    /// catalog is full, the remaining books fail with
+    /// [`LibraryError::CapacityReached`].
+    pub fn add_books(&mut self, books: impl IntoIterator<Item = Book>) -> BatchReport {
+        let mut report = BatchReport::default();
+        let mut room = Room::new(self, Capacity::Books);
+        let mut batch_ids = HashSet::new();
+        let mut batch_keys = HashMap::new();
+        let mut accepted = Vec::new();
+        for (position, book) in books.into_iter().enumerate() {
+            let key = duplicate_key(&book);
+            let existing = self
+                .find_duplicate(&book)
+                .map(Book::id)
+                .or_else(|| key.as_ref().and_then(|key| batch_keys.get(key).copied()));
+            let checked = if self.index.contains(book.id()) || batch_ids.contains(&book.id()) {
+                Err(LibraryError::DuplicateBook(book.id()))
+            } else if let Some(existing) = existing {
+                Err(LibraryError::LikelyDuplicate { book_id: book.id(), existing })
+            } else {
+                room.check(1)
+            };
+            match checked {
+                Ok(()) => {
+                    room.take(1);
+                    batch_ids.insert(book.id());
+                    if let Some(key) = key {
+                        batch_keys.insert(key, book.id());
+                    }
+                    accepted.push(book);
+                }
+                Err(error) => report.failed.push(BatchError { position, error }),
+            }
+        }
+
+        self.books.reserve(accepted.len());
+        self.index.reserve(accepted.len());
+        for book in accepted {
+            report.added.push(book.id());
+            self.insert_book(book);
+        }
+        report
+    }
+
+    /// Registers every member that
+    /// [`register_member`](Library::register_member) would accept,
+    /// checking the whole batch before registering any of it.
+    pub fn register_members(&mut self, members: impl IntoIterator<Item = Member>) -> BatchReport {
+        let mut report = BatchReport::default();
+        let mut member_room = Room::new(self, Capacity::Members);
+        let mut batch_members = HashSet::new();
+        let mut accepted = Vec::new();
+        for (position, member) in members.into_iter().enumerate() {
+            let checked = if self.members.contains_key(&member.id()) || batch_members.contains(&member.id()) {
+                Err(LibraryError::DuplicateMember(member.id()))
+            } else {
+                member_room.check(1)
+            };
+            match checked {
+                Ok(()) => {
+                    member_room.take(1);
+                    batch_members.insert(member.id());
+                    accepted.push(member);
+                }
+                Err(error) => report.failed.push(BatchError { position, error }),
+            }
+        }
+
+        self.members.reserve(accepted.len());
+        self.member_order.reserve(accepted.len());
+        for member in accepted {
+            report.added.push(member.id());
+            self.insert_member(member);
+        }
+        report
+    }
+}
+
+// =============================================================================
+// TESTS
+// =============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Genre, LibraryConfig, MembershipTier};
+
+    #[test]
+    fn test_add_books_stops_at_capacity() {
+        let mut lib = Library::with_config(LibraryConfig { max_books: Some(3), ..LibraryConfig::default() });
+        lib.add_book(Book::new(1, "Dune", Genre::SciFi)).unwrap();
+        let batch = (2..=5).map(|id| Book::new(id, &format!("Volume {}", id), Genre::NonFiction));
+        let report = lib.add_books(batch);
+
+        assert_eq!(report.added, [2, 3]);
+        let full = LibraryError::CapacityReached { capacity: Capacity::Books, limit: 3 };
+        assert_eq!(report.failed, [BatchError { position: 2, error: full.clone() }, BatchError { position: 3, error: full }]);
+        assert!(!report.is_complete());
+        assert_eq!(lib.search_titles("volume").len(), 2);
+        assert_eq!(lib.check_invariants(), Ok(()));
+        assert!(lib.add_books(Vec::new()).is_complete());
+    }
+
+    #[test]
+    fn test_add_books_refuses_likely_duplicates() {
+        let mut lib = Library::new();
+        lib.add_book(Book::builder(1, "Dune").isbn("0441172717").build()).unwrap();
+        let report = lib.add_books(vec![
+            Book::builder(2, "dune").isbn("0-441-17271-7").build(),
+            Book::builder(3, "Emma").isbn("0141439580").build(),
+            Book::builder(4, "EMMA!").isbn("0141439580").build(),
+            Book::new(5, "Emma", Genre::Fiction),
+        ]);
+
+        assert_eq!(report.added, [3, 5]);
+        let errors: Vec<LibraryError> = report.failed.into_iter().map(|f| f.error).collect();
+        assert_eq!(
+            errors,
+            [
+                LibraryError::LikelyDuplicate { book_id: 2, existing: 1 },
+                LibraryError::LikelyDuplicate { book_id: 4, existing: 3 },
+            ]
+        );
+        assert_eq!(lib.book_count(), 3);
+    }
+
+    #[test]
+    fn test_register_members_refuses_taken_ids_and_stops_at_capacity() {
+        let mut lib = Library::with_config(LibraryConfig { max_members: Some(3), ..LibraryConfig::default() });
+        lib.register_member(Member::new(10, "Alice", MembershipTier::Basic)).unwrap();
+
+        let report = lib.register_members(vec![
+            Member::new(10, "Bob", MembershipTier::Basic),
+            Member::new(11, "Carol", MembershipTier::Basic),
+            Member::new(11, "Dave", MembershipTier::Gold),
+            Member::new(12, "Erin", MembershipTier::Basic),
+            Member::new(13, "Frank", MembershipTier::Basic),
+        ]);
+
+        assert_eq!(report.added, [11, 12]);
+        let errors: Vec<(usize, LibraryError)> = report.failed.into_iter().map(|f| (f.position, f.error)).collect();
+        assert_eq!(
+            errors,
+            [
+                (0, LibraryError::DuplicateMember(10)),
+                (2, LibraryError::DuplicateMember(11)),
+                (4, LibraryError::CapacityReached { capacity: Capacity::Members, limit: 3 }),
+            ]
+        );
+        assert_eq!(lib.find_member(11).unwrap().name, "Carol");
+        assert_eq!(lib.check_invariants(), Ok(()));
+    }
+}
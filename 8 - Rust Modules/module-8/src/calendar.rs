@@ -0,0 +1,173 @@
+//! Calendar module - the days the library is open.
+//!
+//! Late fees only run on days a member could have returned the book. A
+//! [`Calendar`] lists the weekdays the library is closed every week and its
+//! holidays; [`FeePolicy::late_fee_between`](crate::FeePolicy::late_fee_between)
+//! counts the open days between the due date and the return, and `Library`
+//! uses it for every late fee it charges. The default calendar is open
+//! every day, which charges exactly as before.
+//!
+//! ```
+//! use chrono::{NaiveDate, Weekday};
+//! use module_8::{Book, Calendar, Genre, Library, Member, MembershipTier};
+//!
+//! let day = |d| NaiveDate::from_ymd_opt(2024, 3, d).unwrap();
+//! let mut lib = Library::new();
+//! lib.set_calendar(Calendar::always_open().closed_on(Weekday::Sun).with_holiday(day(18)));
+//! lib.add_book(Book::new(1, "Dune", Genre::SciFi)).unwrap();
+//! lib.register_member(Member::new(10, "Alice", MembershipTier::Basic)).unwrap();
+//! lib.checkout(10, 1, day(1)).unwrap(); // due Friday the 15th
+//!
+//! // Sat 16 and Tue 19 count; Sun 17 and the holiday on the 18th don't
+//! assert_eq!(lib.return_book(10, 1, day(19)).unwrap().to_string(), "$0.50");
+//! ```
+
+use std::collections::BTreeSet;
+
+use chrono::{Datelike, NaiveDate, Weekday};
+
+use crate::circulation::Loan;
+use crate::member::Member;
+use crate::money::Money;
+use crate::Library;
+
+// =============================================================================
+// CALENDAR
+// =============================================================================
+
+/// Weekly closing days and holidays.
+#[derive(Debug, Clone, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Calendar {
+    closed_weekdays: Vec<Weekday>,
+    holidays: BTreeSet<NaiveDate>,
+}
+
+impl Calendar {
+    /// A calendar with no closed days.
+    pub fn always_open() -> Self {
+        Calendar::default()
+    }
+
+    /// Closes the library on `weekday` every week.
+    pub fn closed_on(mut self, weekday: Weekday) -> Self {
+        if !self.closed_weekdays.contains(&weekday) {
+            self.closed_weekdays.push(weekday);
+        }
+        self
+    }
+
+    /// Closes the library on `date`.
+    pub fn with_holiday(mut self, date: NaiveDate) -> Self {
+        self.holidays.insert(date);
+        self
+    }
+
+    pub fn is_open(&self, date: NaiveDate) -> bool {
+        !self.closed_weekdays.contains(&date.weekday()) && !self.holidays.contains(&date)
+    }
+
+    /// Open days after `from`, up to and including `to` (0 if `to` isn't
+    /// after `from`). For an always-open calendar this is
+    /// [`Loan::days_overdue`] when `from` is the due date.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use chrono::{NaiveDate, Weekday};
+    /// use module_8::Calendar;
+    ///
+    /// let day = |d| NaiveDate::from_ymd_opt(2024, 3, d).unwrap();
+    /// let weekdays = Calendar::always_open().closed_on(Weekday::Sat).closed_on(Weekday::Sun);
+    /// assert_eq!(weekdays.open_days_between(day(15), day(22)), 5);
+    /// assert_eq!(weekdays.open_days_between(day(22), day(15)), 0);
+    /// ```
+    pub fn open_days_between(&self, from: NaiveDate, to: NaiveDate) -> u32 {
+        if self.closed_weekdays.is_empty() && self.holidays.is_empty() {
+            return (to - from).num_days().max(0) as u32;
+        }
+        from.iter_days().skip(1).take_while(|day| *day <= to).filter(|day| self.is_open(*day)).count() as u32
+    }
+}
+
+// =============================================================================
+// LIBRARY INTEGRATION
+// =============================================================================
+
+impl Library {
+    /// The calendar late fees are counted on.
+    pub fn calendar(&self) -> &Calendar {
+        &self.calendar
+    }
+
+    /// Replaces the calendar; fees charged from now on skip its closed days.
+    pub fn set_calendar(&mut self, calendar: Calendar) {
+        self.calendar = calendar;
+    }
+
+    /// The fee policy's late fee for `loan` as of `today`, before the
+    /// member's tier discount.
+    pub(crate) fn base_late_fee(&self, loan: &Loan, today: NaiveDate) -> Money {
+        self.fee_policy.late_fee_between(loan.due_on, today, &self.calendar)
+    }
+
+    /// What `member` owes in late fees for `loan` as of `today`: the base
+    /// fee less their tier discount, up to their tier's fee cap.
+    pub(crate) fn member_late_fee(&self, member: &Member, loan: &Loan, today: NaiveDate) -> Money {
+        let fee = member.effective_fee(self.base_late_fee(loan, today));
+        match self.config.fee_cap(member.tier()) {
+            Some(cap) => fee.min(cap),
+            None => fee,
+        }
+    }
+}
+
+// =============================================================================
+// TESTS
+// =============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::fees::{calculate_late_fee, calculate_late_fee_between};
+    use crate::{Book, Genre, Member, MembershipTier};
+
+    fn date(day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(2024, 3, day).unwrap()
+    }
+
+    #[test]
+    fn test_open_days_skip_closures() {
+        let always = Calendar::always_open();
+        assert_eq!(always.open_days_between(date(15), date(19)), 4);
+        assert_eq!(always.open_days_between(date(19), date(15)), 0);
+
+        // Fri 15 due; Sat 16, Sun 17 closed weekly; Mon 18 a holiday
+        let calendar = Calendar::always_open()
+            .closed_on(Weekday::Sat)
+            .closed_on(Weekday::Sun)
+            .closed_on(Weekday::Sun)
+            .with_holiday(date(18));
+        assert!(!calendar.is_open(date(17)) && calendar.is_open(date(19)));
+        assert_eq!(calendar.open_days_between(date(15), date(18)), 0);
+        assert_eq!(calendar.open_days_between(date(15), date(20)), 2);
+        assert_eq!(calculate_late_fee_between(date(15), date(20), &calendar), calculate_late_fee(2));
+    }
+
+    #[test]
+    fn test_library_charges_open_days_only() {
+        let mut lib = Library::new();
+        lib.set_calendar(Calendar::always_open().closed_on(Weekday::Sun));
+        lib.add_book(Book::new(1, "Dune", Genre::SciFi)).unwrap();
+        lib.add_book(Book::new(2, "Emma", Genre::Fiction)).unwrap();
+        lib.register_member(Member::new(10, "Alice", MembershipTier::Basic)).unwrap();
+        lib.checkout(10, 1, date(1)).unwrap();
+        lib.checkout(10, 2, date(1)).unwrap();
+
+        // Sat 16 and Mon 18 are charged by the daily job, Sun 17 isn't
+        assert_eq!(lib.accrue_fees(date(18)), Money::from_cents(2 * 50));
+        assert_eq!(lib.accrue_fees(date(18)), Money::ZERO);
+        assert_eq!(lib.return_book(10, 1, date(19)).unwrap(), Money::from_cents(75));
+        assert_eq!(lib.find_member(10).unwrap().outstanding_fines(), Money::from_cents(125));
+    }
+}
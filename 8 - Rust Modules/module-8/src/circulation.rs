@@ -0,0 +1,1016 @@
+//! Circulation module - checkouts, returns and holds.
+//!
+//! This file-based module adds methods to `Library` from outside `lib.rs`:
+//! an `impl` block can live in any module of the crate that defines the type,
+//! and child modules may read the crate root's private fields.
+//!
+//! Every date-dependent operation takes `today` explicitly, so the library
+//! never reads the clock itself and tests stay deterministic.
+
+use std::fmt;
+
+use chrono::{Duration, NaiveDate};
+
+use crate::access::{Actor, AuditEntry, Operation};
+use crate::book::{Book, Condition};
+use crate::error::LibraryError;
+use crate::events::LibraryEvent;
+use crate::index::BookLocation;
+use crate::item::Format;
+use crate::ledger::FineKind;
+use crate::member::{LoanRecord, Member, MemberStatus};
+use crate::money::Money;
+use crate::Library;
+
+// =============================================================================
+// LOANS AND HOLDS
+// =============================================================================
+
+/// A book currently out on loan.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Loan {
+    pub book_id: u64,
+    pub member_id: u64,
+    pub borrowed_on: NaiveDate,
+    pub due_on: NaiveDate,
+    /// Times the loan has been renewed.
+    pub renewals: u32,
+}
+
+impl Loan {
+    /// Whole days past the due date as of `today` (0 if not yet due).
+    pub fn days_overdue(&self, today: NaiveDate) -> u32 {
+        (today - self.due_on).num_days().max(0) as u32
+    }
+}
+
+/// A member waiting for a book.
+///
+/// Holds on the same book are served in the order they were placed.
+/// `ready_since` is set once the book is set aside for this member, who
+/// then has [`hold_pickup_days`](crate::LibraryConfig::hold_pickup_days)
+/// open days to collect it; see [`Library::process_expired_holds`].
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Hold {
+    pub book_id: u64,
+    pub member_id: u64,
+    pub placed_on: NaiveDate,
+    pub ready_since: Option<NaiveDate>,
+}
+
+// =============================================================================
+// HOLD QUEUE POLICIES
+// =============================================================================
+
+/// Decides the order in which waiting holds on a book are served.
+///
+/// When a held book comes back, the waiting hold with the smallest
+/// `queue_key` is set aside; equal keys are served in the order the holds
+/// were placed. Install one with [`Library::set_hold_policy`].
+///
+/// # Examples
+///
+/// ```
+/// use module_8::{Hold, HoldQueuePolicy, Library, Member};
+///
+/// /// Serves members who owe the least in fines first.
+/// #[derive(Debug)]
+/// struct SmallestFinesFirst;
+///
+/// impl HoldQueuePolicy for SmallestFinesFirst {
+///     fn queue_key(&self, _hold: &Hold, member: &Member) -> i64 {
+///         member.outstanding_fines().cents() as i64
+///     }
+/// }
+///
+/// let mut lib = Library::new();
+/// lib.set_hold_policy(SmallestFinesFirst);
+/// ```
+pub trait HoldQueuePolicy: fmt::Debug + Send + Sync {
+    fn queue_key(&self, hold: &Hold, member: &Member) -> i64;
+}
+
+/// First come, first served (the default).
+#[derive(Debug, Default, Clone, Copy)]
+pub struct FifoHolds;
+
+impl HoldQueuePolicy for FifoHolds {
+    fn queue_key(&self, _hold: &Hold, _member: &Member) -> i64 {
+        0
+    }
+}
+
+/// Higher tiers first (by [`TierPolicy::hold_priority`](crate::TierPolicy::hold_priority)),
+/// first come, first served within a tier.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct TierPriorityHolds;
+
+impl HoldQueuePolicy for TierPriorityHolds {
+    fn queue_key(&self, _hold: &Hold, member: &Member) -> i64 {
+        -i64::from(member.tier().hold_priority())
+    }
+}
+
+// =============================================================================
+// LIBRARY CIRCULATION
+// =============================================================================
+
+impl Library {
+    /// Lends a book on the shelf to a member.
+    ///
+    /// Book and member lookups go through the library's indexes, so a
+    /// checkout costs the same however large the catalog is. The loan period
+    /// comes from the member's tier, capped by the item's
+    /// [`kind`](crate::ItemKind::max_loan_days). A [`Format::Digital`] copy
+    /// stays on the shelf and is lent for the configured
+    /// [`digital_loan_days`](crate::LibraryConfig::digital_loan_days) instead.
+    /// Digital loans count towards the member's borrow limit. Returns the due
+    /// date.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use chrono::NaiveDate;
+    /// use module_8::{Book, Genre, Library, Member, MembershipTier};
+    ///
+    /// let mut lib = Library::new();
+    /// lib.add_book(Book::new(1, "Dune", Genre::SciFi)).unwrap();
+    /// lib.register_member(Member::new(7, "Alice", MembershipTier::Basic)).unwrap();
+    ///
+    /// let today = NaiveDate::from_ymd_opt(2024, 3, 1).unwrap();
+    /// let due = lib.checkout(7, 1, today).unwrap();
+    /// assert_eq!(due, NaiveDate::from_ymd_opt(2024, 3, 15).unwrap());
+    /// ```
+    pub fn checkout(
+        &mut self,
+        member_id: u64,
+        book_id: u64,
+        today: NaiveDate,
+    ) -> Result<NaiveDate, LibraryError> {
+        let digital = match self.index.location(book_id) {
+            Some(BookLocation::Shelf) => self.books[&book_id].format == Format::Digital,
+            Some(BookLocation::OnLoan) => return Err(LibraryError::BookUnavailable(book_id)),
+            None => return Err(LibraryError::BookNotFound(book_id)),
+        };
+        // Any number of members may read a digital copy, but each only once
+        if digital && self.loans.iter().any(|l| l.book_id == book_id && l.member_id == member_id) {
+            return Err(LibraryError::BookUnavailable(book_id));
+        }
+        let loan_count = self.loan_count(member_id);
+        let member = self
+            .members
+            .get(&member_id)
+            .ok_or(LibraryError::MemberNotFound(member_id))?;
+
+        match member.status() {
+            MemberStatus::Active => {}
+            MemberStatus::Suspended { reason } => {
+                return Err(LibraryError::MemberSuspended { member_id, reason: reason.clone() })
+            }
+            MemberStatus::Closed => return Err(LibraryError::MemberInactive(member_id)),
+        }
+        if member.is_expired(today) {
+            return Err(LibraryError::MembershipExpired(member_id));
+        }
+        if loan_count >= member.max_books() {
+            return Err(LibraryError::BorrowLimitReached {
+                member_id,
+                limit: member.max_books(),
+            });
+        }
+
+        let loan_days = member.tier().loan_days();
+        if digital {
+            let due_on = today + Duration::days(i64::from(self.config.digital_loan_days));
+            self.books
+                .get_mut(&book_id)
+                .expect("indexed as on the shelf")
+                .lend_digital_copy();
+            self.metrics.record_checkout();
+            self.open_loan(
+                self.loans.len(),
+                Loan {
+                    book_id,
+                    member_id,
+                    borrowed_on: today,
+                    due_on,
+                    renewals: 0,
+                },
+            );
+            return Ok(due_on);
+        }
+
+        // A book on the hold shelf may only go to the member it was set aside for
+        let ready_hold = self
+            .holds
+            .iter()
+            .position(|h| h.book_id == book_id && h.ready_since.is_some());
+        if let Some(pos) = ready_hold {
+            if self.holds[pos].member_id != member_id {
+                return Err(LibraryError::BookOnHold(book_id));
+            }
+            self.holds.remove(pos);
+        }
+
+        let book = self.books.get_mut(&book_id).expect("indexed as on the shelf");
+        let due_on = today + Duration::days(i64::from(book.kind.loan_days(loan_days)));
+        book.borrow_book();
+        // Picked up before it was put away
+        self.mark_shelved(book_id);
+
+        self.metrics.record_checkout();
+        self.open_loan(
+            self.loans.len(),
+            Loan {
+                book_id,
+                member_id,
+                borrowed_on: today,
+                due_on,
+                renewals: 0,
+            },
+        );
+        Ok(due_on)
+    }
+
+    /// Records a loan at `position` in [`loans`](Library::loans), taking a
+    /// physical book off the shelf. Together with
+    /// [`close_loan`](Library::close_loan) the only place the index and the
+    /// book's availability follow the loans, which are the record of who
+    /// has what.
+    pub(crate) fn open_loan(&mut self, position: usize, loan: Loan) {
+        if !self.is_digital_loan(&loan) {
+            self.index.lend(loan.book_id);
+            if let Some(book) = self.books.get_mut(&loan.book_id) {
+                book.mark_on_loan();
+            }
+        }
+        self.loans.insert(position.min(self.loans.len()), loan);
+    }
+
+    /// Removes the loan at `position`, putting a physical book back on the
+    /// shelf, and returns it.
+    pub(crate) fn close_loan(&mut self, position: usize) -> Loan {
+        let loan = self.loans.remove(position);
+        if !self.is_digital_loan(&loan) {
+            self.index.shelve(loan.book_id);
+            if let Some(book) = self.books.get_mut(&loan.book_id) {
+                book.return_book();
+            }
+        }
+        loan
+    }
+
+    /// Opens loans carried over from elsewhere (a snapshot, a saved file or
+    /// another library), after checking each one: the book must be
+    /// catalogued and, unless it's a digital copy, on the shelf; the member
+    /// must be registered and may read a digital copy only once.
+    ///
+    /// Loans before the first bad one stay open; callers build a fresh
+    /// library, so this is only ever all or nothing for them.
+    pub(crate) fn restore_loans(&mut self, loans: Vec<Loan>) -> Result<(), LibraryError> {
+        for loan in loans {
+            if !self.members.contains_key(&loan.member_id) {
+                return Err(LibraryError::MemberNotFound(loan.member_id));
+            }
+            let available = match self.index.location(loan.book_id) {
+                None => return Err(LibraryError::BookNotFound(loan.book_id)),
+                Some(BookLocation::OnLoan) => false,
+                Some(BookLocation::Shelf) => {
+                    !self.is_digital_loan(&loan)
+                        || !self.loans.iter().any(|l| l.book_id == loan.book_id && l.member_id == loan.member_id)
+                }
+            };
+            if !available {
+                return Err(LibraryError::BookUnavailable(loan.book_id));
+            }
+            self.open_loan(self.loans.len(), loan);
+        }
+        Ok(())
+    }
+
+    /// Takes a book back from a member and puts it on the shelf.
+    ///
+    /// The fee policy's charge is reduced by the member's tier discount (see
+    /// [`Member::effective_fee`]) and capped at their tier's
+    /// [`fee_cap`](crate::LibraryConfig::fee_cap). Returns that fee. Whatever part
+    /// of it [`accrue_fees`](Library::accrue_fees) hasn't already charged is
+    /// added to the member's [`outstanding_fines`](crate::Member::outstanding_fines).
+    /// The loan is appended to the member's [`history`](crate::Member::history).
+    /// If other members hold the book, the earliest hold becomes ready and a
+    /// [`LibraryEvent::HoldAvailable`] fires.
+    ///
+    /// The book keeps the condition it had; use [`return_book_in`] to record
+    /// wear or damage.
+    ///
+    /// [`return_book_in`]: Library::return_book_in
+    pub fn return_book(
+        &mut self,
+        member_id: u64,
+        book_id: u64,
+        today: NaiveDate,
+    ) -> Result<Money, LibraryError> {
+        self.check_in(member_id, book_id, today, None)
+    }
+
+    /// Like [`return_book`](Library::return_book), recording the condition
+    /// the book came back in.
+    ///
+    /// A book reported [`Condition::Lost`] leaves the catalog instead of
+    /// going back on the shelf, any holds on it are dropped, and the member
+    /// is billed the configured
+    /// [`replacement_cost`](crate::LibraryConfig::replacement_cost) on top of
+    /// any late fee. The tier discount and fee cap apply to the late fee only. Returns
+    /// the total charged. The condition of a digital copy isn't recorded.
+    /// The copy is kept in [`lost_books`](Library::lost_books) in case it
+    /// turns up.
+    pub fn return_book_in(
+        &mut self,
+        member_id: u64,
+        book_id: u64,
+        today: NaiveDate,
+        condition: Condition,
+    ) -> Result<Money, LibraryError> {
+        self.check_in(member_id, book_id, today, Some(condition))
+    }
+
+    fn check_in(
+        &mut self,
+        member_id: u64,
+        book_id: u64,
+        today: NaiveDate,
+        condition: Option<Condition>,
+    ) -> Result<Money, LibraryError> {
+        let not_on_loan = LibraryError::NotOnLoan { member_id, book_id };
+        let loan_pos = self
+            .loans
+            .iter()
+            .position(|l| l.book_id == book_id && l.member_id == member_id)
+            .ok_or(not_on_loan.clone())?;
+        let loan = self.loans[loan_pos].clone();
+        let member = self.find_member(member_id).ok_or(not_on_loan.clone())?;
+        let late_fee = self.member_late_fee(member, &loan, today);
+        let accrued = self.accrued_for(&loan);
+        let digital = self.is_digital_loan(&loan);
+        // A digital copy can't be lost or damaged
+        let condition = condition.filter(|_| !digital);
+        let lost = condition == Some(Condition::Lost);
+        let replacement = if lost { self.config.replacement_cost } else { Money::ZERO };
+        let member = self.members.get_mut(&member_id).ok_or(not_on_loan)?;
+        let fee = late_fee.saturating_add(replacement);
+        let charged = late_fee.saturating_sub(accrued).saturating_add(replacement);
+        member.charge(charged);
+        member.record_history(LoanRecord {
+            book_id,
+            borrowed_on: Some(loan.borrowed_on),
+            returned_on: today,
+            fee_charged: fee,
+        });
+
+        self.close_loan(loan_pos);
+        self.metrics.record_return();
+        self.record_fine(today, member_id, FineKind::Assessed, charged);
+        self.review_status(member_id);
+        if digital {
+            return Ok(fee);
+        }
+        if lost {
+            // Kept in the condition it left in, should it turn up
+            self.set_aside_lost(loan, today, replacement, late_fee);
+            return Ok(fee);
+        }
+        if let (Some(condition), Some(book)) = (condition, self.books.get_mut(&book_id)) {
+            book.condition = condition;
+        }
+
+        // A held book goes to the hold shelf rather than its own
+        match self.next_hold(book_id) {
+            Some(pos) => {
+                let hold = &mut self.holds[pos];
+                hold.ready_since = Some(today);
+                let member_id = hold.member_id;
+                self.notify(&LibraryEvent::HoldAvailable { member_id, book_id });
+            }
+            None => self.queue_for_shelving(book_id, today),
+        }
+        Ok(fee)
+    }
+
+    /// Books on the shelf that came back [`Condition::Damaged`], in catalog
+    /// order.
+    pub fn needs_repair(&self) -> Vec<&Book> {
+        self.books()
+            .filter(|b| b.condition == Condition::Damaged)
+            .collect()
+    }
+
+    /// Extends a loan by the member's loan period (capped by the item's
+    /// kind, or the digital loan period, as at checkout), counted from `today`.
+    ///
+    /// A loan can't be renewed while other members hold the book, more than
+    /// the configured [`max_renewals`](crate::LibraryConfig::max_renewals)
+    /// times, or once the membership has expired; the error says which.
+    /// Returns the new due date; a renewal never moves the due date earlier.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use chrono::NaiveDate;
+    /// use module_8::{Book, Genre, Library, LibraryError, Member, MembershipTier};
+    ///
+    /// let mut lib = Library::new();
+    /// lib.add_book(Book::new(1, "Dune", Genre::SciFi)).unwrap();
+    /// lib.register_member(Member::new(7, "Alice", MembershipTier::Basic)).unwrap();
+    /// let day = |d| NaiveDate::from_ymd_opt(2024, 3, d).unwrap();
+    ///
+    /// lib.checkout(7, 1, day(1)).unwrap();
+    /// assert_eq!(lib.renew(7, 1, day(14)), Ok(day(28)));
+    /// assert_eq!(lib.renew(7, 1, day(27)), Ok(NaiveDate::from_ymd_opt(2024, 4, 10).unwrap()));
+    /// assert_eq!(lib.renew(7, 1, day(30)), Err(LibraryError::RenewalLimitReached { book_id: 1, limit: 2 }));
+    /// ```
+    pub fn renew(
+        &mut self,
+        member_id: u64,
+        book_id: u64,
+        today: NaiveDate,
+    ) -> Result<NaiveDate, LibraryError> {
+        let loan_pos = self
+            .loans
+            .iter()
+            .position(|l| l.book_id == book_id && l.member_id == member_id)
+            .ok_or(LibraryError::NotOnLoan { member_id, book_id })?;
+        if self.holds.iter().any(|h| h.book_id == book_id) {
+            return Err(LibraryError::BookOnHold(book_id));
+        }
+        let limit = self.config.max_renewals;
+        if self.loans[loan_pos].renewals >= limit {
+            return Err(LibraryError::RenewalLimitReached { book_id, limit });
+        }
+        let member = self
+            .find_member(member_id)
+            .ok_or(LibraryError::MemberNotFound(member_id))?;
+        if member.is_expired(today) {
+            return Err(LibraryError::MembershipExpired(member_id));
+        }
+
+        let loan_days = if self.is_digital_loan(&self.loans[loan_pos]) {
+            self.config.digital_loan_days
+        } else {
+            self.get_book(book_id)
+                .map_or(member.tier().loan_days(), |book| book.kind.loan_days(member.tier().loan_days()))
+        };
+        let renewed = today + Duration::days(i64::from(loan_days));
+        let loan = &mut self.loans[loan_pos];
+        loan.due_on = loan.due_on.max(renewed);
+        loan.renewals += 1;
+        Ok(loan.due_on)
+    }
+
+    /// Replaces the policy that orders waiting holds.
+    pub fn set_hold_policy(&mut self, policy: impl HoldQueuePolicy + 'static) {
+        self.hold_policy = Box::new(policy);
+    }
+
+    /// Returns the holds waiting for a book, in the order the current
+    /// policy will serve them. A hold already set aside is not included.
+    pub fn hold_queue(&self, book_id: u64) -> Vec<&Hold> {
+        let mut queue: Vec<(i64, &Hold)> = self
+            .holds
+            .iter()
+            .filter(|h| h.book_id == book_id && h.ready_since.is_none())
+            .filter_map(|h| Some((self.hold_policy.queue_key(h, self.find_member(h.member_id)?), h)))
+            .collect();
+        // Stable sort, so equal keys keep placement order
+        queue.sort_by_key(|&(key, _)| key);
+        queue.into_iter().map(|(_, hold)| hold).collect()
+    }
+
+    /// Position in `self.holds` of the hold to serve next for a book.
+    fn next_hold(&self, book_id: u64) -> Option<usize> {
+        let next = *self.hold_queue(book_id).first()?;
+        self.holds.iter().position(|h| std::ptr::eq(h, next))
+    }
+
+    /// Places a hold on a book for a member.
+    ///
+    /// If the book is on the shelf and not already set aside, the hold is
+    /// ready immediately. Placing the same hold twice is a no-op. Digital
+    /// copies can't be held: they are always available. Nor can members
+    /// whose tier doesn't [allow holds](crate::TierPolicy::can_place_holds)
+    /// place them.
+    pub fn place_hold(
+        &mut self,
+        member_id: u64,
+        book_id: u64,
+        today: NaiveDate,
+    ) -> Result<(), LibraryError> {
+        let on_shelf = match self.index.location(book_id) {
+            Some(location) => location == BookLocation::Shelf,
+            None => return Err(LibraryError::BookNotFound(book_id)),
+        };
+        if on_shelf && self.books[&book_id].format == Format::Digital {
+            return Err(LibraryError::HoldNotNeeded(book_id));
+        }
+        match self.find_member(member_id) {
+            None => return Err(LibraryError::MemberNotFound(member_id)),
+            Some(member) if !member.tier().can_place_holds() => {
+                return Err(LibraryError::HoldsNotAllowed(member_id));
+            }
+            Some(_) => {}
+        }
+        if self
+            .holds
+            .iter()
+            .any(|h| h.book_id == book_id && h.member_id == member_id)
+        {
+            return Ok(());
+        }
+
+        let ready = on_shelf && !self.holds.iter().any(|h| h.book_id == book_id);
+        self.holds.push(Hold {
+            book_id,
+            member_id,
+            placed_on: today,
+            ready_since: ready.then_some(today),
+        });
+        if ready {
+            self.notify(&LibraryEvent::HoldAvailable { member_id, book_id });
+        }
+        Ok(())
+    }
+
+    /// Returns all loans currently out.
+    pub fn loans(&self) -> &[Loan] {
+        &self.loans
+    }
+
+    /// The physical books a member is borrowing, in the order they were
+    /// checked out. Empty for an unknown member.
+    ///
+    /// Worked out from the [`loans`](Library::loans): a member doesn't keep
+    /// their own list. The books stay in the catalog while they're out.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use chrono::NaiveDate;
+    /// use module_8::{Book, Genre, Library, Member, MembershipTier};
+    ///
+    /// let mut lib = Library::new();
+    /// lib.add_book(Book::new(1, "Dune", Genre::SciFi)).unwrap();
+    /// lib.register_member(Member::new(7, "Alice", MembershipTier::Basic)).unwrap();
+    /// lib.checkout(7, 1, NaiveDate::from_ymd_opt(2024, 3, 1).unwrap()).unwrap();
+    ///
+    /// assert_eq!(lib.borrowed_books(7)[0].title, "Dune");
+    /// assert_eq!(lib.borrower(1), Some(7));
+    /// assert!(!lib.find_book(1).unwrap().is_available());
+    /// ```
+    pub fn borrowed_books(&self, member_id: u64) -> Vec<&Book> {
+        self.loans
+            .iter()
+            .filter(|l| l.member_id == member_id && !self.is_digital_loan(l))
+            .filter_map(|l| self.books.get(&l.book_id))
+            .collect()
+    }
+
+    /// The member borrowing a physical book, or `None` if it's on the shelf
+    /// or not catalogued.
+    pub fn borrower(&self, book_id: u64) -> Option<u64> {
+        match self.index.location(book_id)? {
+            BookLocation::OnLoan => self.loans.iter().find(|l| l.book_id == book_id).map(|l| l.member_id),
+            BookLocation::Shelf => None,
+        }
+    }
+
+    /// Number of loans a member has open, physical and digital; what their
+    /// borrow limit is checked against.
+    pub fn loan_count(&self, member_id: u64) -> usize {
+        self.loans.iter().filter(|l| l.member_id == member_id).count()
+    }
+
+    /// Whether a loan is of a digital copy, which stays on the shelf while lent.
+    pub fn is_digital_loan(&self, loan: &Loan) -> bool {
+        self.get_book(loan.book_id)
+            .is_some_and(|book| book.format == Format::Digital)
+    }
+
+    /// Number of digital copies a member is currently borrowing.
+    pub fn digital_loans(&self, member_id: u64) -> usize {
+        self.loans
+            .iter()
+            .filter(|l| l.member_id == member_id && self.is_digital_loan(l))
+            .count()
+    }
+
+    /// Returns all open holds, in the order they were placed.
+    pub fn holds(&self) -> &[Hold] {
+        &self.holds
+    }
+
+    /// Fires a [`LibraryEvent::Overdue`] for every loan past due as of
+    /// `today`, returning how many were found.
+    ///
+    /// Intended to be run once a day, e.g. from a scheduled job.
+    pub fn check_overdue(&self, today: NaiveDate) -> usize {
+        let overdue: Vec<LibraryEvent> = self
+            .loans
+            .iter()
+            .filter(|loan| loan.days_overdue(today) > 0)
+            .map(|loan| LibraryEvent::Overdue {
+                member_id: loan.member_id,
+                book_id: loan.book_id,
+                due_on: loan.due_on,
+                days_overdue: loan.days_overdue(today),
+            })
+            .collect();
+        for event in &overdue {
+            self.notify(event);
+        }
+        overdue.len()
+    }
+
+    /// Fires a [`LibraryEvent::MembershipExpired`] for every member whose
+    /// membership has expired as of `today`, returning how many were found.
+    pub fn check_memberships(&self, today: NaiveDate) -> usize {
+        let expired: Vec<LibraryEvent> = self
+            .members()
+            .filter(|m| m.is_expired(today))
+            .filter_map(|m| {
+                m.expires_on().map(|expired_on| LibraryEvent::MembershipExpired {
+                    member_id: m.id(),
+                    expired_on,
+                })
+            })
+            .collect();
+        for event in &expired {
+            self.notify(event);
+        }
+        expired.len()
+    }
+
+    /// Expires every hold whose book has waited on the hold shelf for more
+    /// than `hold_pickup_days` open days as of `as_of`, returning the
+    /// expired holds.
+    ///
+    /// Each expiry fires a [`LibraryEvent::HoldExpired`] and is recorded in
+    /// the [audit log](Library::audit_log) under [`Actor::system`]. The book
+    /// is then set aside for the next member in its queue, who gets a
+    /// [`LibraryEvent::HoldAvailable`] and a fresh pickup window, or goes
+    /// back on the open shelf if nobody else is waiting.
+    pub fn process_expired_holds(&mut self, as_of: NaiveDate) -> Vec<Hold> {
+        let pickup_days = self.config.hold_pickup_days;
+        let mut expired = Vec::new();
+        while let Some(pos) = self.holds.iter().position(|h| {
+            h.ready_since
+                .is_some_and(|ready| self.calendar.open_days_between(ready, as_of) > pickup_days)
+        }) {
+            let hold = self.holds.remove(pos);
+            let (member_id, book_id) = (hold.member_id, hold.book_id);
+            self.audit_log.push(AuditEntry {
+                on: as_of,
+                actor: Actor::system(),
+                operation: Operation::ExpireHold,
+                member_id: Some(member_id),
+                book_id: Some(book_id),
+                allowed: true,
+            });
+            self.notify(&LibraryEvent::HoldExpired { member_id, book_id });
+            if let Some(next) = self.next_hold(book_id) {
+                let next = &mut self.holds[next];
+                next.ready_since = Some(as_of);
+                let member_id = next.member_id;
+                self.notify(&LibraryEvent::HoldAvailable { member_id, book_id });
+            }
+            expired.push(hold);
+        }
+        expired
+    }
+}
+
+// =============================================================================
+// TESTS
+// =============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Book, Genre, Member, MembershipTier};
+    use std::sync::{Arc, Mutex};
+
+    fn date(day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(2024, 3, day).unwrap()
+    }
+
+    /// A library with two books, two members and a recording notifier.
+    fn setup() -> (Library, Arc<Mutex<Vec<LibraryEvent>>>) {
+        let mut lib = Library::new();
+        lib.add_book(Book::new(1, "Dune", Genre::SciFi)).unwrap();
+        lib.add_book(Book::new(2, "Emma", Genre::Fiction)).unwrap();
+        lib.register_member(Member::new(10, "Alice", MembershipTier::Basic)).unwrap();
+        lib.register_member(Member::new(11, "Bob", MembershipTier::Basic)).unwrap();
+
+        let events = Arc::new(Mutex::new(Vec::new()));
+        let sink = Arc::clone(&events);
+        lib.add_notifier(move |e: &LibraryEvent| sink.lock().unwrap().push(e.clone()));
+        (lib, events)
+    }
+
+    #[test]
+    fn test_checkout_and_return() {
+        let (mut lib, _) = setup();
+        let due = lib.checkout(10, 1, date(1)).unwrap();
+        assert_eq!(due, date(15));
+        assert_eq!(lib.book_count(), 1);
+        assert_eq!(lib.checkout(11, 1, date(1)), Err(LibraryError::BookUnavailable(1)));
+
+        // Returned two days late at the default $0.25 per day
+        assert_eq!(lib.return_book(10, 1, date(17)), Ok(Money::from_cents(50)));
+        assert_eq!(lib.book_count(), 2);
+        assert!(lib.loans().is_empty());
+    }
+
+    #[test]
+    fn test_return_records_history() {
+        let (mut lib, _) = setup();
+        lib.checkout(10, 1, date(1)).unwrap();
+        lib.return_book(10, 1, date(17)).unwrap();
+
+        let history = lib.find_member(10).unwrap().history();
+        assert_eq!(
+            history,
+            [LoanRecord { book_id: 1, borrowed_on: Some(date(1)), returned_on: date(17), fee_charged: Money::from_cents(50) }]
+        );
+    }
+
+    #[test]
+    fn test_return_uses_fee_policy() {
+        let (mut lib, _) = setup();
+        lib.set_fee_policy(crate::fee_policy::GracePeriod::new(crate::FlatFeePolicy { per_day: Money::from_cents(25) }, 3));
+        lib.checkout(10, 1, date(1)).unwrap();
+
+        // Due on the 15th; 4 days late minus 3 days' grace
+        assert_eq!(lib.return_book(10, 1, date(19)), Ok(Money::from_cents(25)));
+    }
+
+    #[test]
+    fn test_late_fees_are_capped_per_tier() {
+        let (mut lib, _) = setup();
+        lib.register_member(Member::new(12, "Gwen", MembershipTier::Gold)).unwrap();
+        lib.checkout(10, 1, date(1)).unwrap();
+        lib.checkout(12, 2, date(1)).unwrap();
+
+        // 100 days late: $25.00 for Basic and $20.00 for Gold after the discount
+        let returned = date(1) + chrono::Duration::days(130);
+        assert_eq!(lib.tick(returned).fees_charged, Money::from_cents(1500 + 800));
+        assert_eq!(lib.return_book(10, 1, returned), Ok(MembershipTier::Basic.fee_cap()));
+        assert_eq!(lib.return_book(12, 2, returned), Ok(MembershipTier::Gold.fee_cap()));
+        assert_eq!(lib.find_member(12).unwrap().outstanding_fines(), Money::from_cents(800));
+
+        // A lost book still costs its replacement on top of the capped fee
+        let mut lib = Library::with_config(crate::LibraryConfig {
+            fee_caps: [(String::from("Basic"), Money::from_cents(100))].into(),
+            ..crate::LibraryConfig::default()
+        });
+        lib.add_book(Book::new(1, "Dune", Genre::SciFi)).unwrap();
+        lib.register_member(Member::new(10, "Alice", MembershipTier::Basic)).unwrap();
+        lib.checkout(10, 1, date(1)).unwrap();
+        assert_eq!(lib.return_book_in(10, 1, date(30), crate::Condition::Lost), Ok(Money::from_cents(2100)));
+    }
+
+    #[test]
+    fn test_configured_grace_period_boundaries() {
+        let config = crate::LibraryConfig { grace_days: 2, first_day_percent: 50, ..crate::LibraryConfig::default() };
+        let mut lib = Library::with_config(config);
+        assert_eq!(lib.fee_policy().grace_days(), 2);
+        lib.add_book(Book::new(1, "Dune", Genre::SciFi)).unwrap();
+        lib.register_member(Member::new(10, "Alice", MembershipTier::Basic)).unwrap();
+
+        // Due on the 15th each time
+        let mut returned_on = |day| {
+            lib.checkout(10, 1, date(1)).unwrap();
+            lib.return_book(10, 1, date(day)).unwrap()
+        };
+        assert_eq!(returned_on(15), Money::ZERO, "on the due date");
+        assert_eq!(returned_on(16), Money::ZERO, "first day of grace");
+        assert_eq!(returned_on(17), Money::ZERO, "last day of grace");
+        assert_eq!(returned_on(18), Money::from_cents(12), "half of the first charged day");
+        assert_eq!(returned_on(19), Money::from_cents(37), "then full days");
+    }
+
+    #[test]
+    fn test_return_applies_tier_discount() {
+        let (mut lib, _) = setup();
+        lib.register_member(Member::new(12, "Gwen", MembershipTier::Gold)).unwrap();
+        lib.checkout(12, 1, date(1)).unwrap();
+
+        // Gold loans are due after 30 days; 4 days late = $1.00, less 20%
+        assert_eq!(lib.return_book(12, 1, NaiveDate::from_ymd_opt(2024, 4, 4).unwrap()), Ok(Money::from_cents(80)));
+        assert_eq!(lib.find_member(12).unwrap().outstanding_fines(), Money::from_cents(80));
+    }
+
+    #[test]
+    fn test_renew() {
+        let (mut lib, _) = setup();
+        lib.checkout(10, 1, date(1)).unwrap();
+
+        assert_eq!(lib.renew(10, 1, date(10)), Ok(date(24)));
+        assert_eq!(lib.loans()[0].due_on, date(24));
+        // Renewing early never shortens the loan
+        assert_eq!(lib.renew(10, 1, date(2)), Ok(date(24)));
+        assert_eq!(lib.renew(11, 1, date(10)), Err(LibraryError::NotOnLoan { member_id: 11, book_id: 1 }));
+
+        lib.place_hold(11, 1, date(11)).unwrap();
+        assert_eq!(lib.renew(10, 1, date(12)), Err(LibraryError::BookOnHold(1)));
+    }
+
+    #[test]
+    fn test_renewal_limit() {
+        let (mut lib, _) = setup();
+        lib.checkout(10, 1, date(1)).unwrap();
+        assert_eq!(lib.renew(10, 1, date(10)), Ok(date(24)));
+        assert_eq!(lib.renew(10, 1, date(20)), Ok(NaiveDate::from_ymd_opt(2024, 4, 3).unwrap()));
+        assert_eq!(lib.renew(10, 1, date(30)), Err(LibraryError::RenewalLimitReached { book_id: 1, limit: 2 }));
+        assert_eq!(lib.loans()[0].renewals, 2);
+
+        // A new loan of the same book starts the count again
+        lib.return_book(10, 1, date(30)).unwrap();
+        lib.checkout(10, 1, date(30)).unwrap();
+        assert_eq!(lib.loans()[0].renewals, 0);
+        assert!(lib.renew(10, 1, date(30)).is_ok());
+    }
+
+    #[test]
+    fn test_checkout_errors() {
+        let (mut lib, _) = setup();
+        assert_eq!(lib.checkout(10, 99, date(1)), Err(LibraryError::BookNotFound(99)));
+        assert_eq!(lib.checkout(99, 1, date(1)), Err(LibraryError::MemberNotFound(99)));
+        assert_eq!(
+            lib.return_book(10, 1, date(1)),
+            Err(LibraryError::NotOnLoan { member_id: 10, book_id: 1 })
+        );
+    }
+
+    #[test]
+    fn test_overdue_detection_notifies() {
+        let (mut lib, events) = setup();
+        lib.checkout(10, 1, date(1)).unwrap();
+
+        assert_eq!(lib.check_overdue(date(15)), 0);
+        assert_eq!(lib.check_overdue(date(18)), 1);
+        assert_eq!(
+            events.lock().unwrap().as_slice(),
+            [LibraryEvent::Overdue { member_id: 10, book_id: 1, due_on: date(15), days_overdue: 3 }]
+        );
+    }
+
+    #[test]
+    fn test_hold_becomes_available_on_return() {
+        let (mut lib, events) = setup();
+        lib.checkout(10, 1, date(1)).unwrap();
+        lib.place_hold(11, 1, date(2)).unwrap();
+        assert!(events.lock().unwrap().is_empty());
+
+        lib.return_book(10, 1, date(5)).unwrap();
+        assert_eq!(
+            events.lock().unwrap().as_slice(),
+            [LibraryEvent::HoldAvailable { member_id: 11, book_id: 1 }]
+        );
+
+        // Set aside for Bob: Alice can't take it, Bob can
+        assert_eq!(lib.checkout(10, 1, date(6)), Err(LibraryError::BookOnHold(1)));
+        assert!(lib.checkout(11, 1, date(6)).is_ok());
+        assert!(lib.holds().is_empty());
+    }
+
+    #[test]
+    fn test_uncollected_holds_expire_and_pass_on() {
+        let (mut lib, events) = setup();
+        lib.register_member(Member::new(12, "Carol", MembershipTier::Basic)).unwrap();
+        lib.checkout(10, 1, date(1)).unwrap();
+        lib.place_hold(11, 1, date(2)).unwrap();
+        lib.place_hold(12, 1, date(3)).unwrap();
+        lib.return_book(10, 1, date(5)).unwrap();
+        events.lock().unwrap().clear();
+
+        // Seven days to collect: the 12th is the last one
+        assert!(lib.process_expired_holds(date(12)).is_empty());
+        let expired = lib.process_expired_holds(date(13));
+        assert_eq!((expired.len(), expired[0].member_id), (1, 11));
+        assert_eq!(
+            events.lock().unwrap().as_slice(),
+            [
+                LibraryEvent::HoldExpired { member_id: 11, book_id: 1 },
+                LibraryEvent::HoldAvailable { member_id: 12, book_id: 1 },
+            ]
+        );
+        assert_eq!(lib.holds()[0].ready_since, Some(date(13)));
+        let entry = &lib.audit_log()[0];
+        assert_eq!((entry.actor, entry.operation, entry.member_id), (Actor::system(), Operation::ExpireHold, Some(11)));
+
+        // Nobody is left waiting once Carol's hold lapses too
+        assert_eq!(lib.process_expired_holds(date(21)).len(), 1);
+        assert!(lib.holds().is_empty());
+        assert!(lib.checkout(10, 1, date(21)).is_ok());
+        assert_eq!(lib.audit_log().len(), 2);
+    }
+
+    #[test]
+    fn test_tier_priority_hold_queue() {
+        let (mut lib, events) = setup();
+        lib.register_member(Member::new(12, "Gwen", MembershipTier::Gold)).unwrap();
+        lib.checkout(10, 1, date(1)).unwrap();
+        lib.place_hold(11, 1, date(2)).unwrap();
+        lib.place_hold(12, 1, date(3)).unwrap();
+
+        let queue = |lib: &Library| lib.hold_queue(1).iter().map(|h| h.member_id).collect::<Vec<_>>();
+        assert_eq!(queue(&lib), [11, 12]);
+
+        lib.set_hold_policy(TierPriorityHolds);
+        assert_eq!(queue(&lib), [12, 11]);
+
+        // Gold member Gwen placed her hold later but is served first
+        lib.return_book(10, 1, date(5)).unwrap();
+        assert_eq!(
+            events.lock().unwrap().as_slice(),
+            [LibraryEvent::HoldAvailable { member_id: 12, book_id: 1 }]
+        );
+        assert_eq!(queue(&lib), [11]);
+    }
+
+    #[test]
+    fn test_guests_borrow_one_book_without_holds() {
+        let (mut lib, _) = setup();
+        lib.register_member(crate::member::create_guest(12, "Visitor")).unwrap();
+        assert_eq!(lib.checkout(12, 1, date(1)), Ok(date(8)));
+        assert_eq!(
+            lib.checkout(12, 2, date(1)),
+            Err(LibraryError::BorrowLimitReached { member_id: 12, limit: 1 })
+        );
+        assert_eq!(lib.place_hold(12, 2, date(1)), Err(LibraryError::HoldsNotAllowed(12)));
+        assert!(lib.holds().is_empty());
+
+        // Members can still hold a book a guest is reading
+        lib.place_hold(10, 1, date(2)).unwrap();
+        lib.return_book(12, 1, date(8)).unwrap();
+        assert_eq!((lib.holds()[0].member_id, lib.holds()[0].ready_since), (10, Some(date(8))));
+    }
+
+    #[test]
+    fn test_membership_expiry() {
+        let (mut lib, events) = setup();
+        let mut carol = Member::new(12, "Carol", MembershipTier::Basic);
+        carol.set_expires_on(Some(date(10)));
+        lib.register_member(carol).unwrap();
+
+        assert_eq!(lib.check_memberships(date(10)), 0);
+        assert_eq!(lib.check_memberships(date(11)), 1);
+        assert_eq!(
+            events.lock().unwrap().as_slice(),
+            [LibraryEvent::MembershipExpired { member_id: 12, expired_on: date(10) }]
+        );
+        assert_eq!(lib.checkout(12, 1, date(11)), Err(LibraryError::MembershipExpired(12)));
+    }
+
+    #[test]
+    fn test_return_damaged_needs_repair() {
+        let (mut lib, _) = setup();
+        lib.checkout(10, 1, date(1)).unwrap();
+        lib.checkout(11, 2, date(1)).unwrap();
+        lib.return_book_in(10, 1, date(5), Condition::Damaged).unwrap();
+        lib.return_book(11, 2, date(5)).unwrap();
+
+        let ids: Vec<u64> = lib.needs_repair().iter().map(|b| b.id()).collect();
+        assert_eq!(ids, [1]);
+        assert_eq!(lib.find_book(2).unwrap().condition, Condition::New);
+    }
+
+    #[test]
+    fn test_return_lost_bills_replacement() {
+        let (mut lib, events) = setup();
+        lib.checkout(10, 1, date(1)).unwrap();
+        lib.place_hold(11, 1, date(2)).unwrap();
+
+        // Two days late: $0.50 plus the replacement cost
+        let fee = lib.return_book_in(10, 1, date(17), Condition::Lost).unwrap();
+        assert_eq!(fee, Money::from_cents(50) + lib.config().replacement_cost);
+        assert_eq!(lib.find_member(10).unwrap().outstanding_fines(), fee);
+        assert!(lib.get_book(1).is_none());
+        assert!(lib.holds().is_empty());
+        assert!(events.lock().unwrap().is_empty());
+        assert_eq!(lib.check_invariants(), Ok(()));
+    }
+
+    #[test]
+    fn test_huge_replacement_cost_saturates() {
+        let max = Money::from_cents(u64::MAX);
+        let mut lib = Library::with_config(crate::LibraryConfig { replacement_cost: max, ..crate::LibraryConfig::default() });
+        lib.add_book(Book::new(1, "Dune", Genre::SciFi)).unwrap();
+        lib.register_member(Member::new(10, "Alice", MembershipTier::Basic)).unwrap();
+        lib.checkout(10, 1, date(1)).unwrap();
+
+        let fee = lib.return_book_in(10, 1, date(20), Condition::Lost).unwrap();
+        assert_eq!(fee, max);
+        assert_eq!(lib.find_member(10).unwrap().outstanding_fines(), max);
+    }
+}
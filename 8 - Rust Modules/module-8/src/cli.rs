@@ -0,0 +1,271 @@
+//! Command-line interface - runs one library command and saves the result.
+//!
+//! This module belongs to the BINARY crate: it's loaded because `main.rs`
+//! contains `mod cli;`, so it can only use the library's public API, the
+//! same as any other crate depending on `module_8`.
+//!
+//! The library's data lives in a file (see `module_8::store`) that is loaded
+//! at the start of every command and saved again after any change, so state
+//! carries over from one run to the next.
+
+use std::io;
+use std::path::PathBuf;
+
+use chrono::{Local, NaiveDate};
+use module_8::reports::{standard_sections, OverdueLoans};
+use module_8::storage::{FileStorage, Storage};
+use module_8::store::StoreError;
+use module_8::{Book, Genre, Library, LibraryConfig, LibraryError, MembershipTier};
+
+/// Where the library is kept unless `--file` says otherwise.
+const DEFAULT_FILE: &str = "library.csv";
+
+const USAGE: &str = "\
+Usage: module-8 [--file PATH] [--date YYYY-MM-DD] <command> [args]
+
+Commands:
+  add-book <title> [--genre G] [--author A] [--isbn I]
+  add-member <name> [--tier basic|silver|gold|platinum]
+  checkout <member-id> <book-id>
+  return <member-id> <book-id>
+  renew <member-id> <book-id>
+  search <query>
+  report [--format text|markdown|html]
+
+Run without arguments for the module system demonstration.";
+
+/// Runs the command in `args` (program name excluded), returning the
+/// process exit code: 0 on success, 1 if the command failed, 2 for bad usage.
+pub fn run(args: Vec<String>) -> i32 {
+    match parse(args).and_then(execute) {
+        Ok(output) => {
+            println!("{}", output.trim_end());
+            0
+        }
+        Err(CliError::Usage(message)) => {
+            eprintln!("error: {}\n\n{}", message, USAGE);
+            2
+        }
+        Err(CliError::Failed(message)) => {
+            eprintln!("error: {}", message);
+            1
+        }
+    }
+}
+
+enum CliError {
+    /// The arguments don't form a valid command.
+    Usage(String),
+    /// The command was valid but couldn't be carried out.
+    Failed(String),
+}
+
+impl From<StoreError> for CliError {
+    fn from(e: StoreError) -> Self {
+        CliError::Failed(e.to_string())
+    }
+}
+
+impl From<io::Error> for CliError {
+    fn from(e: io::Error) -> Self {
+        CliError::Failed(e.to_string())
+    }
+}
+
+// =============================================================================
+// ARGUMENT PARSING
+// =============================================================================
+
+/// A parsed command line.
+struct Invocation {
+    file: PathBuf,
+    today: NaiveDate,
+    command: String,
+    /// Positional arguments after the command.
+    args: Vec<String>,
+    /// `--name value` options after the command.
+    options: Vec<(String, String)>,
+}
+
+impl Invocation {
+    fn option(&self, name: &str) -> Option<&str> {
+        self.options
+            .iter()
+            .find(|(n, _)| n == name)
+            .map(|(_, value)| value.as_str())
+    }
+
+    /// Positional argument `index`, which must be present.
+    fn arg(&self, index: usize, name: &str) -> Result<&str, CliError> {
+        self.args
+            .get(index)
+            .map(String::as_str)
+            .ok_or_else(|| CliError::Usage(format!("{} needs a {}", self.command, name)))
+    }
+
+    fn id_arg(&self, index: usize, name: &str) -> Result<u64, CliError> {
+        let value = self.arg(index, name)?;
+        value
+            .parse()
+            .map_err(|_| CliError::Usage(format!("{} must be a number, not {:?}", name, value)))
+    }
+}
+
+fn parse(args: Vec<String>) -> Result<Invocation, CliError> {
+    let mut file = PathBuf::from(DEFAULT_FILE);
+    let mut today = Local::now().date_naive();
+    let mut command = None;
+    let mut positional = Vec::new();
+    let mut options = Vec::new();
+
+    let mut args = args.into_iter();
+    while let Some(arg) = args.next() {
+        let Some(name) = arg.strip_prefix("--") else {
+            match command {
+                None => command = Some(arg),
+                Some(_) => positional.push(arg),
+            }
+            continue;
+        };
+        let value = args
+            .next()
+            .ok_or_else(|| CliError::Usage(format!("--{} needs a value", name)))?;
+        match (name, &command) {
+            ("file", None) => file = PathBuf::from(value),
+            ("date", None) => {
+                today = NaiveDate::parse_from_str(&value, "%Y-%m-%d")
+                    .map_err(|_| CliError::Usage(format!("{:?} is not a YYYY-MM-DD date", value)))?
+            }
+            (_, None) => return Err(CliError::Usage(format!("unknown option --{}", name))),
+            (_, Some(_)) => options.push((name.to_string(), value)),
+        }
+    }
+
+    let command = command.ok_or_else(|| CliError::Usage(String::from("no command given")))?;
+    Ok(Invocation { file, today, command, args: positional, options })
+}
+
+// =============================================================================
+// COMMANDS
+// =============================================================================
+
+fn execute(inv: Invocation) -> Result<String, CliError> {
+    let mut library = load(&inv)?;
+    let today = inv.today;
+
+    let output = match inv.command.as_str() {
+        "add-book" => {
+            let genre = match inv.option("genre") {
+                Some(name) => name.parse().map_err(|e| CliError::Usage(format!("{}", e)))?,
+                None => Genre::Fiction,
+            };
+            let mut book = Book::try_new(library.generate_id(), inv.arg(0, "title")?, genre).map_err(|e| CliError::Usage(e.to_string()))?;
+            book.author = inv.option("author").map(String::from);
+            book.isbn = inv.option("isbn").map(String::from);
+            let message = format!("Added book #{}: {}", book.id(), book.title);
+            library.add_book(book).map_err(|e| CliError::Failed(e.to_string()))?;
+            save(&inv, &library)?;
+            message
+        }
+        "add-member" => {
+            let tier = match inv.option("tier") {
+                Some(name) => name.parse().map_err(|e| CliError::Usage(format!("{}", e)))?,
+                None => MembershipTier::Basic,
+            };
+            let name = inv.arg(0, "name")?;
+            let id = library.register_member_auto(name, tier).map_err(|e| match e {
+                LibraryError::Invalid(e) => CliError::Usage(e.to_string()),
+                e => CliError::Failed(e.to_string()),
+            })?;
+            let message = format!("Registered member #{}: {}", id, name);
+            save(&inv, &library)?;
+            message
+        }
+        "checkout" => {
+            let (member_id, book_id) = (inv.id_arg(0, "member-id")?, inv.id_arg(1, "book-id")?);
+            let due = library
+                .checkout(member_id, book_id, today)
+                .map_err(|e| CliError::Failed(e.to_string()))?;
+            save(&inv, &library)?;
+            format!("Book #{} checked out to member #{}, due {}", book_id, member_id, due)
+        }
+        "return" => {
+            let (member_id, book_id) = (inv.id_arg(0, "member-id")?, inv.id_arg(1, "book-id")?);
+            let fee = library
+                .return_book(member_id, book_id, today)
+                .map_err(|e| CliError::Failed(e.to_string()))?;
+            save(&inv, &library)?;
+            if fee.is_zero() {
+                format!("Book #{} returned", book_id)
+            } else {
+                format!("Book #{} returned late; {} charged", book_id, fee)
+            }
+        }
+        "renew" => {
+            let (member_id, book_id) = (inv.id_arg(0, "member-id")?, inv.id_arg(1, "book-id")?);
+            let due = library
+                .renew(member_id, book_id, today)
+                .map_err(|e| CliError::Failed(e.to_string()))?;
+            save(&inv, &library)?;
+            format!("Book #{} renewed for member #{}, now due {}", book_id, member_id, due)
+        }
+        "search" => search(&library, inv.arg(0, "query")?),
+        "report" => report(&library, today, inv.option("format").unwrap_or("text"))?,
+        other => return Err(CliError::Usage(format!("unknown command {:?}", other))),
+    };
+    Ok(output)
+}
+
+/// Books whose title or author contains `query`, ignoring case, or the
+/// closest titles if nothing does.
+fn search(library: &Library, query: &str) -> String {
+    let query = query.to_lowercase();
+    let mut out = String::new();
+    for book in library.catalog() {
+        let author = book.author.as_deref().unwrap_or("");
+        if !book.title.to_lowercase().contains(&query) && !author.to_lowercase().contains(&query) {
+            continue;
+        }
+        let status = match library.borrower(book.id()) {
+            Some(id) => format!("on loan to member #{}", id),
+            None => String::from("on the shelf"),
+        };
+        out.push_str(&format!("#{} {} ({}) - {}\n", book.id(), book.title, book.genre, status));
+    }
+    if out.is_empty() {
+        out = format!("No books match {:?}", query);
+        let suggestions: Vec<&str> = library.search_fuzzy(&query, 3).iter().map(|m| m.book.title.as_str()).collect();
+        if !suggestions.is_empty() {
+            out.push_str(&format!("; did you mean {}?", suggestions.join(", ")));
+        }
+    }
+    out
+}
+
+/// The standard report, plus the loans overdue as of `today`, in `format`.
+fn report(library: &Library, today: NaiveDate, format: &str) -> Result<String, CliError> {
+    let mut sections = standard_sections();
+    sections.push(Box::new(OverdueLoans { as_of: today }));
+    let report = library.build_report(&sections);
+    match format {
+        "text" => Ok(report.to_text()),
+        "markdown" => Ok(report.to_markdown()),
+        "html" => Ok(report.to_html()),
+        other => Err(CliError::Usage(format!("unknown report format {:?}", other))),
+    }
+}
+
+// =============================================================================
+// HELPERS
+// =============================================================================
+
+/// The saved library, or an empty one if nothing has been saved yet.
+fn load(inv: &Invocation) -> Result<Library, CliError> {
+    Ok(FileStorage::new(&inv.file).load(LibraryConfig::default())?)
+}
+
+/// Saves through [`FileStorage`], so a failed save leaves the old file intact.
+fn save(inv: &Invocation, library: &Library) -> Result<(), CliError> {
+    Ok(FileStorage::new(&inv.file).save(library, inv.today)?)
+}
+
@@ -0,0 +1,304 @@
+//! Loading a [`LibraryConfig`] from a TOML file or the environment.
+//!
+//! This file is a submodule of the *inline* `config` module in `lib.rs`:
+//! `mod load;` inside `pub mod config { ... }` makes Rust look for
+//! `src/config/load.rs`, so inline and file modules can be mixed freely.
+//!
+//! Both sources use the same settings; anything left out keeps its
+//! default. Amounts are written in major units (`0.25` is 25 cents) in the
+//! library's currency.
+//!
+//! | TOML key             | Environment variable         |
+//! |----------------------|------------------------------|
+//! | `name`               | `LIBRARY_NAME`               |
+//! | `max_borrowed_books` | `LIBRARY_MAX_BORROWED_BOOKS` |
+//! | `late_fee_per_day`   | `LIBRARY_LATE_FEE_PER_DAY`   |
+//! | `grace_days`         | `LIBRARY_GRACE_DAYS`         |
+//! | `first_day_percent`  | `LIBRARY_FIRST_DAY_PERCENT`  |
+//! | `fee_cap_basic`      | `LIBRARY_FEE_CAP_BASIC`      |
+//! | `fee_cap_silver`     | `LIBRARY_FEE_CAP_SILVER`     |
+//! | `fee_cap_gold`       | `LIBRARY_FEE_CAP_GOLD`       |
+//! | `fee_cap_platinum`   | `LIBRARY_FEE_CAP_PLATINUM`   |
+//! | `replacement_cost`   | `LIBRARY_REPLACEMENT_COST`   |
+//! | `max_unpaid_fines`   | `LIBRARY_MAX_UNPAID_FINES`   |
+//! | `digital_loan_days`  | `LIBRARY_DIGITAL_LOAN_DAYS`  |
+//! | `max_renewals`       | `LIBRARY_MAX_RENEWALS`       |
+//! | `hold_pickup_days`   | `LIBRARY_HOLD_PICKUP_DAYS`   |
+//! | `lost_after_days`    | `LIBRARY_LOST_AFTER_DAYS`    |
+//! | `max_books`          | `LIBRARY_MAX_BOOKS`          |
+//! | `max_members`        | `LIBRARY_MAX_MEMBERS`        |
+//!
+//! The capacity limits `max_books` and `max_members` are off unless set.
+//! A `fee_cap_*` setting replaces that tier's built-in
+//! [`fee_cap`](crate::MembershipTier::fee_cap).
+
+use std::fmt;
+
+use super::fees::CURRENCY;
+use super::LibraryConfig;
+use crate::member::MembershipTier;
+use crate::money::Money;
+
+/// Prefix of the environment variables read by [`LibraryConfig::from_env`].
+pub const ENV_PREFIX: &str = "LIBRARY_";
+
+// =============================================================================
+// ERRORS
+// =============================================================================
+
+/// Why a configuration couldn't be loaded.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ConfigError {
+    /// The TOML itself is malformed.
+    Syntax(String),
+    /// A TOML key that isn't a setting, most likely a typo.
+    UnknownKey(String),
+    /// A setting whose value can't be used.
+    Invalid { key: String, message: String },
+}
+
+impl fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ConfigError::Syntax(message) => write!(f, "Invalid TOML: {}", message),
+            ConfigError::UnknownKey(key) => write!(f, "Unknown setting {}", key),
+            ConfigError::Invalid { key, message } => write!(f, "Invalid {}: {}", key, message),
+        }
+    }
+}
+
+impl std::error::Error for ConfigError {}
+
+fn invalid(key: &str, message: impl Into<String>) -> ConfigError {
+    ConfigError::Invalid {
+        key: String::from(key),
+        message: message.into(),
+    }
+}
+
+// =============================================================================
+// LOADING
+// =============================================================================
+
+impl LibraryConfig {
+    /// Reads settings from a TOML document over the defaults.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use module_8::{LibraryConfig, Money};
+    ///
+    /// let cfg = LibraryConfig::from_toml("name = \"Branch\"\nlate_fee_per_day = 0.5\nmax_renewals = 0").unwrap();
+    /// assert_eq!((cfg.name.as_str(), cfg.late_fee_per_day), ("Branch", Money::from_cents(50)));
+    /// assert_eq!(cfg.max_renewals, 0);
+    ///
+    /// let err = LibraryConfig::from_toml("digital_loan_days = 0").unwrap_err();
+    /// assert_eq!(err.to_string(), "Invalid digital_loan_days: must be at least 1");
+    /// ```
+    pub fn from_toml(text: &str) -> Result<Self, ConfigError> {
+        let table: toml::Table = text.parse().map_err(|e: toml::de::Error| ConfigError::Syntax(e.message().to_string()))?;
+        let mut config = LibraryConfig::default();
+        for (key, value) in &table {
+            let text = match value {
+                toml::Value::String(s) => s.clone(),
+                toml::Value::Integer(n) => n.to_string(),
+                toml::Value::Float(x) => x.to_string(),
+                other => return Err(invalid(key, format!("expected a string or number, not {}", other.type_str()))),
+            };
+            config.set(key, &text)?;
+        }
+        config.validate()?;
+        Ok(config)
+    }
+
+    /// Reads `LIBRARY_*` environment variables over the defaults. Other
+    /// variables, including unknown `LIBRARY_*` ones, are ignored.
+    pub fn from_env() -> Result<Self, ConfigError> {
+        LibraryConfig::default().with_env_vars(std::env::vars())
+    }
+
+    /// Applies `LIBRARY_*` variables from `vars` over this configuration,
+    /// e.g. to let the environment override a TOML file.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use module_8::LibraryConfig;
+    ///
+    /// let file = LibraryConfig::from_toml("max_borrowed_books = 8").unwrap();
+    /// let vars = [("LIBRARY_MAX_BORROWED_BOOKS", "3"), ("HOME", "/root")];
+    /// let cfg = file.with_env_vars(vars.map(|(k, v)| (k.to_string(), v.to_string()))).unwrap();
+    /// assert_eq!(cfg.max_borrowed_books, 3);
+    /// ```
+    pub fn with_env_vars(mut self, vars: impl IntoIterator<Item = (String, String)>) -> Result<Self, ConfigError> {
+        for (name, value) in vars {
+            let Some(key) = name.strip_prefix(ENV_PREFIX) else { continue };
+            let key = key.to_lowercase();
+            if KEYS.contains(&key.as_str()) {
+                self.set(&key, &value)?;
+            }
+        }
+        self.validate()?;
+        Ok(self)
+    }
+
+    /// Checks for values the library can't run with.
+    pub fn validate(&self) -> Result<(), ConfigError> {
+        if self.name.trim().is_empty() {
+            return Err(invalid("name", "must not be empty"));
+        }
+        if self.max_borrowed_books == 0 {
+            return Err(invalid("max_borrowed_books", "must be at least 1"));
+        }
+        if self.digital_loan_days == 0 {
+            return Err(invalid("digital_loan_days", "must be at least 1"));
+        }
+        if self.first_day_percent > 100 {
+            return Err(invalid("first_day_percent", "must be at most 100"));
+        }
+        if self.hold_pickup_days == 0 {
+            return Err(invalid("hold_pickup_days", "must be at least 1"));
+        }
+        if self.max_books == Some(0) {
+            return Err(invalid("max_books", "must be at least 1"));
+        }
+        if self.max_members == Some(0) {
+            return Err(invalid("max_members", "must be at least 1"));
+        }
+        Ok(())
+    }
+
+    fn set(&mut self, key: &str, value: &str) -> Result<(), ConfigError> {
+        let value = value.trim();
+        let number = |value: &str| -> Result<u32, ConfigError> {
+            value.parse().map_err(|_| invalid(key, format!("{:?} is not a whole number", value)))
+        };
+        match key {
+            "name" => self.name = String::from(value),
+            "max_borrowed_books" => self.max_borrowed_books = number(value)? as usize,
+            "late_fee_per_day" => self.late_fee_per_day = parse_amount(key, value)?,
+            "grace_days" => self.grace_days = number(value)?,
+            "first_day_percent" => {
+                self.first_day_percent = number(value)?
+                    .try_into()
+                    .map_err(|_| invalid(key, "must be at most 100"))?
+            }
+            "replacement_cost" => self.replacement_cost = parse_amount(key, value)?,
+            "max_unpaid_fines" => self.max_unpaid_fines = parse_amount(key, value)?,
+            "digital_loan_days" => self.digital_loan_days = number(value)?,
+            "max_renewals" => self.max_renewals = number(value)?,
+            "hold_pickup_days" => self.hold_pickup_days = number(value)?,
+            "lost_after_days" => self.lost_after_days = number(value)?,
+            "max_books" => self.max_books = Some(number(value)? as usize),
+            "max_members" => self.max_members = Some(number(value)? as usize),
+            _ => {
+                let tier = key
+                    .strip_prefix("fee_cap_")
+                    .and_then(|name| name.parse::<MembershipTier>().ok())
+                    .filter(|_| KEYS.contains(&key))
+                    .ok_or_else(|| ConfigError::UnknownKey(String::from(key)))?;
+                self.fee_caps.insert(tier.to_string(), parse_amount(key, value)?);
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Every setting, as named in TOML.
+const KEYS: [&str; 17] = [
+    "name",
+    "max_borrowed_books",
+    "late_fee_per_day",
+    "grace_days",
+    "first_day_percent",
+    "replacement_cost",
+    "max_unpaid_fines",
+    "digital_loan_days",
+    "max_renewals",
+    "hold_pickup_days",
+    "lost_after_days",
+    "max_books",
+    "max_members",
+    "fee_cap_basic",
+    "fee_cap_silver",
+    "fee_cap_gold",
+    "fee_cap_platinum",
+];
+
+/// An amount such as `2`, `0.5` or `12.25` in the library's currency.
+fn parse_amount(key: &str, text: &str) -> Result<Money, ConfigError> {
+    if text.starts_with('-') {
+        return Err(invalid(key, "must not be negative"));
+    }
+    let malformed = || invalid(key, format!("{:?} is not an amount like 0.25", text));
+    let (whole, fraction) = text.split_once('.').unwrap_or((text, ""));
+    if whole.is_empty() || fraction.len() > 2 || !(whole.chars().chain(fraction.chars()).all(|c| c.is_ascii_digit())) {
+        return Err(malformed());
+    }
+    let whole: u64 = whole.parse().map_err(|_| malformed())?;
+    let cents: u64 = format!("{:0<2}", fraction).parse().map_err(|_| malformed())?;
+    whole
+        .checked_mul(100)
+        .and_then(|c| c.checked_add(cents))
+        .map(|c| Money::new(c, CURRENCY))
+        .ok_or_else(malformed)
+}
+
+// =============================================================================
+// TESTS
+// =============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_from_toml_overrides_defaults() {
+        let cfg = LibraryConfig::from_toml(
+            r#"
+            name = "Branch Library"
+            max_borrowed_books = 8
+            late_fee_per_day = "0.10"
+            replacement_cost = 35
+            max_books = 2000
+            hold_pickup_days = 5
+            lost_after_days = 90
+            grace_days = 2
+            fee_cap_gold = 4
+            "#,
+        )
+        .unwrap();
+        assert_eq!(cfg.name, "Branch Library");
+        assert_eq!(cfg.max_borrowed_books, 8);
+        assert_eq!(cfg.late_fee_per_day, Money::from_cents(10));
+        assert_eq!(cfg.replacement_cost, Money::from_cents(3500));
+        assert_eq!(cfg.max_renewals, LibraryConfig::default().max_renewals);
+        assert_eq!((cfg.max_books, cfg.max_members), (Some(2000), None));
+        assert_eq!((cfg.hold_pickup_days, cfg.lost_after_days), (5, 90));
+        assert_eq!((cfg.grace_days, cfg.first_day_percent), (2, 100));
+        assert_eq!(cfg.fee_cap(&MembershipTier::Gold), Some(Money::from_cents(400)));
+        assert_eq!(cfg.fee_cap(&MembershipTier::Silver), Some(MembershipTier::Silver.fee_cap()));
+    }
+
+    #[test]
+    fn test_nonsense_is_rejected() {
+        let err = |text: &str| LibraryConfig::from_toml(text).unwrap_err().to_string();
+        assert_eq!(err("late_fee_per_day = -0.25"), "Invalid late_fee_per_day: must not be negative");
+        assert_eq!(err("late_fee_per_day = 0.125"), "Invalid late_fee_per_day: \"0.125\" is not an amount like 0.25");
+        assert_eq!(err("max_borrowed_books = 0"), "Invalid max_borrowed_books: must be at least 1");
+        assert_eq!(err("max_members = 0"), "Invalid max_members: must be at least 1");
+        assert_eq!(err("first_day_percent = 150"), "Invalid first_day_percent: must be at most 100");
+        assert_eq!(err("first_day_percent = 300"), "Invalid first_day_percent: must be at most 100");
+        assert_eq!(err("max_renewals = 2.5"), "Invalid max_renewals: \"2.5\" is not a whole number");
+        assert_eq!(err("name = \" \""), "Invalid name: must not be empty");
+        assert_eq!(err("late_fee = 1"), "Unknown setting late_fee");
+        assert_eq!(err("fee_cap_diamond = 1"), "Unknown setting fee_cap_diamond");
+        assert_eq!(err("fee_cap_Gold = 1"), "Unknown setting fee_cap_Gold");
+        assert_eq!(err("name = [1]"), "Invalid name: expected a string or number, not array");
+        assert!(err("name = ").starts_with("Invalid TOML: "));
+
+        let vars = [(String::from("LIBRARY_DIGITAL_LOAN_DAYS"), String::from("0"))];
+        let env = LibraryConfig::default().with_env_vars(vars).unwrap_err();
+        assert_eq!(env.to_string(), "Invalid digital_loan_days: must be at least 1");
+    }
+}
@@ -0,0 +1,371 @@
+//! Consortium module - several independent libraries searched as one.
+//!
+//! A [`Consortium`] holds its member libraries by name. Some are run
+//! locally (a full [`Library`]); others are only known through a
+//! [`LibrarySnapshot`] received from elsewhere. Search covers all of them
+//! and says where each hit came from, including how old a remote
+//! snapshot's data is.
+//!
+//! Copies move between local libraries through transfer requests: one
+//! library asks for a book, and the request is later completed (the copy
+//! changes hands) or rejected. Remote libraries can be searched but not
+//! transferred to or from, since their data is read-only here.
+
+use std::fmt;
+
+use chrono::NaiveDate;
+
+use crate::book::Book;
+use crate::error::LibraryError;
+use crate::index::normalize_title;
+use crate::snapshot::LibrarySnapshot;
+use crate::Library;
+
+// =============================================================================
+// SOURCES AND SEARCH RESULTS
+// =============================================================================
+
+/// One member library of a consortium. Both kinds are boxed, as they are
+/// large and a consortium only moves them around.
+pub enum Source {
+    /// A library run in this process.
+    Local(Box<Library>),
+    /// Another library's data as of its snapshot date.
+    Remote(Box<LibrarySnapshot>),
+}
+
+impl Source {
+    fn catalog(&self) -> Box<dyn Iterator<Item = &Book> + '_> {
+        match self {
+            Source::Local(library) => Box::new(library.catalog()),
+            Source::Remote(snapshot) => Box::new(snapshot.catalog()),
+        }
+    }
+}
+
+/// A search result and where it came from.
+#[derive(Debug, Clone, Copy)]
+pub struct SearchHit<'a> {
+    /// Name of the library holding the copy.
+    pub library: &'a str,
+    pub book: &'a Book,
+    /// For remote hits, the date their data is from; `None` for local ones.
+    pub as_of: Option<NaiveDate>,
+}
+
+// =============================================================================
+// TRANSFERS
+// =============================================================================
+
+/// Where a transfer request stands.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TransferStatus {
+    Pending,
+    /// The copy moved; `book_id` is its ID in the receiving library.
+    Completed { book_id: u64 },
+    Rejected,
+}
+
+/// A request to move one copy from one member library to another.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TransferRequest {
+    pub id: u64,
+    pub book_id: u64,
+    pub from: String,
+    pub to: String,
+    pub requested_on: NaiveDate,
+    pub status: TransferStatus,
+}
+
+/// Errors from consortium operations.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ConsortiumError {
+    /// No member library has this name.
+    UnknownLibrary(String),
+    /// A member library with this name already exists.
+    DuplicateLibrary(String),
+    /// The library is only known through a snapshot.
+    RemoteLibrary(String),
+    /// No transfer request has this ID.
+    UnknownRequest(u64),
+    /// The request was already completed or rejected.
+    RequestClosed(u64),
+    /// A member library refused the operation.
+    Library(LibraryError),
+}
+
+impl fmt::Display for ConsortiumError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ConsortiumError::UnknownLibrary(name) => write!(f, "No member library named {}", name),
+            ConsortiumError::DuplicateLibrary(name) => write!(f, "A library named {} already belongs to the consortium", name),
+            ConsortiumError::RemoteLibrary(name) => write!(f, "{} is a remote library; its data is read-only", name),
+            ConsortiumError::UnknownRequest(id) => write!(f, "No transfer request with ID {}", id),
+            ConsortiumError::RequestClosed(id) => write!(f, "Transfer request {} is no longer pending", id),
+            ConsortiumError::Library(e) => e.fmt(f),
+        }
+    }
+}
+
+impl std::error::Error for ConsortiumError {}
+
+impl From<LibraryError> for ConsortiumError {
+    fn from(e: LibraryError) -> Self {
+        ConsortiumError::Library(e)
+    }
+}
+
+// =============================================================================
+// CONSORTIUM
+// =============================================================================
+
+/// Named member libraries plus the transfer requests between them.
+///
+/// # Examples
+///
+/// ```
+/// use chrono::NaiveDate;
+/// use module_8::consortium::{Consortium, TransferStatus};
+/// use module_8::{Book, Genre, Library};
+///
+/// let today = NaiveDate::from_ymd_opt(2024, 3, 1).unwrap();
+/// let mut north = Library::new();
+/// north.add_book(Book::new(1, "Dune", Genre::SciFi)).unwrap();
+/// let mut south = Library::new();
+/// south.add_book(Book::new(1, "Emma", Genre::Fiction)).unwrap();
+///
+/// let mut consortium = Consortium::new();
+/// consortium.add_library("North", north).unwrap();
+/// consortium.add_remote("Island", south.snapshot(today)).unwrap();
+/// consortium.add_library("South", south).unwrap();
+///
+/// let hits = consortium.search("dune");
+/// assert_eq!((hits[0].library, hits[0].book.id()), ("North", 1));
+///
+/// let request = consortium.request_transfer("North", 1, "South", today).unwrap();
+/// // South already has a book 1, so the copy is renumbered on arrival
+/// assert_eq!(consortium.complete_transfer(request), Ok(2));
+/// assert_eq!(consortium.transfers()[0].status, TransferStatus::Completed { book_id: 2 });
+/// ```
+#[derive(Default)]
+pub struct Consortium {
+    sources: Vec<(String, Source)>,
+    transfers: Vec<TransferRequest>,
+}
+
+impl Consortium {
+    pub fn new() -> Self {
+        Consortium::default()
+    }
+
+    /// Adds a locally run library under `name`.
+    pub fn add_library(&mut self, name: &str, library: Library) -> Result<(), ConsortiumError> {
+        self.add_source(name, Source::Local(Box::new(library)))
+    }
+
+    /// Adds a remote library known only through a snapshot. Adding a newer
+    /// snapshot for the same library means removing the old one first.
+    pub fn add_remote(&mut self, name: &str, snapshot: LibrarySnapshot) -> Result<(), ConsortiumError> {
+        self.add_source(name, Source::Remote(Box::new(snapshot)))
+    }
+
+    fn add_source(&mut self, name: &str, source: Source) -> Result<(), ConsortiumError> {
+        if self.source(name).is_some() {
+            return Err(ConsortiumError::DuplicateLibrary(name.to_string()));
+        }
+        self.sources.push((name.to_string(), source));
+        Ok(())
+    }
+
+    /// Takes a member library out of the consortium. Pending transfers
+    /// involving it are rejected.
+    pub fn remove(&mut self, name: &str) -> Option<Source> {
+        let pos = self.sources.iter().position(|(n, _)| n == name)?;
+        for request in &mut self.transfers {
+            if request.status == TransferStatus::Pending && (request.from == name || request.to == name) {
+                request.status = TransferStatus::Rejected;
+            }
+        }
+        Some(self.sources.remove(pos).1)
+    }
+
+    /// Member library names, in the order they joined.
+    pub fn names(&self) -> impl Iterator<Item = &str> {
+        self.sources.iter().map(|(name, _)| name.as_str())
+    }
+
+    pub fn source(&self, name: &str) -> Option<&Source> {
+        self.sources.iter().find(|(n, _)| n == name).map(|(_, s)| s)
+    }
+
+    /// A locally run member library, for day-to-day operations.
+    pub fn library_mut(&mut self, name: &str) -> Result<&mut Library, ConsortiumError> {
+        match self.sources.iter_mut().find(|(n, _)| n == name) {
+            Some((_, Source::Local(library))) => Ok(library),
+            Some((_, Source::Remote(_))) => Err(ConsortiumError::RemoteLibrary(name.to_string())),
+            None => Err(ConsortiumError::UnknownLibrary(name.to_string())),
+        }
+    }
+
+    /// Every copy whose title contains `query`, ignoring case and
+    /// punctuation, in every member library (on the shelf or on loan).
+    ///
+    /// Hits are grouped by library, in the order the libraries joined.
+    pub fn search(&self, query: &str) -> Vec<SearchHit<'_>> {
+        let query = normalize_title(query);
+        let mut hits = Vec::new();
+        for (name, source) in &self.sources {
+            let as_of = match source {
+                Source::Local(_) => None,
+                Source::Remote(snapshot) => Some(snapshot.taken_on),
+            };
+            for book in source.catalog() {
+                if normalize_title(&book.title).contains(&query) {
+                    hits.push(SearchHit { library: name, book, as_of });
+                }
+            }
+        }
+        hits
+    }
+
+    /// Asks for copy `book_id` of library `from` to move to library `to`,
+    /// returning the request's ID.
+    ///
+    /// Both libraries must be local and the copy must exist now; it may be
+    /// on loan, in which case the request waits until it's back.
+    pub fn request_transfer(
+        &mut self,
+        from: &str,
+        book_id: u64,
+        to: &str,
+        today: NaiveDate,
+    ) -> Result<u64, ConsortiumError> {
+        self.library_mut(to)?;
+        let source = self.library_mut(from)?;
+        if source.get_book(book_id).is_none() {
+            return Err(LibraryError::BookNotFound(book_id).into());
+        }
+        let id = self.transfers.len() as u64 + 1;
+        self.transfers.push(TransferRequest {
+            id,
+            book_id,
+            from: from.to_string(),
+            to: to.to_string(),
+            requested_on: today,
+            status: TransferStatus::Pending,
+        });
+        Ok(id)
+    }
+
+    /// Moves the requested copy, returning its ID in the receiving library.
+    ///
+    /// The copy keeps its ID unless the receiving library already uses it,
+    /// in which case it gets that library's next free ID. Fails, leaving the
+    /// request pending, while the copy is on loan or the receiving library
+    /// is full.
+    pub fn complete_transfer(&mut self, request_id: u64) -> Result<u64, ConsortiumError> {
+        let request = self.pending(request_id)?.clone();
+        self.library_mut(&request.to)?.check_capacity(1, 0)?;
+        let mut book = self.library_mut(&request.from)?.remove_book(request.book_id)?;
+        let target = self.library_mut(&request.to)?;
+        if target.get_book(book.id()).is_some() {
+            book.set_id(target.generate_id());
+        }
+        let new_id = book.id();
+        target.insert_book(book);
+        self.transfers[request_id as usize - 1].status = TransferStatus::Completed { book_id: new_id };
+        Ok(new_id)
+    }
+
+    /// Turns a pending request down.
+    pub fn reject_transfer(&mut self, request_id: u64) -> Result<(), ConsortiumError> {
+        self.pending(request_id)?;
+        self.transfers[request_id as usize - 1].status = TransferStatus::Rejected;
+        Ok(())
+    }
+
+    /// Every transfer request, oldest first.
+    pub fn transfers(&self) -> &[TransferRequest] {
+        &self.transfers
+    }
+
+    fn pending(&self, request_id: u64) -> Result<&TransferRequest, ConsortiumError> {
+        let request = request_id
+            .checked_sub(1)
+            .and_then(|i| self.transfers.get(i as usize))
+            .ok_or(ConsortiumError::UnknownRequest(request_id))?;
+        if request.status != TransferStatus::Pending {
+            return Err(ConsortiumError::RequestClosed(request_id));
+        }
+        Ok(request)
+    }
+}
+
+// =============================================================================
+// TESTS
+// =============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Genre, Member, MembershipTier};
+
+    fn date(day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(2024, 3, day).unwrap()
+    }
+
+    fn consortium() -> Consortium {
+        let mut north = Library::new();
+        north.add_book(Book::new(1, "The Rust Book", Genre::Technical)).unwrap();
+        north.register_member(Member::new(10, "Alice", MembershipTier::Basic)).unwrap();
+        let mut remote = Library::new();
+        remote.add_book(Book::new(7, "Rust in Action", Genre::Technical)).unwrap();
+
+        let mut consortium = Consortium::new();
+        consortium.add_library("North", north).unwrap();
+        consortium.add_library("South", Library::new()).unwrap();
+        consortium.add_remote("Remote", remote.snapshot(date(1))).unwrap();
+        consortium
+    }
+
+    #[test]
+    fn test_search_reports_provenance() {
+        let c = consortium();
+        let hits: Vec<(&str, u64, Option<NaiveDate>)> =
+            c.search("RUST").iter().map(|h| (h.library, h.book.id(), h.as_of)).collect();
+        assert_eq!(hits, [("North", 1, None), ("Remote", 7, Some(date(1)))]);
+        assert!(c.search("python").is_empty());
+    }
+
+    #[test]
+    fn test_transfer_waits_for_loan_and_closes_once() {
+        let mut c = consortium();
+        c.library_mut("North").unwrap().checkout(10, 1, date(2)).unwrap();
+        let request = c.request_transfer("North", 1, "South", date(2)).unwrap();
+
+        let on_loan = c.complete_transfer(request);
+        assert_eq!(on_loan, Err(ConsortiumError::Library(LibraryError::BookUnavailable(1))));
+        assert_eq!(c.transfers()[0].status, TransferStatus::Pending);
+
+        c.library_mut("North").unwrap().return_book(10, 1, date(3)).unwrap();
+        assert_eq!(c.complete_transfer(request), Ok(1));
+        assert_eq!(c.complete_transfer(request), Err(ConsortiumError::RequestClosed(request)));
+        assert!(c.library_mut("South").unwrap().find_book(1).is_some());
+    }
+
+    #[test]
+    fn test_remote_and_unknown_libraries() {
+        let mut c = consortium();
+        assert_eq!(
+            c.request_transfer("Remote", 7, "North", date(2)),
+            Err(ConsortiumError::RemoteLibrary(String::from("Remote")))
+        );
+        assert_eq!(
+            c.add_library("North", Library::new()),
+            Err(ConsortiumError::DuplicateLibrary(String::from("North")))
+        );
+        let request = c.request_transfer("North", 1, "South", date(2)).unwrap();
+        assert!(c.remove("South").is_some());
+        assert_eq!(c.transfers()[request as usize - 1].status, TransferStatus::Rejected);
+    }
+}
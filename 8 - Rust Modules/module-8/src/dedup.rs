@@ -0,0 +1,205 @@
+//! Dedup module - spotting books that are already catalogued.
+//!
+//! Imports and manual entry often bring in a book the library already has.
+//! A book is a likely duplicate of a catalogued one when both have the same
+//! ISBN (ignoring hyphens and case) *and* the same title (ignoring case and
+//! punctuation). A book without an ISBN is never treated as a duplicate.
+//!
+//! `Library::add_book` (and `add_books`) refuse a likely duplicate with
+//! `LibraryError::LikelyDuplicate`. [`Library::add_book_deduplicated`] lets
+//! the caller merge it into the existing book instead, or catalogue it on
+//! purpose as another copy.
+//!
+//! ```
+//! use module_8::dedup::{AddOutcome, OnDuplicate};
+//! use module_8::{Book, Genre, Library};
+//!
+//! let mut lib = Library::new();
+//! let dune = Book::builder(1, "Dune").genre(Genre::SciFi).isbn("978-0441172719").build();
+//! assert_eq!(lib.add_book_deduplicated(dune, OnDuplicate::Reject), Ok(AddOutcome::Added(1)));
+//!
+//! let again = Book::builder(2, "DUNE").isbn("9780441172719").author("Frank Herbert").build();
+//! assert_eq!(lib.add_book_deduplicated(again, OnDuplicate::Merge), Ok(AddOutcome::MergedIntoExisting(1)));
+//! assert_eq!(lib.book_count(), 1);
+//! assert_eq!(lib.find_book(1).unwrap().author.as_deref(), Some("Frank Herbert"));
+//! ```
+
+use crate::book::Book;
+use crate::error::LibraryError;
+use crate::index::{normalize_isbn, normalize_title};
+use crate::Library;
+
+// =============================================================================
+// OUTCOMES
+// =============================================================================
+
+/// What to do with a book that duplicates a catalogued one.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum OnDuplicate {
+    /// Fill in the existing book's missing details and tags from the new one.
+    Merge,
+    /// Leave the catalog untouched.
+    Reject,
+    /// Catalogue it anyway, as another copy.
+    AddCopy,
+}
+
+/// What [`Library::add_book_deduplicated`] did with a book.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum AddOutcome {
+    /// Catalogued as a new book with this ID.
+    Added(u64),
+    /// Merged into the existing book with this ID.
+    MergedIntoExisting(u64),
+    /// Not added, because it duplicates the existing book with this ID.
+    DuplicateRejected(u64),
+}
+
+impl AddOutcome {
+    /// The ID the book ended up under, or the one it duplicates.
+    pub fn id(&self) -> u64 {
+        match *self {
+            AddOutcome::Added(id) | AddOutcome::MergedIntoExisting(id) | AddOutcome::DuplicateRejected(id) => id,
+        }
+    }
+}
+
+// =============================================================================
+// LIBRARY INTEGRATION
+// =============================================================================
+
+impl Library {
+    /// The catalogued book (on the shelf or on loan) that `book` duplicates,
+    /// if any.
+    pub fn find_duplicate(&self, book: &Book) -> Option<&Book> {
+        let isbn = normalize_isbn(book.isbn.as_deref()?);
+        self.find_by_title(&book.title)
+            .into_iter()
+            .find(|existing| existing.isbn.as_deref().map(normalize_isbn).as_ref() == Some(&isbn))
+    }
+
+    /// Adds a book unless it duplicates a catalogued one, in which case
+    /// `on_duplicate` decides whether it is merged, rejected or added as
+    /// another copy.
+    ///
+    /// # Errors
+    ///
+    /// [`LibraryError::DuplicateBook`] if the book is added but its ID is
+    /// already in use, and [`LibraryError::CapacityReached`] if it is added
+    /// but the catalog is full.
+    pub fn add_book_deduplicated(&mut self, book: Book, on_duplicate: OnDuplicate) -> Result<AddOutcome, LibraryError> {
+        let Some(existing_id) = self.find_duplicate(&book).map(Book::id) else {
+            let id = book.id();
+            self.add_book(book)?;
+            return Ok(AddOutcome::Added(id));
+        };
+        match on_duplicate {
+            OnDuplicate::Reject => Ok(AddOutcome::DuplicateRejected(existing_id)),
+            OnDuplicate::AddCopy => {
+                let id = book.id();
+                self.add_book_allowing_duplicates(book)?;
+                Ok(AddOutcome::Added(id))
+            }
+            OnDuplicate::Merge => {
+                let existing = self.book_mut(existing_id).ok_or(LibraryError::BookNotFound(existing_id))?;
+                merge_details(existing, book);
+                Ok(AddOutcome::MergedIntoExisting(existing_id))
+            }
+        }
+    }
+}
+
+/// What two books share when one is a likely duplicate of the other: the
+/// normalized ISBN and title. `None` for a book without an ISBN.
+pub(crate) fn duplicate_key(book: &Book) -> Option<(String, String)> {
+    Some((normalize_isbn(book.isbn.as_deref()?), normalize_title(&book.title)))
+}
+
+/// Copies the details `existing` is missing from `incoming`, plus its tags.
+/// Anything `existing` already has wins.
+fn merge_details(existing: &mut Book, incoming: Book) {
+    fn fill<T>(slot: &mut Option<T>, value: Option<T>) {
+        if slot.is_none() {
+            *slot = value;
+        }
+    }
+    for tag in incoming.tags() {
+        existing.add_tag(tag);
+    }
+    fill(&mut existing.author, incoming.author);
+    fill(&mut existing.year, incoming.year);
+    fill(&mut existing.publisher, incoming.publisher);
+    fill(&mut existing.pages, incoming.pages);
+    fill(&mut existing.language, incoming.language);
+    fill(&mut existing.summary, incoming.summary);
+}
+
+// =============================================================================
+// TESTS
+// =============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Genre, Member, MembershipTier};
+    use chrono::NaiveDate;
+
+    fn dune(id: u64, title: &str, isbn: &str) -> Book {
+        Book::builder(id, title).genre(Genre::SciFi).isbn(isbn).build()
+    }
+
+    #[test]
+    fn test_duplicates_need_title_and_isbn() {
+        let mut lib = Library::new();
+        lib.add_book(dune(1, "Dune", "0-441-17271-7")).unwrap();
+
+        assert_eq!(lib.find_duplicate(&dune(2, "  dune!", "044117271 7")).map(Book::id), Some(1));
+        assert!(lib.find_duplicate(&dune(2, "Dune Messiah", "0441172717")).is_none());
+        assert!(lib.find_duplicate(&dune(2, "Dune", "0441013597")).is_none());
+        assert!(lib.find_duplicate(&Book::new(2, "Dune", Genre::SciFi)).is_none());
+
+        let outcome = lib.add_book_deduplicated(dune(2, "DUNE", "0441172717"), OnDuplicate::Reject);
+        assert_eq!(outcome, Ok(AddOutcome::DuplicateRejected(1)));
+        assert_eq!(lib.book_count(), 1);
+        let outcome = lib.add_book_deduplicated(dune(2, "Dune", "0441013597"), OnDuplicate::Reject);
+        assert_eq!(outcome, Ok(AddOutcome::Added(2)));
+        let clash = lib.add_book_deduplicated(dune(2, "Emma", "0141439580"), OnDuplicate::Merge);
+        assert_eq!(clash, Err(LibraryError::DuplicateBook(2)));
+    }
+
+    #[test]
+    fn test_add_book_refuses_duplicates_unless_asked_for_a_copy() {
+        let mut lib = Library::new();
+        lib.add_book(dune(1, "Dune", "0-441-17271-7")).unwrap();
+        let err = lib.add_book(dune(2, "DUNE", "0441172717"));
+        assert_eq!(err, Err(LibraryError::LikelyDuplicate { book_id: 2, existing: 1 }));
+        assert_eq!(lib.book_count(), 1);
+
+        let outcome = lib.add_book_deduplicated(dune(2, "DUNE", "0441172717"), OnDuplicate::AddCopy);
+        assert_eq!(outcome, Ok(AddOutcome::Added(2)));
+        assert_eq!(lib.find_by_title("dune").len(), 2);
+    }
+
+    #[test]
+    fn test_merge_fills_gaps_even_on_loan() {
+        let mut lib = Library::new();
+        let mut original = Book::builder(1, "Dune").isbn("0441172717").year(1965).build();
+        original.add_tag("classic");
+        lib.add_book(original).unwrap();
+        lib.register_member(Member::new(10, "Alice", MembershipTier::Basic)).unwrap();
+        lib.checkout(10, 1, NaiveDate::from_ymd_opt(2024, 3, 1).unwrap()).unwrap();
+
+        let mut incoming = Book::builder(7, "Dune").isbn("0441172717").year(1990).author("Frank Herbert").pages(412).build();
+        incoming.add_tag("book-club");
+        let outcome = lib.add_book_deduplicated(incoming, OnDuplicate::Merge).unwrap();
+        assert_eq!(outcome, AddOutcome::MergedIntoExisting(1));
+        assert_eq!(outcome.id(), 1);
+
+        let merged = lib.get_book(1).unwrap();
+        assert_eq!(merged.year, Some(1965));
+        assert_eq!((merged.author.as_deref(), merged.pages), (Some("Frank Herbert"), Some(412)));
+        assert_eq!(merged.tags(), ["classic", "book-club"]);
+        assert!(lib.get_book(7).is_none());
+        assert_eq!(lib.check_invariants(), Ok(()));
+    }
+}
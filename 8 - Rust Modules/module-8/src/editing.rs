@@ -0,0 +1,224 @@
+//! Editing module - changing books and members in place.
+//!
+//! `find_book` and `find_member` only lend shared references, so fixing a
+//! typo in a title used to mean removing the book and adding it again. The
+//! methods here lend mutable ones instead. A book's title and ISBN are keys
+//! of the catalog's indexes, so a book is lent through a [`BookMut`] guard
+//! that re-indexes it when it is dropped: lookups by the new title or ISBN
+//! work straight away, and the old ones stop matching.
+//!
+//! ```
+//! use module_8::{Book, Genre, Library};
+//!
+//! let mut lib = Library::new();
+//! lib.add_book(Book::new(1, "Dnue", Genre::SciFi)).unwrap();
+//! lib.find_book_mut(1).unwrap().title = String::from("Dune");
+//! assert_eq!(lib.search_titles("dune")[0].id(), 1);
+//! assert!(lib.find_by_title("Dnue").is_empty());
+//!
+//! let pages = lib.update_book(1, |book| *book.pages.insert(412)).unwrap();
+//! assert_eq!(pages, 412);
+//! ```
+//!
+//! A book's ID can't be changed, and whether it is on loan is tracked by
+//! the library: check books out and in with `Library::checkout` and
+//! `Library::return_book`, not with `Book::borrow_book`.
+
+use std::ops::{Deref, DerefMut};
+
+use chrono::NaiveDate;
+
+use crate::book::Book;
+use crate::error::LibraryError;
+use crate::index::{CatalogIndex, IndexKeys};
+use crate::member::{Member, TierChangeError, TierPolicy};
+use crate::Library;
+
+// =============================================================================
+// BOOK GUARD
+// =============================================================================
+
+/// Mutable access to a catalogued book; re-indexes its title and ISBN when
+/// dropped. Dereferences to [`Book`].
+#[derive(Debug)]
+pub struct BookMut<'a> {
+    book: &'a mut Book,
+    index: &'a mut CatalogIndex,
+    keys: IndexKeys,
+}
+
+impl<'a> BookMut<'a> {
+    fn new(book: &'a mut Book, index: &'a mut CatalogIndex) -> Self {
+        let keys = IndexKeys::of(book);
+        BookMut { book, index, keys }
+    }
+}
+
+impl Deref for BookMut<'_> {
+    type Target = Book;
+
+    fn deref(&self) -> &Book {
+        self.book
+    }
+}
+
+impl DerefMut for BookMut<'_> {
+    fn deref_mut(&mut self) -> &mut Book {
+        self.book
+    }
+}
+
+impl Drop for BookMut<'_> {
+    fn drop(&mut self) {
+        self.index.rekey_book(&self.keys, self.book);
+    }
+}
+
+// =============================================================================
+// LIBRARY INTEGRATION
+// =============================================================================
+
+impl Library {
+    /// Mutable access to a catalogued book, on the shelf or on loan, like
+    /// [`find_book`](Library::find_book).
+    pub fn find_book_mut(&mut self, id: u64) -> Option<BookMut<'_>> {
+        let book = self.books.get_mut(&id)?;
+        Some(BookMut::new(book, &mut self.index))
+    }
+
+    /// Mutable access to a registered member, like
+    /// [`find_member`](Library::find_member).
+    ///
+    /// Meant for their details: name, contact, tier and expiry. Loans and
+    /// fines belong to the library's ledgers, so borrow, return and pay
+    /// through `Library` rather than the member, and lower their borrow
+    /// limit with [`downgrade_tier`](Library::downgrade_tier).
+    pub fn find_member_mut(&mut self, id: u64) -> Option<&mut Member> {
+        self.member_mut(id)
+    }
+
+    /// Moves a registered member to a tier with a lower borrow limit, like
+    /// [`Member::downgrade_tier`], as long as the member's open
+    /// [`loan_count`](Library::loan_count) fits under it.
+    ///
+    /// # Errors
+    ///
+    /// [`LibraryError::MemberNotFound`] for an unknown member, and
+    /// [`LibraryError::TierChange`] with [`TierChangeError::TooManyBorrowed`]
+    /// if they have more books out than the new tier allows, or whatever
+    /// `Member::downgrade_tier` refuses.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use chrono::NaiveDate;
+    /// use module_8::member::TierChangeError;
+    /// use module_8::{Book, Genre, Library, LibraryError, Member, MembershipTier};
+    ///
+    /// let day = NaiveDate::from_ymd_opt(2024, 3, 1).unwrap();
+    /// let mut lib = Library::new();
+    /// lib.register_member(Member::new(7, "Alice", MembershipTier::Silver)).unwrap();
+    /// for id in 1..=3 {
+    ///     lib.add_book(Book::new(id, &format!("Volume {}", id), Genre::NonFiction)).unwrap();
+    ///     lib.checkout(7, id, day).unwrap();
+    /// }
+    ///
+    /// let err = lib.downgrade_tier(7, MembershipTier::Basic, day);
+    /// let too_many = TierChangeError::TooManyBorrowed { borrowed: 3, limit: 2 };
+    /// assert_eq!(err, Err(LibraryError::TierChange(too_many)));
+    ///
+    /// lib.return_book(7, 3, day).unwrap();
+    /// lib.downgrade_tier(7, MembershipTier::Basic, day).unwrap();
+    /// assert_eq!(lib.find_member(7).unwrap().tier().name(), "Basic");
+    /// ```
+    pub fn downgrade_tier(
+        &mut self,
+        member_id: u64,
+        tier: impl TierPolicy + 'static,
+        effective_on: NaiveDate,
+    ) -> Result<(), LibraryError> {
+        let borrowed = self.loan_count(member_id);
+        let member = self.member_mut(member_id).ok_or(LibraryError::MemberNotFound(member_id))?;
+        if borrowed > tier.borrow_limit() {
+            let limit = tier.borrow_limit();
+            return Err(LibraryError::TierChange(TierChangeError::TooManyBorrowed { borrowed, limit }));
+        }
+        member.downgrade_tier(tier, effective_on).map_err(LibraryError::TierChange)
+    }
+
+    /// Runs `update` on any catalogued book, on the shelf or on loan, and
+    /// re-indexes it afterwards. Returns what `update` returns.
+    ///
+    /// # Errors
+    ///
+    /// [`LibraryError::BookNotFound`] if the book isn't catalogued.
+    pub fn update_book<R>(&mut self, id: u64, update: impl FnOnce(&mut Book) -> R) -> Result<R, LibraryError> {
+        let book = self.books.get_mut(&id).ok_or(LibraryError::BookNotFound(id))?;
+        Ok(update(&mut BookMut::new(book, &mut self.index)))
+    }
+}
+
+// =============================================================================
+// TESTS
+// =============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Genre, MembershipTier};
+
+    fn library() -> Library {
+        let mut lib = Library::new();
+        lib.add_book(Book::builder(1, "Dune").genre(Genre::SciFi).isbn("978-0441172719").build()).unwrap();
+        lib.add_book(Book::new(2, "Emma", Genre::Fiction)).unwrap();
+        lib.add_book(Book::new(3, "Dune", Genre::SciFi)).unwrap();
+        lib.register_member(Member::new(10, "Alice", MembershipTier::Basic)).unwrap();
+        lib
+    }
+
+    fn ids(books: Vec<&Book>) -> Vec<u64> {
+        books.iter().map(|b| b.id()).collect()
+    }
+
+    #[test]
+    fn test_edits_through_the_guard_are_reindexed() {
+        let mut lib = library();
+        {
+            let mut dune = lib.find_book_mut(1).unwrap();
+            dune.title = String::from("Dune Messiah");
+            dune.isbn = Some(String::from("0-399-12793-9"));
+        }
+        assert_eq!(ids(lib.find_by_title("dune")), [3]);
+        assert_eq!(ids(lib.search_titles("messiah")), [1]);
+        assert!(lib.find_by_isbn("9780441172719").is_none());
+        assert_eq!(lib.find_by_isbn("0399127939").unwrap().id(), 1);
+
+        // Renamed back, it takes its old place among books of that title
+        lib.find_book_mut(1).unwrap().title = String::from("DUNE");
+        assert_eq!(ids(lib.find_by_title("dune")), [1, 3]);
+        assert_eq!(lib.check_invariants(), Ok(()));
+        assert!(lib.find_book_mut(99).is_none());
+    }
+
+    #[test]
+    fn test_update_book_reaches_books_on_loan() {
+        let mut lib = library();
+        lib.checkout(10, 2, NaiveDate::from_ymd_opt(2024, 3, 1).unwrap()).unwrap();
+        lib.find_book_mut(2).unwrap().pages = Some(474);
+
+        let old = lib.update_book(2, |book| std::mem::replace(&mut book.title, String::from("Persuasion")));
+        assert_eq!(old.unwrap(), "Emma");
+        assert_eq!(lib.get_book(2).unwrap().title, "Persuasion");
+        assert_eq!(ids(lib.search_titles("persuasion")), [2]);
+        assert_eq!(lib.update_book(99, |_| ()), Err(LibraryError::BookNotFound(99)));
+        assert_eq!(lib.check_invariants(), Ok(()));
+    }
+
+    #[test]
+    fn test_find_member_mut() {
+        let mut lib = library();
+        lib.find_member_mut(10).unwrap().name = String::from("Alicia");
+        assert_eq!(lib.find_member(10).unwrap().name, "Alicia");
+        assert!(lib.find_member_mut(11).is_none());
+    }
+}
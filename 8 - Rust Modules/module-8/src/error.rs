@@ -0,0 +1,130 @@
+//! Error module - the crate's shared error type.
+//!
+//! A single enum for every fallible `Library` operation lets callers match
+//! on exactly what went wrong, while `Display` gives a ready-made message.
+
+use std::fmt;
+
+use crate::access::{Actor, Operation};
+use crate::config::Capacity;
+use crate::member::{SuspensionReason, TierChangeError};
+use crate::money::Money;
+use crate::validation::ValidationError;
+
+/// Errors returned by `Library` operations.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum LibraryError {
+    /// No book with this ID exists in the library.
+    BookNotFound(u64),
+    /// No member with this ID is registered.
+    MemberNotFound(u64),
+    /// The book exists but is currently out on loan.
+    BookUnavailable(u64),
+    /// The book is waiting on the hold shelf for a different member.
+    BookOnHold(u64),
+    /// Digital copies are always available, so they can't be held.
+    HoldNotNeeded(u64),
+    /// The member already has as many books as their tier allows.
+    BorrowLimitReached { member_id: u64, limit: usize },
+    /// The member's tier doesn't allow holds.
+    HoldsNotAllowed(u64),
+    /// The member's membership has expired.
+    MembershipExpired(u64),
+    /// The member has been deactivated.
+    MemberInactive(u64),
+    /// The member is suspended and may not check out books.
+    MemberSuspended { member_id: u64, reason: SuspensionReason },
+    /// The member still owes late fees.
+    OutstandingFines { member_id: u64, amount: Money },
+    /// The member still has books on loan.
+    MemberHasLoans { member_id: u64, count: usize },
+    /// The member does not have this book on loan.
+    NotOnLoan { member_id: u64, book_id: u64 },
+    /// The loan has already been renewed as often as allowed.
+    RenewalLimitReached { book_id: u64, limit: u32 },
+    /// The book ID is already catalogued, or used in both libraries of a merge.
+    DuplicateBook(u64),
+    /// The member ID is already registered, or used in both libraries of a merge.
+    DuplicateMember(u64),
+    /// A catalogued book already has the new book's title and ISBN.
+    LikelyDuplicate { book_id: u64, existing: u64 },
+    /// The library already holds as many books or members as its
+    /// configuration allows.
+    CapacityReached { capacity: Capacity, limit: usize },
+    /// A library system has no branch with this name.
+    BranchNotFound(String),
+    /// A library system already has a branch with this name.
+    DuplicateBranch(String),
+    /// A new label's barcode already belongs to a catalogued book.
+    LabelInUse { barcode: String, book_id: u64 },
+    /// The actor's role doesn't permit the operation (or not for this member).
+    NotAuthorized { actor: Actor, operation: Operation },
+    /// A registered member's tier couldn't be changed.
+    TierChange(TierChangeError),
+    /// An [`Undo`](crate::undo::Undo) was given to a different kind of
+    /// command than the one that returned it.
+    UndoMismatch,
+    /// A title, name or ID given to the library didn't pass validation.
+    /// Serialized for reports, but never read back.
+    #[cfg_attr(feature = "serde", serde(skip_deserializing))]
+    Invalid(ValidationError),
+}
+
+impl fmt::Display for LibraryError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LibraryError::BookNotFound(id) => write!(f, "No book with ID {}", id),
+            LibraryError::MemberNotFound(id) => write!(f, "No member with ID {}", id),
+            LibraryError::BookUnavailable(id) => write!(f, "Book {} is out on loan", id),
+            LibraryError::BookOnHold(id) => {
+                write!(f, "Book {} is on the hold shelf for another member", id)
+            }
+            LibraryError::HoldNotNeeded(id) => {
+                write!(f, "Book {} is digital and never needs a hold", id)
+            }
+            LibraryError::BorrowLimitReached { member_id, limit } => {
+                write!(f, "Member {} has reached the borrow limit of {}", member_id, limit)
+            }
+            LibraryError::HoldsNotAllowed(id) => write!(f, "Member {} may not place holds", id),
+            LibraryError::MembershipExpired(id) => write!(f, "Membership of member {} has expired", id),
+            LibraryError::MemberInactive(id) => write!(f, "Member {} has been deactivated", id),
+            LibraryError::MemberSuspended { member_id, reason } => {
+                write!(f, "Member {} is suspended: {}", member_id, reason)
+            }
+            LibraryError::OutstandingFines { member_id, amount } => {
+                write!(f, "Member {} still owes {} in fines", member_id, amount)
+            }
+            LibraryError::MemberHasLoans { member_id, count } => {
+                write!(f, "Member {} still has {} book(s) on loan", member_id, count)
+            }
+            LibraryError::NotOnLoan { member_id, book_id } => {
+                write!(f, "Member {} does not have book {} on loan", member_id, book_id)
+            }
+            LibraryError::RenewalLimitReached { book_id, limit } => {
+                write!(f, "The loan of book {} has reached the renewal limit of {}", book_id, limit)
+            }
+            LibraryError::DuplicateBook(id) => write!(f, "Book ID {} is already in use", id),
+            LibraryError::DuplicateMember(id) => write!(f, "Member ID {} is already in use", id),
+            LibraryError::LikelyDuplicate { book_id, existing } => {
+                write!(f, "Book {} has the same title and ISBN as book {}", book_id, existing)
+            }
+            LibraryError::CapacityReached { capacity, limit } => {
+                write!(f, "The library is full: it allows at most {} {}", limit, capacity)
+            }
+            LibraryError::BranchNotFound(name) => write!(f, "No branch named {}", name),
+            LibraryError::DuplicateBranch(name) => write!(f, "A branch named {} already exists", name),
+            LibraryError::LabelInUse { barcode, book_id } => {
+                write!(f, "Barcode {} is already used by book {}", barcode, book_id)
+            }
+            LibraryError::NotAuthorized { actor, operation } => {
+                write!(f, "{} {} may not {}", actor.role, actor.id, operation)
+            }
+            LibraryError::TierChange(e) => write!(f, "Tier change refused: {}", e),
+            LibraryError::UndoMismatch => write!(f, "The undo record belongs to a different command"),
+            LibraryError::Invalid(e) => write!(f, "Invalid input: {}", e),
+        }
+    }
+}
+
+impl std::error::Error for LibraryError {}
@@ -0,0 +1,288 @@
+//! Events module - notification hooks for things that happen in a library.
+//!
+//! `Library` fires a [`LibraryEvent`] at every registered [`Notifier`]
+//! whenever it detects an overdue loan, a hold becoming ready, or an expired
+//! membership. Plug in an email, SMS or logging sink by implementing
+//! `Notifier` - or just register a closure.
+//!
+//! Code running on another thread can instead
+//! [`subscribe`](crate::Library::subscribe) and read the events from a
+//! [`Receiver`], e.g. to persist or forward them without slowing the
+//! library down.
+//!
+//! # Backpressure
+//!
+//! [`Library::subscribe`](crate::Library::subscribe) uses an unbounded
+//! channel: sending never waits, and events pile up in memory for as long
+//! as the receiver falls behind.
+//! [`subscribe_bounded`](crate::Library::subscribe_bounded) holds at most
+//! `capacity` unread events; once it is full, [`WhenFull`] decides whether
+//! the library waits for the receiver or drops the event. Waiting slows
+//! every operation that fires events to the receiver's pace, and blocks
+//! forever if the receiver is read on the thread driving the library.
+//!
+//! Events sent after the receiver is dropped are discarded.
+//!
+//! ```
+//! use std::thread;
+//! use chrono::NaiveDate;
+//! use module_8::{Book, Genre, Library, LibraryEvent, Member, MembershipTier};
+//!
+//! let mut lib = Library::new();
+//! lib.add_book(Book::new(1, "Dune", Genre::SciFi)).unwrap();
+//! lib.register_member(Member::new(10, "Alice", MembershipTier::Basic)).unwrap();
+//! let events = lib.subscribe();
+//! let consumer = thread::spawn(move || events.iter().count());
+//!
+//! lib.checkout(10, 1, NaiveDate::from_ymd_opt(2024, 3, 1).unwrap()).unwrap();
+//! lib.check_overdue(NaiveDate::from_ymd_opt(2024, 3, 20).unwrap());
+//! drop(lib); // closes the channel
+//! assert_eq!(consumer.join().unwrap(), 1);
+//! ```
+
+use std::sync::mpsc::{self, Receiver, Sender, SyncSender};
+
+use chrono::NaiveDate;
+
+use crate::Library;
+
+// =============================================================================
+// EVENTS
+// =============================================================================
+
+/// Something a library patron may need to hear about.
+#[derive(Debug, Clone, PartialEq)]
+pub enum LibraryEvent {
+    /// A loan is past its due date.
+    Overdue {
+        member_id: u64,
+        book_id: u64,
+        due_on: NaiveDate,
+        days_overdue: u32,
+    },
+    /// A held book was returned and is waiting for this member.
+    HoldAvailable { member_id: u64, book_id: u64 },
+    /// The member didn't pick up a held book in time, so the hold lapsed.
+    HoldExpired { member_id: u64, book_id: u64 },
+    /// A membership has reached its expiry date.
+    MembershipExpired { member_id: u64, expired_on: NaiveDate },
+}
+
+// =============================================================================
+// NOTIFIER TRAIT
+// =============================================================================
+
+/// A sink for library events.
+///
+/// `Send + Sync` so a `Library` holding notifiers can still be shared across
+/// threads. Any `Fn(&LibraryEvent)` closure is a `Notifier` too.
+///
+/// # Examples
+///
+/// ```
+/// use std::sync::{Arc, Mutex};
+/// use module_8::{Library, LibraryEvent};
+///
+/// let seen = Arc::new(Mutex::new(Vec::new()));
+/// let sink = Arc::clone(&seen);
+///
+/// let mut lib = Library::new();
+/// lib.add_notifier(move |event: &LibraryEvent| sink.lock().unwrap().push(event.clone()));
+/// ```
+pub trait Notifier: Send + Sync {
+    fn notify(&self, event: &LibraryEvent);
+}
+
+/// Closures work as notifiers, which keeps one-off hooks short.
+impl<F> Notifier for F
+where
+    F: Fn(&LibraryEvent) + Send + Sync,
+{
+    fn notify(&self, event: &LibraryEvent) {
+        self(event)
+    }
+}
+
+/// A notifier that prints each event to stdout.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct ConsoleNotifier;
+
+impl Notifier for ConsoleNotifier {
+    fn notify(&self, event: &LibraryEvent) {
+        println!("🔔 {}", describe(event));
+    }
+}
+
+// =============================================================================
+// CHANNELS
+// =============================================================================
+
+/// What a bounded subscription does with an event when its channel is full.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum WhenFull {
+    /// Wait until the receiver makes room.
+    Block,
+    /// Discard the event and carry on.
+    Drop,
+}
+
+/// Forwards events into a channel; see [`Library::subscribe`].
+enum ChannelNotifier {
+    Unbounded(Sender<LibraryEvent>),
+    Bounded(SyncSender<LibraryEvent>, WhenFull),
+}
+
+impl Notifier for ChannelNotifier {
+    fn notify(&self, event: &LibraryEvent) {
+        // Send errors mean the receiver is gone or, with `WhenFull::Drop`,
+        // full; either way the event is dropped
+        let event = event.clone();
+        match self {
+            ChannelNotifier::Unbounded(sender) => {
+                let _ = sender.send(event);
+            }
+            ChannelNotifier::Bounded(sender, WhenFull::Block) => {
+                let _ = sender.send(event);
+            }
+            ChannelNotifier::Bounded(sender, WhenFull::Drop) => {
+                let _ = sender.try_send(event);
+            }
+        }
+    }
+}
+
+impl Library {
+    /// Returns a receiver of every event fired from now on, for reading on
+    /// another thread. The channel is unbounded; see the
+    /// [module docs](self) on backpressure.
+    pub fn subscribe(&mut self) -> Receiver<LibraryEvent> {
+        let (sender, receiver) = mpsc::channel();
+        self.add_notifier(ChannelNotifier::Unbounded(sender));
+        receiver
+    }
+
+    /// Like [`subscribe`](Library::subscribe), holding at most `capacity`
+    /// unread events; `when_full` decides what happens to the next one.
+    ///
+    /// A `capacity` of 0 makes every event wait for the receiver (with
+    /// [`WhenFull::Block`]) or be dropped unless the receiver is already
+    /// waiting (with [`WhenFull::Drop`]).
+    pub fn subscribe_bounded(&mut self, capacity: usize, when_full: WhenFull) -> Receiver<LibraryEvent> {
+        let (sender, receiver) = mpsc::sync_channel(capacity);
+        self.add_notifier(ChannelNotifier::Bounded(sender, when_full));
+        receiver
+    }
+}
+
+/// Formats an event as a one-line human-readable message.
+pub fn describe(event: &LibraryEvent) -> String {
+    match event {
+        LibraryEvent::Overdue { member_id, book_id, due_on, days_overdue } => format!(
+            "Member {}: book {} was due {} ({} days overdue)",
+            member_id, book_id, due_on, days_overdue
+        ),
+        LibraryEvent::HoldAvailable { member_id, book_id } => {
+            format!("Member {}: your hold on book {} is ready for pickup", member_id, book_id)
+        }
+        LibraryEvent::HoldExpired { member_id, book_id } => {
+            format!("Member {}: your hold on book {} expired before pickup", member_id, book_id)
+        }
+        LibraryEvent::MembershipExpired { member_id, expired_on } => {
+            format!("Member {}: membership expired on {}", member_id, expired_on)
+        }
+    }
+}
+
+// =============================================================================
+// TESTS
+// =============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Book, Genre, Member, MembershipTier};
+    use std::sync::Mutex;
+    use std::thread;
+
+    fn date(day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(2024, 3, day).unwrap()
+    }
+
+    /// A library with three books overdue for member 10 from the 16th.
+    fn overdue_library() -> Library {
+        let mut lib = Library::new();
+        lib.register_member(Member::new(10, "Alice", MembershipTier::Silver)).unwrap();
+        for id in 1..=3 {
+            lib.add_book(Book::new(id, "Dune", Genre::SciFi)).unwrap();
+            lib.checkout(10, id, date(1)).unwrap();
+        }
+        lib
+    }
+
+    #[test]
+    fn test_closure_is_a_notifier() {
+        let seen = Mutex::new(Vec::new());
+        let notifier = |event: &LibraryEvent| seen.lock().unwrap().push(event.clone());
+        let event = LibraryEvent::HoldAvailable { member_id: 1, book_id: 2 };
+
+        notifier.notify(&event);
+        assert_eq!(*seen.lock().unwrap(), vec![event]);
+    }
+
+    #[test]
+    fn test_describe_mentions_ids() {
+        let event = LibraryEvent::Overdue {
+            member_id: 3,
+            book_id: 9,
+            due_on: NaiveDate::from_ymd_opt(2024, 1, 31).unwrap(),
+            days_overdue: 4,
+        };
+        let text = describe(&event);
+        assert!(text.contains("Member 3"));
+        assert!(text.contains("book 9"));
+        assert!(text.contains("4 days overdue"));
+    }
+
+    #[test]
+    fn test_subscribers_receive_events_on_another_thread() {
+        let mut lib = overdue_library();
+        let events = lib.subscribe();
+        let consumer = thread::spawn(move || events.iter().map(|e| describe(&e)).collect::<Vec<_>>());
+
+        lib.check_overdue(date(25));
+        lib.check_overdue(date(26));
+        drop(lib);
+        let seen = consumer.join().unwrap();
+        assert_eq!(seen.len(), 6);
+        assert_eq!(seen[0], "Member 10: book 1 was due 2024-03-22 (3 days overdue)");
+    }
+
+    #[test]
+    fn test_bounded_subscription_drops_when_full() {
+        let mut lib = overdue_library();
+        let dropping = lib.subscribe_bounded(2, WhenFull::Drop);
+        let unbounded = lib.subscribe();
+        lib.check_overdue(date(25));
+
+        let kept: Vec<LibraryEvent> = dropping.try_iter().collect();
+        assert_eq!(kept.len(), 2);
+        assert_eq!(unbounded.try_iter().count(), 3);
+
+        // Room again after reading; a dropped receiver is ignored
+        lib.check_overdue(date(26));
+        assert_eq!(dropping.try_iter().count(), 2);
+        drop(dropping);
+        lib.check_overdue(date(27));
+        assert_eq!(unbounded.try_iter().count(), 6);
+    }
+
+    #[test]
+    fn test_bounded_subscription_blocks_until_read() {
+        let mut lib = overdue_library();
+        let events = lib.subscribe_bounded(1, WhenFull::Block);
+        let consumer = thread::spawn(move || events.iter().count());
+        lib.check_overdue(date(25));
+        drop(lib);
+        assert_eq!(consumer.join().unwrap(), 3);
+    }
+}
@@ -0,0 +1,286 @@
+//! Fee policy module - how late fees are assessed.
+//!
+//! `Library` asks its [`FeePolicy`] for the fee whenever a book comes back
+//! late. The default is [`FlatFeePolicy`], the same per-day formula as
+//! `config::fees::calculate_late_fee`. The other policies here can be
+//! combined: wrap any policy in [`GracePeriod`] or [`FeeCap`].
+//!
+//! Policies price a number of overdue days; which days count is up to the
+//! library's [`Calendar`], through [`FeePolicy::late_fee_between`].
+//!
+//! The built-in policies never panic, whatever the number of days or the
+//! rates: their arithmetic saturates at the largest amount [`Money`] can
+//! hold. Wrap a policy in [`FeeCap`] for a sensible maximum.
+//!
+//! ```
+//! use module_8::fee_policy::{EscalatingFeePolicy, FeeCap, FeePolicy, GracePeriod};
+//! use module_8::Money;
+//!
+//! // 25c/day for the first week, 50c/day after that, 2 days' grace, $10 cap
+//! let bands = vec![(1, Money::from_cents(25)), (8, Money::from_cents(50))];
+//! let policy = FeeCap::new(GracePeriod::new(EscalatingFeePolicy::new(bands), 2), Money::from_cents(1000));
+//! assert_eq!(policy.late_fee(2), Money::ZERO);
+//! assert_eq!(policy.late_fee(9).to_string(), "$1.75");
+//! assert_eq!(policy.late_fee(100).to_string(), "$10.00");
+//! ```
+
+use std::fmt;
+
+use chrono::NaiveDate;
+
+use crate::calendar::Calendar;
+use crate::money::Money;
+
+// =============================================================================
+// FEE POLICY TRAIT
+// =============================================================================
+
+/// Computes the late fee for an overdue loan.
+///
+/// `Send + Sync` so a `Library` holding a policy can be shared across threads.
+pub trait FeePolicy: fmt::Debug + Send + Sync {
+    /// Late fee for a book returned `days_overdue` days after its due date.
+    /// Must be zero when `days_overdue` is 0, and shouldn't panic for any
+    /// number of days.
+    fn late_fee(&self, days_overdue: u32) -> Money;
+
+    /// Overdue days charged nothing before fees start; see [`GracePeriod`].
+    fn grace_days(&self) -> u32 {
+        0
+    }
+
+    /// Late fee for a book due on `due_on` and returned on `returned_on`,
+    /// charging only the days `calendar` has the library open.
+    fn late_fee_between(&self, due_on: NaiveDate, returned_on: NaiveDate, calendar: &Calendar) -> Money {
+        self.late_fee(calendar.open_days_between(due_on, returned_on))
+    }
+}
+
+// =============================================================================
+// BUILT-IN POLICIES
+// =============================================================================
+
+/// The same rate for every overdue day.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct FlatFeePolicy {
+    /// Charged per overdue day.
+    pub per_day: Money,
+}
+
+impl FeePolicy for FlatFeePolicy {
+    fn late_fee(&self, days_overdue: u32) -> Money {
+        self.per_day.saturating_mul(days_overdue.into())
+    }
+}
+
+/// Daily rate that rises the longer a book is overdue.
+///
+/// Each band is `(first_day, per_day)`: from overdue day `first_day`
+/// onwards (1-based), each day costs `per_day` until the next band.
+/// Days before the first band are free.
+#[derive(Debug, Clone, PartialEq)]
+pub struct EscalatingFeePolicy {
+    bands: Vec<(u32, Money)>,
+}
+
+impl EscalatingFeePolicy {
+    /// Creates the policy; bands may be given in any order.
+    pub fn new(mut bands: Vec<(u32, Money)>) -> Self {
+        bands.sort_by_key(|&(first_day, _)| first_day);
+        EscalatingFeePolicy { bands }
+    }
+}
+
+impl FeePolicy for EscalatingFeePolicy {
+    fn late_fee(&self, days_overdue: u32) -> Money {
+        let mut total = Money::ZERO;
+        for (i, &(first_day, per_day)) in self.bands.iter().enumerate() {
+            // Overdue days are 1-based; a band from day 0 starts at day 1
+            let first_day = first_day.max(1);
+            let band_end = self
+                .bands
+                .get(i + 1)
+                .map_or(days_overdue, |&(next, _)| next.saturating_sub(1).min(days_overdue));
+            if band_end >= first_day {
+                let days = u64::from(band_end - first_day) + 1;
+                total = total.saturating_add(per_day.saturating_mul(days));
+            }
+        }
+        total
+    }
+}
+
+/// Limits another policy's fee to a maximum.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FeeCap<P> {
+    inner: P,
+    cap: Money,
+}
+
+impl<P: FeePolicy> FeeCap<P> {
+    pub fn new(inner: P, cap: Money) -> Self {
+        FeeCap { inner, cap }
+    }
+}
+
+impl<P: FeePolicy> FeePolicy for FeeCap<P> {
+    fn late_fee(&self, days_overdue: u32) -> Money {
+        self.inner.late_fee(days_overdue).min(self.cap)
+    }
+
+    fn grace_days(&self) -> u32 {
+        self.inner.grace_days()
+    }
+}
+
+/// Waives the first `days` overdue days, then charges another policy as if
+/// the loan had been due that much later.
+///
+/// The first day charged can be prorated: with
+/// [`first_day_percent`](GracePeriod::first_day_percent) at 50, a book
+/// returned the day after the grace period ends pays half a day's fee, and
+/// every later day is charged in full.
+#[derive(Debug, Clone, PartialEq)]
+pub struct GracePeriod<P> {
+    inner: P,
+    days: u32,
+    first_day_percent: u8,
+}
+
+impl<P: FeePolicy> GracePeriod<P> {
+    pub fn new(inner: P, days: u32) -> Self {
+        GracePeriod { inner, days, first_day_percent: 100 }
+    }
+
+    /// Charges only `percent` of the first day after the grace period;
+    /// values above 100 are treated as 100.
+    pub fn first_day_percent(mut self, percent: u8) -> Self {
+        self.first_day_percent = percent.min(100);
+        self
+    }
+}
+
+impl<P: FeePolicy> FeePolicy for GracePeriod<P> {
+    fn late_fee(&self, days_overdue: u32) -> Money {
+        let charged = days_overdue.saturating_sub(self.days);
+        let fee = self.inner.late_fee(charged);
+        if charged == 0 || self.first_day_percent == 100 {
+            return fee;
+        }
+        // Rounded down, in the payer's favour
+        let first_day = self.inner.late_fee(1);
+        fee.saturating_sub(first_day).saturating_add(first_day.percent(self.first_day_percent))
+    }
+
+    fn grace_days(&self) -> u32 {
+        self.days.saturating_add(self.inner.grace_days())
+    }
+}
+
+// =============================================================================
+// TESTS
+// =============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::fees::calculate_late_fee;
+    use crate::config::LibraryConfig;
+
+    #[test]
+    fn test_flat_matches_config_formula() {
+        let flat = FlatFeePolicy { per_day: LibraryConfig::default().late_fee_per_day };
+        for days in [0, 1, 7, 30] {
+            assert_eq!(flat.late_fee(days), calculate_late_fee(days));
+        }
+    }
+
+    fn cents(amount: u64) -> Money {
+        Money::from_cents(amount)
+    }
+
+    #[test]
+    fn test_escalating_bands() {
+        let policy = EscalatingFeePolicy::new(vec![(8, cents(50)), (1, cents(25)), (15, cents(100))]);
+        assert_eq!(policy.late_fee(0), Money::ZERO);
+        assert_eq!(policy.late_fee(7), cents(7 * 25));
+        assert_eq!(policy.late_fee(10), cents(7 * 25 + 3 * 50));
+        assert_eq!(policy.late_fee(16), cents(7 * 25 + 7 * 50 + 2 * 100));
+    }
+
+    #[test]
+    fn test_escalating_free_days_before_first_band() {
+        let policy = EscalatingFeePolicy::new(vec![(4, cents(10))]);
+        assert_eq!(policy.late_fee(3), Money::ZERO);
+        assert_eq!(policy.late_fee(5), cents(20));
+    }
+
+    #[test]
+    fn test_grace_and_cap_compose() {
+        let policy = FeeCap::new(GracePeriod::new(FlatFeePolicy { per_day: cents(25) }, 3), cents(200));
+        assert_eq!(policy.late_fee(3), Money::ZERO);
+        assert_eq!(policy.late_fee(5), cents(50));
+        assert_eq!(policy.late_fee(60), cents(200));
+        assert_eq!(policy.grace_days(), 3);
+    }
+
+    #[test]
+    fn test_prorated_first_day() {
+        let policy = GracePeriod::new(FlatFeePolicy { per_day: cents(25) }, 2).first_day_percent(50);
+        assert_eq!(policy.late_fee(2), Money::ZERO);
+        assert_eq!(policy.late_fee(3), cents(12));
+        assert_eq!(policy.late_fee(4), cents(37));
+        // Without grace days the proration applies to the first overdue day
+        let policy = GracePeriod::new(FlatFeePolicy { per_day: cents(20) }, 0).first_day_percent(50);
+        assert_eq!(policy.late_fee(0), Money::ZERO);
+        assert_eq!(policy.late_fee(1), cents(10));
+        assert_eq!(FlatFeePolicy { per_day: cents(20) }.grace_days(), 0);
+    }
+
+    #[test]
+    fn test_absurd_inputs_saturate() {
+        let max = cents(u64::MAX);
+        assert_eq!(FlatFeePolicy { per_day: max }.late_fee(2), max);
+        assert_eq!(calculate_late_fee(u32::MAX), cents(25 * u64::from(u32::MAX)));
+        let policy = EscalatingFeePolicy::new(vec![(0, max), (1, cents(1)), (u32::MAX, max)]);
+        assert_eq!(policy.late_fee(0), Money::ZERO);
+        assert_eq!(policy.late_fee(u32::MAX), max);
+        assert_eq!(GracePeriod::new(GracePeriod::new(FlatFeePolicy { per_day: max }, u32::MAX), 1).grace_days(), u32::MAX);
+    }
+
+    mod properties {
+        use super::*;
+        use proptest::prelude::*;
+
+        fn policies(per_day: u64, band_day: u32, grace: u32, percent: u8, cap: u64) -> Vec<Box<dyn FeePolicy>> {
+            let flat = FlatFeePolicy { per_day: cents(per_day) };
+            let escalating = EscalatingFeePolicy::new(vec![(1, cents(per_day)), (band_day, cents(per_day / 3))]);
+            vec![
+                Box::new(flat),
+                Box::new(escalating.clone()),
+                Box::new(GracePeriod::new(flat, grace).first_day_percent(percent)),
+                Box::new(FeeCap::new(GracePeriod::new(escalating, grace), cents(cap))),
+            ]
+        }
+
+        proptest! {
+            #[test]
+            fn fees_never_panic_and_grow_with_days(
+                days in any::<u32>(),
+                per_day in any::<u64>(),
+                band_day in any::<u32>(),
+                grace in any::<u32>(),
+                percent in any::<u8>(),
+                cap in any::<u64>(),
+            ) {
+                let config = LibraryConfig { late_fee_per_day: cents(per_day), ..LibraryConfig::default() };
+                prop_assert!(calculate_late_fee(days) <= calculate_late_fee(days.saturating_add(1)));
+                prop_assert!(config.late_fee(days) <= config.late_fee(days.saturating_add(1)));
+                for policy in policies(per_day, band_day, grace, percent, cap) {
+                    prop_assert_eq!(policy.late_fee(0), Money::ZERO);
+                    prop_assert!(policy.late_fee(days) <= policy.late_fee(days.saturating_add(1)));
+                }
+            }
+        }
+    }
+}
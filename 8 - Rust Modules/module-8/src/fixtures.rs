@@ -0,0 +1,192 @@
+//! Fixtures module - plausible catalogs and member lists for tests and
+//! benchmarks.
+//!
+//! [`Fixtures`] describes a dataset by seed and size. The same seed always
+//! produces the same books and members, so a failing test or a slow
+//! benchmark can be reproduced exactly. Books and members are drawn from
+//! separate streams: asking for more members doesn't change the books.
+//!
+//! ```
+//! use module_8::fixtures::Fixtures;
+//!
+//! let lib = Fixtures { seed: 7, books: 500, members: 50 }.library();
+//! assert_eq!(lib.books().count(), 500);
+//! assert_eq!(lib.members().count(), 50);
+//!
+//! let again = Fixtures { seed: 7, books: 500, members: 50 }.library();
+//! assert_eq!(lib.find_book(123).unwrap().title, again.find_book(123).unwrap().title);
+//! ```
+
+use chrono::NaiveDate;
+
+use crate::book::{Book, Genre};
+use crate::member::{Member, MembershipTier};
+use crate::simulation::Rng;
+use crate::Library;
+
+// =============================================================================
+// WORD LISTS
+// =============================================================================
+
+const FIRST_NAMES: &[&str] = &[
+    "Ada", "Alan", "Alice", "Amara", "Ben", "Carol", "Chen", "Dara", "Elena", "Farid", "Grace", "Hana",
+    "Ivan", "Jun", "Kofi", "Lena", "Mateo", "Nadia", "Omar", "Priya", "Rosa", "Sam", "Tomas", "Yuki",
+];
+
+const LAST_NAMES: &[&str] = &[
+    "Abbott", "Bauer", "Castillo", "Dubois", "Eze", "Fischer", "Garcia", "Hughes", "Ito", "Jensen", "Khan",
+    "Larsen", "Moreau", "Nakamura", "Okafor", "Patel", "Quinn", "Rossi", "Silva", "Tanaka", "Novak", "Walsh",
+];
+
+const ADJECTIVES: &[&str] = &[
+    "Silent", "Broken", "Hidden", "Last", "Crimson", "Distant", "Quiet", "Forgotten", "Burning", "Hollow",
+    "Secret", "Endless", "Glass", "Northern", "Winter", "Golden",
+];
+
+/// Nouns suited to each built-in genre, in `Genre::all()` order.
+const NOUNS: [&[&str]; 5] = [
+    &["Garden", "River", "House", "Summer", "Letters", "Harbor", "Daughter", "Orchard"],
+    &["History", "Empire", "Mind", "Ocean", "Cities", "Century", "Body", "Road"],
+    &["Compilers", "Systems", "Networks", "Algorithms", "Databases", "Rust", "Concurrency", "Types"],
+    &["Witness", "Alibi", "Cellar", "Motive", "Verdict", "Stranger", "Key", "Inquest"],
+    &["Star", "Machine", "Colony", "Signal", "Orbit", "Android", "Void", "Frontier"],
+];
+
+/// Tiers with their weights: most members are Basic, few are Platinum.
+const TIER_WEIGHTS: [(MembershipTier, u64); 4] = [
+    (MembershipTier::Basic, 50),
+    (MembershipTier::Silver, 30),
+    (MembershipTier::Gold, 15),
+    (MembershipTier::Platinum, 5),
+];
+
+/// Mixed into the seed so members don't share the books' stream.
+const MEMBER_STREAM: u64 = 0x6D65_6D62_6572_7321;
+
+// =============================================================================
+// FIXTURES
+// =============================================================================
+
+/// A deterministic dataset: `books` books with IDs `1..=books` and
+/// `members` members with IDs `1..=members`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Fixtures {
+    pub seed: u64,
+    pub books: usize,
+    pub members: usize,
+}
+
+impl Default for Fixtures {
+    /// A small library: 100 books and 20 members from seed 0.
+    fn default() -> Self {
+        Fixtures { seed: 0, books: 100, members: 20 }
+    }
+}
+
+impl Fixtures {
+    /// Books with a title, author, genre, year and a valid ISBN-13.
+    pub fn make_books(&self) -> Vec<Book> {
+        let mut rng = Rng(self.seed);
+        (1..=self.books as u64)
+            .map(|id| {
+                let g = rng.below(NOUNS.len() as u64) as usize;
+                let genre = Genre::all().nth(g).expect("one noun list per genre");
+                let title = format!("The {} {}", pick(&mut rng, ADJECTIVES), pick(&mut rng, NOUNS[g]));
+                let mut book = Book::new(id, &title, genre);
+                book.author = Some(format!("{} {}", pick(&mut rng, FIRST_NAMES), pick(&mut rng, LAST_NAMES)));
+                book.year = Some(1950 + rng.below(75) as u16);
+                book.isbn = Some(isbn13(978_000_000_000 + rng.below(1_000_000_000)));
+                book
+            })
+            .collect()
+    }
+
+    /// Members with a name, a tier (mostly Basic) and, for about a third of
+    /// them, a membership expiring during 2025.
+    pub fn make_members(&self) -> Vec<Member> {
+        let mut rng = Rng(self.seed ^ MEMBER_STREAM);
+        let total: u64 = TIER_WEIGHTS.iter().map(|(_, weight)| weight).sum();
+        (1..=self.members as u64)
+            .map(|id| {
+                let name = format!("{} {}", pick(&mut rng, FIRST_NAMES), pick(&mut rng, LAST_NAMES));
+                let mut roll = rng.below(total);
+                let mut tier = MembershipTier::Basic;
+                for (candidate, weight) in TIER_WEIGHTS {
+                    if roll < weight {
+                        tier = candidate;
+                        break;
+                    }
+                    roll -= weight;
+                }
+                let mut member = Member::new(id, &name, tier);
+                if rng.below(3) == 0 {
+                    let start = NaiveDate::from_ymd_opt(2025, 1, 1).expect("valid date");
+                    member.set_expires_on(start.checked_add_days(chrono::Days::new(rng.below(365))));
+                }
+                member
+            })
+            .collect()
+    }
+
+    /// A library holding the generated books (all on the shelf) and members.
+    pub fn library(&self) -> Library {
+        let mut library = Library::new();
+        library.register_members(self.make_members());
+        library.add_books(self.make_books());
+        library
+    }
+}
+
+fn pick<'a>(rng: &mut Rng, words: &[&'a str]) -> &'a str {
+    words[rng.below(words.len() as u64) as usize]
+}
+
+/// The 13-digit ISBN for a 12-digit prefix, with its check digit.
+fn isbn13(prefix: u64) -> String {
+    let digits = format!("{:012}", prefix);
+    let sum: u32 = digits
+        .bytes()
+        .enumerate()
+        .map(|(i, d)| u32::from(d - b'0') * if i % 2 == 0 { 1 } else { 3 })
+        .sum();
+    format!("{}{}", digits, (10 - sum % 10) % 10)
+}
+
+// =============================================================================
+// TESTS
+// =============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_same_seed_same_data() {
+        let spec = Fixtures { seed: 42, books: 200, members: 40 };
+        let titles = |books: Vec<Book>| books.into_iter().map(|b| (b.title, b.author, b.isbn)).collect::<Vec<_>>();
+        assert_eq!(titles(spec.make_books()), titles(spec.make_books()));
+        assert_ne!(titles(spec.make_books()), titles(Fixtures { seed: 43, ..spec }.make_books()));
+
+        // More members leave the books alone
+        let more = Fixtures { members: 400, ..spec };
+        assert_eq!(titles(spec.make_books()), titles(more.make_books()));
+        let names: Vec<String> = spec.make_members().into_iter().map(|m| m.name).collect();
+        let more_names: Vec<String> = more.make_members().into_iter().map(|m| m.name).collect();
+        assert_eq!(names[..], more_names[..40]);
+    }
+
+    #[test]
+    fn test_generated_data_is_plausible() {
+        assert_eq!(isbn13(978_030_640_615), "9780306406157");
+
+        let lib = Fixtures { seed: 1, books: 1000, members: 200 }.library();
+        assert_eq!(lib.check_invariants(), Ok(()));
+        for genre in Genre::all() {
+            assert!(lib.books().any(|b| b.genre == genre), "no {} books", genre);
+        }
+        let basic = lib.members().filter(|m| m.tier().name() == "Basic").count();
+        let platinum = lib.members().filter(|m| m.tier().name() == "Platinum").count();
+        assert!(basic > platinum && platinum > 0);
+        assert!(lib.books().all(|b| b.isbn.as_ref().is_some_and(|isbn| isbn.len() == 13)));
+    }
+}
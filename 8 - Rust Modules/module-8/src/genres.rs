@@ -0,0 +1,279 @@
+//! Genres module - subgenres nested under broader genres.
+//!
+//! A book has exactly one [`Genre`], but genres can be arranged in a
+//! [`GenreTree`] (Technical → Programming → Rust) so that browsing a broad
+//! genre also finds the books filed under its subgenres. Each genre has at
+//! most one parent, and a parent that would make a genre its own ancestor
+//! is refused.
+//!
+//! ```
+//! use module_8::genres::GenreTree;
+//! use module_8::{Book, Genre, Library};
+//!
+//! let programming = Genre::Custom(String::from("Programming"));
+//! let rust = Genre::Custom(String::from("Rust"));
+//! let mut tree = GenreTree::new();
+//! tree.set_parent(programming.clone(), Genre::Technical).unwrap();
+//! tree.set_parent(rust.clone(), programming.clone()).unwrap();
+//! assert!(tree.set_parent(Genre::Technical, rust.clone()).is_err());
+//!
+//! let mut lib = Library::new();
+//! lib.set_genre_tree(tree);
+//! lib.add_book(Book::new(1, "The Rust Book", rust)).unwrap();
+//! lib.add_book(Book::new(2, "Clean Code", programming)).unwrap();
+//! lib.add_book(Book::new(3, "Dune", Genre::SciFi)).unwrap();
+//!
+//! assert_eq!(lib.books_in_genre_tree(&Genre::Technical).len(), 2);
+//! ```
+
+use std::collections::HashMap;
+use std::fmt;
+
+use crate::book::{Book, Genre};
+use crate::utils::formatting::GenreIcons;
+use crate::Library;
+
+// =============================================================================
+// ERRORS
+// =============================================================================
+
+/// Refused because `parent` is `child` itself or one of its subgenres.
+#[derive(Debug, Clone, PartialEq)]
+pub struct GenreCycleError {
+    pub child: Genre,
+    pub parent: Genre,
+}
+
+impl fmt::Display for GenreCycleError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} can't be a subgenre of {}: {} is already within {}",
+            self.child, self.parent, self.parent, self.child
+        )
+    }
+}
+
+impl std::error::Error for GenreCycleError {}
+
+// =============================================================================
+// GENRE TREE
+// =============================================================================
+
+/// Which genre each subgenre belongs to. Genres without a parent are at the
+/// top level; every genre starts there.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct GenreTree {
+    /// `(child, parent)` pairs, at most one per child.
+    parents: Vec<(Genre, Genre)>,
+}
+
+impl GenreTree {
+    /// A tree with every genre at the top level.
+    pub fn new() -> Self {
+        GenreTree::default()
+    }
+
+    /// Files `child` under `parent`, replacing any parent it had.
+    ///
+    /// # Errors
+    ///
+    /// [`GenreCycleError`] if `parent` is `child` or one of its subgenres.
+    pub fn set_parent(&mut self, child: Genre, parent: Genre) -> Result<(), GenreCycleError> {
+        if self.is_within(&parent, &child) {
+            return Err(GenreCycleError { child, parent });
+        }
+        match self.parents.iter_mut().find(|(c, _)| *c == child) {
+            Some(entry) => entry.1 = parent,
+            None => self.parents.push((child, parent)),
+        }
+        Ok(())
+    }
+
+    /// Moves `genre` back to the top level, keeping its own subgenres.
+    pub fn remove_parent(&mut self, genre: &Genre) -> Option<Genre> {
+        let position = self.parents.iter().position(|(c, _)| c == genre)?;
+        Some(self.parents.remove(position).1)
+    }
+
+    pub fn parent(&self, genre: &Genre) -> Option<&Genre> {
+        self.parents.iter().find(|(c, _)| c == genre).map(|(_, p)| p)
+    }
+
+    /// The genre's parent, grandparent and so on up to the top level.
+    pub fn ancestors<'a>(&'a self, genre: &Genre) -> impl Iterator<Item = &'a Genre> {
+        std::iter::successors(self.parent(genre), move |g| self.parent(g))
+    }
+
+    /// Returns `true` if `genre` is `ancestor` or nested anywhere below it.
+    pub fn is_within(&self, genre: &Genre, ancestor: &Genre) -> bool {
+        genre == ancestor || self.ancestors(genre).any(|g| g == ancestor)
+    }
+
+    /// Every genre nested below `genre`, nearest first.
+    pub fn descendants(&self, genre: &Genre) -> Vec<&Genre> {
+        let mut found: Vec<&Genre> = Vec::new();
+        let mut next = 0;
+        let mut current = genre;
+        loop {
+            found.extend(self.parents.iter().filter(|(_, p)| p == current).map(|(c, _)| c));
+            match found.get(next) {
+                Some(&child) => current = child,
+                None => return found,
+            }
+            next += 1;
+        }
+    }
+}
+
+// =============================================================================
+// LIBRARY INTEGRATION
+// =============================================================================
+
+impl Library {
+    /// How the library's genres nest.
+    pub fn genre_tree(&self) -> &GenreTree {
+        &self.genres
+    }
+
+    /// Replaces the genre hierarchy used by
+    /// [`books_in_genre_tree`](Library::books_in_genre_tree).
+    pub fn set_genre_tree(&mut self, tree: GenreTree) {
+        self.genres = tree;
+    }
+
+    /// Files `child` under `parent` in the library's genre tree; see
+    /// [`GenreTree::set_parent`].
+    pub fn set_genre_parent(&mut self, child: Genre, parent: Genre) -> Result<(), GenreCycleError> {
+        self.genres.set_parent(child, parent)
+    }
+
+    /// The icons the library shows for genres.
+    pub fn genre_icons(&self) -> &GenreIcons {
+        &self.genre_icons
+    }
+
+    /// Lets the application override or add genre icons; see
+    /// [`GenreIcons`].
+    pub fn genre_icons_mut(&mut self) -> &mut GenreIcons {
+        &mut self.genre_icons
+    }
+
+    /// Every catalogued book in `genre` or any of its subgenres: shelf books
+    /// in catalog order, then books on loan.
+    pub fn books_in_genre_tree(&self, genre: &Genre) -> Vec<&Book> {
+        self.catalog()
+            .filter(|book| self.genres.is_within(&book.genre, genre))
+            .collect()
+    }
+
+    /// Every catalogued book grouped under its own genre (not the genres
+    /// above it in the tree), each group in the same order as
+    /// [`books_in_genre_tree`](Library::books_in_genre_tree).
+    pub fn books_by_genre(&self) -> HashMap<Genre, Vec<&Book>> {
+        let mut grouped: HashMap<Genre, Vec<&Book>> = HashMap::new();
+        for book in self.catalog() {
+            grouped.entry(book.genre.clone()).or_default().push(book);
+        }
+        grouped
+    }
+
+    /// How many catalogued books each genre has; genres without books are
+    /// left out.
+    pub fn genre_counts(&self) -> HashMap<Genre, usize> {
+        let mut counts: HashMap<Genre, usize> = HashMap::new();
+        for book in self.catalog() {
+            *counts.entry(book.genre.clone()).or_insert(0) += 1;
+        }
+        counts
+    }
+}
+
+// =============================================================================
+// TESTS
+// =============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Member, MembershipTier};
+    use chrono::NaiveDate;
+
+    fn custom(name: &str) -> Genre {
+        Genre::Custom(String::from(name))
+    }
+
+    fn technical_tree() -> GenreTree {
+        let mut tree = GenreTree::new();
+        tree.set_parent(custom("Programming"), Genre::Technical).unwrap();
+        tree.set_parent(custom("Rust"), custom("Programming")).unwrap();
+        tree.set_parent(custom("Databases"), Genre::Technical).unwrap();
+        tree
+    }
+
+    #[test]
+    fn test_tree_navigation() {
+        let tree = technical_tree();
+        assert_eq!(tree.parent(&custom("Rust")), Some(&custom("Programming")));
+        assert_eq!(tree.parent(&Genre::Technical), None);
+        let ancestors: Vec<&Genre> = tree.ancestors(&custom("Rust")).collect();
+        assert_eq!(ancestors, [&custom("Programming"), &Genre::Technical]);
+        assert_eq!(
+            tree.descendants(&Genre::Technical),
+            [&custom("Programming"), &custom("Databases"), &custom("Rust")]
+        );
+        assert!(tree.is_within(&custom("Rust"), &Genre::Technical));
+        assert!(!tree.is_within(&Genre::Technical, &custom("Rust")));
+    }
+
+    #[test]
+    fn test_cycles_are_refused() {
+        let mut tree = technical_tree();
+        let err = tree.set_parent(Genre::Technical, custom("Rust")).unwrap_err();
+        assert_eq!(err.to_string(), "Technical can't be a subgenre of Rust: Rust is already within Technical");
+        assert!(tree.set_parent(Genre::Mystery, Genre::Mystery).is_err());
+        assert_eq!(tree, technical_tree());
+
+        // Re-parenting moves the whole branch
+        tree.set_parent(custom("Programming"), custom("Databases")).unwrap();
+        assert!(tree.set_parent(custom("Databases"), custom("Rust")).is_err());
+        assert_eq!(tree.remove_parent(&custom("Programming")), Some(custom("Databases")));
+        assert!(tree.set_parent(custom("Databases"), custom("Rust")).is_ok());
+    }
+
+    #[test]
+    fn test_books_in_genre_tree() {
+        let mut lib = Library::new();
+        lib.set_genre_tree(technical_tree());
+        lib.add_book(Book::new(1, "The Rust Book", custom("Rust"))).unwrap();
+        lib.add_book(Book::new(2, "Designing Data-Intensive Applications", custom("Databases"))).unwrap();
+        lib.add_book(Book::new(3, "Clean Code", Genre::Technical)).unwrap();
+        lib.add_book(Book::new(4, "Dune", Genre::SciFi)).unwrap();
+
+        let ids = |genre: &Genre| lib.books_in_genre_tree(genre).iter().map(|b| b.id()).collect::<Vec<_>>();
+        assert_eq!(ids(&Genre::Technical), [1, 2, 3]);
+        assert_eq!(ids(&custom("Programming")), [1]);
+        assert_eq!(ids(&custom("Rust")), [1]);
+        assert_eq!(ids(&Genre::Fiction), Vec::<u64>::new());
+    }
+
+    #[test]
+    fn test_books_grouped_by_genre() {
+        let mut lib = Library::new();
+        lib.set_genre_tree(technical_tree());
+        lib.add_book(Book::new(1, "Dune", Genre::SciFi)).unwrap();
+        lib.add_book(Book::new(2, "The Rust Book", custom("Rust"))).unwrap();
+        lib.add_book(Book::new(3, "Foundation", Genre::SciFi)).unwrap();
+        lib.register_member(Member::new(10, "Alice", MembershipTier::Basic)).unwrap();
+        lib.checkout(10, 1, NaiveDate::from_ymd_opt(2024, 3, 1).unwrap()).unwrap();
+
+        let grouped = lib.books_by_genre();
+        let sci_fi: Vec<u64> = grouped[&Genre::SciFi].iter().map(|b| b.id()).collect();
+        assert_eq!(sci_fi, [3, 1]);
+        assert!(!grouped.contains_key(&Genre::Technical));
+
+        let counts = lib.genre_counts();
+        assert_eq!(counts.len(), 2);
+        assert_eq!((counts[&Genre::SciFi], counts[&custom("Rust")]), (2, 1));
+    }
+}
@@ -0,0 +1,153 @@
+//! IDs module - hands out book and member IDs.
+//!
+//! Private to the crate: [`IdAllocator`] only tracks the highest ID of each
+//! kind the library has seen, and the public API is the `impl Library`
+//! block below. Because the allocator remembers IDs that were used and
+//! later removed, a new book never takes over the ID (and barcode) of a
+//! withdrawn one while the library is loaded.
+//!
+//! ```
+//! use module_8::{Book, Genre, Library, LibraryError, MembershipTier};
+//!
+//! let mut lib = Library::new();
+//! lib.add_book(Book::new(7, "Dune", Genre::SciFi)).unwrap();
+//! assert_eq!(lib.add_book_auto("Emma", Genre::Fiction), Ok(8));
+//! assert_eq!(lib.register_member_auto("Alice", MembershipTier::Basic), Ok(1));
+//!
+//! let err = lib.add_book(Book::new(7, "Dune Messiah", Genre::SciFi));
+//! assert_eq!(err, Err(LibraryError::DuplicateBook(7)));
+//! ```
+
+use crate::book::{Book, Genre};
+use crate::error::LibraryError;
+use crate::member::{Member, TierPolicy};
+use crate::Library;
+
+/// The next free book and member IDs.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct IdAllocator {
+    next_book: u64,
+    next_member: u64,
+}
+
+impl Default for IdAllocator {
+    /// IDs start at 1 (see [`validate_id`](crate::validation::validate_id)).
+    fn default() -> Self {
+        IdAllocator { next_book: 1, next_member: 1 }
+    }
+}
+
+impl IdAllocator {
+    /// Records that a book ID is taken, explicitly or automatically.
+    pub(crate) fn book_used(&mut self, id: u64) {
+        self.next_book = self.next_book.max(id.saturating_add(1));
+    }
+
+    pub(crate) fn member_used(&mut self, id: u64) {
+        self.next_member = self.next_member.max(id.saturating_add(1));
+    }
+
+    pub(crate) fn next_book(&self) -> u64 {
+        self.next_book
+    }
+
+    pub(crate) fn next_member(&self) -> u64 {
+        self.next_member
+    }
+}
+
+// =============================================================================
+// LIBRARY INTEGRATION
+// =============================================================================
+
+impl Library {
+    /// Catalogues a new book under the next free ID, and returns the ID.
+    ///
+    /// # Errors
+    ///
+    /// [`LibraryError::Invalid`] if the title fails
+    /// [`validate_title`](crate::validation::validate_title), and otherwise
+    /// whatever [`add_book`](Library::add_book) returns, such as
+    /// `CapacityReached` when the catalog is full.
+    pub fn add_book_auto(&mut self, title: &str, genre: Genre) -> Result<u64, LibraryError> {
+        let id = self.ids.next_book();
+        let book = Book::try_new(id, title, genre).map_err(LibraryError::Invalid)?;
+        self.add_book(book)?;
+        Ok(id)
+    }
+
+    /// Registers a new member under the next free ID, and returns the ID.
+    ///
+    /// # Errors
+    ///
+    /// [`LibraryError::Invalid`] if the name fails
+    /// [`validate_member_name`](crate::validation::validate_member_name), and
+    /// otherwise whatever [`register_member`](Library::register_member)
+    /// returns.
+    pub fn register_member_auto(&mut self, name: &str, tier: impl TierPolicy + 'static) -> Result<u64, LibraryError> {
+        let id = self.ids.next_member();
+        let member = Member::try_new(id, name, tier).map_err(LibraryError::Invalid)?;
+        self.register_member(member)?;
+        Ok(id)
+    }
+}
+
+// =============================================================================
+// TESTS
+// =============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::validation::ValidationError;
+    use crate::MembershipTier;
+
+    #[test]
+    fn test_auto_ids_skip_used_and_removed_ids() {
+        let mut lib = Library::new();
+        assert_eq!(lib.add_book_auto("Dune", Genre::SciFi), Ok(1));
+        lib.add_book(Book::new(5, "Emma", Genre::Fiction)).unwrap();
+        assert_eq!(lib.add_book_auto("Beloved", Genre::Fiction), Ok(6));
+        lib.remove_book(6).unwrap();
+        assert_eq!(lib.generate_id(), 7);
+        assert_eq!(lib.add_book_auto("Anathem", Genre::SciFi), Ok(7));
+
+        lib.add_book(Book::new(20, "Gone Girl", Genre::Mystery)).unwrap();
+        lib.register_member(Member::new(3, "Bob", MembershipTier::Basic)).unwrap();
+        assert_eq!(lib.register_member_auto("Alice", MembershipTier::Gold), Ok(4));
+        assert_eq!(lib.add_book_auto("Persuasion", Genre::Fiction), Ok(21));
+        assert_eq!(lib.check_invariants(), Ok(()));
+    }
+
+    #[test]
+    fn test_explicit_duplicates_are_rejected() {
+        let mut lib = Library::new();
+        lib.add_book(Book::new(1, "Dune", Genre::SciFi)).unwrap();
+        lib.register_member(Member::new(1, "Alice", MembershipTier::Basic)).unwrap();
+
+        let err = lib.add_book(Book::new(1, "Emma", Genre::Fiction)).unwrap_err();
+        assert_eq!(err.to_string(), "Book ID 1 is already in use");
+        assert_eq!(lib.find_book(1).unwrap().title, "Dune");
+
+        let err = lib.register_member(Member::new(1, "Bob", MembershipTier::Basic));
+        assert_eq!(err, Err(LibraryError::DuplicateMember(1)));
+        assert_eq!(lib.find_member(1).unwrap().name, "Alice");
+        assert_eq!(lib.add_book(Book::new(2, "Emma", Genre::Fiction)), Ok(()));
+    }
+
+    #[test]
+    fn test_auto_ids_report_errors() {
+        let mut lib = Library::with_config(crate::LibraryConfig { max_books: Some(1), ..Default::default() });
+        let err = lib.add_book_auto("  ", Genre::Fiction);
+        assert_eq!(err, Err(LibraryError::Invalid(ValidationError::Empty { field: "title" })));
+        let err = lib.register_member_auto("R2-D2", MembershipTier::Basic).unwrap_err();
+        assert!(matches!(err, LibraryError::Invalid(ValidationError::InvalidCharacter { character: '2', .. })));
+
+        assert_eq!(lib.add_book_auto("Dune", Genre::SciFi), Ok(1));
+        let err = lib.add_book_auto("Emma", Genre::Fiction);
+        assert!(matches!(err, Err(LibraryError::CapacityReached { limit: 1, .. })));
+        // Failed adds don't use up IDs
+        assert_eq!(lib.register_member_auto("Alice", MembershipTier::Basic), Ok(1));
+        assert_eq!(lib.generate_id(), 2);
+    }
+}
@@ -0,0 +1,357 @@
+//! ILL module - interlibrary loans of titles the library doesn't own.
+//!
+//! When a member asks for a title that isn't in the catalog, staff can
+//! borrow it from another library. [`Library::request_ill`] records the
+//! request against the member who asked, and the request then moves through
+//! [`IllStatus::Requested`], [`Shipped`](IllStatus::Shipped),
+//! [`Received`](IllStatus::Received) and [`Returned`](IllStatus::Returned)
+//! in that order. Postage, lending fees and the like are added to the
+//! request as [`IllCost`]s, so the library can see what each loan cost.
+//!
+//! Borrowed titles never enter the catalog: they belong to the lender.
+//!
+//! ```
+//! use chrono::NaiveDate;
+//! use module_8::ill::IllStatus;
+//! use module_8::{Library, Member, MembershipTier, Money};
+//!
+//! let day = |d| NaiveDate::from_ymd_opt(2024, 3, d).unwrap();
+//! let mut lib = Library::new();
+//! lib.register_member(Member::new(10, "Alice", MembershipTier::Basic)).unwrap();
+//!
+//! let id = lib.request_ill(10, "The Name of the Rose", None, day(1)).unwrap();
+//! lib.record_ill_shipped(id, "City Library", day(4)).unwrap();
+//! lib.add_ill_cost(id, "Postage", Money::from_cents(450), day(4)).unwrap();
+//! lib.record_ill_received(id, day(6)).unwrap();
+//!
+//! let request = lib.ill_request(id).unwrap();
+//! assert_eq!(request.status, IllStatus::Received);
+//! assert_eq!(request.lender.as_deref(), Some("City Library"));
+//! assert_eq!(lib.ill_spending(), Money::from_cents(450));
+//! ```
+
+use std::fmt;
+
+use chrono::NaiveDate;
+
+use crate::error::LibraryError;
+use crate::money::Money;
+use crate::validation::{validate_title, ValidationError};
+use crate::Library;
+
+// =============================================================================
+// REQUESTS
+// =============================================================================
+
+/// Where an interlibrary loan stands. Requests only move forward, one step
+/// at a time.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum IllStatus {
+    /// Asked for, not yet sent by a lender.
+    Requested,
+    /// On its way from the lender.
+    Shipped,
+    /// Here, and with the member or waiting for them.
+    Received,
+    /// Sent back to the lender; the request is closed.
+    Returned,
+}
+
+impl IllStatus {
+    /// The status that follows this one, or `None` once returned.
+    pub fn next(self) -> Option<IllStatus> {
+        match self {
+            IllStatus::Requested => Some(IllStatus::Shipped),
+            IllStatus::Shipped => Some(IllStatus::Received),
+            IllStatus::Received => Some(IllStatus::Returned),
+            IllStatus::Returned => None,
+        }
+    }
+}
+
+impl fmt::Display for IllStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            IllStatus::Requested => "requested",
+            IllStatus::Shipped => "shipped",
+            IllStatus::Received => "received",
+            IllStatus::Returned => "returned",
+        };
+        f.write_str(name)
+    }
+}
+
+/// Something the library paid for an interlibrary loan.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct IllCost {
+    pub description: String,
+    pub amount: Money,
+    pub on: NaiveDate,
+}
+
+/// A member's request for a title borrowed from another library.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct IllRequest {
+    pub id: u64,
+    /// The member who asked for the title.
+    pub member_id: u64,
+    pub title: String,
+    pub isbn: Option<String>,
+    /// The library lending the title, known once it ships.
+    pub lender: Option<String>,
+    pub status: IllStatus,
+    pub requested_on: NaiveDate,
+    pub shipped_on: Option<NaiveDate>,
+    pub received_on: Option<NaiveDate>,
+    pub returned_on: Option<NaiveDate>,
+    pub costs: Vec<IllCost>,
+}
+
+impl IllRequest {
+    /// Everything paid for this loan so far.
+    pub fn total_cost(&self) -> Money {
+        Money::saturating_sum(self.costs.iter().map(|cost| cost.amount))
+    }
+
+    /// Returns `true` until the title has gone back to the lender.
+    pub fn is_open(&self) -> bool {
+        self.status != IllStatus::Returned
+    }
+}
+
+// =============================================================================
+// ERRORS
+// =============================================================================
+
+/// Errors from interlibrary loan operations.
+#[derive(Debug, Clone, PartialEq)]
+pub enum IllError {
+    /// No interlibrary loan request has this ID.
+    UnknownRequest(u64),
+    /// The title is already in the catalog as this book.
+    AlreadyOwned { book_id: u64 },
+    /// The request isn't at the status before the one being recorded.
+    OutOfOrder { request_id: u64, status: IllStatus, next: IllStatus },
+    /// The requested title is not a valid title.
+    InvalidTitle(ValidationError),
+    /// The library refused the operation, e.g. the member doesn't exist.
+    Library(LibraryError),
+}
+
+impl fmt::Display for IllError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            IllError::UnknownRequest(id) => write!(f, "No interlibrary loan request with ID {}", id),
+            IllError::AlreadyOwned { book_id } => write!(f, "The library already owns this title as book {}", book_id),
+            IllError::OutOfOrder { request_id, status, next } => write!(
+                f,
+                "Interlibrary loan {} is {} and can't be marked {}",
+                request_id, status, next
+            ),
+            IllError::InvalidTitle(e) => e.fmt(f),
+            IllError::Library(e) => e.fmt(f),
+        }
+    }
+}
+
+impl std::error::Error for IllError {}
+
+impl From<LibraryError> for IllError {
+    fn from(e: LibraryError) -> Self {
+        IllError::Library(e)
+    }
+}
+
+// =============================================================================
+// LIBRARY INTEGRATION
+// =============================================================================
+
+impl Library {
+    /// Records a member's request for a title the library doesn't own,
+    /// returning the new request's ID.
+    ///
+    /// # Errors
+    ///
+    /// [`IllError::Library`] with [`LibraryError::MemberNotFound`] for an
+    /// unknown member, [`IllError::InvalidTitle`] for an empty title, and
+    /// [`IllError::AlreadyOwned`] if a catalogued book has the same ISBN or
+    /// title.
+    pub fn request_ill(&mut self, member_id: u64, title: &str, isbn: Option<&str>, today: NaiveDate) -> Result<u64, IllError> {
+        self.find_member(member_id).ok_or(LibraryError::MemberNotFound(member_id))?;
+        validate_title(title).map_err(IllError::InvalidTitle)?;
+        let owned = match isbn.and_then(|isbn| self.find_by_isbn(isbn)) {
+            Some(book) => Some(book),
+            None => self.find_by_title(title).into_iter().next(),
+        };
+        if let Some(book) = owned {
+            return Err(IllError::AlreadyOwned { book_id: book.id() });
+        }
+
+        let id = self.ill_requests.len() as u64 + 1;
+        self.ill_requests.push(IllRequest {
+            id,
+            member_id,
+            title: String::from(title.trim()),
+            isbn: isbn.map(String::from),
+            lender: None,
+            status: IllStatus::Requested,
+            requested_on: today,
+            shipped_on: None,
+            received_on: None,
+            returned_on: None,
+            costs: Vec::new(),
+        });
+        Ok(id)
+    }
+
+    /// Records that `lender` has sent the title.
+    pub fn record_ill_shipped(&mut self, request_id: u64, lender: &str, on: NaiveDate) -> Result<(), IllError> {
+        let request = self.advance_ill(request_id, IllStatus::Shipped)?;
+        request.lender = Some(String::from(lender));
+        request.shipped_on = Some(on);
+        Ok(())
+    }
+
+    /// Records that the title has arrived.
+    pub fn record_ill_received(&mut self, request_id: u64, on: NaiveDate) -> Result<(), IllError> {
+        self.advance_ill(request_id, IllStatus::Received)?.received_on = Some(on);
+        Ok(())
+    }
+
+    /// Records that the title has been sent back to the lender, closing the
+    /// request.
+    pub fn record_ill_returned(&mut self, request_id: u64, on: NaiveDate) -> Result<(), IllError> {
+        self.advance_ill(request_id, IllStatus::Returned)?.returned_on = Some(on);
+        Ok(())
+    }
+
+    /// Adds a cost to a request, at any status; return postage usually comes
+    /// after the title has gone back.
+    pub fn add_ill_cost(&mut self, request_id: u64, description: &str, amount: Money, on: NaiveDate) -> Result<(), IllError> {
+        let request = self.ill_request_mut(request_id)?;
+        request.costs.push(IllCost {
+            description: String::from(description),
+            amount,
+            on,
+        });
+        Ok(())
+    }
+
+    /// Every interlibrary loan request, oldest first.
+    pub fn ill_requests(&self) -> &[IllRequest] {
+        &self.ill_requests
+    }
+
+    pub fn ill_request(&self, request_id: u64) -> Option<&IllRequest> {
+        self.ill_requests.iter().find(|r| r.id == request_id)
+    }
+
+    /// The requests `member_id` has made, oldest first.
+    pub fn ill_requests_for(&self, member_id: u64) -> Vec<&IllRequest> {
+        self.ill_requests.iter().filter(|r| r.member_id == member_id).collect()
+    }
+
+    /// What all interlibrary loans have cost so far.
+    pub fn ill_spending(&self) -> Money {
+        Money::saturating_sum(self.ill_requests.iter().map(IllRequest::total_cost))
+    }
+
+    fn ill_request_mut(&mut self, request_id: u64) -> Result<&mut IllRequest, IllError> {
+        self.ill_requests
+            .iter_mut()
+            .find(|r| r.id == request_id)
+            .ok_or(IllError::UnknownRequest(request_id))
+    }
+
+    /// Moves a request on to `next`, which must follow its current status.
+    fn advance_ill(&mut self, request_id: u64, next: IllStatus) -> Result<&mut IllRequest, IllError> {
+        let request = self.ill_request_mut(request_id)?;
+        if request.status.next() != Some(next) {
+            return Err(IllError::OutOfOrder { request_id, status: request.status, next });
+        }
+        request.status = next;
+        Ok(request)
+    }
+}
+
+// =============================================================================
+// TESTS
+// =============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Book, Genre, Member, MembershipTier};
+
+    fn date(day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(2024, 3, day).unwrap()
+    }
+
+    fn library() -> Library {
+        let mut lib = Library::new();
+        lib.add_book(Book::builder(1, "Dune").genre(Genre::SciFi).isbn("0441172717").build()).unwrap();
+        lib.register_member(Member::new(10, "Alice", MembershipTier::Basic)).unwrap();
+        lib.register_member(Member::new(11, "Bob", MembershipTier::Gold)).unwrap();
+        lib
+    }
+
+    #[test]
+    fn test_request_moves_through_each_status() {
+        let mut lib = library();
+        let id = lib.request_ill(10, " Foucault's Pendulum ", Some("0345368754"), date(1)).unwrap();
+        assert_eq!(lib.ill_request(id).unwrap().title, "Foucault's Pendulum");
+
+        let early = lib.record_ill_received(id, date(2)).unwrap_err();
+        assert_eq!(early, IllError::OutOfOrder { request_id: id, status: IllStatus::Requested, next: IllStatus::Received });
+        assert_eq!(early.to_string(), "Interlibrary loan 1 is requested and can't be marked received");
+
+        lib.record_ill_shipped(id, "City Library", date(3)).unwrap();
+        lib.record_ill_received(id, date(5)).unwrap();
+        assert!(lib.ill_request(id).unwrap().is_open());
+        lib.record_ill_returned(id, date(20)).unwrap();
+        assert!(lib.record_ill_shipped(id, "City Library", date(21)).is_err());
+
+        let request = lib.ill_request(id).unwrap();
+        assert_eq!(request.status, IllStatus::Returned);
+        assert!(!request.is_open());
+        assert_eq!(
+            (request.shipped_on, request.received_on, request.returned_on),
+            (Some(date(3)), Some(date(5)), Some(date(20)))
+        );
+        assert_eq!(lib.record_ill_received(9, date(5)), Err(IllError::UnknownRequest(9)));
+    }
+
+    #[test]
+    fn test_requests_are_refused_for_owned_titles_and_unknown_members() {
+        let mut lib = library();
+        assert_eq!(lib.request_ill(10, "DUNE", None, date(1)), Err(IllError::AlreadyOwned { book_id: 1 }));
+        assert_eq!(lib.request_ill(10, "Dune (2nd ed.)", Some("0441172717"), date(1)), Err(IllError::AlreadyOwned { book_id: 1 }));
+        assert_eq!(
+            lib.request_ill(99, "Emma", None, date(1)),
+            Err(IllError::Library(LibraryError::MemberNotFound(99)))
+        );
+        assert!(matches!(lib.request_ill(10, "  ", None, date(1)), Err(IllError::InvalidTitle(_))));
+        assert!(lib.ill_requests().is_empty());
+    }
+
+    #[test]
+    fn test_costs_per_request_and_member() {
+        let mut lib = library();
+        let first = lib.request_ill(10, "Emma", None, date(1)).unwrap();
+        let second = lib.request_ill(11, "Ulysses", None, date(2)).unwrap();
+        let third = lib.request_ill(10, "Beloved", None, date(3)).unwrap();
+        lib.add_ill_cost(first, "Lending fee", Money::from_cents(500), date(2)).unwrap();
+        lib.add_ill_cost(first, "Postage", Money::from_cents(325), date(2)).unwrap();
+        lib.add_ill_cost(second, "Postage", Money::from_cents(325), date(4)).unwrap();
+
+        assert_eq!(lib.ill_request(first).unwrap().total_cost(), Money::from_cents(825));
+        assert_eq!(lib.ill_request(third).unwrap().total_cost(), Money::ZERO);
+        assert_eq!(lib.ill_spending(), Money::from_cents(1150));
+        let ids: Vec<u64> = lib.ill_requests_for(10).iter().map(|r| r.id).collect();
+        assert_eq!(ids, [first, third]);
+        assert_eq!(lib.add_ill_cost(7, "Postage", Money::ZERO, date(4)), Err(IllError::UnknownRequest(7)));
+    }
+}
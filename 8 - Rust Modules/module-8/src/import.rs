@@ -0,0 +1,94 @@
+//! Import module - loads catalog data from external sources.
+//!
+//! This is another DIRECTORY-BASED MODULE in the modern style:
+//!
+//! ```text
+//! src/
+//! ├── import.rs           ← Entry point (THIS FILE): shared report types
+//! └── import/
+//!     ├── goodreads.rs    ← Goodreads "My Books" CSV export
+//!     └── openlibrary.rs  ← Open Library edition records (JSON)
+//! ```
+//!
+//! Every importer is lenient: a malformed row is recorded in the
+//! [`ImportReport`] and the import carries on with the next row.
+
+use std::fmt;
+
+// Submodule per source format. Public so callers can reach format-specific
+// helpers, e.g. `module_8::import::goodreads::shelf_to_genre`.
+pub mod goodreads;
+pub mod openlibrary;
+
+// =============================================================================
+// SHARED REPORT TYPES
+// =============================================================================
+
+/// A row that could not be imported.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RowError {
+    /// 1-based line number in the source (the header is line 1).
+    pub line: u64,
+    pub message: String,
+}
+
+/// Summary of an import run.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct ImportReport {
+    /// IDs assigned to the books that were added, in source order.
+    pub imported: Vec<u64>,
+    /// IDs of catalogued books that rows were merged into, because they
+    /// were duplicates (see [`dedup`](crate::dedup)).
+    pub merged: Vec<u64>,
+    /// Rows that were skipped, with the reason.
+    pub errors: Vec<RowError>,
+}
+
+impl ImportReport {
+    /// Returns `true` if every row was imported.
+    pub fn is_clean(&self) -> bool {
+        self.errors.is_empty()
+    }
+}
+
+// =============================================================================
+// FATAL ERRORS
+// =============================================================================
+
+/// Errors that abort an import before any row is processed.
+#[derive(Debug)]
+pub enum ImportError {
+    /// The source could not be read or parsed at all.
+    Csv(csv::Error),
+    /// The source could not be read.
+    Io(std::io::Error),
+    /// A required column is absent from the header.
+    MissingColumn(&'static str),
+    /// The member designated to receive reading history is not registered.
+    UnknownMember(u64),
+}
+
+impl fmt::Display for ImportError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ImportError::Csv(e) => write!(f, "Could not read CSV: {}", e),
+            ImportError::Io(e) => write!(f, "Could not read input: {}", e),
+            ImportError::MissingColumn(name) => write!(f, "Missing required column: {}", name),
+            ImportError::UnknownMember(id) => write!(f, "No member with ID {}", id),
+        }
+    }
+}
+
+impl std::error::Error for ImportError {}
+
+impl From<csv::Error> for ImportError {
+    fn from(e: csv::Error) -> Self {
+        ImportError::Csv(e)
+    }
+}
+
+impl From<std::io::Error> for ImportError {
+    fn from(e: std::io::Error) -> Self {
+        ImportError::Io(e)
+    }
+}
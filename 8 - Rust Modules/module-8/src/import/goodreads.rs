@@ -0,0 +1,311 @@
+//! Goodreads importer - reads the CSV produced by "My Books → Export".
+//!
+//! This file is loaded because `import.rs` contains `pub mod goodreads;`,
+//! so its full path is `crate::import::goodreads`.
+//!
+//! Recognized columns (matched by header name, in any order):
+//!
+//! | Column                     | Used for                          |
+//! |----------------------------|-----------------------------------|
+//! | `Title` (required)         | `Book::title`                     |
+//! | `Author`                   | `Book::author`                    |
+//! | `ISBN13`                   | `Book::isbn`                      |
+//! | `Bookshelves` / `Shelves`  | genre (first shelf naming one)    |
+//! | `Date Read`                | history of the designated member  |
+
+use std::io::Read;
+
+use chrono::NaiveDate;
+
+use super::{ImportError, ImportReport, RowError};
+use crate::book::{Book, Genre};
+use crate::dedup::{AddOutcome, OnDuplicate};
+use crate::member::LoanRecord;
+use crate::money::Money;
+use crate::Library;
+
+/// Genre given to rows whose shelves don't name a known genre.
+pub const DEFAULT_GENRE: Genre = Genre::Fiction;
+
+// =============================================================================
+// FIELD MAPPING HELPERS
+// =============================================================================
+
+/// Maps a Goodreads shelf name to a genre, if it names one.
+///
+/// # Examples
+///
+/// ```
+/// use module_8::import::goodreads::shelf_to_genre;
+/// use module_8::Genre;
+///
+/// assert_eq!(shelf_to_genre("Science Fiction"), Some(Genre::SciFi));
+/// assert_eq!(shelf_to_genre("to-read"), None);
+/// ```
+pub fn shelf_to_genre(shelf: &str) -> Option<Genre> {
+    let normalized = shelf.trim().to_lowercase().replace(['_', ' '], "-");
+    match normalized.as_str() {
+        "sci-fi" | "scifi" | "science-fiction" => Some(Genre::SciFi),
+        "mystery" | "mysteries" | "crime" | "thriller" => Some(Genre::Mystery),
+        "programming" | "technical" | "tech" | "computer-science" => Some(Genre::Technical),
+        "non-fiction" | "nonfiction" => Some(Genre::NonFiction),
+        "fiction" | "novels" => Some(Genre::Fiction),
+        _ => None,
+    }
+}
+
+/// Goodreads wraps ISBNs as `="9780131103627"` to stop spreadsheets from
+/// mangling them; keep only the digits (and a trailing `X` check digit).
+fn clean_isbn(raw: &str) -> Option<String> {
+    let isbn: String = raw
+        .chars()
+        .filter(|c| c.is_ascii_digit() || *c == 'X' || *c == 'x')
+        .collect();
+    if isbn.is_empty() {
+        None
+    } else {
+        Some(isbn.to_uppercase())
+    }
+}
+
+/// Parses a `Date Read` cell; Goodreads uses `YYYY/MM/DD`.
+fn parse_date_read(raw: &str) -> Result<Option<NaiveDate>, String> {
+    let raw = raw.trim();
+    if raw.is_empty() {
+        return Ok(None);
+    }
+    NaiveDate::parse_from_str(raw, "%Y/%m/%d")
+        .or_else(|_| NaiveDate::parse_from_str(raw, "%Y-%m-%d"))
+        .map(Some)
+        .map_err(|_| format!("Invalid Date Read: {:?}", raw))
+}
+
+/// Column positions resolved from the header row.
+struct Columns {
+    title: usize,
+    author: Option<usize>,
+    isbn13: Option<usize>,
+    shelves: Option<usize>,
+    date_read: Option<usize>,
+}
+
+impl Columns {
+    fn from_headers(headers: &csv::StringRecord) -> Result<Columns, ImportError> {
+        let find = |name: &str| headers.iter().position(|h| h.trim() == name);
+        Ok(Columns {
+            title: find("Title").ok_or(ImportError::MissingColumn("Title"))?,
+            author: find("Author"),
+            isbn13: find("ISBN13"),
+            shelves: find("Bookshelves").or_else(|| find("Shelves")),
+            date_read: find("Date Read"),
+        })
+    }
+}
+
+// =============================================================================
+// LIBRARY INTEGRATION
+// =============================================================================
+
+impl Library {
+    /// Imports books from a Goodreads CSV export.
+    ///
+    /// Each row becomes a new [`Book`] with a freshly assigned ID. Rows with a
+    /// `Date Read` are added to the history of the member `member_id`, so an
+    /// existing reading log carries over. A row for a book already in the
+    /// catalog (same title and ISBN) fills in that book's missing author
+    /// instead of adding another. Bad rows are skipped and listed in the
+    /// returned [`ImportReport`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use module_8::{Library, Member, MembershipTier};
+    ///
+    /// let csv = "Title,Author,ISBN13,Bookshelves,Date Read\n\
+    ///            Dune,Frank Herbert,=\"9780441172719\",sci-fi,2023/05/14\n\
+    ///            ,Nobody,,,\n";
+    ///
+    /// let mut lib = Library::new();
+    /// lib.register_member(Member::new(1, "Alice", MembershipTier::Gold)).unwrap();
+    /// let report = lib.import_goodreads_csv(csv.as_bytes(), 1).unwrap();
+    ///
+    /// assert_eq!(report.imported.len(), 1);
+    /// assert_eq!(report.errors.len(), 1);
+    /// assert_eq!(lib.find_member(1).unwrap().history().len(), 1);
+    /// ```
+    pub fn import_goodreads_csv<R: Read>(
+        &mut self,
+        reader: R,
+        member_id: u64,
+    ) -> Result<ImportReport, ImportError> {
+        if self.find_member(member_id).is_none() {
+            return Err(ImportError::UnknownMember(member_id));
+        }
+
+        let mut csv_reader = csv::ReaderBuilder::new()
+            .flexible(true)
+            .from_reader(reader);
+        let columns = Columns::from_headers(csv_reader.headers()?)?;
+
+        let mut report = ImportReport::default();
+        let mut next_id = self.next_book_id();
+
+        for result in csv_reader.records() {
+            let record = match result {
+                Ok(record) => record,
+                Err(e) => {
+                    let line = e.position().map_or(0, |p| p.line());
+                    report.errors.push(RowError { line, message: e.to_string() });
+                    continue;
+                }
+            };
+            let line = record.position().map_or(0, |p| p.line());
+            let cell = |index: Option<usize>| index.and_then(|i| record.get(i)).unwrap_or("");
+
+            let title = cell(Some(columns.title)).trim();
+            if title.is_empty() {
+                report.errors.push(RowError { line, message: String::from("Missing title") });
+                continue;
+            }
+            let date_read = match parse_date_read(cell(columns.date_read)) {
+                Ok(date) => date,
+                Err(message) => {
+                    report.errors.push(RowError { line, message });
+                    continue;
+                }
+            };
+
+            let genre = cell(columns.shelves)
+                .split(',')
+                .find_map(shelf_to_genre)
+                .unwrap_or(DEFAULT_GENRE);
+            let mut book = match Book::try_new(next_id, title, genre) {
+                Ok(book) => book,
+                Err(e) => {
+                    report.errors.push(RowError { line, message: e.to_string() });
+                    continue;
+                }
+            };
+            let author = cell(columns.author).trim();
+            if !author.is_empty() {
+                book.author = Some(String::from(author));
+            }
+            book.isbn = clean_isbn(cell(columns.isbn13));
+            let book_id = match self.add_book_deduplicated(book, OnDuplicate::Merge) {
+                Ok(AddOutcome::Added(id)) => {
+                    report.imported.push(id);
+                    next_id += 1;
+                    id
+                }
+                Ok(outcome) => {
+                    report.merged.push(outcome.id());
+                    outcome.id()
+                }
+                Err(e) => {
+                    report.errors.push(RowError { line, message: e.to_string() });
+                    continue;
+                }
+            };
+
+            if let Some(returned_on) = date_read {
+                if let Some(member) = self.member_mut(member_id) {
+                    member.record_history(LoanRecord {
+                        book_id,
+                        borrowed_on: None,
+                        returned_on,
+                        fee_charged: Money::ZERO,
+                    });
+                }
+            }
+        }
+
+        Ok(report)
+    }
+}
+
+// =============================================================================
+// TESTS
+// =============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Member, MembershipTier};
+
+    const EXPORT: &str = "\
+Book Id,Title,Author,ISBN13,Bookshelves,Date Read
+1,\"Rust in Action\",Tim McNamara,=\"9781617294556\",\"programming, to-read\",
+2,Foundation,Isaac Asimov,,\"classics, sci-fi\",2022/11/03
+3,Gone Girl,Gillian Flynn,,thriller,not-a-date
+4,Plain Title,,,,
+";
+
+    fn library_with_reader() -> Library {
+        let mut lib = Library::new();
+        lib.register_member(Member::new(7, "Reader", MembershipTier::Basic)).unwrap();
+        lib
+    }
+
+    #[test]
+    fn test_import_maps_columns() {
+        let mut lib = library_with_reader();
+        let report = lib.import_goodreads_csv(EXPORT.as_bytes(), 7).unwrap();
+
+        assert_eq!(report.imported, vec![1, 2, 3]);
+        let rust = lib.find_book(1).unwrap();
+        assert_eq!(rust.genre, Genre::Technical);
+        assert_eq!(rust.author.as_deref(), Some("Tim McNamara"));
+        assert_eq!(rust.isbn.as_deref(), Some("9781617294556"));
+        assert_eq!(lib.find_book(2).unwrap().genre, Genre::SciFi);
+        assert_eq!(lib.find_book(3).unwrap().genre, DEFAULT_GENRE);
+    }
+
+    #[test]
+    fn test_import_reports_bad_rows() {
+        let mut lib = library_with_reader();
+        let report = lib.import_goodreads_csv(EXPORT.as_bytes(), 7).unwrap();
+
+        assert_eq!(report.errors.len(), 1);
+        assert_eq!(report.errors[0].line, 4);
+        assert!(report.errors[0].message.contains("not-a-date"));
+    }
+
+    #[test]
+    fn test_import_records_read_dates() {
+        let mut lib = library_with_reader();
+        lib.import_goodreads_csv(EXPORT.as_bytes(), 7).unwrap();
+
+        let history = lib.find_member(7).unwrap().history();
+        assert_eq!(history.len(), 1);
+        assert_eq!(history[0].book_id, 2);
+        assert_eq!(history[0].returned_on, NaiveDate::from_ymd_opt(2022, 11, 3).unwrap());
+    }
+
+    #[test]
+    fn test_import_merges_duplicate_rows() {
+        let mut lib = library_with_reader();
+        lib.add_book(Book::builder(40, "Rust in Action").isbn("978-1-61729-455-6").build()).unwrap();
+        let csv = "Title,Author,ISBN13,Date Read\n\
+                   rust in action,Tim McNamara,=\"9781617294556\",2023/01/02\n\
+                   Rust in Action,Someone Else,,\n";
+        let report = lib.import_goodreads_csv(csv.as_bytes(), 7).unwrap();
+
+        assert_eq!(report.merged, vec![40]);
+        assert_eq!(report.imported, vec![41]);
+        assert_eq!(lib.find_book(40).unwrap().author.as_deref(), Some("Tim McNamara"));
+        assert_eq!(lib.find_member(7).unwrap().history()[0].book_id, 40);
+    }
+
+    #[test]
+    fn test_import_requires_title_and_member() {
+        let mut lib = library_with_reader();
+        assert!(matches!(
+            lib.import_goodreads_csv("Author\nX\n".as_bytes(), 7),
+            Err(ImportError::MissingColumn("Title"))
+        ));
+        assert!(matches!(
+            lib.import_goodreads_csv(EXPORT.as_bytes(), 99),
+            Err(ImportError::UnknownMember(99))
+        ));
+    }
+}
@@ -0,0 +1,301 @@
+//! Open Library importer - reads edition records from Open Library data dumps.
+//!
+//! This file is loaded because `import.rs` contains `pub mod openlibrary;`,
+//! so its full path is `crate::import::openlibrary`.
+//!
+//! The input has one record per line, either as plain JSON or as a line of
+//! the tab-separated bulk dump (`ol_dump_editions_*.txt`), whose last column
+//! is the JSON. Recognized fields:
+//!
+//! | Field                          | Used for                            |
+//! |--------------------------------|-------------------------------------|
+//! | `title` (required), `subtitle` | `Book::title`                       |
+//! | `authors`, `by_statement`      | `Book::author`                      |
+//! | `isbn_13`, `isbn_10`           | `Book::isbn` (first one, 13 first)  |
+//! | `publish_date`                 | `Book::year` (a four-digit year)    |
+//! | `publishers`                   | `Book::publisher` (the first one)   |
+//! | `number_of_pages`              | `Book::pages`                       |
+//! | `subjects`, `genres`           | genre (see [`infer_genre`])         |
+//!
+//! Records of another `type` (works, authors, ...) are skipped, as are
+//! editions whose ISBN is already in the catalog.
+
+use std::io::{BufRead, BufReader, Read};
+
+use serde_json::Value;
+
+use super::{ImportError, ImportReport, RowError};
+use crate::book::{Book, Genre};
+use crate::Library;
+
+/// Genre given to records whose subjects don't suggest one.
+pub const DEFAULT_GENRE: Genre = Genre::Fiction;
+
+// =============================================================================
+// FIELD MAPPING HELPERS
+// =============================================================================
+
+/// Guesses a genre from Open Library subject headings, which are free text
+/// such as "Science fiction" or "Detective and mystery stories".
+///
+/// The first subject that mentions a known genre decides.
+///
+/// # Examples
+///
+/// ```
+/// use module_8::import::openlibrary::infer_genre;
+/// use module_8::Genre;
+///
+/// assert_eq!(infer_genre(&["Accessible book", "Science fiction, American"]), Some(Genre::SciFi));
+/// assert_eq!(infer_genre(&["Computer programming"]), Some(Genre::Technical));
+/// assert_eq!(infer_genre(&["Protected DAISY"]), None);
+/// ```
+pub fn infer_genre(subjects: &[&str]) -> Option<Genre> {
+    subjects.iter().find_map(|subject| {
+        let subject = subject.to_lowercase();
+        let mentions = |words: &[&str]| words.iter().any(|w| subject.contains(w));
+        if mentions(&["science fiction", "sci-fi"]) {
+            Some(Genre::SciFi)
+        } else if mentions(&["mystery", "detective", "crime"]) {
+            Some(Genre::Mystery)
+        } else if mentions(&["programming", "computer", "software", "engineering"]) {
+            Some(Genre::Technical)
+        } else if mentions(&["nonfiction", "non-fiction", "biography", "history"]) {
+            Some(Genre::NonFiction)
+        } else if mentions(&["fiction", "novel"]) {
+            Some(Genre::Fiction)
+        } else {
+            None
+        }
+    })
+}
+
+/// Digits and a trailing `X` check digit, dropping hyphens and spaces.
+fn clean_isbn(raw: &str) -> Option<String> {
+    let isbn: String = raw
+        .chars()
+        .filter(|c| c.is_ascii_digit() || *c == 'X' || *c == 'x')
+        .collect();
+    if isbn.is_empty() {
+        None
+    } else {
+        Some(isbn.to_uppercase())
+    }
+}
+
+/// The first four-digit number in a free-text date such as "March 1965"
+/// or "1965-03-01".
+fn publish_year(date: &str) -> Option<u16> {
+    date.split(|c: char| !c.is_ascii_digit())
+        .find(|part| part.len() == 4)
+        .and_then(|year| year.parse().ok())
+}
+
+/// The strings in an array field; anything else in it is ignored.
+fn strings<'a>(record: &'a Value, field: &str) -> Vec<&'a str> {
+    record[field]
+        .as_array()
+        .map(|items| items.iter().filter_map(Value::as_str).collect())
+        .unwrap_or_default()
+}
+
+/// Author names, from `authors` entries that carry a name (API responses
+/// do; dump records only link to author records) or else `by_statement`.
+fn authors(record: &Value) -> Option<String> {
+    let named: Vec<&str> = record["authors"]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .filter_map(|a| a.as_str().or_else(|| a["name"].as_str()))
+        .map(str::trim)
+        .filter(|name| !name.is_empty())
+        .collect();
+    if !named.is_empty() {
+        return Some(named.join(", "));
+    }
+    record["by_statement"]
+        .as_str()
+        .map(|by| by.trim().trim_end_matches('.').trim())
+        .filter(|by| !by.is_empty())
+        .map(String::from)
+}
+
+/// Maps one edition record to a book with the given ID.
+///
+/// # Examples
+///
+/// ```
+/// use module_8::import::openlibrary::edition_to_book;
+/// use module_8::Genre;
+///
+/// let record = serde_json::json!({
+///     "title": "Dune",
+///     "authors": [{"name": "Frank Herbert"}],
+///     "isbn_13": ["978-0-441-17271-9"],
+///     "subjects": ["Science fiction"],
+/// });
+/// let book = edition_to_book(7, &record).unwrap();
+/// assert_eq!((book.id(), book.title.as_str(), book.genre), (7, "Dune", Genre::SciFi));
+/// assert_eq!(book.isbn.as_deref(), Some("9780441172719"));
+/// ```
+pub fn edition_to_book(id: u64, record: &Value) -> Result<Book, String> {
+    if !record.is_object() {
+        return Err(String::from("Record is not a JSON object"));
+    }
+    if let Some(kind) = record["type"]["key"].as_str() {
+        if kind != "/type/edition" {
+            return Err(format!("Not an edition: {}", kind));
+        }
+    }
+    let title = record["title"].as_str().map(str::trim).unwrap_or("");
+    if title.is_empty() {
+        return Err(String::from("Missing title"));
+    }
+    let title = match record["subtitle"].as_str().map(str::trim) {
+        Some(subtitle) if !subtitle.is_empty() => format!("{}: {}", title, subtitle),
+        _ => String::from(title),
+    };
+
+    let mut subjects = strings(record, "subjects");
+    subjects.extend(strings(record, "genres"));
+    let mut book = Book::try_new(id, &title, infer_genre(&subjects).unwrap_or(DEFAULT_GENRE)).map_err(|e| e.to_string())?;
+    book.author = authors(record);
+    book.year = record["publish_date"].as_str().and_then(publish_year);
+    book.publisher = strings(record, "publishers").first().map(|p| p.trim().to_string());
+    book.pages = record["number_of_pages"].as_u64().and_then(|n| u32::try_from(n).ok());
+    book.isbn = strings(record, "isbn_13")
+        .into_iter()
+        .chain(strings(record, "isbn_10"))
+        .find_map(clean_isbn);
+    Ok(book)
+}
+
+// =============================================================================
+// LIBRARY INTEGRATION
+// =============================================================================
+
+impl Library {
+    /// Imports editions from Open Library JSON records, one per line.
+    ///
+    /// Each edition becomes a new [`Book`] with a freshly assigned ID. Blank
+    /// lines are ignored; lines that aren't JSON, aren't editions, lack a
+    /// title, repeat a catalogued ISBN or don't fit within the catalog's
+    /// capacity limit are skipped and listed in the returned
+    /// [`ImportReport`]. Only a read failure stops the import.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use module_8::Library;
+    ///
+    /// let dump = "\
+    /// /type/edition\t/books/OL1M\t3\t2020-01-01\t{\"title\": \"Dune\", \"isbn_10\": [\"0441172717\"]}
+    /// {\"type\": {\"key\": \"/type/work\"}, \"title\": \"Dune\"}
+    /// not json
+    /// ";
+    ///
+    /// let mut lib = Library::new();
+    /// let report = lib.import_openlibrary(dump.as_bytes()).unwrap();
+    /// assert_eq!(report.imported, [1]);
+    /// assert_eq!(report.errors.len(), 2);
+    /// assert_eq!(lib.find_by_isbn("0441172717").unwrap().title, "Dune");
+    /// ```
+    pub fn import_openlibrary<R: Read>(&mut self, reader: R) -> Result<ImportReport, ImportError> {
+        let mut report = ImportReport::default();
+        let mut next_id = self.next_book_id();
+
+        for (index, line) in BufReader::new(reader).lines().enumerate() {
+            let line = line?;
+            let line_number = index as u64 + 1;
+            let json = line.rsplit('\t').next().unwrap_or("").trim();
+            if json.is_empty() {
+                continue;
+            }
+            let mut skip = |message: String| report.errors.push(RowError { line: line_number, message });
+
+            let record: Value = match serde_json::from_str(json) {
+                Ok(record) => record,
+                Err(e) => {
+                    skip(format!("Invalid JSON: {}", e));
+                    continue;
+                }
+            };
+            let book = match edition_to_book(next_id, &record) {
+                Ok(book) => book,
+                Err(message) => {
+                    skip(message);
+                    continue;
+                }
+            };
+            if let Some(isbn) = &book.isbn {
+                if let Some(existing) = self.find_by_isbn(isbn) {
+                    skip(format!("ISBN {} is already book {}", isbn, existing.id()));
+                    continue;
+                }
+            }
+
+            if let Err(e) = self.add_book(book) {
+                skip(e.to_string());
+                continue;
+            }
+            report.imported.push(next_id);
+            next_id += 1;
+        }
+
+        Ok(report)
+    }
+}
+
+// =============================================================================
+// TESTS
+// =============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DUMP: &str = r#"{"type": {"key": "/type/edition"}, "title": "The Rust Programming Language", "subtitle": "2nd Edition", "authors": [{"key": "/authors/OL1A"}], "by_statement": "Steve Klabnik and Carol Nichols.", "isbn_13": ["9781718503106"], "subjects": ["Rust (Computer program language)"], "publishers": ["No Starch Press"], "number_of_pages": 560}
+{"title": "Foundation", "authors": [{"name": "Isaac Asimov"}], "genres": ["Fiction."], "isbn_10": ["0-553-29335-4"], "publish_date": "May 1991"}
+
+{"title": "  ", "isbn_13": ["9780000000002"]}
+{"title": "Duplicate Rust Book", "isbn_13": ["978-1-7185-0310-6"]}
+[1, 2, 3]
+{"title": "Untitled Subjects", "subjects": ["Accessible book"]}
+"#;
+
+    #[test]
+    fn test_import_maps_fields() {
+        let mut lib = Library::new();
+        let report = lib.import_openlibrary(DUMP.as_bytes()).unwrap();
+        assert_eq!(report.imported, [1, 2, 3]);
+
+        let rust = lib.find_book(1).unwrap();
+        assert_eq!(rust.title, "The Rust Programming Language: 2nd Edition");
+        assert_eq!(rust.author.as_deref(), Some("Steve Klabnik and Carol Nichols"));
+        assert_eq!(rust.genre, Genre::Technical);
+        assert_eq!((rust.publisher.as_deref(), rust.pages), (Some("No Starch Press"), Some(560)));
+
+        let foundation = lib.find_book(2).unwrap();
+        assert_eq!(foundation.author.as_deref(), Some("Isaac Asimov"));
+        assert_eq!(foundation.isbn.as_deref(), Some("0553293354"));
+        assert_eq!(foundation.genre, Genre::Fiction);
+        assert_eq!((foundation.year, rust.year), (Some(1991), None));
+        assert_eq!(lib.find_book(3).unwrap().genre, DEFAULT_GENRE);
+    }
+
+    #[test]
+    fn test_import_reports_skipped_records() {
+        let mut lib = Library::new();
+        let report = lib.import_openlibrary(DUMP.as_bytes()).unwrap();
+
+        let errors: Vec<(u64, &str)> = report.errors.iter().map(|e| (e.line, e.message.as_str())).collect();
+        assert_eq!(
+            errors,
+            [
+                (4, "Missing title"),
+                (5, "ISBN 9781718503106 is already book 1"),
+                (6, "Record is not a JSON object"),
+            ]
+        );
+    }
+}
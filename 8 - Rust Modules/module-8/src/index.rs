@@ -0,0 +1,537 @@
+//! Index module - hash map storage bookkeeping for `Library`.
+//!
+//! `Library` stores every catalogued book and every member in `HashMap`s
+//! keyed by ID. This module keeps the extra maps that make every other lookup O(1)
+//! and lets iteration follow insertion order:
+//!
+//! | Index | Key | Value |
+//! |-------|-----|-------|
+//! | books | book ID | catalog sequence number + shelf or on loan |
+//! | shelf_order | sequence number | book ID (shelf books only, ordered) |
+//! | isbn | normalized ISBN | book ID |
+//! | titles | normalized title | book IDs |
+//! | words | word of a normalized title | book IDs (posting list) |
+//!
+//! Who has a book on loan is recorded only in the library's [`Loan`](crate::Loan)s;
+//! the index just keeps lent books out of shelf order.
+//!
+//! The module itself is private (`mod index;` in lib.rs); only the
+//! `Library` methods at the bottom of this file are public.
+
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::time::Instant;
+
+use crate::book::Book;
+use crate::Library;
+
+// =============================================================================
+// NORMALIZATION
+// =============================================================================
+
+/// Normalizes a title for lookups: lowercase alphanumeric words separated
+/// by single spaces, so "The  Rust Book!" and "the rust book" match.
+pub(crate) fn normalize_title(title: &str) -> String {
+    title
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .map(str::to_lowercase)
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Normalizes an ISBN for lookups: digits and `X` only, uppercased.
+pub(crate) fn normalize_isbn(isbn: &str) -> String {
+    isbn.chars()
+        .filter(|c| c.is_ascii_digit() || c.eq_ignore_ascii_case(&'x'))
+        .map(|c| c.to_ascii_uppercase())
+        .collect()
+}
+
+// =============================================================================
+// CATALOG INDEX
+// =============================================================================
+
+/// Where a catalogued book currently is.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) enum BookLocation {
+    /// On the shelf.
+    Shelf,
+    /// Lent out; the library's loans say to whom.
+    OnLoan,
+}
+
+/// Index entry for one catalogued book.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) struct BookEntry {
+    /// Position in catalog order; never reused, so it survives checkouts.
+    pub(crate) seq: u64,
+    pub(crate) location: BookLocation,
+}
+
+/// The normalized fields of a book that the secondary indexes are keyed by.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct IndexKeys {
+    title: String,
+    isbn: Option<String>,
+}
+
+impl IndexKeys {
+    pub(crate) fn of(book: &Book) -> Self {
+        IndexKeys {
+            title: normalize_title(&book.title),
+            isbn: book.isbn.as_deref().map(normalize_isbn),
+        }
+    }
+}
+
+/// Secondary indexes kept in sync with `Library`'s maps.
+#[derive(Debug, Default)]
+pub(crate) struct CatalogIndex {
+    books: HashMap<u64, BookEntry>,
+    shelf_order: BTreeMap<u64, u64>,
+    next_seq: u64,
+    isbn: HashMap<String, u64>,
+    titles: HashMap<String, Vec<u64>>,
+    words: HashMap<String, BTreeSet<u64>>,
+}
+
+impl CatalogIndex {
+    /// Indexes a newly catalogued book at the end of catalog order.
+    pub(crate) fn insert_book(&mut self, book: &Book, location: BookLocation) {
+        let seq = self.next_seq;
+        self.next_seq += 1;
+        self.books.insert(book.id(), BookEntry { seq, location });
+        if location == BookLocation::Shelf {
+            self.shelf_order.insert(seq, book.id());
+        }
+        self.insert_keys(book.id(), &IndexKeys::of(book));
+    }
+
+    /// Drops a book from every index.
+    pub(crate) fn remove_book(&mut self, book: &Book) {
+        if let Some(entry) = self.books.remove(&book.id()) {
+            self.shelf_order.remove(&entry.seq);
+        }
+        self.remove_keys(book.id(), &IndexKeys::of(book));
+    }
+
+    /// Re-indexes a book whose title or ISBN changed from `old`, keeping its
+    /// place in catalog order and where it is.
+    pub(crate) fn rekey_book(&mut self, old: &IndexKeys, book: &Book) {
+        let new = IndexKeys::of(book);
+        if *old != new {
+            self.remove_keys(book.id(), old);
+            self.insert_keys(book.id(), &new);
+        }
+    }
+
+    fn insert_keys(&mut self, id: u64, keys: &IndexKeys) {
+        if let Some(isbn) = &keys.isbn {
+            self.isbn.insert(isbn.clone(), id);
+        }
+        for word in keys.title.split_whitespace() {
+            self.words.entry(String::from(word)).or_default().insert(id);
+        }
+        // Keep books with equal titles in catalog order
+        let books = &self.books;
+        let seq = |id: &u64| books.get(id).map(|entry| entry.seq);
+        let ids = self.titles.entry(keys.title.clone()).or_default();
+        let at = ids.partition_point(|other| seq(other) < seq(&id));
+        ids.insert(at, id);
+    }
+
+    fn remove_keys(&mut self, id: u64, keys: &IndexKeys) {
+        if let Some(isbn) = &keys.isbn {
+            // Another copy may share the ISBN and own the entry
+            if self.isbn.get(isbn) == Some(&id) {
+                self.isbn.remove(isbn);
+            }
+        }
+        for word in keys.title.split_whitespace() {
+            if let Some(ids) = self.words.get_mut(word) {
+                ids.remove(&id);
+                if ids.is_empty() {
+                    self.words.remove(word);
+                }
+            }
+        }
+        if let Some(ids) = self.titles.get_mut(&keys.title) {
+            ids.retain(|&other| other != id);
+            if ids.is_empty() {
+                self.titles.remove(&keys.title);
+            }
+        }
+    }
+
+    /// Makes room for `additional` more books without rehashing.
+    pub(crate) fn reserve(&mut self, additional: usize) {
+        self.books.reserve(additional);
+        self.isbn.reserve(additional);
+        self.titles.reserve(additional);
+    }
+
+    /// Number of catalogued books, on the shelf or on loan.
+    pub(crate) fn len(&self) -> usize {
+        self.books.len()
+    }
+
+    /// Number of books on the shelf.
+    pub(crate) fn shelf_len(&self) -> usize {
+        self.shelf_order.len()
+    }
+
+    pub(crate) fn contains(&self, id: u64) -> bool {
+        self.books.contains_key(&id)
+    }
+
+    pub(crate) fn location(&self, id: u64) -> Option<BookLocation> {
+        self.books.get(&id).map(|entry| entry.location)
+    }
+
+    /// Marks a book as lent, taking it out of shelf order.
+    pub(crate) fn lend(&mut self, id: u64) {
+        if let Some(entry) = self.books.get_mut(&id) {
+            self.shelf_order.remove(&entry.seq);
+            entry.location = BookLocation::OnLoan;
+        }
+    }
+
+    /// Marks a book as back on the shelf, in its original catalog position.
+    pub(crate) fn shelve(&mut self, id: u64) {
+        if let Some(entry) = self.books.get_mut(&id) {
+            self.shelf_order.insert(entry.seq, id);
+            entry.location = BookLocation::Shelf;
+        }
+    }
+
+    /// Shelf book IDs in catalog order.
+    pub(crate) fn shelf_ids(&self) -> impl Iterator<Item = u64> + '_ {
+        self.shelf_order.values().copied()
+    }
+
+    pub(crate) fn book_by_isbn(&self, isbn: &str) -> Option<u64> {
+        self.isbn.get(&normalize_isbn(isbn)).copied()
+    }
+
+    pub(crate) fn books_by_title(&self, title: &str) -> &[u64] {
+        self.titles
+            .get(&normalize_title(title))
+            .map_or(&[], Vec::as_slice)
+    }
+
+    /// IDs of the books whose titles contain every word of `query`, in
+    /// catalog order. Intersects the posting lists, shortest first.
+    pub(crate) fn books_with_words(&self, query: &str) -> Vec<u64> {
+        let query = normalize_title(query);
+        let mut lists = Vec::new();
+        for word in query.split_whitespace() {
+            match self.words.get(word) {
+                Some(ids) => lists.push(ids),
+                None => return Vec::new(),
+            }
+        }
+        lists.sort_by_key(|ids| ids.len());
+        let Some((shortest, rest)) = lists.split_first() else {
+            return Vec::new();
+        };
+        let mut ids: Vec<u64> = shortest
+            .iter()
+            .copied()
+            .filter(|id| rest.iter().all(|ids| ids.contains(id)))
+            .collect();
+        ids.sort_by_key(|id| self.books.get(id).map(|entry| entry.seq));
+        ids
+    }
+}
+
+// =============================================================================
+// LIBRARY LOOKUPS
+// =============================================================================
+
+impl Library {
+    /// Looks up any catalogued book by ID, on the shelf or out on loan;
+    /// the same as [`find_book`](Library::find_book).
+    pub fn get_book(&self, id: u64) -> Option<&Book> {
+        self.books.get(&id)
+    }
+
+    /// Finds a book by ISBN, ignoring hyphens, spaces and case.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use module_8::{Book, Genre, Library};
+    ///
+    /// let mut lib = Library::new();
+    /// let mut book = Book::new(1, "Dune", Genre::SciFi);
+    /// book.isbn = Some(String::from("978-0-441-17271-9"));
+    /// lib.add_book(book).unwrap();
+    ///
+    /// assert_eq!(lib.find_by_isbn("9780441172719").unwrap().id(), 1);
+    /// ```
+    pub fn find_by_isbn(&self, isbn: &str) -> Option<&Book> {
+        self.get_book(self.index.book_by_isbn(isbn)?)
+    }
+
+    /// Finds all books whose normalized title matches exactly.
+    ///
+    /// Case and punctuation are ignored. Useful as a pre-filter before a
+    /// more expensive fuzzy search.
+    pub fn find_by_title(&self, title: &str) -> Vec<&Book> {
+        self.index
+            .books_by_title(title)
+            .iter()
+            .filter_map(|&id| self.get_book(id))
+            .collect()
+    }
+
+    /// Catalogued books (on the shelf or on loan) whose titles contain every
+    /// word of `query` as a whole word, in catalog order. Case, punctuation
+    /// and word order are ignored; an empty query finds nothing.
+    ///
+    /// Backed by an inverted index from title words to books, so the cost
+    /// depends on how many books share the query words rather than on the
+    /// size of the catalog.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use module_8::{Book, Genre, Library};
+    ///
+    /// let mut lib = Library::new();
+    /// lib.add_book(Book::new(1, "The Rust Programming Language", Genre::Technical)).unwrap();
+    /// lib.add_book(Book::new(2, "Programming Rust", Genre::Technical)).unwrap();
+    /// lib.add_book(Book::new(3, "Rust in Action", Genre::Technical)).unwrap();
+    ///
+    /// let ids: Vec<u64> = lib.search_titles("rust, programming").iter().map(|b| b.id()).collect();
+    /// assert_eq!(ids, [1, 2]);
+    /// assert!(lib.search_titles("prog").is_empty());
+    /// ```
+    pub fn search_titles(&self, query: &str) -> Vec<&Book> {
+        let started = Instant::now();
+        let found = self
+            .index
+            .books_with_words(query)
+            .into_iter()
+            .filter_map(|id| self.get_book(id))
+            .collect();
+        self.metrics.record_search(started.elapsed());
+        found
+    }
+
+    /// Verifies that every index agrees with the underlying maps.
+    ///
+    /// Returns a description of the first inconsistency found. Cheap enough
+    /// for tests and debug builds; every mutating method keeps this `Ok`.
+    pub fn check_invariants(&self) -> Result<(), String> {
+        let index = &self.index;
+
+        // Loans are the only record of who has what; the index must agree
+        let mut lent: HashMap<u64, usize> = HashMap::new();
+        for loan in &self.loans {
+            if !self.books.contains_key(&loan.book_id) {
+                return Err(format!("loan of uncatalogued book {}", loan.book_id));
+            }
+            if !self.members.contains_key(&loan.member_id) {
+                return Err(format!("loan of book {} to unknown member {}", loan.book_id, loan.member_id));
+            }
+            if !self.is_digital_loan(loan) {
+                *lent.entry(loan.book_id).or_default() += 1;
+            }
+        }
+
+        for (&id, book) in &self.books {
+            if book.id() != id {
+                return Err(format!("book {} is stored under key {}", book.id(), id));
+            }
+            let loans = lent.get(&id).copied().unwrap_or(0);
+            match index.books.get(&id) {
+                Some(BookEntry { location: BookLocation::Shelf, seq, .. }) => {
+                    if index.shelf_order.get(seq) != Some(&id) {
+                        return Err(format!("shelf book {} is missing from shelf order", id));
+                    }
+                    if loans > 0 {
+                        return Err(format!("book {} is on loan but indexed as on the shelf", id));
+                    }
+                }
+                Some(BookEntry { location: BookLocation::OnLoan, .. }) => {
+                    if loans != 1 {
+                        return Err(format!("book {} is off the shelf with {} loans", id, loans));
+                    }
+                }
+                None => return Err(format!("book {} is not indexed", id)),
+            }
+        }
+        let on_shelf = index.books.values().filter(|e| e.location == BookLocation::Shelf).count();
+        if index.shelf_order.len() != on_shelf {
+            return Err(format!(
+                "{} books in shelf order but {} on the shelf",
+                index.shelf_order.len(),
+                on_shelf
+            ));
+        }
+
+        let catalogued = self.books.len();
+        if index.books.len() != catalogued {
+            return Err(format!("{} books indexed but {} catalogued", index.books.len(), catalogued));
+        }
+
+        if self.member_order.len() != self.members.len() {
+            return Err(format!(
+                "{} members in registration order but {} registered",
+                self.member_order.len(),
+                self.members.len()
+            ));
+        }
+        for id in &self.member_order {
+            if self.members.get(id).map(|m| m.id()) != Some(*id) {
+                return Err(format!("member {} in registration order is mis-stored", id));
+            }
+        }
+
+        for (isbn, &id) in &index.isbn {
+            let matches = self
+                .get_book(id)
+                .and_then(|b| b.isbn.as_deref())
+                .is_some_and(|actual| normalize_isbn(actual) == *isbn);
+            if !matches {
+                return Err(format!("ISBN {} points at book {} which does not carry it", isbn, id));
+            }
+        }
+        let mut titled = 0;
+        for (title, ids) in &index.titles {
+            for &id in ids {
+                if self.get_book(id).map(|b| normalize_title(&b.title)).as_ref() != Some(title) {
+                    return Err(format!("title '{}' points at book {} which does not carry it", title, id));
+                }
+            }
+            titled += ids.len();
+        }
+        if titled != catalogued {
+            return Err(format!("{} books title-indexed but {} catalogued", titled, catalogued));
+        }
+        let mut postings = 0;
+        for (word, ids) in &index.words {
+            for &id in ids {
+                let carries = self
+                    .get_book(id)
+                    .is_some_and(|b| normalize_title(&b.title).split_whitespace().any(|w| w == word));
+                if !carries {
+                    return Err(format!("word '{}' points at book {} whose title lacks it", word, id));
+                }
+            }
+            postings += ids.len();
+        }
+        let expected: usize = self
+            .catalog()
+            .map(|b| normalize_title(&b.title).split_whitespace().collect::<BTreeSet<_>>().len())
+            .sum();
+        if postings != expected {
+            return Err(format!("{} title words indexed but {} in catalogued titles", postings, expected));
+        }
+        Ok(())
+    }
+}
+
+// =============================================================================
+// TESTS
+// =============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Genre, LibraryError, Member, MembershipTier};
+    use chrono::NaiveDate;
+
+    fn library() -> Library {
+        let mut lib = Library::new();
+        for (id, title) in [(1, "Dune"), (2, "Emma"), (3, "The Rust Book"), (4, "Dune")] {
+            let mut book = Book::new(id, title, Genre::Fiction);
+            book.isbn = Some(format!("978-0-00-00000{}-0", id));
+            lib.add_book(book).unwrap();
+        }
+        lib.register_member(Member::new(10, "Alice", MembershipTier::Gold)).unwrap();
+        lib.register_member(Member::new(11, "Bob", MembershipTier::Gold)).unwrap();
+        lib
+    }
+
+    #[test]
+    fn test_normalization() {
+        assert_eq!(normalize_title("  The Rust   Book! "), "the rust book");
+        assert_eq!(normalize_isbn("0-306-40615-x"), "030640615X");
+    }
+
+    #[test]
+    fn test_lookups() {
+        let lib = library();
+        assert_eq!(lib.find_book(3).unwrap().title, "The Rust Book");
+        assert_eq!(lib.find_member(11).unwrap().name, "Bob");
+        assert_eq!(lib.find_by_isbn("9780000000020").unwrap().id(), 2);
+        assert_eq!(lib.find_by_title("the RUST book").len(), 1);
+        assert_eq!(lib.find_by_title("dune").len(), 2);
+        assert!(lib.find_by_title("missing").is_empty());
+    }
+
+    #[test]
+    fn test_indexes_follow_circulation() {
+        let mut lib = library();
+        let today = NaiveDate::from_ymd_opt(2024, 3, 1).unwrap();
+
+        lib.checkout(10, 1, today).unwrap();
+        lib.checkout(11, 3, today).unwrap();
+        assert_eq!(lib.check_invariants(), Ok(()));
+        assert!(!lib.find_book(1).unwrap().is_available());
+        assert_eq!(lib.borrower(1), Some(10));
+        assert_eq!(lib.find_by_title("dune").len(), 2);
+
+        lib.return_book(10, 1, today).unwrap();
+        assert_eq!(lib.check_invariants(), Ok(()));
+        assert!(lib.find_book(1).unwrap().is_available());
+        assert_eq!(lib.borrower(1), None);
+    }
+
+    #[test]
+    fn test_iteration_keeps_insertion_order() {
+        let mut lib = library();
+        let today = NaiveDate::from_ymd_opt(2024, 3, 1).unwrap();
+        let shelf = |lib: &Library| lib.books().map(|b| b.id()).collect::<Vec<_>>();
+
+        lib.checkout(10, 2, today).unwrap();
+        assert_eq!(shelf(&lib), [1, 3, 4]);
+
+        // A returned book goes back to its original catalog position
+        lib.return_book(10, 2, today).unwrap();
+        assert_eq!(shelf(&lib), [1, 2, 3, 4]);
+
+        let names: Vec<&str> = lib.members().map(|m| m.name.as_str()).collect();
+        assert_eq!(names, ["Alice", "Bob"]);
+    }
+
+    #[test]
+    fn test_word_index_follows_adds_and_removals() {
+        let mut lib = library();
+        lib.add_book(Book::new(5, "Dune Messiah", Genre::SciFi)).unwrap();
+        lib.add_book(Book::new(6, "Children of Dune", Genre::SciFi)).unwrap();
+        let ids = |lib: &Library, query: &str| lib.search_titles(query).iter().map(|b| b.id()).collect::<Vec<_>>();
+
+        lib.checkout(10, 5, NaiveDate::from_ymd_opt(2024, 3, 1).unwrap()).unwrap();
+        // Catalog order, whether or not the book is on loan
+        assert_eq!(ids(&lib, "DUNE"), [1, 4, 5, 6]);
+        assert_eq!(ids(&lib, "of dune"), [6]);
+        assert!(ids(&lib, "dune emma").is_empty());
+        assert!(ids(&lib, " !").is_empty());
+
+        lib.remove_book(4).unwrap();
+        lib.remove_book(6).unwrap();
+        assert_eq!(ids(&lib, "dune"), [1, 5]);
+        assert!(ids(&lib, "children").is_empty());
+        assert_eq!(lib.check_invariants(), Ok(()));
+    }
+
+    #[test]
+    fn test_duplicate_book_id_is_refused() {
+        let mut lib = library();
+        assert_eq!(lib.add_book(Book::new(1, "Again", Genre::Fiction)), Err(LibraryError::DuplicateBook(1)));
+        assert_eq!(lib.find_book(1).unwrap().title, "Dune");
+    }
+}
@@ -0,0 +1,276 @@
+//! Item module - the kinds of things the library lends.
+//!
+//! Besides books, the library lends magazines and DVDs. Rather than a
+//! separate type per kind, every catalog entry is still a [`Book`] record
+//! with an [`ItemKind`]: loans, holds, fees and reports work on any kind
+//! unchanged, and code that only knows about books keeps working, since a
+//! new record is an `ItemKind::Book` unless told otherwise.
+//!
+//! Kinds differ in how long they may be kept: see [`ItemKind::max_loan_days`].
+//! Any kind may also come as a [`Format::Digital`] copy, which any number of
+//! members can borrow at once.
+//!
+//! ```
+//! use chrono::NaiveDate;
+//! use module_8::item::ItemKind;
+//! use module_8::{Book, Genre, Library, Member, MembershipTier};
+//!
+//! let mut lib = Library::new();
+//! lib.add_book(Book::new(1, "Dune", Genre::SciFi)).unwrap();
+//! lib.add_book(Book::media(2, "Dune (2021)", Genre::SciFi, 155)).unwrap();
+//! lib.register_member(Member::new(10, "Alice", MembershipTier::Gold)).unwrap();
+//! let today = NaiveDate::from_ymd_opt(2024, 3, 1).unwrap();
+//!
+//! // Gold members keep books for 30 days, but media only for a week
+//! assert_eq!(lib.checkout(10, 1, today).unwrap().to_string(), "2024-03-31");
+//! assert_eq!(lib.checkout(10, 2, today).unwrap().to_string(), "2024-03-08");
+//! assert_eq!(lib.borrowed_books(10)[1].kind, ItemKind::Media { runtime_minutes: 155 });
+//! ```
+
+use std::fmt;
+
+use crate::book::{Book, Genre};
+
+// =============================================================================
+// ITEM KINDS
+// =============================================================================
+
+/// What kind of item a catalog record describes.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum ItemKind {
+    #[default]
+    Book,
+    /// One issue of a magazine or journal, e.g. "Spring 2024" or "No. 112".
+    Periodical { issue: String },
+    /// A film or recording on disc.
+    Media { runtime_minutes: u32 },
+}
+
+impl ItemKind {
+    /// Longest loan allowed for this kind, whatever the member's tier.
+    /// `None` means the tier's loan period applies in full.
+    pub fn max_loan_days(&self) -> Option<u32> {
+        match self {
+            ItemKind::Book => None,
+            ItemKind::Periodical { .. } | ItemKind::Media { .. } => Some(7),
+        }
+    }
+
+    /// Loan period for an item of this kind lent to a member whose tier
+    /// allows `tier_days`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use module_8::item::ItemKind;
+    ///
+    /// let issue = ItemKind::Periodical { issue: String::from("May 2024") };
+    /// assert_eq!(issue.loan_days(14), 7);
+    /// assert_eq!(ItemKind::Book.loan_days(14), 14);
+    /// ```
+    pub fn loan_days(&self, tier_days: u32) -> u32 {
+        self.max_loan_days().map_or(tier_days, |max| max.min(tier_days))
+    }
+}
+
+impl fmt::Display for ItemKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ItemKind::Book => write!(f, "Book"),
+            ItemKind::Periodical { issue } => write!(f, "Periodical, {}", issue),
+            ItemKind::Media { runtime_minutes } => write!(f, "Media, {} min", runtime_minutes),
+        }
+    }
+}
+
+// =============================================================================
+// FORMATS
+// =============================================================================
+
+/// Whether a catalog record is a physical copy or a digital licence.
+///
+/// A digital copy never leaves the shelf: every checkout is a new loan of
+/// the same record, for the configured
+/// [`digital_loan_days`](crate::LibraryConfig::digital_loan_days), and
+/// holds on it are refused since there is never a wait.
+///
+/// # Examples
+///
+/// ```
+/// use chrono::NaiveDate;
+/// use module_8::{Book, Format, Genre, Library, Member, MembershipTier};
+///
+/// let mut lib = Library::new();
+/// let mut ebook = Book::new(1, "Dune", Genre::SciFi);
+/// ebook.format = Format::Digital;
+/// lib.add_book(ebook).unwrap();
+/// lib.register_member(Member::new(10, "Alice", MembershipTier::Basic)).unwrap();
+/// lib.register_member(Member::new(11, "Bob", MembershipTier::Gold)).unwrap();
+/// let today = NaiveDate::from_ymd_opt(2024, 3, 1).unwrap();
+///
+/// assert_eq!(lib.checkout(10, 1, today).unwrap().to_string(), "2024-03-22");
+/// assert_eq!(lib.checkout(11, 1, today).unwrap().to_string(), "2024-03-22");
+/// assert!(lib.find_book(1).unwrap().is_available());
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Format {
+    #[default]
+    Physical,
+    Digital,
+}
+
+impl fmt::Display for Format {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Format::Physical => write!(f, "Physical"),
+            Format::Digital => write!(f, "Digital"),
+        }
+    }
+}
+
+// =============================================================================
+// LIBRARY ITEM TRAIT
+// =============================================================================
+
+/// Anything the library can lend.
+///
+/// [`Book`] is the only implementation in this crate, covering every
+/// [`ItemKind`]; the trait lets code such as reports and notifiers be
+/// written once against the common surface.
+pub trait LibraryItem {
+    fn id(&self) -> u64;
+    fn title(&self) -> &str;
+    fn kind(&self) -> &ItemKind;
+
+    /// Loan period for this item given the member's tier allowance.
+    fn loan_days(&self, tier_days: u32) -> u32 {
+        self.kind().loan_days(tier_days)
+    }
+}
+
+impl LibraryItem for Book {
+    fn id(&self) -> u64 {
+        Book::id(self)
+    }
+
+    fn title(&self) -> &str {
+        &self.title
+    }
+
+    fn kind(&self) -> &ItemKind {
+        &self.kind
+    }
+}
+
+impl Book {
+    /// Creates a catalog record for one issue of a periodical.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use module_8::{Book, Genre};
+    ///
+    /// let issue = Book::periodical(3, "Rust Monthly", Genre::Technical, "May 2024");
+    /// assert_eq!(issue.kind.to_string(), "Periodical, May 2024");
+    /// ```
+    pub fn periodical(id: u64, title: &str, genre: Genre, issue: &str) -> Self {
+        let mut item = Book::new(id, title, genre);
+        item.kind = ItemKind::Periodical { issue: String::from(issue) };
+        item
+    }
+
+    /// Creates a catalog record for a film or recording.
+    pub fn media(id: u64, title: &str, genre: Genre, runtime_minutes: u32) -> Self {
+        let mut item = Book::new(id, title, genre);
+        item.kind = ItemKind::Media { runtime_minutes };
+        item
+    }
+}
+
+// =============================================================================
+// TESTS
+// =============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Condition, Library, LibraryError, Member, MembershipTier, Money};
+    use chrono::NaiveDate;
+
+    fn date(day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(2024, 3, day).unwrap()
+    }
+
+    #[test]
+    fn test_item_kinds_cap_loans() {
+        let mut lib = Library::new();
+        lib.add_book(Book::new(1, "Dune", Genre::SciFi)).unwrap();
+        lib.add_book(Book::periodical(2, "Rust Monthly", Genre::Technical, "March 2024")).unwrap();
+        lib.register_member(Member::new(10, "Alice", MembershipTier::Basic)).unwrap();
+
+        assert_eq!(lib.checkout(10, 1, date(1)), Ok(date(15)));
+        assert_eq!(lib.checkout(10, 2, date(1)), Ok(date(8)));
+        // Renewal is capped the same way
+        assert_eq!(lib.renew(10, 2, date(5)), Ok(date(12)));
+        assert_eq!(lib.renew(10, 1, date(5)), Ok(date(19)));
+
+        // Late fees work the same for every kind
+        assert_eq!(lib.return_book(10, 2, date(14)).unwrap().cents(), 50);
+    }
+
+    #[test]
+    fn test_digital_copies_are_always_available() {
+        let mut lib = Library::new();
+        let mut ebook = Book::new(1, "Dune", Genre::SciFi);
+        ebook.format = Format::Digital;
+        lib.add_book(ebook).unwrap();
+        lib.register_member(Member::new(10, "Alice", MembershipTier::Basic)).unwrap();
+        lib.register_member(Member::new(11, "Bob", MembershipTier::Basic)).unwrap();
+
+        assert_eq!(lib.checkout(10, 1, date(1)), Ok(date(22)));
+        assert_eq!(lib.checkout(11, 1, date(2)), Ok(date(23)));
+        assert_eq!(lib.checkout(10, 1, date(3)), Err(LibraryError::BookUnavailable(1)));
+        assert_eq!(lib.place_hold(10, 1, date(3)), Err(LibraryError::HoldNotNeeded(1)));
+        assert_eq!(lib.remove_book(1).unwrap_err(), LibraryError::BookUnavailable(1));
+        assert_eq!(lib.digital_loans(10), 1);
+        assert!(lib.report().contains("Books on loan: 0\nDigital loans: 2\n"));
+        assert_eq!(lib.check_invariants(), Ok(()));
+
+        // Nothing comes back to the shelf, and a digital copy can't be lost
+        assert_eq!(lib.return_book_in(10, 1, date(24), Condition::Lost), Ok(Money::from_cents(50)));
+        let dune = lib.find_book(1).unwrap();
+        assert_eq!((dune.times_borrowed(), dune.condition), (2, Condition::New));
+        assert_eq!(lib.loans().len(), 1);
+        assert_eq!(lib.check_invariants(), Ok(()));
+    }
+
+    #[test]
+    fn test_digital_loans_count_towards_the_limit() {
+        let mut lib = Library::new();
+        lib.register_member(Member::new(10, "Alice", MembershipTier::Basic)).unwrap();
+        let limit = lib.find_member(10).unwrap().max_books();
+        for id in 1..=limit as u64 {
+            let mut ebook = Book::new(id, "Dune", Genre::SciFi);
+            ebook.format = Format::Digital;
+            lib.add_book(ebook).unwrap();
+            lib.checkout(10, id, date(1)).unwrap();
+        }
+        lib.add_book(Book::new(99, "Emma", Genre::Fiction)).unwrap();
+
+        assert_eq!(lib.checkout(10, 99, date(1)), Err(LibraryError::BorrowLimitReached { member_id: 10, limit }));
+        assert_eq!(lib.deactivate_member(10), Err(LibraryError::MemberHasLoans { member_id: 10, count: limit }));
+    }
+
+    #[test]
+    fn test_generic_over_items() {
+        fn titles<T: LibraryItem>(items: &[T]) -> Vec<String> {
+            items.iter().map(|i| format!("{} ({})", i.title(), i.kind())).collect()
+        }
+        let items = [Book::new(1, "Dune", Genre::SciFi), Book::media(2, "Dune", Genre::SciFi, 155)];
+        assert_eq!(titles(&items), ["Dune (Book)", "Dune (Media, 155 min)"]);
+        assert_eq!(items[1].loan_days(45), 7);
+        assert_eq!(items[0].loan_days(45), 45);
+    }
+}
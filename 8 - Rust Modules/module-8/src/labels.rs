@@ -0,0 +1,184 @@
+//! Labels module - barcodes and spine labels for physical copies.
+//!
+//! A book's barcode is derived from its genre and ID alone, so reprinting a
+//! label always gives the same string. Barcodes only use the Code 39
+//! character set and end in a mod-43 check character, so they can be fed
+//! straight to any Code 39 font or printer.
+//!
+//! ```
+//! use module_8::labels::{barcode, spine_label};
+//! use module_8::{Book, Genre};
+//!
+//! let mut book = Book::new(42, "Dune", Genre::SciFi);
+//! book.author = Some(String::from("Frank Herbert"));
+//! assert_eq!(barcode(&book.genre, book.id()), "*SCI-000042E*");
+//! assert_eq!(spine_label(&book), "SCI\nHER\n42");
+//! ```
+
+use crate::book::{Book, Genre};
+use crate::error::LibraryError;
+use crate::Library;
+
+/// The Code 39 characters, in check-value order (`0` is 0, `%` is 42).
+pub const CODE39_CHARS: &str = "0123456789ABCDEFGHIJKLMNOPQRSTUVWXYZ-. $/+%";
+
+/// Three-letter shelf code for a genre.
+///
+/// Custom genres use the first three ASCII letters or digits of their name,
+/// padded with `X`, so "Poetry" becomes `POE` and "3D" becomes `3DX`.
+pub fn genre_code(genre: &Genre) -> String {
+    match genre {
+        Genre::Fiction => String::from("FIC"),
+        Genre::NonFiction => String::from("NON"),
+        Genre::Technical => String::from("TEC"),
+        Genre::Mystery => String::from("MYS"),
+        Genre::SciFi => String::from("SCI"),
+        Genre::Custom(name) => letters(name, 3),
+    }
+}
+
+/// The mod-43 check character for `data`, or `None` if `data` contains a
+/// character outside [`CODE39_CHARS`].
+pub fn check_char(data: &str) -> Option<char> {
+    let mut sum = 0;
+    for c in data.chars() {
+        sum += CODE39_CHARS.find(c)?;
+    }
+    CODE39_CHARS.chars().nth(sum % 43)
+}
+
+/// The barcode for a copy: shelf code, zero-padded ID and check character,
+/// between the `*` start/stop characters.
+pub fn barcode(genre: &Genre, id: u64) -> String {
+    let data = format!("{}-{:06}", genre_code(genre), id);
+    let check = check_char(&data).expect("genre codes and digits are Code 39");
+    format!("*{}{}*", data, check)
+}
+
+/// Reads the book ID back out of a barcode, checking its format and check
+/// character. Returns `None` for anything [`barcode`] couldn't have produced.
+pub fn parse_barcode(code: &str) -> Option<u64> {
+    let inner = code.strip_prefix('*')?.strip_suffix('*')?;
+    let mut chars = inner.chars();
+    let check = chars.next_back()?;
+    let data = chars.as_str();
+    if check_char(data)? != check {
+        return None;
+    }
+    let (shelf, id) = data.split_once('-')?;
+    if shelf.len() != 3 || id.len() < 6 || !id.bytes().all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+    id.parse().ok()
+}
+
+/// The three-line spine label: shelf code, the first three letters of the
+/// author's surname (or of the title, if there's no author), and the ID.
+pub fn spine_label(book: &Book) -> String {
+    format!("{}\n{}\n{}", genre_code(&book.genre), cutter(book), book.id())
+}
+
+/// The first three letters of the author's surname, or of the title if
+/// there's no author.
+pub(crate) fn cutter(book: &Book) -> String {
+    match book.author.as_deref().and_then(|a| a.split_whitespace().last()) {
+        Some(surname) => letters(surname, 3),
+        None => letters(&book.title, 3),
+    }
+}
+
+/// The first `n` ASCII letters or digits of `text`, uppercased and padded
+/// with `X`.
+fn letters(text: &str, n: usize) -> String {
+    let mut out: String = text
+        .chars()
+        .filter(char::is_ascii_alphanumeric)
+        .take(n)
+        .map(|c| c.to_ascii_uppercase())
+        .collect();
+    while out.len() < n {
+        out.push('X');
+    }
+    out
+}
+
+impl Library {
+    /// The lowest ID above every book catalogued so far, on the shelf, on
+    /// loan or since removed; the ID [`add_book_auto`](Library::add_book_auto)
+    /// would use. Only a reloaded library may hand out a removed book's ID
+    /// again.
+    pub fn generate_id(&self) -> u64 {
+        self.next_book_id()
+    }
+
+    /// The barcode for a new copy with this genre and ID.
+    ///
+    /// Fails with [`LibraryError::LabelInUse`] if a catalogued book already
+    /// has the ID, since its label would then be ambiguous at the desk.
+    pub fn new_barcode(&self, genre: &Genre, id: u64) -> Result<String, LibraryError> {
+        let code = barcode(genre, id);
+        if self.index.contains(id) {
+            return Err(LibraryError::LabelInUse { barcode: code, book_id: id });
+        }
+        Ok(code)
+    }
+
+    /// The barcode of a catalogued book, on the shelf or on loan.
+    pub fn barcode_of(&self, book_id: u64) -> Option<String> {
+        self.get_book(book_id).map(|b| barcode(&b.genre, b.id()))
+    }
+
+    /// Looks up a scanned barcode, on the shelf or on loan.
+    ///
+    /// Returns `None` if the barcode is malformed or doesn't match the
+    /// book's current genre (e.g. a stale label after reclassifying).
+    pub fn find_by_barcode(&self, code: &str) -> Option<&Book> {
+        let book = self.get_book(parse_barcode(code)?)?;
+        (barcode(&book.genre, book.id()) == code).then_some(book)
+    }
+}
+
+// =============================================================================
+// TESTS
+// =============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_barcode_round_trip() {
+        let code = barcode(&Genre::Custom(String::from("Poetry")), 7);
+        assert_eq!(&code[..11], "*POE-000007");
+        assert!(code.chars().all(|c| c == '*' || CODE39_CHARS.contains(c)));
+        assert_eq!(parse_barcode(&code), Some(7));
+
+        // A misread character fails the check
+        let misread = code.replacen('7', "1", 1);
+        assert_eq!(parse_barcode(&misread), None);
+        assert_eq!(parse_barcode("POE-000007"), None);
+    }
+
+    #[test]
+    fn test_spine_label_without_author() {
+        let book = Book::new(3, "1984", Genre::Fiction);
+        assert_eq!(spine_label(&book), "FIC\n198\n3");
+        assert_eq!(genre_code(&Genre::Custom(String::from("é!"))), "XXX");
+    }
+
+    #[test]
+    fn test_new_barcode_detects_collisions() {
+        let mut lib = Library::new();
+        lib.add_book(Book::new(1, "Dune", Genre::SciFi)).unwrap();
+        assert_eq!(lib.generate_id(), 2);
+        assert!(lib.new_barcode(&Genre::Fiction, 2).is_ok());
+        assert!(matches!(
+            lib.new_barcode(&Genre::Fiction, 1),
+            Err(LibraryError::LabelInUse { book_id: 1, .. })
+        ));
+
+        let code = lib.barcode_of(1).unwrap();
+        assert_eq!(lib.find_by_barcode(&code).unwrap().title, "Dune");
+        assert!(lib.find_by_barcode(&barcode(&Genre::Fiction, 1)).is_none());
+    }
+}
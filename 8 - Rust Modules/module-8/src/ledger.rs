@@ -0,0 +1,121 @@
+//! Ledger module - a dated record of every change to members' fines.
+//!
+//! A member's [`outstanding_fines`](crate::Member::outstanding_fines) is
+//! only a running balance. The fine ledger keeps the entries behind it:
+//! each charge (late fees at return or by [`accrue_fees`](Library::accrue_fees),
+//! replacement costs), payment and waiver, with the day it happened. Reports
+//! such as [`reports::financials`](crate::reports::financials) are built
+//! from it.
+//!
+//! ```
+//! use chrono::NaiveDate;
+//! use module_8::ledger::FineKind;
+//! use module_8::{Book, Genre, Library, Member, MembershipTier, Money};
+//!
+//! let mut lib = Library::new();
+//! lib.add_book(Book::new(1, "Dune", Genre::SciFi)).unwrap();
+//! lib.register_member(Member::new(10, "Alice", MembershipTier::Basic)).unwrap();
+//! let day = |d| NaiveDate::from_ymd_opt(2024, 3, d).unwrap();
+//!
+//! lib.checkout(10, 1, day(1)).unwrap();
+//! lib.return_book(10, 1, day(17)).unwrap(); // two days late
+//! lib.pay_fines(10, Money::from_cents(100), day(18)).unwrap(); // only 50 owed
+//!
+//! let entries: Vec<(FineKind, u64)> = lib.fine_ledger().iter().map(|e| (e.kind, e.amount.cents())).collect();
+//! assert_eq!(entries, [(FineKind::Assessed, 50), (FineKind::Collected, 50)]);
+//! ```
+
+use chrono::NaiveDate;
+
+use crate::money::Money;
+use crate::Library;
+
+/// What a ledger entry did to the member's balance.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum FineKind {
+    /// A fee was charged.
+    Assessed,
+    /// The member paid.
+    Collected,
+    /// Staff forgave part of the balance.
+    Waived,
+}
+
+/// One change to a member's fines.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct FineEntry {
+    pub on: NaiveDate,
+    pub member_id: u64,
+    pub kind: FineKind,
+    /// Always the amount actually applied: paying more than is owed records
+    /// only what was owed.
+    pub amount: Money,
+}
+
+impl FineEntry {
+    /// The entry's effect on the balance: positive for charges.
+    pub fn signed_cents(&self) -> i128 {
+        let cents = i128::from(self.amount.cents());
+        match self.kind {
+            FineKind::Assessed => cents,
+            FineKind::Collected | FineKind::Waived => -cents,
+        }
+    }
+}
+
+impl Library {
+    /// Every fine entry, oldest first.
+    pub fn fine_ledger(&self) -> &[FineEntry] {
+        &self.fine_ledger
+    }
+
+    /// Appends an entry; zero amounts aren't recorded.
+    pub(crate) fn record_fine(&mut self, on: NaiveDate, member_id: u64, kind: FineKind, amount: Money) {
+        if !amount.is_zero() {
+            self.fine_ledger.push(FineEntry { on, member_id, kind, amount });
+        }
+    }
+}
+
+// =============================================================================
+// TESTS
+// =============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Book, Condition, Genre, Member, MembershipTier};
+
+    fn date(day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(2024, 3, day).unwrap()
+    }
+
+    #[test]
+    fn test_ledger_matches_balance() {
+        let mut lib = Library::new();
+        lib.add_book(Book::new(1, "Dune", Genre::SciFi)).unwrap();
+        lib.register_member(Member::new(10, "Alice", MembershipTier::Basic)).unwrap();
+        lib.checkout(10, 1, date(1)).unwrap();
+        lib.accrue_fees(date(17));
+        lib.return_book_in(10, 1, date(19), Condition::Lost).unwrap();
+        lib.waive_fines(10, Money::from_cents(2000), date(20)).unwrap();
+        lib.pay_fines(10, Money::from_cents(30), date(21)).unwrap();
+
+        let entries: Vec<(NaiveDate, FineKind, u64)> =
+            lib.fine_ledger().iter().map(|e| (e.on, e.kind, e.amount.cents())).collect();
+        assert_eq!(
+            entries,
+            [
+                (date(17), FineKind::Assessed, 50),
+                // Two more days late, plus the replacement cost
+                (date(19), FineKind::Assessed, 2050),
+                (date(20), FineKind::Waived, 2000),
+                (date(21), FineKind::Collected, 30),
+            ]
+        );
+        let balance: i128 = lib.fine_ledger().iter().map(FineEntry::signed_cents).sum();
+        assert_eq!(balance, i128::from(lib.find_member(10).unwrap().outstanding_fines().cents()));
+    }
+}
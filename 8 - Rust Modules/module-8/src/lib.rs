@@ -52,6 +52,72 @@ pub mod member;
 // Made public to allow access to nested modules like `utils::formatting`.
 pub mod utils;
 
+// FILE-BASED MODULES for circulation: the shared error type, event hooks,
+// and an `impl Library` block living outside lib.rs.
+pub mod access;
+pub mod accrual;
+pub mod analytics;
+pub mod branches;
+pub mod bulk;
+pub mod calendar;
+pub mod circulation;
+pub mod consortium;
+pub mod dedup;
+pub mod editing;
+pub mod error;
+pub mod events;
+pub mod fee_policy;
+pub mod fixtures;
+pub mod genres;
+pub mod ill;
+pub mod item;
+pub mod labels;
+pub mod ledger;
+pub mod locale;
+pub mod location;
+pub mod lost;
+pub mod merge;
+pub mod metrics;
+pub mod money;
+pub mod page;
+pub mod reading_lists;
+pub mod receipts;
+pub mod recommend;
+pub mod reports;
+pub mod reviews;
+pub mod search;
+pub mod shared;
+pub mod shelving;
+pub mod simulation;
+pub mod snapshot;
+pub mod sorting;
+pub mod stats;
+pub mod storage;
+pub mod store;
+pub mod suspension;
+pub mod tags;
+pub mod templates;
+pub mod undo;
+pub mod validation;
+
+// PRIVATE FILE-BASED MODULE: HashMap indexes behind Library's lookups.
+// No `pub`, so `index::CatalogIndex` is an implementation detail; only the
+// `impl Library` methods it defines are visible outside the crate.
+mod index;
+
+// PRIVATE FILE-BASED MODULE: the ID allocator behind `add_book_auto` and
+// `register_member_auto`; the methods are public, the allocator isn't.
+mod ids;
+
+// FEATURE-GATED PRIVATE MODULE: only compiled with `--features serde`.
+// The hand-written Serialize/Deserialize impls that derives can't cover.
+#[cfg(feature = "serde")]
+mod serde_impls;
+
+// DIRECTORY-BASED MODULE for catalog importers (`import.rs` + `import/`).
+// Each supported source format lives in its own submodule.
+pub mod import;
+
 // =============================================================================
 // INLINE MODULE
 // =============================================================================
@@ -61,10 +127,29 @@ pub mod utils;
 // private by default unless marked `pub`.
 // We make the entire module `pub` to expose it to external crates.
 pub mod config {
+    use std::collections::BTreeMap;
+    use std::fmt;
+
+    use crate::fee_policy::{FeePolicy, FlatFeePolicy, GracePeriod};
+    use crate::member::TierPolicy;
+    use crate::money::Money;
+
     /// Maximum number of books a member can borrow at once.
     /// This is pub(crate) - visible within this crate but not to external users.
     pub(crate) const MAX_BORROWED_BOOKS: usize = 5;
 
+    /// Loan period for digital copies, whatever the member's tier.
+    pub(crate) const DIGITAL_LOAN_DAYS: u32 = 21;
+
+    /// How many times one loan may be renewed.
+    pub(crate) const MAX_RENEWALS: u32 = 2;
+
+    /// Open days a book waits on the hold shelf before the hold expires.
+    pub(crate) const HOLD_PICKUP_DAYS: u32 = 7;
+
+    /// Days past due before a loan may be declared lost.
+    pub(crate) const LOST_AFTER_DAYS: u32 = 60;
+
     /// Library operating hours (internal configuration).
     /// This is completely private - only accessible within this `config` module.
     #[allow(dead_code)]
@@ -73,29 +158,211 @@ pub mod config {
     /// A public constant that external crates can access.
     pub const LIBRARY_NAME: &str = "Rustacean Library";
 
+    // FILE MODULE INSIDE AN INLINE MODULE: `mod load;` here is looked up at
+    // `src/config/load.rs`, because the parent module is `config`.
+    mod load;
+    pub use load::{ConfigError, ENV_PREFIX};
+
     // NESTED INLINE MODULE: Modules can be nested to any depth.
     // This demonstrates how child modules can access parent items.
     pub mod fees {
-        /// Late fee per day in cents.
-        pub const LATE_FEE_PER_DAY: u32 = 25;
+        use crate::money::{Currency, Money};
+
+        /// Currency of every fee, fine and payment.
+        pub const CURRENCY: Currency = Currency::USD;
+
+        /// Late fee per day.
+        pub const LATE_FEE_PER_DAY: Money = Money::from_cents(25);
+
+        /// Overdue days that are free before late fees start.
+        pub const GRACE_DAYS: u32 = 0;
+
+        /// Charge for a book returned as lost.
+        pub const REPLACEMENT_COST: Money = Money::from_cents(2000);
+
+        /// Unpaid fines above which a member is suspended.
+        pub const MAX_UNPAID_FINES: Money = Money::from_cents(1000);
 
         /// Calculate total late fee.
         ///
+        /// Never panics: like every fee, it saturates at the largest
+        /// amount [`Money`] can hold rather than overflowing.
+        ///
         /// # Examples
         ///
         /// ```
         /// use module_8::config::fees::calculate_late_fee;
-        /// assert_eq!(calculate_late_fee(3), 75);
+        /// assert_eq!(calculate_late_fee(3).to_string(), "$0.75");
+        /// ```
+        pub fn calculate_late_fee(days_overdue: u32) -> Money {
+            LATE_FEE_PER_DAY.saturating_mul(days_overdue.into())
+        }
+
+        /// Late fee for a book due on `due_on` and returned on `returned_on`,
+        /// counting only the days `calendar` has the library open.
+        ///
+        /// # Examples
+        ///
+        /// ```
+        /// use chrono::{NaiveDate, Weekday};
+        /// use module_8::config::fees::calculate_late_fee_between;
+        /// use module_8::Calendar;
+        ///
+        /// let closed_sundays = Calendar::always_open().closed_on(Weekday::Sun);
+        /// let due = NaiveDate::from_ymd_opt(2024, 3, 15).unwrap(); // a Friday
+        /// let returned = NaiveDate::from_ymd_opt(2024, 3, 18).unwrap();
+        /// assert_eq!(calculate_late_fee_between(due, returned, &closed_sundays).to_string(), "$0.50");
+        /// ```
+        pub fn calculate_late_fee_between(
+            due_on: chrono::NaiveDate,
+            returned_on: chrono::NaiveDate,
+            calendar: &crate::calendar::Calendar,
+        ) -> Money {
+            calculate_late_fee(calendar.open_days_between(due_on, returned_on))
+        }
+    }
+
+    /// Runtime configuration for a [`Library`](crate::Library).
+    ///
+    /// The constants above are compile-time values; `LibraryConfig` lets each
+    /// deployment tune them without recompiling. `Default` uses the constants,
+    /// so `Library::new()` behaves exactly as before.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use module_8::config::LibraryConfig;
+    /// use module_8::{Library, Money};
+    ///
+    /// let cfg = LibraryConfig {
+    ///     name: String::from("Branch Library"),
+    ///     late_fee_per_day: Money::from_cents(50),
+    ///     ..LibraryConfig::default()
+    /// };
+    /// let lib = Library::with_config(cfg);
+    /// assert_eq!(lib.name(), "Branch Library");
+    /// assert_eq!(lib.config().late_fee(3), Money::from_cents(150));
+    /// ```
+    #[derive(Debug, Clone, PartialEq)]
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+    pub struct LibraryConfig {
+        /// Display name of the library.
+        pub name: String,
+        /// Maximum number of books a member can borrow at once.
+        pub max_borrowed_books: usize,
+        /// Late fee per day.
+        pub late_fee_per_day: Money,
+        /// Overdue open days that are free before late fees start.
+        pub grace_days: u32,
+        /// Share of the daily fee, in percent, charged for the first day
+        /// after the grace period: 100 charges it in full, 50 prorates it to
+        /// half a day.
+        pub first_day_percent: u8,
+        /// Late-fee caps by tier name, replacing those tiers'
+        /// [`fee_cap`](crate::TierPolicy::fee_cap).
+        pub fee_caps: BTreeMap<String, Money>,
+        /// Charge for a book returned as lost.
+        pub replacement_cost: Money,
+        /// Unpaid fines above which a member is suspended.
+        pub max_unpaid_fines: Money,
+        /// Loan period for digital copies, whatever the member's tier.
+        pub digital_loan_days: u32,
+        /// How many times one loan may be renewed.
+        pub max_renewals: u32,
+        /// Open days a book waits on the hold shelf before the hold expires.
+        pub hold_pickup_days: u32,
+        /// Days past due before staff may declare a loan lost (see
+        /// [`lost`](crate::lost)).
+        pub lost_after_days: u32,
+        /// Most books the catalog may hold, on the shelf or on loan; `None`
+        /// for no limit.
+        pub max_books: Option<usize>,
+        /// Most members that may be registered; `None` for no limit.
+        pub max_members: Option<usize>,
+    }
+
+    impl LibraryConfig {
+        /// Calculates the late fee for this configuration's daily rate,
+        /// saturating instead of overflowing for absurd rates.
+        pub fn late_fee(&self, days_overdue: u32) -> Money {
+            self.late_fee_per_day.saturating_mul(days_overdue.into())
+        }
+
+        /// The late-fee policy this configuration describes: a
+        /// [`FlatFeePolicy`](crate::FlatFeePolicy) at the daily rate, behind
+        /// a [`GracePeriod`](crate::fee_policy::GracePeriod) when there are
+        /// grace days or a prorated first day.
+        pub fn fee_policy(&self) -> Box<dyn FeePolicy> {
+            let flat = FlatFeePolicy { per_day: self.late_fee_per_day };
+            if self.grace_days == 0 && self.first_day_percent == 100 {
+                return Box::new(flat);
+            }
+            Box::new(GracePeriod::new(flat, self.grace_days).first_day_percent(self.first_day_percent))
+        }
+
+        /// Most one overdue loan can cost a member of `tier` in late fees:
+        /// the configured cap for the tier's name, or else the tier's own.
+        ///
+        /// # Examples
+        ///
         /// ```
-        pub fn calculate_late_fee(days_overdue: u32) -> u32 {
-            days_overdue * LATE_FEE_PER_DAY
+        /// use module_8::{LibraryConfig, MembershipTier, Money};
+        ///
+        /// let mut cfg = LibraryConfig::default();
+        /// cfg.fee_caps.insert(String::from("Gold"), Money::from_cents(300));
+        /// assert_eq!(cfg.fee_cap(&MembershipTier::Gold), Some(Money::from_cents(300)));
+        /// assert_eq!(cfg.fee_cap(&MembershipTier::Basic), Some(MembershipTier::Basic.fee_cap()));
+        /// ```
+        pub fn fee_cap(&self, tier: &dyn TierPolicy) -> Option<Money> {
+            self.fee_caps.get(tier.name()).copied().or_else(|| tier.fee_cap())
+        }
+
+        /// The configured limit on books or members, if any.
+        pub fn limit(&self, capacity: Capacity) -> Option<usize> {
+            match capacity {
+                Capacity::Books => self.max_books,
+                Capacity::Members => self.max_members,
+            }
+        }
+    }
+
+    impl Default for LibraryConfig {
+        fn default() -> Self {
+            LibraryConfig {
+                name: String::from(LIBRARY_NAME),
+                max_borrowed_books: MAX_BORROWED_BOOKS,
+                late_fee_per_day: fees::LATE_FEE_PER_DAY,
+                grace_days: fees::GRACE_DAYS,
+                first_day_percent: 100,
+                fee_caps: BTreeMap::new(),
+                replacement_cost: fees::REPLACEMENT_COST,
+                max_unpaid_fines: fees::MAX_UNPAID_FINES,
+                digital_loan_days: DIGITAL_LOAN_DAYS,
+                max_renewals: MAX_RENEWALS,
+                hold_pickup_days: HOLD_PICKUP_DAYS,
+                lost_after_days: LOST_AFTER_DAYS,
+                max_books: None,
+                max_members: None,
+            }
         }
+    }
+
+    /// What a capacity limit in [`LibraryConfig`] counts.
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+    pub enum Capacity {
+        /// Catalogued books, on the shelf or on loan.
+        Books,
+        /// Registered members.
+        Members,
+    }
 
-        /// Internal helper - uses `super::` to access parent module's items.
-        #[allow(dead_code)]
-        pub(crate) fn max_fee() -> u32 {
-            // `super::` refers to the parent module (config)
-            super::MAX_BORROWED_BOOKS as u32 * LATE_FEE_PER_DAY * 30
+    impl fmt::Display for Capacity {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            match self {
+                Capacity::Books => write!(f, "books"),
+                Capacity::Members => write!(f, "members"),
+            }
         }
     }
 }
@@ -111,11 +378,25 @@ pub mod config {
 //      instead of: `use module_8::book::Book;`
 
 // Re-export main types at the crate root for convenient access
-pub use book::{Book, Genre};
-pub use member::{Member, MembershipTier};
+pub use book::{Book, BookBuilder, Condition, Genre, ParseGenreError};
+pub use member::{GuestTier, LoanRecord, Member, MemberStatus, MembershipTier, ParseTierError, SuspensionReason, TierPolicy};
 
 // Re-export the config module itself (users can access config::LIBRARY_NAME)
-pub use config::LIBRARY_NAME;
+pub use config::{Capacity, ConfigError, LibraryConfig, LIBRARY_NAME};
+
+pub use analytics::ReaderActivity;
+pub use branches::LibrarySystem;
+pub use calendar::Calendar;
+pub use circulation::{FifoHolds, Hold, HoldQueuePolicy, Loan, TierPriorityHolds};
+pub use error::LibraryError;
+pub use events::{ConsoleNotifier, LibraryEvent, Notifier};
+pub use fee_policy::{FeePolicy, FlatFeePolicy};
+pub use item::{Format, ItemKind, LibraryItem};
+pub use money::{Currency, Money};
+pub use receipts::Receipt;
+pub use reports::ReportSection;
+pub use snapshot::LibrarySnapshot;
+pub use validation::ValidationError;
 
 // Selectively re-export from config::fees
 pub use config::fees::calculate_late_fee;
@@ -127,17 +408,81 @@ pub use utils::format_book_info;
 // CRATE-LEVEL FUNCTIONALITY
 // =============================================================================
 
+use std::collections::HashMap;
+
+use chrono::NaiveDate;
+
+use access::AuditEntry;
+use accrual::FeeAccrual;
+use genres::GenreTree;
+use utils::formatting::GenreIcons;
+use ids::IdAllocator;
+use ill::IllRequest;
+use index::{BookLocation, CatalogIndex};
+use ledger::{FineEntry, FineKind};
+use locale::Locales;
+use location::AwaitingShelving;
+use lost::LostBook;
+use metrics::Metrics;
+use reading_lists::Collection;
+use reviews::Review;
+use suspension::{FinesThreshold, StatusRule};
+use undo::CommandHistory;
+
 /// Represents the library system that manages books and members.
 ///
 /// This struct demonstrates using types from different modules.
+///
+/// # Performance
+///
+/// Every catalogued book, on the shelf or out on loan, and every member are
+/// stored in `HashMap`s keyed by ID, with secondary indexes (see the private
+/// `index` module) for everything else. A loan is a [`Loan`] record linking
+/// a book and a member by ID; lending a book never moves it out of the
+/// library. Book and member IDs must be unique.
+///
+/// | Operation | Cost |
+/// |-----------|------|
+/// | `find_book`, `get_book`, `find_member` | O(1) |
+/// | `find_by_isbn`, `find_by_title` | O(1) + number of matches |
+/// | `add_book`, `register_member` | O(1) amortized |
+/// | `add_books`, `register_members` | O(n) for n items, reserving space once |
+/// | `checkout`, `return_book`, `remove_book` | O(log n) for shelf order, plus a scan of open loans/holds |
+/// | `books()`, `members()` | O(n), in insertion order |
+///
+/// Shelf iteration follows catalog order: a returned book goes back to the
+/// position it was added at, not to the end.
 pub struct Library {
-    name: String,
-    books: Vec<Book>,
-    members: Vec<Member>,
+    config: LibraryConfig,
+    books: HashMap<u64, Book>,
+    members: HashMap<u64, Member>,
+    member_order: Vec<u64>,
+    loans: Vec<Loan>,
+    holds: Vec<Hold>,
+    hold_policy: Box<dyn HoldQueuePolicy>,
+    fee_policy: Box<dyn FeePolicy>,
+    calendar: Calendar,
+    genres: GenreTree,
+    genre_icons: GenreIcons,
+    notifiers: Vec<Box<dyn Notifier>>,
+    index: CatalogIndex,
+    ids: IdAllocator,
+    audit_log: Vec<AuditEntry>,
+    fee_accruals: Vec<FeeAccrual>,
+    fine_ledger: Vec<FineEntry>,
+    ill_requests: Vec<IllRequest>,
+    reviews: Vec<Review>,
+    reshelving: Vec<AwaitingShelving>,
+    lost_books: Vec<LostBook>,
+    collections: Vec<Collection>,
+    status_rules: Vec<Box<dyn StatusRule>>,
+    commands: CommandHistory,
+    metrics: Metrics,
+    locales: Locales,
 }
 
 impl Library {
-    /// Creates a new library with the default name.
+    /// Creates a new library with the default configuration.
     ///
     /// # Examples
     ///
@@ -146,31 +491,263 @@ impl Library {
     /// let lib = Library::new();
     /// ```
     pub fn new() -> Self {
+        Self::with_config(LibraryConfig::default())
+    }
+
+    /// Creates a new library using a runtime configuration.
+    ///
+    /// Late fees use the configured [`fee_policy`](LibraryConfig::fee_policy) until
+    /// [`set_fee_policy`](Library::set_fee_policy) replaces it, and members
+    /// owing more than the configured fine limit are suspended (see
+    /// [`suspension`](crate::suspension)).
+    pub fn with_config(config: LibraryConfig) -> Self {
         Library {
-            name: String::from(LIBRARY_NAME),
-            books: Vec::new(),
-            members: Vec::new(),
+            fee_policy: config.fee_policy(),
+            status_rules: vec![Box::new(FinesThreshold {
+                max_fines: config.max_unpaid_fines,
+            })],
+            config,
+            books: HashMap::new(),
+            members: HashMap::new(),
+            member_order: Vec::new(),
+            loans: Vec::new(),
+            holds: Vec::new(),
+            hold_policy: Box::new(FifoHolds),
+            calendar: Calendar::always_open(),
+            genres: GenreTree::new(),
+            genre_icons: GenreIcons::new(),
+            notifiers: Vec::new(),
+            index: CatalogIndex::default(),
+            ids: IdAllocator::default(),
+            audit_log: Vec::new(),
+            fee_accruals: Vec::new(),
+            fine_ledger: Vec::new(),
+            ill_requests: Vec::new(),
+            reviews: Vec::new(),
+            reshelving: Vec::new(),
+            lost_books: Vec::new(),
+            collections: Vec::new(),
+            commands: CommandHistory::default(),
+            metrics: Metrics::default(),
+            locales: Locales::default(),
         }
     }
 
+    /// Returns the active configuration.
+    pub fn config(&self) -> &LibraryConfig {
+        &self.config
+    }
+
     /// Adds a book to the library.
-    pub fn add_book(&mut self, book: Book) {
-        self.books.push(book);
+    ///
+    /// # Errors
+    ///
+    /// [`LibraryError::DuplicateBook`] if a book with the same ID is already
+    /// catalogued, [`LibraryError::LikelyDuplicate`] if a catalogued book has
+    /// the same title and ISBN (see [`dedup`](crate::dedup)), and
+    /// [`LibraryError::CapacityReached`] if the catalog is at its configured
+    /// `max_books`. See also [`add_book_auto`](Library::add_book_auto).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use module_8::{Book, Genre, Library, LibraryError};
+    ///
+    /// let mut lib = Library::new();
+    /// lib.add_book(Book::builder(1, "Dune").isbn("0441172717").build()).unwrap();
+    /// let again = lib.add_book(Book::new(1, "Emma", Genre::Fiction));
+    /// assert_eq!(again, Err(LibraryError::DuplicateBook(1)));
+    /// let copy = lib.add_book(Book::builder(2, "DUNE").isbn("0-441-17271-7").build());
+    /// assert_eq!(copy, Err(LibraryError::LikelyDuplicate { book_id: 2, existing: 1 }));
+    /// ```
+    pub fn add_book(&mut self, book: Book) -> Result<(), LibraryError> {
+        if self.index.contains(book.id()) {
+            return Err(LibraryError::DuplicateBook(book.id()));
+        }
+        if let Some(existing) = self.find_duplicate(&book) {
+            return Err(LibraryError::LikelyDuplicate { book_id: book.id(), existing: existing.id() });
+        }
+        self.check_capacity(1, 0)?;
+        self.insert_book(book);
+        Ok(())
+    }
+
+    /// Adds a book like [`add_book`](Library::add_book), but as another copy
+    /// if the catalog already has one with the same title and ISBN: used to
+    /// put back a book that was catalogued before (undo, a found copy, a
+    /// snapshot).
+    pub(crate) fn add_book_allowing_duplicates(&mut self, book: Book) -> Result<(), LibraryError> {
+        if self.index.contains(book.id()) {
+            return Err(LibraryError::DuplicateBook(book.id()));
+        }
+        self.check_capacity(1, 0)?;
+        self.insert_book(book);
+        Ok(())
+    }
+
+    /// Catalogues a book whose ID and room in the catalog the caller has
+    /// already checked.
+    pub(crate) fn insert_book(&mut self, book: Book) {
+        debug_assert!(!self.index.contains(book.id()), "book {} is already catalogued", book.id());
+        self.ids.book_used(book.id());
+        self.index.insert_book(&book, BookLocation::Shelf);
+        self.books.insert(book.id(), book);
     }
 
     /// Registers a new member.
-    pub fn register_member(&mut self, member: Member) {
-        self.members.push(member);
+    ///
+    /// # Errors
+    ///
+    /// [`LibraryError::DuplicateMember`] if a member with the same ID is
+    /// already registered, and [`LibraryError::CapacityReached`] if they
+    /// would take the library past its configured `max_members`. See also
+    /// [`register_member_auto`](Library::register_member_auto).
+    pub fn register_member(&mut self, member: Member) -> Result<(), LibraryError> {
+        if self.members.contains_key(&member.id()) {
+            return Err(LibraryError::DuplicateMember(member.id()));
+        }
+        self.check_capacity(0, 1)?;
+        self.insert_member(member);
+        Ok(())
+    }
+
+    /// Registers a member the caller has already checked.
+    pub(crate) fn insert_member(&mut self, member: Member) {
+        debug_assert!(!self.members.contains_key(&member.id()), "member {} is already registered", member.id());
+        self.ids.member_used(member.id());
+        self.member_order.push(member.id());
+        self.members.insert(member.id(), member);
+    }
+
+    /// Removes a book from the catalog, returning it.
+    ///
+    /// Books out on loan can't be removed, nor can digital copies anyone is
+    /// reading. Any holds on the book are dropped, and it leaves every
+    /// [collection](crate::reading_lists).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use module_8::{Book, Genre, Library, LibraryError};
+    ///
+    /// let mut lib = Library::new();
+    /// lib.add_book(Book::new(1, "Dune", Genre::SciFi)).unwrap();
+    /// assert_eq!(lib.remove_book(1).unwrap().title, "Dune");
+    /// assert_eq!(lib.remove_book(1).unwrap_err(), LibraryError::BookNotFound(1));
+    /// ```
+    pub fn remove_book(&mut self, id: u64) -> Result<Book, LibraryError> {
+        match self.index.location(id) {
+            Some(BookLocation::Shelf) if self.loans.iter().any(|l| l.book_id == id) => {
+                return Err(LibraryError::BookUnavailable(id))
+            }
+            Some(BookLocation::Shelf) => {}
+            Some(BookLocation::OnLoan) => return Err(LibraryError::BookUnavailable(id)),
+            None => return Err(LibraryError::BookNotFound(id)),
+        }
+        let book = self.books.remove(&id).expect("indexed as on the shelf");
+        self.index.remove_book(&book);
+        self.holds.retain(|h| h.book_id != id);
+        self.mark_shelved(id);
+        self.forget_in_collections(id);
+        Ok(book)
+    }
+
+    /// Deactivates a member so they can no longer borrow.
+    ///
+    /// The member stays registered (their history is kept), but only once
+    /// all books are returned and all fines are paid.
+    pub fn deactivate_member(&mut self, id: u64) -> Result<(), LibraryError> {
+        let count = self.loan_count(id);
+        let member = self.member_mut(id).ok_or(LibraryError::MemberNotFound(id))?;
+        if count > 0 {
+            return Err(LibraryError::MemberHasLoans { member_id: id, count });
+        }
+        if !member.outstanding_fines().is_zero() {
+            return Err(LibraryError::OutstandingFines {
+                member_id: id,
+                amount: member.outstanding_fines(),
+            });
+        }
+        member.deactivate();
+        self.holds.retain(|h| h.member_id != id);
+        Ok(())
+    }
+
+    /// Records a fine payment, returning the member's remaining balance.
+    ///
+    /// A suspension for unpaid fines is lifted once the balance is back
+    /// within the limit.
+    pub fn pay_fines(&mut self, member_id: u64, amount: Money, today: NaiveDate) -> Result<Money, LibraryError> {
+        self.settle_fines(member_id, amount, today, FineKind::Collected)
+    }
+
+    /// Forgives part of a member's fines, returning the remaining balance.
+    ///
+    /// Mechanically the same as a payment; kept separate so the two can be
+    /// authorized differently (see [`access`](crate::access)) and show up
+    /// separately in the [`fine_ledger`](Library::fine_ledger).
+    pub fn waive_fines(&mut self, member_id: u64, amount: Money, today: NaiveDate) -> Result<Money, LibraryError> {
+        self.settle_fines(member_id, amount, today, FineKind::Waived)
+    }
+
+    fn settle_fines(
+        &mut self,
+        member_id: u64,
+        amount: Money,
+        today: NaiveDate,
+        kind: FineKind,
+    ) -> Result<Money, LibraryError> {
+        let member = self
+            .member_mut(member_id)
+            .ok_or(LibraryError::MemberNotFound(member_id))?;
+        let owed = member.outstanding_fines();
+        let left = member.pay_fines(amount);
+        self.record_fine(today, member_id, kind, owed - left);
+        self.review_status(member_id);
+        Ok(left)
+    }
+
+    /// Finds a catalogued book by ID, on the shelf or out on loan; see
+    /// [`Book::is_available`] and [`borrower`](Library::borrower) for which.
+    pub fn find_book(&self, id: u64) -> Option<&Book> {
+        self.get_book(id)
+    }
+
+    /// Finds a registered member by ID.
+    pub fn find_member(&self, id: u64) -> Option<&Member> {
+        self.members.get(&id)
+    }
+
+    /// Mutable access to a registered member, for use inside the crate.
+    pub(crate) fn member_mut(&mut self, id: u64) -> Option<&mut Member> {
+        self.members.get_mut(&id)
+    }
+
+    /// Mutable access to any catalogued book, on the shelf or on loan, for
+    /// use inside the crate. Changing its title or ISBN needs re-indexing;
+    /// see [`update_book`](Library::update_book).
+    pub(crate) fn book_mut(&mut self, id: u64) -> Option<&mut Book> {
+        self.books.get_mut(&id)
+    }
+
+    /// Iterates over the books on the shelf, in the order they were added.
+    pub fn books(&self) -> impl Iterator<Item = &Book> {
+        self.index.shelf_ids().map(move |id| &self.books[&id])
+    }
+
+    /// Iterates over members, in the order they registered.
+    pub fn members(&self) -> impl Iterator<Item = &Member> {
+        self.member_order.iter().map(move |id| &self.members[id])
     }
 
     /// Returns the library name.
     pub fn name(&self) -> &str {
-        &self.name
+        &self.config.name
     }
 
-    /// Returns the number of books.
+    /// Returns the number of books on the shelf.
     pub fn book_count(&self) -> usize {
-        self.books.len()
+        self.index.shelf_len()
     }
 
     /// Returns the number of members.
@@ -178,16 +755,105 @@ impl Library {
         self.members.len()
     }
 
+    /// How many more books or members the configured limit leaves room
+    /// for, or `None` if there is no limit. Books on loan count against
+    /// `max_books` too.
+    pub fn remaining_capacity(&self, capacity: Capacity) -> Option<usize> {
+        let used = match capacity {
+            Capacity::Books => self.index.len(),
+            Capacity::Members => self.members.len(),
+        };
+        self.config.limit(capacity).map(|limit| limit.saturating_sub(used))
+    }
+
+    /// Checks that `books` more books and `members` more members fit within
+    /// the configured capacity limits.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use module_8::{Book, Capacity, Genre, Library, LibraryConfig, LibraryError};
+    ///
+    /// let mut lib = Library::with_config(LibraryConfig { max_books: Some(2), ..LibraryConfig::default() });
+    /// lib.add_book(Book::new(1, "Dune", Genre::SciFi)).unwrap();
+    /// assert_eq!(lib.remaining_capacity(Capacity::Books), Some(1));
+    /// assert_eq!(lib.remaining_capacity(Capacity::Members), None);
+    /// assert!(lib.check_capacity(1, 100).is_ok());
+    ///
+    /// let err = lib.check_capacity(2, 0).unwrap_err();
+    /// assert_eq!(err, LibraryError::CapacityReached { capacity: Capacity::Books, limit: 2 });
+    /// assert_eq!(err.to_string(), "The library is full: it allows at most 2 books");
+    /// ```
+    pub fn check_capacity(&self, books: usize, members: usize) -> Result<(), LibraryError> {
+        for (capacity, adding) in [(Capacity::Books, books), (Capacity::Members, members)] {
+            if let (Some(room), Some(limit)) = (self.remaining_capacity(capacity), self.config.limit(capacity)) {
+                if adding > room {
+                    return Err(LibraryError::CapacityReached { capacity, limit });
+                }
+            }
+        }
+        Ok(())
+    }
+
     /// Gets the maximum books allowed per member.
-    /// Uses a crate-private constant from the config module.
+    /// Defaults to the crate-private `config::MAX_BORROWED_BOOKS` constant.
     pub fn max_books_per_member(&self) -> usize {
-        // Accessing a pub(crate) item - works within this crate
-        config::MAX_BORROWED_BOOKS
+        self.config.max_borrowed_books
+    }
+
+    /// The policy assessing late fees.
+    pub fn fee_policy(&self) -> &dyn FeePolicy {
+        self.fee_policy.as_ref()
+    }
+
+    /// Replaces the policy used to assess late fees at return time.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use module_8::fee_policy::{FeeCap, FlatFeePolicy};
+    /// use module_8::{Library, Money};
+    ///
+    /// let mut lib = Library::new();
+    /// let per_day = Money::from_cents(25);
+    /// lib.set_fee_policy(FeeCap::new(FlatFeePolicy { per_day }, Money::from_cents(500)));
+    /// ```
+    pub fn set_fee_policy(&mut self, policy: impl FeePolicy + 'static) {
+        self.fee_policy = Box::new(policy);
+    }
+
+    /// Registers a notifier to receive every [`LibraryEvent`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use module_8::{ConsoleNotifier, Library};
+    /// let mut lib = Library::new();
+    /// lib.add_notifier(ConsoleNotifier);
+    /// ```
+    pub fn add_notifier(&mut self, notifier: impl Notifier + 'static) {
+        self.notifiers.push(Box::new(notifier));
+    }
+
+    /// Sends an event to every registered notifier, in registration order.
+    pub(crate) fn notify(&self, event: &LibraryEvent) {
+        for notifier in &self.notifiers {
+            notifier.notify(event);
+        }
+    }
+
+    /// Returns an ID one past the highest book ID used so far, including
+    /// books currently out on loan or since removed.
+    pub(crate) fn next_book_id(&self) -> u64 {
+        self.ids.next_book()
     }
 
     /// Displays all books in the library.
+    ///
+    /// Prints the whole catalog; see [`books_page`](Library::books_page)
+    /// for listing it a page at a time.
     pub fn display_books(&self) {
-        for book in &self.books {
+        for book in self.books() {
             // Using the re-exported utility function
             println!("{}", format_book_info(book));
         }
@@ -199,3 +865,125 @@ impl Default for Library {
         Self::new()
     }
 }
+
+// =============================================================================
+// TESTS
+// =============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_default_config_matches_constants() {
+        let lib = Library::new();
+        assert_eq!(lib.name(), LIBRARY_NAME);
+        assert_eq!(lib.max_books_per_member(), config::MAX_BORROWED_BOOKS);
+        assert_eq!(lib.config().late_fee(3), calculate_late_fee(3));
+    }
+
+    #[test]
+    fn test_with_config_overrides_defaults() {
+        let lib = Library::with_config(LibraryConfig {
+            name: String::from("Kiosk"),
+            max_borrowed_books: 2,
+            late_fee_per_day: Money::from_cents(10),
+            grace_days: 1,
+            first_day_percent: 100,
+            fee_caps: std::collections::BTreeMap::from([(String::from("Basic"), Money::from_cents(250))]),
+            replacement_cost: Money::from_cents(1500),
+            max_unpaid_fines: Money::from_cents(500),
+            digital_loan_days: 7,
+            max_renewals: 0,
+            hold_pickup_days: 3,
+            lost_after_days: 30,
+            max_books: Some(500),
+            max_members: Some(50),
+        });
+        assert_eq!(lib.name(), "Kiosk");
+        assert_eq!(lib.config().digital_loan_days, 7);
+        assert_eq!(lib.config().max_renewals, 0);
+        assert_eq!(lib.max_books_per_member(), 2);
+        assert_eq!(lib.config().late_fee(4), Money::from_cents(40));
+        assert_eq!(lib.remaining_capacity(Capacity::Members), Some(50));
+        assert_eq!(lib.fee_policy().late_fee(4), Money::from_cents(30));
+        assert_eq!(lib.config().fee_cap(&MembershipTier::Basic), Some(Money::from_cents(250)));
+    }
+
+    #[test]
+    fn test_capacity_limits() {
+        let mut lib = Library::with_config(LibraryConfig {
+            max_books: Some(2),
+            max_members: Some(1),
+            ..LibraryConfig::default()
+        });
+        lib.add_book(Book::new(1, "Dune", Genre::SciFi)).unwrap();
+        lib.add_book(Book::new(2, "Emma", Genre::Fiction)).unwrap();
+        lib.register_member(Member::new(10, "Alice", MembershipTier::Basic)).unwrap();
+        lib.checkout(10, 2, chrono::NaiveDate::from_ymd_opt(2024, 3, 1).unwrap()).unwrap();
+        assert_eq!(lib.remaining_capacity(Capacity::Books), Some(0));
+
+        let full = |capacity, limit| Err(LibraryError::CapacityReached { capacity, limit });
+        assert_eq!(lib.add_book(Book::new(3, "Ulysses", Genre::Fiction)), full(Capacity::Books, 2));
+        let bob = Member::new(11, "Bob", MembershipTier::Basic);
+        assert_eq!(lib.register_member(bob), full(Capacity::Members, 1));
+        assert_eq!((lib.book_count(), lib.member_count()), (1, 1));
+
+        // Removing a book makes room again
+        lib.remove_book(1).unwrap();
+        assert_eq!(lib.add_book(Book::new(3, "Ulysses", Genre::Fiction)), Ok(()));
+    }
+
+    #[test]
+    fn test_add_book_stops_at_max_books() {
+        let mut lib = Library::with_config(LibraryConfig { max_books: Some(3), ..LibraryConfig::default() });
+        for id in 1..=3 {
+            lib.add_book(Book::new(id, "Copy", Genre::Fiction)).unwrap();
+        }
+        let err = lib.add_book(Book::new(4, "One too many", Genre::Fiction));
+        assert!(matches!(err, Err(LibraryError::CapacityReached { capacity: Capacity::Books, .. })));
+        assert_eq!(lib.book_count(), 3);
+        assert!(lib.find_book(4).is_none());
+    }
+
+
+    #[test]
+    fn test_remove_book_refuses_loaned_copy() {
+        let mut lib = Library::new();
+        lib.add_book(Book::new(1, "Dune", Genre::SciFi)).unwrap();
+        lib.add_book(Book::new(2, "Emma", Genre::Fiction)).unwrap();
+        lib.register_member(Member::new(10, "Alice", MembershipTier::Basic)).unwrap();
+        lib.checkout(10, 1, chrono::NaiveDate::from_ymd_opt(2024, 3, 1).unwrap()).unwrap();
+
+        assert_eq!(lib.remove_book(1).unwrap_err(), LibraryError::BookUnavailable(1));
+        assert_eq!(lib.remove_book(2).unwrap().title, "Emma");
+        assert!(lib.find_by_title("emma").is_empty());
+        assert_eq!(lib.check_invariants(), Ok(()));
+    }
+
+    #[test]
+    fn test_deactivate_member_requires_clean_account() {
+        let day = |d| chrono::NaiveDate::from_ymd_opt(2024, 3, d).unwrap();
+        let mut lib = Library::new();
+        lib.add_book(Book::new(1, "Dune", Genre::SciFi)).unwrap();
+        lib.register_member(Member::new(10, "Alice", MembershipTier::Basic)).unwrap();
+
+        lib.checkout(10, 1, day(1)).unwrap();
+        assert_eq!(
+            lib.deactivate_member(10),
+            Err(LibraryError::MemberHasLoans { member_id: 10, count: 1 })
+        );
+
+        // Two days late at $0.25 a day
+        lib.return_book(10, 1, day(17)).unwrap();
+        assert_eq!(
+            lib.deactivate_member(10),
+            Err(LibraryError::OutstandingFines { member_id: 10, amount: Money::from_cents(50) })
+        );
+
+        assert_eq!(lib.pay_fines(10, Money::from_cents(50), day(17)), Ok(Money::ZERO));
+        assert_eq!(lib.deactivate_member(10), Ok(()));
+        assert!(!lib.find_member(10).unwrap().is_active());
+        assert_eq!(lib.checkout(10, 1, day(18)), Err(LibraryError::MemberInactive(10)));
+    }
+}
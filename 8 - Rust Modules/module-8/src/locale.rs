@@ -0,0 +1,238 @@
+//! Locale module - the words the library prints, in more than one language.
+//!
+//! Book listings, genre names and receipts are written in English unless a
+//! [`Locale`] says otherwise. A locale only needs the [`Message`]s it
+//! translates: anything it leaves out falls back to English, and custom
+//! genres are always shown by their own name. Applications register their
+//! locales with [`Library::register_locale`] and look them up by code with
+//! [`Library::locale`], e.g. from a member's language preference.
+//!
+//! ```
+//! use module_8::locale::{Locale, Message};
+//! use module_8::utils::format_book_info_in;
+//! use module_8::{Book, Genre, Library};
+//!
+//! let mut lib = Library::new();
+//! lib.register_locale(
+//!     Locale::new("es")
+//!         .with(Message::SciFi, "Ciencia ficción")
+//!         .with(Message::Available, "Disponible")
+//!         .with(Message::BookInfo, "[#{id}] \"{title}\" ({genre}) - {availability} | Prestado {count} veces"),
+//! );
+//!
+//! let book = Book::new(1, "Dune", Genre::SciFi);
+//! let info = format_book_info_in(&book, lib.locale("es"));
+//! assert_eq!(info, "[#1] \"Dune\" (Ciencia ficción) - Disponible | Prestado 0 veces");
+//! // Unknown codes get English
+//! assert_eq!(lib.locale("fr").genre_name(&Genre::SciFi), "Science Fiction");
+//! ```
+
+use std::collections::BTreeMap;
+
+use crate::book::Genre;
+use crate::utils::format_genre;
+use crate::Library;
+
+/// The code of the built-in English locale.
+pub const ENGLISH: &str = "en";
+
+// =============================================================================
+// MESSAGES
+// =============================================================================
+
+/// A piece of text a [`Locale`] can translate. Messages with
+/// `{placeholder}`s list them on the variant.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum Message {
+    /// A one-line book listing: `{id}`, `{title}`, `{genre}`,
+    /// `{availability}` and `{count}` (times borrowed).
+    BookInfo,
+    Available,
+    Borrowed,
+    Fiction,
+    NonFiction,
+    Technical,
+    Mystery,
+    SciFi,
+    CheckoutReceipt,
+    ReturnReceipt,
+    /// The member line of a receipt: `{name}` and `{id}`.
+    ReceiptMember,
+    /// When an item on a checkout receipt is due: `{date}`.
+    DueOn,
+    Fees,
+    BalanceOwed,
+    /// Column headings of a receipt's table.
+    Item,
+    Due,
+    Fee,
+}
+
+impl Message {
+    /// The English text, used when a locale doesn't translate a message.
+    pub fn english(&self) -> &'static str {
+        match self {
+            Message::BookInfo => "[#{id}] \"{title}\" ({genre}) - {availability} | Borrowed {count} times",
+            Message::Available => "Available",
+            Message::Borrowed => "Borrowed",
+            Message::Fiction => "Fiction",
+            Message::NonFiction => "Non-Fiction",
+            Message::Technical => "Technical",
+            Message::Mystery => "Mystery",
+            Message::SciFi => "Science Fiction",
+            Message::CheckoutReceipt => "Checkout receipt",
+            Message::ReturnReceipt => "Return receipt",
+            Message::ReceiptMember => "Member: {name} (#{id})",
+            Message::DueOn => "due {date}",
+            Message::Fees => "Fees",
+            Message::BalanceOwed => "Balance owed",
+            Message::Item => "Item",
+            Message::Due => "Due",
+            Message::Fee => "Fee",
+        }
+    }
+
+    /// The message naming a built-in genre; `None` for a custom one.
+    pub fn for_genre(genre: &Genre) -> Option<Message> {
+        match genre {
+            Genre::Fiction => Some(Message::Fiction),
+            Genre::NonFiction => Some(Message::NonFiction),
+            Genre::Technical => Some(Message::Technical),
+            Genre::Mystery => Some(Message::Mystery),
+            Genre::SciFi => Some(Message::SciFi),
+            Genre::Custom(_) => None,
+        }
+    }
+}
+
+// =============================================================================
+// LOCALES
+// =============================================================================
+
+/// Translations for one language, identified by a code such as `"de"`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Locale {
+    code: String,
+    messages: BTreeMap<Message, String>,
+}
+
+impl Locale {
+    /// A locale with no translations yet: everything is English until
+    /// [`with`](Locale::with) says otherwise.
+    pub fn new(code: &str) -> Self {
+        Locale {
+            code: String::from(code),
+            messages: BTreeMap::new(),
+        }
+    }
+
+    /// The built-in English locale.
+    pub fn english() -> Self {
+        Locale::new(ENGLISH)
+    }
+
+    /// Translates `message` as `text`.
+    pub fn with(mut self, message: Message, text: &str) -> Self {
+        self.messages.insert(message, String::from(text));
+        self
+    }
+
+    pub fn code(&self) -> &str {
+        &self.code
+    }
+
+    /// The locale's text for `message`, or the English one.
+    pub fn text(&self, message: Message) -> &str {
+        self.messages.get(&message).map_or(message.english(), String::as_str)
+    }
+
+    /// A genre's name in this language; custom genres keep their own name.
+    pub fn genre_name<'a>(&'a self, genre: &'a Genre) -> &'a str {
+        match Message::for_genre(genre) {
+            Some(message) => self.text(message),
+            None => format_genre(genre),
+        }
+    }
+}
+
+impl Default for Locale {
+    fn default() -> Self {
+        Locale::english()
+    }
+}
+
+/// The locales a library knows, by code. English is always there.
+#[derive(Debug, Default)]
+pub(crate) struct Locales {
+    locales: BTreeMap<String, Locale>,
+    english: Locale,
+}
+
+// =============================================================================
+// LIBRARY INTEGRATION
+// =============================================================================
+
+impl Library {
+    /// Adds a locale, replacing any registered under the same code
+    /// (including English).
+    pub fn register_locale(&mut self, locale: Locale) {
+        if locale.code == ENGLISH {
+            self.locales.english = locale;
+        } else {
+            self.locales.locales.insert(locale.code.clone(), locale);
+        }
+    }
+
+    /// The locale registered under `code`, or English if there is none.
+    pub fn locale(&self, code: &str) -> &Locale {
+        self.locales.locales.get(code).unwrap_or(&self.locales.english)
+    }
+
+    /// Codes of the registered locales, English included, in order.
+    pub fn locale_codes(&self) -> Vec<&str> {
+        let mut codes: Vec<&str> = self.locales.locales.keys().map(String::as_str).collect();
+        codes.push(ENGLISH);
+        codes.sort_unstable();
+        codes
+    }
+}
+
+// =============================================================================
+// TESTS
+// =============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn german() -> Locale {
+        Locale::new("de")
+            .with(Message::Fiction, "Belletristik")
+            .with(Message::Borrowed, "Ausgeliehen")
+    }
+
+    #[test]
+    fn test_missing_messages_fall_back_to_english() {
+        let de = german();
+        assert_eq!(de.code(), "de");
+        assert_eq!(de.genre_name(&Genre::Fiction), "Belletristik");
+        assert_eq!(de.genre_name(&Genre::Mystery), "Mystery");
+        assert_eq!(de.genre_name(&Genre::Custom(String::from("Krimi"))), "Krimi");
+        assert_eq!(de.text(Message::Available), "Available");
+        assert_eq!(Locale::default().text(Message::Fees), "Fees");
+    }
+
+    #[test]
+    fn test_registering_locales() {
+        let mut lib = Library::new();
+        lib.register_locale(german());
+        assert_eq!(lib.locale("de").text(Message::Borrowed), "Ausgeliehen");
+        assert_eq!(lib.locale("xx").code(), ENGLISH);
+
+        lib.register_locale(Locale::english().with(Message::Fees, "Charges"));
+        assert_eq!(lib.locale("xx").text(Message::Fees), "Charges");
+        lib.register_locale(Locale::new("de"));
+        assert_eq!(lib.locale("de").text(Message::Borrowed), "Borrowed");
+        assert_eq!(lib.locale_codes(), ["de", "en"]);
+    }
+}
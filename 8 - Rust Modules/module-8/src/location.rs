@@ -0,0 +1,250 @@
+//! Location module - where each copy is kept, and returns waiting to go back.
+//!
+//! A copy's [`Location`] is its branch, room and shelf code, kept on the
+//! book as [`Book::location`]. Set it with [`Library::move_book`], or move a
+//! whole shelf at once with [`Library::move_shelf`]; a book without one
+//! hasn't been given a place yet.
+//!
+//! A physical book that comes back joins the re-shelving queue until it is
+//! put away and [`Library::mark_shelved`] is called. Books set aside for a
+//! hold skip the queue, since they go to the hold shelf instead.
+//! [`Library::shelving_list`] orders the queue for one walk around the
+//! building: by location, then call number.
+//!
+//! ```
+//! use chrono::NaiveDate;
+//! use module_8::location::Location;
+//! use module_8::{Book, Genre, Library, Member, MembershipTier};
+//!
+//! let day = NaiveDate::from_ymd_opt(2024, 3, 1).unwrap();
+//! let mut lib = Library::new();
+//! lib.add_book(Book::new(1, "Dune", Genre::SciFi)).unwrap();
+//! lib.register_member(Member::new(10, "Alice", MembershipTier::Basic)).unwrap();
+//! lib.move_book(1, Location::new("Main", "Fiction Hall", "SCI-3")).unwrap();
+//!
+//! lib.checkout(10, 1, day).unwrap();
+//! lib.return_book(10, 1, day).unwrap();
+//! let list = lib.shelving_list();
+//! assert_eq!(list[0].book.location.as_ref().unwrap().to_string(), "Main / Fiction Hall / SCI-3");
+//!
+//! assert!(lib.mark_shelved(1));
+//! assert!(lib.shelving_list().is_empty());
+//! ```
+
+use std::fmt;
+
+use chrono::NaiveDate;
+
+use crate::book::Book;
+use crate::error::LibraryError;
+use crate::shelving::CallNumber;
+use crate::Library;
+
+/// Where a copy is kept: a shelf in a room of a branch.
+///
+/// Locations sort by branch, then room, then shelf code, which is the order
+/// [`Library::shelving_list`] visits them in.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Location {
+    pub branch: String,
+    pub room: String,
+    /// The code on the shelf's label, e.g. `SCI-3`.
+    pub shelf: String,
+}
+
+impl Location {
+    pub fn new(branch: &str, room: &str, shelf: &str) -> Location {
+        Location {
+            branch: String::from(branch),
+            room: String::from(room),
+            shelf: String::from(shelf),
+        }
+    }
+}
+
+impl fmt::Display for Location {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} / {} / {}", self.branch, self.room, self.shelf)
+    }
+}
+
+/// A returned book waiting to be put back on its shelf.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct AwaitingShelving {
+    pub book_id: u64,
+    pub returned_on: NaiveDate,
+}
+
+/// One line of [`Library::shelving_list`].
+#[derive(Debug, Clone)]
+pub struct ShelvingItem<'a> {
+    pub book: &'a Book,
+    pub call_number: CallNumber,
+    pub returned_on: NaiveDate,
+}
+
+// =============================================================================
+// LIBRARY INTEGRATION
+// =============================================================================
+
+impl Library {
+    /// Gives a book a new location, returning the one it had.
+    ///
+    /// Books on loan can be moved too; they go to the new place when they
+    /// come back. A book waiting to be re-shelved is taken off the queue,
+    /// since moving it means putting it away.
+    ///
+    /// # Errors
+    ///
+    /// [`LibraryError::BookNotFound`] if the book isn't catalogued.
+    pub fn move_book(&mut self, id: u64, to: Location) -> Result<Option<Location>, LibraryError> {
+        let book = self.books.get_mut(&id).ok_or(LibraryError::BookNotFound(id))?;
+        let previous = book.location.replace(to);
+        self.mark_shelved(id);
+        Ok(previous)
+    }
+
+    /// Moves every copy kept at `from`, on the shelf or on loan, to `to`.
+    /// Returns their IDs in catalog order.
+    pub fn move_shelf(&mut self, from: &Location, to: &Location) -> Vec<u64> {
+        let moved: Vec<u64> = self
+            .catalog()
+            .filter(|book| book.location.as_ref() == Some(from))
+            .map(Book::id)
+            .collect();
+        for id in &moved {
+            if let Some(book) = self.books.get_mut(id) {
+                book.location = Some(to.clone());
+            }
+        }
+        moved
+    }
+
+    /// Books on the shelf at `location`, in shelving order.
+    pub fn books_at(&self, location: &Location) -> Vec<&Book> {
+        self.shelf_order()
+            .into_iter()
+            .filter(|book| book.location.as_ref() == Some(location))
+            .collect()
+    }
+
+    /// Returned books not yet put back, in the order they came back.
+    pub fn awaiting_shelving(&self) -> &[AwaitingShelving] {
+        &self.reshelving
+    }
+
+    /// Takes a book off the re-shelving queue once it is back on its shelf.
+    /// Returns `false` if it wasn't waiting.
+    pub fn mark_shelved(&mut self, id: u64) -> bool {
+        let before = self.reshelving.len();
+        self.reshelving.retain(|entry| entry.book_id != id);
+        self.reshelving.len() != before
+    }
+
+    /// The books waiting to be re-shelved, ordered for putting them away:
+    /// by location, then call number. Books without a location come last.
+    pub fn shelving_list(&self) -> Vec<ShelvingItem<'_>> {
+        let mut list: Vec<ShelvingItem> = self
+            .reshelving
+            .iter()
+            .filter_map(|entry| {
+                let book = self.get_book(entry.book_id)?;
+                Some(ShelvingItem {
+                    book,
+                    call_number: CallNumber::of(book),
+                    returned_on: entry.returned_on,
+                })
+            })
+            .collect();
+        list.sort_by(|a, b| {
+            (a.book.location.is_none(), &a.book.location, &a.call_number, a.book.id()).cmp(&(
+                b.book.location.is_none(),
+                &b.book.location,
+                &b.call_number,
+                b.book.id(),
+            ))
+        });
+        list
+    }
+
+    /// Puts a book that has just come back on the re-shelving queue.
+    pub(crate) fn queue_for_shelving(&mut self, book_id: u64, returned_on: NaiveDate) {
+        self.mark_shelved(book_id);
+        self.reshelving.push(AwaitingShelving { book_id, returned_on });
+    }
+}
+
+// =============================================================================
+// TESTS
+// =============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Genre, Member, MembershipTier};
+
+    fn date(day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(2024, 3, day).unwrap()
+    }
+
+    fn library() -> Library {
+        let mut lib = Library::new();
+        lib.add_book(Book::new(1, "Foundation", Genre::SciFi)).unwrap();
+        lib.add_book(Book::new(2, "Emma", Genre::Fiction)).unwrap();
+        lib.add_book(Book::new(3, "Dune", Genre::SciFi)).unwrap();
+        lib.add_book(Book::new(4, "Persuasion", Genre::Fiction)).unwrap();
+        lib.register_member(Member::new(10, "Alice", MembershipTier::Gold)).unwrap();
+        lib.register_member(Member::new(11, "Bob", MembershipTier::Gold)).unwrap();
+        lib
+    }
+
+    fn ids(list: &[ShelvingItem]) -> Vec<u64> {
+        list.iter().map(|item| item.book.id()).collect()
+    }
+
+    #[test]
+    fn test_moving_books_and_shelves() {
+        let mut lib = library();
+        let scifi = Location::new("Main", "Hall", "SCI-1");
+        let annex = Location::new("Main", "Annex", "SCI-9");
+        assert_eq!(lib.move_book(1, scifi.clone()), Ok(None));
+        lib.move_book(3, scifi.clone()).unwrap();
+        assert_eq!(lib.move_book(99, scifi.clone()), Err(LibraryError::BookNotFound(99)));
+
+        lib.checkout(10, 1, date(1)).unwrap();
+        assert_eq!(lib.books_at(&scifi).iter().map(|b| b.id()).collect::<Vec<_>>(), [3]);
+        assert_eq!(lib.move_shelf(&scifi, &annex), [3, 1]);
+        assert!(lib.books_at(&scifi).is_empty());
+        assert_eq!(lib.move_book(1, scifi.clone()), Ok(Some(annex)));
+    }
+
+    #[test]
+    fn test_shelving_list_follows_locations() {
+        let mut lib = library();
+        lib.move_book(1, Location::new("Main", "Hall", "SCI-1")).unwrap();
+        lib.move_book(2, Location::new("Main", "Hall", "FIC-1")).unwrap();
+        lib.move_book(3, Location::new("Main", "Hall", "SCI-1")).unwrap();
+        for id in 1..=4 {
+            lib.checkout(10, id, date(1)).unwrap();
+        }
+        lib.place_hold(11, 4, date(2)).unwrap();
+        for id in [1, 2, 3, 4] {
+            lib.return_book(10, id, date(5)).unwrap();
+        }
+
+        // Persuasion went to the hold shelf; Dune sorts before Foundation
+        let list = lib.shelving_list();
+        assert_eq!(ids(&list), [2, 3, 1]);
+        assert_eq!(list[0].returned_on, date(5));
+
+        // Moving a book puts it away; a checkout takes it off the cart
+        lib.move_book(2, Location::new("North", "Hall", "FIC-1")).unwrap();
+        lib.checkout(11, 3, date(6)).unwrap();
+        assert_eq!(ids(&lib.shelving_list()), [1]);
+        assert!(lib.mark_shelved(1));
+        assert!(!lib.mark_shelved(1));
+        assert!(lib.awaiting_shelving().is_empty());
+    }
+}
@@ -0,0 +1,295 @@
+//! Lost module - declaring very overdue loans lost, and finding them again.
+//!
+//! Once a loan is [`lost_after_days`](crate::LibraryConfig::lost_after_days)
+//! past due, staff may [mark it lost](Library::mark_lost): the copy leaves
+//! the catalog and the member is billed the
+//! [`replacement_cost`](crate::LibraryConfig::replacement_cost) plus the most
+//! their tier can be fined for one loan. A copy reported lost at the desk
+//! with [`Condition::Lost`] is recorded the same way, billed its late fee so
+//! far.
+//!
+//! Lost copies are kept aside, so if one turns up
+//! [`mark_found`](Library::mark_found) puts it back on the shelf and waives
+//! the replacement cost and whatever part of the fine exceeds the late fee
+//! for returning it that day.
+//!
+//! ```
+//! use chrono::{Duration, NaiveDate};
+//! use module_8::{Book, Genre, Library, Member, MembershipTier, Money};
+//!
+//! let day = NaiveDate::from_ymd_opt(2024, 1, 1).unwrap();
+//! let mut lib = Library::new();
+//! lib.add_book(Book::new(1, "Dune", Genre::SciFi)).unwrap();
+//! lib.register_member(Member::new(10, "Alice", MembershipTier::Basic)).unwrap();
+//! let due = lib.checkout(10, 1, day).unwrap();
+//!
+//! let billed = lib.mark_lost(10, 1, due + Duration::days(60)).unwrap();
+//! assert_eq!(billed, Money::from_cents(2000 + 1500)); // replacement + Basic's cap
+//! assert!(lib.get_book(1).is_none());
+//!
+//! lib.mark_found(1, due + Duration::days(90)).unwrap();
+//! assert!(lib.find_book(1).is_some());
+//! assert_eq!(lib.find_member(10).unwrap().outstanding_fines(), Money::from_cents(1500));
+//! ```
+
+use std::fmt;
+
+use chrono::NaiveDate;
+
+use crate::book::Book;
+use crate::circulation::Loan;
+use crate::error::LibraryError;
+use crate::ledger::FineKind;
+use crate::member::LoanRecord;
+use crate::money::Money;
+use crate::Library;
+
+/// A copy declared lost, kept aside in case it turns up.
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct LostBook {
+    pub book: Book,
+    /// The loan it was lost on.
+    pub loan: Loan,
+    pub declared_on: NaiveDate,
+    /// The replacement cost billed.
+    pub replacement: Money,
+    /// The late fee billed, before any part already accrued.
+    pub fine: Money,
+}
+
+// =============================================================================
+// ERRORS
+// =============================================================================
+
+/// Why a loan couldn't be marked lost, or a book found.
+#[derive(Debug, Clone, PartialEq)]
+pub enum LostError {
+    /// The loan isn't yet `required` days past due.
+    NotOverdueEnough { book_id: u64, days_overdue: u32, required: u32 },
+    /// Digital copies can't be lost.
+    DigitalCopy(u64),
+    /// No lost copy has this ID.
+    NotLost(u64),
+    /// The loan doesn't exist, or the found copy's ID is in use again.
+    Library(LibraryError),
+}
+
+impl fmt::Display for LostError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LostError::NotOverdueEnough { book_id, days_overdue, required } => write!(
+                f,
+                "book {} is {} day(s) overdue; it can be marked lost after {}",
+                book_id, days_overdue, required
+            ),
+            LostError::DigitalCopy(id) => write!(f, "book {} is a digital copy and can't be lost", id),
+            LostError::NotLost(id) => write!(f, "book {} is not lost", id),
+            LostError::Library(e) => e.fmt(f),
+        }
+    }
+}
+
+impl std::error::Error for LostError {}
+
+impl From<LibraryError> for LostError {
+    fn from(e: LibraryError) -> Self {
+        LostError::Library(e)
+    }
+}
+
+// =============================================================================
+// LIBRARY INTEGRATION
+// =============================================================================
+
+impl Library {
+    /// Declares a very overdue loan lost: the copy leaves the catalog, any
+    /// holds on it are dropped, and the member is billed the replacement
+    /// cost plus their tier's [fee cap](crate::LibraryConfig::fee_cap) (or
+    /// the late fee so far, for a tier without one). Fees already accrued
+    /// on the loan count towards it. Returns the amount charged.
+    ///
+    /// # Errors
+    ///
+    /// [`LostError::NotOverdueEnough`] before the loan is
+    /// [`lost_after_days`](crate::LibraryConfig::lost_after_days) past due,
+    /// [`LostError::DigitalCopy`] for a digital loan, and
+    /// `LibraryError::NotOnLoan` if the member isn't borrowing the book.
+    pub fn mark_lost(&mut self, member_id: u64, book_id: u64, today: NaiveDate) -> Result<Money, LostError> {
+        let not_on_loan = LibraryError::NotOnLoan { member_id, book_id };
+        let loan = self
+            .loans
+            .iter()
+            .find(|l| l.book_id == book_id && l.member_id == member_id)
+            .cloned()
+            .ok_or(not_on_loan.clone())?;
+        if self.is_digital_loan(&loan) {
+            return Err(LostError::DigitalCopy(book_id));
+        }
+        let required = self.config.lost_after_days;
+        let days_overdue = loan.days_overdue(today);
+        if days_overdue < required {
+            return Err(LostError::NotOverdueEnough { book_id, days_overdue, required });
+        }
+        let member = self.find_member(member_id).ok_or(not_on_loan)?;
+        let fine = match self.config.fee_cap(member.tier()) {
+            Some(cap) => cap,
+            None => self.member_late_fee(member, &loan, today),
+        };
+        let replacement = self.config.replacement_cost;
+        let charged = fine.saturating_sub(self.accrued_for(&loan)).saturating_add(replacement);
+
+        let member = self.members.get_mut(&member_id).expect("found above");
+        member.charge(charged);
+        member.record_history(LoanRecord {
+            book_id,
+            borrowed_on: Some(loan.borrowed_on),
+            returned_on: today,
+            fee_charged: fine.saturating_add(replacement),
+        });
+        let position = self.loans.iter().position(|l| *l == loan).expect("found above");
+        self.close_loan(position);
+        self.record_fine(today, member_id, FineKind::Assessed, charged);
+        self.review_status(member_id);
+        self.set_aside_lost(loan, today, replacement, fine);
+        Ok(charged)
+    }
+
+    /// Puts a lost copy back on the shelf, queued for re-shelving, and
+    /// waives what losing it cost the member beyond the late fee for
+    /// returning it `today` (capped at the fine they were billed). Returns
+    /// the amount waived, which is less than that if they've paid some of
+    /// their fines since; a refund of the rest is left to staff.
+    ///
+    /// # Errors
+    ///
+    /// [`LostError::NotLost`] if no lost copy has the ID, and
+    /// [`LostError::Library`] if the ID has been given to another book
+    /// since or the catalog is full.
+    pub fn mark_found(&mut self, book_id: u64, today: NaiveDate) -> Result<Money, LostError> {
+        let pos = self
+            .lost_books
+            .iter()
+            .position(|lost| lost.book.id() == book_id)
+            .ok_or(LostError::NotLost(book_id))?;
+        self.add_book_allowing_duplicates(self.lost_books[pos].book.clone())?;
+        let lost = self.lost_books.remove(pos);
+        self.queue_for_shelving(book_id, today);
+
+        let member_id = lost.loan.member_id;
+        let Some(member) = self.find_member(member_id) else {
+            return Ok(Money::ZERO);
+        };
+        let late_fee = self.member_late_fee(member, &lost.loan, today).min(lost.fine);
+        let credit = lost.replacement.saturating_add(lost.fine.saturating_sub(late_fee));
+        let owed = member.outstanding_fines();
+        let left = self.waive_fines(member_id, credit, today)?;
+        Ok(owed - left)
+    }
+
+    /// Copies declared lost and not found since, oldest first.
+    pub fn lost_books(&self) -> &[LostBook] {
+        &self.lost_books
+    }
+
+    /// Takes a lost copy out of the catalog and keeps it aside.
+    pub(crate) fn set_aside_lost(&mut self, loan: Loan, today: NaiveDate, replacement: Money, fine: Money) {
+        let book = self.books.remove(&loan.book_id).expect("lent books stay catalogued");
+        self.index.remove_book(&book);
+        self.holds.retain(|h| h.book_id != loan.book_id);
+        self.lost_books.push(LostBook {
+            book,
+            loan,
+            declared_on: today,
+            replacement,
+            fine,
+        });
+    }
+}
+
+// =============================================================================
+// TESTS
+// =============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Condition, Format, Genre, Member, MembershipTier};
+    use chrono::Duration;
+
+    fn start() -> NaiveDate {
+        NaiveDate::from_ymd_opt(2024, 1, 1).unwrap()
+    }
+
+    fn library() -> Library {
+        let mut lib = Library::new();
+        lib.add_book(Book::new(1, "Dune", Genre::SciFi)).unwrap();
+        let mut ebook = Book::new(2, "Emma", Genre::Fiction);
+        ebook.format = Format::Digital;
+        lib.add_book(ebook).unwrap();
+        lib.register_member(Member::new(10, "Alice", MembershipTier::Basic)).unwrap();
+        lib.register_member(Member::new(11, "Bob", MembershipTier::Basic)).unwrap();
+        lib.checkout(10, 1, start()).unwrap();
+        lib.checkout(10, 2, start()).unwrap();
+        lib
+    }
+
+    #[test]
+    fn test_mark_lost_bills_replacement_and_max_fine() {
+        let mut lib = library();
+        let due = lib.loans()[0].due_on;
+        lib.place_hold(11, 1, start()).unwrap();
+        assert_eq!(
+            lib.mark_lost(10, 1, due + Duration::days(59)),
+            Err(LostError::NotOverdueEnough { book_id: 1, days_overdue: 59, required: 60 })
+        );
+        assert_eq!(lib.mark_lost(10, 2, due + Duration::days(90)), Err(LostError::DigitalCopy(2)));
+        assert_eq!(
+            lib.mark_lost(11, 1, due + Duration::days(90)),
+            Err(LostError::Library(LibraryError::NotOnLoan { member_id: 11, book_id: 1 }))
+        );
+
+        // A fee accrued on the way counts towards the fine
+        lib.return_book(10, 2, start()).unwrap();
+        lib.accrue_fees(due + Duration::days(10));
+        let charged = lib.mark_lost(10, 1, due + Duration::days(60)).unwrap();
+        assert_eq!(charged, Money::from_cents(2000 + 1500 - 250));
+        let alice = lib.find_member(10).unwrap();
+        assert_eq!(alice.outstanding_fines(), Money::from_cents(3500));
+        assert!(lib.borrowed_books(10).is_empty());
+        assert!(lib.get_book(1).is_none() && lib.holds().is_empty());
+        assert_eq!(lib.lost_books()[0].declared_on, due + Duration::days(60));
+        assert_eq!(lib.check_invariants(), Ok(()));
+    }
+
+    #[test]
+    fn test_mark_found_waives_all_but_the_late_fee() {
+        let mut lib = library();
+        let due = lib.loans()[0].due_on;
+        lib.mark_lost(10, 1, due + Duration::days(60)).unwrap();
+        lib.pay_fines(10, Money::from_cents(1000), due + Duration::days(61)).unwrap();
+
+        // Returned 10 days late it would have cost $2.50, but only $25.00 is still owed
+        let waived = lib.mark_found(1, due + Duration::days(10)).unwrap();
+        assert_eq!(waived, Money::from_cents(2500));
+        assert_eq!(lib.find_member(10).unwrap().outstanding_fines(), Money::ZERO);
+        assert_eq!(lib.awaiting_shelving()[0].book_id, 1);
+        assert!(lib.lost_books().is_empty());
+        assert_eq!(lib.mark_found(1, start()), Err(LostError::NotLost(1)));
+        assert_eq!(lib.check_invariants(), Ok(()));
+    }
+
+    #[test]
+    fn test_books_returned_lost_can_be_found() {
+        let mut lib = library();
+        let due = lib.loans()[0].due_on;
+        lib.return_book_in(10, 1, due + Duration::days(4), Condition::Lost).unwrap();
+        assert_eq!(lib.lost_books()[0].fine, Money::from_cents(100));
+
+        lib.add_book(Book::new(1, "Dune (replacement)", Genre::SciFi)).unwrap();
+        assert_eq!(lib.mark_found(1, due), Err(LostError::Library(LibraryError::DuplicateBook(1))));
+        lib.remove_book(1).unwrap();
+        assert_eq!(lib.mark_found(1, due), Ok(Money::from_cents(2100)));
+        assert_eq!(lib.find_book(1).unwrap().title, "Dune");
+    }
+}
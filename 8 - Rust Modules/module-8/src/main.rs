@@ -21,17 +21,32 @@
 
 // GROUPED IMPORTS: Import multiple items from the same crate using braces.
 // These work because lib.rs re-exports them with `pub use`.
-use module_8::{Book, Genre, Library, Member, MembershipTier};
+use module_8::{Book, Calendar, Condition, ConsoleNotifier, Format, Genre, Library, LibraryConfig, LibrarySystem, Member, MembershipTier, Money};
 
 // INDIVIDUAL IMPORTS: You can also import items one by one.
+use module_8::access::Actor;
+use module_8::consortium::Consortium;
+use module_8::dedup::OnDuplicate;
+use module_8::fixtures::Fixtures;
+use module_8::labels;
+use module_8::locale::{Locale, Message};
+use module_8::location::Location;
+use module_8::reports::{financials, standard_sections, MostBorrowed, OverdueLoans, ShelvingList};
 use module_8::calculate_late_fee;
+use module_8::simulation::{simulate, SimulationPolicy};
+use module_8::stats::Statistics;
+use module_8::storage::{MemoryStorage, Storage};
+use module_8::undo::LibraryCommand;
+use module_8::sorting::{SortField, SortKey};
+use module_8::templates::NoticeKind;
 use module_8::format_book_info;
+use module_8::utils::format_book_info_in;
 use module_8::LIBRARY_NAME;
 
 // NESTED PATH IMPORTS: Access items from nested modules.
 // Even though `config` is a module inside lib.rs, we can access its
 // public submodules.
-use module_8::config::fees::LATE_FEE_PER_DAY;
+use module_8::config::fees::{calculate_late_fee_between, LATE_FEE_PER_DAY};
 
 // ALIAS IMPORT: Rename an import to avoid conflicts or improve clarity.
 use module_8::utils::formatting::genre_emoji as get_emoji;
@@ -43,9 +58,19 @@ use module_8::utils::formatting::genre_emoji as get_emoji;
 // External crates are added to Cargo.toml under [dependencies].
 // After adding `chrono = "0.4"` to Cargo.toml, we can use it here.
 // This demonstrates using crates from crates.io.
-use chrono::Local;
+use chrono::{Duration, Local, Weekday};
+
+// A module of the BINARY crate (src/cli.rs), separate from the library's
+// modules even though it sits in the same directory.
+mod cli;
 
 fn main() {
+    // With arguments, run a library command instead of the demonstration
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    if !args.is_empty() {
+        std::process::exit(cli::run(args));
+    }
+
     println!("╔════════════════════════════════════════════════════════════╗");
     println!("║     RUST MODULES DEMONSTRATION - Library Management        ║");
     println!("╚════════════════════════════════════════════════════════════╝\n");
@@ -69,7 +94,14 @@ fn main() {
     println!("─────────────────────────────────────────────────────────────");
 
     // Create books using the re-exported types
-    let book1 = Book::new(1, "The Rust Programming Language", Genre::Technical);
+    // The builder takes the optional catalog metadata
+    let book1 = Book::builder(1, "The Rust Programming Language")
+        .genre(Genre::Technical)
+        .isbn("978-1-7185-0310-6")
+        .year(2023)
+        .publisher("No Starch Press")
+        .pages(560)
+        .build();
     let book2 = Book::new(2, "Clean Code", Genre::Technical);
     let book3 = Book::new(3, "Foundation", Genre::SciFi);
     let book4 = Book::new(4, "Murder on the Orient Express", Genre::Mystery);
@@ -79,6 +111,20 @@ fn main() {
     println!("{}", format_book_info(&book2));
     println!("{}", format_book_info(&book3));
     println!("{}", format_book_info(&book4));
+    // The same listing for Spanish-speaking patrons; untranslated words stay English
+    library.register_locale(
+        Locale::new("es")
+            .with(Message::SciFi, "Ciencia ficción")
+            .with(Message::Available, "Disponible")
+            .with(Message::BookInfo, "[#{id}] \"{title}\" ({genre}) - {availability} | Prestado {count} veces"),
+    );
+    println!("{}", format_book_info_in(&book3, library.locale("es")));
+    println!(
+        "  #1 published by {} in {}, {} pages",
+        book1.publisher.as_deref().unwrap_or("unknown"),
+        book1.year.map_or(String::from("unknown"), |y| y.to_string()),
+        book1.pages.unwrap_or(0)
+    );
 
     // Using the aliased import for emoji
     println!(
@@ -87,12 +133,87 @@ fn main() {
         get_emoji(&Genre::SciFi),
         get_emoji(&Genre::Mystery)
     );
+    // Applications can swap icons or give custom genres their own
+    let poetry = Genre::Custom(String::from("Poetry"));
+    library.genre_icons_mut().set(poetry.clone(), "🪶");
+    println!("Custom genre icon: {}", library.genre_icons().label(&poetry));
+
+    for book in [book1, book2, book3, book4] {
+        library.add_book(book).expect("IDs 1-4 are free");
+    }
+    // A second record of the same title and ISBN fills in gaps instead
+    let again = Book::builder(5, "the rust programming language")
+        .isbn("9781718503106")
+        .author("Steve Klabnik")
+        .build();
+    let outcome = library
+        .add_book_deduplicated(again, OnDuplicate::Merge)
+        .expect("ID 5 is free");
+    println!("\nAdding the Rust book again: {:?}", outcome);
+    println!("Total books in library: {}", library.book_count());
+    let by_title: Vec<&str> = library
+        .books_sorted_by(SortKey::ascending(SortField::Title))
+        .into_iter()
+        .map(|b| b.title.as_str())
+        .collect();
+    println!("Sorted by title: {}", by_title.join(", "));
+    let indexed: Vec<&str> = library.search_titles("programming rust").iter().map(|b| b.title.as_str()).collect();
+    println!("Titles with \"programming\" and \"rust\": {:?}", indexed);
+    for hit in library.search_fuzzy("rust programing langauge", 2) {
+        println!("Fuzzy match ({:.2}): {}", hit.score, hit.book.title);
+    }
+    let globbed: Vec<&str> = library.find_books_glob("the *").iter().map(|b| b.title.as_str()).collect();
+    println!("Titles matching \"the *\": {:?}", globbed);
+
+    // Staff curate reading lists; patrons see what's on the shelf
+    let rust_books: Vec<u64> = library.find_books_glob("*rust*").iter().map(|b| b.id()).collect();
+    library
+        .create_collection("Summer Rust Reading", "Our favourite books on Rust")
+        .expect("no collections yet");
+    for id in rust_books {
+        library.add_to_collection("Summer Rust Reading", id).expect("book is catalogued");
+    }
+    if let Ok(text) = library.render_collection("Summer Rust Reading") {
+        print!("{}", text);
+    }
+    // Edits through find_book_mut are re-indexed when the guard is dropped
+    if let Some(mut book) = library.find_book_mut(2) {
+        book.title = String::from("Clean Code: A Handbook of Agile Software Craftsmanship");
+    }
+    println!("Retitled: {:?}", library.search_titles("agile").iter().map(|b| b.id()).collect::<Vec<_>>());
+
+    // Tags are finer-grained than genres and match case-insensitively
+    for (id, tag) in [(1, "beginner"), (1, "Classic"), (2, "classic"), (3, "classic")] {
+        library.tag_book(id, tag).expect("book was just added");
+    }
+    let classics: Vec<&str> = library.books_with_tag("CLASSIC").iter().map(|b| b.title.as_str()).collect();
+    println!("Classics: {:?}", classics);
+    println!("Tag cloud: {:?}", library.tag_cloud());
+
+    // Subgenres: browsing Technical also finds books filed under Programming
+    let programming = Genre::Custom(String::from("Programming"));
+    library
+        .set_genre_parent(programming.clone(), Genre::Technical)
+        .expect("Technical isn't within Programming");
+    if let Err(e) = library.set_genre_parent(Genre::Technical, programming) {
+        println!("Refused: {}", e);
+    }
+    println!("Technical and its subgenres: {} books", library.books_in_genre_tree(&Genre::Technical).len());
+    let mut counts: Vec<(Genre, usize)> = library.genre_counts().into_iter().collect();
+    counts.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.to_string().cmp(&b.0.to_string())));
+    for (genre, count) in counts {
+        println!("  {}: {}", genre, count);
+    }
 
-    library.add_book(book1);
-    library.add_book(book2);
-    library.add_book(book3);
-    library.add_book(book4);
-    println!("\nTotal books in library: {}", library.book_count());
+    // Every copy gets a barcode and spine label derived from genre + ID
+    let next_id = library.generate_id();
+    let label = library
+        .new_barcode(&Genre::Mystery, next_id)
+        .expect("generated IDs are unused");
+    println!("Label for the next copy: {}", label);
+    if let Some(book) = library.find_by_barcode(&library.barcode_of(1).expect("book 1 exists")) {
+        println!("Spine label for \"{}\":\n{}", book.title, labels::spine_label(book));
+    }
     println!();
 
     // -------------------------------------------------------------------------
@@ -101,8 +222,10 @@ fn main() {
     println!("👥 REGISTERING MEMBERS");
     println!("─────────────────────────────────────────────────────────────");
 
-    let member1 = Member::new(1, "Alice", MembershipTier::Gold);
-    let member2 = Member::new(2, "Bob", MembershipTier::Silver);
+    let mut member1 = Member::new(1, "Alice", MembershipTier::Gold);
+    member1.set_email(Some("alice@example.com")).expect("valid address");
+    member1.set_phone(Some("+1 555 010 0001")).expect("valid number");
+    let mut member2 = Member::new(2, "Bob", MembershipTier::Silver);
     let member3 = Member::new(3, "Charlie", MembershipTier::Basic);
 
     // Using module function (not re-exported, accessed via full path)
@@ -110,19 +233,42 @@ fn main() {
 
     for member in [&member1, &member2, &member3, &guest] {
         println!(
-            "Member: {} | Tier: {:?} | Max Books: {} | Discount: {}%",
+            "Member: {} | Tier: {} | Max Books: {} | Discount: {}% | Fee cap: {}",
             member.name,
-            member.tier,
+            member.tier().name(),
             member.max_books(),
-            member.discount_percentage()
+            member.discount_percentage(),
+            member.tier().fee_cap().map_or_else(|| String::from("none"), |cap| cap.to_string())
         );
     }
 
-    library.register_member(member1);
-    library.register_member(member2);
-    library.register_member(member3);
-    library.register_member(guest);
-    println!("\nTotal members: {}", library.member_count());
+    // Tier changes are dated, so a fee can be checked against the tier of the day
+    let upgraded_on = Local::now().date_naive();
+    member2.upgrade_tier(MembershipTier::Gold, upgraded_on).expect("Gold lends more than Silver");
+    if let Err(e) = member2.downgrade_tier(MembershipTier::Platinum, upgraded_on) {
+        println!("Bob's downgrade refused: {}", e);
+    }
+    println!(
+        "Bob was {} the day before upgrading, {} since",
+        member2.tier_on(upgraded_on - Duration::days(1)).name(),
+        member2.tier_on(upgraded_on).name()
+    );
+
+    for member in [member1, member2, member3, guest] {
+        library.register_member(member).expect("member IDs are unique");
+    }
+    // Guests borrow one book at a time and can't place holds
+    if let Err(e) = library.place_hold(4, 1, Local::now().date_naive()) {
+        println!("Guest hold refused: {}", e);
+    }
+    // Or let the library pick the next free ID
+    let new_id = library
+        .register_member_auto("Dana Walsh", MembershipTier::Silver)
+        .expect("the name is valid");
+    println!("\nRegistered Dana Walsh as member #{}", new_id);
+    println!("Total members: {}", library.member_count());
+    let incomplete: Vec<&str> = library.members_missing_contact().iter().map(|m| m.name.as_str()).collect();
+    println!("Missing contact details: {}", incomplete.join(", "));
     println!();
 
     // -------------------------------------------------------------------------
@@ -131,9 +277,16 @@ fn main() {
     println!("💰 FEE CALCULATIONS");
     println!("─────────────────────────────────────────────────────────────");
 
-    println!("Late fee per day: {} cents", LATE_FEE_PER_DAY);
-    println!("Late fee for 3 days: {} cents", calculate_late_fee(3));
-    println!("Late fee for 7 days: {} cents", calculate_late_fee(7));
+    println!("Late fee per day: {}", LATE_FEE_PER_DAY);
+    println!("Late fee for 3 days: {}", calculate_late_fee(3));
+    println!("Late fee for 7 days: {}", calculate_late_fee(7));
+    // Closed on Sundays: only the open days in a week count
+    let closed_sundays = Calendar::always_open().closed_on(Weekday::Sun);
+    let due = Local::now().date_naive();
+    println!(
+        "Late fee for 7 days, closed Sundays: {}",
+        calculate_late_fee_between(due, due + Duration::days(7), &closed_sundays)
+    );
     println!();
 
     // -------------------------------------------------------------------------
@@ -174,6 +327,283 @@ fn main() {
     println!("  Book available: {}", book.is_available());
     println!();
 
+    // -------------------------------------------------------------------------
+    // Circulation with notification hooks
+    // -------------------------------------------------------------------------
+    println!("🔔 CIRCULATION & NOTIFICATIONS");
+    println!("─────────────────────────────────────────────────────────────");
+
+    // Any Notifier (or closure) can be plugged in; ConsoleNotifier prints.
+    library.add_notifier(ConsoleNotifier);
+    // Another thread could read this; here we just drain it later
+    let event_stream = library.subscribe();
+
+    let today = now.date_naive();
+    match library.checkout(3, 3, today) {
+        Ok(due) => println!("Charlie borrowed Foundation, due {}", due),
+        Err(e) => println!("Checkout failed: {}", e),
+    }
+    if let Some(receipt) = library.checkout_receipt(3, 3) {
+        print!("{}", receipt);
+    }
+    // Lent books stay catalogued; the loan just records who has them
+    if let Some(member_id) = library.borrower(3) {
+        println!("Book #3 is on loan to member #{} ({} books in the catalog)", member_id, library.catalog().count());
+    }
+    if let Err(e) = library.checkout(1, 3, today) {
+        println!("Alice can't borrow it: {}", e);
+    }
+    library.place_hold(1, 3, today).expect("book and member exist");
+
+    // Returned 20 days later: the daily job has been charging the late fee,
+    // then the return settles it and the hold becomes available
+    let later = today + Duration::days(20);
+    let run = library.tick(later);
+    println!("Daily run: {} overdue loan(s), {} accrued", run.overdue_loans, run.fees_charged);
+    for notice in library.render_notices(NoticeKind::Overdue, later) {
+        println!("Mail to {}: {}", notice.recipient, notice.subject);
+    }
+    let fee = library.return_book(3, 3, later).expect("book is on loan");
+    println!("Charlie paid a late fee of {}", fee);
+    if let Some(receipt) = library.return_receipt(3, 3) {
+        print!("{}", receipt.to_markdown());
+    }
+    // Only members who've returned a book may review it
+    library.rate(3, 3, 5, "A galaxy-sized idea").expect("Charlie returned it");
+    if let Err(e) = library.rate(1, 3, 4, "Looks good") {
+        println!("Alice can't review it yet: {}", e);
+    }
+    for book in library.top_rated_in_genre(&Genre::SciFi, 3) {
+        println!("Top rated sci-fi: {} ({:.1} stars)", book.title, book.average_rating().unwrap_or_default());
+    }
+    for notice in library.render_notices(NoticeKind::HoldReady, later) {
+        println!("Mail to {}: {}", notice.recipient, notice.subject);
+    }
+    // Alice has a week of open days to collect it before the hold lapses
+    let lapsed = library.process_expired_holds(later + Duration::days(10));
+    println!("Holds expired ten days on: {}; Foundation is back on the shelf", lapsed.len());
+
+    // A title the library doesn't own is borrowed from another library
+    let ill = library
+        .request_ill(1, "The Name of the Rose", None, today)
+        .expect("Alice is a member and the title isn't catalogued");
+    library.record_ill_shipped(ill, "City Library", today + Duration::days(3)).expect("request is open");
+    library
+        .add_ill_cost(ill, "Postage", Money::from_cents(450), today + Duration::days(3))
+        .expect("request exists");
+    library.record_ill_received(ill, today + Duration::days(5)).expect("title has shipped");
+    if let Some(request) = library.ill_request(ill) {
+        println!("Interlibrary loan #{} of {} is {}; cost {}", request.id, request.title, request.status, request.total_cost());
+    }
+
+    // A checkout keyed against the wrong card is taken back
+    let misclick = LibraryCommand::Checkout { member_id: 1, book_id: 3, today: later };
+    if library.execute(misclick).is_ok() {
+        library.undo().expect("nothing changed since the checkout");
+        println!("Undid a mistaken checkout; open loans: {}", library.loans().len());
+    }
+
+    // Patrons and staff go through sessions that check permissions
+    let charlie = Actor::patron(3);
+    if let Err(e) = library.session(charlie, later).waive_fines(3, fee) {
+        println!("Denied: {}", e);
+    }
+    let remaining = library
+        .session(Actor::librarian(900), later)
+        .waive_fines(3, fee)
+        .expect("librarians may waive fines");
+    println!("Librarian waived the fee; Charlie owes {}", remaining);
+    println!("Audit log entries: {}", library.audit_log().len());
+    println!("Events waiting in the subscribed channel: {}", event_stream.try_iter().count());
+    // What a Prometheus scrape of the service would see, minus the histogram
+    for line in library.metrics_text(later).lines().filter(|l| !l.starts_with('#') && !l.contains("latency")) {
+        println!("  {}", line);
+    }
+
+    // The fine ledger behind the balances feeds the treasurer's summary
+    let summary = financials(&library, today..=later);
+    println!(
+        "Fines this period: {} assessed, {} waived, {} collected, {} outstanding",
+        summary.assessed, summary.waived, summary.collected, summary.outstanding
+    );
+
+    // Condition is recorded at the desk; damaged copies queue for repair
+    library
+        .move_book(1, Location::new("Main", "Reading Room", "TEC-1"))
+        .expect("book is catalogued");
+    library.checkout(2, 1, later).expect("book is on the shelf");
+    library
+        .return_book_in(2, 1, later, Condition::Damaged)
+        .expect("book is on loan");
+    let repairs: Vec<&str> = library.needs_repair().iter().map(|b| b.title.as_str()).collect();
+    println!("Needs repair: {:?}", repairs);
+
+    // Shelf reading: a scan in the wrong order names the copies to move
+    let expected: Vec<u64> = library.shelf_order().iter().map(|b| b.id()).collect();
+    let mut scanned = expected.clone();
+    scanned.rotate_left(1);
+    for moved in library.read_shelf(&scanned).misplaced {
+        let place = moved.belongs_after.map_or(String::from("at the start"), |id| format!("after #{}", id));
+        println!("Misplaced: #{} ({}) belongs {}", moved.book_id, moved.call_number, place);
+    }
+
+    // Returns wait on the cart until they're put back; the shelving list
+    // orders them by shelf
+    print!("{}", library.report_with(&[Box::new(ShelvingList)]));
+    let cart: Vec<u64> = library.awaiting_shelving().iter().map(|entry| entry.book_id).collect();
+    for id in cart {
+        library.mark_shelved(id);
+    }
+
+    // A loan two months overdue can be written off as lost, and taken back
+    // if the copy turns up
+    let due = library.checkout(2, 1, later).expect("book is on the shelf");
+    let declared = due + Duration::days(i64::from(library.config().lost_after_days));
+    match library.mark_lost(2, 1, declared) {
+        Ok(billed) => println!("Book #1 lost; member #2 billed {}", billed),
+        Err(e) => println!("Couldn't mark book #1 lost: {}", e),
+    }
+    if let Ok(waived) = library.mark_found(1, declared + Duration::days(3)) {
+        println!("Book #1 found again; {} waived", waived);
+    }
+
+    // DVDs and magazines circulate like books, on shorter loans
+    let dvd = Book::media(library.generate_id(), "The Matrix", Genre::SciFi, 136);
+    let (dvd_id, kind) = (dvd.id(), dvd.kind.clone());
+    library.add_book(dvd).expect("generated IDs are unused");
+    let due = library.checkout(2, dvd_id, later).expect("DVD is on the shelf");
+    println!("The Matrix ({}) is due back {}", kind, due);
+    let mut ebook = Book::new(library.generate_id(), "Neuromancer", Genre::SciFi);
+    ebook.format = Format::Digital;
+    let ebook_id = ebook.id();
+    library.add_book(ebook).expect("generated IDs are unused");
+    library.checkout(2, ebook_id, later).expect("digital copies are always available");
+    library.checkout(3, ebook_id, later).expect("digital copies are always available");
+    println!("Neuromancer e-book: {} readers at once", library.loans().iter().filter(|l| l.book_id == ebook_id).count());
+
+    // Suggestions weigh Charlie's borrow history and overall popularity
+    let picks = library.recommend_for(3, 2).expect("Charlie is registered");
+    for pick in picks {
+        println!("Recommended for Charlie: {} (score {})", pick.book.title, pick.score);
+    }
+    println!();
+
+    // Reports are built from ReportSection plugins
+    println!("{}", library.report());
+    let mut sections = standard_sections();
+    sections.push(Box::new(MostBorrowed { limit: 3 }));
+    sections.push(Box::new(OverdueLoans { as_of: later }));
+    let wiki_page = library.build_report(&sections).to_markdown();
+    println!("Markdown report for the wiki: {} lines", wiki_page.lines().count());
+
+    // What would a 30-day loan period do over the next quarter?
+    let snapshot = library.snapshot(later);
+    let longer_loans = SimulationPolicy { loan_days: Some(30), ..SimulationPolicy::default() };
+    let projected = simulate(&snapshot, &longer_loans, 90, 2024);
+    println!(
+        "Simulated 30-day loans: {:.0}% availability, {} in fines, {} holds placed",
+        projected.average_availability * 100.0,
+        projected.fine_revenue,
+        projected.holds_placed
+    );
+    // Or run the real daily job a month ahead, look, and roll it back
+    let trial = library.snapshot(later);
+    let month_on = library.tick(later + Duration::days(30));
+    library.restore(trial).expect("the snapshot came from this library");
+    println!(
+        "A month ahead the daily job would charge {}; rolled back to {} ledger entries",
+        month_on.fees_charged,
+        library.fine_ledger().len()
+    );
+    // Any Storage backend can keep the data between runs
+    let mut storage = MemoryStorage::default();
+    storage.save(&library, later).expect("memory storage can't fail");
+    let reloaded = storage.load(library.config().clone()).expect("saved from a valid library");
+    println!("Reloaded from storage: {} books, {} members", reloaded.book_count(), reloaded.member_count());
+
+    // A system of branches moves copies between them and compares figures
+    let branch = |name: &str| Library::with_config(LibraryConfig { name: name.to_string(), ..LibraryConfig::default() });
+    let mut system = LibrarySystem::new();
+    system.add_branch(branch("Downtown")).expect("names are unique");
+    system.add_branch(branch("Riverside")).expect("names are unique");
+    system
+        .branch_mut("Downtown")
+        .expect("branch was just added")
+        .add_book(Book::new(1, "Dune", Genre::SciFi))
+        .expect("the branch is empty");
+    system.transfer_book(1, "Downtown", "Riverside").expect("Dune is on the shelf");
+    for stats in system.stats(later) {
+        println!("{}: {} on the shelf, {} on loan", stats.branch, stats.on_shelf, stats.on_loan);
+    }
+    // The same Statistics trait covers one library or a whole system
+    let figures: [(&str, &dyn Statistics); 2] = [("Main library", &library), ("Branch system", &system)];
+    for (label, stats) in figures {
+        let average = stats.average_loans_per_member().unwrap_or(0.0);
+        println!("{}: {} loans, {:.1} per member, {} in fees", label, stats.total_loans(), average, stats.total_fees());
+    }
+
+    // Member libraries search each other's catalogs and trade copies
+    let mut annex = Library::new();
+    annex
+        .add_book(Book::new(1, "The Rust Programming Language", Genre::Technical))
+        .expect("the annex is empty");
+    let mut consortium = Consortium::new();
+    consortium.add_remote("Archive", snapshot).expect("names are unique");
+    consortium.add_library("Main", library).expect("names are unique");
+    consortium.add_library("Annex", annex).expect("names are unique");
+    for hit in consortium.search("rust") {
+        println!("Consortium hit: {} at {} (#{})", hit.book.title, hit.library, hit.book.id());
+    }
+    let request = consortium
+        .request_transfer("Annex", 1, "Main", later)
+        .expect("both libraries are local");
+    let new_id = consortium.complete_transfer(request).expect("copy is on the shelf");
+    println!("Annex copy transferred to Main as book #{}", new_id);
+    println!();
+
+    // Generated data for tests and benchmarks: the same seed, the same library
+    let generated = Fixtures { seed: 2024, books: 1000, members: 100 }.library();
+    let sample = generated.find_book(1).expect("fixture books start at 1");
+    println!(
+        "Fixture library: {} books, {} members; book #1 is {:?} by {}",
+        generated.books().count(),
+        generated.members().count(),
+        sample.title,
+        sample.author.as_deref().unwrap_or("unknown")
+    );
+    let mut restocked = Library::new();
+    let shipment = Fixtures { seed: 7, books: 500, members: 0 }.make_books();
+    let report = restocked.add_books(shipment.iter().cloned().chain(shipment.iter().take(3).cloned()));
+    println!(
+        "Bulk add: {} books added, {} refused (first: {})",
+        report.added.len(),
+        report.failed.len(),
+        report.failed.first().map_or_else(String::new, |f| f.error.to_string())
+    );
+    println!();
+
+    // Startup settings from a file, then LIBRARY_* environment variables
+    let settings = "name = \"Harbor Branch\"\nlate_fee_per_day = \"0.10\"\nmax_borrowed_books = 8\ngrace_days = 2";
+    match LibraryConfig::from_toml(settings).and_then(|cfg| cfg.with_env_vars(std::env::vars())) {
+        Ok(cfg) => {
+            println!("Loaded config: {} lends {} books, {}/day late", cfg.name, cfg.max_borrowed_books, cfg.late_fee_per_day);
+            let policy = cfg.fee_policy();
+            println!("  {} days' grace: 3 days late costs {}", policy.grace_days(), policy.late_fee(3));
+        }
+        Err(e) => println!("Config error: {}", e),
+    }
+    if let Err(e) = LibraryConfig::from_toml("digital_loan_days = 0") {
+        println!("Rejected config: {}", e);
+    }
+    if let Ok(cfg) = LibraryConfig::from_toml("max_books = 1") {
+        let mut kiosk = Library::with_config(cfg);
+        kiosk.add_book(Book::new(1, "Dune", Genre::SciFi)).expect("the kiosk has room for one");
+        if let Err(e) = kiosk.add_book(Book::new(2, "Emma", Genre::Fiction)) {
+            println!("Kiosk refused a book: {}", e);
+        }
+    }
+    println!();
+
     // -------------------------------------------------------------------------
     // Summary
     // -------------------------------------------------------------------------
@@ -48,32 +48,161 @@ mod membership;
 // RE-EXPORTS FROM SUBMODULE
 // =============================================================================
 
-// Re-export `MembershipTier` so users can access it as `member::MembershipTier`
-// instead of `member::membership::MembershipTier`.
+// Re-export `MembershipTier` and `TierPolicy` so users can access them as
+// `member::MembershipTier` instead of `member::membership::MembershipTier`.
 // The original `membership` module remains private - users can't access it directly.
-pub use membership::MembershipTier;
+pub use membership::{GuestTier, MembershipTier, ParseTierError, TierPolicy};
+pub(crate) use membership::{is_built_in, parse_tier};
 
 // =============================================================================
 // MAIN STRUCT
 // =============================================================================
 
-use crate::book::Book;
+use std::fmt;
+use std::sync::Arc;
+
+use chrono::NaiveDate;
+
+use crate::money::Money;
+use crate::validation::{validate_email, validate_id, validate_member_name, validate_phone, ValidationError};
+
+/// A completed loan kept in a member's borrowing history.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct LoanRecord {
+    pub book_id: u64,
+    /// `None` when the borrow date is unknown (e.g. imported read dates).
+    pub borrowed_on: Option<NaiveDate>,
+    pub returned_on: NaiveDate,
+    /// Late fee charged at return time.
+    pub fee_charged: Money,
+}
+
+/// Why a member was suspended.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum SuspensionReason {
+    /// A [`StatusRule`](crate::suspension::StatusRule) was broken; lifted
+    /// automatically once no rule is.
+    Rule(String),
+    /// Suspended by staff; lifted only by staff.
+    Staff(String),
+}
+
+impl fmt::Display for SuspensionReason {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SuspensionReason::Rule(why) | SuspensionReason::Staff(why) => f.write_str(why),
+        }
+    }
+}
+
+/// Where a member stands with the library.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum MemberStatus {
+    Active,
+    /// May not check out books until reinstated.
+    Suspended { reason: SuspensionReason },
+    /// Deactivated for good; history is kept.
+    Closed,
+}
+
+/// A change of a member's tier, kept in their
+/// [`tier_history`](Member::tier_history).
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct TierChange {
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_impls::tier"))]
+    from: Arc<dyn TierPolicy>,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_impls::tier"))]
+    to: Arc<dyn TierPolicy>,
+    /// The first day the new tier applies.
+    pub effective_on: NaiveDate,
+}
+
+impl TierChange {
+    pub(crate) fn new(from: Arc<dyn TierPolicy>, to: Arc<dyn TierPolicy>, effective_on: NaiveDate) -> Self {
+        TierChange { from, to, effective_on }
+    }
+
+    /// The tier before the change.
+    pub fn from(&self) -> &dyn TierPolicy {
+        self.from.as_ref()
+    }
+
+    /// The tier after the change.
+    pub fn to(&self) -> &dyn TierPolicy {
+        self.to.as_ref()
+    }
+}
+
+/// Why a tier change was refused.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum TierChangeError {
+    /// The member is already on a tier of that name.
+    SameTier(String),
+    /// An upgrade must not lower the borrow limit.
+    NotAnUpgrade { from: String, to: String },
+    /// A downgrade must not raise the borrow limit.
+    NotADowngrade { from: String, to: String },
+    /// The new tier's limit is below the number of books the member has out.
+    TooManyBorrowed { borrowed: usize, limit: usize },
+    /// Changes must be dated on or after the member's latest one.
+    BeforeLastChange { last: NaiveDate },
+}
+
+impl fmt::Display for TierChangeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TierChangeError::SameTier(tier) => write!(f, "member is already {}", tier),
+            TierChangeError::NotAnUpgrade { from, to } => write!(f, "{} is not an upgrade from {}", to, from),
+            TierChangeError::NotADowngrade { from, to } => write!(f, "{} is not a downgrade from {}", to, from),
+            TierChangeError::TooManyBorrowed { borrowed, limit } => {
+                write!(f, "member has {} books out but the new tier allows {}", borrowed, limit)
+            }
+            TierChangeError::BeforeLastChange { last } => {
+                write!(f, "tier change must take effect on or after {}", last)
+            }
+        }
+    }
+}
+
+impl std::error::Error for TierChangeError {}
 
 /// A library member who can borrow books.
 ///
+/// A member doesn't keep track of the books they're borrowing: each loan is
+/// a [`Loan`](crate::Loan) record in the [`Library`](crate::Library) that
+/// lent the book, which stays catalogued there (see
+/// [`Library::borrowed_books`](crate::Library::borrowed_books)).
+///
 /// This struct demonstrates:
-/// - Using types from sibling modules (`Book` via `crate::book`)
-/// - Using types from submodules (`MembershipTier`)
+/// - Using types from sibling modules (`Money` via `crate::money`)
+/// - Using types from submodules (`TierPolicy`, `MembershipTier`)
 /// - Mixed field visibility
-#[derive(Debug)]
+/// - Trait objects: the tier is any `TierPolicy`, shared via `Arc`
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Member {
     // Private fields - controlled via methods
     id: u64,
-    borrowed_books: Vec<Book>,
+    history: Vec<LoanRecord>,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_impls::tier"))]
+    tier: Arc<dyn TierPolicy>,
+    #[cfg_attr(feature = "serde", serde(default))]
+    tier_history: Vec<TierChange>,
+    expires_on: Option<NaiveDate>,
+    fines_owed: Money,
+    status: MemberStatus,
+    #[cfg_attr(feature = "serde", serde(default))]
+    email: Option<String>,
+    #[cfg_attr(feature = "serde", serde(default))]
+    phone: Option<String>,
 
     // Public fields
     pub name: String,
-    pub tier: MembershipTier,
 }
 
 impl Member {
@@ -85,72 +214,301 @@ impl Member {
     /// use module_8::{Member, MembershipTier};
     /// let member = Member::new(1, "Alice", MembershipTier::Gold);
     /// assert_eq!(member.name, "Alice");
+    /// assert_eq!(member.tier().name(), "Gold");
     /// ```
-    pub fn new(id: u64, name: &str, tier: MembershipTier) -> Self {
+    ///
+    /// The ID and name aren't checked; use [`try_new`](Member::try_new) for
+    /// input that hasn't been validated yet.
+    pub fn new(id: u64, name: &str, tier: impl TierPolicy + 'static) -> Self {
         Member {
             id,
             name: String::from(name),
-            tier,
-            borrowed_books: Vec::new(),
+            tier: Arc::new(tier),
+            tier_history: Vec::new(),
+            history: Vec::new(),
+            expires_on: None,
+            fines_owed: Money::ZERO,
+            status: MemberStatus::Active,
+            email: None,
+            phone: None,
         }
     }
 
+    /// Creates a new member, or says why `id` or `name` can't be used (see
+    /// [`validation`](crate::validation)).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use module_8::{Member, MembershipTier};
+    /// let err = Member::try_new(0, "Alice", MembershipTier::Basic).unwrap_err();
+    /// assert_eq!(err.to_string(), "ID must not be 0");
+    /// ```
+    pub fn try_new(id: u64, name: &str, tier: impl TierPolicy + 'static) -> Result<Self, ValidationError> {
+        validate_id(id)?;
+        validate_member_name(name)?;
+        Ok(Member::new(id, name, tier))
+    }
+
     /// Returns the member's ID.
     pub fn id(&self) -> u64 {
         self.id
     }
 
-    /// Returns the number of books currently borrowed.
-    pub fn borrowed_count(&self) -> usize {
-        self.borrowed_books.len()
+    /// Returns the member's tier policy.
+    pub fn tier(&self) -> &dyn TierPolicy {
+        self.tier.as_ref()
+    }
+
+    /// Moves the member to a different tier without recording it; use
+    /// [`upgrade_tier`](Member::upgrade_tier) or
+    /// [`downgrade_tier`](Member::downgrade_tier) to keep the history.
+    pub fn set_tier(&mut self, tier: impl TierPolicy + 'static) {
+        self.tier = Arc::new(tier);
+    }
+
+    /// Moves the member to a tier with at least the current borrow limit,
+    /// recording the change as taking effect on `effective_on`.
+    ///
+    /// The member is on the new tier straight away; the date is what
+    /// [`tier_on`](Member::tier_on) goes by, so a change can be backdated
+    /// to when it was paid for.
+    ///
+    /// # Errors
+    ///
+    /// [`TierChangeError::SameTier`] for the tier the member is on,
+    /// [`TierChangeError::NotAnUpgrade`] if the new borrow limit is lower,
+    /// and [`TierChangeError::BeforeLastChange`] if `effective_on` is
+    /// earlier than the latest change.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use chrono::NaiveDate;
+    /// use module_8::{Member, MembershipTier};
+    ///
+    /// let day = |d| NaiveDate::from_ymd_opt(2024, 3, d).unwrap();
+    /// let mut alice = Member::new(1, "Alice", MembershipTier::Basic);
+    /// alice.upgrade_tier(MembershipTier::Gold, day(10)).unwrap();
+    ///
+    /// assert_eq!(alice.tier().name(), "Gold");
+    /// assert_eq!(alice.tier_on(day(9)).name(), "Basic");
+    /// assert_eq!(alice.tier_on(day(10)).name(), "Gold");
+    /// assert!(alice.upgrade_tier(MembershipTier::Silver, day(11)).is_err());
+    /// ```
+    pub fn upgrade_tier(
+        &mut self,
+        tier: impl TierPolicy + 'static,
+        effective_on: NaiveDate,
+    ) -> Result<(), TierChangeError> {
+        if tier.borrow_limit() < self.max_books() {
+            return Err(TierChangeError::NotAnUpgrade {
+                from: self.tier.name().to_string(),
+                to: tier.name().to_string(),
+            });
+        }
+        self.change_tier(Arc::new(tier), effective_on)
+    }
+
+    /// Moves the member to a tier with at most the current borrow limit,
+    /// recording the change as taking effect on `effective_on`, like
+    /// [`upgrade_tier`](Member::upgrade_tier).
+    ///
+    /// # Errors
+    ///
+    /// As for `upgrade_tier`, with [`TierChangeError::NotADowngrade`] if
+    /// the new borrow limit is higher.
+    ///
+    /// A member doesn't know what they have on loan, so this can't check
+    /// the new limit against it; for a registered member use
+    /// [`Library::downgrade_tier`](crate::Library::downgrade_tier), which
+    /// does.
+    pub fn downgrade_tier(
+        &mut self,
+        tier: impl TierPolicy + 'static,
+        effective_on: NaiveDate,
+    ) -> Result<(), TierChangeError> {
+        if tier.borrow_limit() > self.max_books() {
+            return Err(TierChangeError::NotADowngrade {
+                from: self.tier.name().to_string(),
+                to: tier.name().to_string(),
+            });
+        }
+        self.change_tier(Arc::new(tier), effective_on)
+    }
+
+    fn change_tier(&mut self, tier: Arc<dyn TierPolicy>, effective_on: NaiveDate) -> Result<(), TierChangeError> {
+        if tier.name() == self.tier.name() {
+            return Err(TierChangeError::SameTier(tier.name().to_string()));
+        }
+        if let Some(last) = self.tier_history.last() {
+            if effective_on < last.effective_on {
+                return Err(TierChangeError::BeforeLastChange { last: last.effective_on });
+            }
+        }
+        let from = std::mem::replace(&mut self.tier, Arc::clone(&tier));
+        self.tier_history.push(TierChange::new(from, tier, effective_on));
+        Ok(())
+    }
+
+    /// The member's tier changes, oldest first.
+    pub fn tier_history(&self) -> &[TierChange] {
+        &self.tier_history
+    }
+
+    /// The tier the member was on on `date`, e.g. to check a disputed fee
+    /// against the discount and fee cap that applied then. Before the
+    /// first recorded change, that's the tier the member started on.
+    pub fn tier_on(&self, date: NaiveDate) -> &dyn TierPolicy {
+        match self.tier_history.iter().rev().find(|change| change.effective_on <= date) {
+            Some(change) => change.to(),
+            None => self.tier_history.first().map_or(self.tier(), TierChange::from),
+        }
+    }
+
+    /// Sets a tier loaded from storage, which is shared rather than moved in.
+    pub(crate) fn restore_tier(&mut self, tier: Arc<dyn TierPolicy>) {
+        self.tier = tier;
+    }
+
+    /// Appends a change loaded from storage, without checking it.
+    pub(crate) fn restore_tier_change(&mut self, change: TierChange) {
+        self.tier_history.push(change);
     }
 
     /// Returns the maximum books this member can borrow based on their tier.
     pub fn max_books(&self) -> usize {
-        // Using a method from the submodule's type
+        // Dynamic dispatch through the TierPolicy trait object
         self.tier.borrow_limit()
     }
 
-    /// Attempts to borrow a book.
+    /// Gives the member a new ID, e.g. when merging libraries.
+    pub(crate) fn set_id(&mut self, id: u64) {
+        self.id = id;
+    }
+
+    /// Returns the member's past loans, oldest first.
+    pub fn history(&self) -> &[LoanRecord] {
+        &self.history
+    }
+
+    /// Appends a completed loan to the member's history.
+    pub fn record_history(&mut self, record: LoanRecord) {
+        self.history.push(record);
+    }
+
+    /// Returns the date the membership expires, if it has one.
+    pub fn expires_on(&self) -> Option<NaiveDate> {
+        self.expires_on
+    }
+
+    /// Sets (or clears, with `None`) the membership expiry date.
+    pub fn set_expires_on(&mut self, date: Option<NaiveDate>) {
+        self.expires_on = date;
+    }
+
+    /// Returns the member's email address, if on file.
+    pub fn email(&self) -> Option<&str> {
+        self.email.as_deref()
+    }
+
+    /// Sets (or clears, with `None`) the email address, which must pass
+    /// [`validate_email`]. Surrounding whitespace is dropped.
+    ///
+    /// # Examples
     ///
-    /// Returns `Ok(())` if successful, `Err` with a message if not.
-    pub fn borrow(&mut self, mut book: Book) -> Result<(), &'static str> {
-        if self.borrowed_books.len() >= self.max_books() {
-            return Err("Borrow limit reached");
+    /// ```
+    /// use module_8::{Member, MembershipTier};
+    /// let mut member = Member::new(1, "Alice", MembershipTier::Basic);
+    /// member.set_email(Some(" alice@example.com ")).unwrap();
+    /// assert_eq!(member.email(), Some("alice@example.com"));
+    /// assert!(member.set_email(Some("alice@")).is_err());
+    /// assert_eq!(member.email(), Some("alice@example.com"));
+    /// ```
+    pub fn set_email(&mut self, email: Option<&str>) -> Result<(), ValidationError> {
+        let email = email.map(str::trim);
+        if let Some(email) = email {
+            validate_email(email)?;
         }
+        self.email = email.map(String::from);
+        Ok(())
+    }
 
-        if !book.is_available() {
-            return Err("Book is not available");
-        }
+    /// Returns the member's phone number, if on file.
+    pub fn phone(&self) -> Option<&str> {
+        self.phone.as_deref()
+    }
 
-        book.borrow_book();
-        self.borrowed_books.push(book);
+    /// Sets (or clears, with `None`) the phone number, which must pass
+    /// [`validate_phone`]. Surrounding whitespace is dropped.
+    pub fn set_phone(&mut self, phone: Option<&str>) -> Result<(), ValidationError> {
+        let phone = phone.map(str::trim);
+        if let Some(phone) = phone {
+            validate_phone(phone)?;
+        }
+        self.phone = phone.map(String::from);
         Ok(())
     }
 
-    /// Returns a borrowed book.
+    /// Returns `true` if the membership has expired as of `today`.
     ///
-    /// Returns the book if found, or `None` if the member doesn't have it.
-    pub fn return_book(&mut self, book_id: u64) -> Option<Book> {
-        if let Some(pos) = self.borrowed_books.iter().position(|b| b.id() == book_id) {
-            let mut book = self.borrowed_books.remove(pos);
-            book.return_book();
-            Some(book)
-        } else {
-            None
-        }
+    /// A membership is valid through its expiry date and expired the day after.
+    pub fn is_expired(&self, today: NaiveDate) -> bool {
+        self.expires_on.is_some_and(|date| today > date)
+    }
+
+    /// Returns unpaid late fees.
+    pub fn outstanding_fines(&self) -> Money {
+        self.fines_owed
+    }
+
+    /// Adds a fee to the member's unpaid balance, saturating rather than
+    /// overflowing.
+    pub(crate) fn charge(&mut self, amount: Money) {
+        self.fines_owed = self.fines_owed.saturating_add(amount);
+    }
+
+    /// Pays off up to `amount` of fines, returning the balance left.
+    pub fn pay_fines(&mut self, amount: Money) -> Money {
+        self.fines_owed = self.fines_owed.saturating_sub(amount);
+        self.fines_owed
+    }
+
+    /// Returns `true` only while the member is [`MemberStatus::Active`].
+    pub fn is_active(&self) -> bool {
+        self.status == MemberStatus::Active
+    }
+
+    pub fn status(&self) -> &MemberStatus {
+        &self.status
+    }
+
+    pub(crate) fn set_status(&mut self, status: MemberStatus) {
+        self.status = status;
     }
 
-    /// Lists all borrowed books (read-only access).
-    pub fn borrowed_books(&self) -> &[Book] {
-        &self.borrowed_books
+    pub(crate) fn deactivate(&mut self) {
+        self.status = MemberStatus::Closed;
     }
 
-    /// Calculates the member's discount based on tier.
-    /// Uses `pub(super)` function from membership module.
+    /// Returns the member's fee discount based on tier.
     pub fn discount_percentage(&self) -> u8 {
-        // Access pub(super) function - works because we're in the parent module
-        membership::calculate_discount(&self.tier)
+        self.tier.discount_percentage()
+    }
+
+    /// Applies the tier discount to a fee, rounding in the member's favour.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use module_8::{Member, MembershipTier, Money};
+    ///
+    /// let gold = Member::new(1, "Alice", MembershipTier::Gold);
+    /// assert_eq!(gold.effective_fee(Money::from_cents(100)), Money::from_cents(80)); // 20% off
+    /// assert_eq!(gold.effective_fee(Money::from_cents(3)), Money::from_cents(2));
+    /// ```
+    pub fn effective_fee(&self, base_fee: Money) -> Money {
+        base_fee.percent(100 - self.discount_percentage().min(100))
     }
 }
 
@@ -158,10 +516,75 @@ impl Member {
 // MODULE-LEVEL FUNCTION
 // =============================================================================
 
-/// Creates a guest member with basic tier.
+/// Creates a guest member, on the [`GuestTier`].
 ///
 /// This is a module-level function (not a method) that demonstrates
 /// another way to construct types.
 pub fn create_guest(id: u64, name: &str) -> Member {
-    Member::new(id, name, MembershipTier::Basic)
+    Member::new(id, name, GuestTier)
+}
+
+// =============================================================================
+// TESTS
+// =============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Book, Genre};
+
+    fn date(day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(2024, 3, day).unwrap()
+    }
+
+    #[test]
+    fn test_new_accepts_what_try_new_rejects() {
+        let guest = Member::new(7, "Alice 2nd", MembershipTier::Basic);
+        assert_eq!(guest.name, "Alice 2nd");
+        assert_eq!(
+            Member::try_new(7, "Alice 2nd", MembershipTier::Basic).unwrap_err(),
+            ValidationError::InvalidCharacter { field: "member name", character: '2' }
+        );
+        assert_eq!(Book::new(0, "", Genre::Fiction).id(), 0);
+        assert_eq!(Book::try_new(0, "Dune", Genre::SciFi).unwrap_err(), ValidationError::ZeroId);
+        assert!(Book::builder(1, " ").try_build().is_err());
+    }
+
+    #[test]
+    fn test_tier_changes_are_checked_and_recorded() {
+        let mut alice = Member::new(1, "Alice", MembershipTier::Silver);
+        assert_eq!(
+            alice.upgrade_tier(MembershipTier::Basic, date(1)),
+            Err(TierChangeError::NotAnUpgrade { from: String::from("Silver"), to: String::from("Basic") })
+        );
+        assert_eq!(
+            alice.downgrade_tier(MembershipTier::Gold, date(1)),
+            Err(TierChangeError::NotADowngrade { from: String::from("Silver"), to: String::from("Gold") })
+        );
+        assert_eq!(alice.upgrade_tier(MembershipTier::Silver, date(1)), Err(TierChangeError::SameTier(String::from("Silver"))));
+
+        alice.upgrade_tier(MembershipTier::Platinum, date(10)).unwrap();
+        assert_eq!(
+            alice.downgrade_tier(MembershipTier::Gold, date(9)),
+            Err(TierChangeError::BeforeLastChange { last: date(10) })
+        );
+        alice.downgrade_tier(MembershipTier::Silver, date(20)).unwrap();
+
+        let history: Vec<(&str, &str, NaiveDate)> =
+            alice.tier_history().iter().map(|c| (c.from().name(), c.to().name(), c.effective_on)).collect();
+        assert_eq!(history, [("Silver", "Platinum", date(10)), ("Platinum", "Silver", date(20))]);
+    }
+
+    #[test]
+    fn test_tier_on_a_given_day() {
+        let mut bob = Member::new(2, "Bob", MembershipTier::Basic);
+        assert_eq!(bob.tier_on(date(1)).name(), "Basic");
+        bob.upgrade_tier(MembershipTier::Gold, date(5)).unwrap();
+        bob.upgrade_tier(MembershipTier::Platinum, date(5)).unwrap();
+        bob.downgrade_tier(MembershipTier::Silver, date(12)).unwrap();
+
+        let tiers: Vec<&str> = [1, 5, 11, 12, 30].iter().map(|&d| bob.tier_on(date(d)).name()).collect();
+        assert_eq!(tiers, ["Basic", "Platinum", "Platinum", "Silver", "Silver"]);
+        assert_eq!(bob.tier().name(), "Silver");
+    }
 }
@@ -3,6 +3,83 @@
 //! This file is loaded because `member/mod.rs` contains `mod membership;`.
 //! It's a submodule of `member`, so its full path is `crate::member::membership`.
 
+use std::fmt;
+use std::str::FromStr;
+use std::sync::Arc;
+
+use crate::money::Money;
+
+// =============================================================================
+// TIER POLICY TRAIT
+// =============================================================================
+
+/// The privileges attached to a membership tier.
+///
+/// `Member` holds its tier as a `TierPolicy` trait object, so institutions can
+/// define their own tiers in downstream crates. [`MembershipTier`] is the
+/// built-in implementation.
+///
+/// The `Send + Sync` bounds let members (and therefore a `Library`) be shared
+/// between threads; `Debug` keeps `Member` printable.
+///
+/// # Examples
+///
+/// ```
+/// use module_8::{Member, TierPolicy};
+///
+/// #[derive(Debug)]
+/// struct Faculty;
+///
+/// impl TierPolicy for Faculty {
+///     fn name(&self) -> &str { "Faculty" }
+///     fn borrow_limit(&self) -> usize { 50 }
+///     fn loan_days(&self) -> u32 { 120 }
+///     fn discount_percentage(&self) -> u8 { 100 }
+/// }
+///
+/// let prof = Member::new(1, "Dr. Ferris", Faculty);
+/// assert_eq!(prof.max_books(), 50);
+/// assert_eq!(prof.tier().name(), "Faculty");
+/// ```
+pub trait TierPolicy: fmt::Debug + Send + Sync {
+    /// Display name of the tier.
+    fn name(&self) -> &str;
+
+    /// Maximum number of books a member of this tier can borrow at once.
+    fn borrow_limit(&self) -> usize;
+
+    /// Loan period in days.
+    fn loan_days(&self) -> u32;
+
+    /// Discount on fees, as a percentage (0-100).
+    fn discount_percentage(&self) -> u8;
+
+    /// Priority in tier-weighted hold queues; higher is served first.
+    ///
+    /// Defaults to 0, so custom tiers queue behind every built-in tier
+    /// unless they say otherwise.
+    fn hold_priority(&self) -> u8 {
+        0
+    }
+
+    /// Most one overdue loan can cost a member of this tier in late fees,
+    /// after their discount; `None` for no cap.
+    ///
+    /// Defaults to `None`. A library can override any tier's cap with
+    /// [`LibraryConfig::fee_caps`](crate::LibraryConfig::fee_caps).
+    fn fee_cap(&self) -> Option<Money> {
+        None
+    }
+
+    /// Whether members of this tier may place holds.
+    ///
+    /// Defaults to `true`; [`Library::place_hold`](crate::Library::place_hold)
+    /// refuses holds for a tier that says otherwise.
+    fn can_place_holds(&self) -> bool {
+        true
+    }
+}
+
 // =============================================================================
 // MEMBERSHIP TIER ENUM
 // =============================================================================
@@ -12,6 +89,7 @@
 /// This enum is re-exported by the parent module (`member/mod.rs`),
 /// so users can access it as `module_8::MembershipTier` or `module_8::member::MembershipTier`.
 #[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum MembershipTier {
     /// Basic membership - limited privileges
     Basic,
@@ -19,15 +97,38 @@ pub enum MembershipTier {
     Silver,
     /// Gold membership - premium privileges
     Gold,
+    /// Platinum membership - highest privileges
+    Platinum,
 }
 
 impl MembershipTier {
+    /// Every tier, from lowest to highest, e.g. for building a pick list.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use module_8::MembershipTier;
+    ///
+    /// let names: Vec<String> = MembershipTier::all().map(|t| t.to_string()).collect();
+    /// assert_eq!(names, ["Basic", "Silver", "Gold", "Platinum"]);
+    /// ```
+    pub fn all() -> impl Iterator<Item = MembershipTier> {
+        [
+            MembershipTier::Basic,
+            MembershipTier::Silver,
+            MembershipTier::Gold,
+            MembershipTier::Platinum,
+        ]
+        .into_iter()
+    }
+
     /// Returns the maximum number of books this tier can borrow.
     pub fn borrow_limit(&self) -> usize {
         match self {
             MembershipTier::Basic => 2,
             MembershipTier::Silver => 5,
             MembershipTier::Gold => 10,
+            MembershipTier::Platinum => 20,
         }
     }
 
@@ -37,8 +138,164 @@ impl MembershipTier {
             MembershipTier::Basic => 14,
             MembershipTier::Silver => 21,
             MembershipTier::Gold => 30,
+            MembershipTier::Platinum => 45,
+        }
+    }
+
+    /// Returns the most one overdue loan can cost in late fees; higher
+    /// tiers are capped lower.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use module_8::MembershipTier;
+    ///
+    /// assert_eq!(MembershipTier::Basic.fee_cap().to_string(), "$15.00");
+    /// assert!(MembershipTier::Gold.fee_cap() < MembershipTier::Basic.fee_cap());
+    /// ```
+    pub fn fee_cap(&self) -> Money {
+        match self {
+            MembershipTier::Basic => Money::from_cents(1500),
+            MembershipTier::Silver => Money::from_cents(1200),
+            MembershipTier::Gold => Money::from_cents(800),
+            MembershipTier::Platinum => Money::from_cents(500),
+        }
+    }
+}
+
+/// The built-in tiers delegate to their inherent methods.
+impl TierPolicy for MembershipTier {
+    fn name(&self) -> &str {
+        match self {
+            MembershipTier::Basic => "Basic",
+            MembershipTier::Silver => "Silver",
+            MembershipTier::Gold => "Gold",
+            MembershipTier::Platinum => "Platinum",
         }
     }
+
+    fn borrow_limit(&self) -> usize {
+        MembershipTier::borrow_limit(self)
+    }
+
+    fn loan_days(&self) -> u32 {
+        MembershipTier::loan_days(self)
+    }
+
+    fn discount_percentage(&self) -> u8 {
+        // Private-module helper, reachable because we're in the same module
+        calculate_discount(self)
+    }
+
+    fn hold_priority(&self) -> u8 {
+        tier_rank(self)
+    }
+
+    fn fee_cap(&self) -> Option<Money> {
+        Some(MembershipTier::fee_cap(self))
+    }
+}
+
+// =============================================================================
+// GUEST TIER
+// =============================================================================
+
+/// Visitors without a full membership: one book at a time on a week's
+/// loan, and no holds. Fees are charged and capped as for
+/// [`MembershipTier::Basic`].
+///
+/// # Examples
+///
+/// ```
+/// use module_8::{GuestTier, Member, TierPolicy};
+///
+/// let guest = Member::new(1, "Visitor", GuestTier);
+/// assert_eq!((guest.max_books(), guest.tier().loan_days()), (1, 7));
+/// assert!(!guest.tier().can_place_holds());
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct GuestTier;
+
+impl TierPolicy for GuestTier {
+    fn name(&self) -> &str {
+        "Guest"
+    }
+
+    fn borrow_limit(&self) -> usize {
+        1
+    }
+
+    fn loan_days(&self) -> u32 {
+        7
+    }
+
+    fn discount_percentage(&self) -> u8 {
+        0
+    }
+
+    fn fee_cap(&self) -> Option<Money> {
+        Some(MembershipTier::Basic.fee_cap())
+    }
+
+    fn can_place_holds(&self) -> bool {
+        false
+    }
+}
+
+/// Parses the name of a built-in tier, a [`MembershipTier`] or the
+/// [`GuestTier`], ignoring case and surrounding whitespace.
+pub(crate) fn parse_tier(name: &str) -> Result<Arc<dyn TierPolicy>, ParseTierError> {
+    if name.trim().eq_ignore_ascii_case(GuestTier.name()) {
+        return Ok(Arc::new(GuestTier));
+    }
+    Ok(Arc::new(name.parse::<MembershipTier>()?))
+}
+
+/// Whether `tier` is one of the built-in tiers, which can be saved by name.
+pub(crate) fn is_built_in(tier: &dyn TierPolicy) -> bool {
+    tier.name() == GuestTier.name() || MembershipTier::all().any(|t| t.name() == tier.name())
+}
+
+/// Error returned when parsing a [`MembershipTier`] from an unknown name.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ParseTierError {
+    /// The name that didn't match, trimmed.
+    pub input: String,
+}
+
+impl fmt::Display for ParseTierError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let names: Vec<String> = MembershipTier::all().map(|t| t.to_string()).collect();
+        write!(f, "unknown membership tier {:?} (expected one of: {})", self.input, names.join(", "))
+    }
+}
+
+impl std::error::Error for ParseTierError {}
+
+impl FromStr for MembershipTier {
+    type Err = ParseTierError;
+
+    /// Parses a tier name, ignoring case and surrounding whitespace.
+    ///
+    /// ```
+    /// use module_8::MembershipTier;
+    ///
+    /// assert_eq!(" gold ".parse::<MembershipTier>(), Ok(MembershipTier::Gold));
+    /// let err = "Diamond".parse::<MembershipTier>().unwrap_err();
+    /// assert_eq!(err.to_string(), "unknown membership tier \"Diamond\" (expected one of: Basic, Silver, Gold, Platinum)");
+    /// ```
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let name = s.trim();
+        MembershipTier::all()
+            .find(|tier| tier.name().eq_ignore_ascii_case(name))
+            .ok_or_else(|| ParseTierError { input: String::from(name) })
+    }
+}
+
+impl fmt::Display for MembershipTier {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
 }
 
 // =============================================================================
@@ -48,8 +305,8 @@ impl MembershipTier {
 /// Calculates discount percentage for a membership tier.
 ///
 /// This function is `pub(super)` - visible to the parent module (`member`)
-/// but NOT to modules outside of `member`. This allows `Member` to use it
-/// internally while keeping it hidden from the public API.
+/// but NOT to modules outside of `member`. Outside callers reach it through
+/// `TierPolicy::discount_percentage` instead.
 ///
 /// # Visibility Levels:
 /// - `pub`: visible everywhere the parent module is visible
@@ -62,17 +319,18 @@ pub(super) fn calculate_discount(tier: &MembershipTier) -> u8 {
         MembershipTier::Basic => 0,
         MembershipTier::Silver => 10,
         MembershipTier::Gold => 20,
+        MembershipTier::Platinum => 30,
     }
 }
 
 /// Internal function - completely private to this module.
 /// Not even the parent module (`member`) can access this.
-#[allow(dead_code)]
 fn tier_rank(tier: &MembershipTier) -> u8 {
     match tier {
         MembershipTier::Basic => 1,
         MembershipTier::Silver => 2,
         MembershipTier::Gold => 3,
+        MembershipTier::Platinum => 4,
     }
 }
 
@@ -82,7 +340,7 @@ fn tier_rank(tier: &MembershipTier) -> u8 {
 /// that multiple modules need but shouldn't be part of the public API.
 #[allow(dead_code)]
 pub(crate) fn is_premium_tier(tier: &MembershipTier) -> bool {
-    matches!(tier, MembershipTier::Gold)
+    matches!(tier, MembershipTier::Gold | MembershipTier::Platinum)
 }
 
 // =============================================================================
@@ -98,6 +356,43 @@ mod tests {
         assert_eq!(MembershipTier::Basic.borrow_limit(), 2);
         assert_eq!(MembershipTier::Silver.borrow_limit(), 5);
         assert_eq!(MembershipTier::Gold.borrow_limit(), 10);
+        assert_eq!(MembershipTier::Platinum.borrow_limit(), 20);
+    }
+
+    #[test]
+    fn test_tier_policy_matches_inherent_methods() {
+        for tier in [
+            MembershipTier::Basic,
+            MembershipTier::Silver,
+            MembershipTier::Gold,
+            MembershipTier::Platinum,
+        ] {
+            let policy: &dyn TierPolicy = &tier;
+            assert_eq!(policy.borrow_limit(), tier.borrow_limit());
+            assert_eq!(policy.loan_days(), tier.loan_days());
+            assert_eq!(policy.discount_percentage(), calculate_discount(&tier));
+            assert_eq!(policy.fee_cap(), Some(tier.fee_cap()));
+        }
+        assert_eq!(MembershipTier::Platinum.name(), "Platinum");
+    }
+
+    #[test]
+    fn test_tier_parse_and_display_round_trip() {
+        for tier in MembershipTier::all() {
+            assert_eq!(tier.to_string().parse::<MembershipTier>(), Ok(tier));
+            assert_eq!(tier.to_string().to_uppercase().parse::<MembershipTier>(), Ok(tier));
+        }
+        assert_eq!(MembershipTier::all().count(), 4);
+        assert_eq!("".parse::<MembershipTier>(), Err(ParseTierError { input: String::new() }));
+    }
+
+    #[test]
+    fn test_guest_tier_is_built_in() {
+        assert_eq!(parse_tier(" guest ").unwrap().name(), "Guest");
+        assert_eq!(parse_tier("Gold").unwrap().borrow_limit(), 10);
+        assert!(parse_tier("Diamond").is_err());
+        assert!(is_built_in(&GuestTier) && is_built_in(&MembershipTier::Silver));
+        assert!(MembershipTier::all().all(|tier| tier.can_place_holds()));
     }
 
     #[test]
@@ -0,0 +1,331 @@
+//! Merge module - folding one library into another.
+//!
+//! [`Library::merge`] moves every book, member, loan and hold of another
+//! library into this one. When both libraries use the same book or member
+//! ID, the [`MergePolicy`] decides what happens, separately for books and
+//! members. Accrued late fees follow their loans. The other library's
+//! configuration, policies, notifiers and audit log are not carried over.
+
+use std::collections::HashMap;
+
+use crate::error::LibraryError;
+use crate::index::BookLocation;
+use crate::Library;
+
+/// What to do with an incoming record whose ID is already taken.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum OnConflict {
+    /// Give the incoming record the next unused ID.
+    Renumber,
+    /// Keep the existing record and drop the incoming one.
+    Skip,
+    /// Abort the merge without changing anything.
+    #[default]
+    Fail,
+}
+
+/// Conflict handling for a merge; the default fails on any duplicate ID.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct MergePolicy {
+    pub books: OnConflict,
+    pub members: OnConflict,
+}
+
+impl MergePolicy {
+    /// The same handling for books and members.
+    pub fn all(on_conflict: OnConflict) -> Self {
+        MergePolicy {
+            books: on_conflict,
+            members: on_conflict,
+        }
+    }
+}
+
+/// What a merge did.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct MergeReport {
+    pub books_added: usize,
+    pub members_added: usize,
+    pub loans_added: usize,
+    pub holds_added: usize,
+    /// `(old_id, new_id)` for each renumbered book.
+    pub renumbered_books: Vec<(u64, u64)>,
+    /// `(old_id, new_id)` for each renumbered member.
+    pub renumbered_members: Vec<(u64, u64)>,
+    /// IDs of incoming books dropped as duplicates.
+    pub skipped_books: Vec<u64>,
+    /// IDs of incoming members dropped as duplicates.
+    pub skipped_members: Vec<u64>,
+}
+
+/// Decides the new ID (or `None` to skip) for each incoming ID, in order.
+fn assign_ids(
+    incoming: &[u64],
+    taken: impl Fn(u64) -> bool,
+    mut next_free: u64,
+    on_conflict: OnConflict,
+    duplicate: fn(u64) -> LibraryError,
+    renumbered: &mut Vec<(u64, u64)>,
+    skipped: &mut Vec<u64>,
+) -> Result<HashMap<u64, Option<u64>>, LibraryError> {
+    let mut ids = HashMap::new();
+    for &id in incoming {
+        let new_id = if !taken(id) {
+            Some(id)
+        } else {
+            match on_conflict {
+                OnConflict::Fail => return Err(duplicate(id)),
+                OnConflict::Skip => {
+                    skipped.push(id);
+                    None
+                }
+                OnConflict::Renumber => {
+                    renumbered.push((id, next_free));
+                    next_free += 1;
+                    Some(next_free - 1)
+                }
+            }
+        };
+        ids.insert(id, new_id);
+    }
+    Ok(ids)
+}
+
+impl Library {
+    /// Moves all of `other`'s books, members, loans and holds into this
+    /// library, resolving duplicate IDs according to `policy`.
+    ///
+    /// Renumbered IDs start after the highest ID used by either library.
+    /// Loans and holds follow their book and member to the new IDs, and are
+    /// dropped if either was skipped. Books on loan to a skipped member come
+    /// in on the shelf. With [`OnConflict::Fail`], nothing changes if any
+    /// ID is duplicated; likewise, nothing changes and the merge fails with
+    /// [`LibraryError::CapacityReached`] if the books and members it would
+    /// bring in don't fit within this library's capacity limits.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use module_8::merge::{MergePolicy, OnConflict};
+    /// use module_8::{Book, Genre, Library};
+    ///
+    /// let mut main = Library::new();
+    /// main.add_book(Book::new(1, "Dune", Genre::SciFi)).unwrap();
+    /// let mut branch = Library::new();
+    /// branch.add_book(Book::new(1, "Emma", Genre::Fiction)).unwrap();
+    ///
+    /// let report = main.merge(branch, MergePolicy::all(OnConflict::Renumber)).unwrap();
+    /// assert_eq!(report.renumbered_books, [(1, 2)]);
+    /// assert_eq!(main.find_book(2).unwrap().title, "Emma");
+    /// ```
+    pub fn merge(&mut self, other: Library, policy: MergePolicy) -> Result<MergeReport, LibraryError> {
+        let Library {
+            mut books,
+            mut members,
+            member_order,
+            loans,
+            holds,
+            index,
+            fee_accruals,
+            fine_ledger,
+            reviews,
+            ..
+        } = other;
+        let mut report = MergeReport::default();
+
+        // Work out every new ID before touching `self`, so Fail is atomic
+        let next_member = members
+            .keys()
+            .map(|id| id + 1)
+            .fold(self.ids.next_member(), u64::max);
+        let member_ids = assign_ids(
+            &member_order,
+            |id| self.members.contains_key(&id),
+            next_member,
+            policy.members,
+            LibraryError::DuplicateMember,
+            &mut report.renumbered_members,
+            &mut report.skipped_members,
+        )?;
+
+        let shelf_ids: Vec<u64> = index.shelf_ids().collect();
+        let lent_ids = loans
+            .iter()
+            .map(|loan| loan.book_id)
+            .filter(|&id| index.location(id) == Some(BookLocation::OnLoan));
+        let incoming_books: Vec<u64> = shelf_ids.iter().copied().chain(lent_ids).collect();
+        let next_book = incoming_books
+            .iter()
+            .map(|id| id + 1)
+            .fold(self.ids.next_book(), u64::max);
+        let book_ids = assign_ids(
+            &incoming_books,
+            |id| self.index.contains(id),
+            next_book,
+            policy.books,
+            LibraryError::DuplicateBook,
+            &mut report.renumbered_books,
+            &mut report.skipped_books,
+        )?;
+        let books_added = book_ids.values().flatten().count();
+        let members_added = member_ids.values().flatten().count();
+        self.check_capacity(books_added, members_added)?;
+
+        // Shelf books in the other library's catalog order, then the books
+        // out on loan; those go out again below if their borrower comes too
+        for old_id in incoming_books {
+            let mut book = books.remove(&old_id).expect("catalogued in the other library");
+            if let Some(new_id) = book_ids[&old_id] {
+                book.set_id(new_id);
+                book.return_book();
+                self.insert_book(book);
+                report.books_added += 1;
+            }
+        }
+
+        for old_id in &member_order {
+            let mut member = members.remove(old_id).expect("member_order matches members");
+            let Some(new_id) = member_ids[old_id] else { continue };
+            member.set_id(new_id);
+            self.insert_member(member);
+            report.members_added += 1;
+        }
+
+        for mut loan in loans {
+            if let (Some(Some(book_id)), Some(Some(member_id))) =
+                (book_ids.get(&loan.book_id), member_ids.get(&loan.member_id))
+            {
+                loan.book_id = *book_id;
+                loan.member_id = *member_id;
+                self.open_loan(self.loans.len(), loan);
+                report.loans_added += 1;
+            }
+        }
+        // Accruals too, so returning a merged loan doesn't charge them twice
+        for mut accrual in fee_accruals {
+            if let (Some(Some(book_id)), Some(Some(member_id))) =
+                (book_ids.get(&accrual.book_id), member_ids.get(&accrual.member_id))
+            {
+                accrual.book_id = *book_id;
+                accrual.member_id = *member_id;
+                self.fee_accruals.push(accrual);
+            }
+        }
+        // Fine entries of members that were merged in, under their new IDs
+        for mut entry in fine_ledger {
+            if let Some(Some(member_id)) = member_ids.get(&entry.member_id) {
+                entry.member_id = *member_id;
+                self.fine_ledger.push(entry);
+            }
+        }
+        // Reviews of books and by members that were merged in; the books
+        // brought their rating totals with them
+        for mut review in reviews {
+            if let (Some(Some(book_id)), Some(Some(member_id))) =
+                (book_ids.get(&review.book_id), member_ids.get(&review.member_id))
+            {
+                review.book_id = *book_id;
+                review.member_id = *member_id;
+                self.reviews.push(review);
+            }
+        }
+        for mut hold in holds {
+            if let (Some(Some(book_id)), Some(Some(member_id))) =
+                (book_ids.get(&hold.book_id), member_ids.get(&hold.member_id))
+            {
+                hold.book_id = *book_id;
+                hold.member_id = *member_id;
+                self.holds.push(hold);
+                report.holds_added += 1;
+            }
+        }
+
+        Ok(report)
+    }
+}
+
+// =============================================================================
+// TESTS
+// =============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Book, Genre, Member, MembershipTier};
+    use chrono::NaiveDate;
+
+    fn date(day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(2024, 3, day).unwrap()
+    }
+
+    /// Main: book 1, member 10. Branch: books 1 and 2, member 10 with book 2 on loan.
+    fn libraries() -> (Library, Library) {
+        let mut main = Library::new();
+        main.add_book(Book::new(1, "Dune", Genre::SciFi)).unwrap();
+        main.register_member(Member::new(10, "Alice", MembershipTier::Basic)).unwrap();
+
+        let mut branch = Library::new();
+        branch.add_book(Book::new(1, "Emma", Genre::Fiction)).unwrap();
+        branch.add_book(Book::new(2, "Ulysses", Genre::Fiction)).unwrap();
+        branch.register_member(Member::new(10, "Zed", MembershipTier::Basic)).unwrap();
+        branch.checkout(10, 2, date(1)).unwrap();
+        branch.place_hold(10, 1, date(2)).unwrap();
+        (main, branch)
+    }
+
+    #[test]
+    fn test_merge_renumbers_and_follows_loans() {
+        let (mut main, branch) = libraries();
+        let report = main.merge(branch, MergePolicy::all(OnConflict::Renumber)).unwrap();
+
+        assert_eq!(report.renumbered_members, [(10, 11)]);
+        assert_eq!(report.renumbered_books, [(1, 3)]);
+        assert_eq!((report.books_added, report.members_added), (2, 1));
+        assert_eq!((report.loans_added, report.holds_added), (1, 1));
+
+        assert_eq!(main.find_member(11).unwrap().name, "Zed");
+        assert_eq!(main.find_book(3).unwrap().title, "Emma");
+        assert_eq!(main.loans()[0].member_id, 11);
+        assert_eq!((main.holds()[0].member_id, main.holds()[0].book_id), (11, 3));
+        assert_eq!(main.return_book(11, 2, date(3)), Ok(crate::Money::ZERO));
+        assert_eq!(main.check_invariants(), Ok(()));
+    }
+
+    #[test]
+    fn test_merge_skip_keeps_existing() {
+        let (mut main, branch) = libraries();
+        let report = main.merge(branch, MergePolicy::all(OnConflict::Skip)).unwrap();
+
+        assert_eq!(report.skipped_members, [10]);
+        assert_eq!(report.skipped_books, [1]);
+        assert_eq!(main.find_member(10).unwrap().name, "Alice");
+        assert_eq!(main.find_book(1).unwrap().title, "Dune");
+        // Zed was skipped, so the book lent to him comes in on the shelf
+        assert!(main.find_book(2).unwrap().is_available());
+        assert!(main.loans().is_empty() && main.holds().is_empty());
+        assert_eq!(main.check_invariants(), Ok(()));
+    }
+
+    #[test]
+    fn test_merge_fail_changes_nothing() {
+        let (mut main, branch) = libraries();
+        let policy = MergePolicy { books: OnConflict::Renumber, members: OnConflict::Fail };
+        assert_eq!(main.merge(branch, policy), Err(LibraryError::DuplicateMember(10)));
+
+        let (mut main, branch) = libraries();
+        let policy = MergePolicy { books: OnConflict::Fail, members: OnConflict::Renumber };
+        assert_eq!(main.merge(branch, policy), Err(LibraryError::DuplicateBook(1)));
+        assert_eq!((main.book_count(), main.member_count()), (1, 1));
+    }
+
+    #[test]
+    fn test_merge_respects_capacity() {
+        let (main, branch) = libraries();
+        let mut small = Library::with_config(crate::LibraryConfig { max_books: Some(2), ..main.config().clone() });
+        small.merge(main, MergePolicy::all(OnConflict::Fail)).unwrap();
+
+        let full = Err(LibraryError::CapacityReached { capacity: crate::Capacity::Books, limit: 2 });
+        assert_eq!(small.merge(branch, MergePolicy::all(OnConflict::Renumber)), full);
+        assert_eq!((small.book_count(), small.member_count()), (1, 1));
+    }
+}
@@ -0,0 +1,186 @@
+//! Metrics module - counters for monitoring a service built on the library.
+//!
+//! Every `Library` keeps a small registry of operational metrics: how many
+//! checkouts and returns it has handled and how long title searches take.
+//! [`Library::metrics_text`] renders them, with the current number of
+//! overdue loans, in the Prometheus text exposition format, ready to be
+//! served from a `/metrics` endpoint:
+//!
+//! ```
+//! use chrono::NaiveDate;
+//! use module_8::{Book, Genre, Library, Member, MembershipTier};
+//!
+//! let mut lib = Library::new();
+//! lib.add_book(Book::new(1, "Dune", Genre::SciFi)).unwrap();
+//! lib.register_member(Member::new(10, "Alice", MembershipTier::Basic)).unwrap();
+//! lib.checkout(10, 1, NaiveDate::from_ymd_opt(2024, 3, 1).unwrap()).unwrap();
+//! lib.search_titles("dune");
+//!
+//! let text = lib.metrics_text(NaiveDate::from_ymd_opt(2024, 4, 1).unwrap());
+//! assert!(text.contains("library_checkouts_total 1\n"));
+//! assert!(text.contains("library_overdue_current 1\n"));
+//! assert!(text.contains("library_search_latency_seconds_count 1\n"));
+//! ```
+//!
+//! The counters are atomics, so searches (which only borrow the library)
+//! can record their latency, and a `SharedLibrary` can be scraped from any
+//! thread. They count since the library was created; loading, merging or
+//! restoring a snapshot doesn't change them.
+
+use std::fmt::Write;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::Duration;
+
+use chrono::NaiveDate;
+
+use crate::Library;
+
+/// Upper bounds, in seconds, of the search latency histogram's buckets.
+pub const SEARCH_LATENCY_BUCKETS: [f64; 8] = [0.000_01, 0.000_05, 0.000_1, 0.000_5, 0.001, 0.005, 0.01, 0.05];
+
+// =============================================================================
+// REGISTRY
+// =============================================================================
+
+/// A Prometheus-style histogram over [`SEARCH_LATENCY_BUCKETS`].
+#[derive(Debug, Default)]
+struct Histogram {
+    /// Observations per bucket, not yet cumulative; the last slot is `+Inf`.
+    buckets: [AtomicU64; SEARCH_LATENCY_BUCKETS.len() + 1],
+    sum_nanos: AtomicU64,
+    count: AtomicU64,
+}
+
+impl Histogram {
+    fn observe(&self, elapsed: Duration) {
+        let seconds = elapsed.as_secs_f64();
+        let bucket = SEARCH_LATENCY_BUCKETS
+            .iter()
+            .position(|&bound| seconds <= bound)
+            .unwrap_or(SEARCH_LATENCY_BUCKETS.len());
+        self.buckets[bucket].fetch_add(1, Ordering::Relaxed);
+        let nanos = u64::try_from(elapsed.as_nanos()).unwrap_or(u64::MAX);
+        self.sum_nanos.fetch_add(nanos, Ordering::Relaxed);
+        self.count.fetch_add(1, Ordering::Relaxed);
+    }
+}
+
+/// The library's metrics registry.
+#[derive(Debug, Default)]
+pub(crate) struct Metrics {
+    checkouts: AtomicU64,
+    returns: AtomicU64,
+    search_latency: Histogram,
+}
+
+impl Metrics {
+    pub(crate) fn record_checkout(&self) {
+        self.checkouts.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub(crate) fn record_return(&self) {
+        self.returns.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub(crate) fn record_search(&self, elapsed: Duration) {
+        self.search_latency.observe(elapsed);
+    }
+}
+
+// =============================================================================
+// LIBRARY INTEGRATION
+// =============================================================================
+
+impl Library {
+    /// The library's metrics in the Prometheus text exposition format, with
+    /// loans past their due date counted as of `today`:
+    ///
+    /// - `library_checkouts_total` and `library_returns_total` (counters)
+    /// - `library_overdue_current` (gauge)
+    /// - `library_search_latency_seconds` (histogram of
+    ///   [`search_titles`](Library::search_titles),
+    ///   [`search_fuzzy`](Library::search_fuzzy) and
+    ///   [`find_books_glob`](Library::find_books_glob) calls)
+    pub fn metrics_text(&self, today: NaiveDate) -> String {
+        let metrics = &self.metrics;
+        let overdue = self.loans.iter().filter(|l| l.days_overdue(today) > 0).count();
+        let mut out = String::new();
+        let mut single = |name: &str, kind: &str, help: &str, value: u64| {
+            let _ = write!(out, "# HELP {0} {1}\n# TYPE {0} {2}\n{0} {3}\n", name, help, kind, value);
+        };
+        single("library_checkouts_total", "counter", "Books checked out.", metrics.checkouts.load(Ordering::Relaxed));
+        single("library_returns_total", "counter", "Books returned.", metrics.returns.load(Ordering::Relaxed));
+        single("library_overdue_current", "gauge", "Open loans past their due date.", overdue as u64);
+
+        let histogram = &metrics.search_latency;
+        let name = "library_search_latency_seconds";
+        let _ = write!(out, "# HELP {0} Time taken by title searches.\n# TYPE {0} histogram\n", name);
+        let mut cumulative = 0;
+        let bounds = SEARCH_LATENCY_BUCKETS.iter().map(|bound| bound.to_string()).chain(["+Inf".to_string()]);
+        for (bucket, bound) in histogram.buckets.iter().zip(bounds) {
+            cumulative += bucket.load(Ordering::Relaxed);
+            let _ = writeln!(out, "{}_bucket{{le=\"{}\"}} {}", name, bound, cumulative);
+        }
+        let sum = histogram.sum_nanos.load(Ordering::Relaxed) as f64 / 1e9;
+        let _ = writeln!(out, "{}_sum {}", name, sum);
+        let _ = writeln!(out, "{}_count {}", name, histogram.count.load(Ordering::Relaxed));
+        out
+    }
+}
+
+// =============================================================================
+// TESTS
+// =============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Book, Format, Genre, Member, MembershipTier};
+
+    fn date(day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(2024, 3, day).unwrap()
+    }
+
+    fn value(text: &str, series: &str) -> String {
+        let line = text.lines().find(|l| l.starts_with(series) && l[series.len()..].starts_with(' '));
+        line.expect("series is exported")[series.len() + 1..].to_string()
+    }
+
+    #[test]
+    fn test_counts_only_successful_operations() {
+        let mut lib = Library::new();
+        lib.add_book(Book::new(1, "Dune", Genre::SciFi)).unwrap();
+        let mut ebook = Book::new(2, "Emma", Genre::Fiction);
+        ebook.format = Format::Digital;
+        lib.add_book(ebook).unwrap();
+        lib.register_member(Member::new(10, "Alice", MembershipTier::Basic)).unwrap();
+        lib.checkout(10, 1, date(1)).unwrap();
+        lib.checkout(10, 2, date(1)).unwrap();
+        assert!(lib.checkout(10, 1, date(1)).is_err());
+        lib.return_book(10, 2, date(2)).unwrap();
+        assert!(lib.return_book(10, 2, date(2)).is_err());
+
+        let text = lib.metrics_text(date(20));
+        assert_eq!(value(&text, "library_checkouts_total"), "2");
+        assert_eq!(value(&text, "library_returns_total"), "1");
+        assert_eq!(value(&text, "library_overdue_current"), "1");
+        assert!(text.contains("# TYPE library_returns_total counter\n"));
+        assert_eq!(value(&lib.metrics_text(date(2)), "library_overdue_current"), "0");
+    }
+
+    #[test]
+    fn test_search_latency_histogram_is_cumulative() {
+        let lib = Library::new();
+        lib.search_titles("dune");
+        lib.search_fuzzy("dune", 5);
+        lib.metrics.record_search(Duration::from_secs(1));
+
+        let text = lib.metrics_text(date(1));
+        let name = "library_search_latency_seconds";
+        assert_eq!(value(&text, &format!("{}_bucket{{le=\"0.05\"}}", name)), "2");
+        assert_eq!(value(&text, &format!("{}_bucket{{le=\"+Inf\"}}", name)), "3");
+        assert_eq!(value(&text, &format!("{}_count", name)), "3");
+        let sum: f64 = value(&text, &format!("{}_sum", name)).parse().unwrap();
+        assert!(sum >= 1.0);
+    }
+}
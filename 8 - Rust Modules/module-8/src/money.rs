@@ -0,0 +1,354 @@
+//! Money module - amounts of money in a currency.
+//!
+//! Fees, fines and payments used to be bare `u32` cent counts, which made it
+//! easy to mix them up with day counts or IDs, and meant overflow went
+//! unnoticed. [`Money`] keeps the amount (in minor units: cents, pence) and
+//! its [`Currency`] together, and formats itself for display.
+//!
+//! The operators (`+`, `-`, `+=`, `sum()`) panic on overflow, a negative
+//! result, or a currency mismatch, since those are bugs in the library's own
+//! arithmetic. Use the `checked_` methods for amounts that come from outside.
+//!
+//! ```
+//! use module_8::{Currency, Money};
+//!
+//! let fee = Money::from_cents(125);
+//! assert_eq!(fee.to_string(), "$1.25");
+//! assert_eq!(fee + Money::from_cents(75), Money::from_cents(200));
+//! assert_eq!(fee.checked_sub(Money::from_cents(200)), None);
+//!
+//! let euros = Money::new(50, Currency::EUR);
+//! assert_eq!(euros.to_string(), "€0.50");
+//! assert_eq!(fee.checked_add(euros), None);
+//! ```
+
+use std::cmp::Ordering;
+use std::fmt;
+use std::iter::Sum;
+use std::ops::{Add, AddAssign, Sub, SubAssign};
+
+use crate::config::fees::CURRENCY;
+
+// =============================================================================
+// CURRENCY
+// =============================================================================
+
+/// An ISO 4217 currency code such as `USD`.
+///
+/// Amounts are assumed to have two decimal places (100 minor units to the
+/// major unit).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Currency([u8; 3]);
+
+impl Currency {
+    pub const USD: Currency = Currency(*b"USD");
+    pub const EUR: Currency = Currency(*b"EUR");
+    pub const GBP: Currency = Currency(*b"GBP");
+
+    /// Parses a three-letter uppercase code; `None` if it isn't one.
+    pub fn new(code: &str) -> Option<Currency> {
+        let bytes: [u8; 3] = code.as_bytes().try_into().ok()?;
+        bytes.iter().all(u8::is_ascii_uppercase).then_some(Currency(bytes))
+    }
+
+    pub fn code(&self) -> &str {
+        std::str::from_utf8(&self.0).expect("codes are ASCII")
+    }
+
+    /// The sign written before amounts, for currencies that have a common one.
+    pub fn symbol(&self) -> Option<&'static str> {
+        match &self.0 {
+            b"USD" => Some("$"),
+            b"EUR" => Some("€"),
+            b"GBP" => Some("£"),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for Currency {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.code())
+    }
+}
+
+/// Serialized as its code, e.g. `"USD"`.
+#[cfg(feature = "serde")]
+impl serde::Serialize for Currency {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.code())
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for Currency {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let code = String::deserialize(deserializer)?;
+        Currency::new(&code).ok_or_else(|| serde::de::Error::custom(format!("invalid currency code {:?}", code)))
+    }
+}
+
+// =============================================================================
+// MONEY
+// =============================================================================
+
+/// A non-negative amount of money in one currency.
+///
+/// Amounts in different currencies are never equal, and comparing them with
+/// `<` or `>` is always false.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Money {
+    cents: u64,
+    currency: Currency,
+}
+
+impl Money {
+    /// Nothing, in the library's currency.
+    pub const ZERO: Money = Money::from_cents(0);
+
+    pub const fn new(cents: u64, currency: Currency) -> Money {
+        Money { cents, currency }
+    }
+
+    /// An amount in the library's currency ([`CURRENCY`]).
+    pub const fn from_cents(cents: u64) -> Money {
+        Money::new(cents, CURRENCY)
+    }
+
+    /// The amount in minor units (cents).
+    pub fn cents(&self) -> u64 {
+        self.cents
+    }
+
+    pub fn currency(&self) -> Currency {
+        self.currency
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.cents == 0
+    }
+
+    /// `None` on overflow or if the currencies differ.
+    pub fn checked_add(self, rhs: Money) -> Option<Money> {
+        self.same_currency(rhs)?;
+        Some(Money::new(self.cents.checked_add(rhs.cents)?, self.currency))
+    }
+
+    /// `None` if `rhs` is larger or the currencies differ.
+    pub fn checked_sub(self, rhs: Money) -> Option<Money> {
+        self.same_currency(rhs)?;
+        Some(Money::new(self.cents.checked_sub(rhs.cents)?, self.currency))
+    }
+
+    /// `None` on overflow.
+    pub fn checked_mul(self, factor: u64) -> Option<Money> {
+        Some(Money::new(self.cents.checked_mul(factor)?, self.currency))
+    }
+
+    /// Adds, stopping at the largest representable amount.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the currencies differ.
+    pub fn saturating_add(self, rhs: Money) -> Money {
+        self.assert_same_currency(rhs);
+        Money::new(self.cents.saturating_add(rhs.cents), self.currency)
+    }
+
+    /// Sums in the currency of the first amount, stopping at the largest
+    /// representable amount; an empty sum is [`Money::ZERO`]. Fees and fines
+    /// are totalled this way, so no number of them can overflow.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the currencies differ.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use module_8::Money;
+    ///
+    /// let fees = [Money::from_cents(25), Money::from_cents(75)];
+    /// assert_eq!(Money::saturating_sum(fees), Money::from_cents(100));
+    /// let max = Money::from_cents(u64::MAX);
+    /// assert_eq!(Money::saturating_sum([max, max]), max);
+    /// ```
+    pub fn saturating_sum(amounts: impl IntoIterator<Item = Money>) -> Money {
+        let mut amounts = amounts.into_iter();
+        let Some(first) = amounts.next() else { return Money::ZERO };
+        amounts.fold(first, Money::saturating_add)
+    }
+
+    /// Multiplies, stopping at the largest representable amount.
+    pub fn saturating_mul(self, factor: u64) -> Money {
+        Money::new(self.cents.saturating_mul(factor), self.currency)
+    }
+
+    /// Subtracts, stopping at zero.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the currencies differ.
+    pub fn saturating_sub(self, rhs: Money) -> Money {
+        self.assert_same_currency(rhs);
+        Money::new(self.cents.saturating_sub(rhs.cents), self.currency)
+    }
+
+    /// `percent`% of the amount, rounded down (so a discount computed from
+    /// it rounds in the payer's favour).
+    pub fn percent(self, percent: u8) -> Money {
+        // Widened so even the largest amounts can't overflow
+        let cents = u128::from(self.cents) * u128::from(percent) / 100;
+        Money::new(cents as u64, self.currency)
+    }
+
+    /// The smaller of two amounts in the same currency.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the currencies differ.
+    pub fn min(self, other: Money) -> Money {
+        self.assert_same_currency(other);
+        if other.cents < self.cents { other } else { self }
+    }
+
+    fn same_currency(self, other: Money) -> Option<()> {
+        (self.currency == other.currency).then_some(())
+    }
+
+    fn assert_same_currency(self, other: Money) {
+        assert!(
+            self.currency == other.currency,
+            "cannot combine {} and {} amounts",
+            self.currency,
+            other.currency
+        );
+    }
+}
+
+impl Default for Money {
+    fn default() -> Money {
+        Money::ZERO
+    }
+}
+
+impl PartialOrd for Money {
+    fn partial_cmp(&self, other: &Money) -> Option<Ordering> {
+        self.same_currency(*other)?;
+        Some(self.cents.cmp(&other.cents))
+    }
+}
+
+/// `$1.25`, `€0.50`, or `12.00 CHF` for currencies without a symbol.
+impl fmt::Display for Money {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let amount = format!("{}.{:02}", self.cents / 100, self.cents % 100);
+        match self.currency.symbol() {
+            Some(symbol) => write!(f, "{}{}", symbol, amount),
+            None => write!(f, "{} {}", amount, self.currency),
+        }
+    }
+}
+
+// =============================================================================
+// OPERATORS
+// =============================================================================
+
+/// Panics on overflow; fee arithmetic uses [`Money::saturating_add`] instead.
+impl Add for Money {
+    type Output = Money;
+
+    fn add(self, rhs: Money) -> Money {
+        self.assert_same_currency(rhs);
+        self.checked_add(rhs).expect("money overflow")
+    }
+}
+
+impl AddAssign for Money {
+    fn add_assign(&mut self, rhs: Money) {
+        *self = *self + rhs;
+    }
+}
+
+impl Sub for Money {
+    type Output = Money;
+
+    fn sub(self, rhs: Money) -> Money {
+        self.assert_same_currency(rhs);
+        self.checked_sub(rhs).expect("money subtraction went below zero")
+    }
+}
+
+impl SubAssign for Money {
+    fn sub_assign(&mut self, rhs: Money) {
+        *self = *self - rhs;
+    }
+}
+
+/// Sums in the currency of the first amount; an empty sum is [`Money::ZERO`].
+/// Panics on overflow like `+`; see [`Money::saturating_sum`].
+impl Sum for Money {
+    fn sum<I: Iterator<Item = Money>>(mut iter: I) -> Money {
+        let Some(first) = iter.next() else { return Money::ZERO };
+        iter.fold(first, |total, amount| total + amount)
+    }
+}
+
+impl<'a> Sum<&'a Money> for Money {
+    fn sum<I: Iterator<Item = &'a Money>>(iter: I) -> Money {
+        iter.copied().sum()
+    }
+}
+
+// =============================================================================
+// TESTS
+// =============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_display() {
+        assert_eq!(Money::from_cents(5).to_string(), "$0.05");
+        assert_eq!(Money::new(123456, Currency::GBP).to_string(), "£1234.56");
+        let francs = Money::new(1200, Currency::new("CHF").unwrap());
+        assert_eq!(francs.to_string(), "12.00 CHF");
+        assert_eq!(Currency::new("chf"), None);
+    }
+
+    #[test]
+    fn test_checked_arithmetic() {
+        let max = Money::from_cents(u64::MAX);
+        assert_eq!(max.checked_add(Money::from_cents(1)), None);
+        assert_eq!(max.checked_mul(2), None);
+        assert_eq!(Money::from_cents(30).saturating_sub(Money::from_cents(50)), Money::ZERO);
+        assert_eq!(max.saturating_add(Money::from_cents(1)), max);
+        assert_eq!(max.saturating_mul(2), max);
+        assert_eq!(Money::from_cents(30).saturating_mul(3), Money::from_cents(90));
+        assert_eq!(Money::from_cents(3).percent(80), Money::from_cents(2));
+
+        let euros = Money::new(100, Currency::EUR);
+        assert_eq!(Money::from_cents(100).partial_cmp(&euros), None);
+        assert_ne!(Money::from_cents(100), euros);
+    }
+
+    #[test]
+    #[should_panic(expected = "cannot combine USD and EUR")]
+    fn test_mixed_currency_operator_panics() {
+        let _ = Money::from_cents(1) + Money::new(1, Currency::EUR);
+    }
+
+    #[test]
+    fn test_sum() {
+        let amounts = [Money::from_cents(25), Money::from_cents(75)];
+        assert_eq!(amounts.iter().sum::<Money>(), Money::from_cents(100));
+        assert_eq!(Vec::<Money>::new().into_iter().sum::<Money>(), Money::ZERO);
+
+        let max = Money::from_cents(u64::MAX);
+        assert_eq!(Money::saturating_sum([Money::from_cents(1), max, max]), max);
+        assert_eq!(Money::saturating_sum([]), Money::ZERO);
+        assert_eq!(Money::saturating_sum([Money::new(5, Currency::EUR)]).currency(), Currency::EUR);
+    }
+}
@@ -0,0 +1,137 @@
+//! Page module - one page of a longer listing.
+//!
+//! Listings that can grow without bound (the catalog, the member list) have
+//! a paginated variant returning a [`Page`], which carries the total count
+//! alongside the items so a frontend can render "page 2 of 7" controls.
+
+use crate::book::Book;
+use crate::member::Member;
+use crate::Library;
+
+/// A slice of a listing plus what's needed to navigate the rest.
+///
+/// Page numbers start at 1.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Page<T> {
+    pub items: Vec<T>,
+    /// This page's number (1-based).
+    pub page: usize,
+    pub per_page: usize,
+    /// Items in the whole listing, across all pages.
+    pub total_items: usize,
+}
+
+impl<T> Page<T> {
+    /// Cuts page `page` out of a listing.
+    ///
+    /// Page 0 is treated as page 1. A page past the end has no items but
+    /// still reports the totals.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `per_page` is 0.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use module_8::page::Page;
+    ///
+    /// let page = Page::from_iter(1..=25, 3, 10);
+    /// assert_eq!(page.items, vec![21, 22, 23, 24, 25]);
+    /// assert_eq!(page.total_pages(), 3);
+    /// assert!(!page.has_next());
+    /// ```
+    pub fn from_iter(items: impl IntoIterator<Item = T>, page: usize, per_page: usize) -> Self {
+        assert!(per_page > 0, "per_page must be at least 1");
+        let page = page.max(1);
+        let mut total_items = 0;
+        let mut selected = Vec::with_capacity(per_page);
+        let start = (page - 1).saturating_mul(per_page);
+        for item in items {
+            if total_items >= start && selected.len() < per_page {
+                selected.push(item);
+            }
+            total_items += 1;
+        }
+        Page {
+            items: selected,
+            page,
+            per_page,
+            total_items,
+        }
+    }
+
+    /// Number of pages in the listing (0 if it's empty).
+    pub fn total_pages(&self) -> usize {
+        self.total_items.div_ceil(self.per_page)
+    }
+
+    pub fn has_next(&self) -> bool {
+        self.page < self.total_pages()
+    }
+
+    pub fn has_prev(&self) -> bool {
+        self.page > 1
+    }
+}
+
+impl Library {
+    /// One page of the books on the shelf, in catalog order.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `per_page` is 0.
+    pub fn books_page(&self, page: usize, per_page: usize) -> Page<&Book> {
+        Page::from_iter(self.books(), page, per_page)
+    }
+
+    /// One page of the members, in registration order.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `per_page` is 0.
+    pub fn members_page(&self, page: usize, per_page: usize) -> Page<&Member> {
+        Page::from_iter(self.members(), page, per_page)
+    }
+}
+
+// =============================================================================
+// TESTS
+// =============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Genre;
+
+    #[test]
+    fn test_books_page() {
+        let mut lib = Library::new();
+        for id in 1..=7 {
+            lib.add_book(Book::new(id, "Book", Genre::Fiction)).unwrap();
+        }
+
+        let first = lib.books_page(1, 3);
+        let ids: Vec<u64> = first.items.iter().map(|b| b.id()).collect();
+        assert_eq!(ids, [1, 2, 3]);
+        assert_eq!((first.total_items, first.total_pages()), (7, 3));
+        assert!(first.has_next() && !first.has_prev());
+
+        let last = lib.books_page(3, 3);
+        assert_eq!(last.items.len(), 1);
+        assert!(!last.has_next() && last.has_prev());
+
+        let beyond = lib.books_page(9, 3);
+        assert!(beyond.items.is_empty());
+        assert_eq!(beyond.total_items, 7);
+    }
+
+    #[test]
+    fn test_empty_listing() {
+        let lib = Library::new();
+        let page = lib.members_page(0, 10);
+        assert_eq!(page.page, 1);
+        assert_eq!(page.total_pages(), 0);
+        assert!(!page.has_next() && !page.has_prev());
+    }
+}
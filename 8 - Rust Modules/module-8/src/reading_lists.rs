@@ -0,0 +1,380 @@
+//! Reading lists module - collections of books curated by staff.
+//!
+//! A [`Collection`] is a named, ordered list of books, such as "Summer Rust
+//! Reading", with a description for patrons. Staff build one with
+//! [`Library::create_collection`] and [`Library::add_to_collection`], and
+//! reorder it with [`Library::move_in_collection`];
+//! [`Library::render_collection`] lists it with whether each book can be
+//! borrowed right now.
+//!
+//! A book removed from the catalog drops out of every collection. A lost
+//! copy stays listed, shown as lost, in case it turns up.
+//!
+//! ```
+//! use chrono::NaiveDate;
+//! use module_8::{Book, Genre, Library, Member, MembershipTier};
+//!
+//! let mut lib = Library::new();
+//! lib.add_book(Book::new(1, "The Rust Programming Language", Genre::Technical)).unwrap();
+//! lib.add_book(Book::new(2, "Rust in Action", Genre::Technical)).unwrap();
+//! lib.register_member(Member::new(10, "Alice", MembershipTier::Basic)).unwrap();
+//!
+//! lib.create_collection("Summer Rust Reading", "Crabs on the beach").unwrap();
+//! lib.add_to_collection("Summer Rust Reading", 2).unwrap();
+//! lib.add_to_collection("Summer Rust Reading", 1).unwrap();
+//! lib.move_in_collection("Summer Rust Reading", 1, 0).unwrap();
+//! lib.checkout(10, 2, NaiveDate::from_ymd_opt(2024, 6, 1).unwrap()).unwrap();
+//!
+//! assert_eq!(
+//!     lib.render_collection("Summer Rust Reading").unwrap(),
+//!     "Summer Rust Reading\n\
+//!      Crabs on the beach\n\
+//!      1. The Rust Programming Language (#1): available\n\
+//!      2. Rust in Action (#2): on loan, due 2024-06-15\n"
+//! );
+//! ```
+
+use std::fmt::{self, Write};
+
+use chrono::NaiveDate;
+
+use crate::book::Book;
+use crate::error::LibraryError;
+use crate::index::BookLocation;
+use crate::Library;
+
+/// A named, ordered list of books put together by staff.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Collection {
+    pub name: String,
+    pub description: String,
+    book_ids: Vec<u64>,
+}
+
+impl Collection {
+    pub fn new(name: &str, description: &str) -> Collection {
+        Collection {
+            name: String::from(name),
+            description: String::from(description),
+            book_ids: Vec::new(),
+        }
+    }
+
+    /// The books in the collection, in the order staff arranged them.
+    pub fn book_ids(&self) -> &[u64] {
+        &self.book_ids
+    }
+
+    pub fn contains(&self, book_id: u64) -> bool {
+        self.book_ids.contains(&book_id)
+    }
+
+    /// Restores the book list of a loaded collection, whose books are
+    /// checked by the loader.
+    pub(crate) fn set_book_ids(&mut self, book_ids: Vec<u64>) {
+        self.book_ids = book_ids;
+    }
+}
+
+/// Whether a book in a collection can be borrowed right now.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Availability {
+    Available,
+    /// Back, but set aside for a member's hold.
+    OnHoldShelf,
+    OnLoan { due_on: NaiveDate },
+    /// Declared lost (see [`lost`](crate::lost)).
+    Lost,
+}
+
+impl fmt::Display for Availability {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Availability::Available => write!(f, "available"),
+            Availability::OnHoldShelf => write!(f, "on the hold shelf"),
+            Availability::OnLoan { due_on } => write!(f, "on loan, due {}", due_on),
+            Availability::Lost => write!(f, "lost"),
+        }
+    }
+}
+
+/// One book of [`Library::collection_entries`].
+#[derive(Debug, Clone)]
+pub struct CollectionEntry<'a> {
+    pub book: &'a Book,
+    pub availability: Availability,
+}
+
+// =============================================================================
+// ERRORS
+// =============================================================================
+
+/// Why a collection couldn't be changed or shown.
+#[derive(Debug, Clone, PartialEq)]
+pub enum CollectionError {
+    /// No collection has this name.
+    NotFound(String),
+    /// A collection with this name already exists.
+    Duplicate(String),
+    AlreadyListed { collection: String, book_id: u64 },
+    NotListed { collection: String, book_id: u64 },
+    /// The book isn't catalogued.
+    Library(LibraryError),
+}
+
+impl fmt::Display for CollectionError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CollectionError::NotFound(name) => write!(f, "no collection named {:?}", name),
+            CollectionError::Duplicate(name) => write!(f, "a collection named {:?} already exists", name),
+            CollectionError::AlreadyListed { collection, book_id } => {
+                write!(f, "book {} is already in {:?}", book_id, collection)
+            }
+            CollectionError::NotListed { collection, book_id } => {
+                write!(f, "book {} is not in {:?}", book_id, collection)
+            }
+            CollectionError::Library(e) => e.fmt(f),
+        }
+    }
+}
+
+impl std::error::Error for CollectionError {}
+
+impl From<LibraryError> for CollectionError {
+    fn from(e: LibraryError) -> Self {
+        CollectionError::Library(e)
+    }
+}
+
+// =============================================================================
+// LIBRARY INTEGRATION
+// =============================================================================
+
+impl Library {
+    /// Starts an empty collection.
+    ///
+    /// # Errors
+    ///
+    /// [`CollectionError::Duplicate`] if the name is taken.
+    pub fn create_collection(&mut self, name: &str, description: &str) -> Result<(), CollectionError> {
+        if self.collection(name).is_some() {
+            return Err(CollectionError::Duplicate(String::from(name)));
+        }
+        self.collections.push(Collection::new(name, description));
+        Ok(())
+    }
+
+    /// Deletes a collection, returning it. The books stay in the catalog.
+    pub fn remove_collection(&mut self, name: &str) -> Result<Collection, CollectionError> {
+        let pos = self
+            .collections
+            .iter()
+            .position(|c| c.name == name)
+            .ok_or_else(|| CollectionError::NotFound(String::from(name)))?;
+        Ok(self.collections.remove(pos))
+    }
+
+    pub fn collection(&self, name: &str) -> Option<&Collection> {
+        self.collections.iter().find(|c| c.name == name)
+    }
+
+    /// Every collection, in the order they were created.
+    pub fn collections(&self) -> &[Collection] {
+        &self.collections
+    }
+
+    /// Adds a catalogued book, on the shelf or on loan, to the end of a
+    /// collection.
+    pub fn add_to_collection(&mut self, name: &str, book_id: u64) -> Result<(), CollectionError> {
+        if self.get_book(book_id).is_none() {
+            return Err(LibraryError::BookNotFound(book_id).into());
+        }
+        let collection = self.collection_mut(name)?;
+        if collection.contains(book_id) {
+            return Err(CollectionError::AlreadyListed { collection: String::from(name), book_id });
+        }
+        collection.book_ids.push(book_id);
+        Ok(())
+    }
+
+    pub fn remove_from_collection(&mut self, name: &str, book_id: u64) -> Result<(), CollectionError> {
+        let collection = self.collection_mut(name)?;
+        let pos = listed_at(collection, book_id)?;
+        collection.book_ids.remove(pos);
+        Ok(())
+    }
+
+    /// Moves a book to `position` (0 for the top) in a collection, shifting
+    /// the books in between. A position past the end moves it to the end.
+    pub fn move_in_collection(&mut self, name: &str, book_id: u64, position: usize) -> Result<(), CollectionError> {
+        let collection = self.collection_mut(name)?;
+        let pos = listed_at(collection, book_id)?;
+        collection.book_ids.remove(pos);
+        let position = position.min(collection.book_ids.len());
+        collection.book_ids.insert(position, book_id);
+        Ok(())
+    }
+
+    /// The books of a collection in order, with their availability.
+    pub fn collection_entries(&self, name: &str) -> Result<Vec<CollectionEntry<'_>>, CollectionError> {
+        let collection = self
+            .collection(name)
+            .ok_or_else(|| CollectionError::NotFound(String::from(name)))?;
+        Ok(collection.book_ids.iter().filter_map(|&id| self.collection_entry(id)).collect())
+    }
+
+    /// A collection as text for patrons: its name and description, then one
+    /// numbered line per book saying whether it can be borrowed.
+    pub fn render_collection(&self, name: &str) -> Result<String, CollectionError> {
+        let entries = self.collection_entries(name)?;
+        let collection = self.collection(name).expect("found above");
+        let mut out = format!("{}\n", collection.name);
+        if !collection.description.is_empty() {
+            let _ = writeln!(out, "{}", collection.description);
+        }
+        if entries.is_empty() {
+            out.push_str("No books yet.\n");
+        }
+        for (n, entry) in entries.iter().enumerate() {
+            let _ = writeln!(
+                out,
+                "{}. {} (#{}): {}",
+                n + 1,
+                entry.book.title,
+                entry.book.id(),
+                entry.availability
+            );
+        }
+        Ok(out)
+    }
+
+    /// Takes a book that has left the catalog out of every collection.
+    pub(crate) fn forget_in_collections(&mut self, book_id: u64) {
+        for collection in &mut self.collections {
+            collection.book_ids.retain(|&id| id != book_id);
+        }
+    }
+
+    fn collection_mut(&mut self, name: &str) -> Result<&mut Collection, CollectionError> {
+        self.collections
+            .iter_mut()
+            .find(|c| c.name == name)
+            .ok_or_else(|| CollectionError::NotFound(String::from(name)))
+    }
+
+    fn collection_entry(&self, book_id: u64) -> Option<CollectionEntry<'_>> {
+        if let Some(lost) = self.lost_books.iter().find(|lost| lost.book.id() == book_id) {
+            return Some(CollectionEntry { book: &lost.book, availability: Availability::Lost });
+        }
+        let book = self.get_book(book_id)?;
+        let availability = match self.index.location(book_id)? {
+            BookLocation::OnLoan => {
+                let loan = self.loans.iter().find(|l| l.book_id == book_id)?;
+                Availability::OnLoan { due_on: loan.due_on }
+            }
+            BookLocation::Shelf if self.holds.iter().any(|h| h.book_id == book_id && h.ready_since.is_some()) => {
+                Availability::OnHoldShelf
+            }
+            BookLocation::Shelf => Availability::Available,
+        };
+        Some(CollectionEntry { book, availability })
+    }
+}
+
+fn listed_at(collection: &Collection, book_id: u64) -> Result<usize, CollectionError> {
+    collection
+        .book_ids
+        .iter()
+        .position(|&id| id == book_id)
+        .ok_or_else(|| CollectionError::NotListed { collection: collection.name.clone(), book_id })
+}
+
+// =============================================================================
+// TESTS
+// =============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Genre, Member, MembershipTier};
+    use chrono::Duration;
+
+    const LIST: &str = "Summer Rust Reading";
+
+    fn date(day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(2024, 6, day).unwrap()
+    }
+
+    fn library() -> Library {
+        let mut lib = Library::new();
+        lib.add_book(Book::new(1, "The Rust Programming Language", Genre::Technical)).unwrap();
+        lib.add_book(Book::new(2, "Rust in Action", Genre::Technical)).unwrap();
+        lib.add_book(Book::new(3, "Programming Rust", Genre::Technical)).unwrap();
+        lib.add_book(Book::new(4, "Zero to Production", Genre::Technical)).unwrap();
+        lib.register_member(Member::new(10, "Alice", MembershipTier::Gold)).unwrap();
+        lib.register_member(Member::new(11, "Bob", MembershipTier::Basic)).unwrap();
+        lib.create_collection(LIST, "").unwrap();
+        for id in 1..=4 {
+            lib.add_to_collection(LIST, id).unwrap();
+        }
+        lib
+    }
+
+    fn ids(lib: &Library) -> Vec<u64> {
+        lib.collection(LIST).unwrap().book_ids().to_vec()
+    }
+
+    #[test]
+    fn test_editing_a_collection() {
+        let mut lib = library();
+        assert_eq!(lib.create_collection(LIST, "again"), Err(CollectionError::Duplicate(String::from(LIST))));
+        assert_eq!(
+            lib.add_to_collection(LIST, 2),
+            Err(CollectionError::AlreadyListed { collection: String::from(LIST), book_id: 2 })
+        );
+        assert_eq!(lib.add_to_collection(LIST, 99), Err(CollectionError::Library(LibraryError::BookNotFound(99))));
+        assert_eq!(lib.add_to_collection("Winter", 1), Err(CollectionError::NotFound(String::from("Winter"))));
+
+        lib.move_in_collection(LIST, 4, 0).unwrap();
+        lib.move_in_collection(LIST, 1, 99).unwrap();
+        assert_eq!(ids(&lib), [4, 2, 3, 1]);
+        lib.remove_from_collection(LIST, 2).unwrap();
+        assert_eq!(
+            lib.remove_from_collection(LIST, 2),
+            Err(CollectionError::NotListed { collection: String::from(LIST), book_id: 2 })
+        );
+
+        // Removing a book from the catalog removes it from the collection
+        lib.remove_book(3).unwrap();
+        assert_eq!(ids(&lib), [4, 1]);
+        assert_eq!(lib.remove_collection(LIST).unwrap().book_ids(), [4, 1]);
+        assert!(lib.collections().is_empty());
+    }
+
+    #[test]
+    fn test_entries_show_availability() {
+        let mut lib = library();
+        let due = lib.checkout(10, 1, date(1)).unwrap();
+        lib.checkout(10, 2, date(1)).unwrap();
+        lib.checkout(10, 3, date(1)).unwrap();
+        lib.place_hold(11, 2, date(2)).unwrap();
+        lib.return_book(10, 2, date(3)).unwrap();
+        lib.mark_lost(10, 3, due + Duration::days(60)).unwrap();
+
+        let availability: Vec<Availability> = lib.collection_entries(LIST).unwrap().iter().map(|e| e.availability).collect();
+        assert_eq!(
+            availability,
+            [
+                Availability::OnLoan { due_on: due },
+                Availability::OnHoldShelf,
+                Availability::Lost,
+                Availability::Available
+            ]
+        );
+        assert!(lib.render_collection(LIST).unwrap().contains("3. Programming Rust (#3): lost\n"));
+
+        lib.create_collection("Empty", "Nothing here").unwrap();
+        assert_eq!(lib.render_collection("Empty").unwrap(), "Empty\nNothing here\nNo books yet.\n");
+    }
+}
@@ -0,0 +1,335 @@
+//! Receipts module - printable slips for checkouts and returns.
+//!
+//! A [`Receipt`] is plain data: the library, the member, one line per item
+//! and the fees. [`Receipt::to_text`] lays it out for a 40-column slip
+//! printer and [`Receipt::to_markdown`] for screens and email, both with the
+//! layout helpers in [`utils::formatting`](crate::utils::formatting). Their
+//! `_in` variants word the receipt in another [`Locale`].
+//!
+//! ```
+//! use chrono::NaiveDate;
+//! use module_8::{Book, Genre, Library, Member, MembershipTier};
+//!
+//! let mut lib = Library::new();
+//! lib.add_book(Book::new(1, "Dune", Genre::SciFi)).unwrap();
+//! lib.register_member(Member::new(10, "Alice", MembershipTier::Basic)).unwrap();
+//! lib.checkout(10, 1, NaiveDate::from_ymd_opt(2024, 3, 1).unwrap()).unwrap();
+//!
+//! let receipt = lib.checkout_receipt(10, 1).unwrap();
+//! let text = receipt.to_text();
+//! assert!(text.contains("Dune                      due 2024-03-15"));
+//! assert!(receipt.to_markdown().contains("| Dune | 2024-03-15 | $0.00 |"));
+//! ```
+
+use std::fmt;
+
+use std::collections::BTreeMap;
+
+use chrono::NaiveDate;
+
+use crate::book::Book;
+use crate::circulation::Loan;
+use crate::config::LIBRARY_NAME;
+use crate::locale::{Locale, Message};
+use crate::member::{LoanRecord, Member};
+use crate::money::Money;
+use crate::templates::Template;
+use crate::utils::formatting::{centered, columns, markdown_table};
+use crate::Library;
+
+/// Width of a plain-text receipt, in characters.
+pub const RECEIPT_WIDTH: usize = 40;
+
+// =============================================================================
+// RECEIPT DATA
+// =============================================================================
+
+/// Whether items went out or came back.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ReceiptKind {
+    Checkout,
+    Return,
+}
+
+impl ReceiptKind {
+    fn message(&self) -> Message {
+        match self {
+            ReceiptKind::Checkout => Message::CheckoutReceipt,
+            ReceiptKind::Return => Message::ReturnReceipt,
+        }
+    }
+}
+
+impl fmt::Display for ReceiptKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ReceiptKind::Checkout => write!(f, "Checkout receipt"),
+            ReceiptKind::Return => write!(f, "Return receipt"),
+        }
+    }
+}
+
+/// One item on a receipt.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ReceiptLine {
+    pub book_id: u64,
+    pub title: String,
+    /// When a checked-out item is due; `None` on a return.
+    pub due_on: Option<NaiveDate>,
+    /// Charged for this item: on a return, the late fee plus any
+    /// replacement cost.
+    pub fee: Money,
+}
+
+/// A checkout or return slip.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Receipt {
+    pub library: String,
+    pub kind: ReceiptKind,
+    pub date: NaiveDate,
+    pub member_name: String,
+    pub member_id: u64,
+    pub lines: Vec<ReceiptLine>,
+    /// The member's unpaid fines after the transaction.
+    pub balance: Money,
+}
+
+impl Receipt {
+    /// A receipt for one checkout of `book`.
+    pub fn for_checkout(member: &Member, book: &Book, loan: &Loan) -> Receipt {
+        Receipt {
+            library: String::from(LIBRARY_NAME),
+            kind: ReceiptKind::Checkout,
+            date: loan.borrowed_on,
+            member_name: member.name.clone(),
+            member_id: member.id(),
+            lines: vec![ReceiptLine {
+                book_id: loan.book_id,
+                title: book.title.clone(),
+                due_on: Some(loan.due_on),
+                fee: Money::ZERO,
+            }],
+            balance: member.outstanding_fines(),
+        }
+    }
+
+    /// A receipt for one return, from the loan's record in the member's
+    /// history.
+    pub fn for_return(member: &Member, book: &Book, record: &LoanRecord) -> Receipt {
+        Receipt::returned(member, &book.title, record)
+    }
+
+    fn returned(member: &Member, title: &str, record: &LoanRecord) -> Receipt {
+        Receipt {
+            library: String::from(LIBRARY_NAME),
+            kind: ReceiptKind::Return,
+            date: record.returned_on,
+            member_name: member.name.clone(),
+            member_id: member.id(),
+            lines: vec![ReceiptLine {
+                book_id: record.book_id,
+                title: String::from(title),
+                due_on: None,
+                fee: record.fee_charged,
+            }],
+            balance: member.outstanding_fines(),
+        }
+    }
+
+    /// Prints `name` at the top instead of the default library name.
+    pub fn with_library_name(mut self, name: &str) -> Receipt {
+        self.library = String::from(name);
+        self
+    }
+
+    /// What the items on the receipt cost.
+    pub fn total_fees(&self) -> Money {
+        Money::saturating_sum(self.lines.iter().map(|line| line.fee))
+    }
+
+    /// A plain-text slip, [`RECEIPT_WIDTH`] characters wide.
+    pub fn to_text(&self) -> String {
+        self.to_text_in(&Locale::english())
+    }
+
+    /// Like [`to_text`](Receipt::to_text), worded in `locale`.
+    pub fn to_text_in(&self, locale: &Locale) -> String {
+        let rule = |c: &str| c.repeat(RECEIPT_WIDTH);
+        let mut lines = vec![
+            centered(&self.library, RECEIPT_WIDTH),
+            rule("="),
+            columns(locale.text(self.kind.message()), &self.date.to_string(), RECEIPT_WIDTH),
+            self.member_line(locale),
+            rule("-"),
+        ];
+        for line in &self.lines {
+            let right = match line.due_on {
+                Some(due) => {
+                    let values = BTreeMap::from([("date", due.to_string())]);
+                    Template::fill(locale.text(Message::DueOn), &values)
+                }
+                None => line.fee.to_string(),
+            };
+            lines.push(columns(&line.title, &right, RECEIPT_WIDTH));
+        }
+        lines.push(rule("-"));
+        lines.push(columns(locale.text(Message::Fees), &self.total_fees().to_string(), RECEIPT_WIDTH));
+        lines.push(columns(locale.text(Message::BalanceOwed), &self.balance.to_string(), RECEIPT_WIDTH));
+        lines.join("\n") + "\n"
+    }
+
+    /// The receipt as a Markdown heading and table.
+    pub fn to_markdown(&self) -> String {
+        self.to_markdown_in(&Locale::english())
+    }
+
+    /// Like [`to_markdown`](Receipt::to_markdown), worded in `locale`.
+    pub fn to_markdown_in(&self, locale: &Locale) -> String {
+        let rows: Vec<Vec<String>> = self
+            .lines
+            .iter()
+            .map(|line| {
+                let due = line.due_on.map_or_else(|| String::from("-"), |d| d.to_string());
+                vec![line.title.clone(), due, line.fee.to_string()]
+            })
+            .collect();
+        let headers = [Message::Item, Message::Due, Message::Fee].map(|m| locale.text(m));
+        format!(
+            "## {}\n\n**{}**, {}  \n{}\n\n{}\n**{}:** {}  \n**{}:** {}\n",
+            self.library,
+            locale.text(self.kind.message()),
+            self.date,
+            self.member_line(locale),
+            markdown_table(&headers, &rows),
+            locale.text(Message::Fees),
+            self.total_fees(),
+            locale.text(Message::BalanceOwed),
+            self.balance
+        )
+    }
+
+    fn member_line(&self, locale: &Locale) -> String {
+        let values = BTreeMap::from([("name", self.member_name.clone()), ("id", self.member_id.to_string())]);
+        Template::fill(locale.text(Message::ReceiptMember), &values)
+    }
+}
+
+impl fmt::Display for Receipt {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.to_text())
+    }
+}
+
+fn untitled(book_id: u64) -> String {
+    format!("Book #{}", book_id)
+}
+
+// =============================================================================
+// LIBRARY INTEGRATION
+// =============================================================================
+
+impl Library {
+    /// A receipt for the member's current loan of `book_id`, under this
+    /// library's name.
+    pub fn checkout_receipt(&self, member_id: u64, book_id: u64) -> Option<Receipt> {
+        let member = self.find_member(member_id)?;
+        let loan = self.loans().iter().find(|l| l.member_id == member_id && l.book_id == book_id)?;
+        let book = self.get_book(book_id)?;
+        Some(Receipt::for_checkout(member, book, loan).with_library_name(self.name()))
+    }
+
+    /// A receipt for the member's latest return of `book_id`, under this
+    /// library's name.
+    pub fn return_receipt(&self, member_id: u64, book_id: u64) -> Option<Receipt> {
+        let member = self.find_member(member_id)?;
+        let record = member.history().iter().rev().find(|r| r.book_id == book_id)?;
+        // A lost book has left the catalog
+        let title = self.get_book(book_id).map_or_else(|| untitled(book_id), |b| b.title.clone());
+        Some(Receipt::returned(member, &title, record).with_library_name(self.name()))
+    }
+}
+
+// =============================================================================
+// TESTS
+// =============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Condition, Format, Genre, MembershipTier};
+
+    fn date(day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(2024, 3, day).unwrap()
+    }
+
+    fn library() -> Library {
+        let mut lib = Library::new();
+        lib.add_book(Book::new(1, "Dune", Genre::SciFi)).unwrap();
+        let mut ebook = Book::new(2, "Emma | A Novel", Genre::Fiction);
+        ebook.format = Format::Digital;
+        lib.add_book(ebook).unwrap();
+        lib.register_member(Member::new(10, "Alice", MembershipTier::Basic)).unwrap();
+        lib
+    }
+
+    #[test]
+    fn test_checkout_receipt_text() {
+        let mut lib = library();
+        lib.checkout(10, 1, date(1)).unwrap();
+        let receipt = lib.checkout_receipt(10, 1).unwrap();
+        let expected = "           Rustacean Library
+========================================
+Checkout receipt              2024-03-01
+Member: Alice (#10)
+----------------------------------------
+Dune                      due 2024-03-15
+----------------------------------------
+Fees                               $0.00
+Balance owed                       $0.00
+";
+        assert_eq!(receipt.to_text(), expected);
+        assert_eq!(receipt.to_string(), expected);
+        assert!(lib.checkout_receipt(10, 2).is_none());
+    }
+
+    #[test]
+    fn test_digital_and_return_receipts() {
+        let mut lib = library();
+        lib.checkout(10, 2, date(1)).unwrap();
+        let loan = lib.loans()[0].clone();
+        let bare = Receipt::for_checkout(lib.find_member(10).unwrap(), lib.get_book(2).unwrap(), &loan);
+        assert_eq!((bare.library.as_str(), bare.lines[0].title.as_str()), (LIBRARY_NAME, "Emma | A Novel"));
+        let markdown = lib.checkout_receipt(10, 2).unwrap().to_markdown();
+        assert!(markdown.starts_with("## Rustacean Library\n\n**Checkout receipt**, 2024-03-01"));
+        assert!(markdown.contains("| Emma \\| A Novel | 2024-03-22 | $0.00 |"));
+
+        lib.checkout(10, 1, date(1)).unwrap();
+        lib.return_book_in(10, 1, date(18), Condition::Lost).unwrap();
+        let receipt = lib.return_receipt(10, 1).unwrap();
+        assert_eq!(receipt.kind, ReceiptKind::Return);
+        // Three days late plus the replacement cost
+        assert_eq!((receipt.lines[0].title.as_str(), receipt.lines[0].fee), ("Book #1", Money::from_cents(2075)));
+        assert!(receipt.to_text().contains(&columns("Book #1", "$20.75", RECEIPT_WIDTH)));
+        assert_eq!(receipt.balance, receipt.total_fees());
+    }
+
+    #[test]
+    fn test_receipt_in_another_locale() {
+        let mut lib = library();
+        lib.checkout(10, 1, date(1)).unwrap();
+        let fr = Locale::new("fr")
+            .with(Message::CheckoutReceipt, "Reçu d'emprunt")
+            .with(Message::DueOn, "rendre le {date}")
+            .with(Message::ReceiptMember, "Lecteur : {name} (n° {id})")
+            .with(Message::Item, "Titre");
+        let receipt = lib.checkout_receipt(10, 1).unwrap();
+
+        let text = receipt.to_text_in(&fr);
+        assert!(text.contains(&columns("Reçu d'emprunt", "2024-03-01", RECEIPT_WIDTH)));
+        assert!(text.contains("Lecteur : Alice (n° 10)\n"));
+        assert!(text.contains(&columns("Dune", "rendre le 2024-03-15", RECEIPT_WIDTH)));
+        // Untranslated messages stay English
+        assert!(text.contains(&columns("Fees", "$0.00", RECEIPT_WIDTH)));
+        assert!(receipt.to_markdown_in(&fr).contains("| Titre | Due | Fee |"));
+    }
+}
@@ -0,0 +1,162 @@
+//! Recommend module - "readers like you also borrowed" suggestions.
+//!
+//! A member's taste is read off their loan history: the genres and tags of
+//! the books they've returned. Each available book scores points for every
+//! past loan sharing its genre and every past tag it carries, plus a small
+//! bonus for how often anyone has borrowed it.
+
+use std::cmp::Reverse;
+use std::collections::{HashMap, HashSet};
+
+use crate::book::Book;
+use crate::error::LibraryError;
+use crate::index::normalize_title;
+use crate::Library;
+
+/// Points per past loan in the same genre.
+pub const GENRE_WEIGHT: u32 = 3;
+/// Points per past loan carrying each shared tag.
+pub const TAG_WEIGHT: u32 = 2;
+/// Points per time anyone has borrowed the book.
+pub const POPULARITY_WEIGHT: u32 = 1;
+
+/// A suggested book and why it ranked where it did.
+#[derive(Debug, Clone, Copy)]
+pub struct Recommendation<'a> {
+    pub book: &'a Book,
+    pub score: u32,
+}
+
+impl Library {
+    /// Up to `n` books on the shelf that `member_id` is likely to enjoy,
+    /// best first.
+    ///
+    /// Books whose title the member has already borrowed (any copy) or has
+    /// on loan now are left out. A member with no history gets the most
+    /// popular books; ties keep catalog order.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use chrono::NaiveDate;
+    /// use module_8::{Book, Genre, Library, Member, MembershipTier};
+    ///
+    /// let mut lib = Library::new();
+    /// lib.add_book(Book::new(1, "Dune", Genre::SciFi)).unwrap();
+    /// lib.add_book(Book::new(2, "Emma", Genre::Fiction)).unwrap();
+    /// lib.add_book(Book::new(3, "Foundation", Genre::SciFi)).unwrap();
+    /// lib.register_member(Member::new(7, "Alice", MembershipTier::Basic)).unwrap();
+    ///
+    /// let day = NaiveDate::from_ymd_opt(2024, 3, 1).unwrap();
+    /// lib.checkout(7, 1, day).unwrap();
+    /// lib.return_book(7, 1, day).unwrap();
+    ///
+    /// let picks = lib.recommend_for(7, 1).unwrap();
+    /// assert_eq!(picks[0].book.title, "Foundation");
+    /// ```
+    pub fn recommend_for(&self, member_id: u64, n: usize) -> Result<Vec<Recommendation<'_>>, LibraryError> {
+        let member = self
+            .find_member(member_id)
+            .ok_or(LibraryError::MemberNotFound(member_id))?;
+
+        // Books removed since (e.g. lost) no longer say anything about taste
+        let read: Vec<&Book> = member
+            .history()
+            .iter()
+            .filter_map(|record| self.get_book(record.book_id))
+            .collect();
+        let mut tag_counts: HashMap<&str, u32> = HashMap::new();
+        for book in &read {
+            for tag in book.tags() {
+                *tag_counts.entry(tag).or_insert(0) += 1;
+            }
+        }
+        // Digital copies being read stay on the shelf, so look them up by loan
+        let reading_digital = self
+            .loans
+            .iter()
+            .filter(|l| l.member_id == member_id && self.is_digital_loan(l))
+            .filter_map(|l| self.find_book(l.book_id));
+        let seen: HashSet<String> = read
+            .iter()
+            .copied()
+            .chain(self.borrowed_books(member_id))
+            .chain(reading_digital)
+            .map(|b| normalize_title(&b.title))
+            .collect();
+
+        let mut picks: Vec<Recommendation> = self
+            .books()
+            .filter(|book| book.is_available() && !seen.contains(&normalize_title(&book.title)))
+            .map(|book| {
+                let genre = read.iter().filter(|r| r.genre == book.genre).count() as u32;
+                let tags: u32 = book.tags().iter().filter_map(|t| tag_counts.get(t.as_str())).sum();
+                let score = genre * GENRE_WEIGHT + tags * TAG_WEIGHT + book.times_borrowed() * POPULARITY_WEIGHT;
+                Recommendation { book, score }
+            })
+            .collect();
+
+        // Stable sort, so equal scores stay in catalog order
+        picks.sort_by_key(|pick| Reverse(pick.score));
+        picks.truncate(n);
+        Ok(picks)
+    }
+}
+
+// =============================================================================
+// TESTS
+// =============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Genre, Member, MembershipTier};
+    use chrono::NaiveDate;
+
+    fn date(day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(2024, 3, day).unwrap()
+    }
+
+    fn borrow(lib: &mut Library, member_id: u64, book_id: u64) {
+        lib.checkout(member_id, book_id, date(1)).unwrap();
+        lib.return_book(member_id, book_id, date(2)).unwrap();
+    }
+
+    #[test]
+    fn test_recommend_by_genre_tags_and_popularity() {
+        let mut lib = Library::new();
+        lib.add_book(Book::new(1, "Dune", Genre::SciFi)).unwrap();
+        lib.add_book(Book::new(2, "Emma", Genre::Fiction)).unwrap();
+        lib.add_book(Book::new(3, "Foundation", Genre::SciFi)).unwrap();
+        lib.add_book(Book::new(4, "Persuasion", Genre::Fiction)).unwrap();
+        lib.add_book(Book::new(5, "Dune", Genre::SciFi)).unwrap();
+        lib.tag_book(1, "space").unwrap();
+        lib.tag_book(4, "space").unwrap();
+        lib.register_member(Member::new(10, "Alice", MembershipTier::Basic)).unwrap();
+        lib.register_member(Member::new(11, "Bob", MembershipTier::Basic)).unwrap();
+        borrow(&mut lib, 10, 1);
+        borrow(&mut lib, 11, 2);
+
+        let picks = lib.recommend_for(10, 10).unwrap();
+        let ranked: Vec<(u64, u32)> = picks.iter().map(|p| (p.book.id(), p.score)).collect();
+        // Foundation shares the genre; Persuasion the tag; Emma is popular.
+        // Book 5 is another copy of Dune, which Alice has read.
+        assert_eq!(ranked, [(3, 3), (4, 2), (2, 1)]);
+    }
+
+    #[test]
+    fn test_recommend_without_history() {
+        let mut lib = Library::new();
+        lib.add_book(Book::new(1, "Dune", Genre::SciFi)).unwrap();
+        lib.add_book(Book::new(2, "Emma", Genre::Fiction)).unwrap();
+        lib.register_member(Member::new(10, "Alice", MembershipTier::Basic)).unwrap();
+        lib.register_member(Member::new(11, "Bob", MembershipTier::Basic)).unwrap();
+        borrow(&mut lib, 11, 2);
+        lib.checkout(11, 1, date(3)).unwrap();
+
+        let picks = lib.recommend_for(10, 5).unwrap();
+        assert_eq!(picks.len(), 1);
+        assert_eq!(picks[0].book.title, "Emma");
+        assert!(matches!(lib.recommend_for(99, 5), Err(LibraryError::MemberNotFound(99))));
+    }
+}
@@ -0,0 +1,870 @@
+//! Reports module - library reports built from sections.
+//!
+//! A report is a list of [`ReportSection`]s rendered one after another.
+//! The built-in sections come from [`standard_sections`]; downstream crates
+//! add their own by implementing the trait and passing the combined list to
+//! [`Library::report_with`]. [`MostBorrowed`], [`OverdueLoans`],
+//! [`ShelvingList`] and [`FeeSummary`] are there to add when wanted.
+//!
+//! [`Library::build_report`] keeps the sections apart in a [`Report`],
+//! which renders as plain text, Markdown or HTML. Sections that provide a
+//! [`Table`] become real tables in the latter two; the rest are shown
+//! preformatted.
+//!
+//! ```
+//! use module_8::reports::{standard_sections, MostBorrowed};
+//! use module_8::Library;
+//!
+//! let mut sections = standard_sections();
+//! sections.push(Box::new(MostBorrowed::default()));
+//! let report = Library::new().build_report(&sections);
+//!
+//! assert!(report.to_markdown().contains("## Catalog\n\n| Figure | Count |\n"));
+//! assert!(report.to_html().contains("<h2>Most Borrowed Books</h2>\n<pre>No books borrowed yet.</pre>"));
+//! assert_eq!(report.to_text(), Library::new().report_with(&sections));
+//! ```
+//!
+//! [`financials`] is the treasurer's view: fine revenue by month and tier,
+//! exportable as CSV.
+
+use std::cmp::Reverse;
+use std::collections::BTreeSet;
+use std::fmt::Write;
+use std::io;
+use std::ops::RangeInclusive;
+
+use chrono::{Datelike, Months, NaiveDate};
+
+use crate::book::Book;
+use crate::circulation::Loan;
+use crate::ledger::{FineEntry, FineKind};
+use crate::money::Money;
+use crate::utils::formatting::{html_escape, html_table, markdown_table};
+use crate::Library;
+
+// =============================================================================
+// REPORT SECTION TRAIT
+// =============================================================================
+
+/// One titled block of a library report.
+///
+/// # Examples
+///
+/// ```
+/// use module_8::reports::{standard_sections, ReportSection};
+/// use module_8::Library;
+///
+/// struct Motto;
+///
+/// impl ReportSection for Motto {
+///     fn title(&self) -> String {
+///         String::from("Motto")
+///     }
+///
+///     fn render(&self, _library: &Library) -> String {
+///         String::from("Read more Rust.")
+///     }
+/// }
+///
+/// let mut sections = standard_sections();
+/// sections.push(Box::new(Motto));
+///
+/// let report = Library::new().report_with(&sections);
+/// assert!(report.contains("Motto\n-----\nRead more Rust."));
+/// ```
+pub trait ReportSection {
+    /// Heading printed above the section.
+    fn title(&self) -> String;
+
+    /// The section body. Trailing whitespace is trimmed.
+    fn render(&self, library: &Library) -> String;
+
+    /// The section body as a table, for the Markdown and HTML renderers.
+    /// The default, `None`, shows [`render`](ReportSection::render)'s text
+    /// preformatted instead.
+    fn table(&self, _library: &Library) -> Option<Table> {
+        None
+    }
+}
+
+// =============================================================================
+// TABLES
+// =============================================================================
+
+/// Rows and columns of report data.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Table {
+    pub headers: Vec<String>,
+    pub rows: Vec<Vec<String>>,
+}
+
+impl Table {
+    /// A table with these column headings and no rows yet.
+    pub fn new(headers: &[&str]) -> Table {
+        Table {
+            headers: headers.iter().map(|h| h.to_string()).collect(),
+            rows: Vec::new(),
+        }
+    }
+
+    /// Appends a row; it should have a cell per header.
+    pub fn push_row(&mut self, cells: Vec<String>) {
+        self.rows.push(cells);
+    }
+
+    pub fn to_markdown(&self) -> String {
+        markdown_table(&self.header_refs(), &self.rows)
+    }
+
+    pub fn to_html(&self) -> String {
+        html_table(&self.header_refs(), &self.rows)
+    }
+
+    fn header_refs(&self) -> Vec<&str> {
+        self.headers.iter().map(String::as_str).collect()
+    }
+}
+
+// =============================================================================
+// BUILT-IN SECTIONS
+// =============================================================================
+
+/// Counts of books, loans (physical and digital), holds and members.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct CatalogSummary;
+
+impl CatalogSummary {
+    fn figures(library: &Library) -> [(&'static str, usize); 5] {
+        let digital = library.loans().iter().filter(|l| library.is_digital_loan(l)).count();
+        [
+            ("Books on shelf", library.book_count()),
+            ("Books on loan", library.loans().len() - digital),
+            ("Digital loans", digital),
+            ("Open holds", library.holds().len()),
+            ("Members", library.member_count()),
+        ]
+    }
+}
+
+impl ReportSection for CatalogSummary {
+    fn title(&self) -> String {
+        String::from("Catalog")
+    }
+
+    fn render(&self, library: &Library) -> String {
+        let lines: Vec<String> = CatalogSummary::figures(library)
+            .iter()
+            .map(|(figure, count)| format!("{}: {}", figure, count))
+            .collect();
+        lines.join("\n")
+    }
+
+    fn table(&self, library: &Library) -> Option<Table> {
+        let mut table = Table::new(&["Figure", "Count"]);
+        for (figure, count) in CatalogSummary::figures(library) {
+            table.push_row(vec![figure.to_string(), count.to_string()]);
+        }
+        Some(table)
+    }
+}
+
+/// The members with the most completed loans.
+#[derive(Debug, Clone, Copy)]
+pub struct TopReaders {
+    pub limit: usize,
+}
+
+impl Default for TopReaders {
+    fn default() -> Self {
+        TopReaders { limit: 5 }
+    }
+}
+
+impl ReportSection for TopReaders {
+    fn title(&self) -> String {
+        format!("Top {} Readers", self.limit)
+    }
+
+    fn render(&self, library: &Library) -> String {
+        let readers = library.most_active_readers(self.limit, None);
+        if readers.is_empty() {
+            return String::from("No completed loans yet.");
+        }
+        let mut out = String::new();
+        for (rank, reader) in readers.iter().enumerate() {
+            let _ = writeln!(out, "{}. {} ({} loans)", rank + 1, reader.member.name, reader.loans);
+        }
+        out
+    }
+
+    fn table(&self, library: &Library) -> Option<Table> {
+        let readers = library.most_active_readers(self.limit, None);
+        if readers.is_empty() {
+            return None;
+        }
+        let mut table = Table::new(&["Rank", "Member", "Loans"]);
+        for (rank, reader) in readers.iter().enumerate() {
+            table.push_row(vec![(rank + 1).to_string(), reader.member.name.clone(), reader.loans.to_string()]);
+        }
+        Some(table)
+    }
+}
+
+/// The catalogued books borrowed most often, ties in catalog order.
+#[derive(Debug, Clone, Copy)]
+pub struct MostBorrowed {
+    pub limit: usize,
+}
+
+impl Default for MostBorrowed {
+    fn default() -> Self {
+        MostBorrowed { limit: 10 }
+    }
+}
+
+impl MostBorrowed {
+    fn books<'a>(&self, library: &'a Library) -> Vec<&'a Book> {
+        let mut books: Vec<&Book> = library.catalog().filter(|b| b.times_borrowed() > 0).collect();
+        books.sort_by_key(|b| Reverse(b.times_borrowed()));
+        books.truncate(self.limit);
+        books
+    }
+}
+
+impl ReportSection for MostBorrowed {
+    fn title(&self) -> String {
+        String::from("Most Borrowed Books")
+    }
+
+    fn render(&self, library: &Library) -> String {
+        let books = self.books(library);
+        if books.is_empty() {
+            return String::from("No books borrowed yet.");
+        }
+        let mut out = String::new();
+        for (rank, book) in books.iter().enumerate() {
+            let _ = writeln!(out, "{}. {} ({} loans)", rank + 1, book.title, book.times_borrowed());
+        }
+        out
+    }
+
+    fn table(&self, library: &Library) -> Option<Table> {
+        let books = self.books(library);
+        if books.is_empty() {
+            return None;
+        }
+        let mut table = Table::new(&["Rank", "Book", "Title", "Loans"]);
+        for (rank, book) in books.iter().enumerate() {
+            table.push_row(vec![
+                (rank + 1).to_string(),
+                format!("#{}", book.id()),
+                book.title.clone(),
+                book.times_borrowed().to_string(),
+            ]);
+        }
+        Some(table)
+    }
+}
+
+/// Loans past their due date as of a given day, most overdue first.
+#[derive(Debug, Clone, Copy)]
+pub struct OverdueLoans {
+    pub as_of: NaiveDate,
+}
+
+impl OverdueLoans {
+    /// `(loan, book title, member name)` for each overdue loan.
+    fn overdue<'a>(&self, library: &'a Library) -> Vec<(&'a Loan, &'a str, &'a str)> {
+        let mut overdue: Vec<(&Loan, &str, &str)> = library
+            .loans()
+            .iter()
+            .filter(|loan| loan.days_overdue(self.as_of) > 0)
+            .map(|loan| {
+                let title = library.get_book(loan.book_id).map_or("?", |b| b.title.as_str());
+                let name = library.find_member(loan.member_id).map_or("?", |m| m.name.as_str());
+                (loan, title, name)
+            })
+            .collect();
+        overdue.sort_by_key(|(loan, _, _)| Reverse(loan.days_overdue(self.as_of)));
+        overdue
+    }
+}
+
+impl ReportSection for OverdueLoans {
+    fn title(&self) -> String {
+        format!("Overdue on {}", self.as_of)
+    }
+
+    fn render(&self, library: &Library) -> String {
+        let overdue = self.overdue(library);
+        if overdue.is_empty() {
+            return String::from("Nothing overdue.");
+        }
+        let mut out = String::new();
+        for (loan, title, name) in overdue {
+            let _ = writeln!(
+                out,
+                "{} (#{}) with {} (#{}): {} day(s) late",
+                title,
+                loan.book_id,
+                name,
+                loan.member_id,
+                loan.days_overdue(self.as_of)
+            );
+        }
+        out
+    }
+
+    fn table(&self, library: &Library) -> Option<Table> {
+        let overdue = self.overdue(library);
+        if overdue.is_empty() {
+            return None;
+        }
+        let mut table = Table::new(&["Book", "Title", "Member", "Name", "Due", "Days late"]);
+        for (loan, title, name) in overdue {
+            table.push_row(vec![
+                format!("#{}", loan.book_id),
+                title.to_string(),
+                format!("#{}", loan.member_id),
+                name.to_string(),
+                loan.due_on.to_string(),
+                loan.days_overdue(self.as_of).to_string(),
+            ]);
+        }
+        Some(table)
+    }
+}
+
+/// Returned books waiting to go back on their shelves, in the order of
+/// [`Library::shelving_list`].
+#[derive(Debug, Default, Clone, Copy)]
+pub struct ShelvingList;
+
+impl ReportSection for ShelvingList {
+    fn title(&self) -> String {
+        String::from("Shelving List")
+    }
+
+    fn render(&self, library: &Library) -> String {
+        let list = library.shelving_list();
+        if list.is_empty() {
+            return String::from("Nothing to shelve.");
+        }
+        let mut out = String::new();
+        for item in list {
+            let location = item.book.location.as_ref().map_or(String::from("No location"), |l| l.to_string());
+            let _ = writeln!(
+                out,
+                "{}: {} {} (#{}), returned {}",
+                location,
+                item.call_number,
+                item.book.title,
+                item.book.id(),
+                item.returned_on
+            );
+        }
+        out
+    }
+
+    fn table(&self, library: &Library) -> Option<Table> {
+        let list = library.shelving_list();
+        if list.is_empty() {
+            return None;
+        }
+        let mut table = Table::new(&["Branch", "Room", "Shelf", "Call number", "Book", "Title", "Returned"]);
+        for item in list {
+            let (branch, room, shelf) = match &item.book.location {
+                Some(l) => (l.branch.clone(), l.room.clone(), l.shelf.clone()),
+                None => (String::new(), String::new(), String::new()),
+            };
+            table.push_row(vec![
+                branch,
+                room,
+                shelf,
+                item.call_number.to_string(),
+                format!("#{}", item.book.id()),
+                item.book.title.clone(),
+                item.returned_on.to_string(),
+            ]);
+        }
+        Some(table)
+    }
+}
+
+/// The [`financials`] for a period: totals as text, monthly rows as a
+/// table.
+#[derive(Debug, Clone)]
+pub struct FeeSummary {
+    pub period: RangeInclusive<NaiveDate>,
+}
+
+impl ReportSection for FeeSummary {
+    fn title(&self) -> String {
+        format!("Fines, {} to {}", self.period.start(), self.period.end())
+    }
+
+    fn render(&self, library: &Library) -> String {
+        let summary = financials(library, self.period.clone());
+        format!(
+            "Assessed: {}\nWaived: {}\nCollected: {}\nOutstanding: {}",
+            summary.assessed, summary.waived, summary.collected, summary.outstanding
+        )
+    }
+
+    fn table(&self, library: &Library) -> Option<Table> {
+        Some(financials(library, self.period.clone()).table())
+    }
+}
+
+/// The sections used by [`Library::report`].
+pub fn standard_sections() -> Vec<Box<dyn ReportSection>> {
+    vec![Box::new(CatalogSummary), Box::new(TopReaders::default())]
+}
+
+// =============================================================================
+// LIBRARY REPORTS
+// =============================================================================
+
+/// A report with its sections rendered, ready to print as text, Markdown
+/// or HTML.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Report {
+    pub title: String,
+    pub sections: Vec<RenderedSection>,
+}
+
+/// One section of a [`Report`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct RenderedSection {
+    pub title: String,
+    /// The plain-text body, trailing whitespace trimmed.
+    pub text: String,
+    pub table: Option<Table>,
+}
+
+impl Report {
+    /// The plain-text report, as returned by [`Library::report_with`].
+    pub fn to_text(&self) -> String {
+        let mut out = format!("{}\n", self.title);
+        for section in &self.sections {
+            let underline = "-".repeat(section.title.chars().count());
+            let _ = write!(out, "\n{}\n{}\n{}\n", section.title, underline, section.text);
+        }
+        out
+    }
+
+    /// A Markdown document, e.g. for a wiki page.
+    pub fn to_markdown(&self) -> String {
+        let mut out = format!("# {}\n", self.title);
+        for section in &self.sections {
+            let body = match &section.table {
+                Some(table) => table.to_markdown(),
+                None => format!("```text\n{}\n```\n", section.text),
+            };
+            let _ = write!(out, "\n## {}\n\n{}", section.title, body);
+        }
+        out
+    }
+
+    /// An HTML fragment (no `<html>` or `<body>`) to embed in a page.
+    pub fn to_html(&self) -> String {
+        let mut out = format!("<h1>{}</h1>\n", html_escape(&self.title));
+        for section in &self.sections {
+            let body = match &section.table {
+                Some(table) => table.to_html(),
+                None => format!("<pre>{}</pre>\n", html_escape(&section.text)),
+            };
+            let _ = write!(out, "<h2>{}</h2>\n{}", html_escape(&section.title), body);
+        }
+        out
+    }
+}
+
+impl Library {
+    /// Renders the standard report.
+    pub fn report(&self) -> String {
+        self.report_with(&standard_sections())
+    }
+
+    /// Renders a report made of exactly `sections`, in order.
+    ///
+    /// Start from [`standard_sections`] to keep the built-in content.
+    pub fn report_with(&self, sections: &[Box<dyn ReportSection>]) -> String {
+        self.build_report(sections).to_text()
+    }
+
+    /// Renders each of `sections`, in order, into a [`Report`] that can be
+    /// printed in any of its formats.
+    pub fn build_report(&self, sections: &[Box<dyn ReportSection>]) -> Report {
+        Report {
+            title: format!("{} Report", self.name()),
+            sections: sections
+                .iter()
+                .map(|section| RenderedSection {
+                    title: section.title(),
+                    text: section.render(self).trim_end().to_string(),
+                    table: section.table(self),
+                })
+                .collect(),
+        }
+    }
+}
+
+// =============================================================================
+// FINANCIAL SUMMARY
+// =============================================================================
+
+/// One membership tier's fine activity in one month.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FinancialRow {
+    /// First day of the month.
+    pub month: NaiveDate,
+    /// Name of the tier the members have now.
+    pub tier: String,
+    pub assessed: Money,
+    pub waived: Money,
+    pub collected: Money,
+    /// Owed by the tier's members at the end of the month.
+    pub outstanding: Money,
+}
+
+/// A member whose balance doesn't match their fine ledger entries, e.g.
+/// because fines were charged before the ledger was kept.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Discrepancy {
+    pub member_id: u64,
+    /// What the member owes.
+    pub balance: Money,
+    /// What their ledger entries add up to; may be negative.
+    pub ledger_cents: i128,
+}
+
+/// Fines assessed, waived, collected and outstanding over a period, as
+/// returned by [`financials`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct Financials {
+    pub period: RangeInclusive<NaiveDate>,
+    /// By month, then tier name. Every tier with fine activity up to the
+    /// end of the period gets a row for every month.
+    pub rows: Vec<FinancialRow>,
+    pub assessed: Money,
+    pub waived: Money,
+    pub collected: Money,
+    /// Owed at the end of the period, according to the ledger.
+    pub outstanding: Money,
+    /// Members whose balance today disagrees with the whole ledger.
+    pub discrepancies: Vec<Discrepancy>,
+}
+
+/// Summarizes the library's [fine ledger](Library::fine_ledger) for the
+/// days in `period`, by month and membership tier, and checks the ledger
+/// against each member's current balance.
+///
+/// # Examples
+///
+/// ```
+/// use chrono::NaiveDate;
+/// use module_8::reports::financials;
+/// use module_8::{Book, Genre, Library, Member, MembershipTier, Money};
+///
+/// let mut lib = Library::new();
+/// lib.add_book(Book::new(1, "Dune", Genre::SciFi)).unwrap();
+/// lib.register_member(Member::new(10, "Alice", MembershipTier::Basic)).unwrap();
+/// let day = |m, d| NaiveDate::from_ymd_opt(2024, m, d).unwrap();
+///
+/// lib.checkout(10, 1, day(3, 1)).unwrap();
+/// lib.return_book(10, 1, day(3, 19)).unwrap(); // $1.00 late fee
+/// lib.pay_fines(10, Money::from_cents(60), day(4, 2)).unwrap();
+///
+/// let summary = financials(&lib, day(3, 1)..=day(4, 30));
+/// assert_eq!(summary.rows.len(), 2);
+/// assert_eq!(summary.rows[0].outstanding, Money::from_cents(100));
+/// assert_eq!(summary.rows[1].collected, Money::from_cents(60));
+/// assert_eq!(summary.outstanding, Money::from_cents(40));
+/// assert!(summary.discrepancies.is_empty());
+/// ```
+pub fn financials(library: &Library, period: RangeInclusive<NaiveDate>) -> Financials {
+    let tier_of = |member_id: u64| {
+        library
+            .find_member(member_id)
+            .map_or(String::from("Unknown"), |m| m.tier().name().to_string())
+    };
+    let (start, end) = (*period.start(), *period.end());
+    let up_to_end: Vec<&FineEntry> = library.fine_ledger().iter().filter(|e| e.on <= end).collect();
+    let tiers: BTreeSet<String> = up_to_end.iter().map(|e| tier_of(e.member_id)).collect();
+
+    let mut rows = Vec::new();
+    let mut month = start.with_day(1).expect("every month has a first day");
+    while month <= end {
+        let next = month + Months::new(1);
+        for tier in &tiers {
+            let of_tier = up_to_end.iter().filter(|e| &tier_of(e.member_id) == tier);
+            let in_month: Vec<&&FineEntry> = of_tier
+                .clone()
+                .filter(|e| e.on >= month.max(start) && e.on < next)
+                .collect();
+            let total =
+                |kind: FineKind| Money::saturating_sum(in_month.iter().filter(|e| e.kind == kind).map(|e| e.amount));
+            rows.push(FinancialRow {
+                month,
+                tier: tier.clone(),
+                assessed: total(FineKind::Assessed),
+                waived: total(FineKind::Waived),
+                collected: total(FineKind::Collected),
+                outstanding: balance(of_tier.filter(|e| e.on < next).copied()),
+            });
+        }
+        month = next;
+    }
+
+    let in_period: Vec<&FineEntry> = up_to_end.iter().copied().filter(|e| e.on >= start).collect();
+    let total = |kind: FineKind| Money::saturating_sum(in_period.iter().filter(|e| e.kind == kind).map(|e| e.amount));
+    let discrepancies = library
+        .members()
+        .filter_map(|member| {
+            let ledger_cents: i128 = library
+                .fine_ledger()
+                .iter()
+                .filter(|e| e.member_id == member.id())
+                .map(FineEntry::signed_cents)
+                .sum();
+            let balance = member.outstanding_fines();
+            (ledger_cents != i128::from(balance.cents())).then_some(Discrepancy {
+                member_id: member.id(),
+                balance,
+                ledger_cents,
+            })
+        })
+        .collect();
+
+    Financials {
+        assessed: total(FineKind::Assessed),
+        waived: total(FineKind::Waived),
+        collected: total(FineKind::Collected),
+        outstanding: balance(up_to_end.iter().copied()),
+        rows,
+        period,
+        discrepancies,
+    }
+}
+
+/// What `entries` leave owing, never below zero.
+fn balance<'a>(entries: impl Iterator<Item = &'a FineEntry>) -> Money {
+    let cents: i128 = entries.map(FineEntry::signed_cents).sum();
+    Money::from_cents(u64::try_from(cents.max(0)).unwrap_or(u64::MAX))
+}
+
+impl Financials {
+    /// Writes the monthly rows as CSV for a spreadsheet, amounts in major
+    /// units (`12.50`):
+    ///
+    /// ```text
+    /// month,tier,assessed,waived,collected,outstanding
+    /// 2024-03,Basic,1.00,0.00,0.00,1.00
+    /// ```
+    pub fn write_csv(&self, writer: impl io::Write) -> Result<(), csv::Error> {
+        let mut out = csv::Writer::from_writer(writer);
+        out.write_record(["month", "tier", "assessed", "waived", "collected", "outstanding"])?;
+        for row in &self.rows {
+            out.write_record([
+                row.month.format("%Y-%m").to_string(),
+                row.tier.clone(),
+                decimal(row.assessed),
+                decimal(row.waived),
+                decimal(row.collected),
+                decimal(row.outstanding),
+            ])?;
+        }
+        out.flush()?;
+        Ok(())
+    }
+
+    /// The monthly rows and a total row, amounts formatted as money.
+    pub fn table(&self) -> Table {
+        let mut table = Table::new(&["Month", "Tier", "Assessed", "Waived", "Collected", "Outstanding"]);
+        for row in &self.rows {
+            table.push_row(vec![
+                row.month.format("%Y-%m").to_string(),
+                row.tier.clone(),
+                row.assessed.to_string(),
+                row.waived.to_string(),
+                row.collected.to_string(),
+                row.outstanding.to_string(),
+            ]);
+        }
+        table.push_row(vec![
+            String::from("Total"),
+            String::new(),
+            self.assessed.to_string(),
+            self.waived.to_string(),
+            self.collected.to_string(),
+            self.outstanding.to_string(),
+        ]);
+        table
+    }
+}
+
+fn decimal(amount: Money) -> String {
+    format!("{}.{:02}", amount.cents() / 100, amount.cents() % 100)
+}
+
+// =============================================================================
+// TESTS
+// =============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::location::Location;
+    use crate::{Book, Genre, Member, MembershipTier};
+    use chrono::NaiveDate;
+
+    #[test]
+    fn test_standard_report() {
+        let mut lib = Library::new();
+        lib.add_book(Book::new(1, "Dune", Genre::SciFi)).unwrap();
+        lib.add_book(Book::new(2, "Emma", Genre::Fiction)).unwrap();
+        lib.register_member(Member::new(10, "Alice", MembershipTier::Basic)).unwrap();
+        let day = NaiveDate::from_ymd_opt(2024, 3, 1).unwrap();
+        lib.checkout(10, 1, day).unwrap();
+        lib.return_book(10, 1, day).unwrap();
+        lib.checkout(10, 2, day).unwrap();
+
+        let report = lib.report();
+        assert!(report.starts_with("Rustacean Library Report\n"));
+        assert!(report.contains("Catalog\n-------\nBooks on shelf: 1\nBooks on loan: 1\n"));
+        assert!(report.contains("Top 5 Readers\n-------------\n1. Alice (1 loans)\n"));
+    }
+
+    #[test]
+    fn test_financials_by_month_and_tier() {
+        let day = |m, d| NaiveDate::from_ymd_opt(2024, m, d).unwrap();
+        let mut lib = Library::new();
+        lib.add_book(Book::new(1, "Dune", Genre::SciFi)).unwrap();
+        lib.add_book(Book::new(2, "Emma", Genre::Fiction)).unwrap();
+        lib.register_member(Member::new(10, "Alice", MembershipTier::Basic)).unwrap();
+        lib.register_member(Member::new(11, "Bob", MembershipTier::Gold)).unwrap();
+        lib.checkout(10, 1, day(2, 1)).unwrap();
+        lib.return_book(10, 1, day(2, 17)).unwrap(); // $0.50, before the period
+        lib.checkout(11, 2, day(2, 1)).unwrap();
+        lib.return_book(11, 2, day(3, 7)).unwrap(); // 5 days late, 20% off: $1.00
+        lib.waive_fines(11, Money::from_cents(40), day(3, 8)).unwrap();
+        lib.pay_fines(10, Money::from_cents(50), day(4, 1)).unwrap();
+        lib.pay_fines(11, Money::from_cents(60), day(5, 1)).unwrap(); // after the period
+
+        let summary = financials(&lib, day(3, 1)..=day(4, 30));
+        let rows: Vec<(u32, &str, u64, u64, u64, u64)> = summary
+            .rows
+            .iter()
+            .map(|r| {
+                let cents = (r.assessed.cents(), r.waived.cents(), r.collected.cents(), r.outstanding.cents());
+                (r.month.month(), r.tier.as_str(), cents.0, cents.1, cents.2, cents.3)
+            })
+            .collect();
+        assert_eq!(
+            rows,
+            [
+                (3, "Basic", 0, 0, 0, 50),
+                (3, "Gold", 100, 40, 0, 60),
+                (4, "Basic", 0, 0, 50, 0),
+                (4, "Gold", 0, 0, 0, 60),
+            ]
+        );
+        assert_eq!(
+            (summary.assessed, summary.waived, summary.collected, summary.outstanding),
+            (Money::from_cents(100), Money::from_cents(40), Money::from_cents(50), Money::from_cents(60))
+        );
+        assert!(summary.discrepancies.is_empty());
+
+        let mut csv = Vec::new();
+        summary.write_csv(&mut csv).unwrap();
+        let csv = String::from_utf8(csv).unwrap();
+        assert!(csv.starts_with("month,tier,assessed,waived,collected,outstanding\n2024-03,Basic,0.00,0.00,0.00,0.50\n"));
+    }
+
+    #[test]
+    fn test_financials_flag_unledgered_balances() {
+        let mut lib = Library::new();
+        let mut alice = Member::new(10, "Alice", MembershipTier::Basic);
+        alice.charge(Money::from_cents(300));
+        lib.register_member(alice).unwrap();
+
+        let day = NaiveDate::from_ymd_opt(2024, 3, 1).unwrap();
+        let summary = financials(&lib, day..=day);
+        assert!(summary.rows.is_empty());
+        assert_eq!(
+            summary.discrepancies,
+            [Discrepancy { member_id: 10, balance: Money::from_cents(300), ledger_cents: 0 }]
+        );
+    }
+
+    #[test]
+    fn test_markdown_and_html_tables() {
+        let day = |d| NaiveDate::from_ymd_opt(2024, 3, d).unwrap();
+        let mut lib = Library::new();
+        lib.add_book(Book::new(1, "Dune", Genre::SciFi)).unwrap();
+        lib.add_book(Book::new(2, "Emma <Annotated>", Genre::Fiction)).unwrap();
+        lib.register_member(Member::new(10, "Alice", MembershipTier::Basic)).unwrap();
+        for _ in 0..2 {
+            lib.checkout(10, 2, day(1)).unwrap();
+            lib.return_book(10, 2, day(2)).unwrap();
+        }
+        lib.checkout(10, 1, day(1)).unwrap();
+
+        let sections: Vec<Box<dyn ReportSection>> = vec![
+            Box::new(MostBorrowed { limit: 5 }),
+            Box::new(OverdueLoans { as_of: day(20) }),
+            Box::new(FeeSummary { period: day(1)..=day(31) }),
+        ];
+        let report = lib.build_report(&sections);
+        let markdown = report.to_markdown();
+        assert!(markdown.starts_with("# Rustacean Library Report\n\n## Most Borrowed Books\n\n| Rank | Book | Title | Loans |\n"));
+        assert!(markdown.contains("| 1 | #2 | Emma <Annotated> | 2 |\n| 2 | #1 | Dune | 1 |\n"));
+        assert!(markdown.contains("## Overdue on 2024-03-20\n\n| Book | Title | Member | Name | Due | Days late |\n"));
+        assert!(markdown.contains("| #1 | Dune | #10 | Alice | 2024-03-15 | 5 |\n"));
+        assert!(markdown.contains("| Total |  | $0.00 | $0.00 | $0.00 | $0.00 |\n"));
+
+        let html = report.to_html();
+        assert!(html.starts_with("<h1>Rustacean Library Report</h1>\n<h2>Most Borrowed Books</h2>\n<table>\n"));
+        assert!(html.contains("<td>Emma &lt;Annotated&gt;</td>"));
+        assert!(report.to_text().contains("Dune (#1) with Alice (#10): 5 day(s) late\n"));
+
+        lib.return_book(10, 1, day(15)).unwrap();
+        let quiet = lib.build_report(&sections[1..2]);
+        assert_eq!(quiet.sections[0].table, None);
+        assert!(quiet.to_markdown().ends_with("```text\nNothing overdue.\n```\n"));
+    }
+
+    #[test]
+    fn test_shelving_list_section() {
+        let day = NaiveDate::from_ymd_opt(2024, 3, 1).unwrap();
+        let mut lib = Library::new();
+        lib.add_book(Book::new(1, "Dune", Genre::SciFi)).unwrap();
+        lib.add_book(Book::new(2, "Emma", Genre::Fiction)).unwrap();
+        lib.register_member(Member::new(10, "Alice", MembershipTier::Basic)).unwrap();
+        lib.move_book(1, Location::new("Main", "Hall", "SCI-3")).unwrap();
+        let sections: Vec<Box<dyn ReportSection>> = vec![Box::new(ShelvingList)];
+        assert!(lib.report_with(&sections).contains("Nothing to shelve."));
+
+        for id in [2, 1] {
+            lib.checkout(10, id, day).unwrap();
+            lib.return_book(10, id, day).unwrap();
+        }
+        let report = lib.build_report(&sections);
+        assert!(report.to_text().contains(
+            "Main / Hall / SCI-3: SCI DUN Dune (#1), returned 2024-03-01
+No location: FIC EMM Emma (#2), returned 2024-03-01
+"
+        ));
+        assert!(report.to_markdown().contains("| Main | Hall | SCI-3 | SCI DUN | #1 | Dune | 2024-03-01 |
+"));
+    }
+
+    #[test]
+    fn test_report_with_only_given_sections() {
+        let sections: Vec<Box<dyn ReportSection>> = vec![Box::new(TopReaders { limit: 3 })];
+        let report = Library::new().report_with(&sections);
+        assert!(!report.contains("Catalog"));
+        assert!(report.contains("Top 3 Readers\n-------------\nNo completed loans yet.\n"));
+    }
+}
@@ -0,0 +1,218 @@
+//! Reviews module - member ratings and short reviews of books.
+//!
+//! A member who has borrowed and returned a book may rate it from 1 to 5
+//! stars and add a short review with [`Library::rate`]. Rating the same book
+//! again replaces the member's earlier review. Each book keeps its
+//! [`average_rating`](Book::average_rating), which
+//! [`Library::top_rated_in_genre`] ranks by.
+//!
+//! ```
+//! use chrono::NaiveDate;
+//! use module_8::{Book, Genre, Library, Member, MembershipTier};
+//!
+//! let day = NaiveDate::from_ymd_opt(2024, 3, 1).unwrap();
+//! let mut lib = Library::new();
+//! lib.add_book(Book::new(1, "Dune", Genre::SciFi)).unwrap();
+//! lib.register_member(Member::new(10, "Alice", MembershipTier::Basic)).unwrap();
+//! assert!(lib.rate(10, 1, 5, "Haven't read it").is_err());
+//!
+//! lib.checkout(10, 1, day).unwrap();
+//! lib.return_book(10, 1, day).unwrap();
+//! lib.rate(10, 1, 4, "Slow start, great ending").unwrap();
+//! assert_eq!(lib.find_book(1).unwrap().average_rating(), Some(4.0));
+//! assert_eq!(lib.top_rated_in_genre(&Genre::SciFi, 3)[0].id(), 1);
+//! ```
+
+use std::fmt;
+
+use crate::book::{Book, Genre};
+use crate::error::LibraryError;
+use crate::validation::{validate_review, ValidationError};
+use crate::Library;
+
+/// Ratings run from 1 to 5 stars.
+pub const RATINGS: std::ops::RangeInclusive<u8> = 1..=5;
+
+/// One member's rating and review of a book.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Review {
+    pub member_id: u64,
+    pub book_id: u64,
+    /// 1 to 5 stars.
+    pub rating: u8,
+    /// Empty for a rating without a review.
+    pub text: String,
+}
+
+// =============================================================================
+// ERRORS
+// =============================================================================
+
+/// Why a rating was refused.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ReviewError {
+    /// The rating isn't in [`RATINGS`].
+    InvalidRating(u8),
+    /// Only members who have returned the book may review it.
+    NotBorrowed { member_id: u64, book_id: u64 },
+    InvalidText(ValidationError),
+    /// The member or book doesn't exist.
+    Library(LibraryError),
+}
+
+impl fmt::Display for ReviewError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ReviewError::InvalidRating(rating) => write!(f, "rating {} is not between 1 and 5", rating),
+            ReviewError::NotBorrowed { member_id, book_id } => {
+                write!(f, "member {} has not borrowed book {}", member_id, book_id)
+            }
+            ReviewError::InvalidText(e) => e.fmt(f),
+            ReviewError::Library(e) => e.fmt(f),
+        }
+    }
+}
+
+impl std::error::Error for ReviewError {}
+
+impl From<LibraryError> for ReviewError {
+    fn from(e: LibraryError) -> Self {
+        ReviewError::Library(e)
+    }
+}
+
+// =============================================================================
+// LIBRARY INTEGRATION
+// =============================================================================
+
+impl Library {
+    /// Records a member's rating and review of a book they've returned,
+    /// replacing any they gave it before.
+    ///
+    /// # Errors
+    ///
+    /// [`ReviewError::InvalidRating`] and [`ReviewError::InvalidText`] for
+    /// bad input, [`ReviewError::NotBorrowed`] if the member has no returned
+    /// loan of the book, and [`ReviewError::Library`] if the member isn't
+    /// registered or the book isn't catalogued.
+    pub fn rate(&mut self, member_id: u64, book_id: u64, rating: u8, text: &str) -> Result<(), ReviewError> {
+        if !RATINGS.contains(&rating) {
+            return Err(ReviewError::InvalidRating(rating));
+        }
+        validate_review(text).map_err(ReviewError::InvalidText)?;
+        let member = self.find_member(member_id).ok_or(LibraryError::MemberNotFound(member_id))?;
+        if !self.index.contains(book_id) {
+            return Err(LibraryError::BookNotFound(book_id).into());
+        }
+        if !member.history().iter().any(|record| record.book_id == book_id) {
+            return Err(ReviewError::NotBorrowed { member_id, book_id });
+        }
+        self.record_review(Review { member_id, book_id, rating, text: text.trim().to_string() })?;
+        Ok(())
+    }
+
+    /// Every review, oldest first.
+    pub fn reviews(&self) -> &[Review] {
+        &self.reviews
+    }
+
+    /// The reviews of one book, oldest first.
+    pub fn reviews_of(&self, book_id: u64) -> Vec<&Review> {
+        self.reviews.iter().filter(|r| r.book_id == book_id).collect()
+    }
+
+    /// Up to `limit` rated books of `genre`, highest average rating first;
+    /// equal averages keep catalog order.
+    pub fn top_rated_in_genre(&self, genre: &Genre, limit: usize) -> Vec<&Book> {
+        let mut rated: Vec<(f64, &Book)> = self
+            .catalog()
+            .filter(|book| &book.genre == genre)
+            .filter_map(|book| Some((book.average_rating()?, book)))
+            .collect();
+        rated.sort_by(|a, b| b.0.total_cmp(&a.0));
+        rated.into_iter().take(limit).map(|(_, book)| book).collect()
+    }
+
+    /// Adds a review and counts its rating on the book, replacing the
+    /// member's earlier review of it. Doesn't check who may review.
+    pub(crate) fn record_review(&mut self, review: Review) -> Result<(), LibraryError> {
+        let previous = self
+            .reviews
+            .iter()
+            .position(|r| r.member_id == review.member_id && r.book_id == review.book_id);
+        let previous_rating = previous.map(|i| self.reviews[i].rating);
+        let book = self.book_mut(review.book_id).ok_or(LibraryError::BookNotFound(review.book_id))?;
+        book.add_rating(review.rating, previous_rating);
+        if let Some(i) = previous {
+            self.reviews.remove(i);
+        }
+        self.reviews.push(review);
+        Ok(())
+    }
+}
+
+// =============================================================================
+// TESTS
+// =============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Member, MembershipTier};
+    use chrono::NaiveDate;
+
+    fn date(day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(2024, 3, day).unwrap()
+    }
+
+    fn library() -> Library {
+        let mut lib = Library::new();
+        lib.add_book(Book::new(1, "Dune", Genre::SciFi)).unwrap();
+        lib.add_book(Book::new(2, "Foundation", Genre::SciFi)).unwrap();
+        lib.add_book(Book::new(3, "Emma", Genre::Fiction)).unwrap();
+        lib.register_member(Member::new(10, "Alice", MembershipTier::Basic)).unwrap();
+        lib.register_member(Member::new(11, "Bob", MembershipTier::Basic)).unwrap();
+        for (member_id, book_id) in [(10, 1), (11, 1), (10, 2), (10, 3)] {
+            lib.checkout(member_id, book_id, date(1)).unwrap();
+            lib.return_book(member_id, book_id, date(2)).unwrap();
+        }
+        lib
+    }
+
+    #[test]
+    fn test_only_borrowers_may_rate() {
+        let mut lib = library();
+        assert_eq!(lib.rate(10, 1, 0, ""), Err(ReviewError::InvalidRating(0)));
+        assert_eq!(lib.rate(10, 1, 6, ""), Err(ReviewError::InvalidRating(6)));
+        assert_eq!(lib.rate(11, 2, 5, ""), Err(ReviewError::NotBorrowed { member_id: 11, book_id: 2 }));
+        assert_eq!(lib.rate(12, 1, 5, ""), Err(ReviewError::Library(LibraryError::MemberNotFound(12))));
+        assert!(matches!(lib.rate(10, 1, 5, &"x".repeat(501)), Err(ReviewError::InvalidText(_))));
+
+        // Alice may review it while Bob has it, but Bob not until he returns it
+        lib.checkout(11, 2, date(3)).unwrap();
+        lib.rate(10, 2, 3, "").unwrap();
+        assert_eq!(lib.get_book(2).unwrap().average_rating(), Some(3.0));
+        assert_eq!(lib.rate(11, 2, 5, ""), Err(ReviewError::NotBorrowed { member_id: 11, book_id: 2 }));
+    }
+
+    #[test]
+    fn test_rerating_replaces_and_ranks_by_average() {
+        let mut lib = library();
+        lib.rate(10, 1, 5, "Loved it").unwrap();
+        lib.rate(11, 1, 2, "  Too long  ").unwrap();
+        lib.rate(10, 2, 4, "").unwrap();
+        assert_eq!(lib.find_book(1).unwrap().average_rating(), Some(3.5));
+        assert_eq!(lib.reviews_of(1)[1].text, "Too long");
+
+        lib.rate(10, 1, 1, "Changed my mind").unwrap();
+        let dune = lib.find_book(1).unwrap();
+        assert_eq!((dune.average_rating(), dune.rating_count()), (Some(1.5), 2));
+        assert_eq!(lib.reviews().len(), 3);
+
+        let top: Vec<u64> = lib.top_rated_in_genre(&Genre::SciFi, 5).iter().map(|b| b.id()).collect();
+        assert_eq!(top, [2, 1]);
+        assert!(lib.top_rated_in_genre(&Genre::Fiction, 5).is_empty());
+        assert_eq!(lib.top_rated_in_genre(&Genre::SciFi, 1).len(), 1);
+    }
+}
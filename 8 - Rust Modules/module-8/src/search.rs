@@ -0,0 +1,316 @@
+//! Search module - typo-tolerant and wildcard title search.
+//!
+//! [`Library::find_by_title`] only finds exact (normalized) titles. Patrons
+//! misspell things, so [`Library::search_fuzzy`] compares each word of the
+//! query with the closest word of every title by [`levenshtein`] distance
+//! and ranks the books by how well the query matched.
+//!
+//! In between, [`Library::find_books_glob`] matches whole titles against a
+//! [`GlobPattern`], where `*` stands for any run of characters and `?` for
+//! exactly one: `rust*` finds titles starting with "rust", `*rust` ending
+//! with it and `*rust*` containing it.
+//!
+//! ```
+//! use module_8::{Book, Genre, Library};
+//!
+//! let mut lib = Library::new();
+//! lib.add_book(Book::new(1, "The Rust Programming Language", Genre::Technical)).unwrap();
+//! lib.add_book(Book::new(2, "Programming Pearls", Genre::Technical)).unwrap();
+//! lib.add_book(Book::new(3, "Dune", Genre::SciFi)).unwrap();
+//!
+//! let hits = lib.search_fuzzy("rust programing", 5);
+//! assert_eq!(hits[0].book.id(), 1);
+//! assert!(hits.iter().all(|hit| hit.book.id() != 3));
+//!
+//! let ids: Vec<u64> = lib.find_books_glob("*programming*").iter().map(|b| b.id()).collect();
+//! assert_eq!(ids, [1, 2]);
+//! ```
+
+use std::time::Instant;
+
+use crate::book::Book;
+use crate::index::normalize_title;
+use crate::Library;
+
+/// Lowest score [`Library::search_fuzzy`] reports. At 0.6 a five-letter
+/// word may have two typos.
+pub const MIN_FUZZY_SCORE: f64 = 0.6;
+
+// =============================================================================
+// SCORING
+// =============================================================================
+
+/// A book and how closely its title matched, from [`MIN_FUZZY_SCORE`] up to
+/// 1.0 for a title containing every query word exactly.
+#[derive(Debug, Clone, Copy)]
+pub struct FuzzyMatch<'a> {
+    pub book: &'a Book,
+    pub score: f64,
+}
+
+/// The number of single-character insertions, deletions and substitutions
+/// that turn `a` into `b`.
+///
+/// # Examples
+///
+/// ```
+/// use module_8::search::levenshtein;
+///
+/// assert_eq!(levenshtein("kitten", "sitting"), 3);
+/// assert_eq!(levenshtein("Dune", "Dune"), 0);
+/// assert_eq!(levenshtein("", "abc"), 3);
+/// ```
+pub fn levenshtein(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    // One row of the edit-distance table at a time
+    let mut row: Vec<usize> = (0..=b.len()).collect();
+    for (i, ca) in a.chars().enumerate() {
+        let mut diagonal = row[0];
+        row[0] = i + 1;
+        for (j, &cb) in b.iter().enumerate() {
+            let substitution = diagonal + usize::from(ca != cb);
+            diagonal = row[j + 1];
+            row[j + 1] = substitution.min(row[j] + 1).min(diagonal + 1);
+        }
+    }
+    row[b.len()]
+}
+
+// =============================================================================
+// GLOB PATTERNS
+// =============================================================================
+
+/// One piece of a compiled [`GlobPattern`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum GlobToken {
+    /// `*`: any run of characters, including none.
+    Any,
+    /// `?`: exactly one character.
+    One,
+    Char(char),
+}
+
+/// A wildcard pattern, compiled once and matched against many titles.
+///
+/// `*` matches any run of characters and `?` exactly one; everything else
+/// matches itself, ignoring case. The whole title must match, so a pattern
+/// without wildcards only finds that exact title.
+///
+/// # Examples
+///
+/// ```
+/// use module_8::search::GlobPattern;
+///
+/// let pattern = GlobPattern::new("rust*");
+/// assert!(pattern.matches("Rust in Action"));
+/// assert!(!pattern.matches("Programming Rust"));
+/// assert!(GlobPattern::new("d?ne").matches("Dune"));
+/// assert!(GlobPattern::new("*").matches(""));
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct GlobPattern {
+    tokens: Vec<GlobToken>,
+}
+
+impl GlobPattern {
+    pub fn new(pattern: &str) -> GlobPattern {
+        let mut tokens = Vec::new();
+        for c in pattern.chars().flat_map(char::to_lowercase) {
+            let token = match c {
+                '*' => GlobToken::Any,
+                '?' => GlobToken::One,
+                c => GlobToken::Char(c),
+            };
+            // `**` means the same as `*`
+            if !(token == GlobToken::Any && tokens.last() == Some(&GlobToken::Any)) {
+                tokens.push(token);
+            }
+        }
+        GlobPattern { tokens }
+    }
+
+    /// Whether all of `text` matches the pattern, ignoring case.
+    pub fn matches(&self, text: &str) -> bool {
+        let text: Vec<char> = text.chars().flat_map(char::to_lowercase).collect();
+        let (mut t, mut p) = (0, 0);
+        // The last `*` seen and where in the text it started matching, so a
+        // failed match can backtrack by letting that `*` swallow one more
+        let mut backtrack: Option<(usize, usize)> = None;
+        while t < text.len() {
+            match self.tokens.get(p) {
+                Some(GlobToken::Any) => {
+                    backtrack = Some((p, t));
+                    p += 1;
+                }
+                Some(GlobToken::One) => {
+                    t += 1;
+                    p += 1;
+                }
+                Some(&GlobToken::Char(c)) if c == text[t] => {
+                    t += 1;
+                    p += 1;
+                }
+                _ => match backtrack {
+                    Some((star, start)) => {
+                        backtrack = Some((star, start + 1));
+                        p = star + 1;
+                        t = start + 1;
+                    }
+                    None => return false,
+                },
+            }
+        }
+        self.tokens[p..].iter().all(|&token| token == GlobToken::Any)
+    }
+}
+
+/// 1.0 for equal words down to 0.0 for nothing in common.
+fn word_similarity(a: &str, b: &str) -> f64 {
+    let longest = a.chars().count().max(b.chars().count());
+    if longest == 0 {
+        return 1.0;
+    }
+    1.0 - levenshtein(a, b) as f64 / longest as f64
+}
+
+/// How well `title` matches the query words: each word's best match among
+/// the title's words, averaged.
+fn score(query: &[&str], title: &str) -> f64 {
+    let title = normalize_title(title);
+    let words: Vec<&str> = title.split(' ').collect();
+    let total: f64 = query
+        .iter()
+        .map(|q| words.iter().map(|w| word_similarity(q, w)).fold(0.0, f64::max))
+        .sum();
+    total / query.len() as f64
+}
+
+// =============================================================================
+// LIBRARY INTEGRATION
+// =============================================================================
+
+impl Library {
+    /// Up to `max_results` catalogued books whose titles resemble `query`,
+    /// best match first; equal scores keep catalog order (shelf, then on
+    /// loan). Case and punctuation are ignored, and the order of the query
+    /// words doesn't matter.
+    pub fn search_fuzzy(&self, query: &str, max_results: usize) -> Vec<FuzzyMatch<'_>> {
+        let started = Instant::now();
+        let query = normalize_title(query);
+        let words: Vec<&str> = query.split(' ').filter(|w| !w.is_empty()).collect();
+        let mut matches: Vec<FuzzyMatch<'_>> = if words.is_empty() {
+            Vec::new()
+        } else {
+            self.catalog()
+                .map(|book| FuzzyMatch { book, score: score(&words, &book.title) })
+                .filter(|m| m.score >= MIN_FUZZY_SCORE)
+                .collect()
+        };
+        matches.sort_by(|a, b| b.score.total_cmp(&a.score));
+        matches.truncate(max_results);
+        self.metrics.record_search(started.elapsed());
+        matches
+    }
+
+    /// Catalogued books whose titles match the wildcard `pattern` (see
+    /// [`GlobPattern`]), in catalog order. Stricter than
+    /// [`search_fuzzy`](Library::search_fuzzy), looser than
+    /// [`find_by_title`](Library::find_by_title); unlike both, punctuation
+    /// in the pattern must match.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use module_8::{Book, Genre, Library};
+    ///
+    /// let mut lib = Library::new();
+    /// lib.add_book(Book::new(1, "Rust in Action", Genre::Technical)).unwrap();
+    /// lib.add_book(Book::new(2, "Programming Rust", Genre::Technical)).unwrap();
+    ///
+    /// assert_eq!(lib.find_books_glob("rust*")[0].id(), 1);
+    /// assert_eq!(lib.find_books_glob("*rust").len(), 1);
+    /// assert_eq!(lib.find_books_glob("*rust*").len(), 2);
+    /// ```
+    pub fn find_books_glob(&self, pattern: &str) -> Vec<&Book> {
+        let started = Instant::now();
+        let pattern = GlobPattern::new(pattern);
+        let found = self.catalog().filter(|book| pattern.matches(&book.title)).collect();
+        self.metrics.record_search(started.elapsed());
+        found
+    }
+}
+
+// =============================================================================
+// TESTS
+// =============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Genre, Member, MembershipTier};
+    use chrono::NaiveDate;
+
+    #[test]
+    fn test_levenshtein() {
+        assert_eq!(levenshtein("flaw", "lawn"), 2);
+        assert_eq!(levenshtein("abc", ""), 3);
+        assert_eq!(levenshtein("café", "cafe"), 1);
+        assert_eq!(word_similarity("dune", "dnue"), 0.5);
+    }
+
+    #[test]
+    fn test_search_fuzzy_ranks_and_limits() {
+        let mut lib = Library::new();
+        lib.add_book(Book::new(1, "Foundation and Empire", Genre::SciFi)).unwrap();
+        lib.add_book(Book::new(2, "Foundation", Genre::SciFi)).unwrap();
+        lib.add_book(Book::new(3, "Second Foundation", Genre::SciFi)).unwrap();
+        lib.add_book(Book::new(4, "The Fountainhead", Genre::Fiction)).unwrap();
+        lib.add_book(Book::new(5, "Emma", Genre::Fiction)).unwrap();
+        lib.register_member(Member::new(10, "Alice", MembershipTier::Basic)).unwrap();
+        lib.checkout(10, 2, NaiveDate::from_ymd_opt(2024, 3, 1).unwrap()).unwrap();
+
+        let hits = lib.search_fuzzy("  FOUNDATON!", 10);
+        let ids: Vec<u64> = hits.iter().map(|h| h.book.id()).collect();
+        // Equal scores: shelf books in catalog order, then the one on loan
+        assert_eq!(ids, [1, 3, 2]);
+        // One typo in ten letters
+        assert!(hits.iter().all(|h| (h.score - 0.9).abs() < 1e-9));
+
+        assert_eq!(lib.search_fuzzy("empire foundation", 10)[0].score, 1.0);
+        assert_eq!(lib.search_fuzzy("foundation", 1).len(), 1);
+        assert!(lib.search_fuzzy("xyz", 10).is_empty());
+        assert!(lib.search_fuzzy("  ", 10).is_empty());
+    }
+
+    #[test]
+    fn test_glob_patterns() {
+        let matches = |pattern: &str, text: &str| GlobPattern::new(pattern).matches(text);
+        assert!(matches("foundation*", "Foundation and Empire"));
+        assert!(matches("*FOUNDATION", "Second Foundation"));
+        assert!(matches("*and*", "Foundation and Empire"));
+        assert!(matches("f?und*n", "Foundation"));
+        assert!(matches("*a*a*", "banana"));
+        assert!(matches("**", ""));
+        assert!(matches("caf?", "Café"));
+        assert!(!matches("dune", "Dune Messiah"));
+        assert!(!matches("d?ne", "Dne"));
+        assert!(!matches("*a*b", "banana"));
+        assert!(!matches("", "Dune"));
+    }
+
+    #[test]
+    fn test_find_books_glob() {
+        let mut lib = Library::new();
+        lib.add_book(Book::new(1, "Foundation and Empire", Genre::SciFi)).unwrap();
+        lib.add_book(Book::new(2, "Foundation", Genre::SciFi)).unwrap();
+        lib.add_book(Book::new(3, "Second Foundation", Genre::SciFi)).unwrap();
+        lib.register_member(Member::new(10, "Alice", MembershipTier::Basic)).unwrap();
+        lib.checkout(10, 1, NaiveDate::from_ymd_opt(2024, 3, 1).unwrap()).unwrap();
+
+        let ids = |pattern| lib.find_books_glob(pattern).iter().map(|b| b.id()).collect::<Vec<_>>();
+        assert_eq!(ids("foundation*"), [2, 1]);
+        assert_eq!(ids("*foundation"), [2, 3]);
+        assert_eq!(ids("*"), [2, 3, 1]);
+        assert!(ids("foundation").len() == 1 && ids("empire").is_empty());
+    }
+}
@@ -0,0 +1,260 @@
+//! Serde support, compiled only with the `serde` cargo feature.
+//!
+//! Plain data types derive `Serialize` and `Deserialize` where they are
+//! defined. This module holds the hand-written parts:
+//!
+//! - A member's tier is a `TierPolicy` trait object, so it is written as its
+//!   name and read back as a [`MembershipTier`]. Custom tiers can't be
+//!   serialized, for the same reason [`Library::save`] refuses them.
+//! - A [`Library`] is written as its data: config, books, members, loans,
+//!   holds, fee accruals, the fine ledger, interlibrary loans, reviews and
+//!   the re-shelving queue. Fee and hold policies, the
+//!   calendar, the genre tree, notifiers, status rules and the audit log are
+//!   behaviour or session state and are left out; a deserialized library starts with
+//!   the defaults for its config, like one from [`Library::load`].
+
+use std::collections::HashSet;
+use std::sync::Arc;
+
+use serde::de::Error as _;
+use serde::ser::Error as _;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+use crate::accrual::FeeAccrual;
+use crate::book::Book;
+use crate::circulation::{Hold, Loan};
+use crate::config::LibraryConfig;
+use crate::ill::IllRequest;
+use crate::reviews::Review;
+use crate::ledger::FineEntry;
+use crate::location::AwaitingShelving;
+use crate::lost::LostBook;
+use crate::member::{is_built_in, parse_tier, Member, TierPolicy};
+use crate::reading_lists::Collection;
+use crate::Library;
+
+// =============================================================================
+// MEMBER TIERS
+// =============================================================================
+
+/// `#[serde(with = ...)]` helpers for `Member::tier`.
+pub(crate) mod tier {
+    use super::*;
+
+    pub(crate) fn serialize<S: Serializer>(tier: &Arc<dyn TierPolicy>, serializer: S) -> Result<S::Ok, S::Error> {
+        if !is_built_in(tier.as_ref()) {
+            return Err(S::Error::custom(format!("custom tier {} can't be serialized", tier.name())));
+        }
+        serializer.serialize_str(tier.name())
+    }
+
+    pub(crate) fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Arc<dyn TierPolicy>, D::Error> {
+        let name = String::deserialize(deserializer)?;
+        parse_tier(&name).map_err(D::Error::custom)
+    }
+}
+
+// =============================================================================
+// LIBRARY
+// =============================================================================
+
+/// A library's data, borrowed for serializing.
+#[derive(Serialize)]
+#[serde(rename = "Library")]
+struct LibraryRef<'a> {
+    config: &'a LibraryConfig,
+    books: Vec<&'a Book>,
+    members: Vec<&'a Member>,
+    loans: &'a [Loan],
+    holds: &'a [Hold],
+    fee_accruals: &'a [FeeAccrual],
+    fine_ledger: &'a [FineEntry],
+    ill_requests: &'a [IllRequest],
+    reviews: &'a [Review],
+    awaiting_shelving: &'a [AwaitingShelving],
+    lost_books: &'a [LostBook],
+    collections: &'a [Collection],
+}
+
+/// A library's data, owned after deserializing.
+#[derive(Deserialize)]
+#[serde(rename = "Library")]
+struct LibraryData {
+    config: LibraryConfig,
+    books: Vec<Book>,
+    members: Vec<Member>,
+    #[serde(default)]
+    loans: Vec<Loan>,
+    #[serde(default)]
+    holds: Vec<Hold>,
+    #[serde(default)]
+    fee_accruals: Vec<FeeAccrual>,
+    #[serde(default)]
+    fine_ledger: Vec<FineEntry>,
+    #[serde(default)]
+    ill_requests: Vec<IllRequest>,
+    #[serde(default)]
+    reviews: Vec<Review>,
+    #[serde(default)]
+    awaiting_shelving: Vec<AwaitingShelving>,
+    #[serde(default)]
+    lost_books: Vec<LostBook>,
+    #[serde(default)]
+    collections: Vec<Collection>,
+}
+
+impl Serialize for Library {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        LibraryRef {
+            config: &self.config,
+            books: self.catalog().collect(),
+            members: self.members().collect(),
+            loans: &self.loans,
+            holds: &self.holds,
+            fee_accruals: &self.fee_accruals,
+            fine_ledger: &self.fine_ledger,
+            ill_requests: &self.ill_requests,
+            reviews: &self.reviews,
+            awaiting_shelving: &self.reshelving,
+            lost_books: &self.lost_books,
+            collections: &self.collections,
+        }
+        .serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for Library {
+    /// Rebuilds the library and its indexes, failing on duplicate IDs or
+    /// data that contradicts itself (see
+    /// [`check_invariants`](Library::check_invariants)).
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let data = LibraryData::deserialize(deserializer)?;
+
+        let mut member_ids = HashSet::new();
+        let mut book_ids = HashSet::new();
+        for member in &data.members {
+            if !member_ids.insert(member.id()) {
+                return Err(D::Error::custom(format!("duplicate member {}", member.id())));
+            }
+        }
+        for book in &data.books {
+            if !book_ids.insert(book.id()) {
+                return Err(D::Error::custom(format!("duplicate book {}", book.id())));
+            }
+        }
+        let mut library = Library::with_config(data.config);
+        library.check_capacity(book_ids.len(), member_ids.len()).map_err(D::Error::custom)?;
+        for book in data.books {
+            library.insert_book(book);
+        }
+        for member in data.members {
+            library.insert_member(member);
+        }
+        library.restore_loans(data.loans).map_err(D::Error::custom)?;
+        library.holds = data.holds;
+        library.fee_accruals = data.fee_accruals;
+        library.fine_ledger = data.fine_ledger;
+        if let Some(request) = data.ill_requests.iter().find(|r| library.find_member(r.member_id).is_none()) {
+            return Err(D::Error::custom(format!(
+                "interlibrary loan {} for unknown member {}",
+                request.id, request.member_id
+            )));
+        }
+        library.ill_requests = data.ill_requests;
+        // Books carry their own rating totals, so reviews are kept as they are
+        library.reviews = data.reviews;
+        if let Some(entry) = data.awaiting_shelving.iter().find(|e| !book_ids.contains(&e.book_id)) {
+            return Err(D::Error::custom(format!("re-shelving of unknown book {}", entry.book_id)));
+        }
+        library.reshelving = data.awaiting_shelving;
+        if let Some(lost) = data.lost_books.iter().find(|l| book_ids.contains(&l.book.id())) {
+            return Err(D::Error::custom(format!("lost book {} is also catalogued", lost.book.id())));
+        }
+        library.lost_books = data.lost_books;
+        let mut names = HashSet::new();
+        for collection in &data.collections {
+            if !names.insert(collection.name.as_str()) {
+                return Err(D::Error::custom(format!("duplicate collection {:?}", collection.name)));
+            }
+            let known = |id: &u64| book_ids.contains(id) || library.lost_books.iter().any(|l| l.book.id() == *id);
+            if let Some(id) = collection.book_ids().iter().find(|id| !known(id)) {
+                return Err(D::Error::custom(format!("collection {:?} lists unknown book {}", collection.name, id)));
+            }
+        }
+        library.collections = data.collections;
+        library.check_invariants().map_err(D::Error::custom)?;
+        Ok(library)
+    }
+}
+
+// =============================================================================
+// TESTS
+// =============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Genre, LibraryError, MembershipTier};
+    use chrono::NaiveDate;
+
+    #[test]
+    fn test_library_round_trips_through_json() {
+        let day = NaiveDate::from_ymd_opt(2024, 3, 1).unwrap();
+        let mut lib = Library::new();
+        lib.add_book(Book::new(1, "Dune", Genre::SciFi)).unwrap();
+        lib.add_book(Book::new(2, "Emma", Genre::Fiction)).unwrap();
+        lib.register_member(Member::new(10, "Alice", MembershipTier::Gold)).unwrap();
+        lib.register_member(Member::new(11, "Bob", MembershipTier::Basic)).unwrap();
+        lib.checkout(10, 1, day).unwrap();
+        lib.place_hold(11, 1, day).unwrap();
+
+        let json = serde_json::to_string(&lib).unwrap();
+        let loaded: Library = serde_json::from_str(&json).unwrap();
+        assert_eq!(loaded.find_book(2).unwrap().title, "Emma");
+        assert_eq!(loaded.find_member(10).unwrap().tier().name(), "Gold");
+        assert_eq!(loaded.loans(), lib.loans());
+        assert_eq!(loaded.check_invariants(), Ok(()));
+        assert_eq!(serde_json::to_string(&loaded).unwrap(), json);
+
+        let err: LibraryError = serde_json::from_str(r#"{"BookNotFound": 7}"#).unwrap();
+        assert_eq!(err, LibraryError::BookNotFound(7));
+    }
+
+    #[test]
+    fn test_inconsistent_or_custom_data_is_refused() {
+        #[derive(Debug)]
+        struct Faculty;
+        impl TierPolicy for Faculty {
+            fn name(&self) -> &str {
+                "Faculty"
+            }
+            fn borrow_limit(&self) -> usize {
+                50
+            }
+            fn loan_days(&self) -> u32 {
+                120
+            }
+            fn discount_percentage(&self) -> u8 {
+                100
+            }
+        }
+        let prof = Member::new(1, "Dr. Ferris", Faculty);
+        assert!(serde_json::to_string(&prof).unwrap_err().to_string().contains("custom tier Faculty"));
+
+        let mut lib = Library::new();
+        lib.add_book(Book::new(1, "Dune", Genre::SciFi)).unwrap();
+        let mut value = serde_json::to_value(&lib).unwrap();
+        let book = value["books"][0].clone();
+        value["books"].as_array_mut().unwrap().push(book);
+        let err = serde_json::from_value::<Library>(value).err().map(|e| e.to_string());
+        assert_eq!(err.as_deref(), Some("duplicate book 1"));
+
+        lib.register_member(Member::new(10, "Alice", MembershipTier::Basic)).unwrap();
+        let mut value = serde_json::to_value(&lib).unwrap();
+        value["loans"] = serde_json::json!([
+            { "book_id": 2, "member_id": 10, "borrowed_on": "2024-03-01", "due_on": "2024-03-15", "renewals": 0 }
+        ]);
+        let err = serde_json::from_value::<Library>(value).err().map(|e| e.to_string());
+        assert_eq!(err.as_deref(), Some("No book with ID 2"));
+    }
+}
@@ -0,0 +1,199 @@
+//! Shared module - a `Library` that many threads can use at once.
+//!
+//! `Library` itself is `Send + Sync`: every policy and notifier it stores is
+//! required to be, and the rest is plain owned data. What it lacks is
+//! interior synchronization, since mutating methods take `&mut self`.
+//! [`SharedLibrary`] adds that with a single `RwLock`: lookups run in
+//! parallel, while checkouts, returns and holds run one at a time, so
+//! each is atomic with respect to all the others.
+//!
+//! A single lock rather than one per collection is deliberate: a checkout
+//! touches the shelf, the member, the loan list, the holds and the catalog
+//! index together, and per-collection locks would need a global lock order
+//! to avoid deadlocks for no real gain at library scale.
+
+use std::sync::{Arc, RwLock, RwLockReadGuard, RwLockWriteGuard};
+
+use chrono::NaiveDate;
+
+use crate::error::LibraryError;
+use crate::money::Money;
+use crate::Library;
+
+// Compile-time check that the claim above stays true as fields are added
+const _: () = {
+    const fn assert_send_sync<T: Send + Sync>() {}
+    assert_send_sync::<Library>();
+};
+
+/// A cloneable, thread-safe handle to one `Library`.
+///
+/// Clones share the same library. Notifiers run while the write lock is
+/// held, so they must not call back into the same `SharedLibrary`.
+///
+/// # Panics
+///
+/// If a thread panics while holding the write lock, the lock is poisoned
+/// and every later call panics too, rather than exposing a library that
+/// may have been left half-updated.
+///
+/// # Examples
+///
+/// ```
+/// use std::thread;
+///
+/// use chrono::NaiveDate;
+/// use module_8::shared::SharedLibrary;
+/// use module_8::{Book, Genre, Library, Member, MembershipTier};
+///
+/// let mut library = Library::new();
+/// library.add_book(Book::new(1, "Dune", Genre::SciFi)).unwrap();
+/// library.register_member(Member::new(10, "Alice", MembershipTier::Basic)).unwrap();
+/// let shared = SharedLibrary::new(library);
+///
+/// let worker = shared.clone();
+/// let day = NaiveDate::from_ymd_opt(2024, 3, 1).unwrap();
+/// thread::spawn(move || worker.checkout(10, 1, day).unwrap())
+///     .join()
+///     .unwrap();
+///
+/// assert_eq!(shared.read(|lib| lib.loans().len()), 1);
+/// ```
+#[derive(Clone)]
+pub struct SharedLibrary {
+    inner: Arc<RwLock<Library>>,
+}
+
+impl SharedLibrary {
+    /// Wraps a library for sharing between threads.
+    pub fn new(library: Library) -> Self {
+        SharedLibrary {
+            inner: Arc::new(RwLock::new(library)),
+        }
+    }
+
+    /// Runs `f` with shared access; other readers may run at the same time.
+    pub fn read<R>(&self, f: impl FnOnce(&Library) -> R) -> R {
+        f(&self.read_guard())
+    }
+
+    /// Runs `f` with exclusive access, so several operations can be
+    /// combined into one atomic step.
+    pub fn write<R>(&self, f: impl FnOnce(&mut Library) -> R) -> R {
+        f(&mut self.write_guard())
+    }
+
+    /// [`Library::checkout`] under the write lock.
+    pub fn checkout(&self, member_id: u64, book_id: u64, today: NaiveDate) -> Result<NaiveDate, LibraryError> {
+        self.write_guard().checkout(member_id, book_id, today)
+    }
+
+    /// [`Library::return_book`] under the write lock.
+    pub fn return_book(&self, member_id: u64, book_id: u64, today: NaiveDate) -> Result<Money, LibraryError> {
+        self.write_guard().return_book(member_id, book_id, today)
+    }
+
+    /// [`Library::place_hold`] under the write lock.
+    pub fn place_hold(&self, member_id: u64, book_id: u64, today: NaiveDate) -> Result<(), LibraryError> {
+        self.write_guard().place_hold(member_id, book_id, today)
+    }
+
+    /// Takes the library back out, if this is the last handle.
+    pub fn into_inner(self) -> Option<Library> {
+        let lock = Arc::into_inner(self.inner)?;
+        Some(lock.into_inner().expect("library lock poisoned"))
+    }
+
+    fn read_guard(&self) -> RwLockReadGuard<'_, Library> {
+        self.inner.read().expect("library lock poisoned")
+    }
+
+    fn write_guard(&self) -> RwLockWriteGuard<'_, Library> {
+        self.inner.write().expect("library lock poisoned")
+    }
+}
+
+impl From<Library> for SharedLibrary {
+    fn from(library: Library) -> Self {
+        SharedLibrary::new(library)
+    }
+}
+
+// =============================================================================
+// TESTS
+// =============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::thread;
+
+    use crate::{Book, Genre, Member, MembershipTier};
+
+    fn date(day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(2024, 3, day).unwrap()
+    }
+
+    #[test]
+    fn test_concurrent_checkouts_of_one_book() {
+        let mut library = Library::new();
+        library.add_book(Book::new(1, "Dune", Genre::SciFi)).unwrap();
+        for id in 1..=8 {
+            library.register_member(Member::new(id, "Reader", MembershipTier::Basic)).unwrap();
+        }
+        let shared = SharedLibrary::new(library);
+
+        let handles: Vec<_> = (1..=8)
+            .map(|member_id| {
+                let shared = shared.clone();
+                thread::spawn(move || shared.checkout(member_id, 1, date(1)).is_ok())
+            })
+            .collect();
+        let winners = handles.into_iter().map(|h| h.join().unwrap()).filter(|&won| won).count();
+
+        assert_eq!(winners, 1);
+        let library = shared.into_inner().expect("all workers finished");
+        assert_eq!(library.loans().len(), 1);
+        assert_eq!(library.check_invariants(), Ok(()));
+    }
+
+    #[test]
+    fn test_readers_see_writes_atomically() {
+        let mut library = Library::new();
+        for id in 1..=50 {
+            library.add_book(Book::new(id, "Book", Genre::Fiction)).unwrap();
+        }
+        library.register_member(Member::new(10, "Alice", MembershipTier::Platinum)).unwrap();
+        let shared = SharedLibrary::new(library);
+
+        let writer = {
+            let shared = shared.clone();
+            thread::spawn(move || {
+                for round in 0..20 {
+                    let book_id = round % 5 + 1;
+                    shared.checkout(10, book_id, date(1)).unwrap();
+                    shared.return_book(10, book_id, date(1)).unwrap();
+                }
+            })
+        };
+        let readers: Vec<_> = (0..4)
+            .map(|_| {
+                let shared = shared.clone();
+                thread::spawn(move || {
+                    for _ in 0..100 {
+                        // A checkout is never seen half-done
+                        shared.read(|lib| {
+                            assert_eq!(lib.book_count() + lib.loans().len(), 50);
+                        });
+                    }
+                })
+            })
+            .collect();
+
+        writer.join().unwrap();
+        for reader in readers {
+            reader.join().unwrap();
+        }
+        assert_eq!(shared.read(|lib| lib.book_count()), 50);
+    }
+}
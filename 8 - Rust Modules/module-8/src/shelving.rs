@@ -0,0 +1,228 @@
+//! Shelving module - call numbers, shelving order and shelf reading.
+//!
+//! Every book gets a [`CallNumber`] made of its genre's shelf code (the same
+//! one printed on [labels](crate::labels)), a cutter from the author's
+//! surname and the publication year. Shelves are kept in call-number order;
+//! shelf reading walks a shelf with a scanner and reports the copies that
+//! are out of sequence.
+//!
+//! ```
+//! use module_8::shelving::CallNumber;
+//! use module_8::{Book, Genre};
+//!
+//! let mut book = Book::new(42, "Dune", Genre::SciFi);
+//! book.author = Some(String::from("Frank Herbert"));
+//! book.year = Some(1965);
+//! assert_eq!(CallNumber::of(&book).to_string(), "SCI HER 1965");
+//! ```
+
+use std::fmt;
+
+use crate::book::Book;
+use crate::labels::{cutter, genre_code};
+use crate::Library;
+
+// =============================================================================
+// CALL NUMBERS
+// =============================================================================
+
+/// Where a book belongs on the shelves.
+///
+/// Call numbers sort in shelving order: by shelf code, then cutter, then
+/// year, with undated books before dated ones.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct CallNumber {
+    /// Shelf code for the genre, e.g. `SCI`.
+    pub class: String,
+    /// First letters of the author's surname (or of the title).
+    pub cutter: String,
+    pub year: Option<u16>,
+}
+
+impl CallNumber {
+    /// The call number for a book, from its current genre, author and year.
+    pub fn of(book: &Book) -> CallNumber {
+        CallNumber {
+            class: genre_code(&book.genre),
+            cutter: cutter(book),
+            year: book.year,
+        }
+    }
+}
+
+impl fmt::Display for CallNumber {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} {}", self.class, self.cutter)?;
+        if let Some(year) = self.year {
+            write!(f, " {}", year)?;
+        }
+        Ok(())
+    }
+}
+
+// =============================================================================
+// SHELF READING
+// =============================================================================
+
+/// A copy found out of sequence during shelf reading.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Misplaced {
+    pub book_id: u64,
+    pub call_number: CallNumber,
+    /// The correctly placed copy it should be moved after, or `None` to
+    /// move it to the start of the shelf.
+    pub belongs_after: Option<u64>,
+}
+
+/// The outcome of [`Library::read_shelf`].
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ShelfReading {
+    /// The fewest copies that need moving, in scanned order.
+    pub misplaced: Vec<Misplaced>,
+    /// Scanned IDs that aren't on this library's shelves (on loan, removed
+    /// or from another library), in scanned order.
+    pub unknown: Vec<u64>,
+}
+
+impl Library {
+    /// Books on the shelf in shelving sequence: by call number, then title,
+    /// then ID.
+    pub fn shelf_order(&self) -> Vec<&Book> {
+        let mut books: Vec<(CallNumber, &Book)> = self.books().map(|b| (CallNumber::of(b), b)).collect();
+        books.sort_by(|(a_call, a), (b_call, b)| {
+            a_call
+                .cmp(b_call)
+                .then_with(|| a.title.cmp(&b.title))
+                .then_with(|| a.id().cmp(&b.id()))
+        });
+        books.into_iter().map(|(_, book)| book).collect()
+    }
+
+    /// Compares the order copies were scanned in against
+    /// [`shelf_order`](Library::shelf_order).
+    ///
+    /// The copies left in place are the longest run already in shelving
+    /// sequence, so `misplaced` lists as few moves as possible.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use module_8::{Book, Genre, Library};
+    ///
+    /// let mut lib = Library::new();
+    /// for (id, title) in [(1, "Anathem"), (2, "Blindsight"), (3, "Contact"), (4, "Dune")] {
+    ///     lib.add_book(Book::new(id, title, Genre::SciFi)).unwrap();
+    /// }
+    ///
+    /// // Dune was shelved between Anathem and Blindsight
+    /// let reading = lib.read_shelf(&[1, 4, 2, 3, 99]);
+    /// assert_eq!(reading.misplaced.len(), 1);
+    /// assert_eq!((reading.misplaced[0].book_id, reading.misplaced[0].belongs_after), (4, Some(3)));
+    /// assert_eq!(reading.unknown, [99]);
+    /// ```
+    pub fn read_shelf(&self, scanned: &[u64]) -> ShelfReading {
+        let expected = self.shelf_order();
+        let rank = |id: u64| expected.iter().position(|b| b.id() == id);
+
+        let mut reading = ShelfReading::default();
+        let mut found: Vec<(usize, u64)> = Vec::new();
+        for &id in scanned {
+            match rank(id) {
+                Some(r) => found.push((r, id)),
+                None => reading.unknown.push(id),
+            }
+        }
+
+        let keep = longest_increasing(&found);
+        let mut in_place: Vec<usize> = keep.iter().map(|&i| found[i].0).collect();
+        in_place.sort_unstable();
+        for (i, &(r, id)) in found.iter().enumerate() {
+            if keep.binary_search(&i).is_ok() {
+                continue;
+            }
+            let before = in_place.partition_point(|&kept| kept < r);
+            reading.misplaced.push(Misplaced {
+                book_id: id,
+                call_number: CallNumber::of(expected[r]),
+                belongs_after: before.checked_sub(1).map(|p| expected[in_place[p]].id()),
+            });
+        }
+        reading
+    }
+}
+
+/// Positions in `items` of a longest run with strictly increasing ranks,
+/// in ascending order (patience sorting, O(n log n)).
+fn longest_increasing(items: &[(usize, u64)]) -> Vec<usize> {
+    // tails[k]: index of the smallest last rank of any run of length k + 1
+    let mut tails: Vec<usize> = Vec::new();
+    let mut previous: Vec<Option<usize>> = vec![None; items.len()];
+    for (i, &(rank, _)) in items.iter().enumerate() {
+        let k = tails.partition_point(|&t| items[t].0 < rank);
+        previous[i] = k.checked_sub(1).map(|p| tails[p]);
+        if k == tails.len() {
+            tails.push(i);
+        } else {
+            tails[k] = i;
+        }
+    }
+
+    let mut run = Vec::new();
+    let mut next = tails.last().copied();
+    while let Some(i) = next {
+        run.push(i);
+        next = previous[i];
+    }
+    run.reverse();
+    run
+}
+
+// =============================================================================
+// TESTS
+// =============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Genre;
+
+    fn book(id: u64, title: &str, genre: Genre, author: &str, year: Option<u16>) -> Book {
+        let mut book = Book::new(id, title, genre);
+        book.author = Some(String::from(author));
+        book.year = year;
+        book
+    }
+
+    fn library() -> Library {
+        let mut lib = Library::new();
+        lib.add_book(book(1, "Foundation", Genre::SciFi, "Isaac Asimov", Some(1951))).unwrap();
+        lib.add_book(book(2, "Emma", Genre::Fiction, "Jane Austen", Some(1815))).unwrap();
+        lib.add_book(book(3, "I, Robot", Genre::SciFi, "Isaac Asimov", Some(1950))).unwrap();
+        lib.add_book(book(4, "Persuasion", Genre::Fiction, "Jane Austen", None)).unwrap();
+        lib.add_book(book(5, "Dune", Genre::SciFi, "Frank Herbert", Some(1965))).unwrap();
+        lib
+    }
+
+    #[test]
+    fn test_shelf_order_follows_call_numbers() {
+        let lib = library();
+        let order: Vec<u64> = lib.shelf_order().iter().map(|b| b.id()).collect();
+        assert_eq!(order, [4, 2, 3, 1, 5]);
+        assert_eq!(CallNumber::of(lib.find_book(4).unwrap()).to_string(), "FIC AUS");
+    }
+
+    #[test]
+    fn test_read_shelf_finds_fewest_moves() {
+        let lib = library();
+        assert_eq!(lib.read_shelf(&[4, 2, 3, 1, 5]), ShelfReading::default());
+
+        // Dune at the front, Emma after Foundation
+        let reading = lib.read_shelf(&[5, 4, 3, 1, 2]);
+        let moves: Vec<(u64, Option<u64>)> = reading.misplaced.iter().map(|m| (m.book_id, m.belongs_after)).collect();
+        assert_eq!(moves, [(5, Some(1)), (2, Some(4))]);
+
+        let reading = lib.read_shelf(&[2, 4]);
+        assert_eq!(reading.misplaced[0].book_id, 2);
+        assert_eq!(reading.misplaced[0].belongs_after, Some(4));
+    }
+}
@@ -0,0 +1,433 @@
+//! Simulation module - try circulation policies before adopting them.
+//!
+//! [`simulate`] replays synthetic demand against a [`LibrarySnapshot`]
+//! under a [`SimulationPolicy`] and reports projected availability, fine
+//! revenue and hold wait times. Demand is drawn from the snapshot's own
+//! history:
+//!
+//! - **Borrow rate**: past loans per day, from the earliest recorded
+//!   borrow date up to the snapshot date.
+//! - **Who and what**: members and books are picked in proportion to how
+//!   often they borrowed / were borrowed (plus one, so nobody is excluded).
+//! - **How long**: each simulated borrower keeps the book for a duration
+//!   sampled from past loans. Shorter loan periods therefore mean more
+//!   overdue days and more fines.
+//!
+//! Loans already out in the snapshot come back on their due date. Digital
+//! copies are left out, since they never run short. The random generator
+//! is seeded, so the same inputs always give the same report.
+
+use std::collections::BTreeMap;
+
+use chrono::{Duration, NaiveDate};
+
+use crate::circulation::{FifoHolds, Hold, HoldQueuePolicy};
+use crate::config::fees::LATE_FEE_PER_DAY;
+use crate::fee_policy::{FeePolicy, FlatFeePolicy};
+use crate::item::Format;
+use crate::member::Member;
+use crate::money::Money;
+use crate::snapshot::LibrarySnapshot;
+
+// =============================================================================
+// POLICY AND REPORT
+// =============================================================================
+
+/// The policies to evaluate.
+#[derive(Debug)]
+pub struct SimulationPolicy {
+    pub fee_policy: Box<dyn FeePolicy>,
+    pub hold_policy: Box<dyn HoldQueuePolicy>,
+    /// Loan period for every member; `None` keeps each tier's own period.
+    pub loan_days: Option<u32>,
+}
+
+impl Default for SimulationPolicy {
+    /// The policies a fresh `Library` uses.
+    fn default() -> Self {
+        SimulationPolicy {
+            fee_policy: Box::new(FlatFeePolicy { per_day: LATE_FEE_PER_DAY }),
+            hold_policy: Box::new(FifoHolds),
+            loan_days: None,
+        }
+    }
+}
+
+/// Projected outcomes over the simulated horizon.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SimulationReport {
+    pub horizon_days: u32,
+    /// Borrow requests generated.
+    pub requests: u32,
+    /// Loans started (directly or by serving a hold).
+    pub loans: u32,
+    /// Requests that found the book out and joined its hold queue.
+    pub holds_placed: u32,
+    /// Mean share of the catalog on the shelf at the end of each day (0.0-1.0).
+    pub average_availability: f64,
+    /// Late fees charged on loans returned within the horizon.
+    pub fine_revenue: Money,
+    /// Mean days from placing a hold to getting the book, over served holds.
+    pub average_wait_days: f64,
+    pub max_wait_days: u32,
+    /// Holds still waiting when the horizon ends.
+    pub unserved_holds: usize,
+}
+
+// =============================================================================
+// RANDOM NUMBERS
+// =============================================================================
+
+/// SplitMix64: tiny, fast and good enough for demand sampling. Also used
+/// by [`fixtures`](crate::fixtures) to generate test data.
+pub(crate) struct Rng(pub(crate) u64);
+
+impl Rng {
+    fn next_u64(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+
+    /// Uniform in [0, 1).
+    fn next_f64(&mut self) -> f64 {
+        (self.next_u64() >> 11) as f64 / (1u64 << 53) as f64
+    }
+
+    /// Uniform in 0..n (n > 0).
+    pub(crate) fn below(&mut self, n: u64) -> u64 {
+        self.next_u64() % n
+    }
+
+    /// Poisson-distributed count with mean `lambda` (Knuth's method; large
+    /// means are split into chunks so `exp(-lambda)` doesn't underflow).
+    fn poisson(&mut self, mut lambda: f64) -> u32 {
+        let mut count = 0;
+        while lambda > 0.0 {
+            let chunk = lambda.min(30.0);
+            lambda -= chunk;
+            let limit = (-chunk).exp();
+            let mut product = self.next_f64();
+            while product > limit {
+                count += 1;
+                product *= self.next_f64();
+            }
+        }
+        count
+    }
+
+    /// Picks an index with probability proportional to its weight.
+    fn weighted(&mut self, cumulative: &[u64]) -> usize {
+        let target = self.below(*cumulative.last().expect("non-empty weights"));
+        cumulative.partition_point(|&c| c <= target)
+    }
+}
+
+// =============================================================================
+// DEMAND MODEL
+// =============================================================================
+
+/// Rates and distributions estimated from the snapshot's history.
+struct Demand {
+    borrows_per_day: f64,
+    keep_days: Vec<i64>,
+    book_ids: Vec<u64>,
+    book_weights: Vec<u64>,
+    member_slots: Vec<usize>,
+    member_weights: Vec<u64>,
+}
+
+impl Demand {
+    fn from_snapshot(snapshot: &LibrarySnapshot) -> Demand {
+        let mut borrow_dates: Vec<NaiveDate> = snapshot.loans.iter().map(|l| l.borrowed_on).collect();
+        let mut keep_days = Vec::new();
+        let mut book_counts: BTreeMap<u64, u64> = BTreeMap::new();
+        let mut member_counts: BTreeMap<u64, u64> = BTreeMap::new();
+
+        for loan in &snapshot.loans {
+            *book_counts.entry(loan.book_id).or_default() += 1;
+            *member_counts.entry(loan.member_id).or_default() += 1;
+        }
+        for member in &snapshot.members {
+            for record in member.history() {
+                let Some(borrowed_on) = record.borrowed_on else { continue };
+                borrow_dates.push(borrowed_on);
+                keep_days.push((record.returned_on - borrowed_on).num_days().max(0));
+                *book_counts.entry(record.book_id).or_default() += 1;
+                *member_counts.entry(member.id()).or_default() += 1;
+            }
+        }
+
+        let borrows_per_day = match borrow_dates.iter().min() {
+            Some(&first) => {
+                let window = (snapshot.taken_on - first).num_days().max(1);
+                borrow_dates.len() as f64 / window as f64
+            }
+            None => 0.0,
+        };
+
+        let mut book_ids = Vec::new();
+        let mut book_weights = Vec::new();
+        let mut total = 0;
+        for book in snapshot.catalog().filter(|b| b.format == Format::Physical) {
+            total += 1 + book_counts.get(&book.id()).copied().unwrap_or(0);
+            book_ids.push(book.id());
+            book_weights.push(total);
+        }
+
+        let mut member_slots = Vec::new();
+        let mut member_weights = Vec::new();
+        let mut total = 0;
+        for (slot, member) in snapshot.members.iter().enumerate() {
+            if member.is_active() && !member.is_expired(snapshot.taken_on) {
+                total += 1 + member_counts.get(&member.id()).copied().unwrap_or(0);
+                member_slots.push(slot);
+                member_weights.push(total);
+            }
+        }
+
+        Demand {
+            borrows_per_day,
+            keep_days,
+            book_ids,
+            book_weights,
+            member_slots,
+            member_weights,
+        }
+    }
+}
+
+// =============================================================================
+// SIMULATION
+// =============================================================================
+
+struct SimLoan {
+    member_slot: usize,
+    due_day: i64,
+    return_day: i64,
+}
+
+struct SimHold {
+    member_slot: usize,
+    placed_day: i64,
+}
+
+/// Runs `horizon_days` of synthetic circulation from `snapshot` under `policy`.
+///
+/// # Examples
+///
+/// ```
+/// use chrono::NaiveDate;
+/// use module_8::simulation::{simulate, SimulationPolicy};
+/// use module_8::Library;
+///
+/// let lib = Library::new();
+/// let snapshot = lib.snapshot(NaiveDate::from_ymd_opt(2024, 3, 1).unwrap());
+/// let report = simulate(&snapshot, &SimulationPolicy::default(), 30, 7);
+/// assert_eq!(report.requests, 0); // no history, no demand
+/// ```
+pub fn simulate(
+    snapshot: &LibrarySnapshot,
+    policy: &SimulationPolicy,
+    horizon_days: u32,
+    seed: u64,
+) -> SimulationReport {
+    let demand = Demand::from_snapshot(snapshot);
+    let members: &[Member] = &snapshot.members;
+    let slot_of = |id: u64| members.iter().position(|m| m.id() == id);
+    let date_of = |day: i64| snapshot.taken_on + Duration::days(day);
+    let mut rng = Rng(seed);
+
+    let mut report = SimulationReport {
+        horizon_days,
+        requests: 0,
+        loans: 0,
+        holds_placed: 0,
+        average_availability: 0.0,
+        fine_revenue: Money::ZERO,
+        average_wait_days: 0.0,
+        max_wait_days: 0,
+        unserved_holds: 0,
+    };
+
+    // BTreeMaps keep iteration order, and so results, deterministic
+    let mut on_loan: BTreeMap<u64, SimLoan> = BTreeMap::new();
+    let mut queues: BTreeMap<u64, Vec<SimHold>> = BTreeMap::new();
+    let mut borrowed: Vec<usize> = vec![0; members.len()];
+    let digital = |book_id: u64| {
+        snapshot
+            .books
+            .iter()
+            .any(|b| b.id() == book_id && b.format == Format::Digital)
+    };
+    for loan in snapshot.loans.iter().filter(|l| !digital(l.book_id)) {
+        if let Some(member_slot) = slot_of(loan.member_id) {
+            let due_day = (loan.due_on - snapshot.taken_on).num_days();
+            on_loan.insert(loan.book_id, SimLoan { member_slot, due_day, return_day: due_day.max(1) });
+            borrowed[member_slot] += 1;
+        }
+    }
+    for hold in &snapshot.holds {
+        if let Some(member_slot) = slot_of(hold.member_id) {
+            let placed_day = (hold.placed_on - snapshot.taken_on).num_days();
+            queues.entry(hold.book_id).or_default().push(SimHold { member_slot, placed_day });
+        }
+    }
+
+    let mut waits: Vec<i64> = Vec::new();
+    let mut available_total = 0.0;
+    let catalog_size = demand.book_ids.len();
+
+    let lend = |on_loan: &mut BTreeMap<u64, SimLoan>, rng: &mut Rng, book_id: u64, member_slot: usize, day: i64| {
+        let member = &members[member_slot];
+        let loan_days = i64::from(policy.loan_days.unwrap_or_else(|| member.tier().loan_days()));
+        let keep = if demand.keep_days.is_empty() {
+            loan_days
+        } else {
+            demand.keep_days[rng.below(demand.keep_days.len() as u64) as usize]
+        };
+        on_loan.insert(book_id, SimLoan { member_slot, due_day: day + loan_days, return_day: day + keep.max(1) });
+    };
+
+    for day in 1..=i64::from(horizon_days) {
+        // 1. Returns, then hand the book to the next hold in the queue
+        let returning: Vec<u64> = on_loan
+            .iter()
+            .filter(|(_, loan)| loan.return_day == day)
+            .map(|(&book_id, _)| book_id)
+            .collect();
+        for book_id in returning {
+            let loan = on_loan.remove(&book_id).expect("collected above");
+            let member = &members[loan.member_slot];
+            let days_overdue = (loan.return_day - loan.due_day).max(0) as u32;
+            let fee = member.effective_fee(policy.fee_policy.late_fee(days_overdue));
+            let capped = snapshot.config.fee_cap(member.tier()).map_or(fee, |cap| fee.min(cap));
+            report.fine_revenue = report.fine_revenue.saturating_add(capped);
+            borrowed[loan.member_slot] -= 1;
+
+            let Some(queue) = queues.get_mut(&book_id) else { continue };
+            let next = queue
+                .iter()
+                .enumerate()
+                .min_by_key(|(_, waiting)| {
+                    let hold = Hold {
+                        book_id,
+                        member_id: members[waiting.member_slot].id(),
+                        placed_on: date_of(waiting.placed_day),
+                        ready_since: None,
+                    };
+                    policy.hold_policy.queue_key(&hold, &members[waiting.member_slot])
+                })
+                .map(|(pos, _)| pos);
+            if let Some(pos) = next {
+                let served = queue.remove(pos);
+                waits.push(day - served.placed_day);
+                borrowed[served.member_slot] += 1;
+                report.loans += 1;
+                lend(&mut on_loan, &mut rng, book_id, served.member_slot, day);
+            }
+        }
+
+        // 2. New borrow requests
+        if !demand.member_slots.is_empty() && catalog_size > 0 {
+            for _ in 0..rng.poisson(demand.borrows_per_day) {
+                report.requests += 1;
+                let member_slot = demand.member_slots[rng.weighted(&demand.member_weights)];
+                let book_id = demand.book_ids[rng.weighted(&demand.book_weights)];
+                let queue = queues.entry(book_id).or_default();
+                let already_has = on_loan.get(&book_id).is_some_and(|l| l.member_slot == member_slot);
+                if already_has || queue.iter().any(|h| h.member_slot == member_slot) {
+                    continue;
+                }
+                if on_loan.contains_key(&book_id) {
+                    queue.push(SimHold { member_slot, placed_day: day });
+                    report.holds_placed += 1;
+                } else if borrowed[member_slot] < members[member_slot].max_books() {
+                    borrowed[member_slot] += 1;
+                    report.loans += 1;
+                    lend(&mut on_loan, &mut rng, book_id, member_slot, day);
+                }
+            }
+        }
+
+        // 3. End-of-day availability
+        if catalog_size > 0 {
+            available_total += (catalog_size - on_loan.len()) as f64 / catalog_size as f64;
+        }
+    }
+
+    if horizon_days > 0 && catalog_size > 0 {
+        report.average_availability = available_total / f64::from(horizon_days);
+    }
+    if !waits.is_empty() {
+        report.average_wait_days = waits.iter().sum::<i64>() as f64 / waits.len() as f64;
+        report.max_wait_days = *waits.iter().max().expect("non-empty") as u32;
+    }
+    report.unserved_holds = queues.values().map(Vec::len).sum();
+    report
+}
+
+// =============================================================================
+// TESTS
+// =============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Book, Genre, Library, MembershipTier};
+
+    /// Sixty days of history: every loan is kept 20 days (6 days late for Basic).
+    fn busy_snapshot() -> LibrarySnapshot {
+        let start = NaiveDate::from_ymd_opt(2024, 1, 1).unwrap();
+        let mut lib = Library::new();
+        for id in 1..=3 {
+            lib.add_book(Book::new(id, "Book", Genre::Fiction)).unwrap();
+        }
+        for id in 10..=12 {
+            lib.register_member(Member::new(id, "Reader", MembershipTier::Basic)).unwrap();
+        }
+        for round in 0..3 {
+            let out = start + Duration::days(round * 20);
+            for (member, book) in [(10, 1), (11, 2), (12, 3)] {
+                lib.checkout(member, book, out).unwrap();
+                lib.return_book(member, book, out + Duration::days(20)).unwrap();
+            }
+        }
+        lib.snapshot(start + Duration::days(60))
+    }
+
+    #[test]
+    fn test_same_seed_same_report() {
+        let snapshot = busy_snapshot();
+        let policy = SimulationPolicy::default();
+        let a = simulate(&snapshot, &policy, 90, 42);
+        let b = simulate(&snapshot, &policy, 90, 42);
+        assert_eq!(a, b);
+        assert!(a.requests > 0);
+        assert!(a.average_availability > 0.0 && a.average_availability <= 1.0);
+    }
+
+    #[test]
+    fn test_longer_loans_mean_no_fines() {
+        let snapshot = busy_snapshot();
+        let strict = simulate(&snapshot, &SimulationPolicy::default(), 120, 1);
+        let lenient = SimulationPolicy {
+            loan_days: Some(30),
+            ..SimulationPolicy::default()
+        };
+        let relaxed = simulate(&snapshot, &lenient, 120, 1);
+
+        assert!(!strict.fine_revenue.is_zero());
+        assert_eq!(relaxed.fine_revenue, Money::ZERO);
+    }
+
+    #[test]
+    fn test_empty_library() {
+        let snapshot = Library::new().snapshot(NaiveDate::from_ymd_opt(2024, 1, 1).unwrap());
+        let report = simulate(&snapshot, &SimulationPolicy::default(), 10, 0);
+        assert_eq!(report.requests, 0);
+        assert_eq!(report.average_availability, 0.0);
+    }
+}
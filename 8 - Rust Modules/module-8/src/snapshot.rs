@@ -0,0 +1,234 @@
+//! Snapshot module - a point-in-time copy of a library's data.
+//!
+//! A [`LibrarySnapshot`] is plain data: it owns clones of every book,
+//! member, loan and hold, plus the fine ledger, fee accruals, interlibrary
+//! loans and reviews, so it can be kept, compared or analysed while the
+//! live `Library` carries on changing. Policies and notifiers are behaviour,
+//! not data, and are not captured.
+//!
+//! [`Library::restore`] puts a snapshot's data back, which makes snapshots
+//! handy for trying something out and rolling it back:
+//!
+//! ```
+//! use chrono::NaiveDate;
+//! use module_8::{Book, Genre, Library, Member, MembershipTier};
+//!
+//! let day = NaiveDate::from_ymd_opt(2024, 3, 1).unwrap();
+//! let mut lib = Library::new();
+//! lib.add_book(Book::new(1, "Dune", Genre::SciFi)).unwrap();
+//! lib.register_member(Member::new(10, "Alice", MembershipTier::Basic)).unwrap();
+//! lib.checkout(10, 1, day).unwrap();
+//!
+//! let before = lib.snapshot(day);
+//! let fee = lib.return_book(10, 1, NaiveDate::from_ymd_opt(2024, 4, 1).unwrap()).unwrap();
+//! assert!(fee.cents() > 0);
+//! lib.restore(before).unwrap();
+//! assert_eq!(lib.loans().len(), 1);
+//! assert!(lib.find_member(10).unwrap().outstanding_fines().is_zero());
+//! ```
+//!
+//! # Cost
+//!
+//! There is no structural sharing: both directions are deep copies.
+//! [`Library::snapshot`] clones every book, member (with their history),
+//! loan, hold and ledger entry, and [`Library::restore`] rebuilds the lookup
+//! indexes from the snapshot, so each is O(n) in the size of the library.
+//! Taking one per what-if run is fine; taking one per operation is not (see
+//! [`undo`](crate::undo) for that).
+
+use std::collections::HashSet;
+
+use chrono::NaiveDate;
+
+use crate::accrual::FeeAccrual;
+use crate::book::Book;
+use crate::circulation::{Hold, Loan};
+use crate::config::LibraryConfig;
+use crate::error::LibraryError;
+use crate::ill::IllRequest;
+use crate::item::Format;
+use crate::ledger::FineEntry;
+use crate::location::AwaitingShelving;
+use crate::lost::LostBook;
+use crate::member::Member;
+use crate::reading_lists::Collection;
+use crate::reviews::Review;
+use crate::undo::CommandHistory;
+use crate::Library;
+
+/// A library's books, members, loans and holds as of one day.
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct LibrarySnapshot {
+    /// The day the snapshot describes.
+    pub taken_on: NaiveDate,
+    pub config: LibraryConfig,
+    /// Every catalogued book: those on the shelf in catalog order, then
+    /// those out on loan.
+    pub books: Vec<Book>,
+    /// Members in registration order, with the IDs of the books they're
+    /// borrowing and their history.
+    pub members: Vec<Member>,
+    pub loans: Vec<Loan>,
+    pub holds: Vec<Hold>,
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub fee_accruals: Vec<FeeAccrual>,
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub fine_ledger: Vec<FineEntry>,
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub ill_requests: Vec<IllRequest>,
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub reviews: Vec<Review>,
+    /// Returned books not yet put back on their shelves.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub awaiting_shelving: Vec<AwaitingShelving>,
+    /// Copies declared lost, kept aside outside the catalog.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub lost_books: Vec<LostBook>,
+    /// Reading lists curated by staff.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub collections: Vec<Collection>,
+}
+
+impl LibrarySnapshot {
+    /// Every catalogued book: on the shelf or borrowed by a member.
+    pub fn catalog(&self) -> impl Iterator<Item = &Book> {
+        self.books.iter()
+    }
+
+    /// The books on the shelf when the snapshot was taken: all but the
+    /// physical copies with a loan. Digital copies never leave the shelf.
+    pub fn shelf(&self) -> impl Iterator<Item = &Book> {
+        let lent: HashSet<u64> = self.loans.iter().map(|loan| loan.book_id).collect();
+        self.books
+            .iter()
+            .filter(move |book| book.format == Format::Digital || !lent.contains(&book.id()))
+    }
+}
+
+impl Library {
+    /// Copies the library's current data into a [`LibrarySnapshot`].
+    pub fn snapshot(&self, taken_on: NaiveDate) -> LibrarySnapshot {
+        LibrarySnapshot {
+            taken_on,
+            config: self.config.clone(),
+            books: self.catalog().cloned().collect(),
+            members: self.members().cloned().collect(),
+            loans: self.loans.clone(),
+            holds: self.holds.clone(),
+            fee_accruals: self.fee_accruals.clone(),
+            fine_ledger: self.fine_ledger.clone(),
+            ill_requests: self.ill_requests.clone(),
+            reviews: self.reviews.clone(),
+            awaiting_shelving: self.reshelving.clone(),
+            lost_books: self.lost_books.clone(),
+            collections: self.collections.clone(),
+        }
+    }
+
+    /// Replaces the library's books, members, loans, holds, fine ledger,
+    /// fee accruals, interlibrary loans, reviews, re-shelving queue, lost
+    /// copies and collections with the snapshot's.
+    ///
+    /// The configuration, policies, notifiers and audit log stay as they
+    /// are, and so do the IDs handed out so far: a book added afterwards
+    /// still gets an ID no earlier book has had. The undo history is
+    /// cleared, since its commands no longer describe the library.
+    ///
+    /// # Errors
+    ///
+    /// [`LibraryError::DuplicateBook`] or [`LibraryError::DuplicateMember`]
+    /// if the snapshot uses an ID twice, `BookNotFound`, `MemberNotFound`
+    /// or `BookUnavailable` if a loan is of an unknown book, to an unknown
+    /// member or of a copy already lent, and
+    /// [`LibraryError::CapacityReached`] if it doesn't fit the current
+    /// configuration's limits. The library is unchanged when it fails.
+    pub fn restore(&mut self, snapshot: LibrarySnapshot) -> Result<(), LibraryError> {
+        let mut restored = Library::with_config(self.config.clone());
+        for book in snapshot.books {
+            restored.add_book_allowing_duplicates(book)?;
+        }
+        for member in snapshot.members {
+            restored.register_member(member)?;
+        }
+        restored.restore_loans(snapshot.loans)?;
+
+        // The allocator only moves forward, so IDs used since stay used
+        self.ids.book_used(restored.ids.next_book() - 1);
+        self.ids.member_used(restored.ids.next_member() - 1);
+        self.books = restored.books;
+        self.members = restored.members;
+        self.member_order = restored.member_order;
+        self.index = restored.index;
+        self.loans = restored.loans;
+        self.holds = snapshot.holds;
+        self.fee_accruals = snapshot.fee_accruals;
+        self.fine_ledger = snapshot.fine_ledger;
+        self.ill_requests = snapshot.ill_requests;
+        self.reviews = snapshot.reviews;
+        self.reshelving = snapshot.awaiting_shelving;
+        self.lost_books = snapshot.lost_books;
+        self.collections = snapshot.collections;
+        self.commands = CommandHistory::default();
+        Ok(())
+    }
+}
+
+// =============================================================================
+// TESTS
+// =============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Genre, MembershipTier};
+
+    #[test]
+    fn test_snapshot_is_independent_of_library() {
+        let day = NaiveDate::from_ymd_opt(2024, 3, 1).unwrap();
+        let mut lib = Library::new();
+        lib.add_book(Book::new(1, "Dune", Genre::SciFi)).unwrap();
+        lib.add_book(Book::new(2, "Emma", Genre::Fiction)).unwrap();
+        lib.register_member(Member::new(10, "Alice", MembershipTier::Basic)).unwrap();
+        lib.checkout(10, 1, day).unwrap();
+
+        let snapshot = lib.snapshot(day);
+        lib.return_book(10, 1, day).unwrap();
+
+        assert_eq!(snapshot.books.len(), 2);
+        assert_eq!(snapshot.loans.len(), 1);
+        assert_eq!(snapshot.shelf().map(Book::id).collect::<Vec<_>>(), [2]);
+    }
+
+    #[test]
+    fn test_restore_rolls_back_and_keeps_ids_moving() {
+        let day = NaiveDate::from_ymd_opt(2024, 3, 1).unwrap();
+        let late = NaiveDate::from_ymd_opt(2024, 4, 1).unwrap();
+        let mut lib = Library::new();
+        lib.add_book(Book::new(1, "Dune", Genre::SciFi)).unwrap();
+        lib.register_member(Member::new(10, "Alice", MembershipTier::Basic)).unwrap();
+        lib.checkout(10, 1, day).unwrap();
+        let before = lib.snapshot(day);
+
+        lib.return_book(10, 1, late).unwrap();
+        lib.add_book(Book::new(5, "Emma", Genre::Fiction)).unwrap();
+        assert!(!lib.fine_ledger().is_empty());
+        lib.restore(before.clone()).unwrap();
+
+        assert_eq!(lib.loans(), before.loans.as_slice());
+        assert!(lib.fine_ledger().is_empty());
+        assert!(lib.find_book(5).is_none());
+        assert_eq!(lib.borrower(1), Some(10));
+        assert_eq!(lib.check_invariants(), Ok(()));
+        // Book 5 existed after the snapshot, so its ID isn't handed out again
+        assert_eq!(lib.add_book_auto("Ulysses", Genre::Fiction), Ok(6));
+
+        let mut clash = before.clone();
+        clash.books.push(Book::new(1, "Dune again", Genre::SciFi));
+        assert_eq!(lib.restore(clash), Err(LibraryError::DuplicateBook(1)));
+        let mut lent_twice = before;
+        lent_twice.loans.push(lent_twice.loans[0].clone());
+        assert_eq!(lib.restore(lent_twice), Err(LibraryError::BookUnavailable(1)));
+        assert_eq!(lib.book_count(), 1);
+    }
+}
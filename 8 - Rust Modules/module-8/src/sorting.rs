@@ -0,0 +1,143 @@
+//! Sorting module - the catalog in a chosen order.
+//!
+//! [`Library::books_sorted_by`] returns references to the books on the
+//! shelf, so the result can be filtered further or cut into a
+//! [`Page`](crate::page::Page) like any other listing. Books that compare
+//! equal keep their catalog order.
+//!
+//! ```
+//! use module_8::page::Page;
+//! use module_8::sorting::{SortField, SortKey};
+//! use module_8::{Book, Genre, Library};
+//!
+//! let mut lib = Library::new();
+//! lib.add_book(Book::new(1, "emma", Genre::Fiction)).unwrap();
+//! lib.add_book(Book::new(2, "Dune", Genre::SciFi)).unwrap();
+//! lib.add_book(Book::new(3, "Beloved", Genre::Fiction)).unwrap();
+//!
+//! let by_title = lib.books_sorted_by(SortKey::ascending(SortField::Title));
+//! let titles: Vec<&str> = by_title.iter().map(|b| b.title.as_str()).collect();
+//! assert_eq!(titles, ["Beloved", "Dune", "emma"]);
+//!
+//! let newest = Page::from_iter(lib.books_sorted_by(SortKey::ascending(SortField::RecentlyAdded)), 1, 2);
+//! assert_eq!(newest.items.iter().map(|b| b.id()).collect::<Vec<_>>(), [3, 2]);
+//! ```
+
+use std::cmp::Ordering;
+
+use crate::book::Book;
+use crate::index::normalize_title;
+use crate::Library;
+
+// =============================================================================
+// SORT KEYS
+// =============================================================================
+
+/// What to sort books by.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum SortField {
+    /// Alphabetical, ignoring case and surrounding whitespace.
+    Title,
+    /// Alphabetical by genre name, e.g. "Fiction" before "Mystery".
+    Genre,
+    /// Fewest loans first.
+    TimesBorrowed,
+    /// Newest addition to the catalog first.
+    RecentlyAdded,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum SortOrder {
+    Ascending,
+    Descending,
+}
+
+/// A field and a direction. Descending reverses the field's order, so
+/// `RecentlyAdded` descending lists the oldest books first.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct SortKey {
+    pub field: SortField,
+    pub order: SortOrder,
+}
+
+impl SortKey {
+    pub fn ascending(field: SortField) -> Self {
+        SortKey { field, order: SortOrder::Ascending }
+    }
+
+    pub fn descending(field: SortField) -> Self {
+        SortKey { field, order: SortOrder::Descending }
+    }
+
+    /// Compares two books, given their positions in catalog order.
+    fn compare(&self, (a_pos, a): (usize, &Book), (b_pos, b): (usize, &Book)) -> Ordering {
+        let ordering = match self.field {
+            SortField::Title => normalize_title(&a.title).cmp(&normalize_title(&b.title)),
+            SortField::Genre => a.genre.to_string().to_lowercase().cmp(&b.genre.to_string().to_lowercase()),
+            SortField::TimesBorrowed => a.times_borrowed().cmp(&b.times_borrowed()),
+            SortField::RecentlyAdded => b_pos.cmp(&a_pos),
+        };
+        match self.order {
+            SortOrder::Ascending => ordering,
+            SortOrder::Descending => ordering.reverse(),
+        }
+    }
+}
+
+// =============================================================================
+// LIBRARY INTEGRATION
+// =============================================================================
+
+impl Library {
+    /// The books on the shelf, sorted by `key`; ties stay in catalog order.
+    pub fn books_sorted_by(&self, key: SortKey) -> Vec<&Book> {
+        let mut books: Vec<(usize, &Book)> = self.books().enumerate().collect();
+        books.sort_by(|&a, &b| key.compare(a, b));
+        books.into_iter().map(|(_, book)| book).collect()
+    }
+}
+
+// =============================================================================
+// TESTS
+// =============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Genre, Member, MembershipTier};
+    use chrono::NaiveDate;
+
+    fn ids(books: Vec<&Book>) -> Vec<u64> {
+        books.iter().map(|b| b.id()).collect()
+    }
+
+    #[test]
+    fn test_each_field_both_ways() {
+        let mut lib = Library::new();
+        lib.add_book(Book::new(1, "Dune", Genre::SciFi)).unwrap();
+        lib.add_book(Book::new(2, "  anathem", Genre::SciFi)).unwrap();
+        lib.add_book(Book::new(3, "Emma", Genre::Fiction)).unwrap();
+        lib.add_book(Book::new(4, "Gone Girl", Genre::Mystery)).unwrap();
+        lib.register_member(Member::new(10, "Alice", MembershipTier::Gold)).unwrap();
+        let day = NaiveDate::from_ymd_opt(2024, 3, 1).unwrap();
+        for _ in 0..2 {
+            lib.checkout(10, 4, day).unwrap();
+            lib.return_book(10, 4, day).unwrap();
+        }
+        lib.checkout(10, 1, day).unwrap();
+        lib.return_book(10, 1, day).unwrap();
+
+        assert_eq!(ids(lib.books_sorted_by(SortKey::ascending(SortField::Title))), [2, 1, 3, 4]);
+        assert_eq!(ids(lib.books_sorted_by(SortKey::descending(SortField::Title))), [4, 3, 1, 2]);
+        // Ties keep catalog order in both directions
+        assert_eq!(ids(lib.books_sorted_by(SortKey::ascending(SortField::Genre))), [3, 4, 1, 2]);
+        assert_eq!(ids(lib.books_sorted_by(SortKey::descending(SortField::Genre))), [1, 2, 4, 3]);
+        assert_eq!(ids(lib.books_sorted_by(SortKey::descending(SortField::TimesBorrowed))), [4, 1, 2, 3]);
+        assert_eq!(ids(lib.books_sorted_by(SortKey::ascending(SortField::RecentlyAdded))), [4, 3, 2, 1]);
+        assert_eq!(ids(lib.books_sorted_by(SortKey::descending(SortField::RecentlyAdded))), [1, 2, 3, 4]);
+
+        // Books on loan aren't on the shelf
+        lib.checkout(10, 3, day).unwrap();
+        assert_eq!(ids(lib.books_sorted_by(SortKey::ascending(SortField::Title))), [2, 1, 4]);
+    }
+}
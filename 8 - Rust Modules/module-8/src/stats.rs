@@ -0,0 +1,142 @@
+//! Stats module - headline figures behind a common trait.
+//!
+//! Module 6 gives its projects a `Statistics` trait so that reporting code
+//! can take any `&impl Statistics`. [`Statistics`] does the same here for a
+//! [`Library`] and a whole [`LibrarySystem`]: code written against the
+//! trait works for either without knowing which it has.
+//!
+//! ```
+//! use chrono::NaiveDate;
+//! use module_8::stats::Statistics;
+//! use module_8::{Book, Genre, Library, Member, MembershipTier};
+//!
+//! fn headline(stats: &impl Statistics) -> String {
+//!     match stats.average_loans_per_member() {
+//!         Some(average) => format!("{} loans, {:.1} per member", stats.total_loans(), average),
+//!         None => String::from("no members yet"),
+//!     }
+//! }
+//!
+//! let mut lib = Library::new();
+//! assert_eq!(headline(&lib), "no members yet");
+//! lib.add_book(Book::new(1, "Dune", Genre::SciFi)).unwrap();
+//! lib.register_member(Member::new(10, "Alice", MembershipTier::Basic)).unwrap();
+//! lib.register_member(Member::new(11, "Bob", MembershipTier::Basic)).unwrap();
+//! lib.checkout(10, 1, NaiveDate::from_ymd_opt(2024, 3, 1).unwrap()).unwrap();
+//! assert_eq!(headline(&lib), "1 loans, 0.5 per member");
+//! ```
+
+use crate::branches::LibrarySystem;
+use crate::ledger::FineKind;
+use crate::money::Money;
+use crate::Library;
+
+/// Types that can report loan and fee statistics.
+pub trait Statistics {
+    /// Loans made so far: returned ones in members' histories plus those
+    /// still out.
+    fn total_loans(&self) -> usize;
+
+    fn member_count(&self) -> usize;
+
+    /// Every fee charged, paid or not: late fees and replacement costs.
+    fn total_fees(&self) -> Money;
+
+    /// `None` when there are no members to average over.
+    fn average_loans_per_member(&self) -> Option<f64> {
+        match self.member_count() {
+            0 => None,
+            members => Some(self.total_loans() as f64 / members as f64),
+        }
+    }
+}
+
+impl Statistics for Library {
+    fn total_loans(&self) -> usize {
+        let returned: usize = self.members().map(|m| m.history().len()).sum();
+        returned + self.loans().len()
+    }
+
+    fn member_count(&self) -> usize {
+        Library::member_count(self)
+    }
+
+    fn total_fees(&self) -> Money {
+        Money::saturating_sum(
+            self.fine_ledger()
+                .iter()
+                .filter(|entry| entry.kind == FineKind::Assessed)
+                .map(|entry| entry.amount),
+        )
+    }
+}
+
+/// Figures for all branches together.
+impl Statistics for LibrarySystem {
+    fn total_loans(&self) -> usize {
+        self.branches().map(Statistics::total_loans).sum()
+    }
+
+    fn member_count(&self) -> usize {
+        self.branches().map(Library::member_count).sum()
+    }
+
+    fn total_fees(&self) -> Money {
+        Money::saturating_sum(self.branches().map(Statistics::total_fees))
+    }
+}
+
+// =============================================================================
+// TESTS
+// =============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Book, Genre, LibraryConfig, Member, MembershipTier};
+    use chrono::NaiveDate;
+
+    fn date(day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(2024, 3, day).unwrap()
+    }
+
+    fn branch(name: &str) -> Library {
+        let mut lib = Library::with_config(LibraryConfig { name: name.to_string(), ..LibraryConfig::default() });
+        lib.add_book(Book::new(1, "Dune", Genre::SciFi)).unwrap();
+        lib.add_book(Book::new(2, "Emma", Genre::Fiction)).unwrap();
+        lib.register_member(Member::new(10, "Alice", MembershipTier::Basic)).unwrap();
+        lib
+    }
+
+    #[test]
+    fn test_library_statistics() {
+        let mut lib = branch("North");
+        lib.register_member(Member::new(11, "Bob", MembershipTier::Basic)).unwrap();
+        lib.checkout(10, 1, date(1)).unwrap();
+        lib.return_book(10, 1, date(17)).unwrap();
+        lib.checkout(10, 1, date(18)).unwrap();
+        lib.checkout(11, 2, date(18)).unwrap();
+        lib.pay_fines(10, Money::from_cents(50), date(18)).unwrap();
+
+        assert_eq!(lib.total_loans(), 3);
+        assert_eq!(lib.average_loans_per_member(), Some(1.5));
+        // Paying the late fee doesn't change what was charged
+        assert_eq!(lib.total_fees(), Money::from_cents(50));
+    }
+
+    #[test]
+    fn test_system_statistics_add_up_branches() {
+        let mut system = LibrarySystem::new();
+        assert_eq!(system.average_loans_per_member(), None);
+        system.add_branch(branch("North")).unwrap();
+        system.add_branch(branch("South")).unwrap();
+        system.branch_mut("North").unwrap().checkout(10, 1, date(1)).unwrap();
+        system.branch_mut("North").unwrap().return_book(10, 1, date(20)).unwrap();
+        system.branch_mut("South").unwrap().checkout(10, 2, date(1)).unwrap();
+
+        assert_eq!(system.total_loans(), 2);
+        assert_eq!(Statistics::member_count(&system), 2);
+        assert_eq!(system.average_loans_per_member(), Some(1.0));
+        assert_eq!(system.total_fees(), system.branch("North").unwrap().total_fees());
+    }
+}
@@ -0,0 +1,185 @@
+//! Storage module - where a library's data is kept between runs.
+//!
+//! The [`Storage`] trait saves and loads a whole library at once, so code
+//! that keeps a library between runs doesn't need to know whether its data
+//! goes to a file or stays in memory. It works on complete snapshots: the
+//! library's books and members still live in its own maps while it runs,
+//! and a backend never sees a single book or member change. Two backends
+//! come with the crate:
+//!
+//! - [`MemoryStorage`] keeps a [`LibrarySnapshot`] in memory. It is the
+//!   default, and the one to use in tests.
+//! - [`FileStorage`] writes the CSV format of [`Library::save`] to a file.
+//!
+//! ```
+//! use chrono::NaiveDate;
+//! use module_8::storage::{MemoryStorage, Storage};
+//! use module_8::{Book, Genre, Library, LibraryConfig};
+//!
+//! fn add_and_save(storage: &mut dyn Storage) {
+//!     let mut lib = storage.load(LibraryConfig::default()).unwrap();
+//!     lib.add_book_auto("Dune", Genre::SciFi).unwrap();
+//!     storage.save(&lib, NaiveDate::from_ymd_opt(2024, 3, 1).unwrap()).unwrap();
+//! }
+//!
+//! let mut storage = MemoryStorage::default();
+//! add_and_save(&mut storage);
+//! add_and_save(&mut storage);
+//! assert_eq!(storage.load(LibraryConfig::default()).unwrap().book_count(), 2);
+//! ```
+
+use std::fs::File;
+use std::io::{self, BufReader, BufWriter, Write};
+use std::path::{Path, PathBuf};
+
+use chrono::NaiveDate;
+
+use crate::config::LibraryConfig;
+use crate::snapshot::LibrarySnapshot;
+use crate::store::StoreError;
+use crate::Library;
+
+/// A place a library's data can be saved to and loaded from.
+pub trait Storage {
+    /// Stores the library's data as of `today`, replacing whatever was
+    /// stored before.
+    fn save(&mut self, library: &Library, today: NaiveDate) -> Result<(), StoreError>;
+
+    /// Builds a library with `config` from the stored data, or an empty
+    /// one if nothing has been stored yet.
+    fn load(&self, config: LibraryConfig) -> Result<Library, StoreError>;
+}
+
+// =============================================================================
+// IN-MEMORY STORAGE
+// =============================================================================
+
+/// Keeps the last saved library as a [`LibrarySnapshot`].
+///
+/// Nothing is serialized, so members with custom tiers are kept as they are.
+#[derive(Debug, Clone, Default)]
+pub struct MemoryStorage {
+    snapshot: Option<LibrarySnapshot>,
+}
+
+impl MemoryStorage {
+    /// The stored data, if anything has been saved.
+    pub fn snapshot(&self) -> Option<&LibrarySnapshot> {
+        self.snapshot.as_ref()
+    }
+}
+
+impl Storage for MemoryStorage {
+    /// Keeps a snapshot taken on `today`.
+    fn save(&mut self, library: &Library, today: NaiveDate) -> Result<(), StoreError> {
+        self.snapshot = Some(library.snapshot(today));
+        Ok(())
+    }
+
+    fn load(&self, config: LibraryConfig) -> Result<Library, StoreError> {
+        let mut library = Library::with_config(config);
+        if let Some(snapshot) = &self.snapshot {
+            library.restore(snapshot.clone()).map_err(StoreError::Library)?;
+        }
+        Ok(library)
+    }
+}
+
+// =============================================================================
+// FILE STORAGE
+// =============================================================================
+
+/// Saves the library to a file in the CSV format of [`Library::save`].
+///
+/// A missing file loads as an empty library, so the first run needs no
+/// setup. Saving writes a temporary file next to the real one and renames
+/// it into place, so a failed save leaves the previous data intact.
+#[derive(Debug, Clone)]
+pub struct FileStorage {
+    path: PathBuf,
+}
+
+impl FileStorage {
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        FileStorage { path: path.into() }
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+}
+
+impl Storage for FileStorage {
+    /// The CSV format has no save date, so `today` isn't recorded.
+    fn save(&mut self, library: &Library, _today: NaiveDate) -> Result<(), StoreError> {
+        let mut temp = self.path.clone().into_os_string();
+        temp.push(".tmp");
+        let temp = PathBuf::from(temp);
+        let mut writer = BufWriter::new(File::create(&temp).map_err(csv::Error::from)?);
+        library.save(&mut writer)?;
+        writer.flush().map_err(csv::Error::from)?;
+        drop(writer);
+        std::fs::rename(&temp, &self.path).map_err(csv::Error::from)?;
+        Ok(())
+    }
+
+    fn load(&self, config: LibraryConfig) -> Result<Library, StoreError> {
+        match File::open(&self.path) {
+            Ok(file) => Library::load(BufReader::new(file), config),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(Library::with_config(config)),
+            Err(e) => Err(csv::Error::from(e).into()),
+        }
+    }
+}
+
+// =============================================================================
+// TESTS
+// =============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Book, Genre, Member, MembershipTier};
+
+    fn library() -> Library {
+        let mut lib = Library::new();
+        lib.add_book(Book::new(1, "Dune", Genre::SciFi)).unwrap();
+        lib.add_book(Book::new(2, "Emma", Genre::Fiction)).unwrap();
+        lib.register_member(Member::new(10, "Alice", MembershipTier::Basic)).unwrap();
+        lib.checkout(10, 1, NaiveDate::from_ymd_opt(2024, 3, 1).unwrap()).unwrap();
+        lib
+    }
+
+    fn round_trip(storage: &mut dyn Storage) -> Library {
+        assert_eq!(storage.load(LibraryConfig::default()).unwrap().book_count(), 0);
+        storage.save(&library(), NaiveDate::from_ymd_opt(2024, 3, 2).unwrap()).unwrap();
+        storage.load(LibraryConfig::default()).unwrap()
+    }
+
+    #[test]
+    fn test_memory_storage_round_trip() {
+        let mut storage = MemoryStorage::default();
+        let loaded = round_trip(&mut storage);
+        assert_eq!(loaded.loans().len(), 1);
+        assert_eq!(loaded.borrowed_books(10).len(), 1);
+        assert_eq!(storage.snapshot().unwrap().books.len(), 2);
+        assert_eq!(storage.snapshot().unwrap().taken_on, NaiveDate::from_ymd_opt(2024, 3, 2).unwrap());
+    }
+
+    #[test]
+    fn test_file_storage_round_trip() {
+        let path = std::env::temp_dir().join(format!("module-8-storage-{}.csv", std::process::id()));
+        let mut storage = FileStorage::new(&path);
+        let loaded = round_trip(&mut storage);
+        assert_eq!(loaded.loans().len(), 1);
+        assert_eq!(loaded.get_book(1).unwrap().title, "Dune");
+        assert_eq!(loaded.check_invariants(), Ok(()));
+        std::fs::remove_file(storage.path()).unwrap();
+    }
+
+    #[test]
+    fn test_file_storage_reports_unreadable_files() {
+        let storage = FileStorage::new(std::env::temp_dir());
+        assert!(matches!(storage.load(LibraryConfig::default()), Err(StoreError::Csv(_))));
+    }
+}
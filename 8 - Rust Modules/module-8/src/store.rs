@@ -0,0 +1,1139 @@
+//! Store module - saves a library's data to a file and loads it back.
+//!
+//! [`Library::save`] writes every book, member, loan, hold, accrued fee,
+//! fine ledger entry, interlibrary loan, review, shelf location, book
+//! waiting to be re-shelved, lost copy and collection as CSV, one record per
+//! line with the record kind in the first field:
+//!
+//! ```text
+//! member,10,Alice,Basic,0 USD,,active,,alice@example.com,555 0100
+//! history,10,2,2024-02-01,2024-02-10,0 USD
+//! tier,10,Silver,Basic,2024-02-15
+//! item,3,periodical,May 2024,,
+//! item,4,book,,digital,1951
+//! item,5,book,,,2019,O'Reilly,552,English,The official guide
+//! book,1,Dune,Science Fiction,Frank Herbert,,New,1,10,classic
+//! book,2,Emma,Fiction,,,Good,1,
+//! location,1,Main,Fiction Hall,SCI-3
+//! shelve,2,2024-03-01
+//! lost,6,11,2024-01-02,2024-01-16,0,2024-03-16,2000 USD,1500 USD
+//! loan,10,1,2024-03-01,2024-03-15,0
+//! hold,11,1,2024-03-02,
+//! accrual,10,1,2024-03-01,2024-03-17,50 USD
+//! fine,10,2024-03-17,assessed,50 USD
+//! ill,1,11,received,2024-03-01,2024-03-04,2024-03-06,,Ulysses,,City Library
+//! illcost,1,2024-03-04,450 USD,Postage
+//! review,10,2,4,A slow start
+//! collection,Summer Reading,Books for the beach,2,1
+//! ```
+//!
+//! A member's ninth and tenth fields are their email address and phone
+//! number, empty if not on file. Their `history` records are their past
+//! loans, and their `tier` records the tier changes in their
+//! [`tier_history`](Member::tier_history): from, to and effective date.
+//!
+//! A book's ninth field is the member borrowing it (empty on the shelf),
+//! which must match a `loan` record; any further fields are its tags. Periodicals, media, digital copies and
+//! books with a publication year, publisher, page count, language or
+//! summary have an `item` record giving their [`ItemKind`], [`Format`] and
+//! that metadata ahead of the `book` records; anything without one is a
+//! physical book with no such details. A `location` record after the
+//! `book` records gives a copy's branch, room and shelf, and a `shelve`
+//! record a returned book waiting to go back, with the day it came back.
+//! A `lost` record sets a copy aside as lost: the member and dates of the
+//! loan it was lost on, the day it was declared lost, and the replacement
+//! cost and fine billed.
+//! Loans of digital copies refer
+//! to the copy on the shelf. An `ill` record's dates are when the
+//! interlibrary loan was requested, shipped, received and returned, and
+//! each of its costs follows it in an `illcost` record. A `review` record
+//! gives a member's rating of a book and their review; books' average
+//! ratings are worked out from these on loading. A `collection` record gives
+//! a [`Collection`]'s name and description, then its books in order.
+//! [`Library::load`] reads the file back into a library with the given
+//! configuration.
+//!
+//! Like a [`LibrarySnapshot`](crate::LibrarySnapshot), the file holds data
+//! only: policies, notifiers, status rules and the audit log are not saved.
+//! Members must have one of the built-in [`MembershipTier`]s, or be on the
+//! [`GuestTier`](crate::GuestTier).
+
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+use std::io::{Read, Write};
+use std::sync::Arc;
+
+use chrono::NaiveDate;
+
+use crate::accrual::FeeAccrual;
+use crate::book::{Book, Condition, Genre};
+use crate::circulation::{Hold, Loan};
+use crate::config::LibraryConfig;
+use crate::error::LibraryError;
+use crate::ill::{IllCost, IllRequest, IllStatus};
+use crate::item::{Format, ItemKind};
+use crate::ledger::{FineEntry, FineKind};
+use crate::location::{AwaitingShelving, Location};
+use crate::lost::LostBook;
+use crate::member::{
+    is_built_in, parse_tier, LoanRecord, Member, MemberStatus, MembershipTier, SuspensionReason, TierChange, TierPolicy,
+};
+use crate::money::{Currency, Money};
+use crate::reading_lists::Collection;
+use crate::reviews::{Review, RATINGS};
+use crate::Library;
+
+// =============================================================================
+// ERRORS
+// =============================================================================
+
+/// Errors from saving or loading a library.
+#[derive(Debug)]
+pub enum StoreError {
+    /// The file could not be read or written.
+    Csv(csv::Error),
+    /// A record is malformed or contradicts an earlier one.
+    Invalid { line: u64, message: String },
+    /// A member has a custom tier, which can't be restored from its name.
+    CustomTier { member_id: u64, tier: String },
+    /// The records don't fit in a library with the given configuration,
+    /// e.g. there are more books than its `max_books`.
+    Library(LibraryError),
+}
+
+impl fmt::Display for StoreError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            StoreError::Csv(e) => write!(f, "Could not access library file: {}", e),
+            StoreError::Invalid { line, message } => write!(f, "Line {}: {}", line, message),
+            StoreError::CustomTier { member_id, tier } => {
+                write!(f, "Member {} has custom tier {}, which can't be saved", member_id, tier)
+            }
+            StoreError::Library(e) => write!(f, "Could not load library: {}", e),
+        }
+    }
+}
+
+impl std::error::Error for StoreError {}
+
+impl From<csv::Error> for StoreError {
+    fn from(e: csv::Error) -> Self {
+        StoreError::Csv(e)
+    }
+}
+
+// =============================================================================
+// SAVING
+// =============================================================================
+
+impl Library {
+    /// Writes the library's data to `writer`.
+    ///
+    /// Fails with [`StoreError::CustomTier`] before writing anything if a
+    /// member's tier isn't a built-in one.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use module_8::{Book, Genre, Library, LibraryConfig};
+    ///
+    /// let mut lib = Library::new();
+    /// lib.add_book(Book::new(1, "Dune", Genre::SciFi)).unwrap();
+    ///
+    /// let mut file = Vec::new();
+    /// lib.save(&mut file).unwrap();
+    /// let loaded = Library::load(file.as_slice(), LibraryConfig::default()).unwrap();
+    /// assert_eq!(loaded.find_book(1).unwrap().title, "Dune");
+    /// ```
+    pub fn save(&self, writer: impl Write) -> Result<(), StoreError> {
+        for member in self.members() {
+            let past = member.tier_history().iter().flat_map(|change| [change.from(), change.to()]);
+            if let Some(tier) = std::iter::once(member.tier()).chain(past).find(|&tier| !is_built_in(tier)) {
+                return Err(StoreError::CustomTier {
+                    member_id: member.id(),
+                    tier: tier.name().to_string(),
+                });
+            }
+        }
+
+        let mut out = csv::WriterBuilder::new().flexible(true).from_writer(writer);
+        for member in self.members() {
+            let (status, reason) = match member.status() {
+                MemberStatus::Active => ("active", ""),
+                MemberStatus::Closed => ("closed", ""),
+                MemberStatus::Suspended { reason: SuspensionReason::Rule(why) } => ("rule", why.as_str()),
+                MemberStatus::Suspended { reason: SuspensionReason::Staff(why) } => ("staff", why.as_str()),
+            };
+            out.write_record([
+                "member",
+                &member.id().to_string(),
+                &member.name,
+                member.tier().name(),
+                &money_field(member.outstanding_fines()),
+                &date_field(member.expires_on()),
+                status,
+                reason,
+                member.email().unwrap_or_default(),
+                member.phone().unwrap_or_default(),
+            ])?;
+            for record in member.history() {
+                out.write_record([
+                    "history",
+                    &member.id().to_string(),
+                    &record.book_id.to_string(),
+                    &date_field(record.borrowed_on),
+                    &record.returned_on.to_string(),
+                    &money_field(record.fee_charged),
+                ])?;
+            }
+            for change in member.tier_history() {
+                out.write_record([
+                    "tier",
+                    &member.id().to_string(),
+                    change.from().name(),
+                    change.to().name(),
+                    &change.effective_on.to_string(),
+                ])?;
+            }
+        }
+
+        let catalog: Vec<_> = self
+            .catalog()
+            .map(|book| (book, self.borrower(book.id())))
+            .chain(self.lost_books.iter().map(|lost| (&lost.book, None)))
+            .collect();
+        for (book, _) in &catalog {
+            let plain = book.year.is_none()
+                && book.publisher.is_none()
+                && book.pages.is_none()
+                && book.language.is_none()
+                && book.summary.is_none();
+            let (kind, detail) = match &book.kind {
+                ItemKind::Book if book.format == Format::Physical && plain => continue,
+                ItemKind::Book => ("book", String::new()),
+                ItemKind::Periodical { issue } => ("periodical", issue.clone()),
+                ItemKind::Media { runtime_minutes } => ("media", runtime_minutes.to_string()),
+            };
+            let format = match book.format {
+                Format::Physical => "",
+                Format::Digital => "digital",
+            };
+            let year = book.year.map_or(String::new(), |y| y.to_string());
+            let pages = book.pages.map_or(String::new(), |p| p.to_string());
+            out.write_record([
+                "item",
+                &book.id().to_string(),
+                kind,
+                &detail,
+                format,
+                &year,
+                book.publisher.as_deref().unwrap_or_default(),
+                &pages,
+                book.language.as_deref().unwrap_or_default(),
+                book.summary.as_deref().unwrap_or_default(),
+            ])?;
+        }
+        for &(book, borrower) in &catalog {
+            let mut record = vec![
+                String::from("book"),
+                book.id().to_string(),
+                book.title.clone(),
+                book.genre.to_string(),
+                book.author.clone().unwrap_or_default(),
+                book.isbn.clone().unwrap_or_default(),
+                format!("{:?}", book.condition),
+                book.times_borrowed().to_string(),
+                borrower.map_or(String::new(), |id| id.to_string()),
+            ];
+            record.extend(book.tags().iter().cloned());
+            out.write_record(&record)?;
+        }
+        for (book, _) in &catalog {
+            if let Some(location) = &book.location {
+                out.write_record([
+                    "location",
+                    &book.id().to_string(),
+                    &location.branch,
+                    &location.room,
+                    &location.shelf,
+                ])?;
+            }
+        }
+        for entry in &self.reshelving {
+            out.write_record(["shelve", &entry.book_id.to_string(), &entry.returned_on.to_string()])?;
+        }
+        for lost in &self.lost_books {
+            out.write_record([
+                "lost",
+                &lost.book.id().to_string(),
+                &lost.loan.member_id.to_string(),
+                &lost.loan.borrowed_on.to_string(),
+                &lost.loan.due_on.to_string(),
+                &lost.loan.renewals.to_string(),
+                &lost.declared_on.to_string(),
+                &money_field(lost.replacement),
+                &money_field(lost.fine),
+            ])?;
+        }
+
+        for loan in &self.loans {
+            out.write_record([
+                "loan",
+                &loan.member_id.to_string(),
+                &loan.book_id.to_string(),
+                &loan.borrowed_on.to_string(),
+                &loan.due_on.to_string(),
+                &loan.renewals.to_string(),
+            ])?;
+        }
+        for hold in &self.holds {
+            out.write_record([
+                "hold",
+                &hold.member_id.to_string(),
+                &hold.book_id.to_string(),
+                &hold.placed_on.to_string(),
+                &date_field(hold.ready_since),
+            ])?;
+        }
+        for accrual in &self.fee_accruals {
+            out.write_record([
+                "accrual",
+                &accrual.member_id.to_string(),
+                &accrual.book_id.to_string(),
+                &accrual.borrowed_on.to_string(),
+                &accrual.on.to_string(),
+                &money_field(accrual.amount),
+            ])?;
+        }
+        for entry in &self.fine_ledger {
+            let kind = match entry.kind {
+                FineKind::Assessed => "assessed",
+                FineKind::Collected => "collected",
+                FineKind::Waived => "waived",
+            };
+            out.write_record([
+                "fine",
+                &entry.member_id.to_string(),
+                &entry.on.to_string(),
+                kind,
+                &money_field(entry.amount),
+            ])?;
+        }
+        for request in &self.ill_requests {
+            out.write_record([
+                "ill",
+                &request.id.to_string(),
+                &request.member_id.to_string(),
+                &request.status.to_string(),
+                &request.requested_on.to_string(),
+                &date_field(request.shipped_on),
+                &date_field(request.received_on),
+                &date_field(request.returned_on),
+                &request.title,
+                request.isbn.as_deref().unwrap_or_default(),
+                request.lender.as_deref().unwrap_or_default(),
+            ])?;
+            for cost in &request.costs {
+                out.write_record([
+                    "illcost",
+                    &request.id.to_string(),
+                    &cost.on.to_string(),
+                    &money_field(cost.amount),
+                    &cost.description,
+                ])?;
+            }
+        }
+        for review in &self.reviews {
+            out.write_record([
+                "review",
+                &review.member_id.to_string(),
+                &review.book_id.to_string(),
+                &review.rating.to_string(),
+                &review.text,
+            ])?;
+        }
+        for collection in &self.collections {
+            let mut record = vec![String::from("collection"), collection.name.clone(), collection.description.clone()];
+            record.extend(collection.book_ids().iter().map(u64::to_string));
+            out.write_record(&record)?;
+        }
+        out.flush().map_err(csv::Error::from)?;
+        Ok(())
+    }
+
+    // =========================================================================
+    // LOADING
+    // =========================================================================
+
+    /// Reads a library written by [`save`](Library::save), using `config`
+    /// for everything the file doesn't hold.
+    ///
+    /// Nothing is loaded if any record is invalid: unknown kinds, duplicate
+    /// IDs, and loans or holds that refer to unknown members or books are
+    /// all errors.
+    pub fn load(reader: impl Read, config: LibraryConfig) -> Result<Library, StoreError> {
+        let mut input = csv::ReaderBuilder::new()
+            .has_headers(false)
+            .flexible(true)
+            .from_reader(reader);
+
+        let mut members: Vec<Member> = Vec::new();
+        let mut member_index: HashMap<u64, usize> = HashMap::new();
+        let mut book_ids = HashSet::new();
+        let mut details: HashMap<u64, ItemDetails> = HashMap::new();
+        let mut books = Vec::new();
+        // Book ID to the borrower its record names, and the record's line
+        let mut borrowers: HashMap<u64, (u64, u64)> = HashMap::new();
+        let mut loans = Vec::new();
+        let mut holds = Vec::new();
+        let mut fee_accruals = Vec::new();
+        let mut fine_ledger = Vec::new();
+        let mut ill_requests: Vec<IllRequest> = Vec::new();
+        let mut reviews = Vec::new();
+        let mut locations: HashMap<u64, Location> = HashMap::new();
+        let mut reshelving = Vec::new();
+        let mut lost_books: HashMap<u64, LostBookRecord> = HashMap::new();
+        let mut collections: Vec<Collection> = Vec::new();
+
+        for result in input.records() {
+            let record = result?;
+            let line = record.position().map_or(0, |p| p.line());
+            let fields = Fields { record: &record, line };
+            match fields.text(0)? {
+                "member" => {
+                    let id = fields.id(1)?;
+                    let tier = parse_tier(fields.text(3)?).map_err(|e| fields.invalid(e.to_string()))?;
+                    let mut member =
+                        Member::try_new(id, fields.text(2)?, MembershipTier::Basic).map_err(|e| fields.invalid(e.to_string()))?;
+                    member.restore_tier(tier);
+                    member.charge(fields.money(4)?);
+                    member.set_expires_on(fields.optional_date(5)?);
+                    member.set_email(fields.optional_text(8).as_deref()).map_err(|e| fields.invalid(e.to_string()))?;
+                    member.set_phone(fields.optional_text(9).as_deref()).map_err(|e| fields.invalid(e.to_string()))?;
+                    let reason = fields.text(7).unwrap_or_default().to_string();
+                    member.set_status(match fields.text(6)? {
+                        "active" => MemberStatus::Active,
+                        "closed" => MemberStatus::Closed,
+                        "rule" => MemberStatus::Suspended { reason: SuspensionReason::Rule(reason) },
+                        "staff" => MemberStatus::Suspended { reason: SuspensionReason::Staff(reason) },
+                        other => return Err(fields.invalid(format!("unknown member status {:?}", other))),
+                    });
+                    if member_index.insert(id, members.len()).is_some() {
+                        return Err(fields.invalid(format!("member {} appears twice", id)));
+                    }
+                    members.push(member);
+                }
+                "history" => {
+                    let member = fields.member(1, &member_index)?;
+                    let record = LoanRecord {
+                        book_id: fields.id(2)?,
+                        borrowed_on: fields.optional_date(3)?,
+                        returned_on: fields.date(4)?,
+                        fee_charged: fields.money(5)?,
+                    };
+                    members[member].record_history(record);
+                }
+                "tier" => {
+                    let member = fields.member(1, &member_index)?;
+                    let tier = |index| -> Result<Arc<dyn TierPolicy>, StoreError> {
+                        parse_tier(fields.text(index)?).map_err(|e| fields.invalid(e.to_string()))
+                    };
+                    let change = TierChange::new(tier(2)?, tier(3)?, fields.date(4)?);
+                    members[member].restore_tier_change(change);
+                }
+                "item" => {
+                    let kind = match fields.text(2)? {
+                        "book" => ItemKind::Book,
+                        "periodical" => ItemKind::Periodical { issue: fields.text(3)?.to_string() },
+                        "media" => ItemKind::Media { runtime_minutes: fields.number(3)? },
+                        other => return Err(fields.invalid(format!("unknown item kind {:?}", other))),
+                    };
+                    let format = match fields.optional_text(4).as_deref() {
+                        None => Format::Physical,
+                        Some("digital") => Format::Digital,
+                        Some(other) => return Err(fields.invalid(format!("unknown format {:?}", other))),
+                    };
+                    let year = match fields.optional_text(5) {
+                        Some(_) => Some(fields.number(5)?),
+                        None => None,
+                    };
+                    let pages = match fields.optional_text(7) {
+                        Some(_) => Some(fields.number(7)?),
+                        None => None,
+                    };
+                    let id = fields.id(1)?;
+                    if book_ids.contains(&id) {
+                        return Err(fields.invalid(format!("item details for book {} must come before it", id)));
+                    }
+                    let item = ItemDetails {
+                        line,
+                        kind,
+                        format,
+                        year,
+                        publisher: fields.optional_text(6),
+                        pages,
+                        language: fields.optional_text(8),
+                        summary: fields.optional_text(9),
+                    };
+                    if details.insert(id, item).is_some() {
+                        return Err(fields.invalid(format!("item details for book {} appear twice", id)));
+                    }
+                }
+                "book" => {
+                    let id = fields.id(1)?;
+                    if !book_ids.insert(id) {
+                        return Err(fields.invalid(format!("book {} appears twice", id)));
+                    }
+                    let genre: Genre = fields.text(3)?.parse().map_err(|e| fields.invalid(format!("{}", e)))?;
+                    let mut book = Book::try_new(id, fields.text(2)?, genre).map_err(|e| fields.invalid(e.to_string()))?;
+                    book.author = fields.optional_text(4);
+                    book.isbn = fields.optional_text(5);
+                    if let Some(item) = details.remove(&id) {
+                        book.kind = item.kind;
+                        book.format = item.format;
+                        book.year = item.year;
+                        book.publisher = item.publisher;
+                        book.pages = item.pages;
+                        book.language = item.language;
+                        book.summary = item.summary;
+                    }
+                    let condition = fields.text(6)?;
+                    book.condition = parse_condition(condition)
+                        .ok_or_else(|| fields.invalid(format!("unknown condition {:?}", condition)))?;
+                    let times_borrowed = fields.number(7)?;
+                    for tag in record.iter().skip(9) {
+                        book.add_tag(tag);
+                    }
+                    book.set_times_borrowed(times_borrowed);
+                    if fields.optional_text(8).is_some() {
+                        fields.member(8, &member_index)?;
+                        borrowers.insert(id, (fields.id(8)?, line));
+                    }
+                    books.push(book);
+                }
+                "loan" => {
+                    let loan = Loan {
+                        member_id: fields.id(1)?,
+                        book_id: fields.id(2)?,
+                        borrowed_on: fields.date(3)?,
+                        due_on: fields.date(4)?,
+                        // Files saved before renewals were counted have no sixth field
+                        renewals: match fields.optional_text(5) {
+                            Some(_) => fields.number(5)?,
+                            None => 0,
+                        },
+                    };
+                    fields.member(1, &member_index)?;
+                    let digital = books
+                        .iter()
+                        .any(|b: &Book| b.id() == loan.book_id && b.format == Format::Digital);
+                    let borrower = borrowers.get(&loan.book_id).map(|&(member_id, _)| member_id);
+                    if !digital && borrower != Some(loan.member_id) {
+                        return Err(fields.invalid(format!(
+                            "loan of book {} to member {}, who doesn't have it",
+                            loan.book_id, loan.member_id
+                        )));
+                    }
+                    loans.push(loan);
+                }
+                "hold" => {
+                    fields.member(1, &member_index)?;
+                    let hold = Hold {
+                        member_id: fields.id(1)?,
+                        book_id: fields.id(2)?,
+                        placed_on: fields.date(3)?,
+                        ready_since: fields.optional_date(4)?,
+                    };
+                    holds.push((line, hold));
+                }
+                "accrual" => fee_accruals.push(FeeAccrual {
+                    member_id: fields.id(1)?,
+                    book_id: fields.id(2)?,
+                    borrowed_on: fields.date(3)?,
+                    on: fields.date(4)?,
+                    amount: fields.money(5)?,
+                }),
+                "fine" => {
+                    let kind = match fields.text(3)? {
+                        "assessed" => FineKind::Assessed,
+                        "collected" => FineKind::Collected,
+                        "waived" => FineKind::Waived,
+                        other => return Err(fields.invalid(format!("unknown fine kind {:?}", other))),
+                    };
+                    fine_ledger.push(FineEntry {
+                        member_id: fields.id(1)?,
+                        on: fields.date(2)?,
+                        kind,
+                        amount: fields.money(4)?,
+                    });
+                }
+                "ill" => {
+                    fields.member(2, &member_index)?;
+                    let id = fields.id(1)?;
+                    if ill_requests.iter().any(|r| r.id == id) {
+                        return Err(fields.invalid(format!("interlibrary loan {} appears twice", id)));
+                    }
+                    let status = match fields.text(3)? {
+                        "requested" => IllStatus::Requested,
+                        "shipped" => IllStatus::Shipped,
+                        "received" => IllStatus::Received,
+                        "returned" => IllStatus::Returned,
+                        other => return Err(fields.invalid(format!("unknown interlibrary loan status {:?}", other))),
+                    };
+                    ill_requests.push(IllRequest {
+                        id,
+                        member_id: fields.id(2)?,
+                        title: fields.text(8)?.to_string(),
+                        isbn: fields.optional_text(9),
+                        lender: fields.optional_text(10),
+                        status,
+                        requested_on: fields.date(4)?,
+                        shipped_on: fields.optional_date(5)?,
+                        received_on: fields.optional_date(6)?,
+                        returned_on: fields.optional_date(7)?,
+                        costs: Vec::new(),
+                    });
+                }
+                "illcost" => {
+                    let id = fields.id(1)?;
+                    let cost = IllCost {
+                        description: fields.text(4)?.to_string(),
+                        amount: fields.money(3)?,
+                        on: fields.date(2)?,
+                    };
+                    ill_requests
+                        .iter_mut()
+                        .find(|r| r.id == id)
+                        .ok_or_else(|| fields.invalid(format!("cost of unknown interlibrary loan {}", id)))?
+                        .costs
+                        .push(cost);
+                }
+                "review" => {
+                    fields.member(1, &member_index)?;
+                    let rating = fields.number(3)?;
+                    if !RATINGS.contains(&rating) {
+                        return Err(fields.invalid(format!("rating {} is not between 1 and 5", rating)));
+                    }
+                    let review = Review {
+                        member_id: fields.id(1)?,
+                        book_id: fields.id(2)?,
+                        rating,
+                        text: fields.optional_text(4).unwrap_or_default(),
+                    };
+                    reviews.push((line, review));
+                }
+                "location" => {
+                    let id = fields.id(1)?;
+                    if !book_ids.contains(&id) {
+                        return Err(fields.invalid(format!("location of unknown book {}", id)));
+                    }
+                    let location = Location::new(fields.text(2)?, fields.text(3)?, fields.text(4)?);
+                    if locations.insert(id, location).is_some() {
+                        return Err(fields.invalid(format!("location of book {} appears twice", id)));
+                    }
+                }
+                "shelve" => {
+                    let book_id = fields.id(1)?;
+                    if !book_ids.contains(&book_id) {
+                        return Err(fields.invalid(format!("re-shelving of unknown book {}", book_id)));
+                    }
+                    reshelving.push(AwaitingShelving {
+                        book_id,
+                        returned_on: fields.date(2)?,
+                    });
+                }
+                "lost" => {
+                    let book_id = fields.id(1)?;
+                    if !book_ids.contains(&book_id) {
+                        return Err(fields.invalid(format!("lost record for unknown book {}", book_id)));
+                    }
+                    if borrowers.contains_key(&book_id) {
+                        return Err(fields.invalid(format!("lost book {} is on loan", book_id)));
+                    }
+                    // The member may have left since, so isn't looked up
+                    let record = LostBookRecord {
+                        loan: Loan {
+                            member_id: fields.id(2)?,
+                            book_id,
+                            borrowed_on: fields.date(3)?,
+                            due_on: fields.date(4)?,
+                            renewals: fields.number(5)?,
+                        },
+                        declared_on: fields.date(6)?,
+                        replacement: fields.money(7)?,
+                        fine: fields.money(8)?,
+                    };
+                    if lost_books.insert(book_id, record).is_some() {
+                        return Err(fields.invalid(format!("lost book {} appears twice", book_id)));
+                    }
+                }
+                "collection" => {
+                    let name = fields.text(1)?;
+                    if collections.iter().any(|c| c.name == name) {
+                        return Err(fields.invalid(format!("collection {:?} appears twice", name)));
+                    }
+                    let mut collection = Collection::new(name, fields.text(2).unwrap_or_default());
+                    let mut listed = Vec::new();
+                    for index in 3..record.len() {
+                        let id = fields.id(index)?;
+                        if !book_ids.contains(&id) {
+                            return Err(fields.invalid(format!("collection {:?} lists unknown book {}", name, id)));
+                        }
+                        listed.push(id);
+                    }
+                    collection.set_book_ids(listed);
+                    collections.push(collection);
+                }
+                other => return Err(fields.invalid(format!("unknown record kind {:?}", other))),
+            }
+        }
+
+        if let Some((id, item)) = details.iter().min_by_key(|(_, item)| item.line) {
+            return Err(StoreError::Invalid {
+                line: item.line,
+                message: format!("item details for unknown book {}", id),
+            });
+        }
+        // Books come after members in the file, so holds are checked at the end
+        if let Some((line, hold)) = holds.iter().find(|(_, h)| !book_ids.contains(&h.book_id)) {
+            return Err(StoreError::Invalid {
+                line: *line,
+                message: format!("hold on unknown book {}", hold.book_id),
+            });
+        }
+
+        let unrecorded = borrowers
+            .iter()
+            .filter(|(&book_id, _)| !loans.iter().any(|l: &Loan| l.book_id == book_id))
+            .min_by_key(|(_, &(_, line))| line);
+        if let Some((book_id, &(member_id, line))) = unrecorded {
+            return Err(StoreError::Invalid {
+                line,
+                message: format!("book {} is lent to member {} without a loan record", book_id, member_id),
+            });
+        }
+
+        let mut library = Library::with_config(config);
+        library
+            .check_capacity(book_ids.len() - lost_books.len(), members.len())
+            .map_err(StoreError::Library)?;
+        for mut book in books {
+            book.location = locations.remove(&book.id());
+            match lost_books.remove(&book.id()) {
+                Some(record) => library.lost_books.push(LostBook {
+                    book,
+                    loan: record.loan,
+                    declared_on: record.declared_on,
+                    replacement: record.replacement,
+                    fine: record.fine,
+                }),
+                None => library.insert_book(book),
+            }
+        }
+        for member in members {
+            library.insert_member(member);
+        }
+        library.restore_loans(loans).map_err(StoreError::Library)?;
+        library.holds = holds.into_iter().map(|(_, hold)| hold).collect();
+        library.fee_accruals = fee_accruals;
+        library.fine_ledger = fine_ledger;
+        library.ill_requests = ill_requests;
+        library.reshelving = reshelving;
+        library.collections = collections;
+        for (line, review) in reviews {
+            let book_id = review.book_id;
+            library.record_review(review).map_err(|_| StoreError::Invalid {
+                line,
+                message: format!("review of unknown book {}", book_id),
+            })?;
+        }
+        Ok(library)
+    }
+}
+
+// =============================================================================
+// FIELD HELPERS
+// =============================================================================
+
+/// A `lost` record, joined with its `book` record once all are read.
+struct LostBookRecord {
+    loan: Loan,
+    declared_on: NaiveDate,
+    replacement: Money,
+    fine: Money,
+}
+
+/// An `item` record waiting for its `book` record.
+struct ItemDetails {
+    line: u64,
+    kind: ItemKind,
+    format: Format,
+    year: Option<u16>,
+    publisher: Option<String>,
+    pages: Option<u32>,
+    language: Option<String>,
+    summary: Option<String>,
+}
+
+/// One record being loaded, with its line number for error messages.
+struct Fields<'a> {
+    record: &'a csv::StringRecord,
+    line: u64,
+}
+
+impl Fields<'_> {
+    fn invalid(&self, message: String) -> StoreError {
+        StoreError::Invalid { line: self.line, message }
+    }
+
+    fn text(&self, index: usize) -> Result<&str, StoreError> {
+        self.record
+            .get(index)
+            .ok_or_else(|| self.invalid(format!("missing field {}", index + 1)))
+    }
+
+    /// `None` for an empty or missing field.
+    fn optional_text(&self, index: usize) -> Option<String> {
+        self.record.get(index).filter(|s| !s.is_empty()).map(String::from)
+    }
+
+    fn number<T: std::str::FromStr>(&self, index: usize) -> Result<T, StoreError> {
+        let text = self.text(index)?;
+        text.parse().map_err(|_| self.invalid(format!("{:?} is not a number", text)))
+    }
+
+    fn id(&self, index: usize) -> Result<u64, StoreError> {
+        self.number(index)
+    }
+
+    fn date(&self, index: usize) -> Result<NaiveDate, StoreError> {
+        let text = self.text(index)?;
+        NaiveDate::parse_from_str(text, "%Y-%m-%d").map_err(|_| self.invalid(format!("{:?} is not a date", text)))
+    }
+
+    fn optional_date(&self, index: usize) -> Result<Option<NaiveDate>, StoreError> {
+        match self.optional_text(index) {
+            Some(_) => self.date(index).map(Some),
+            None => Ok(None),
+        }
+    }
+
+    /// An amount written by `money_field`, e.g. `125 USD`.
+    fn money(&self, index: usize) -> Result<Money, StoreError> {
+        let text = self.text(index)?;
+        text.split_once(' ')
+            .and_then(|(cents, code)| Some(Money::new(cents.parse().ok()?, Currency::new(code)?)))
+            .ok_or_else(|| self.invalid(format!("{:?} is not an amount", text)))
+    }
+
+    /// Position in `members` of the member whose ID is in field `index`.
+    fn member(&self, index: usize, members: &HashMap<u64, usize>) -> Result<usize, StoreError> {
+        let id = self.id(index)?;
+        members
+            .get(&id)
+            .copied()
+            .ok_or_else(|| self.invalid(format!("unknown member {}", id)))
+    }
+}
+
+fn money_field(amount: Money) -> String {
+    format!("{} {}", amount.cents(), amount.currency())
+}
+
+fn date_field(date: Option<NaiveDate>) -> String {
+    date.map_or(String::new(), |d| d.to_string())
+}
+
+fn parse_condition(name: &str) -> Option<Condition> {
+    [Condition::New, Condition::Good, Condition::Worn, Condition::Damaged, Condition::Lost]
+        .into_iter()
+        .find(|condition| format!("{:?}", condition) == name)
+}
+
+// =============================================================================
+// TESTS
+// =============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(2024, 3, day).unwrap()
+    }
+
+    fn round_trip(lib: &Library) -> Library {
+        let mut file = Vec::new();
+        lib.save(&mut file).unwrap();
+        Library::load(file.as_slice(), LibraryConfig::default()).unwrap()
+    }
+
+    #[test]
+    fn test_round_trip_keeps_loans_and_fines() {
+        let mut lib = Library::new();
+        let mut dune = Book::new(1, "Dune, Part One", Genre::SciFi);
+        dune.author = Some(String::from("Frank Herbert"));
+        dune.add_tag("classic");
+        lib.add_book(dune).unwrap();
+        lib.add_book(Book::new(2, "Emma", Genre::Custom(String::from("Romance")))).unwrap();
+        let mut alice = Member::new(10, "Alice", MembershipTier::Basic);
+        alice.set_email(Some("alice@example.com")).unwrap();
+        alice.upgrade_tier(MembershipTier::Silver, date(1)).unwrap();
+        lib.register_member(alice).unwrap();
+        lib.register_member(Member::new(11, "Bob", MembershipTier::Gold)).unwrap();
+        lib.register_member(crate::member::create_guest(12, "Visitor")).unwrap();
+        lib.checkout(10, 2, date(1)).unwrap();
+        lib.checkout(10, 1, date(2)).unwrap();
+        lib.accrue_fees(date(17));
+        lib.return_book(10, 2, date(20)).unwrap();
+        lib.place_hold(11, 1, date(21)).unwrap();
+
+        let loaded = round_trip(&lib);
+        assert_eq!(loaded.check_invariants(), Ok(()));
+        assert_eq!(loaded.loans(), lib.loans());
+        assert_eq!(loaded.holds(), lib.holds());
+        assert_eq!(loaded.fee_accruals(10), lib.fee_accruals(10));
+        assert_eq!(loaded.fine_ledger(), lib.fine_ledger());
+
+        let alice = loaded.find_member(10).unwrap();
+        assert_eq!(alice.outstanding_fines(), lib.find_member(10).unwrap().outstanding_fines());
+        assert_eq!(alice.history(), lib.find_member(10).unwrap().history());
+        let change = &alice.tier_history()[0];
+        assert_eq!((change.from().name(), change.to().name(), change.effective_on), ("Basic", "Silver", date(1)));
+        assert_eq!((alice.email(), alice.phone()), (Some("alice@example.com"), None));
+        let dune = loaded.borrowed_books(10)[0];
+        assert_eq!((dune.title.as_str(), dune.tags()), ("Dune, Part One", &[String::from("classic")][..]));
+        assert_eq!(dune.author.as_deref(), Some("Frank Herbert"));
+        assert!(!dune.is_available());
+
+        let emma = loaded.find_book(2).unwrap();
+        assert_eq!(emma.genre, Genre::Custom(String::from("Romance")));
+        assert_eq!(emma.times_borrowed(), 1);
+        assert_eq!(loaded.find_member(11).unwrap().tier().name(), "Gold");
+        assert!(!loaded.find_member(12).unwrap().tier().can_place_holds());
+    }
+
+    #[test]
+    fn test_round_trip_keeps_item_details() {
+        let mut lib = Library::new();
+        lib.add_book(Book::periodical(1, "Rust Monthly", Genre::Technical, "May 2024")).unwrap();
+        lib.add_book(Book::media(2, "Dune", Genre::SciFi, 155)).unwrap();
+        let mut emma = Book::new(3, "Emma", Genre::Fiction);
+        emma.year = Some(1815);
+        lib.add_book(emma).unwrap();
+        let mut ebook = Book::new(4, "Emma", Genre::Fiction);
+        ebook.format = Format::Digital;
+        lib.add_book(ebook).unwrap();
+        lib.register_member(Member::new(10, "Alice", MembershipTier::Basic)).unwrap();
+        lib.register_member(Member::new(11, "Bob", MembershipTier::Basic)).unwrap();
+        lib.checkout(10, 2, date(1)).unwrap();
+        lib.checkout(10, 4, date(1)).unwrap();
+        lib.checkout(11, 4, date(2)).unwrap();
+        lib.renew(10, 2, date(3)).unwrap();
+
+        let loaded = round_trip(&lib);
+        assert_eq!(loaded.check_invariants(), Ok(()));
+        assert_eq!(loaded.loans(), lib.loans());
+        assert_eq!(loaded.find_book(4).unwrap().format, Format::Digital);
+        assert_eq!(loaded.find_book(4).unwrap().times_borrowed(), 2);
+        assert_eq!(loaded.find_book(1).unwrap().kind, ItemKind::Periodical { issue: String::from("May 2024") });
+        assert_eq!(loaded.borrowed_books(10)[0].kind, ItemKind::Media { runtime_minutes: 155 });
+        assert_eq!(loaded.find_book(3).unwrap().kind, ItemKind::Book);
+        assert_eq!(loaded.find_book(3).unwrap().year, Some(1815));
+        assert_eq!(loaded.find_book(4).unwrap().year, None);
+
+        let mut lib = Library::new();
+        let summary = "Ownership, traits and \"fearless\" concurrency, explained";
+        lib.add_book(Book::builder(5, "The Rust Book").publisher("No Starch").pages(552).summary(summary).build()).unwrap();
+        let book = round_trip(&lib).find_book(5).unwrap().clone();
+        assert_eq!(book.publisher.as_deref(), Some("No Starch"));
+        assert_eq!((book.pages, book.year, book.language), (Some(552), None, None));
+        assert_eq!(book.summary.as_deref(), Some(summary));
+    }
+
+    #[test]
+    fn test_round_trip_keeps_interlibrary_loans() {
+        let mut lib = Library::new();
+        lib.register_member(Member::new(10, "Alice", MembershipTier::Basic)).unwrap();
+        let id = lib.request_ill(10, "Ulysses, Annotated", Some("0679722769"), date(1)).unwrap();
+        lib.record_ill_shipped(id, "City Library", date(4)).unwrap();
+        lib.add_ill_cost(id, "Postage", Money::from_cents(450), date(4)).unwrap();
+        lib.request_ill(10, "Emma", None, date(5)).unwrap();
+
+        let loaded = round_trip(&lib);
+        assert_eq!(loaded.ill_requests(), lib.ill_requests());
+        assert_eq!(loaded.ill_spending(), Money::from_cents(450));
+
+        let load = |text: &str| Library::load(text.as_bytes(), LibraryConfig::default()).err().map(|e| e.to_string());
+        assert_eq!(
+            load("ill,1,10,requested,2024-03-01,,,,Emma,,\n").as_deref(),
+            Some("Line 1: unknown member 10")
+        );
+        assert_eq!(
+            load("illcost,1,2024-03-04,450 USD,Postage\n").as_deref(),
+            Some("Line 1: cost of unknown interlibrary loan 1")
+        );
+    }
+
+    #[test]
+    fn test_round_trip_keeps_locations_and_reshelving() {
+        let mut lib = Library::new();
+        lib.add_book(Book::new(1, "Dune", Genre::SciFi)).unwrap();
+        lib.add_book(Book::new(2, "Emma", Genre::Fiction)).unwrap();
+        lib.register_member(Member::new(10, "Alice", MembershipTier::Basic)).unwrap();
+        lib.move_book(1, Location::new("Main", "Fiction Hall", "SCI-3")).unwrap();
+        lib.checkout(10, 1, date(1)).unwrap();
+        lib.checkout(10, 2, date(1)).unwrap();
+        lib.return_book(10, 2, date(4)).unwrap();
+
+        let loaded = round_trip(&lib);
+        assert_eq!(loaded.get_book(1).unwrap().location, Some(Location::new("Main", "Fiction Hall", "SCI-3")));
+        assert_eq!(loaded.get_book(2).unwrap().location, None);
+        assert_eq!(loaded.awaiting_shelving(), lib.awaiting_shelving());
+
+        let load = |text: &str| Library::load(text.as_bytes(), LibraryConfig::default()).err().map(|e| e.to_string());
+        assert_eq!(load("location,1,Main,Hall,A\n").as_deref(), Some("Line 1: location of unknown book 1"));
+        assert_eq!(load("shelve,1,2024-03-01\n").as_deref(), Some("Line 1: re-shelving of unknown book 1"));
+    }
+
+    #[test]
+    fn test_round_trip_keeps_lost_books() {
+        let mut lib = Library::new();
+        lib.add_book(Book::new(1, "Dune", Genre::SciFi)).unwrap();
+        lib.add_book(Book::new(2, "Emma", Genre::Fiction)).unwrap();
+        lib.register_member(Member::new(10, "Alice", MembershipTier::Basic)).unwrap();
+        let due = lib.checkout(10, 1, date(1)).unwrap();
+        lib.mark_lost(10, 1, due + chrono::Duration::days(60)).unwrap();
+
+        let mut loaded = round_trip(&lib);
+        assert_eq!(loaded.check_invariants(), Ok(()));
+        assert!(loaded.get_book(1).is_none());
+        let lost = &loaded.lost_books()[0];
+        assert_eq!((lost.book.title.as_str(), &lost.loan), ("Dune", &lib.lost_books()[0].loan));
+        assert_eq!((lost.replacement, lost.fine), (Money::from_cents(2000), Money::from_cents(1500)));
+        assert_eq!(loaded.mark_found(1, due), Ok(Money::from_cents(3500)));
+
+        let load = |text: &str| Library::load(text.as_bytes(), LibraryConfig::default()).err().map(|e| e.to_string());
+        let lost = "lost,1,10,2024-03-01,2024-03-15,0,2024-05-14,2000 USD,1500 USD\n";
+        assert_eq!(load(lost).as_deref(), Some("Line 1: lost record for unknown book 1"));
+    }
+
+    #[test]
+    fn test_round_trip_keeps_collections() {
+        let mut lib = Library::new();
+        lib.add_book(Book::new(1, "Dune", Genre::SciFi)).unwrap();
+        lib.add_book(Book::new(2, "Emma", Genre::Fiction)).unwrap();
+        lib.register_member(Member::new(10, "Alice", MembershipTier::Basic)).unwrap();
+        lib.create_collection("Summer, 2024", "Beach reads").unwrap();
+        lib.create_collection("Empty", "").unwrap();
+        lib.add_to_collection("Summer, 2024", 2).unwrap();
+        lib.add_to_collection("Summer, 2024", 1).unwrap();
+        let due = lib.checkout(10, 1, date(1)).unwrap();
+        lib.mark_lost(10, 1, due + chrono::Duration::days(60)).unwrap();
+
+        let loaded = round_trip(&lib);
+        assert_eq!(loaded.collections(), lib.collections());
+        assert_eq!(loaded.render_collection("Summer, 2024"), lib.render_collection("Summer, 2024"));
+
+        let load = |text: &str| Library::load(text.as_bytes(), LibraryConfig::default()).err().map(|e| e.to_string());
+        let unknown = "collection,Summer,,3\n";
+        assert_eq!(load(unknown).as_deref(), Some("Line 1: collection \"Summer\" lists unknown book 3"));
+        let twice = "collection,Summer,\ncollection,Summer,\n";
+        assert_eq!(load(twice).as_deref(), Some("Line 2: collection \"Summer\" appears twice"));
+    }
+
+    #[test]
+    fn test_round_trip_recounts_ratings() {
+        let mut lib = Library::new();
+        lib.add_book(Book::new(1, "Dune", Genre::SciFi)).unwrap();
+        lib.register_member(Member::new(10, "Alice", MembershipTier::Basic)).unwrap();
+        lib.register_member(Member::new(11, "Bob", MembershipTier::Basic)).unwrap();
+        for member_id in [10, 11] {
+            lib.checkout(member_id, 1, date(1)).unwrap();
+            lib.return_book(member_id, 1, date(2)).unwrap();
+        }
+        lib.rate(10, 1, 5, "Sand, spice,\nand politics").unwrap();
+        lib.rate(11, 1, 2, "").unwrap();
+        lib.checkout(11, 1, date(3)).unwrap();
+
+        let loaded = round_trip(&lib);
+        assert_eq!(loaded.reviews(), lib.reviews());
+        assert_eq!(loaded.get_book(1).unwrap().average_rating(), Some(3.5));
+
+        let load = |text: &str| Library::load(text.as_bytes(), LibraryConfig::default()).err().map(|e| e.to_string());
+        let alice = "member,10,Alice,Basic,0 USD,,active,\n";
+        assert_eq!(load(&format!("{}review,10,1,4,\n", alice)).as_deref(), Some("Line 2: review of unknown book 1"));
+        assert_eq!(
+            load(&format!("{}review,10,1,9,\n", alice)).as_deref(),
+            Some("Line 2: rating 9 is not between 1 and 5")
+        );
+    }
+
+    #[test]
+    fn test_invalid_records_are_rejected() {
+        let load = |text: &str| Library::load(text.as_bytes(), LibraryConfig::default());
+
+        // Library isn't Debug, so compare messages rather than unwrap_err()
+        let error = |text: &str| load(text).err().map(|e| e.to_string());
+
+        assert_eq!(
+            error("member,10,Alice,Basic,0 USD,,active,\nbook,1,Dune,Sci-Fi,,,New,0,99\n").as_deref(),
+            Some("Line 2: unknown member 99")
+        );
+        assert_eq!(
+            error("member,10,Alice,Basic,0 USD,,active,\nbook,1,Dune,Sci-Fi,,,New,0,10\n").as_deref(),
+            Some("Line 2: book 1 is lent to member 10 without a loan record")
+        );
+        assert_eq!(
+            error("book,1,Dune,Sci-Fi,,,New,0,\nbook,1,Emma,Fiction,,,New,0,\n").as_deref(),
+            Some("Line 2: book 1 appears twice")
+        );
+        assert_eq!(
+            error("item,1,media,155\nbook,2,Dune,Sci-Fi,,,New,0,\n").as_deref(),
+            Some("Line 1: item details for unknown book 1")
+        );
+        assert_eq!(
+            error("member,0,Alice,Basic,0 USD,,active,\n").as_deref(),
+            Some("Line 1: ID must not be 0")
+        );
+        assert_eq!(
+            error("book,1,,Sci-Fi,,,New,0,\n").as_deref(),
+            Some("Line 1: title must not be empty")
+        );
+        assert!(matches!(load("shelf,1\n"), Err(StoreError::Invalid { line: 1, .. })));
+
+        let kiosk = LibraryConfig { max_members: Some(1), ..LibraryConfig::default() };
+        let two = "member,10,Alice,Basic,0 USD,,active,\nmember,11,Bob,Basic,0 USD,,active,\n";
+        assert_eq!(
+            Library::load(two.as_bytes(), kiosk).err().map(|e| e.to_string()).as_deref(),
+            Some("Could not load library: The library is full: it allows at most 1 members")
+        );
+    }
+
+    #[test]
+    fn test_custom_tiers_are_not_saved() {
+        #[derive(Debug)]
+        struct Faculty;
+
+        impl TierPolicy for Faculty {
+            fn name(&self) -> &str {
+                "Faculty"
+            }
+            fn borrow_limit(&self) -> usize {
+                50
+            }
+            fn loan_days(&self) -> u32 {
+                120
+            }
+            fn discount_percentage(&self) -> u8 {
+                100
+            }
+        }
+
+        let mut lib = Library::new();
+        lib.register_member(Member::new(1, "Dr. Ferris", Faculty)).unwrap();
+        let mut file = Vec::new();
+        assert!(matches!(lib.save(&mut file), Err(StoreError::CustomTier { member_id: 1, .. })));
+        assert!(file.is_empty());
+    }
+}
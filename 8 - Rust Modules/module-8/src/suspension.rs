@@ -0,0 +1,189 @@
+//! Suspension module - rules that decide when a member may not borrow.
+//!
+//! A [`StatusRule`] looks at one member and says whether they're in breach.
+//! The library re-checks a member whenever their balance changes (a return,
+//! a fee accrual, a payment): an active member in breach of any rule is
+//! [`Suspended`](MemberStatus::Suspended) with a [`SuspensionReason::Rule`],
+//! and that suspension is lifted as soon as no rule is broken any more.
+//! Suspensions made by staff are left alone by the rules.
+//!
+//! Every library starts with a [`FinesThreshold`] at the configured
+//! [`max_unpaid_fines`](crate::LibraryConfig::max_unpaid_fines).
+//!
+//! ```
+//! use chrono::NaiveDate;
+//! use module_8::{Book, Genre, Library, LibraryConfig, LibraryError, Member, MembershipTier, Money};
+//!
+//! let mut lib = Library::with_config(LibraryConfig {
+//!     max_unpaid_fines: Money::from_cents(300),
+//!     ..LibraryConfig::default()
+//! });
+//! lib.add_book(Book::new(1, "Dune", Genre::SciFi)).unwrap();
+//! lib.register_member(Member::new(10, "Alice", MembershipTier::Basic)).unwrap();
+//! let day = |d| NaiveDate::from_ymd_opt(2024, 3, d).unwrap();
+//!
+//! lib.checkout(10, 1, day(1)).unwrap();
+//! lib.return_book(10, 1, day(31)).unwrap(); // 16 days late: $4.00
+//! assert!(matches!(lib.checkout(10, 1, day(31)), Err(LibraryError::MemberSuspended { .. })));
+//!
+//! lib.pay_fines(10, Money::from_cents(100), day(31)).unwrap();
+//! assert!(lib.checkout(10, 1, day(31)).is_ok());
+//! ```
+
+use std::fmt;
+
+use crate::error::LibraryError;
+use crate::member::{Member, MemberStatus, SuspensionReason};
+use crate::money::Money;
+use crate::Library;
+
+/// A condition a member must meet to keep borrowing.
+///
+/// `Send + Sync` so a `Library` holding rules stays shareable across threads.
+pub trait StatusRule: fmt::Debug + Send + Sync {
+    /// Why `member` should be suspended, or `None` if they meet the rule.
+    fn violation(&self, member: &Member) -> Option<String>;
+}
+
+/// Suspends members who owe more than `max_fines`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct FinesThreshold {
+    pub max_fines: Money,
+}
+
+impl StatusRule for FinesThreshold {
+    fn violation(&self, member: &Member) -> Option<String> {
+        let owed = member.outstanding_fines();
+        (owed > self.max_fines).then(|| format!("owes {} in fines (limit {})", owed, self.max_fines))
+    }
+}
+
+impl Library {
+    /// Adds a rule and re-checks every member against the full rule set.
+    pub fn add_status_rule(&mut self, rule: impl StatusRule + 'static) {
+        self.status_rules.push(Box::new(rule));
+        self.apply_status_rules();
+    }
+
+    /// Re-checks every member against the rules, returning how many are
+    /// suspended by a rule afterwards.
+    pub fn apply_status_rules(&mut self) -> usize {
+        for id in self.member_order.clone() {
+            self.review_status(id);
+        }
+        self.members()
+            .filter(|m| matches!(m.status(), MemberStatus::Suspended { reason: SuspensionReason::Rule(_) }))
+            .count()
+    }
+
+    /// Suspends a member by hand; only [`reinstate_member`] lifts it.
+    ///
+    /// [`reinstate_member`]: Library::reinstate_member
+    pub fn suspend_member(&mut self, member_id: u64, reason: &str) -> Result<(), LibraryError> {
+        let member = self.member_mut(member_id).ok_or(LibraryError::MemberNotFound(member_id))?;
+        if *member.status() == MemberStatus::Closed {
+            return Err(LibraryError::MemberInactive(member_id));
+        }
+        member.set_status(MemberStatus::Suspended {
+            reason: SuspensionReason::Staff(reason.to_string()),
+        });
+        Ok(())
+    }
+
+    /// Lifts any suspension, unless a rule still applies, in which case the
+    /// member stays suspended under that rule.
+    pub fn reinstate_member(&mut self, member_id: u64) -> Result<(), LibraryError> {
+        let member = self.member_mut(member_id).ok_or(LibraryError::MemberNotFound(member_id))?;
+        if *member.status() == MemberStatus::Closed {
+            return Err(LibraryError::MemberInactive(member_id));
+        }
+        member.set_status(MemberStatus::Active);
+        self.review_status(member_id);
+        Ok(())
+    }
+
+    /// Applies the rules to one member after something about them changed.
+    pub(crate) fn review_status(&mut self, member_id: u64) {
+        let Some(member) = self.members.get(&member_id) else { return };
+        let violation = self.status_rules.iter().find_map(|rule| rule.violation(member));
+        let status = match (member.status(), violation) {
+            (MemberStatus::Active, Some(why)) => MemberStatus::Suspended {
+                reason: SuspensionReason::Rule(why),
+            },
+            (MemberStatus::Suspended { reason: SuspensionReason::Rule(_) }, None) => MemberStatus::Active,
+            _ => return,
+        };
+        if let Some(member) = self.member_mut(member_id) {
+            member.set_status(status);
+        }
+    }
+}
+
+// =============================================================================
+// TESTS
+// =============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Book, Genre, MembershipTier};
+    use chrono::NaiveDate;
+
+    fn date(day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(2024, 3, day).unwrap()
+    }
+
+    /// Alice returns Dune 41 days late: $10.25, over the $10.00 limit.
+    fn suspended_alice() -> Library {
+        let mut lib = Library::new();
+        lib.add_book(Book::new(1, "Dune", Genre::SciFi)).unwrap();
+        lib.register_member(Member::new(10, "Alice", MembershipTier::Basic)).unwrap();
+        lib.checkout(10, 1, NaiveDate::from_ymd_opt(2024, 2, 1).unwrap()).unwrap();
+        lib.return_book(10, 1, date(27)).unwrap();
+        lib
+    }
+
+    #[test]
+    fn test_unpaid_fines_suspend_until_paid() {
+        let mut lib = suspended_alice();
+        let status = lib.find_member(10).unwrap().status().clone();
+        assert!(matches!(status, MemberStatus::Suspended { reason: SuspensionReason::Rule(_) }));
+        assert!(matches!(lib.checkout(10, 1, date(28)), Err(LibraryError::MemberSuspended { member_id: 10, .. })));
+
+        // Paying down to the limit is enough
+        assert_eq!(lib.pay_fines(10, Money::from_cents(25), date(28)), Ok(Money::from_cents(1000)));
+        assert!(lib.find_member(10).unwrap().is_active());
+        assert!(lib.checkout(10, 1, date(28)).is_ok());
+    }
+
+    #[test]
+    fn test_staff_suspension_outlasts_payment() {
+        let mut lib = suspended_alice();
+        lib.suspend_member(10, "damaged several books").unwrap();
+        lib.pay_fines(10, Money::from_cents(1025), date(28)).unwrap();
+        assert!(!lib.find_member(10).unwrap().is_active());
+
+        lib.reinstate_member(10).unwrap();
+        assert!(lib.find_member(10).unwrap().is_active());
+    }
+
+    #[test]
+    fn test_added_rule_applies_to_existing_members() {
+        #[derive(Debug)]
+        struct NoFinesAtAll;
+        impl StatusRule for NoFinesAtAll {
+            fn violation(&self, member: &Member) -> Option<String> {
+                (!member.outstanding_fines().is_zero()).then(|| String::from("has unpaid fines"))
+            }
+        }
+
+        let mut lib = suspended_alice();
+        lib.pay_fines(10, Money::from_cents(1000), date(28)).unwrap();
+        assert!(lib.find_member(10).unwrap().is_active());
+        lib.add_status_rule(NoFinesAtAll);
+        assert_eq!(
+            lib.find_member(10).unwrap().status(),
+            &MemberStatus::Suspended { reason: SuspensionReason::Rule(String::from("has unpaid fines")) }
+        );
+    }
+}
@@ -0,0 +1,101 @@
+//! Tags module - free-form labels on books.
+//!
+//! Genres give every book exactly one coarse category; tags add any number
+//! of finer ones ("beginner", "award-winner", "book-club"). Matching is
+//! case-insensitive because tags are normalized by `Book::add_tag`.
+
+use std::collections::BTreeMap;
+
+use crate::book::{normalize_tag, Book};
+use crate::error::LibraryError;
+use crate::Library;
+
+impl Library {
+    /// Tags a catalogued book, whether it's on the shelf or on loan.
+    ///
+    /// Returns `Ok(false)` if the tag is blank or already present.
+    pub fn tag_book(&mut self, book_id: u64, tag: &str) -> Result<bool, LibraryError> {
+        let book = self.book_mut(book_id).ok_or(LibraryError::BookNotFound(book_id))?;
+        Ok(book.add_tag(tag))
+    }
+
+    /// Every catalogued book carrying `tag` (ignoring case): shelf books in
+    /// catalog order, then books on loan.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use module_8::{Book, Genre, Library};
+    ///
+    /// let mut lib = Library::new();
+    /// let mut book = Book::new(1, "The Rust Book", Genre::Technical);
+    /// book.add_tag("beginner");
+    /// lib.add_book(book).unwrap();
+    /// lib.add_book(Book::new(2, "Dune", Genre::SciFi)).unwrap();
+    ///
+    /// let found = lib.books_with_tag("Beginner");
+    /// assert_eq!(found.len(), 1);
+    /// assert_eq!(found[0].title, "The Rust Book");
+    /// ```
+    pub fn books_with_tag(&self, tag: &str) -> Vec<&Book> {
+        let tag = normalize_tag(tag);
+        self.catalog()
+            .filter(|book| book.tags().contains(&tag))
+            .collect()
+    }
+
+    /// How many catalogued books carry each tag, keyed by tag name.
+    pub fn tag_cloud(&self) -> BTreeMap<String, usize> {
+        let mut cloud = BTreeMap::new();
+        for tag in self.catalog().flat_map(Book::tags) {
+            *cloud.entry(tag.clone()).or_default() += 1;
+        }
+        cloud
+    }
+
+    /// Every catalogued book: those on the shelf in catalog order, then the
+    /// books out on loan, in the order they were lent. Unlike
+    /// [`books`](Library::books), this includes books out on loan.
+    pub fn catalog(&self) -> impl Iterator<Item = &Book> {
+        let lent = self
+            .loans
+            .iter()
+            .filter(move |loan| !self.is_digital_loan(loan))
+            .map(move |loan| &self.books[&loan.book_id]);
+        self.books().chain(lent)
+    }
+}
+
+// =============================================================================
+// TESTS
+// =============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Genre, Member, MembershipTier};
+    use chrono::NaiveDate;
+
+    #[test]
+    fn test_tags_follow_books_on_loan() {
+        let mut lib = Library::new();
+        lib.add_book(Book::new(1, "Dune", Genre::SciFi)).unwrap();
+        lib.add_book(Book::new(2, "Emma", Genre::Fiction)).unwrap();
+        lib.register_member(Member::new(10, "Alice", MembershipTier::Basic)).unwrap();
+        lib.checkout(10, 1, NaiveDate::from_ymd_opt(2024, 3, 1).unwrap()).unwrap();
+
+        assert_eq!(lib.tag_book(1, "Classic"), Ok(true));
+        assert_eq!(lib.tag_book(2, "classic"), Ok(true));
+        assert_eq!(lib.tag_book(2, "CLASSIC"), Ok(false));
+        assert_eq!(lib.tag_book(2, "romance"), Ok(true));
+        assert_eq!(lib.tag_book(99, "lost"), Err(LibraryError::BookNotFound(99)));
+
+        let classics: Vec<u64> = lib.books_with_tag("classic").iter().map(|b| b.id()).collect();
+        assert_eq!(classics, [2, 1]);
+
+        let cloud = lib.tag_cloud();
+        assert_eq!(cloud["classic"], 2);
+        assert_eq!(cloud["romance"], 1);
+        assert_eq!(cloud.len(), 2);
+    }
+}
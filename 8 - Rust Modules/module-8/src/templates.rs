@@ -0,0 +1,276 @@
+//! Templates module - member notices ready for mail merge.
+//!
+//! [`Library::render_notices`] fills a [`Template`] once per member who
+//! needs to hear about something, and returns the messages as plain
+//! [`Notice`] data. Sending them (email, SMS, print) is up to the host
+//! application; nothing here talks to the outside world.
+//!
+//! Templates are plain text with `{placeholder}`s. Every notice can use
+//! `{name}`, `{member_id}`, `{library}`, `{date}`, `{count}` and `{items}`
+//! (one line per book or ledger entry); each [`NoticeKind`] adds its own,
+//! listed on the variant. A placeholder with no value is left as written,
+//! so a typo shows up in the output instead of vanishing.
+//!
+//! ```
+//! use chrono::NaiveDate;
+//! use module_8::templates::NoticeKind;
+//! use module_8::{Book, Genre, Library, Member, MembershipTier};
+//!
+//! let mut lib = Library::new();
+//! lib.add_book(Book::new(1, "Dune", Genre::SciFi)).unwrap();
+//! lib.register_member(Member::new(10, "Alice", MembershipTier::Basic)).unwrap();
+//! let day = |d| NaiveDate::from_ymd_opt(2024, 3, d).unwrap();
+//! lib.checkout(10, 1, day(1)).unwrap();
+//!
+//! let notices = lib.render_notices(NoticeKind::Overdue, day(18));
+//! assert_eq!(notices.len(), 1);
+//! assert_eq!(notices[0].recipient, "Alice");
+//! assert!(notices[0].body.contains("- Dune (due 2024-03-15, 3 days overdue)"));
+//! assert!(notices[0].body.contains("$0.75"));
+//! ```
+
+use std::collections::BTreeMap;
+
+use chrono::NaiveDate;
+
+use crate::member::Member;
+use crate::money::Money;
+use crate::Library;
+
+// =============================================================================
+// TEMPLATES
+// =============================================================================
+
+/// The notices the library knows how to send.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum NoticeKind {
+    /// To members with loans past due. Adds `{fees}`: the late fees the
+    /// loans would cost if returned today.
+    Overdue,
+    /// To members with held books waiting on the hold shelf.
+    HoldReady,
+    /// To members who owe fines. Adds `{balance}`; `{items}` lists the
+    /// member's fine ledger.
+    FineStatement,
+}
+
+/// A subject line and body with `{placeholder}`s.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Template {
+    pub subject: String,
+    pub body: String,
+}
+
+impl Template {
+    pub fn new(subject: &str, body: &str) -> Self {
+        Template {
+            subject: String::from(subject),
+            body: String::from(body),
+        }
+    }
+
+    /// The wording used by [`Library::render_notices`].
+    pub fn default_for(kind: NoticeKind) -> Self {
+        match kind {
+            NoticeKind::Overdue => Template::new(
+                "{library}: {count} overdue item(s)",
+                "Dear {name},\n\nAs of {date}, these items are overdue:\n{items}\n\n\
+                 Late fees so far: {fees}. Please return them as soon as you can.\n",
+            ),
+            NoticeKind::HoldReady => Template::new(
+                "{library}: your hold is ready",
+                "Dear {name},\n\nThese items are waiting for you at the front desk:\n{items}\n",
+            ),
+            NoticeKind::FineStatement => Template::new(
+                "{library}: fine statement",
+                "Dear {name},\n\nYour balance on {date} is {balance}.\n\nRecent activity:\n{items}\n",
+            ),
+        }
+    }
+
+    /// Replaces each `{key}` with its value from `values`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::collections::BTreeMap;
+    /// use module_8::templates::Template;
+    ///
+    /// let values = BTreeMap::from([("name", String::from("Alice"))]);
+    /// assert_eq!(Template::fill("Hi {name}, {unknown}", &values), "Hi Alice, {unknown}");
+    /// ```
+    pub fn fill(text: &str, values: &BTreeMap<&str, String>) -> String {
+        let mut out = String::with_capacity(text.len());
+        let mut rest = text;
+        while let Some(open) = rest.find('{') {
+            out.push_str(&rest[..open]);
+            let after = &rest[open + 1..];
+            match after.find('}').and_then(|close| Some((values.get(&after[..close])?, close))) {
+                Some((value, close)) => {
+                    out.push_str(value);
+                    rest = &after[close + 1..];
+                }
+                None => {
+                    out.push('{');
+                    rest = after;
+                }
+            }
+        }
+        out.push_str(rest);
+        out
+    }
+}
+
+// =============================================================================
+// NOTICES
+// =============================================================================
+
+/// One filled-in message for one member.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Notice {
+    pub member_id: u64,
+    /// The member's name, as registered.
+    pub recipient: String,
+    pub subject: String,
+    pub body: String,
+}
+
+impl Library {
+    /// One notice of `kind` per member it applies to as of `today`, in
+    /// registration order, using the [default wording](Template::default_for).
+    pub fn render_notices(&self, kind: NoticeKind, today: NaiveDate) -> Vec<Notice> {
+        self.render_notices_with(kind, &Template::default_for(kind), today)
+    }
+
+    /// Like [`render_notices`](Library::render_notices), with the library's
+    /// own wording.
+    pub fn render_notices_with(&self, kind: NoticeKind, template: &Template, today: NaiveDate) -> Vec<Notice> {
+        let mut notices = Vec::new();
+        for member in self.members() {
+            let mut values = BTreeMap::new();
+            let items = match kind {
+                NoticeKind::Overdue => self.overdue_items(member, today, &mut values),
+                NoticeKind::HoldReady => self.hold_items(member),
+                NoticeKind::FineStatement => self.fine_items(member, &mut values),
+            };
+            if items.is_empty() {
+                continue;
+            }
+            values.insert("name", member.name.clone());
+            values.insert("member_id", member.id().to_string());
+            values.insert("library", self.name().to_string());
+            values.insert("date", today.to_string());
+            values.insert("count", items.len().to_string());
+            values.insert("items", items.join("\n"));
+            notices.push(Notice {
+                member_id: member.id(),
+                recipient: member.name.clone(),
+                subject: Template::fill(&template.subject, &values),
+                body: Template::fill(&template.body, &values),
+            });
+        }
+        notices
+    }
+
+    fn title_of(&self, book_id: u64) -> String {
+        self.get_book(book_id)
+            .map_or_else(|| format!("Book #{}", book_id), |b| b.title.clone())
+    }
+
+    fn overdue_items(&self, member: &Member, today: NaiveDate, values: &mut BTreeMap<&str, String>) -> Vec<String> {
+        let mut fees = Money::ZERO;
+        let items = self
+            .loans
+            .iter()
+            .filter(|loan| loan.member_id == member.id() && loan.days_overdue(today) > 0)
+            .map(|loan| {
+                let days = loan.days_overdue(today);
+                fees += self.member_late_fee(member, loan, today);
+                format!("- {} (due {}, {} days overdue)", self.title_of(loan.book_id), loan.due_on, days)
+            })
+            .collect();
+        values.insert("fees", fees.to_string());
+        items
+    }
+
+    fn hold_items(&self, member: &Member) -> Vec<String> {
+        self.holds
+            .iter()
+            .filter(|hold| hold.member_id == member.id())
+            .filter_map(|hold| {
+                let ready = hold.ready_since?;
+                Some(format!("- {} (ready since {})", self.title_of(hold.book_id), ready))
+            })
+            .collect()
+    }
+
+    fn fine_items(&self, member: &Member, values: &mut BTreeMap<&str, String>) -> Vec<String> {
+        if member.outstanding_fines().is_zero() {
+            return Vec::new();
+        }
+        values.insert("balance", member.outstanding_fines().to_string());
+        self.fine_ledger
+            .iter()
+            .filter(|entry| entry.member_id == member.id())
+            .map(|entry| format!("- {} {:?} {}", entry.on, entry.kind, entry.amount))
+            .collect()
+    }
+}
+
+// =============================================================================
+// TESTS
+// =============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Book, Genre, MembershipTier};
+
+    fn date(day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(2024, 3, day).unwrap()
+    }
+
+    fn library() -> Library {
+        let mut lib = Library::new();
+        lib.add_book(Book::new(1, "Dune", Genre::SciFi)).unwrap();
+        lib.add_book(Book::new(2, "Emma", Genre::Fiction)).unwrap();
+        lib.register_member(Member::new(10, "Alice", MembershipTier::Basic)).unwrap();
+        lib.register_member(Member::new(11, "Bob", MembershipTier::Basic)).unwrap();
+        lib.checkout(10, 1, date(1)).unwrap();
+        lib.checkout(10, 2, date(1)).unwrap();
+        lib.place_hold(11, 1, date(2)).unwrap();
+        lib
+    }
+
+    #[test]
+    fn test_one_notice_per_affected_member() {
+        let mut lib = library();
+        assert!(lib.render_notices(NoticeKind::Overdue, date(15)).is_empty());
+
+        let overdue = lib.render_notices(NoticeKind::Overdue, date(17));
+        assert_eq!(overdue.len(), 1);
+        assert_eq!(overdue[0].subject, format!("{}: 2 overdue item(s)", lib.name()));
+        assert!(overdue[0].body.contains("- Dune (due 2024-03-15, 2 days overdue)\n- Emma"));
+        assert!(overdue[0].body.contains("Late fees so far: $1.00."));
+
+        lib.return_book(10, 1, date(17)).unwrap();
+        let ready = lib.render_notices(NoticeKind::HoldReady, date(17));
+        let recipients: Vec<&str> = ready.iter().map(|n| n.recipient.as_str()).collect();
+        assert_eq!(recipients, ["Bob"]);
+        assert!(ready[0].body.contains("- Dune (ready since 2024-03-17)"));
+
+        let statements = lib.render_notices(NoticeKind::FineStatement, date(17));
+        assert_eq!(statements.len(), 1);
+        assert!(statements[0].body.contains("Your balance on 2024-03-17 is $0.50."));
+        assert!(statements[0].body.contains("- 2024-03-17 Assessed $0.50"));
+    }
+
+    #[test]
+    fn test_custom_template() {
+        let lib = library();
+        let template = Template::new("{count} late", "{name} (#{member_id}) owes {fees}{}{nope");
+        let notices = lib.render_notices_with(NoticeKind::Overdue, &template, date(16));
+        assert_eq!(notices[0].subject, "2 late");
+        assert_eq!(notices[0].body, "Alice (#10) owes $0.50{}{nope");
+    }
+}
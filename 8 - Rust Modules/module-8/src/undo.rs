@@ -0,0 +1,431 @@
+//! Undo module - reversible staff operations.
+//!
+//! Each [`LibraryCommand`] is one operation staff can take back: adding or
+//! removing a book, a checkout or a return. [`LibraryCommand::apply`]
+//! carries it out and returns an [`Undo`] holding what
+//! [`LibraryCommand::revert`] needs to put the library back as it was.
+//!
+//! [`Library::execute`] applies a command and remembers it, so
+//! [`Library::undo`] and [`Library::redo`] can step backwards and forwards
+//! through the last [`UNDO_LIMIT`] commands. Executing a new command
+//! forgets anything that was undone.
+//!
+//! ```
+//! use chrono::NaiveDate;
+//! use module_8::undo::LibraryCommand;
+//! use module_8::{Book, Genre, Library, Member, MembershipTier};
+//!
+//! let today = NaiveDate::from_ymd_opt(2024, 3, 1).unwrap();
+//! let mut lib = Library::new();
+//! lib.register_member(Member::new(10, "Alice", MembershipTier::Basic)).unwrap();
+//! lib.execute(LibraryCommand::AddBook(Box::new(Book::new(1, "Dune", Genre::SciFi)))).unwrap();
+//! lib.execute(LibraryCommand::Checkout { member_id: 10, book_id: 1, today }).unwrap();
+//!
+//! // Wrong member: take it back
+//! lib.undo().unwrap();
+//! assert!(lib.loans().is_empty());
+//! assert_eq!(lib.find_book(1).unwrap().times_borrowed(), 0);
+//! lib.redo().unwrap();
+//! assert_eq!(lib.loans().len(), 1);
+//! ```
+//!
+//! Undo assumes the library hasn't changed since the command ran, other
+//! than through later commands that were undone first. An operation made
+//! outside `execute` in between can make `undo` fail, e.g. when the book
+//! to take off the shelf has been lent since. Events a command fired, such
+//! as a [`HoldAvailable`](crate::LibraryEvent::HoldAvailable) notice, can't
+//! be taken back.
+
+use std::collections::VecDeque;
+
+use chrono::NaiveDate;
+
+use crate::book::Book;
+use crate::circulation::{Hold, Loan};
+use crate::error::LibraryError;
+use crate::index::BookLocation;
+use crate::location::AwaitingShelving;
+use crate::member::Member;
+use crate::Library;
+
+/// How many commands [`Library::undo`] can go back.
+pub const UNDO_LIMIT: usize = 50;
+
+// =============================================================================
+// COMMANDS
+// =============================================================================
+
+/// A reversible library operation.
+#[derive(Debug, Clone)]
+pub enum LibraryCommand {
+    /// [`Library::add_book`]; undone by removing the book.
+    AddBook(Box<Book>),
+    /// [`Library::remove_book`]; undone by adding the book back at the end
+    /// of catalog order, with the holds that were on it.
+    RemoveBook(u64),
+    /// [`Library::checkout`]; undone as if the loan never happened.
+    Checkout { member_id: u64, book_id: u64, today: NaiveDate },
+    /// [`Library::return_book`]; undone by reopening the loan and taking
+    /// back the fee, history entry and any hold it made ready.
+    Return { member_id: u64, book_id: u64, today: NaiveDate },
+}
+
+/// What [`LibraryCommand::revert`] needs to undo one application of a
+/// command.
+#[derive(Debug, Clone)]
+pub struct Undo(Saved);
+
+/// Holds are saved with their positions in `Library::holds`, so they go
+/// back in the order they were served.
+#[derive(Debug, Clone)]
+enum Saved {
+    Added,
+    Removed { book: Book, holds: Vec<(usize, Hold)> },
+    /// The book as it was on the shelf, the ready hold the checkout
+    /// collected and its place on the re-shelving queue.
+    CheckedOut {
+        book: Book,
+        hold: Option<(usize, Hold)>,
+        awaiting: Option<(usize, AwaitingShelving)>,
+    },
+    Returned {
+        member: Box<Member>,
+        loan: (usize, Loan),
+        holds: Vec<(usize, Hold)>,
+        fines_recorded: usize,
+    },
+}
+
+impl LibraryCommand {
+    /// Carries the command out.
+    ///
+    /// # Errors
+    ///
+    /// Whatever the underlying operation returns; the library is unchanged
+    /// when it fails.
+    pub fn apply(&self, library: &mut Library) -> Result<Undo, LibraryError> {
+        let saved = match *self {
+            LibraryCommand::AddBook(ref book) => {
+                library.add_book(Book::clone(book))?;
+                Saved::Added
+            }
+            LibraryCommand::RemoveBook(book_id) => {
+                let holds = holds_on(library, book_id);
+                let book = library.remove_book(book_id)?;
+                Saved::Removed { book, holds }
+            }
+            LibraryCommand::Checkout { member_id, book_id, today } => {
+                let book = library.get_book(book_id).cloned().ok_or(LibraryError::BookNotFound(book_id))?;
+                let hold = holds_on(library, book_id)
+                    .into_iter()
+                    .find(|(_, h)| h.member_id == member_id && h.ready_since.is_some());
+                let awaiting = library
+                    .reshelving
+                    .iter()
+                    .cloned()
+                    .enumerate()
+                    .find(|(_, entry)| entry.book_id == book_id);
+                library.checkout(member_id, book_id, today)?;
+                Saved::CheckedOut { book, hold, awaiting }
+            }
+            LibraryCommand::Return { member_id, book_id, today } => {
+                let not_on_loan = LibraryError::NotOnLoan { member_id, book_id };
+                let member = library.find_member(member_id).cloned().ok_or(not_on_loan.clone())?;
+                let loan = library
+                    .loans
+                    .iter()
+                    .cloned()
+                    .enumerate()
+                    .find(|(_, l)| l.member_id == member_id && l.book_id == book_id)
+                    .ok_or(not_on_loan)?;
+                let holds = holds_on(library, book_id);
+                let fines_recorded = library.fine_ledger.len();
+                library.return_book(member_id, book_id, today)?;
+                Saved::Returned {
+                    member: Box::new(member),
+                    loan,
+                    holds,
+                    fines_recorded,
+                }
+            }
+        };
+        Ok(Undo(saved))
+    }
+
+    /// Puts the library back as it was before [`apply`](LibraryCommand::apply)
+    /// returned `undo`.
+    ///
+    /// # Errors
+    ///
+    /// [`LibraryError::BookUnavailable`], [`LibraryError::NotOnLoan`] and
+    /// similar when the library has changed since in a way that stops the
+    /// command being undone, and [`LibraryError::UndoMismatch`] if `undo`
+    /// was returned by a different kind of command. The library is then
+    /// left unchanged.
+    pub fn revert(&self, undo: Undo, library: &mut Library) -> Result<(), LibraryError> {
+        match (self, undo.0) {
+            (LibraryCommand::AddBook(book), Saved::Added) => {
+                library.remove_book(book.id())?;
+            }
+            (LibraryCommand::RemoveBook(_), Saved::Removed { book, holds }) => {
+                library.add_book_allowing_duplicates(book)?;
+                restore_holds(library, holds);
+            }
+            (&LibraryCommand::Checkout { member_id, book_id, .. }, Saved::CheckedOut { book, hold, awaiting }) => {
+                let not_on_loan = LibraryError::NotOnLoan { member_id, book_id };
+                let position = library
+                    .loans
+                    .iter()
+                    .position(|l| l.member_id == member_id && l.book_id == book_id)
+                    .ok_or(not_on_loan)?;
+                library.close_loan(position);
+                library.books.insert(book_id, book);
+                restore_holds(library, hold.into_iter().collect());
+                if let Some((position, entry)) = awaiting {
+                    library.reshelving.insert(position.min(library.reshelving.len()), entry);
+                }
+            }
+            (
+                &LibraryCommand::Return { member_id, book_id, .. },
+                Saved::Returned { member, loan: (position, loan), holds, fines_recorded },
+            ) => {
+                match library.index.location(book_id) {
+                    Some(BookLocation::Shelf) => {}
+                    Some(BookLocation::OnLoan) => return Err(LibraryError::BookUnavailable(book_id)),
+                    None => return Err(LibraryError::BookNotFound(book_id)),
+                }
+                *library.member_mut(member_id).ok_or(LibraryError::MemberNotFound(member_id))? = *member;
+                library.open_loan(position, loan);
+                library.holds.retain(|h| h.book_id != book_id);
+                restore_holds(library, holds);
+                library.fine_ledger.truncate(fines_recorded);
+                library.mark_shelved(book_id);
+            }
+            _ => return Err(LibraryError::UndoMismatch),
+        }
+        Ok(())
+    }
+}
+
+/// The holds on a book, with their positions.
+fn holds_on(library: &Library, book_id: u64) -> Vec<(usize, Hold)> {
+    library
+        .holds
+        .iter()
+        .enumerate()
+        .filter(|(_, h)| h.book_id == book_id)
+        .map(|(position, h)| (position, h.clone()))
+        .collect()
+}
+
+/// Puts holds saved by [`holds_on`] back where they were.
+fn restore_holds(library: &mut Library, holds: Vec<(usize, Hold)>) {
+    for (position, hold) in holds {
+        let position = position.min(library.holds.len());
+        library.holds.insert(position, hold);
+    }
+}
+
+// =============================================================================
+// LIBRARY INTEGRATION
+// =============================================================================
+
+/// Commands that can be undone, newest last, and those undone since the
+/// last new command, most recently undone last.
+#[derive(Debug, Default)]
+pub(crate) struct CommandHistory {
+    done: VecDeque<(LibraryCommand, Undo)>,
+    undone: Vec<LibraryCommand>,
+}
+
+impl Library {
+    /// Applies a command and records it for [`undo`](Library::undo). Only
+    /// the last [`UNDO_LIMIT`] commands are kept, and anything undone can
+    /// no longer be redone.
+    ///
+    /// # Errors
+    ///
+    /// Whatever the command returns; nothing is recorded when it fails.
+    pub fn execute(&mut self, command: LibraryCommand) -> Result<(), LibraryError> {
+        let undo = command.apply(self)?;
+        if self.commands.done.len() == UNDO_LIMIT {
+            self.commands.done.pop_front();
+        }
+        self.commands.done.push_back((command, undo));
+        self.commands.undone.clear();
+        Ok(())
+    }
+
+    /// Reverts the most recent command, returning it, or `None` if there's
+    /// nothing left to undo.
+    ///
+    /// # Errors
+    ///
+    /// Whatever [`LibraryCommand::revert`] returns; the command then stays
+    /// the next one to undo.
+    pub fn undo(&mut self) -> Result<Option<LibraryCommand>, LibraryError> {
+        let Some((command, undo)) = self.commands.done.pop_back() else { return Ok(None) };
+        if let Err(error) = command.revert(undo.clone(), self) {
+            self.commands.done.push_back((command, undo));
+            return Err(error);
+        }
+        self.commands.undone.push(command.clone());
+        Ok(Some(command))
+    }
+
+    /// Applies the most recently undone command again, returning it, or
+    /// `None` if there's nothing to redo.
+    ///
+    /// # Errors
+    ///
+    /// Whatever [`LibraryCommand::apply`] returns; the command then stays
+    /// the next one to redo.
+    pub fn redo(&mut self) -> Result<Option<LibraryCommand>, LibraryError> {
+        let Some(command) = self.commands.undone.pop() else { return Ok(None) };
+        match command.apply(self) {
+            Ok(undo) => {
+                self.commands.done.push_back((command.clone(), undo));
+                Ok(Some(command))
+            }
+            Err(error) => {
+                self.commands.undone.push(command);
+                Err(error)
+            }
+        }
+    }
+
+    /// Returns `true` if [`undo`](Library::undo) has a command to revert.
+    pub fn can_undo(&self) -> bool {
+        !self.commands.done.is_empty()
+    }
+
+    /// Returns `true` if [`redo`](Library::redo) has a command to apply.
+    pub fn can_redo(&self) -> bool {
+        !self.commands.undone.is_empty()
+    }
+}
+
+// =============================================================================
+// TESTS
+// =============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::money::Money;
+    use crate::{Genre, MembershipTier};
+
+    fn date(day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(2024, 3, day).unwrap()
+    }
+
+    fn library() -> Library {
+        let mut lib = Library::new();
+        lib.add_book(Book::new(1, "Dune", Genre::SciFi)).unwrap();
+        lib.add_book(Book::new(2, "Emma", Genre::Fiction)).unwrap();
+        lib.register_member(Member::new(10, "Alice", MembershipTier::Basic)).unwrap();
+        lib.register_member(Member::new(11, "Bob", MembershipTier::Basic)).unwrap();
+        lib
+    }
+
+    #[test]
+    fn test_undo_and_redo_checkout_and_return() {
+        let mut lib = library();
+        lib.execute(LibraryCommand::Checkout { member_id: 10, book_id: 1, today: date(1) }).unwrap();
+        lib.place_hold(11, 1, date(2)).unwrap();
+        lib.execute(LibraryCommand::Return { member_id: 10, book_id: 1, today: date(20) }).unwrap();
+        assert!(lib.holds()[0].ready_since.is_some());
+        assert!(lib.find_member(10).unwrap().outstanding_fines() > Money::ZERO);
+
+        // Undoing the late return reopens the loan and takes the fee back
+        assert!(matches!(lib.undo(), Ok(Some(LibraryCommand::Return { .. }))));
+        assert_eq!(lib.loans().len(), 1);
+        assert!(lib.holds()[0].ready_since.is_none());
+        assert_eq!(lib.borrower(1), Some(10));
+        let alice = lib.find_member(10).unwrap();
+        assert_eq!(alice.outstanding_fines(), Money::ZERO);
+        assert!(alice.history().is_empty());
+        assert!(lib.fine_ledger().is_empty());
+        assert_eq!(lib.check_invariants(), Ok(()));
+
+        assert!(lib.undo().unwrap().is_some());
+        assert!(lib.loans().is_empty());
+        assert_eq!(lib.find_book(1).unwrap().times_borrowed(), 0);
+        assert!(lib.borrowed_books(10).is_empty());
+        assert_eq!(lib.check_invariants(), Ok(()));
+        assert!(!lib.can_undo());
+        assert!(matches!(lib.undo(), Ok(None)));
+
+        lib.redo().unwrap();
+        lib.redo().unwrap();
+        assert!(!lib.can_redo());
+        assert!(lib.loans().is_empty());
+        assert_eq!(lib.find_member(10).unwrap().history().len(), 1);
+        assert_eq!(lib.check_invariants(), Ok(()));
+    }
+
+    #[test]
+    fn test_undo_add_and_remove_book() {
+        let mut lib = library();
+        lib.place_hold(10, 2, date(1)).unwrap();
+        lib.execute(LibraryCommand::AddBook(Box::new(Book::new(3, "Ulysses", Genre::Fiction)))).unwrap();
+        lib.execute(LibraryCommand::RemoveBook(2)).unwrap();
+        assert!(lib.holds().is_empty());
+        assert_eq!(
+            lib.execute(LibraryCommand::RemoveBook(2)).unwrap_err(),
+            LibraryError::BookNotFound(2)
+        );
+
+        lib.undo().unwrap();
+        assert_eq!(lib.find_book(2).map(|b| b.title.as_str()), Some("Emma"));
+        assert_eq!(lib.holds().len(), 1);
+        lib.undo().unwrap();
+        assert!(lib.find_book(3).is_none());
+        assert_eq!(lib.check_invariants(), Ok(()));
+
+        // A new command forgets what was undone
+        lib.execute(LibraryCommand::Checkout { member_id: 11, book_id: 1, today: date(1) }).unwrap();
+        assert!(!lib.can_redo());
+        assert!(matches!(lib.redo(), Ok(None)));
+    }
+
+    #[test]
+    fn test_failed_undo_keeps_the_command() {
+        let mut lib = library();
+        lib.execute(LibraryCommand::Checkout { member_id: 10, book_id: 1, today: date(1) }).unwrap();
+        // Returned outside the history, so the checkout can't be undone
+        lib.return_book(10, 1, date(5)).unwrap();
+        let not_on_loan = LibraryError::NotOnLoan { member_id: 10, book_id: 1 };
+        assert_eq!(lib.undo().unwrap_err(), not_on_loan);
+        assert!(lib.can_undo());
+        assert_eq!(lib.check_invariants(), Ok(()));
+    }
+
+    #[test]
+    fn test_history_is_bounded() {
+        let mut lib = Library::new();
+        for id in 1..=UNDO_LIMIT as u64 + 5 {
+            lib.execute(LibraryCommand::AddBook(Box::new(Book::new(id, "Volume", Genre::NonFiction)))).unwrap();
+        }
+        while lib.undo().unwrap().is_some() {}
+        assert_eq!(lib.book_count(), 5);
+    }
+
+    #[test]
+    fn test_mismatched_undo_is_an_error() {
+        let mut lib = library();
+        let add = LibraryCommand::AddBook(Box::new(Book::new(3, "Ulysses", Genre::Fiction)));
+        let undo = add.apply(&mut lib).unwrap();
+        let checkout = LibraryCommand::Checkout { member_id: 10, book_id: 3, today: date(1) };
+        assert_eq!(checkout.revert(undo.clone(), &mut lib), Err(LibraryError::UndoMismatch));
+        assert!(lib.find_book(3).is_some());
+        assert_eq!(add.revert(undo, &mut lib), Ok(()));
+
+        // Nothing to save means nothing to apply
+        let ret = LibraryCommand::Return { member_id: 10, book_id: 1, today: date(2) };
+        assert_eq!(ret.apply(&mut lib).unwrap_err(), LibraryError::NotOnLoan { member_id: 10, book_id: 1 });
+        let missing = LibraryCommand::Checkout { member_id: 10, book_id: 9, today: date(2) };
+        assert_eq!(missing.apply(&mut lib).unwrap_err(), LibraryError::BookNotFound(9));
+        assert_eq!(lib.check_invariants(), Ok(()));
+    }
+}
@@ -12,7 +12,11 @@
 
 // ABSOLUTE PATH: Start from the crate root using `crate::`
 // This is unambiguous and works from anywhere in the crate.
+use std::collections::BTreeMap;
+
 use crate::book::{Book, Genre};
+use crate::locale::{Locale, Message};
+use crate::templates::Template;
 
 // We can also use multiple items from the same module with nested paths:
 // use crate::member::{Member, MembershipTier};
@@ -35,59 +39,42 @@ use crate::book::{Book, Genre};
 /// assert!(info.contains("Rust Basics"));
 /// ```
 pub fn format_book_info(book: &Book) -> String {
+    format_book_info_in(book, &Locale::english())
+}
+
+/// Like [`format_book_info`], in `locale`'s language (see
+/// [`locale`](crate::locale)).
+pub fn format_book_info_in(book: &Book, locale: &Locale) -> String {
     let availability = if book.is_available() {
-        "Available"
+        Message::Available
     } else {
-        "Borrowed"
+        Message::Borrowed
     };
-
-    format!(
-        "[#{}] \"{}\" ({:?}) - {} | Borrowed {} times",
-        book.id(),
-        book.title,
-        book.genre,
-        availability,
-        book.times_borrowed()
-    )
+    let values = BTreeMap::from([
+        ("id", book.id().to_string()),
+        ("title", book.title.clone()),
+        ("genre", locale.genre_name(&book.genre).to_string()),
+        ("availability", locale.text(availability).to_string()),
+        ("count", book.times_borrowed().to_string()),
+    ]);
+    Template::fill(locale.text(Message::BookInfo), &values)
 }
 
 /// Formats a genre for display.
-pub fn format_genre(genre: &Genre) -> &'static str {
+///
+/// Custom genres are shown by their own name, which is why the returned
+/// string borrows from `genre` rather than being `&'static str`.
+pub fn format_genre(genre: &Genre) -> &str {
     match genre {
         Genre::Fiction => "Fiction",
         Genre::NonFiction => "Non-Fiction",
         Genre::Technical => "Technical",
         Genre::Mystery => "Mystery",
         Genre::SciFi => "Science Fiction",
+        Genre::Custom(name) => name,
     }
 }
 
-// =============================================================================
-// CRATE-INTERNAL UTILITIES
-// =============================================================================
-
-/// Validates a book title.
-///
-/// `pub(crate)` means this is accessible anywhere in the crate,
-/// but NOT by external users of the library.
-#[allow(dead_code)]
-pub(crate) fn validate_title(title: &str) -> bool {
-    !title.is_empty() && title.len() <= 200
-}
-
-/// Generates a unique identifier.
-///
-/// Completely private - only accessible within this `utils` module.
-#[allow(dead_code)]
-fn generate_id() -> u64 {
-    // In a real app, this would use a proper ID generation strategy
-    use std::time::{SystemTime, UNIX_EPOCH};
-    SystemTime::now()
-        .duration_since(UNIX_EPOCH)
-        .unwrap()
-        .as_nanos() as u64
-}
-
 // =============================================================================
 // NESTED MODULE DEMONSTRATING `self::` AND `super::`
 // =============================================================================
@@ -96,11 +83,14 @@ fn generate_id() -> u64 {
 ///
 /// This demonstrates inline nested modules and path resolution.
 pub mod formatting {
+    use std::collections::HashMap;
+
     // `super::` refers to the parent module (utils)
     // This imports the Genre type that `utils` imported from `crate::book`
     use super::Genre;
 
-    /// Formats genre as an emoji.
+    /// Formats genre as an emoji: the built-in icon, which a
+    /// [`GenreIcons`] registry falls back to.
     pub fn genre_emoji(genre: &Genre) -> &'static str {
         match genre {
             Genre::Fiction => "📖",
@@ -108,6 +98,7 @@ pub mod formatting {
             Genre::Technical => "💻",
             Genre::Mystery => "🔍",
             Genre::SciFi => "🚀",
+            Genre::Custom(_) => "🏷️",
         }
     }
 
@@ -119,6 +110,129 @@ pub mod formatting {
         format!("{} {}", self::genre_emoji(genre), super::format_genre(genre))
     }
 
+    /// Icons for genres, overriding or adding to the built-in ones from
+    /// [`genre_emoji`]. Custom genres can each have their own icon instead
+    /// of sharing the generic tag.
+    ///
+    /// Each [`Library`](crate::Library) has one; see
+    /// [`genre_icons_mut`](crate::Library::genre_icons_mut).
+    ///
+    /// ```
+    /// use module_8::utils::formatting::GenreIcons;
+    /// use module_8::Genre;
+    ///
+    /// let poetry = Genre::Custom(String::from("Poetry"));
+    /// let icons = GenreIcons::new().with(poetry.clone(), "🪶").with(Genre::SciFi, "👽");
+    /// assert_eq!(icons.icon(&poetry), "🪶");
+    /// assert_eq!(icons.icon(&Genre::SciFi), "👽");
+    /// assert_eq!(icons.icon(&Genre::Mystery), "🔍");
+    /// assert_eq!(icons.icon(&Genre::Custom(String::from("Drama"))), "🏷️");
+    /// ```
+    #[derive(Debug, Clone, Default, PartialEq)]
+    pub struct GenreIcons {
+        overrides: HashMap<Genre, String>,
+    }
+
+    impl GenreIcons {
+        /// A registry with only the built-in icons.
+        pub fn new() -> Self {
+            GenreIcons::default()
+        }
+
+        /// Uses `icon` for `genre`.
+        pub fn with(mut self, genre: Genre, icon: &str) -> Self {
+            self.set(genre, icon);
+            self
+        }
+
+        /// Uses `icon` for `genre`, returning the override it replaces.
+        pub fn set(&mut self, genre: Genre, icon: &str) -> Option<String> {
+            self.overrides.insert(genre, String::from(icon))
+        }
+
+        /// Goes back to the built-in icon for `genre`.
+        pub fn reset(&mut self, genre: &Genre) -> Option<String> {
+            self.overrides.remove(genre)
+        }
+
+        /// The icon for `genre`: its override, or the built-in one.
+        pub fn icon(&self, genre: &Genre) -> &str {
+            self.overrides.get(genre).map_or(genre_emoji(genre), String::as_str)
+        }
+
+        /// Like [`genre_with_emoji`], with this registry's icon.
+        pub fn label(&self, genre: &Genre) -> String {
+            format!("{} {}", self.icon(genre), super::format_genre(genre))
+        }
+    }
+
+    /// `text` centered in a line `width` characters wide (or as is, if
+    /// longer), without trailing spaces.
+    pub fn centered(text: &str, width: usize) -> String {
+        let padding = width.saturating_sub(text.chars().count()) / 2;
+        format!("{}{}", " ".repeat(padding), text)
+    }
+
+    /// `left` and `right` at either end of a line `width` characters wide,
+    /// with at least one space between them.
+    ///
+    /// ```
+    /// use module_8::utils::formatting::columns;
+    /// assert_eq!(columns("Dune", "$0.50", 12), "Dune   $0.50");
+    /// assert_eq!(columns("Anathem", "$0.50", 8), "Anathem $0.50");
+    /// ```
+    pub fn columns(left: &str, right: &str, width: usize) -> String {
+        let used = left.chars().count() + right.chars().count();
+        format!("{}{}{}", left, " ".repeat(width.saturating_sub(used).max(1)), right)
+    }
+
+    /// A Markdown table, escaping `|` in the cells.
+    pub fn markdown_table(headers: &[&str], rows: &[Vec<String>]) -> String {
+        let row = |cells: Vec<String>| format!("| {} |\n", cells.join(" | "));
+        let mut table = row(headers.iter().map(|h| h.to_string()).collect());
+        table.push_str(&row(headers.iter().map(|_| String::from("---")).collect()));
+        for cells in rows {
+            table.push_str(&row(cells.iter().map(|c| c.replace('|', "\\|")).collect()));
+        }
+        table
+    }
+
+    /// `text` with `&`, `<`, `>` and `"` escaped for HTML.
+    ///
+    /// ```
+    /// use module_8::utils::formatting::html_escape;
+    /// assert_eq!(html_escape("Tom & <Jerry>"), "Tom &amp; &lt;Jerry&gt;");
+    /// ```
+    pub fn html_escape(text: &str) -> String {
+        let mut out = String::with_capacity(text.len());
+        for c in text.chars() {
+            match c {
+                '&' => out.push_str("&amp;"),
+                '<' => out.push_str("&lt;"),
+                '>' => out.push_str("&gt;"),
+                '"' => out.push_str("&quot;"),
+                _ => out.push(c),
+            }
+        }
+        out
+    }
+
+    /// An HTML table with a header row, escaping every cell.
+    pub fn html_table(headers: &[&str], rows: &[Vec<String>]) -> String {
+        let row = |tag: &str, cells: Vec<String>| {
+            let cells: String = cells.iter().map(|c| format!("<{0}>{1}</{0}>", tag, html_escape(c))).collect();
+            format!("<tr>{}</tr>\n", cells)
+        };
+        let mut table = String::from("<table>\n<thead>\n");
+        table.push_str(&row("th", headers.iter().map(|h| h.to_string()).collect()));
+        table.push_str("</thead>\n<tbody>\n");
+        for cells in rows {
+            table.push_str(&row("td", cells.clone()));
+        }
+        table.push_str("</tbody>\n</table>\n");
+        table
+    }
+
     // Private nested module
     mod internal {
         /// This function can access its parent (`formatting`) via `super::`
@@ -157,17 +271,38 @@ mod tests {
         assert!(info.contains("Available"));
     }
 
-    #[test]
-    fn test_validate_title() {
-        assert!(validate_title("Valid Title"));
-        assert!(!validate_title(""));
-        assert!(!validate_title(&"x".repeat(201)));
-    }
-
     #[test]
     fn test_formatting_submodule() {
         let genre = Genre::Technical;
         assert_eq!(formatting::genre_emoji(&genre), "💻");
         assert!(formatting::genre_with_emoji(&genre).contains("Technical"));
     }
+
+    #[test]
+    fn test_genre_icon_overrides() {
+        let poetry = Genre::Custom(String::from("Poetry"));
+        let mut icons = formatting::GenreIcons::new();
+        assert_eq!(icons.icon(&poetry), "🏷️");
+        assert_eq!(icons.set(poetry.clone(), "🪶"), None);
+        assert_eq!(icons.set(Genre::Fiction, "📕"), None);
+        assert_eq!(icons.label(&poetry), "🪶 Poetry");
+        assert_eq!(icons.icon(&Genre::Custom(String::from("poetry"))), "🏷️");
+
+        assert_eq!(icons.reset(&Genre::Fiction).as_deref(), Some("📕"));
+        assert_eq!(icons.icon(&Genre::Fiction), formatting::genre_emoji(&Genre::Fiction));
+    }
+
+    #[test]
+    fn test_layout_helpers() {
+        assert_eq!(formatting::centered("Receipt", 11), "  Receipt");
+        assert_eq!(formatting::centered("Receipt", 3), "Receipt");
+        assert_eq!(formatting::columns("Émile", "1", 8), "Émile  1");
+        let table = formatting::markdown_table(&["Title", "Due"], &[vec![String::from("A|B"), String::from("today")]]);
+        assert_eq!(table, "| Title | Due |\n| --- | --- |\n| A\\|B | today |\n");
+        let table = formatting::html_table(&["Title"], &[vec![String::from("\"A\" & B")]]);
+        assert_eq!(
+            table,
+            "<table>\n<thead>\n<tr><th>Title</th></tr>\n</thead>\n<tbody>\n<tr><td>&quot;A&quot; &amp; B</td></tr>\n</tbody>\n</table>\n"
+        );
+    }
 }
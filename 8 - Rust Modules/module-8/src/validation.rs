@@ -0,0 +1,232 @@
+//! Validation module - checks for titles, names, IDs and contact details.
+//!
+//! [`Book::try_new`](crate::Book::try_new) and
+//! [`Member::try_new`](crate::Member::try_new) run these checks and return
+//! the [`ValidationError`]; `Book::new` and `Member::new` don't check, so
+//! existing callers keep working. Call them directly to check user input
+//! before building anything.
+//!
+//! ```
+//! use module_8::validation::{validate_member_name, validate_title, ValidationError};
+//!
+//! assert_eq!(validate_title("The Rust Programming Language"), Ok(()));
+//! assert_eq!(validate_title("   "), Err(ValidationError::Empty { field: "title" }));
+//! let err = validate_member_name("R2-D2").unwrap_err();
+//! assert_eq!(err.to_string(), "member name contains invalid character '2'");
+//! ```
+
+use std::fmt;
+
+/// Longest title accepted, in characters.
+pub const MAX_TITLE_LENGTH: usize = 200;
+
+/// Longest member name accepted, in characters.
+pub const MAX_NAME_LENGTH: usize = 100;
+
+/// Longest email address accepted, in characters.
+pub const MAX_EMAIL_LENGTH: usize = 254;
+
+/// Longest book review accepted, in characters.
+pub const MAX_REVIEW_LENGTH: usize = 500;
+
+/// Fewest and most digits in a phone number, international prefix included.
+pub const PHONE_DIGITS: std::ops::RangeInclusive<usize> = 7..=15;
+
+/// Longest phone number accepted, separators included.
+const MAX_PHONE_LENGTH: usize = 32;
+
+// =============================================================================
+// ERRORS
+// =============================================================================
+
+/// What exactly was wrong with a value. `field` names the value checked,
+/// e.g. `"title"`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub enum ValidationError {
+    /// Empty, or nothing but whitespace.
+    Empty { field: &'static str },
+    /// Longer than `max` characters.
+    TooLong { field: &'static str, max: usize, length: usize },
+    /// Contains a character the field doesn't allow.
+    InvalidCharacter { field: &'static str, character: char },
+    /// IDs start at 1.
+    ZeroId,
+    /// Has the right characters in the wrong shape; `expected` describes
+    /// the right one.
+    Malformed { field: &'static str, expected: &'static str },
+}
+
+impl fmt::Display for ValidationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ValidationError::Empty { field } => write!(f, "{} must not be empty", field),
+            ValidationError::TooLong { field, max, length } => {
+                write!(f, "{} is {} characters long (at most {})", field, length, max)
+            }
+            ValidationError::InvalidCharacter { field, character } => {
+                write!(f, "{} contains invalid character {:?}", field, character)
+            }
+            ValidationError::ZeroId => write!(f, "ID must not be 0"),
+            ValidationError::Malformed { field, expected } => write!(f, "{} must be {}", field, expected),
+        }
+    }
+}
+
+impl std::error::Error for ValidationError {}
+
+// =============================================================================
+// CHECKS
+// =============================================================================
+
+/// A title must have some text, at most [`MAX_TITLE_LENGTH`] characters,
+/// and no control characters such as newlines or tabs.
+pub fn validate_title(title: &str) -> Result<(), ValidationError> {
+    check_text("title", title, MAX_TITLE_LENGTH, |c| !c.is_control())
+}
+
+/// A member name must have some text, at most [`MAX_NAME_LENGTH`]
+/// characters, and only letters, spaces, apostrophes, hyphens and periods
+/// ("Dr. Mary-Jane O'Neil").
+pub fn validate_member_name(name: &str) -> Result<(), ValidationError> {
+    check_text("member name", name, MAX_NAME_LENGTH, |c| c.is_alphabetic() || matches!(c, ' ' | '\'' | '-' | '.'))
+}
+
+/// Book and member IDs start at 1, like the ones
+/// [`Library::generate_id`](crate::Library::generate_id) hands out.
+pub fn validate_id(id: u64) -> Result<(), ValidationError> {
+    if id == 0 {
+        return Err(ValidationError::ZeroId);
+    }
+    Ok(())
+}
+
+/// An email address must look like `name@example.com`: one `@`, a dotted
+/// domain and a top-level domain of two or more letters.
+///
+/// # Examples
+///
+/// ```
+/// use module_8::validation::validate_email;
+///
+/// assert_eq!(validate_email("alice.smith@library.org"), Ok(()));
+/// let err = validate_email("alice@localhost").unwrap_err();
+/// assert_eq!(err.to_string(), "email must be an address like name@example.com");
+/// ```
+pub fn validate_email(email: &str) -> Result<(), ValidationError> {
+    check_text("email", email, MAX_EMAIL_LENGTH, |c| c.is_ascii_alphanumeric() || "@._%+-".contains(c))?;
+    let malformed = ValidationError::Malformed { field: "email", expected: "an address like name@example.com" };
+    let Some((local, domain)) = email.split_once('@') else {
+        return Err(malformed);
+    };
+    let labels: Vec<&str> = domain.split('.').collect();
+    let domain_ok = labels.len() >= 2 && labels.iter().all(|l| !l.is_empty() && !l.contains('@'));
+    let tld_ok = labels.last().is_some_and(|tld| tld.len() >= 2 && tld.chars().all(|c| c.is_ascii_alphabetic()));
+    if local.is_empty() || !domain_ok || !tld_ok {
+        return Err(malformed);
+    }
+    Ok(())
+}
+
+/// A phone number may start with `+` and use spaces, hyphens, periods and
+/// parentheses between its digits, of which it needs 7 to 15
+/// ([`PHONE_DIGITS`]).
+///
+/// # Examples
+///
+/// ```
+/// use module_8::validation::validate_phone;
+///
+/// assert_eq!(validate_phone("+1 (555) 010-4477"), Ok(()));
+/// assert!(validate_phone("555-0199 ext. 2").is_err());
+/// ```
+pub fn validate_phone(phone: &str) -> Result<(), ValidationError> {
+    check_text("phone", phone, MAX_PHONE_LENGTH, |c| c.is_ascii_digit() || " -.()+".contains(c))?;
+    let digits = phone.chars().filter(char::is_ascii_digit).count();
+    let plus_ok = phone.rfind('+').is_none_or(|i| i == phone.len() - phone.trim_start().len());
+    if !PHONE_DIGITS.contains(&digits) || !plus_ok {
+        return Err(ValidationError::Malformed { field: "phone", expected: "a number with 7 to 15 digits" });
+    }
+    Ok(())
+}
+
+/// A review may be left empty (a rating on its own); otherwise it may have
+/// at most [`MAX_REVIEW_LENGTH`] characters and no control characters
+/// other than line breaks.
+pub fn validate_review(text: &str) -> Result<(), ValidationError> {
+    if text.trim().is_empty() {
+        return Ok(());
+    }
+    check_text("review", text, MAX_REVIEW_LENGTH, |c| c == '\n' || !c.is_control())
+}
+
+fn check_text(field: &'static str, text: &str, max: usize, allowed: impl Fn(char) -> bool) -> Result<(), ValidationError> {
+    if text.trim().is_empty() {
+        return Err(ValidationError::Empty { field });
+    }
+    let length = text.chars().count();
+    if length > max {
+        return Err(ValidationError::TooLong { field, max, length });
+    }
+    match text.chars().find(|&c| !allowed(c)) {
+        Some(character) => Err(ValidationError::InvalidCharacter { field, character }),
+        None => Ok(()),
+    }
+}
+
+// =============================================================================
+// TESTS
+// =============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_validate_title() {
+        assert_eq!(validate_title("Valid Title"), Ok(()));
+        assert_eq!(validate_title(""), Err(ValidationError::Empty { field: "title" }));
+        assert_eq!(
+            validate_title(&"x".repeat(201)),
+            Err(ValidationError::TooLong { field: "title", max: 200, length: 201 })
+        );
+        // Length is in characters, not bytes
+        assert_eq!(validate_title(&"é".repeat(200)), Ok(()));
+        assert_eq!(
+            validate_title("Line\nbreak"),
+            Err(ValidationError::InvalidCharacter { field: "title", character: '\n' })
+        );
+    }
+
+    #[test]
+    fn test_validate_name_and_id() {
+        assert_eq!(validate_member_name("Dr. Mary-Jane O'Neil"), Ok(()));
+        assert_eq!(validate_member_name("José Ñúñez"), Ok(()));
+        assert_eq!(validate_member_name(" ").unwrap_err().to_string(), "member name must not be empty");
+        assert_eq!(
+            validate_member_name(&"a".repeat(101)).unwrap_err().to_string(),
+            "member name is 101 characters long (at most 100)"
+        );
+        assert_eq!(validate_member_name("bob@example.com").unwrap_err().to_string(), "member name contains invalid character '@'");
+        assert_eq!(validate_id(1), Ok(()));
+        assert_eq!(validate_id(0), Err(ValidationError::ZeroId));
+    }
+
+    #[test]
+    fn test_validate_contact() {
+        assert_eq!(validate_email("a+b_c@mail.example.co.uk"), Ok(()));
+        for email in ["alice.example.com", "@example.com", "alice@example", "alice@example.c0m", "a@b@example.com", "alice@.com"] {
+            assert!(matches!(validate_email(email), Err(ValidationError::Malformed { .. })), "{}", email);
+        }
+        assert_eq!(
+            validate_email("alice smith@example.com"),
+            Err(ValidationError::InvalidCharacter { field: "email", character: ' ' })
+        );
+
+        assert_eq!(validate_phone("555 0100"), Ok(()));
+        assert_eq!(validate_phone("+44 20 7946 0958"), Ok(()));
+        assert_eq!(validate_phone("555-010").unwrap_err().to_string(), "phone must be a number with 7 to 15 digits");
+        assert!(validate_phone("555+0100").is_err());
+        assert_eq!(validate_phone("").unwrap_err().to_string(), "phone must not be empty");
+    }
+}